@@ -182,6 +182,14 @@ impl Writer<'_> {
                         s.source.as_ref().unwrap().to_token_stream()
                     )?;
                 }
+                ContentField::Slot { roots, .. } => {
+                    write!(self.out, "{name}: render! {{")?;
+                    self.out.indent_level += 1;
+                    self.write_body_indented(roots)?;
+                    self.out.tabbed_line()?;
+                    write!(self.out, "}}")?;
+                    self.out.indent_level -= 1;
+                }
                 ContentField::OnHandlerRaw(exp) => {
                     let out = prettyplease::unparse_expr(exp);
                     let mut lines = out.split('\n').peekable();
@@ -223,6 +231,8 @@ impl Writer<'_> {
             .iter()
             .map(|field| match &field.content {
                 ContentField::Formatted(s) => ifmt_to_string(s).len() ,
+                // slots always span multiple lines
+                ContentField::Slot { .. } => 10000,
                 ContentField::OnHandlerRaw(exp) | ContentField::ManExpr(exp) => {
                     let formatted = prettyplease::unparse_expr(exp);
                     let len = if formatted.contains('\n') {
@@ -28,6 +28,16 @@ pub fn format_args_f(input: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Derive the builder for a props struct.
+///
+/// Fields can be configured with `#[props(...)]`:
+/// * `default`, `default = expr` or `default_with = function`: Make the prop optional. The default can refer to
+/// fields declared before it.
+/// * `into`: Accept anything that converts into the field's type.
+/// * `!optional`: Require `Option` fields to be set.
+/// * `validate = function`: Call `function(&value)` when the props are built, and panic with its error if it returns
+/// `Err`.
+/// * `deprecated = "message"`: Warn at every call site that sets the prop.
 #[proc_macro_derive(Props, attributes(props))]
 pub fn derive_typed_builder(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as syn::DeriveInput);
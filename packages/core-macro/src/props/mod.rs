@@ -253,6 +253,8 @@ mod field_info {
         pub auto_into: bool,
         pub strip_option: bool,
         pub ignore_option: bool,
+        pub validate: Option<syn::Expr>,
+        pub deprecated: Option<syn::Expr>,
     }
 
     impl FieldBuilderAttr {
@@ -313,6 +315,28 @@ mod field_info {
                             self.doc = Some(*assign.right);
                             Ok(())
                         }
+                        // #[props(default_with = make_value)] calls the function to compute the default
+                        "default_with" => {
+                            let function = assign.right;
+                            self.default = Some(syn::parse_quote!((#function)()));
+                            Ok(())
+                        }
+                        "validate" => {
+                            self.validate = Some(*assign.right);
+                            Ok(())
+                        }
+                        "deprecated" => {
+                            if let syn::Expr::Lit(syn::ExprLit {
+                                lit: syn::Lit::Str(_),
+                                ..
+                            }) = *assign.right
+                            {
+                                self.deprecated = Some(*assign.right);
+                                Ok(())
+                            } else {
+                                Err(Error::new_spanned(assign.right, "Expected string"))
+                            }
+                        }
                         "default_code" => {
                             if let syn::Expr::Lit(syn::ExprLit {
                                 lit: syn::Lit::Str(code),
@@ -703,6 +727,9 @@ Finally, call `.build()` to create the instance of `{name}`.
             if *field_name == "key" {
                 return Err(Error::new_spanned(field_name, "Naming a prop `key` is not allowed because the name can conflict with the built in key attribute. See https://dioxuslabs.com/learn/0.4/reference/dynamic_rendering#rendering-lists for more information about keys"));
             }
+            if *field_name == "global_key" {
+                return Err(Error::new_spanned(field_name, "Naming a prop `global_key` is not allowed because the name can conflict with the built in global_key attribute"));
+            }
             let StructInfo {
                 ref builder_name, ..
             } = *self;
@@ -790,6 +817,11 @@ Finally, call `.build()` to create the instance of `{name}`.
                     (quote!(#arg_type), quote!(#field_name))
                 };
 
+            let deprecated = match field.builder_attr.deprecated {
+                Some(ref note) => quote!(#[deprecated(note = #note)]),
+                None => quote!(),
+            };
+
             let repeated_fields_error_type_name = syn::Ident::new(
                 &format!(
                     "{}_Error_Repeated_field_{}",
@@ -804,6 +836,7 @@ Finally, call `.build()` to create the instance of `{name}`.
                 #[allow(dead_code, non_camel_case_types, missing_docs)]
                 impl #impl_generics #builder_name < #( #ty_generics ),* > #where_clause {
                     #doc
+                    #deprecated
                     #[allow(clippy::type_complexity)]
                     pub fn #field_name (self, #field_name: #arg_type) -> #builder_name < #( #target_generics ),* > {
                         let #field_name = (#arg_expr,);
@@ -1019,6 +1052,21 @@ Finally, call `.build()` to create the instance of `{name}`.
                     quote!(let #name = #name.0;)
                 }
             });
+            // Validators run once every field has its final value
+            let validations = self.fields.iter().filter_map(|field| {
+                let validate = field.builder_attr.validate.as_ref()?;
+                let field_name = field.name;
+                let message = format!(
+                    "Invalid value for the prop `{}` of `{}`: {{}}",
+                    strip_raw_ident_prefix(field_name.to_string()),
+                    name
+                );
+                Some(quote! {
+                    if let ::core::result::Result::Err(err) = (#validate)(&#field_name) {
+                        panic!(#message, err);
+                    }
+                })
+            });
             let field_names = self.fields.iter().map(|field| field.name);
             let doc = if self.builder_attr.doc {
                 match self.builder_attr.build_method_doc {
@@ -1035,12 +1083,13 @@ Finally, call `.build()` to create the instance of `{name}`.
                 quote!()
             };
             quote!(
-                #[allow(dead_code, non_camel_case_types, missing_docs)]
+                #[allow(dead_code, non_camel_case_types, missing_docs, clippy::panic)]
                 impl #impl_generics #builder_name #modified_ty_generics #where_clause {
                     #doc
                     pub fn build(self) -> #name #ty_generics {
                         let ( #(#descructuring,)* ) = self.fields;
                         #( #assignments )*
+                        #( #validations )*
                         #name {
                             #( #field_names ),*
                         }
@@ -1,6 +1,7 @@
 use crate::{
-    innerlude::Scoped,
+    innerlude::{Scoped, UnhandledError, UnhandledErrorSource},
     nodes::RenderReturn,
+    runtime::with_runtime,
     scopes::{Scope, ScopeState},
     Element,
 };
@@ -67,8 +68,9 @@ unsafe impl<'a, P> AnyProps<'a> for VProps<'a, P> {
             Ok(Some(e)) => RenderReturn::Ready(e),
             Ok(None) => RenderReturn::default(),
             Err(err) => {
-                let component_name = cx.name();
-                tracing::error!("Error while rendering component `{component_name}`: {err:?}");
+                let error =
+                    UnhandledError::from_panic(UnhandledErrorSource::Render, cx.scope_id(), err);
+                with_runtime(|runtime| runtime.report_unhandled_error(error));
                 RenderReturn::default()
             }
         }
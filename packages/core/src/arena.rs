@@ -88,6 +88,7 @@ impl VirtualDom {
             height: self.scopes[id.0].height(),
             id,
         });
+        self.suspended_scopes.remove(&id);
 
         // Remove all VNode ids from the scope
         for id in self.scopes[id.0]
@@ -113,6 +114,7 @@ impl VirtualDom {
         // Drop all the hooks once the children are dropped
         // this means we'll drop hooks bottom-up
         scope.hooks.get_mut().clear();
+        scope.hook_calls.get_mut().clear();
         {
             let context = scope.context();
 
@@ -138,6 +140,7 @@ impl VirtualDom {
             }
             DynamicNode::Placeholder(_) => {}
             DynamicNode::Text(_) => {}
+            DynamicNode::RawHtml(_) => {}
         });
     }
 
@@ -0,0 +1,87 @@
+use crate::ScopeId;
+use std::{
+    cell::RefCell,
+    fmt::{Debug, Formatter},
+    rc::Rc,
+};
+
+/// A context whose value is resolved by a future, provided with [`crate::ScopeState::provide_async_context`].
+///
+/// Components below the provider read it with [`crate::ScopeState::consume_async_context`], which suspends them until
+/// the value is resolved. This is useful for state that many components need and that has to be loaded first, like the
+/// config of the app or the signed in user:
+///
+/// ```rust, ignore
+/// fn App(cx: Scope) -> Element {
+///     cx.use_hook(|| cx.provide_async_context(async { load_config().await }));
+///     render! { Header {} Content {} }
+/// }
+///
+/// fn Header(cx: Scope) -> Element {
+///     let config = cx.consume_async_context::<Config>()?;
+///     render! { h1 { "{config.title}" } }
+/// }
+/// ```
+pub struct AsyncContext<T> {
+    inner: Rc<AsyncContextInner<T>>,
+}
+
+struct AsyncContextInner<T> {
+    value: RefCell<Option<T>>,
+    // The components that suspended until the value is resolved
+    waiting: RefCell<Vec<ScopeId>>,
+}
+
+impl<T> Clone for AsyncContext<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for AsyncContext<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncContext")
+            .field("value", &self.inner.value.borrow())
+            .finish()
+    }
+}
+
+impl<T: Clone> AsyncContext<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            inner: Rc::new(AsyncContextInner {
+                value: RefCell::new(None),
+                waiting: RefCell::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Get the value if it is resolved already, without suspending the current component
+    pub fn get(&self) -> Option<T> {
+        self.inner.value.borrow().clone()
+    }
+
+    /// Check if the value was resolved
+    pub fn is_resolved(&self) -> bool {
+        self.inner.value.borrow().is_some()
+    }
+
+    /// Rerender `scope` once the value is resolved
+    pub(crate) fn wait(&self, scope: ScopeId) {
+        let mut waiting = self.inner.waiting.borrow_mut();
+        if !waiting.contains(&scope) {
+            waiting.push(scope);
+        }
+    }
+
+    /// Set the value, and rerender the components that waited for it with `update`
+    pub(crate) fn resolve(&self, value: T, update: &dyn Fn(ScopeId)) {
+        *self.inner.value.borrow_mut() = Some(value);
+        let waiting = std::mem::take(&mut *self.inner.waiting.borrow_mut());
+        for scope in waiting {
+            update(scope);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{innerlude::ScopeId, runtime::with_runtime};
+use std::fmt::{Display, Formatter};
+
+/// One component in a [`ComponentStack`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentFrame {
+    /// The name of the component
+    pub name: &'static str,
+
+    /// The scope of the component
+    pub scope: ScopeId,
+
+    /// The location of the `rsx!` call that rendered the component, if it is known
+    pub location: Option<&'static str>,
+}
+
+/// The ancestry of a component, starting with the component itself and ending with the root of the app.
+///
+/// Unlike a Rust backtrace, this shows where each component was rendered from, so you can tell which part of the
+/// tree an error or panic came from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComponentStack {
+    frames: Vec<ComponentFrame>,
+}
+
+impl ComponentStack {
+    /// Capture the stack of the given scope and all of its parents
+    ///
+    /// The stack is empty if this is called outside of a running VirtualDom.
+    pub fn capture(scope: ScopeId) -> Self {
+        let frames = with_runtime(|runtime| {
+            let mut frames = Vec::new();
+            let mut current = Some(scope);
+            while let Some(id) = current {
+                let context = match runtime.get_context(id) {
+                    Some(context) => context,
+                    None => break,
+                };
+                frames.push(ComponentFrame {
+                    name: context.name,
+                    scope: id,
+                    location: context.location,
+                });
+                current = context.parent_id;
+            }
+            frames
+        });
+
+        Self {
+            frames: frames.unwrap_or_default(),
+        }
+    }
+
+    /// The components in the stack, starting with the innermost component
+    pub fn frames(&self) -> &[ComponentFrame] {
+        &self.frames
+    }
+
+    /// Check if the stack has no components
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+impl Display for ComponentStack {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for frame in &self.frames {
+            match frame.location {
+                Some(location) => writeln!(f, "    in {} (at {})", frame.name, location)?,
+                None => writeln!(f, "    in {}", frame.name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Turn the name of a template into the location of the rsx! call that created it
+///
+/// Template names are the location of the macro followed by the index of the template in the macro
+pub(crate) fn template_location(template_name: &'static str) -> &'static str {
+    template_name
+        .rsplit_once(':')
+        .map(|(location, _)| location)
+        .unwrap_or(template_name)
+}
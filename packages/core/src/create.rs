@@ -1,6 +1,7 @@
 use crate::any_props::AnyProps;
+use crate::component_stack::template_location;
 use crate::innerlude::{
-    BorrowedAttributeValue, ElementPath, ElementRef, VComponent, VPlaceholder, VText,
+    BorrowedAttributeValue, ElementPath, ElementRef, VComponent, VPlaceholder, VRawHtml, VText,
 };
 use crate::mutations::Mutation;
 use crate::mutations::Mutation::*;
@@ -213,6 +214,7 @@ impl<'b> VirtualDom {
                 self.create_static_text(value, id);
                 1
             }
+            RawHtml(html) => self.create_raw_html(html),
         }
     }
 
@@ -484,6 +486,7 @@ impl<'b> VirtualDom {
         use DynamicNode::*;
         match node {
             Text(text) => self.create_dynamic_text(parent, text),
+            RawHtml(html) => self.create_raw_html(html),
             Placeholder(place) => self.create_placeholder(place, parent),
             Component(component) => self.create_component_node(Some(parent), component),
             Fragment(frag) => self.create_children(*frag, Some(parent)),
@@ -511,6 +514,19 @@ impl<'b> VirtualDom {
         0
     }
 
+    fn create_raw_html(&mut self, html: &'b VRawHtml<'b>) -> usize {
+        let id = self.next_element();
+        html.id.set(Some(id));
+
+        // Safety: we promise not to re-alias this html later on after committing it to the mutation
+        let value = unsafe { std::mem::transmute(html.value) };
+
+        self.mutations.push(CreateRawHtml { value, id });
+
+        // The node that holds the html is pushed onto the stack
+        1
+    }
+
     pub(crate) fn create_placeholder(
         &mut self,
         placeholder: &VPlaceholder,
@@ -543,7 +559,7 @@ impl<'b> VirtualDom {
         use RenderReturn::*;
 
         // Load up a ScopeId for this vcomponent
-        let scope = self.load_scope_from_vcomponent(component);
+        let scope = self.load_scope_from_vcomponent(component, parent);
 
         component.scope.set(Some(scope));
 
@@ -558,18 +574,47 @@ impl<'b> VirtualDom {
     }
 
     /// Load a scope from a vcomponent. If the props don't exist, that means the component is currently "live"
-    fn load_scope_from_vcomponent(&mut self, component: &VComponent) -> ScopeId {
+    fn load_scope_from_vcomponent(
+        &mut self,
+        component: &VComponent,
+        parent: Option<ElementRef>,
+    ) -> ScopeId {
         component
             .props
             .take()
             .map(|props| {
                 let unbounded_props: Box<dyn AnyProps> = unsafe { std::mem::transmute(props) };
-                self.new_scope(unbounded_props, component.name).context().id
+
+                // Components with a global key take over the scope they had before they moved
+                let unbounded_props = match self.reuse_keyed_scope(component, unbounded_props) {
+                    Ok(scope) => return scope,
+                    Err(props) => props,
+                };
+
+                let location = parent.and_then(|parent| self.template_location(parent));
+                let scope = self
+                    .new_scope(unbounded_props, component.name, location)
+                    .context()
+                    .id;
+                self.track_keyed_scope(component, scope);
+                scope
             })
             .unwrap_or_else(|| component.scope.get().unwrap())
     }
 
-    fn mount_aborted(&mut self, placeholder: &VPlaceholder, parent: Option<ElementRef>) -> usize {
+    /// Get the location of the rsx! call that created the template an element belongs to
+    fn template_location(&self, element: ElementRef) -> Option<&'static str> {
+        let node = (*self.element_refs.get(element.template.0)?)?;
+        // Safety: element refs are cleared before the node they point to is dropped
+        let name = unsafe { node.as_ref() }.template.get().name;
+        Some(template_location(name))
+    }
+
+    pub(crate) fn mount_aborted(
+        &mut self,
+        placeholder: &VPlaceholder,
+        parent: Option<ElementRef>,
+    ) -> usize {
         let id = self.next_element();
         self.mutations.push(Mutation::CreatePlaceholder { id });
         placeholder.id.set(Some(id));
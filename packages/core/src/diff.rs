@@ -3,7 +3,7 @@ use crate::{
     arena::ElementId,
     innerlude::{
         BorrowedAttributeValue, DirtyScope, ElementPath, ElementRef, VComponent, VPlaceholder,
-        VText,
+        VRawHtml, VText,
     },
     mutations::Mutation,
     nodes::RenderReturn,
@@ -172,6 +172,7 @@ impl<'b> VirtualDom {
     ) {
         match (left_node, right_node) {
             (Text(left), Text(right)) => self.diff_vtext(left, right),
+            (RawHtml(left), RawHtml(right)) => self.diff_raw_html(left, right),
             (Fragment(left), Fragment(right)) => self.diff_non_empty_fragment(left, right, parent),
             (Placeholder(left), Placeholder(right)) => {
                 right.id.set(left.id.get());
@@ -206,8 +207,8 @@ impl<'b> VirtualDom {
             return;
         }
 
-        // Replace components that have different render fns
-        if left.render_fn != right.render_fn {
+        // Replace components that have different render fns or global keys
+        if left.render_fn != right.render_fn || left.global_key != right.global_key {
             return self.replace_vcomponent(right, left, parent);
         }
 
@@ -331,6 +332,16 @@ impl<'b> VirtualDom {
         }
     }
 
+    fn diff_raw_html(&mut self, left: &'b VRawHtml<'b>, right: &'b VRawHtml<'b>) {
+        let id = left.id.get().unwrap_or_else(|| self.next_element());
+
+        right.id.set(Some(id));
+        if left.value != right.value {
+            let value = unsafe { std::mem::transmute(right.value) };
+            self.mutations.push(Mutation::SetRawHtml { id, value });
+        }
+    }
+
     fn diff_non_empty_fragment(
         &mut self,
         old: &'b [VNode<'b>],
@@ -753,6 +764,12 @@ impl<'b> VirtualDom {
                         });
                         1
                     }
+                    RawHtml(t) => {
+                        self.mutations.push(Mutation::PushRoot {
+                            id: t.id.get().unwrap(),
+                        });
+                        1
+                    }
                     Placeholder(t) => {
                         self.mutations.push(Mutation::PushRoot {
                             id: t.id.get().unwrap(),
@@ -885,7 +902,7 @@ impl<'b> VirtualDom {
             .for_each(|node| self.remove_node(node, true));
     }
 
-    fn remove_node(&mut self, node: &'b VNode<'b>, gen_muts: bool) {
+    pub(crate) fn remove_node(&mut self, node: &'b VNode<'b>, gen_muts: bool) {
         // Clean up any attributes that have claimed a static node as dynamic for mount/unmounta
         // Will not generate mutations!
         self.reclaim_attributes(node);
@@ -974,6 +991,7 @@ impl<'b> VirtualDom {
         match node {
             Component(comp) => self.remove_component_node(comp, gen_muts),
             Text(t) => self.remove_text_node(t, gen_muts),
+            RawHtml(t) => self.remove_raw_html(t, gen_muts),
             Placeholder(t) => self.remove_placeholder(t, gen_muts),
             Fragment(nodes) => nodes
                 .iter()
@@ -981,7 +999,7 @@ impl<'b> VirtualDom {
         };
     }
 
-    fn remove_placeholder(&mut self, t: &VPlaceholder, gen_muts: bool) {
+    pub(crate) fn remove_placeholder(&mut self, t: &VPlaceholder, gen_muts: bool) {
         if let Some(id) = t.id.take() {
             if gen_muts {
                 self.mutations.push(Mutation::Remove { id });
@@ -999,6 +1017,15 @@ impl<'b> VirtualDom {
         }
     }
 
+    fn remove_raw_html(&mut self, t: &VRawHtml, gen_muts: bool) {
+        if let Some(id) = t.id.take() {
+            if gen_muts {
+                self.mutations.push(Mutation::Remove { id });
+            }
+            self.reclaim(id)
+        }
+    }
+
     fn remove_component_node(&mut self, comp: &VComponent, gen_muts: bool) {
         // Remove the component reference from the vcomponent so they're not tied together
         let scope = comp
@@ -1021,6 +1048,11 @@ impl<'b> VirtualDom {
         let props = self.scopes[scope.0].props.take();
         *comp.props.borrow_mut() = unsafe { std::mem::transmute(props) };
 
+        // Keyed components keep their scope until the end of the render, in case they were moved to another parent
+        if self.detach_keyed_scope(comp, scope) {
+            return;
+        }
+
         // Now drop all the resouces
         self.drop_scope(scope, false);
     }
@@ -1029,6 +1061,7 @@ impl<'b> VirtualDom {
         match node.dynamic_root(0) {
             None => node.root_ids.borrow()[0],
             Some(Text(t)) => t.id.get().unwrap(),
+            Some(RawHtml(t)) => t.id.get().unwrap(),
             Some(Fragment(t)) => self.find_first_element(&t[0]),
             Some(Placeholder(t)) => t.id.get().unwrap(),
             Some(Component(comp)) => {
@@ -1050,6 +1083,7 @@ impl<'b> VirtualDom {
         match node.dynamic_root(node.template.get().roots.len() - 1) {
             None => *node.root_ids.borrow().last().unwrap(),
             Some(Text(t)) => t.id.get().unwrap(),
+            Some(RawHtml(t)) => t.id.get().unwrap(),
             Some(Fragment(t)) => self.find_last_element(t.last().unwrap()),
             Some(Placeholder(t)) => t.id.get().unwrap(),
             Some(Component(comp)) => {
@@ -0,0 +1,69 @@
+//! Effects that run after a render is committed.
+//!
+//! Components must not touch the outside world while they render, because a render may be thrown away or repeated.
+//! Instead they queue effects with [`ScopeState::queue_effect`], which run once the render is committed. The
+//! [`VirtualDom`](crate::VirtualDom) guarantees this order:
+//!
+//! 1. While components render, no effect runs. Effects are queued in the order components render, which is parents
+//!    before children, and hooks in the order they are called.
+//! 2. [`EffectKind::Layout`] effects run after the mutations of the render are applied to the real DOM, and before the
+//!    renderer yields to the browser. Renderers call [`VirtualDom::flush_layout_effects`](crate::VirtualDom::flush_layout_effects)
+//!    right after they apply the mutations, so a layout effect can measure an element and change the state before the
+//!    frame is painted, without a visible flicker.
+//! 3. [`EffectKind::Passive`] effects run after every layout effect, the next time the VirtualDom yields: before
+//!    [`VirtualDom::wait_for_work`](crate::VirtualDom::wait_for_work) polls any task, and before
+//!    [`VirtualDom::handle_event`](crate::VirtualDom::handle_event) calls any listener. An event handler always sees
+//!    the effects of the render that produced the element it was called on.
+//!
+//! State an effect changes marks its component dirty like any other update, and is rendered in the next pass. Effects
+//! of components that are unmounted before their effects run are dropped without running.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use crate::{ScopeId, ScopeState};
+
+/// When an effect queued with [`ScopeState::queue_effect`] runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EffectKind {
+    /// Run right after the mutations of the render are applied, before the browser paints them. Use this to measure
+    /// the DOM and update the state the frame depends on.
+    Layout,
+    /// Run after the render is painted, before the VirtualDom does any other work. Most effects should be passive.
+    Passive,
+}
+
+pub(crate) type EffectCallback = Box<dyn FnOnce(&ScopeState)>;
+
+pub(crate) struct Effect {
+    pub(crate) scope: ScopeId,
+    pub(crate) callback: EffectCallback,
+}
+
+/// The effects that are waiting for their render to be committed
+#[derive(Default)]
+pub(crate) struct EffectQueue {
+    layout: RefCell<VecDeque<Effect>>,
+    passive: RefCell<VecDeque<Effect>>,
+}
+
+impl EffectQueue {
+    pub(crate) fn push(&self, kind: EffectKind, effect: Effect) {
+        match kind {
+            EffectKind::Layout => self.layout.borrow_mut().push_back(effect),
+            EffectKind::Passive => self.passive.borrow_mut().push_back(effect),
+        }
+    }
+
+    /// Take the next effect of `kind`. Effects are taken one at a time so an effect can queue more effects.
+    pub(crate) fn pop(&self, kind: EffectKind) -> Option<Effect> {
+        match kind {
+            EffectKind::Layout => self.layout.borrow_mut().pop_front(),
+            EffectKind::Passive => self.passive.borrow_mut().pop_front(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.layout.borrow().is_empty() && self.passive.borrow().is_empty()
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{
+    component_stack::ComponentStack,
+    runtime::with_runtime,
     scope_context::{consume_context, current_scope_id, schedule_update_any},
+    unhandled_error::{UnhandledError, UnhandledErrorSource},
     Element, IntoDynNode, LazyNodes, Properties, Scope, ScopeId, ScopeState, Template,
     TemplateAttribute, TemplateNode, VNode,
 };
@@ -50,13 +53,16 @@ pub struct CapturedError {
 
     /// The scope that threw the error
     pub scope: ScopeId,
+
+    /// The components between the scope that threw the error and the root of the app
+    pub component_stack: ComponentStack,
 }
 
 impl Display for CapturedError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
-            "Encountered error: {:?}\nIn scope: {:?}\nBacktrace: {}",
-            self.error, self.scope, self.backtrace
+            "Encountered error: {:?}\nIn scope: {:?}\nComponent stack:\n{}Backtrace: {}",
+            self.error, self.scope, self.component_stack, self.backtrace
         ))
     }
 }
@@ -118,6 +124,7 @@ impl ErrorBoundary {
             error,
             scope,
             backtrace,
+            component_stack: ComponentStack::capture(scope),
         }));
         (self.inner.rerun_boundary)(self.inner._id);
     }
@@ -202,12 +209,20 @@ pub trait Throw<S = ()>: Sized {
 }
 
 fn throw_error<T>(e: impl Debug + 'static) -> Option<T> {
-    if let Some(cx) = consume_context::<ErrorBoundary>() {
-        match current_scope_id() {
-            Some(id) => cx.insert_error(id, Box::new(e), Backtrace::capture()),
-            None => {
-                tracing::error!("Cannot throw error outside of a component's scope.")
-            }
+    match (current_scope_id(), consume_context::<ErrorBoundary>()) {
+        (Some(id), Some(cx)) => cx.insert_error(id, Box::new(e), Backtrace::capture()),
+        // There is no boundary above the component, so let the app decide what to do with the error
+        (Some(id), None) => {
+            with_runtime(|runtime| {
+                runtime.report_unhandled_error(UnhandledError::new(
+                    UnhandledErrorSource::Thrown,
+                    id,
+                    Box::new(e),
+                ))
+            });
+        }
+        (None, _) => {
+            tracing::error!("Cannot throw error outside of a component's scope.")
         }
     }
 
@@ -0,0 +1,220 @@
+//! Components with a `global_key` keep their scope, and the state of their hooks, when they move to another parent.
+//!
+//! A keyed component that is removed is detached instead of dropped. If a component with the same key and render
+//! function is created before the render finishes, it takes over the detached scope. If the new component is created
+//! before the old one is removed, the scope is taken from the old position, which gets a fresh scope until its
+//! parent removes it.
+//!
+//! The children of a moved component are rendered again from scratch, unless they have a global key themselves.
+
+use crate::{
+    any_props::AnyProps,
+    innerlude::{DirtyScope, RuntimeGuard, VComponent},
+    mutations::Mutation,
+    nodes::{DynamicNode, RenderReturn, VNode},
+    scopes::ScopeId,
+    virtual_dom::VirtualDom,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// The scopes of the components that have a global key
+#[derive(Default)]
+pub(crate) struct GlobalKeys {
+    scopes: FxHashMap<String, KeyedScope>,
+    detached: FxHashSet<ScopeId>,
+}
+
+struct KeyedScope {
+    id: ScopeId,
+    render_fn: *const (),
+}
+
+impl GlobalKeys {
+    pub(crate) fn is_detached(&self, scope: ScopeId) -> bool {
+        self.detached.contains(&scope)
+    }
+}
+
+impl<'b> VirtualDom {
+    /// Remember the scope of a keyed component that was just created
+    pub(crate) fn track_keyed_scope(&mut self, component: &VComponent, scope: ScopeId) {
+        let key = match component.global_key {
+            Some(key) => key,
+            None => return,
+        };
+
+        let keyed = KeyedScope {
+            id: scope,
+            render_fn: component.render_fn,
+        };
+        if let Some(replaced) = self.global_keys.scopes.insert(key.to_string(), keyed) {
+            // A detached scope of another component with the same key can't be reused anymore
+            if self.global_keys.detached.remove(&replaced.id) {
+                let _runtime = RuntimeGuard::new(self.runtime.clone());
+                self.drop_scope(replaced.id, false);
+            }
+        }
+    }
+
+    /// Find the scope a keyed component used before it moved, and move it under the scope that is rendering
+    pub(crate) fn reuse_keyed_scope(
+        &mut self,
+        component: &VComponent,
+        props: Box<dyn AnyProps<'static>>,
+    ) -> Result<ScopeId, Box<dyn AnyProps<'static>>> {
+        let scope = match component
+            .global_key
+            .and_then(|key| self.global_keys.scopes.get(key))
+        {
+            Some(keyed) if keyed.render_fn == component.render_fn => keyed.id,
+            _ => return Err(props),
+        };
+
+        if !self.global_keys.is_detached(scope) && !self.release_mounted_scope(scope) {
+            return Err(props);
+        }
+        self.global_keys.detached.remove(&scope);
+
+        let parent_id = self.runtime.current_scope_id();
+        let height = parent_id
+            .and_then(|parent_id| self.get_scope(parent_id).map(|f| f.context().height + 1))
+            .unwrap_or(0);
+        if let Some(context) = self.runtime.scope_contexts.borrow_mut()[scope.0].as_mut() {
+            context.parent_id = parent_id;
+            context.height = height;
+        }
+
+        self.scopes[scope.0].props = Some(props);
+
+        Ok(scope)
+    }
+
+    /// Detach the scope of a keyed component that was removed, so a component with the same key can take it over.
+    ///
+    /// Returns false if the scope should be dropped.
+    pub(crate) fn detach_keyed_scope(&mut self, component: &VComponent, scope: ScopeId) -> bool {
+        let tracked = component
+            .global_key
+            .and_then(|key| self.global_keys.scopes.get(key))
+            .filter(|keyed| keyed.id == scope)
+            .is_some();
+        if !tracked {
+            return false;
+        }
+
+        self.release_scope(scope);
+        self.global_keys.detached.insert(scope);
+        true
+    }
+
+    /// Drop the scopes that were detached and not taken over during this render
+    pub(crate) fn drop_detached_scopes(&mut self) {
+        if self.global_keys.detached.is_empty() {
+            return;
+        }
+
+        let detached = std::mem::take(&mut self.global_keys.detached);
+        self.global_keys
+            .scopes
+            .retain(|_, keyed| !detached.contains(&keyed.id));
+
+        let _runtime = RuntimeGuard::new(self.runtime.clone());
+        for scope in detached {
+            self.drop_scope(scope, false);
+        }
+    }
+
+    /// Take a keyed scope that is still mounted away from its component, and mount a fresh scope in its place
+    fn release_mounted_scope(&mut self, scope: ScopeId) -> bool {
+        // A component can't be moved inside of itself
+        if self.runtime.scope_stack.borrow().contains(&scope) {
+            return false;
+        }
+
+        let (name, location, parent) = match self.runtime.get_context(scope) {
+            Some(context) => match context.parent_id {
+                Some(parent) => (context.name, context.location, parent),
+                None => return false,
+            },
+            None => return false,
+        };
+        let component = match self.find_mounted_component(parent, scope) {
+            Some(component) => component,
+            None => return false,
+        };
+        let props = match self.scopes[scope.0].props.take() {
+            Some(props) => props,
+            None => return false,
+        };
+
+        // The old position keeps a fresh scope until its parent removes it
+        self.runtime.scope_stack.borrow_mut().push(parent);
+        let fresh = self.new_scope(props, name, location).context().id;
+        self.runtime.scope_stack.borrow_mut().pop();
+        component.scope.set(Some(fresh));
+
+        let m = match unsafe { self.run_scope(fresh).extend_lifetime_ref() } {
+            RenderReturn::Ready(node) => self.create_scope(fresh, node),
+            RenderReturn::Aborted(placeholder) => self.mount_aborted(placeholder, None),
+        };
+
+        match unsafe { self.scopes[scope.0].root_node().extend_lifetime_ref() } {
+            RenderReturn::Ready(node) => self.remove_node(node, true),
+            RenderReturn::Aborted(placeholder) => self.remove_placeholder(placeholder, true),
+        };
+
+        // Replace the last removed node, which is the first node of the old scope, with the fresh scope
+        match self.mutations.edits.pop() {
+            Some(Mutation::Remove { id }) => self.mutations.push(Mutation::ReplaceWith { id, m }),
+            at => panic!("Expected remove mutation from remove_node {:#?}", at),
+        };
+
+        self.release_scope(scope);
+        true
+    }
+
+    /// Clean up the references into the nodes of a scope that was removed from the dom, without dropping it
+    fn release_scope(&mut self, scope: ScopeId) {
+        self.dirty_scopes.remove(&DirtyScope {
+            height: self.scopes[scope.0].height(),
+            id: scope,
+        });
+
+        for id in self.scopes[scope.0]
+            .element_refs_to_drop
+            .borrow_mut()
+            .drain(..)
+        {
+            self.element_refs.try_remove(id.0);
+        }
+
+        self.ensure_drop_safety(scope);
+    }
+
+    /// Find the component a scope is mounted to in the nodes of its parent
+    fn find_mounted_component(
+        &self,
+        parent: ScopeId,
+        scope: ScopeId,
+    ) -> Option<&'b VComponent<'b>> {
+        let parent = self.get_scope(parent)?;
+
+        // While the parent is diffed, the component may only be in the nodes of the last render
+        [parent.current_frame(), parent.previous_frame()]
+            .iter()
+            .find_map(|frame| match unsafe { frame.try_load_node() } {
+                Some(RenderReturn::Ready(node)) => find_component(node, scope),
+                _ => None,
+            })
+    }
+}
+
+fn find_component<'b>(node: &'b VNode<'b>, scope: ScopeId) -> Option<&'b VComponent<'b>> {
+    node.dynamic_nodes.iter().find_map(|dynamic| match dynamic {
+        DynamicNode::Component(component) if component.scope.get() == Some(scope) => {
+            Some(component)
+        }
+        DynamicNode::Fragment(nodes) => nodes.iter().find_map(|node| find_component(node, scope)),
+        _ => None,
+    })
+}
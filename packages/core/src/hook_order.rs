@@ -0,0 +1,136 @@
+//! Diagnostics for hooks that are called in a different order than in the previous render.
+//!
+//! Hooks are stored by the order they are called in, so a hook that is only called in some renders shifts every
+//! hook after it. Every hook call records the type of its state and where it was called from, so when the order
+//! changes we can show both sequences and point at the code that skipped or added a hook.
+
+use std::fmt::{Display, Formatter};
+use std::panic::Location;
+
+use crate::innerlude::ComponentStack;
+
+/// A call to [`ScopeState::use_hook`](crate::ScopeState::use_hook)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HookCall {
+    /// The type of the state the hook stores
+    name: &'static str,
+    /// Where the hook was called from. Hooks marked `#[track_caller]` report the location of their caller.
+    location: &'static Location<'static>,
+}
+
+impl HookCall {
+    #[track_caller]
+    pub(crate) fn new<State>() -> Self {
+        Self {
+            name: std::any::type_name::<State>(),
+            location: Location::caller(),
+        }
+    }
+
+    /// Check if two calls come from the same place in the code
+    pub(crate) fn same_site(&self, other: &HookCall) -> bool {
+        self.location == other.location
+    }
+}
+
+impl Display for HookCall {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", short_type_name(self.name), self.location)
+    }
+}
+
+/// Remove the module paths from a type name, so `dioxus_hooks::UseState<alloc::string::String>` becomes
+/// `UseState<String>`
+fn short_type_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut segment_start = 0;
+    for (index, c) in name.char_indices() {
+        match c {
+            ':' => segment_start = index + 1,
+            '<' | '>' | ',' | ' ' | '(' | ')' | '[' | ']' | '&' | ';' => {
+                short.push_str(&name[segment_start..index]);
+                short.push(c);
+                segment_start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    short.push_str(&name[segment_start..]);
+    short
+}
+
+/// The diagnostic shown when the hook at `index` doesn't match the hook called there in the previous render
+pub(crate) struct HookOrderDiagnostic<'a> {
+    pub(crate) component: &'a str,
+    pub(crate) index: usize,
+    /// The hooks of the previous render
+    pub(crate) previous: &'a [HookCall],
+    /// The hook that was called at `index` in this render
+    pub(crate) current: HookCall,
+    pub(crate) stack: ComponentStack,
+}
+
+impl HookOrderDiagnostic<'_> {
+    fn write_sequence(
+        &self,
+        f: &mut Formatter<'_>,
+        calls: impl Iterator<Item = HookCall>,
+    ) -> std::fmt::Result {
+        for (index, call) in calls.enumerate() {
+            let marker = if index == self.index { ">" } else { " " };
+            writeln!(f, "  {} {}: {}", marker, index, call)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for HookOrderDiagnostic<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let previous = &self.previous[self.index];
+        writeln!(
+            f,
+            "Hook {} of the component `{}` was called in a different order than in the previous render.",
+            self.index, self.component
+        )?;
+        writeln!(f)?;
+        writeln!(f, "Previous render:")?;
+        self.write_sequence(f, self.previous.iter().copied())?;
+        writeln!(f, "This render:")?;
+        self.write_sequence(
+            f,
+            self.previous[..self.index]
+                .iter()
+                .copied()
+                .chain(std::iter::once(self.current)),
+        )?;
+        writeln!(f)?;
+
+        // The condition that changed the order is somewhere between the last hook that matched and this one
+        match self.index.checked_sub(1).map(|last| self.previous[last]) {
+            Some(last) => writeln!(
+                f,
+                "The hook at {} was skipped or the hook at {} was added, probably by an `if`, `match`, loop or early \
+                 return between {} and {}.",
+                previous.location, self.current.location, last.location, self.current.location
+            )?,
+            None => writeln!(
+                f,
+                "The hook at {} was skipped or the hook at {} was added, probably by an `if`, `match`, loop or early \
+                 return before {}.",
+                previous.location, self.current.location, self.current.location
+            )?,
+        }
+        writeln!(
+            f,
+            "Hooks must be called in the same order on every render. Move the hook above the condition, or move the \
+             conditional part into a child component that calls the hook unconditionally."
+        )?;
+
+        if !self.stack.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Component stack:")?;
+            write!(f, "{}", self.stack)?;
+        }
+        Ok(())
+    }
+}
@@ -5,13 +5,18 @@
 
 mod any_props;
 mod arena;
+mod async_context;
 mod bump_frame;
+mod component_stack;
 mod create;
 mod diff;
 mod dirty_scope;
+mod effects;
 mod error_boundary;
 mod events;
 mod fragment;
+mod global_key;
+mod hook_order;
 mod lazynodes;
 mod mutations;
 mod nodes;
@@ -21,11 +26,17 @@ mod scheduler;
 mod scope_arena;
 mod scope_context;
 mod scopes;
+mod strict_mode;
+mod suspense;
+mod unhandled_error;
 mod virtual_dom;
 
 pub(crate) mod innerlude {
     pub use crate::arena::*;
+    pub use crate::async_context::AsyncContext;
+    pub use crate::component_stack::{ComponentFrame, ComponentStack};
     pub use crate::dirty_scope::*;
+    pub use crate::effects::*;
     pub use crate::error_boundary::*;
     pub use crate::events::*;
     pub use crate::fragment::*;
@@ -38,6 +49,8 @@ pub(crate) mod innerlude {
     pub use crate::scheduler::*;
     pub use crate::scope_context::*;
     pub use crate::scopes::*;
+    pub use crate::suspense::*;
+    pub use crate::unhandled_error::{UnhandledError, UnhandledErrorSource};
     pub use crate::virtual_dom::*;
 
     /// An [`Element`] is a possibly-none [`VNode`] created by calling `render` on [`Scope`] or [`ScopeState`].
@@ -76,11 +89,12 @@ pub(crate) mod innerlude {
 }
 
 pub use crate::innerlude::{
-    fc_to_builder, vdom_is_rendering, AnyValue, Attribute, AttributeValue, BorrowedAttributeValue,
-    CapturedError, Component, DynamicNode, Element, ElementId, Event, Fragment, IntoDynNode,
-    LazyNodes, Mutation, Mutations, Properties, RenderReturn, Scope, ScopeId, ScopeState, Scoped,
-    TaskId, Template, TemplateAttribute, TemplateNode, VComponent, VNode, VPlaceholder, VText,
-    VirtualDom,
+    fc_to_builder, vdom_is_rendering, AnyValue, AsyncContext, Attribute, AttributeValue,
+    BorrowedAttributeValue, CapturedError, Component, ComponentFrame, ComponentStack, DynamicNode,
+    EffectKind, Element, ElementId, Event, Fragment, IntoDynNode, LazyNodes, Mutation, Mutations,
+    Properties, RenderReturn, Scope, ScopeId, ScopeState, Scoped, SuspenseBoundary, SuspensePolicy,
+    TaskId, Template, TemplateAttribute, TemplateNode, UnhandledError, UnhandledErrorSource,
+    VComponent, VNode, VPlaceholder, VRawHtml, VText, VirtualDom,
 };
 
 /// The purpose of this module is to alleviate imports of many common types
@@ -88,13 +102,15 @@ pub use crate::innerlude::{
 /// This includes types like [`Scope`], [`Element`], and [`Component`].
 pub mod prelude {
     pub use crate::innerlude::{
-        consume_context, consume_context_from_scope, current_scope_id, fc_to_builder, has_context,
-        provide_context, provide_context_to_scope, provide_root_context, push_future,
-        remove_future, schedule_update_any, spawn, spawn_forever, suspend, use_error_boundary,
-        AnyValue, Component, Element, ErrorBoundary, Event, EventHandler, Fragment,
-        IntoAttributeValue, IntoDynNode, LazyNodes, Properties, Runtime, RuntimeGuard, Scope,
-        ScopeId, ScopeState, Scoped, TaskId, Template, TemplateAttribute, TemplateNode, Throw,
-        VNode, VirtualDom,
+        consume_async_context, consume_context, consume_context_from_scope, current_scope_id,
+        fc_to_builder, has_context, provide_async_context, provide_context,
+        provide_context_to_scope, provide_root_context, push_future, remove_future,
+        schedule_update_any, spawn, spawn_forever, suspend, use_error_boundary,
+        use_suspense_boundary, AnyValue, AsyncContext, Component, EffectKind, Element,
+        ErrorBoundary, Event, EventHandler, Fragment, IntoAttributeValue, IntoDynNode, LazyNodes,
+        Properties, Runtime, RuntimeGuard, Scope, ScopeId, ScopeState, Scoped, SuspenseBoundary,
+        SuspensePolicy, TaskId, Template, TemplateAttribute, TemplateNode, Throw, VNode,
+        VirtualDom,
     };
 }
 
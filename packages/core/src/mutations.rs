@@ -131,6 +131,15 @@ pub enum Mutation<'a> {
         id: ElementId,
     },
 
+    /// Create a node that holds a chunk of raw HTML and push it onto the stack
+    CreateRawHtml {
+        /// The HTML to put inside the node
+        value: &'a str,
+
+        /// The ID we're assigning to the node
+        id: ElementId,
+    },
+
     /// Load and clone an existing node from a template saved under that specific name
     ///
     /// Dioxus guarantees that the renderer will have already been provided the template.
@@ -214,6 +223,17 @@ pub enum Mutation<'a> {
         id: ElementId,
     },
 
+    /// Replace the HTML inside a node created with [`Mutation::CreateRawHtml`].
+    ///
+    /// Renderers are free to patch the existing HTML incrementally instead of replacing all of it.
+    SetRawHtml {
+        /// The new HTML
+        value: &'a str,
+
+        /// The ID of the node that holds the HTML
+        id: ElementId,
+    },
+
     /// Create a new Event Listener.
     NewEventListener {
         /// The name of the event to listen for.
@@ -311,6 +311,12 @@ pub enum DynamicNode<'a> {
     /// A text node
     Text(VText<'a>),
 
+    /// A chunk of raw HTML
+    ///
+    /// The diff treats the HTML as a single node: if the string changes, renderers are asked to update the HTML in
+    /// place instead of replacing the node.
+    RawHtml(VRawHtml<'a>),
+
     /// A placeholder
     ///
     /// Used by suspense when a node isn't ready and by fragments that don't render anything
@@ -351,6 +357,9 @@ pub struct VComponent<'a> {
     /// It is possible that components get folded at compile time, so these shouldn't be really used as a key
     pub(crate) render_fn: *const (),
 
+    /// The key that keeps the scope of this component alive when it moves to another parent
+    pub(crate) global_key: Option<&'a str>,
+
     pub(crate) props: RefCell<Option<Box<dyn AnyProps<'a> + 'a>>>,
 }
 
@@ -367,6 +376,7 @@ impl<'a> std::fmt::Debug for VComponent<'a> {
             .field("name", &self.name)
             .field("static_props", &self.static_props)
             .field("scope", &self.scope)
+            .field("global_key", &self.global_key)
             .finish()
     }
 }
@@ -396,6 +406,33 @@ impl<'a> VText<'a> {
     }
 }
 
+/// A chunk of raw HTML, mounted to the DOM
+///
+/// Renderers mount the HTML inside a single node, so the chunk can be moved, replaced and removed like any other node.
+#[derive(Debug)]
+pub struct VRawHtml<'a> {
+    /// The HTML itself
+    pub value: &'a str,
+
+    /// The ID of the node that holds the HTML in the real DOM
+    pub(crate) id: Cell<Option<ElementId>>,
+}
+
+impl<'a> VRawHtml<'a> {
+    /// Create a new VRawHtml
+    pub fn new(value: &'a str) -> Self {
+        Self {
+            value,
+            id: Default::default(),
+        }
+    }
+
+    /// Get the mounted ID of this node
+    pub fn mounted_element(&self) -> Option<ElementId> {
+        self.id.get()
+    }
+}
+
 /// A placeholder node, used by suspense and fragments
 #[derive(Debug, Default)]
 pub struct VPlaceholder {
@@ -1,6 +1,10 @@
 use std::cell::{Cell, Ref, RefCell};
 
-use crate::{innerlude::Scheduler, scope_context::ScopeContext, scopes::ScopeId};
+use crate::{
+    innerlude::{Scheduler, UnhandledError},
+    scope_context::ScopeContext,
+    scopes::ScopeId,
+};
 use std::rc::Rc;
 
 thread_local! {
@@ -49,6 +53,11 @@ pub struct Runtime {
     // We use this to track the current scope
     pub(crate) scope_stack: RefCell<Vec<ScopeId>>,
     pub(crate) rendering: Cell<bool>,
+
+    // Set by `flush_sync` so the next render doesn't wait for the rest of the batch
+    pub(crate) flush_sync: Cell<bool>,
+
+    pub(crate) unhandled_error_handler: RefCell<Option<Rc<dyn Fn(UnhandledError)>>>,
 }
 
 impl Runtime {
@@ -61,6 +70,10 @@ impl Runtime {
             scope_stack: Default::default(),
 
             rendering: Cell::new(true),
+
+            flush_sync: Cell::new(false),
+
+            unhandled_error_handler: Default::default(),
         })
     }
 
@@ -82,6 +95,16 @@ impl Runtime {
         self.scope_contexts.borrow_mut()[id.0] = None;
     }
 
+    /// Pass an error that escaped every error boundary to the handler of the app, or log it if there is none
+    pub(crate) fn report_unhandled_error(&self, error: UnhandledError) {
+        // Clone the handler so it can replace itself while it runs
+        let handler = self.unhandled_error_handler.borrow().clone();
+        match handler {
+            Some(handler) => handler(error),
+            None => tracing::error!("{}", error),
+        }
+    }
+
     /// Get the current scope id
     pub(crate) fn current_scope_id(&self) -> Option<ScopeId> {
         self.scope_stack.borrow().last().copied()
@@ -1,10 +1,11 @@
-use crate::ScopeId;
+use crate::{innerlude::EffectQueue, ScopeId};
 use slab::Slab;
 
 mod task;
 mod wait;
 
 pub use task::*;
+pub(crate) use wait::YieldNow;
 
 /// The type of message that can be sent to the scheduler.
 ///
@@ -25,6 +26,9 @@ pub(crate) struct Scheduler {
 
     /// Tasks created with cx.spawn
     pub tasks: RefCell<Slab<LocalTask>>,
+
+    /// Effects waiting for their render to be committed
+    pub effects: EffectQueue,
 }
 
 impl Scheduler {
@@ -32,6 +36,7 @@ impl Scheduler {
         Rc::new(Scheduler {
             sender,
             tasks: RefCell::new(Slab::new()),
+            effects: EffectQueue::default(),
         })
     }
 }
@@ -1,5 +1,14 @@
-use crate::{runtime::RuntimeGuard, TaskId, VirtualDom};
-use std::task::Context;
+use crate::{
+    innerlude::{UnhandledError, UnhandledErrorSource},
+    runtime::RuntimeGuard,
+    TaskId, VirtualDom,
+};
+use std::{
+    future::Future,
+    panic::{catch_unwind, AssertUnwindSafe},
+    pin::Pin,
+    task::{Context, Poll},
+};
 
 impl VirtualDom {
     /// Handle notifications by tasks inside the scheduler
@@ -22,18 +31,57 @@ impl VirtualDom {
         self.runtime.scope_stack.borrow_mut().push(task.scope);
         self.runtime.rendering.set(false);
 
-        // If the task completes...
-        if task.task.borrow_mut().as_mut().poll(&mut cx).is_ready() {
+        // A task that panics is dropped like a task that completes, since it can't be polled again
+        let poll = catch_unwind(AssertUnwindSafe(|| {
+            task.task.borrow_mut().as_mut().poll(&mut cx)
+        }));
+        let scope_id = task.scope;
+
+        let mut panicked = None;
+        if !matches!(poll, Ok(Poll::Pending)) {
             // Remove it from the scope so we dont try to double drop it when the scope dropes
-            let scope = &self.get_scope(task.scope).unwrap();
+            let scope = &self.get_scope(scope_id).unwrap();
             scope.context().spawned_tasks.borrow_mut().remove(&id);
 
             // Remove it from the scheduler
             tasks.try_remove(id.0);
+
+            panicked = poll.err();
         }
 
         // Remove the scope from the stack
         self.runtime.scope_stack.borrow_mut().pop();
         self.runtime.rendering.set(true);
+
+        // Release the tasks before the handler runs, it may spawn new ones
+        drop(tasks);
+        if let Some(payload) = panicked {
+            self.runtime
+                .report_unhandled_error(UnhandledError::from_panic(
+                    UnhandledErrorSource::Task,
+                    scope_id,
+                    payload,
+                ));
+        }
+    }
+}
+
+/// Yields to the executor once. The future wakes itself, so it is polled again after the work the executor already
+/// has queued, like the other events of the current tick.
+#[derive(Default)]
+pub(crate) struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
     }
 }
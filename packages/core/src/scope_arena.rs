@@ -1,3 +1,5 @@
+#[cfg(debug_assertions)]
+use crate::strict_mode::RenderFingerprint;
 use crate::{
     any_props::AnyProps,
     bump_frame::BumpFrame,
@@ -13,6 +15,7 @@ impl VirtualDom {
         &mut self,
         props: Box<dyn AnyProps<'static>>,
         name: &'static str,
+        location: Option<&'static str>,
     ) -> &ScopeState {
         let parent_id = self.runtime.current_scope_id();
         let height = parent_id
@@ -33,14 +36,21 @@ impl VirtualDom {
             render_cnt: Default::default(),
             hooks: Default::default(),
             hook_idx: Default::default(),
+            hook_calls: Default::default(),
 
             borrowed_props: Default::default(),
             attributes_to_drop_before_render: Default::default(),
             element_refs_to_drop: Default::default(),
         }));
 
-        let context =
-            ScopeContext::new(name, id, parent_id, height, self.runtime.scheduler.clone());
+        let context = ScopeContext::new(
+            name,
+            location,
+            id,
+            parent_id,
+            height,
+            self.runtime.scheduler.clone(),
+        );
         self.runtime.create_context_at(id, context);
 
         scope
@@ -53,20 +63,13 @@ impl VirtualDom {
         // Remove all the outdated listeners
         self.ensure_drop_safety(scope_id);
 
-        let new_nodes = unsafe {
-            let scope = &self.scopes[scope_id.0];
-            scope.previous_frame().reset();
+        let new_nodes = unsafe { self.render_props(scope_id) };
 
-            scope.context().suspended.set(false);
-
-            scope.hook_idx.set(0);
-
-            // safety: due to how we traverse the tree, we know that the scope is not currently aliased
-            let props: &dyn AnyProps = scope.props.as_ref().unwrap().as_ref();
-            let props: &dyn AnyProps = std::mem::transmute(props);
-
-            let _span = tracing::trace_span!("render", scope = %scope.context().name);
-            props.render(scope).extend_lifetime()
+        #[cfg(debug_assertions)]
+        let new_nodes = if self.strict_mode {
+            self.render_again_strict(scope_id, new_nodes)
+        } else {
+            new_nodes
         };
 
         let scope = &self.scopes[scope_id.0];
@@ -103,4 +106,56 @@ impl VirtualDom {
 
         result
     }
+
+    /// Run the component of a scope with its props. The nodes are allocated in the previous frame of the scope.
+    ///
+    /// # Safety
+    ///
+    /// The scope must not be aliased and the returned nodes must be stored in the previous frame before the frame
+    /// is reset again.
+    unsafe fn render_props(&self, scope_id: ScopeId) -> RenderReturn<'static> {
+        let scope = &self.scopes[scope_id.0];
+        scope.previous_frame().reset();
+
+        scope.context().suspended.set(false);
+
+        scope.hook_idx.set(0);
+
+        // safety: due to how we traverse the tree, we know that the scope is not currently aliased
+        let props: &dyn AnyProps = scope.props.as_ref().unwrap().as_ref();
+        let props: &dyn AnyProps = std::mem::transmute(props);
+
+        let _span = tracing::trace_span!("render", scope = %scope.context().name);
+        props.render(scope).extend_lifetime()
+    }
+
+    /// Throw away the first render of a scope, render it again, and warn if the second render is different.
+    #[cfg(debug_assertions)]
+    fn render_again_strict(
+        &mut self,
+        scope_id: ScopeId,
+        first: RenderReturn<'static>,
+    ) -> RenderReturn<'static> {
+        let first_fingerprint = RenderFingerprint::new(&first);
+
+        // The nodes live in the bump frame which never runs destructors, so we leak them the same way
+        std::mem::forget(first);
+        // Drop the listeners and props the first render borrowed before the frame they live in is reset
+        self.ensure_drop_safety(scope_id);
+
+        let second = unsafe { self.render_props(scope_id) };
+        let second_fingerprint = RenderFingerprint::new(&second);
+
+        if first_fingerprint != second_fingerprint {
+            let name = self.scopes[scope_id.0].context().name;
+            tracing::warn!(
+                "Strict mode: the component {} rendered different output when it was rendered twice with the same props and state. Components should not read values that change between renders or update state while rendering; move side effects into hooks or event handlers.\nFirst render: {}\nSecond render: {}",
+                name,
+                first_fingerprint,
+                second_fingerprint
+            );
+        }
+
+        second
+    }
 }
@@ -1,5 +1,5 @@
 use crate::{
-    innerlude::{Scheduler, SchedulerMsg},
+    innerlude::{AsyncContext, Scheduler, SchedulerMsg},
     runtime::{with_current_scope, with_runtime},
     Element, ScopeId, TaskId,
 };
@@ -17,6 +17,8 @@ use std::{
 /// This struct exists to provide a common interface for all scopes without relying on generics.
 pub(crate) struct ScopeContext {
     pub(crate) name: &'static str,
+    // The location of the rsx! call that rendered this component
+    pub(crate) location: Option<&'static str>,
 
     pub(crate) id: ScopeId,
     pub(crate) parent_id: Option<ScopeId>,
@@ -33,6 +35,7 @@ pub(crate) struct ScopeContext {
 impl ScopeContext {
     pub(crate) fn new(
         name: &'static str,
+        location: Option<&'static str>,
         id: ScopeId,
         parent_id: Option<ScopeId>,
         height: u32,
@@ -40,6 +43,7 @@ impl ScopeContext {
     ) -> Self {
         Self {
             name,
+            location,
             id,
             parent_id,
             height,
@@ -212,6 +216,41 @@ impl ScopeContext {
         .expect("Runtime to exist")
     }
 
+    /// Expose a value that `value` resolves to children further down the [`crate::VirtualDom`] tree. The future runs
+    /// as a task of this component, and children that read the context with [`Self::consume_async_context`] are
+    /// suspended until it finishes.
+    ///
+    /// This is a "fundamental" operation and should only be called during initialization of a hook.
+    pub fn provide_async_context<T: 'static + Clone>(
+        &self,
+        value: impl Future<Output = T> + 'static,
+    ) -> AsyncContext<T> {
+        let context = self.provide_context(AsyncContext::new());
+        let update = self.schedule_update_any();
+        self.push_future({
+            let context = context.clone();
+            async move {
+                let value = value.await;
+                context.resolve(value, &*update);
+            }
+        });
+        context
+    }
+
+    /// Try to retrieve the value of an [`AsyncContext`] with type `T` from any parent scope.
+    ///
+    /// If the value isn't resolved yet, this component is suspended, and rendered again once it is. Returns `None` in
+    /// that case, and when no parent provides the context.
+    pub fn consume_async_context<T: 'static + Clone>(&self) -> Option<T> {
+        let context = self.consume_context::<AsyncContext<T>>()?;
+        let value = context.get();
+        if value.is_none() {
+            context.wait(self.id);
+            self.suspend();
+        }
+        value
+    }
+
     /// Pushes the future onto the poll queue to be polled after the component renders.
     pub fn push_future(&self, fut: impl Future<Output = ()> + 'static) -> TaskId {
         let id = self.tasks.spawn(self.id, fut);
@@ -300,6 +339,18 @@ pub fn provide_root_context<T: 'static + Clone>(value: T) -> Option<T> {
     with_current_scope(|cx| cx.provide_root_context(value))
 }
 
+/// Provide a context that `value` resolves to from the current scope
+pub fn provide_async_context<T: 'static + Clone>(
+    value: impl Future<Output = T> + 'static,
+) -> Option<AsyncContext<T>> {
+    with_current_scope(|cx| cx.provide_async_context(value))
+}
+
+/// Consume the value of an async context from the current scope, suspending it until the value is resolved
+pub fn consume_async_context<T: 'static + Clone>() -> Option<T> {
+    with_current_scope(|cx| cx.consume_async_context::<T>()).flatten()
+}
+
 /// Suspends the current component
 pub fn suspend() -> Option<Element<'static>> {
     with_current_scope(|cx| {
@@ -2,8 +2,12 @@ use crate::{
     any_props::AnyProps,
     any_props::VProps,
     bump_frame::BumpFrame,
+    hook_order::{HookCall, HookOrderDiagnostic},
+    innerlude::AsyncContext,
+    innerlude::ComponentStack,
     innerlude::ErrorBoundary,
-    innerlude::{DynamicNode, EventHandler, VComponent, VNodeId, VText},
+    innerlude::{DynamicNode, EventHandler, VComponent, VNodeId, VRawHtml, VText},
+    innerlude::{Effect, EffectKind},
     lazynodes::LazyNodes,
     nodes::{IntoAttributeValue, IntoDynNode, RenderReturn},
     runtime::Runtime,
@@ -92,6 +96,7 @@ pub struct ScopeState {
 
     pub(crate) hooks: RefCell<Vec<Box<UnsafeCell<dyn Any>>>>,
     pub(crate) hook_idx: Cell<usize>,
+    pub(crate) hook_calls: RefCell<Vec<HookCall>>,
 
     pub(crate) borrowed_props: RefCell<Vec<*const VComponent<'static>>>,
     pub(crate) element_refs_to_drop: RefCell<Vec<VNodeId>>,
@@ -132,6 +137,16 @@ impl<'src> ScopeState {
         self.context().name
     }
 
+    /// Get the location of the `rsx!` call that rendered this component, if it is known
+    pub fn location(&self) -> Option<&'static str> {
+        self.context().location
+    }
+
+    /// Get the components between this component and the root of the app
+    pub fn component_stack(&self) -> ComponentStack {
+        ComponentStack::capture(self.scope_id())
+    }
+
     /// Get the current render since the inception of this component
     ///
     /// This can be used as a helpful diagnostic when debugging hooks/renders, etc
@@ -139,6 +154,23 @@ impl<'src> ScopeState {
         self.render_cnt.get()
     }
 
+    /// Get an estimate of the memory this component holds onto, in bytes
+    ///
+    /// This counts the arenas the component renders into and the stack size of its hooks. Heap memory the hooks own
+    /// is not included.
+    pub fn allocated_bytes(&self) -> usize {
+        let arenas =
+            self.node_arena_1.bump().allocated_bytes() + self.node_arena_2.bump().allocated_bytes();
+        let hooks = match self.hooks.try_borrow() {
+            Ok(hooks) => hooks
+                .iter()
+                .map(|hook| std::mem::size_of_val(&**hook))
+                .sum(),
+            Err(_) => 0,
+        };
+        arenas + hooks
+    }
+
     /// Get a handle to the currently active bump arena for this Scope
     ///
     /// This is a bump memory allocator. Be careful using this directly since the contents will be wiped on the next render.
@@ -254,6 +286,14 @@ impl<'src> ScopeState {
         self.context().needs_update()
     }
 
+    /// Render the updates made so far without waiting for the other events of the tick, when the VirtualDom
+    /// [batches renders](crate::VirtualDom::with_render_batching).
+    ///
+    /// This is for updates that must be visible right away, like the value of a controlled input while the user types.
+    pub fn flush_sync(&self) {
+        self.runtime.flush_sync.set(true);
+    }
+
     /// Get the [`ScopeId`] of a mounted component.
     ///
     /// `ScopeId` is not unique for the lifetime of the [`crate::VirtualDom`] - a [`ScopeId`] will be reused if a component is unmounted.
@@ -309,6 +349,40 @@ impl<'src> ScopeState {
         self.context().provide_root_context(context)
     }
 
+    /// Expose a value that `value` resolves to children further down the [`crate::VirtualDom`] tree. The future runs
+    /// as a task of this component, and children that read the context with [`Self::consume_async_context`] are
+    /// suspended until it finishes.
+    ///
+    /// This is a "fundamental" operation and should only be called during initialization of a hook.
+    ///
+    /// # Example
+    ///
+    /// ```rust, ignore
+    /// static App: Component = |cx| {
+    ///     cx.use_hook(|| cx.provide_async_context(async { fetch_user().await }));
+    ///     render!(Child {})
+    /// }
+    ///
+    /// static Child: Component = |cx| {
+    ///     let user = cx.consume_async_context::<User>()?;
+    ///     render!(div { "hello {user.name}" })
+    /// }
+    /// ```
+    pub fn provide_async_context<T: 'static + Clone>(
+        &self,
+        value: impl Future<Output = T> + 'static,
+    ) -> AsyncContext<T> {
+        self.context().provide_async_context(value)
+    }
+
+    /// Try to retrieve the value of an [`AsyncContext`] with type `T` from any parent scope.
+    ///
+    /// If the value isn't resolved yet, this component is suspended, and rendered again once it is. Returns `None` in
+    /// that case, and when no parent provides the context.
+    pub fn consume_async_context<T: 'static + Clone>(&self) -> Option<T> {
+        self.context().consume_async_context()
+    }
+
     /// Pushes the future onto the poll queue to be polled after the component renders.
     pub fn push_future(&self, fut: impl Future<Output = ()> + 'static) -> TaskId {
         self.context().push_future(fut)
@@ -333,6 +407,28 @@ impl<'src> ScopeState {
         self.context().remove_future(id);
     }
 
+    /// Run `effect` once the current render is committed. See the [`EffectKind`] variants for when each kind runs.
+    ///
+    /// Effects are queued every time this is called, so hooks usually only queue one when their dependencies change.
+    ///
+    /// ```rust, ignore
+    /// fn Tooltip(cx: Scope) -> Element {
+    ///     cx.queue_effect(EffectKind::Layout, |cx| {
+    ///         // The tooltip is in the DOM now, but not painted yet, so it can be measured and moved
+    ///     });
+    ///     render! { div { "Tooltip" } }
+    /// }
+    /// ```
+    pub fn queue_effect(&self, kind: EffectKind, effect: impl FnOnce(&ScopeState) + 'static) {
+        self.context().tasks.effects.push(
+            kind,
+            Effect {
+                scope: self.scope_id(),
+                callback: Box::new(effect),
+            },
+        );
+    }
+
     /// Take a lazy [`crate::VNode`] structure and actually build it with the context of the efficient [`bumpalo::Bump`] allocator.
     ///
     /// ## Example
@@ -393,6 +489,20 @@ impl<'src> ScopeState {
         })
     }
 
+    /// Create a node that renders raw HTML using [`Arguments`] and the [`ScopeState`]'s internal [`Bump`] allocator
+    ///
+    /// Unlike `dangerous_inner_html`, renderers only touch the HTML again when the string changes.
+    ///
+    /// ```rust, ignore
+    /// render! { article { cx.raw_html_node(format_args!("{markdown_html}")) } }
+    /// ```
+    pub fn raw_html_node(&'src self, args: Arguments) -> DynamicNode<'src> {
+        DynamicNode::RawHtml(VRawHtml {
+            value: self.raw_text(args),
+            id: Default::default(),
+        })
+    }
+
     /// Allocate some text inside the [`ScopeState`] from [`Arguments`]
     ///
     /// Uses the currently active [`Bump`] allocator
@@ -466,11 +576,44 @@ impl<'src> ScopeState {
             name: fn_name,
             render_fn: component as *const (),
             static_props: P::IS_STATIC,
+            global_key: None,
             props: RefCell::new(Some(extended)),
             scope: Default::default(),
         })
     }
 
+    /// Create a new [`DynamicNode::Component`] that keeps its state when it moves to another parent.
+    ///
+    /// When a component with a global key is removed and a component with the same key and the same function is
+    /// created in the same render, the new component takes over the scope of the old one, with all of its hooks. The
+    /// key has to be unique across the whole VirtualDom.
+    ///
+    /// This is what `rsx!` calls for components with a `global_key` attribute:
+    ///
+    /// ```rust, ignore
+    /// rsx! {
+    ///     ul { for todo in todos { Todo { global_key: "{todo.id}", todo: todo } } }
+    ///     ul { for todo in done { Todo { global_key: "{todo.id}", todo: todo } } }
+    /// }
+    /// ```
+    pub fn component_with_global_key<'child, P>(
+        &'src self,
+        component: fn(Scope<'child, P>) -> Element<'child>,
+        props: P,
+        fn_name: &'static str,
+        global_key: &'src str,
+    ) -> DynamicNode<'src>
+    where
+        P: Properties + 'src,
+        'src: 'child,
+    {
+        let mut node = self.component(component, props, fn_name);
+        if let DynamicNode::Component(vcomp) = &mut node {
+            vcomp.global_key = Some(global_key);
+        }
+        node
+    }
+
     /// Create a new [`EventHandler`] from an [`FnMut`]
     pub fn event_handler<T>(&'src self, f: impl FnMut(T) + 'src) -> EventHandler<'src, T> {
         let handler: &mut dyn FnMut(T) = self.bump().alloc(f);
@@ -542,29 +685,49 @@ impl<'src> ScopeState {
     /// }
     /// ```
     #[allow(clippy::mut_from_ref)]
+    #[track_caller]
     pub fn use_hook<State: 'static>(&self, initializer: impl FnOnce() -> State) -> &mut State {
         let cur_hook = self.hook_idx.get();
+        let call = HookCall::new::<State>();
         let mut hooks = self.hooks.try_borrow_mut().expect("The hook list is already borrowed: This error is likely caused by trying to use a hook inside a hook which violates the rules of hooks.");
 
         if cur_hook >= hooks.len() {
             hooks.push(Box::new(UnsafeCell::new(initializer())));
+            self.hook_calls.borrow_mut().push(call);
         }
 
-        hooks
-            .get(cur_hook)
-            .and_then(|inn| {
+        let raw_ref = unsafe { &mut *hooks[cur_hook].get() };
+        let mut hook_calls = self.hook_calls.borrow_mut();
+        match raw_ref.downcast_mut::<State>() {
+            Some(state) => {
+                // A hook of the same type from another place in the code still means the order changed, but the
+                // component keeps working with the wrong state, so we only warn about it
+                if cfg!(debug_assertions) && !hook_calls[cur_hook].same_site(&call) {
+                    tracing::warn!(
+                        "{}",
+                        HookOrderDiagnostic {
+                            component: self.name(),
+                            index: cur_hook,
+                            previous: &hook_calls,
+                            current: call,
+                            stack: self.component_stack(),
+                        }
+                    );
+                }
+                hook_calls[cur_hook] = call;
                 self.hook_idx.set(cur_hook + 1);
-                let raw_ref = unsafe { &mut *inn.get() };
-                raw_ref.downcast_mut::<State>()
-            })
-            .expect(
-                r#"
-                Unable to retrieve the hook that was initialized at this index.
-                Consult the `rules of hooks` to understand how to use hooks properly.
-
-                You likely used the hook in a conditional. Hooks rely on consistent ordering between renders.
-                Functions prefixed with "use" should never be called conditionally.
-                "#,
-            )
+                state
+            }
+            None => panic!(
+                "{}",
+                HookOrderDiagnostic {
+                    component: self.name(),
+                    index: cur_hook,
+                    previous: &hook_calls,
+                    current: call,
+                    stack: self.component_stack(),
+                }
+            ),
+        }
     }
 }
@@ -0,0 +1,80 @@
+//! Strict mode renders every component twice in debug builds and warns when the two renders are different.
+//!
+//! A component must return the same nodes every time it runs with the same props and state. If it doesn't, either
+//! the output depends on something outside of the component (like the time or a random number), or the component
+//! changed its own state while it was rendering. Both are bugs that are easy to miss because they only show up when
+//! a component happens to rerender.
+//!
+//! The nodes of the first render are freed before the second render runs, so we compare a text description of each
+//! render instead of the nodes themselves.
+
+use std::fmt::{Display, Write};
+
+use crate::innerlude::{AttributeValue, DynamicNode, RenderReturn, VNode};
+
+/// A description of the output of a render that can be compared after the nodes are freed.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct RenderFingerprint(String);
+
+impl RenderFingerprint {
+    pub(crate) fn new(render: &RenderReturn) -> Self {
+        let mut out = String::new();
+        match render {
+            RenderReturn::Ready(node) => write_node(&mut out, node),
+            RenderReturn::Aborted(_) => out.push_str("<aborted />"),
+        }
+        Self(out)
+    }
+}
+
+impl Display for RenderFingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn write_node(out: &mut String, node: &VNode) {
+    // In debug builds the name of the template is the location of the rsx! call
+    let template = node.template.get().name;
+    let _ = write!(out, "<{}", template);
+    if let Some(key) = node.key {
+        let _ = write!(out, " key={:?}", key);
+    }
+    for attribute in node.dynamic_attrs {
+        let _ = write!(out, " {}=", attribute.name);
+        let _ = match &attribute.value {
+            AttributeValue::Text(value) => write!(out, "{:?}", value),
+            AttributeValue::Float(value) => write!(out, "{}", value),
+            AttributeValue::Int(value) => write!(out, "{}", value),
+            AttributeValue::Bool(value) => write!(out, "{}", value),
+            // Closures are different every render, so we only check that the listener is still there
+            AttributeValue::Listener(_) => write!(out, "listener"),
+            AttributeValue::Any(_) => write!(out, "any"),
+            AttributeValue::None => write!(out, "none"),
+        };
+    }
+    out.push('>');
+
+    for dynamic in node.dynamic_nodes {
+        match dynamic {
+            // The props of child components are checked when the child renders
+            DynamicNode::Component(component) => {
+                let _ = write!(out, "<{} />", component.name);
+            }
+            DynamicNode::Text(text) => {
+                let _ = write!(out, "{:?}", text.value);
+            }
+            DynamicNode::RawHtml(html) => {
+                let _ = write!(out, "<raw {:?} />", html.value);
+            }
+            DynamicNode::Placeholder(_) => out.push_str("<placeholder />"),
+            DynamicNode::Fragment(nodes) => {
+                for node in nodes.iter() {
+                    write_node(out, node);
+                }
+            }
+        }
+    }
+
+    let _ = write!(out, "</{}>", template);
+}
@@ -0,0 +1,259 @@
+use crate::{innerlude::DirtyScope, ScopeId, ScopeState, VirtualDom};
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeSet,
+    future::Future,
+    rc::Rc,
+    time::Duration,
+    time::Instant,
+};
+
+/// How long [`VirtualDom::wait_for_suspense_with_timeouts`] waits for the suspended components below a boundary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuspensePolicy {
+    /// Wait until every suspended component resolved
+    Wait,
+    /// Stop waiting after the duration and render the fallback of the boundary instead
+    Timeout(Duration),
+}
+
+impl Default for SuspensePolicy {
+    fn default() -> Self {
+        Self::Wait
+    }
+}
+
+/// Mark the current component as a suspense boundary with `policy`.
+///
+/// The boundary times out when the components below it are still suspended once its timeout has passed since the
+/// renderer started waiting for them. The component should then render a fallback instead of its children:
+///
+/// ```rust, ignore
+/// fn Comments(cx: Scope) -> Element {
+///     let boundary = use_suspense_boundary(cx, SuspensePolicy::Timeout(Duration::from_millis(200)));
+///
+///     if boundary.timed_out() {
+///         return render! { "Loading comments..." };
+///     }
+///
+///     render! { SlowComments {} }
+/// }
+/// ```
+///
+/// Boundaries only time out while the server waits for suspense. The server sends the boundaries that timed out
+/// with [`VirtualDom::timed_out_suspense_boundaries`], and the client passes them to
+/// [`VirtualDom::with_timed_out_suspense_boundaries`]. Those boundaries start timed out on the client, so its first
+/// render matches the fallback in the page. Right after that render they stop being timed out and render their
+/// children, so the client finishes loading them and replaces the fallback.
+///
+/// Boundaries are matched by the order they are created in, so the server and the client have to create them in the
+/// same order.
+pub fn use_suspense_boundary(cx: &ScopeState, policy: SuspensePolicy) -> &SuspenseBoundary {
+    cx.use_hook(|| {
+        let boundaries = cx
+            .consume_context::<SuspenseBoundaries>()
+            .unwrap_or_else(|| cx.provide_root_context(SuspenseBoundaries::default()));
+        let boundary = SuspenseBoundary::new(cx.scope_id(), boundaries.next_index(), policy);
+
+        if boundaries.resume(boundary.inner.index) {
+            // Render the fallback the server sent once, then load the children
+            boundary.inner.timed_out.set(true);
+            let update = cx.schedule_update();
+            let resumed = boundary.clone();
+            cx.spawn(async move {
+                resumed.inner.timed_out.set(false);
+                update();
+            });
+        }
+
+        cx.provide_context(boundary)
+    })
+}
+
+/// The boundaries of a virtual dom that timed out, by the order the boundaries were created in
+#[derive(Debug, Clone, Default)]
+struct SuspenseBoundaries {
+    inner: Rc<SuspenseBoundariesInner>,
+}
+
+#[derive(Debug, Default)]
+struct SuspenseBoundariesInner {
+    created: Cell<usize>,
+    timed_out: RefCell<BTreeSet<usize>>,
+}
+
+impl SuspenseBoundaries {
+    fn next_index(&self) -> usize {
+        let index = self.inner.created.get();
+        self.inner.created.set(index + 1);
+        index
+    }
+
+    /// Check if the boundary timed out on the server, and forget about it
+    fn resume(&self, index: usize) -> bool {
+        self.inner.timed_out.borrow_mut().remove(&index)
+    }
+}
+
+/// A boundary that decides how long the suspended components below it are waited for
+#[derive(Debug, Clone)]
+pub struct SuspenseBoundary {
+    inner: Rc<SuspenseBoundaryInner>,
+}
+
+#[derive(Debug)]
+struct SuspenseBoundaryInner {
+    id: ScopeId,
+    index: usize,
+    policy: SuspensePolicy,
+    // Set when the renderer starts waiting, because `Instant::now` panics in the browser
+    waiting_since: Cell<Option<Instant>>,
+    timed_out: Cell<bool>,
+}
+
+impl SuspenseBoundary {
+    fn new(id: ScopeId, index: usize, policy: SuspensePolicy) -> Self {
+        Self {
+            inner: Rc::new(SuspenseBoundaryInner {
+                id,
+                index,
+                policy,
+                waiting_since: Cell::new(None),
+                timed_out: Cell::new(false),
+            }),
+        }
+    }
+
+    /// The policy of this boundary
+    pub fn policy(&self) -> SuspensePolicy {
+        self.inner.policy
+    }
+
+    /// Check if the renderer stopped waiting for the components below this boundary. The boundary should render its
+    /// fallback instead of its children.
+    pub fn timed_out(&self) -> bool {
+        self.inner.timed_out.get()
+    }
+
+    /// When the renderer stops waiting for this boundary. A boundary that already timed out isn't waited for again.
+    fn deadline(&self, now: Instant) -> Option<Instant> {
+        match self.inner.policy {
+            SuspensePolicy::Timeout(_) if self.timed_out() => None,
+            SuspensePolicy::Wait => None,
+            SuspensePolicy::Timeout(timeout) => {
+                let waiting_since = self.inner.waiting_since.get().unwrap_or(now);
+                self.inner.waiting_since.set(Some(waiting_since));
+                Some(waiting_since + timeout)
+            }
+        }
+    }
+}
+
+impl VirtualDom {
+    /// Get the boundaries that timed out while the virtual dom waited for suspense, so the client can start them
+    /// timed out too. Send them to the client with the rendered page and pass them to
+    /// [`VirtualDom::with_timed_out_suspense_boundaries`].
+    pub fn timed_out_suspense_boundaries(&self) -> Vec<usize> {
+        self.base_scope()
+            .consume_context::<SuspenseBoundaries>()
+            .map(|boundaries| {
+                boundaries
+                    .inner
+                    .timed_out
+                    .borrow()
+                    .iter()
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Start the boundaries that timed out on the server timed out, so the first render matches the page the server
+    /// sent. Call this before [`VirtualDom::rebuild`] with the boundaries from
+    /// [`VirtualDom::timed_out_suspense_boundaries`].
+    ///
+    /// ```rust, ignore
+    /// let dom = VirtualDom::new(app).with_timed_out_suspense_boundaries(timed_out);
+    /// ```
+    pub fn with_timed_out_suspense_boundaries(self, timed_out: Vec<usize>) -> Self {
+        let boundaries = SuspenseBoundaries::default();
+        boundaries
+            .inner
+            .timed_out
+            .replace(timed_out.into_iter().collect());
+        self.with_root_context(boundaries)
+    }
+
+    /// Render the virtual dom, waiting for all suspense to be finished, except for the suspended components below a
+    /// [`SuspenseBoundary`] whose timeout passed. Those boundaries time out and are rendered again, so they show
+    /// their fallback.
+    ///
+    /// The virtual dom doesn't depend on a runtime, so `sleep` creates the timer for the next timeout, like
+    /// `tokio::time::sleep`.
+    ///
+    /// The mutations will be thrown out, so it's best to use this method for things like SSR that have async content
+    pub async fn wait_for_suspense_with_timeouts<F: Future<Output = ()>>(
+        &mut self,
+        mut sleep: impl FnMut(Duration) -> F,
+    ) {
+        loop {
+            if self.suspended_scopes.is_empty() {
+                return;
+            }
+
+            let now = Instant::now();
+            let mut next_deadline: Option<Instant> = None;
+            let mut timed_out = Vec::new();
+            for &id in &self.suspended_scopes {
+                let Some(boundary) = self
+                    .get_scope(id)
+                    .and_then(|scope| scope.consume_context::<SuspenseBoundary>())
+                else {
+                    continue;
+                };
+                match boundary.deadline(now) {
+                    Some(deadline) if deadline <= now => timed_out.push(boundary),
+                    Some(deadline) => {
+                        next_deadline = Some(next_deadline.map_or(deadline, |d| d.min(deadline)))
+                    }
+                    None => {}
+                }
+            }
+
+            if !timed_out.is_empty() {
+                let boundaries = self.base_scope().consume_context::<SuspenseBoundaries>();
+                for boundary in timed_out {
+                    boundary.inner.timed_out.set(true);
+                    if let Some(boundaries) = &boundaries {
+                        boundaries
+                            .inner
+                            .timed_out
+                            .borrow_mut()
+                            .insert(boundary.inner.index);
+                    }
+                    if let Some(scope) = self.get_scope(boundary.inner.id) {
+                        let height = scope.height();
+                        self.dirty_scopes.insert(DirtyScope {
+                            height,
+                            id: boundary.inner.id,
+                        });
+                    }
+                }
+                _ = self.render_immediate();
+                continue;
+            }
+
+            match next_deadline {
+                Some(deadline) => {
+                    let timer = sleep(deadline.saturating_duration_since(now));
+                    let work = self.wait_for_work();
+                    futures_util::pin_mut!(timer, work);
+                    futures_util::future::select(timer, work).await;
+                }
+                None => self.wait_for_work().await,
+            }
+
+            _ = self.render_immediate();
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Errors that escape every error boundary.
+//!
+//! Errors thrown while rendering are caught by the nearest [`ErrorBoundary`](crate::ErrorBoundary), but panics in
+//! spawned tasks and event handlers, and errors thrown from components without a boundary above them, have nowhere to
+//! go. They are passed to the handler set with [`VirtualDom::set_unhandled_error_handler`](crate::VirtualDom::set_unhandled_error_handler),
+//! or logged if the app didn't set one.
+
+use std::{
+    any::Any,
+    fmt::{Debug, Display, Formatter},
+};
+
+use crate::innerlude::{ComponentStack, ScopeId};
+
+/// Where an [`UnhandledError`] came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnhandledErrorSource {
+    /// A component panicked while rendering
+    Render,
+    /// A task spawned by a component panicked. The task is dropped and will not be polled again.
+    Task,
+    /// An event handler panicked
+    EventHandler {
+        /// The name of the event, without the `on` prefix
+        name: String,
+    },
+    /// An effect queued with [`ScopeState::queue_effect`](crate::ScopeState::queue_effect) panicked
+    Effect,
+    /// A component threw an error with [`Throw`](crate::prelude::Throw), but there is no error boundary above it
+    Thrown,
+}
+
+/// An error or panic that was not caught by any error boundary
+pub struct UnhandledError {
+    /// What the app was doing when the error happened
+    pub source: UnhandledErrorSource,
+
+    /// The error that was thrown, or the message of the panic
+    pub error: Box<dyn Debug + 'static>,
+
+    /// The scope that threw the error, or that owns the task or event handler that panicked
+    pub scope: ScopeId,
+
+    /// The components between the scope and the root of the app
+    pub component_stack: ComponentStack,
+}
+
+impl UnhandledError {
+    pub(crate) fn new(
+        source: UnhandledErrorSource,
+        scope: ScopeId,
+        error: Box<dyn Debug + 'static>,
+    ) -> Self {
+        Self {
+            source,
+            error,
+            scope,
+            component_stack: ComponentStack::capture(scope),
+        }
+    }
+
+    pub(crate) fn from_panic(
+        source: UnhandledErrorSource,
+        scope: ScopeId,
+        payload: Box<dyn Any + Send>,
+    ) -> Self {
+        Self::new(source, scope, Box::new(PanicMessage::new(payload)))
+    }
+}
+
+impl Debug for UnhandledError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnhandledError")
+            .field("source", &self.source)
+            .field("error", &self.error)
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
+impl Display for UnhandledError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            UnhandledErrorSource::Render => write!(f, "A component panicked while rendering")?,
+            UnhandledErrorSource::Task => write!(f, "A task panicked")?,
+            UnhandledErrorSource::Effect => write!(f, "An effect panicked")?,
+            UnhandledErrorSource::EventHandler { name } => {
+                write!(f, "The handler for the `{}` event panicked", name)?
+            }
+            UnhandledErrorSource::Thrown => {
+                write!(f, "An error was thrown outside of an error boundary")?
+            }
+        }
+        write!(
+            f,
+            ": {:?}\nIn scope: {:?}\nComponent stack:\n{}",
+            self.error, self.scope, self.component_stack
+        )
+    }
+}
+
+/// The message of a caught panic
+struct PanicMessage(Option<String>);
+
+impl PanicMessage {
+    fn new(payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => Some(*message),
+            Err(payload) => payload.downcast_ref::<&str>().map(|s| s.to_string()),
+        };
+        Self(message)
+    }
+}
+
+impl Debug for PanicMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(message) => f.write_str(message),
+            None => f.write_str("Box<dyn Any>"),
+        }
+    }
+}
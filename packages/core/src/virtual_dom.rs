@@ -5,18 +5,32 @@
 use crate::{
     any_props::VProps,
     arena::ElementId,
-    innerlude::{DirtyScope, ElementRef, ErrorBoundary, Mutations, Scheduler, SchedulerMsg},
+    global_key::GlobalKeys,
+    innerlude::{
+        DirtyScope, Effect, EffectKind, ElementRef, ErrorBoundary, ListenerCb, Mutations,
+        Scheduler, SchedulerMsg, UnhandledError, UnhandledErrorSource,
+    },
     mutations::Mutation,
     nodes::RenderReturn,
     nodes::{Template, TemplateId},
     runtime::{Runtime, RuntimeGuard},
+    scheduler::YieldNow,
     scopes::{ScopeId, ScopeState},
     AttributeValue, Element, Event, Scope, VNode,
 };
 use futures_util::{pin_mut, StreamExt};
 use rustc_hash::{FxHashMap, FxHashSet};
 use slab::Slab;
-use std::{any::Any, cell::Cell, collections::BTreeSet, future::Future, ptr::NonNull, rc::Rc, sync::Arc};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::BTreeSet,
+    future::Future,
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr::NonNull,
+    rc::Rc,
+    sync::Arc,
+};
 
 /// A virtual node system that progresses user events and diffs UI trees.
 ///
@@ -199,6 +213,18 @@ pub struct VirtualDom {
     pub(crate) suspended_scopes: FxHashSet<ScopeId>,
 
     pub(crate) rx: futures_channel::mpsc::UnboundedReceiver<SchedulerMsg>,
+
+    // Render every component twice in debug builds to catch impure components
+    pub(crate) strict_mode: bool,
+
+    // The scopes of components with a global key
+    pub(crate) global_keys: GlobalKeys,
+
+    // Wait for the other events of the tick before rendering
+    pub(crate) batch_renders: bool,
+
+    // Whether the VirtualDom already yielded for the events of the next render
+    pub(crate) batch_yielded: bool,
 }
 
 impl VirtualDom {
@@ -269,11 +295,16 @@ impl VirtualDom {
             element_refs: Default::default(),
             mutations: Mutations::default(),
             suspended_scopes: Default::default(),
+            strict_mode: false,
+            global_keys: Default::default(),
+            batch_renders: false,
+            batch_yielded: false,
         };
 
         let root = dom.new_scope(
             Box::new(VProps::new(root, |_, _| unreachable!(), root_props)),
             "app",
+            None,
         );
 
         // Unlike react, we provide a default error boundary that just renders the error as a string
@@ -295,6 +326,20 @@ impl VirtualDom {
         self.scopes.get(id.0).map(|s| &**s)
     }
 
+    /// Iterate over every component that is currently mounted
+    ///
+    /// This is useful for tools that inspect the component tree
+    pub fn scopes(&self) -> impl Iterator<Item = &ScopeState> {
+        self.scopes.iter().map(|(_, scope)| &**scope)
+    }
+
+    /// Get an estimate of the memory the mounted components hold onto, in bytes
+    ///
+    /// See [`ScopeState::allocated_bytes`] for what is included in the estimate.
+    pub fn allocated_bytes(&self) -> usize {
+        self.scopes().map(ScopeState::allocated_bytes).sum()
+    }
+
     /// Get the single scope at the top of the VirtualDom tree that will always be around
     ///
     /// This scope has a ScopeId of 0 and is the root of the tree
@@ -310,6 +355,54 @@ impl VirtualDom {
         self
     }
 
+    /// Render every component twice in debug builds and warn when the two renders produce different output
+    ///
+    /// Components should be pure: given the same props and state, they should always return the same nodes and
+    /// never change state while rendering. Strict mode catches components that read values that change between
+    /// renders (like the current time) or update their own state during render. The second render is the one that
+    /// is used, so hooks see one extra render in strict mode.
+    ///
+    /// This has no effect in release builds.
+    ///
+    /// ```rust, ignore
+    /// let dom = VirtualDom::new(app).with_strict_mode(true);
+    /// ```
+    pub fn with_strict_mode(mut self, enabled: bool) -> Self {
+        self.strict_mode = enabled;
+        self
+    }
+
+    /// Batch the updates of the events that arrive in the same tick into a single render.
+    ///
+    /// When a component is marked dirty, [`VirtualDom::wait_for_work`] yields to the executor once before it
+    /// completes, so the renderer handles the other events it already received before it renders. Fast typing then
+    /// renders once per tick instead of once per key. An event handler can skip the wait with
+    /// [`ScopeState::flush_sync`].
+    ///
+    /// Renderers check [`VirtualDom::should_render_after_event`] before they render the updates of an event, and
+    /// leave them to [`VirtualDom::wait_for_work`] otherwise.
+    ///
+    /// ```rust, ignore
+    /// let dom = VirtualDom::new(app).with_render_batching(true);
+    /// ```
+    pub fn with_render_batching(mut self, enabled: bool) -> Self {
+        self.batch_renders = enabled;
+        self
+    }
+
+    /// Set the function that is called with panics in tasks and event handlers, and errors thrown outside of an error
+    /// boundary. Without a handler, these errors are logged.
+    ///
+    /// A task that panics is dropped, and an event handler that panics is called again the next time its event fires.
+    ///
+    /// ```rust, ignore
+    /// let mut dom = VirtualDom::new(app);
+    /// dom.set_unhandled_error_handler(|error| report_crash(error.to_string()));
+    /// ```
+    pub fn set_unhandled_error_handler(&mut self, handler: impl Fn(UnhandledError) + 'static) {
+        *self.runtime.unhandled_error_handler.borrow_mut() = Some(Rc::new(handler));
+    }
+
     /// Manually mark a scope as requiring a re-render
     ///
     /// Whenever the Runtime "works", it will re-render this scope
@@ -337,6 +430,9 @@ impl VirtualDom {
         element: ElementId,
         bubbles: bool,
     ) {
+        // Listeners see the effects of the render that created their element
+        self.flush_effects();
+
         let _runtime = RuntimeGuard::new(self.runtime.clone());
 
         /*
@@ -408,14 +504,7 @@ impl VirtualDom {
                 // We check the bubble state between each call to see if the event has been stopped from bubbling
                 for listener in listeners.drain(..).rev() {
                     if let AttributeValue::Listener(listener) = listener {
-                        let origin = path.scope;
-                        self.runtime.scope_stack.borrow_mut().push(origin);
-                        self.runtime.rendering.set(false);
-                        if let Some(cb) = listener.borrow_mut().as_deref_mut() {
-                            cb(uievent.clone());
-                        }
-                        self.runtime.scope_stack.borrow_mut().pop();
-                        self.runtime.rendering.set(true);
+                        self.call_listener(path.scope, name, listener, uievent.clone());
 
                         if !uievent.propagates.get() {
                             return;
@@ -445,14 +534,7 @@ impl VirtualDom {
                     // Only call the listener if this is the exact target element.
                     if attr.name.trim_start_matches("on") == name && target_path == this_path {
                         if let AttributeValue::Listener(listener) = &attr.value {
-                            let origin = path.scope;
-                            self.runtime.scope_stack.borrow_mut().push(origin);
-                            self.runtime.rendering.set(false);
-                            if let Some(cb) = listener.borrow_mut().as_deref_mut() {
-                                cb(uievent.clone());
-                            }
-                            self.runtime.scope_stack.borrow_mut().pop();
-                            self.runtime.rendering.set(true);
+                            self.call_listener(path.scope, name, listener, uievent.clone());
 
                             break;
                         }
@@ -462,6 +544,37 @@ impl VirtualDom {
         }
     }
 
+    /// Call a listener from the scope that declared it. Panics in the listener are passed to the unhandled error
+    /// handler so one broken handler doesn't take down the whole app.
+    fn call_listener(
+        &self,
+        scope: ScopeId,
+        name: &str,
+        listener: &RefCell<Option<ListenerCb>>,
+        event: Event<dyn Any>,
+    ) {
+        self.runtime.scope_stack.borrow_mut().push(scope);
+        self.runtime.rendering.set(false);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            if let Some(cb) = listener.borrow_mut().as_deref_mut() {
+                cb(event);
+            }
+        }));
+        self.runtime.scope_stack.borrow_mut().pop();
+        self.runtime.rendering.set(true);
+
+        if let Err(payload) = result {
+            self.runtime
+                .report_unhandled_error(UnhandledError::from_panic(
+                    UnhandledErrorSource::EventHandler {
+                        name: name.to_string(),
+                    },
+                    scope,
+                    payload,
+                ));
+        }
+    }
+
     /// Wait for the scheduler to have any work.
     ///
     /// This method polls the internal future queue, waiting for suspense nodes, tasks, or other work. This completes when
@@ -479,6 +592,9 @@ impl VirtualDom {
     /// let sender = dom.get_scheduler_channel();
     /// ```
     pub async fn wait_for_work(&mut self) {
+        // Effects of the last render run before any task is polled
+        self.flush_effects();
+
         let mut some_msg = None;
 
         loop {
@@ -497,6 +613,12 @@ impl VirtualDom {
                         Err(_) => {
                             // If we have any dirty scopes, or finished fiber trees then we should exit
                             if !self.dirty_scopes.is_empty() || !self.suspended_scopes.is_empty() {
+                                // Unless the other events of the tick should be part of the render
+                                if self.should_wait_for_batch() {
+                                    self.batch_yielded = true;
+                                    YieldNow::default().await;
+                                    continue;
+                                }
                                 return;
                             }
 
@@ -508,6 +630,74 @@ impl VirtualDom {
         }
     }
 
+    /// Check if a renderer should render right after it handled an event.
+    ///
+    /// With [render batching](VirtualDom::with_render_batching) the updates of an event wait for
+    /// [`VirtualDom::wait_for_work`], so they are rendered together with the other events of the tick, unless an event
+    /// handler called [`ScopeState::flush_sync`].
+    pub fn should_render_after_event(&self) -> bool {
+        !self.batch_renders || self.runtime.flush_sync.get()
+    }
+
+    /// Whether to yield once before rendering the dirty scopes, so the events of the tick are batched
+    fn should_wait_for_batch(&mut self) -> bool {
+        if !self.batch_renders || self.batch_yielded || self.dirty_scopes.is_empty() {
+            return false;
+        }
+        !self.runtime.flush_sync.take()
+    }
+
+    /// Run the [layout effects](crate::EffectKind::Layout) of the renders so far.
+    ///
+    /// Renderers call this right after they apply the mutations of a render to the real DOM, before they yield to the
+    /// platform, so the effects can measure the DOM before it is painted. Layout effects that are still queued run the
+    /// next time the VirtualDom yields otherwise.
+    pub fn flush_layout_effects(&mut self) {
+        while let Some(effect) = self.runtime.scheduler.effects.pop(EffectKind::Layout) {
+            self.run_effect(effect);
+        }
+    }
+
+    /// Run every queued effect: the [layout effects](crate::EffectKind::Layout) first, then the
+    /// [passive effects](crate::EffectKind::Passive).
+    ///
+    /// This is called by [`VirtualDom::wait_for_work`] and [`VirtualDom::handle_event`], so renderers don't need to
+    /// call it themselves.
+    pub fn flush_effects(&mut self) {
+        // A passive effect may queue a layout effect, which must still run before the next passive effect
+        while !self.runtime.scheduler.effects.is_empty() {
+            self.flush_layout_effects();
+            if let Some(effect) = self.runtime.scheduler.effects.pop(EffectKind::Passive) {
+                self.run_effect(effect);
+            }
+        }
+    }
+
+    /// Run an effect from the scope that queued it. Panics are passed to the unhandled error handler.
+    fn run_effect(&self, effect: Effect) {
+        // The component was unmounted before its render was committed
+        let Some(scope) = self.get_scope(effect.scope) else {
+            return;
+        };
+
+        let _runtime = RuntimeGuard::new(self.runtime.clone());
+        self.runtime.scope_stack.borrow_mut().push(effect.scope);
+        self.runtime.rendering.set(false);
+        let callback = effect.callback;
+        let result = catch_unwind(AssertUnwindSafe(|| callback(scope)));
+        self.runtime.scope_stack.borrow_mut().pop();
+        self.runtime.rendering.set(true);
+
+        if let Err(payload) = result {
+            self.runtime
+                .report_unhandled_error(UnhandledError::from_panic(
+                    UnhandledErrorSource::Effect,
+                    effect.scope,
+                    payload,
+                ));
+        }
+    }
+
     /// Process all events in the queue until there are no more left
     pub fn process_events(&mut self) {
         while let Ok(Some(msg)) = self.rx.try_next() {
@@ -586,6 +776,23 @@ impl VirtualDom {
         self.finalize()
     }
 
+    /// Unmount the whole tree and build it again, for a renderer that lost the nodes it rendered, like a webview whose
+    /// page was loaded again.
+    ///
+    /// The root component keeps its state, but it runs again. The components below it are dropped and created
+    /// again, so their hooks start over. Only components with a global key take their old state over.
+    pub fn remount(&mut self) -> Mutations {
+        let _runtime = RuntimeGuard::new(self.runtime.clone());
+        if let Some(root) = self.scopes[ScopeId::ROOT.0].try_root_node() {
+            // The nodes are gone already, so they are dropped without mutations
+            match unsafe { root.extend_lifetime_ref() } {
+                RenderReturn::Ready(node) => self.remove_node(node, false),
+                RenderReturn::Aborted(placeholder) => self.remove_placeholder(placeholder, false),
+            }
+        }
+        self.rebuild()
+    }
+
     /// Render whatever the VirtualDom has ready as fast as possible without requiring an executor to progress
     /// suspended subtrees.
     pub fn render_immediate(&mut self) -> Mutations {
@@ -635,8 +842,8 @@ impl VirtualDom {
             if let Some(dirty) = self.dirty_scopes.iter().next().cloned() {
                 self.dirty_scopes.remove(&dirty);
 
-                // If the scope doesn't exist for whatever reason, then we should skip it
-                if !self.scopes.contains(dirty.id.0) {
+                // If the scope doesn't exist for whatever reason, or is detached, then we should skip it
+                if !self.scopes.contains(dirty.id.0) || self.global_keys.is_detached(dirty.id) {
                     continue;
                 }
 
@@ -671,6 +878,11 @@ impl VirtualDom {
 
     /// Swap the current mutations with a new
     fn finalize(&mut self) -> Mutations {
+        // Keyed components that were removed and not created again in this render are gone
+        self.drop_detached_scopes();
+        // The next render waits for its own batch
+        self.batch_yielded = false;
+        self.runtime.flush_sync.set(false);
         std::mem::take(&mut self.mutations)
     }
 
@@ -682,6 +894,8 @@ impl VirtualDom {
 
 impl Drop for VirtualDom {
     fn drop(&mut self) {
+        self.drop_detached_scopes();
+
         // Simply drop this scope which drops all of its children
         self.drop_scope(ScopeId::ROOT, true);
     }
@@ -0,0 +1,30 @@
+#![allow(non_snake_case)]
+//! The VirtualDom should be able to estimate how much memory the mounted components hold onto
+
+use dioxus::prelude::*;
+
+#[test]
+fn hooks_count_towards_allocated_bytes() {
+    fn app(cx: Scope<bool>) -> Element {
+        let with_buffer = *cx.props;
+        render! {
+            if with_buffer {
+                rsx! { Buffer {} }
+            }
+        }
+    }
+
+    fn Buffer(cx: Scope) -> Element {
+        cx.use_hook(|| [0u8; 4096]);
+        render! { div {} }
+    }
+
+    let mut empty = VirtualDom::new_with_props(app, false);
+    _ = empty.rebuild();
+
+    let mut buffered = VirtualDom::new_with_props(app, true);
+    _ = buffered.rebuild();
+
+    assert!(empty.allocated_bytes() > 0);
+    assert!(buffered.allocated_bytes() >= empty.allocated_bytes() + 4096);
+}
@@ -0,0 +1,61 @@
+use dioxus::prelude::*;
+use std::time::Duration;
+
+#[derive(Clone)]
+struct Config {
+    title: &'static str,
+}
+
+#[test]
+fn consumers_suspend_until_resolved() {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let mut dom = VirtualDom::new(app);
+            _ = dom.rebuild();
+            dom.wait_for_suspense().await;
+            assert_eq!(
+                dioxus_ssr::render(&dom),
+                "<div><h1>Loaded</h1><p>Loaded</p></div>"
+            );
+        });
+}
+
+fn app(cx: Scope) -> Element {
+    cx.use_hook(|| {
+        cx.provide_async_context(async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Config { title: "Loaded" }
+        })
+    });
+
+    render!(
+        div {
+            page_title {}
+            page_body {}
+        }
+    )
+}
+
+fn page_title(cx: Scope) -> Element {
+    let config = cx.consume_async_context::<Config>()?;
+    render!(h1 { "{config.title}" })
+}
+
+fn page_body(cx: Scope) -> Element {
+    let config = cx.consume_async_context::<Config>()?;
+    render!(p { "{config.title}" })
+}
+
+#[test]
+fn missing_provider() {
+    let mut dom = VirtualDom::new(|cx| {
+        let value = cx.consume_async_context::<Config>();
+        assert!(value.is_none());
+        render!("no provider")
+    });
+    _ = dom.rebuild();
+    assert_eq!(dioxus_ssr::render(&dom), "no provider");
+}
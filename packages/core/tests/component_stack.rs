@@ -0,0 +1,49 @@
+#![allow(non_snake_case)]
+//! Errors thrown by components should know which components they were thrown from
+
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type SharedBoundary = Rc<RefCell<Option<ErrorBoundary>>>;
+
+fn app(cx: Scope<SharedBoundary>) -> Element {
+    let boundary = use_error_boundary(cx);
+    cx.props.replace(Some(boundary.clone()));
+
+    render! { Child {} }
+}
+
+fn Child(cx: Scope) -> Element {
+    render! { GrandChild {} }
+}
+
+fn GrandChild(_cx: Scope) -> Element {
+    Err::<(), _>("oops").throw()?;
+    None
+}
+
+#[test]
+fn thrown_errors_capture_component_stack() {
+    let boundary = SharedBoundary::default();
+    let mut dom = VirtualDom::new_with_props(app, boundary.clone());
+    _ = dom.rebuild();
+
+    let error = boundary
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .take_error()
+        .expect("the error should reach the boundary");
+
+    let frames = error.component_stack.frames();
+    let names: Vec<_> = frames.iter().map(|frame| frame.name).collect();
+    assert_eq!(names, ["GrandChild", "Child", "app"]);
+
+    // Components know which rsx! call rendered them
+    assert!(frames[0].location.unwrap().contains("component_stack.rs"));
+    assert!(frames[1].location.unwrap().contains("component_stack.rs"));
+    assert_eq!(frames[2].location, None);
+
+    assert!(error.to_string().contains("in GrandChild (at "));
+}
@@ -0,0 +1,78 @@
+//! Verify that effects run after the render is committed, layout effects before passive effects
+
+use dioxus::prelude::*;
+use dioxus_core::ElementId;
+use std::{cell::RefCell, rc::Rc};
+
+thread_local! {
+    static LOG: RefCell<Vec<&'static str>> = RefCell::new(Vec::new());
+}
+
+fn log(message: &'static str) {
+    LOG.with(|log| log.borrow_mut().push(message));
+}
+
+fn take_log() -> Vec<&'static str> {
+    LOG.with(|log| std::mem::take(&mut *log.borrow_mut()))
+}
+
+#[test]
+fn effects_wait_for_the_render() {
+    take_log();
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+    assert_eq!(take_log(), ["render app", "render child"]);
+
+    dom.flush_layout_effects();
+    assert_eq!(take_log(), ["layout app", "layout child"]);
+
+    dom.flush_effects();
+    assert_eq!(take_log(), ["passive app"]);
+}
+
+#[test]
+fn layout_effects_run_before_passive_effects() {
+    take_log();
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+    take_log();
+
+    dom.flush_effects();
+    assert_eq!(take_log(), ["layout app", "layout child", "passive app"]);
+}
+
+#[test]
+fn events_see_the_effects_of_the_render() {
+    take_log();
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+    take_log();
+
+    dom.handle_event("click", Rc::new(MouseData::default()), ElementId(1), true);
+    assert_eq!(
+        take_log(),
+        ["layout app", "layout child", "passive app", "clicked"]
+    );
+}
+
+fn app(cx: Scope) -> Element {
+    log("render app");
+    cx.use_hook(|| {
+        cx.queue_effect(EffectKind::Passive, |_| log("passive app"));
+        cx.queue_effect(EffectKind::Layout, |_| log("layout app"));
+    });
+
+    render! {
+        div {
+            onclick: |_| log("clicked"),
+            child {}
+        }
+    }
+}
+
+fn child(cx: Scope) -> Element {
+    log("render child");
+    cx.use_hook(|| cx.queue_effect(EffectKind::Layout, |_| log("layout child")));
+
+    render! { "child" }
+}
@@ -0,0 +1,117 @@
+#![allow(non_snake_case)]
+//! Components with a global key keep their hook state when they move to another parent
+use dioxus::core::Mutation::*;
+use dioxus::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[derive(Clone, Default, PartialEq)]
+struct Drops(Rc<Cell<usize>>);
+
+struct DropCounter(Drops);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0 .0.set(self.0 .0.get() + 1);
+    }
+}
+
+#[derive(Props, PartialEq)]
+struct ItemProps {
+    id: usize,
+    drops: Drops,
+}
+
+fn Item(cx: Scope<ItemProps>) -> Element {
+    cx.use_hook(|| DropCounter(cx.props.drops.clone()));
+    let id = cx.props.id;
+    render!("item {id}")
+}
+
+fn created_text<'a>(edits: &[Mutation<'a>]) -> Vec<&'a str> {
+    edits
+        .iter()
+        .filter_map(|edit| match edit {
+            CreateTextNode { value, .. } | HydrateText { value, .. } => Some(*value),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn moving_between_parents_keeps_state() {
+    fn app(cx: Scope<Drops>) -> Element {
+        // The id is only read when the hook of the item is created
+        let next_id = cx.use_hook(|| 0);
+        let in_first = cx.generation() % 2 == 0;
+        *next_id += 1;
+        let id = *next_id;
+        let first = if in_first { 1 } else { 0 };
+
+        render! {
+            div { (0..first).map(|_| rsx!(Keyed { global_key: "item", id: id, drops: cx.props.clone() })) }
+            div { (first..1).map(|_| rsx!(Keyed { global_key: "item", id: id, drops: cx.props.clone() })) }
+        }
+    }
+
+    // Keeps the id it was first rendered with
+    fn Keyed(cx: Scope<ItemProps>) -> Element {
+        let first_id = *cx.use_hook(|| cx.props.id);
+        render!(Item { id: first_id, drops: cx.props.drops.clone() })
+    }
+
+    let drops = Drops::default();
+    let mut dom = VirtualDom::new_with_props(app, drops.clone());
+    let edits = dom.rebuild();
+    assert_eq!(created_text(&edits.edits), ["item 1"]);
+
+    // Moves from the first list to the second: the old position is removed before the new one is created
+    dom.mark_dirty(ScopeId::ROOT);
+    let edits = dom.render_immediate();
+    assert_eq!(created_text(&edits.edits), ["item 1"]);
+
+    // Moves back: the new position is created before the old one is removed, so the old position gets a fresh
+    // scope until the second list removes it
+    dom.mark_dirty(ScopeId::ROOT);
+    let edits = dom.render_immediate();
+    assert_eq!(created_text(&edits.edits), ["item 2", "item 1"]);
+
+    // The children of the moved component are rendered again, and the fresh scope is dropped with its children
+    assert_eq!(drops.0.get(), 3);
+}
+
+#[test]
+fn removed_keyed_components_are_dropped() {
+    fn app(cx: Scope<Drops>) -> Element {
+        let count = if cx.generation() == 0 { 1 } else { 0 };
+        render! {
+            (0..count).map(|_| rsx!(Item { global_key: "item", id: 0, drops: cx.props.clone() }))
+        }
+    }
+
+    let drops = Drops::default();
+    let mut dom = VirtualDom::new_with_props(app, drops.clone());
+    _ = dom.rebuild();
+
+    dom.mark_dirty(ScopeId::ROOT);
+    _ = dom.render_immediate();
+    assert_eq!(drops.0.get(), 1);
+}
+
+#[test]
+fn changing_the_global_key_replaces_the_component() {
+    fn app(cx: Scope<Drops>) -> Element {
+        let key = if cx.generation() == 0 { "a" } else { "b" };
+        let id = cx.generation();
+        render! { Item { global_key: "{key}", id: id, drops: cx.props.clone() } }
+    }
+
+    let drops = Drops::default();
+    let mut dom = VirtualDom::new_with_props(app, drops.clone());
+    _ = dom.rebuild();
+
+    dom.mark_dirty(ScopeId::ROOT);
+    let edits = dom.render_immediate();
+    assert_eq!(created_text(&edits.edits), ["item 1"]);
+    assert_eq!(drops.0.get(), 1);
+}
@@ -0,0 +1,56 @@
+//! Hooks called in a different order between renders are reported with both hook sequences
+use dioxus::prelude::*;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[test]
+fn conditional_hook_reports_both_sequences() {
+    fn app(cx: Scope) -> Element {
+        let renders = cx.use_hook(|| 0);
+        *renders += 1;
+        if *renders > 1 {
+            cx.use_hook(|| "only called after the first render");
+        }
+        cx.use_hook(|| 0u32);
+        render! { div {} }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+
+    dom.mark_dirty(ScopeId::ROOT);
+    let panic = catch_unwind(AssertUnwindSafe(|| {
+        _ = dom.render_immediate();
+    }))
+    .expect_err("changing the hook order should panic");
+
+    let message = panic
+        .downcast_ref::<String>()
+        .expect("the panic should have a formatted message");
+    assert!(
+        message.contains("Hook 1 of the component `app`"),
+        "{}",
+        message
+    );
+    assert!(message.contains("Previous render:"), "{}", message);
+    assert!(message.contains("> 1: u32 at "), "{}", message);
+    assert!(message.contains("> 1: &str at "), "{}", message);
+    assert!(message.contains(file!()), "{}", message);
+}
+
+#[test]
+fn stable_hook_order_does_not_panic() {
+    fn app(cx: Scope) -> Element {
+        let count = cx.use_hook(|| 0);
+        *count += 1;
+        cx.use_hook(|| "always called");
+        render! { div {} }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+
+    for _ in 0..3 {
+        dom.mark_dirty(ScopeId::ROOT);
+        _ = dom.render_immediate();
+    }
+}
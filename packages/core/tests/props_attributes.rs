@@ -0,0 +1,63 @@
+//! Props can validate their values, compute their defaults with functions and mark fields as deprecated
+use dioxus::prelude::*;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+fn check_percent(value: &u8) -> Result<(), String> {
+    if *value <= 100 {
+        Ok(())
+    } else {
+        Err(format!("{value} is more than 100"))
+    }
+}
+
+fn default_label() -> String {
+    "untitled".to_string()
+}
+
+#[derive(Props, PartialEq)]
+struct ProgressProps {
+    #[props(validate = check_percent)]
+    percent: u8,
+    #[props(default_with = default_label)]
+    label: String,
+    #[props(default, deprecated = "use `label` instead")]
+    title: Option<String>,
+}
+
+#[test]
+fn defaults_are_computed_with_functions() {
+    let props = ProgressProps::builder().percent(50).build();
+    assert_eq!(props.label, "untitled");
+
+    let props = ProgressProps::builder()
+        .percent(50)
+        .label("upload".to_string())
+        .build();
+    assert_eq!(props.label, "upload");
+}
+
+#[test]
+fn invalid_props_panic_with_the_validation_error() {
+    let panic = catch_unwind(AssertUnwindSafe(|| {
+        ProgressProps::builder().percent(150).build();
+    }))
+    .expect_err("an invalid prop should panic");
+
+    let message = panic
+        .downcast_ref::<String>()
+        .expect("the panic should have a formatted message");
+    assert_eq!(
+        message,
+        "Invalid value for the prop `percent` of `ProgressProps`: 150 is more than 100"
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_props_can_still_be_set() {
+    let props = ProgressProps::builder()
+        .percent(10)
+        .title("old".to_string())
+        .build();
+    assert_eq!(props.title.as_deref(), Some("old"));
+}
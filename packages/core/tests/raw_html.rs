@@ -0,0 +1,36 @@
+//! Raw html nodes are created once and then only updated when the html changes
+
+use dioxus::core::{ElementId, Mutation::*};
+use dioxus::prelude::*;
+
+#[test]
+fn raw_html_is_patched_in_place() {
+    fn app(cx: Scope) -> Element {
+        let gen = cx.generation() / 2;
+        render! {
+            div { cx.raw_html_node(format_args!("<b>{gen}</b>")) }
+        }
+    }
+
+    let mut dom = VirtualDom::new(app);
+
+    assert_eq!(
+        dom.rebuild().santize().edits,
+        [
+            LoadTemplate { name: "template", index: 0, id: ElementId(1) },
+            CreateRawHtml { value: "<b>0</b>", id: ElementId(2) },
+            ReplacePlaceholder { path: &[0], m: 1 },
+            AppendChildren { m: 1, id: ElementId(0) },
+        ]
+    );
+
+    // the html didn't change, so nothing is sent to the renderer
+    dom.mark_dirty(ScopeId::ROOT);
+    assert!(dom.render_immediate().edits.is_empty());
+
+    dom.mark_dirty(ScopeId::ROOT);
+    assert_eq!(
+        dom.render_immediate().edits,
+        [SetRawHtml { value: "<b>1</b>", id: ElementId(2) }]
+    );
+}
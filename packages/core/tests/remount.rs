@@ -0,0 +1,59 @@
+#![allow(non_snake_case)]
+//! Remounting builds the tree again for a renderer that lost its nodes
+use dioxus::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[derive(Clone, Default, PartialEq)]
+struct Drops(Rc<Cell<usize>>);
+
+struct DropCounter(Drops);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0 .0.set(self.0 .0.get() + 1);
+    }
+}
+
+#[derive(Props, PartialEq)]
+struct ChildProps {
+    drops: Drops,
+}
+
+#[test]
+fn remount_keeps_the_root_and_recreates_children() {
+    fn app(cx: Scope<Drops>) -> Element {
+        let renders = cx.use_hook(|| 0);
+        *renders += 1;
+        let renders = *renders;
+        render! {
+            "root {renders}"
+            Child { drops: cx.props.clone() }
+        }
+    }
+
+    fn Child(cx: Scope<ChildProps>) -> Element {
+        cx.use_hook(|| DropCounter(cx.props.drops.clone()));
+        render!("child")
+    }
+
+    let drops = Drops::default();
+    let mut dom = VirtualDom::new_with_props(app, drops.clone());
+    _ = dom.rebuild();
+    let scopes = dom.scopes().count();
+
+    let edits = dom.remount();
+    assert!(!edits.edits.is_empty());
+    assert!(edits.edits.iter().any(|edit| matches!(
+        edit,
+        Mutation::CreateTextNode { value: "root 2", .. }
+            | Mutation::HydrateText { value: "root 2", .. }
+    )));
+    // The old child was dropped and a new one created in its place
+    assert_eq!(drops.0.get(), 1);
+    assert_eq!(dom.scopes().count(), scopes);
+
+    _ = dom.remount();
+    assert_eq!(drops.0.get(), 2);
+    assert_eq!(dom.scopes().count(), scopes);
+}
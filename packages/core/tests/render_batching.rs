@@ -0,0 +1,137 @@
+//! Render batching waits for the other events of the tick before rendering
+use dioxus::prelude::*;
+use dioxus_core::ElementId;
+use futures_channel::mpsc::UnboundedReceiver;
+use futures_util::future::{select, Either};
+use futures_util::{pin_mut, FutureExt, StreamExt};
+use std::{cell::Cell, rc::Rc};
+
+thread_local! {
+    static RENDERS: Cell<usize> = Cell::new(0);
+}
+
+fn renders() -> usize {
+    RENDERS.with(Cell::get)
+}
+
+fn click(dom: &mut VirtualDom) {
+    dom.handle_event("click", Rc::new(MouseData::default()), ElementId(1), true);
+}
+
+fn counter(cx: Scope<bool>) -> Element {
+    RENDERS.with(|renders| renders.set(renders.get() + 1));
+    let count = use_state(cx, || 0);
+    let flush_sync = *cx.props;
+
+    render! {
+        button {
+            onclick: move |_| {
+                count.modify(|count| count + 1);
+                if flush_sync {
+                    cx.flush_sync();
+                }
+            },
+            "{count}"
+        }
+    }
+}
+
+/// A renderer loop like the one of the web and liveview renderers, that clicks the button for every event it receives.
+/// Returns how many frames it rendered once the events stop.
+async fn run_renderer(dom: &mut VirtualDom, mut events: UnboundedReceiver<()>) -> usize {
+    let mut frames = 0;
+    loop {
+        let event = {
+            let work = dom.wait_for_work();
+            pin_mut!(work);
+            match select(work, events.next()).await {
+                Either::Left(_) => None,
+                Either::Right((Some(event), _)) => Some(event),
+                Either::Right((None, _)) => break,
+            }
+        };
+
+        if event.is_some() {
+            click(dom);
+            if !dom.should_render_after_event() {
+                continue;
+            }
+        }
+
+        if !dom.render_immediate().edits.is_empty() {
+            frames += 1;
+        }
+    }
+
+    // Render what is left of the last batch
+    if !dom.render_immediate().edits.is_empty() {
+        frames += 1;
+    }
+    frames
+}
+
+/// Send `clicks` events to a renderer loop at once, and count the frames and the renders of the component
+fn frames_for_clicks(batching: bool, flush_sync: bool, clicks: usize) -> (usize, usize) {
+    let mut dom = VirtualDom::new_with_props(counter, flush_sync).with_render_batching(batching);
+    _ = dom.rebuild();
+    let before = renders();
+
+    let (sender, receiver) = futures_channel::mpsc::unbounded();
+    for _ in 0..clicks {
+        sender.unbounded_send(()).unwrap();
+    }
+    drop(sender);
+
+    let frames = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(run_renderer(&mut dom, receiver));
+    (frames, renders() - before)
+}
+
+#[test]
+fn events_in_the_same_tick_render_once() {
+    assert_eq!(frames_for_clicks(true, false, 2), (1, 1));
+}
+
+#[test]
+fn without_batching_every_event_renders() {
+    assert_eq!(frames_for_clicks(false, false, 2), (2, 2));
+}
+
+#[test]
+fn flush_sync_renders_every_event() {
+    assert_eq!(frames_for_clicks(true, true, 2), (2, 2));
+}
+
+#[test]
+fn without_batching_work_is_ready_right_away() {
+    let mut dom = VirtualDom::new_with_props(counter, false);
+    _ = dom.rebuild();
+
+    click(&mut dom);
+    assert!(dom.wait_for_work().now_or_never().is_some());
+}
+
+#[test]
+fn flush_sync_skips_the_batch() {
+    let mut dom = VirtualDom::new_with_props(counter, true).with_render_batching(true);
+    _ = dom.rebuild();
+
+    click(&mut dom);
+    assert!(dom.wait_for_work().now_or_never().is_some());
+}
+
+#[test]
+fn every_render_waits_for_its_own_batch() {
+    let mut dom = VirtualDom::new_with_props(counter, false).with_render_batching(true);
+    _ = dom.rebuild();
+
+    click(&mut dom);
+    assert!(dom.wait_for_work().now_or_never().is_none());
+    assert!(dom.wait_for_work().now_or_never().is_some());
+    _ = dom.render_immediate();
+
+    click(&mut dom);
+    assert!(dom.wait_for_work().now_or_never().is_none());
+}
@@ -0,0 +1,64 @@
+//! Strict mode renders components twice in debug builds
+use dioxus::core::{ElementId, Mutation::*};
+use dioxus::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[test]
+fn strict_mode_renders_twice() {
+    fn app(cx: Scope<Rc<Cell<usize>>>) -> Element {
+        cx.props.set(cx.props.get() + 1);
+        render! { div { "hello" } }
+    }
+
+    let renders = Rc::new(Cell::new(0));
+    let mut dom = VirtualDom::new_with_props(app, renders.clone()).with_strict_mode(true);
+    _ = dom.rebuild();
+
+    if cfg!(debug_assertions) {
+        assert_eq!(renders.get(), 2);
+    } else {
+        assert_eq!(renders.get(), 1);
+    }
+}
+
+#[test]
+fn strict_mode_off_renders_once() {
+    fn app(cx: Scope<Rc<Cell<usize>>>) -> Element {
+        cx.props.set(cx.props.get() + 1);
+        render! { div { "hello" } }
+    }
+
+    let renders = Rc::new(Cell::new(0));
+    let mut dom = VirtualDom::new_with_props(app, renders.clone());
+    _ = dom.rebuild();
+
+    assert_eq!(renders.get(), 1);
+}
+
+#[test]
+fn strict_mode_uses_second_render() {
+    fn app(cx: Scope) -> Element {
+        // Changing state while rendering is what strict mode is meant to catch
+        let count = cx.use_hook(|| 0);
+        *count += 1;
+        let count = *count;
+
+        render! {
+            button { onclick: move |_| {}, "{count}" }
+        }
+    }
+
+    let mut dom = VirtualDom::new(app).with_strict_mode(true);
+    let expected = if cfg!(debug_assertions) { "2" } else { "1" };
+
+    assert_eq!(
+        dom.rebuild().santize().edits,
+        [
+            LoadTemplate { name: "template", index: 0, id: ElementId(1) },
+            NewEventListener { name: "click", id: ElementId(1) },
+            HydrateText { path: &[0], value: expected, id: ElementId(2) },
+            AppendChildren { m: 1, id: ElementId(0) }
+        ]
+    );
+}
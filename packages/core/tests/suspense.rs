@@ -41,3 +41,119 @@ fn suspended_child(cx: Scope) -> Element {
 
     render!("child")
 }
+
+#[test]
+fn boundary_times_out() {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let mut dom = VirtualDom::new(|cx| render!(div { slow_boundary {} }));
+            _ = dom.rebuild();
+            dom.wait_for_suspense_with_timeouts(tokio::time::sleep)
+                .await;
+
+            assert_eq!(dioxus_ssr::render(&dom), "<div>Loading...</div>");
+        });
+}
+
+#[test]
+fn boundary_resolves_before_timeout() {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let mut dom = VirtualDom::new(|cx| render!(div { patient_boundary {} }));
+            _ = dom.rebuild();
+            dom.wait_for_suspense_with_timeouts(tokio::time::sleep)
+                .await;
+
+            assert_eq!(dioxus_ssr::render(&dom), "<div>child</div>");
+        });
+}
+
+#[test]
+fn timed_out_boundary_hydrates_with_fallback() {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let mut server = VirtualDom::new(|cx| render!(div { slow_on_server_boundary {} }))
+                .with_root_context(OnServer);
+            _ = server.rebuild();
+            server
+                .wait_for_suspense_with_timeouts(tokio::time::sleep)
+                .await;
+            let html = dioxus_ssr::pre_render(&server);
+            let timed_out = server.timed_out_suspense_boundaries();
+            assert_eq!(timed_out, [0]);
+
+            // The first render of the client has to match the fallback the server sent
+            let mut client = VirtualDom::new(|cx| render!(div { slow_on_server_boundary {} }))
+                .with_timed_out_suspense_boundaries(timed_out);
+            _ = client.rebuild();
+            assert_eq!(dioxus_ssr::pre_render(&client), html);
+
+            // Then the client loads the children itself
+            client.wait_for_work().await;
+            _ = client.render_immediate();
+            assert_eq!(dioxus_ssr::render(&client), "<div>resolved</div>");
+        });
+}
+
+#[derive(Clone)]
+struct OnServer;
+
+fn slow_on_server_boundary(cx: Scope) -> Element {
+    let boundary = use_suspense_boundary(
+        cx,
+        SuspensePolicy::Timeout(std::time::Duration::from_millis(10)),
+    );
+
+    if boundary.timed_out() {
+        return render!("Loading...");
+    }
+
+    render!(slow_on_server {})
+}
+
+fn slow_on_server(cx: Scope) -> Element {
+    if cx.consume_context::<OnServer>().is_some() {
+        cx.suspend()?;
+    }
+    render!("resolved")
+}
+
+fn slow_boundary(cx: Scope) -> Element {
+    let boundary = use_suspense_boundary(
+        cx,
+        SuspensePolicy::Timeout(std::time::Duration::from_millis(10)),
+    );
+
+    if boundary.timed_out() {
+        return render!("Loading...");
+    }
+
+    render!(never_resolves {})
+}
+
+fn patient_boundary(cx: Scope) -> Element {
+    let boundary = use_suspense_boundary(
+        cx,
+        SuspensePolicy::Timeout(std::time::Duration::from_secs(60)),
+    );
+
+    if boundary.timed_out() {
+        return render!("Loading...");
+    }
+
+    render!(suspended_child {})
+}
+
+fn never_resolves(cx: Scope) -> Element {
+    cx.suspend()?;
+    render!("resolved")
+}
@@ -0,0 +1,99 @@
+//! Panics in tasks and event handlers, and errors thrown without an error boundary, are passed to the unhandled error
+//! handler of the VirtualDom
+use dioxus::prelude::*;
+use dioxus_core::{ElementId, UnhandledError, UnhandledErrorSource};
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+type Reported = Rc<RefCell<Vec<UnhandledError>>>;
+
+fn collect_errors(dom: &mut VirtualDom) -> Reported {
+    let reported = Reported::default();
+    dom.set_unhandled_error_handler({
+        let reported = reported.clone();
+        move |error| reported.borrow_mut().push(error)
+    });
+    reported
+}
+
+#[test]
+fn event_handler_panics_are_reported() {
+    fn app(cx: Scope) -> Element {
+        render! {
+            div { onclick: move |_| panic!("clicked too hard") }
+        }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let reported = collect_errors(&mut dom);
+    _ = dom.rebuild();
+
+    dom.handle_event("click", Rc::new(MouseData::default()), ElementId(1), true);
+
+    let reported = reported.borrow();
+    assert_eq!(reported.len(), 1);
+    assert_eq!(
+        reported[0].source,
+        UnhandledErrorSource::EventHandler { name: "click".to_string() }
+    );
+    assert_eq!(reported[0].scope, ScopeId::ROOT);
+    assert_eq!(format!("{:?}", reported[0].error), "clicked too hard");
+}
+
+#[test]
+fn thrown_errors_without_a_boundary_are_reported() {
+    fn app(cx: Scope) -> Element {
+        render! { Child {} }
+    }
+
+    #[allow(non_snake_case)]
+    fn Child(_cx: Scope) -> Element {
+        Err::<(), _>("no boundary").throw()?;
+        None
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let reported = collect_errors(&mut dom);
+    _ = dom.rebuild();
+
+    let reported = reported.borrow();
+    assert_eq!(reported.len(), 1);
+    assert_eq!(reported[0].source, UnhandledErrorSource::Thrown);
+    let names: Vec<_> = reported[0]
+        .component_stack
+        .frames()
+        .iter()
+        .map(|frame| frame.name)
+        .collect();
+    assert_eq!(names, ["Child", "app"]);
+}
+
+#[cfg(not(miri))]
+#[tokio::test]
+async fn task_panics_are_reported() {
+    fn app(cx: Scope) -> Element {
+        cx.use_hook(|| {
+            cx.spawn(async {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                panic!("the task failed");
+            })
+        });
+        render! { div {} }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let reported = collect_errors(&mut dom);
+    _ = dom.rebuild();
+
+    tokio::select! {
+        _ = dom.wait_for_work() => {}
+        _ = tokio::time::sleep(Duration::from_millis(500)) => {}
+    };
+
+    let reported = reported.borrow();
+    assert_eq!(reported.len(), 1);
+    assert_eq!(reported[0].source, UnhandledErrorSource::Task);
+    assert_eq!(format!("{:?}", reported[0].error), "the task failed");
+    assert!(reported[0]
+        .to_string()
+        .starts_with("A task panicked: the task failed"));
+}
@@ -50,21 +50,55 @@ fn main() {
       }
     }"#;
     let polling_request = format!(
-        r#"// Poll for requests
+        r#"// Wait for edits
     window.interpreter.wait_for_request = (headless) => {{
+      // The edits hold every edit batch rendered since the last request, each prefixed with its length. `applied` is
+      // called once they are in the DOM.
+      const apply = (bytes, applied) => {{
+        const run = () => {{
+          const view = new DataView(bytes);
+          let offset = 0;
+          while (offset < bytes.byteLength) {{
+            const length = view.getUint32(offset, true);
+            offset += 4;
+            run_from_bytes(bytes.slice(offset, offset + length));
+            offset += length;
+          }}
+          if (applied) {{
+            applied();
+          }}
+        }};
+        // In headless mode, the requestAnimationFrame callback is never called, so we need to run the bytes directly
+        if (headless) {{
+          run();
+        }}
+        else {{
+          requestAnimationFrame(run);
+        }}
+      }};
+
+      // With the script transport, the app pushes the edits to the page after it asked for them over IPC. The next
+      // edits are only asked for once these are applied, so the app holds back and coalesces the edits of the frames
+      // the page can't keep up with.
+      if (window.__dioxusEditTransport === "script") {{
+        const request = () => window.ipc.postMessage(window.interpreter.serializeIpcMessage("edits"));
+        window.interpreter.push_edits = (encoded) => {{
+          const binary = atob(encoded);
+          const bytes = new Uint8Array(binary.length);
+          for (let i = 0; i < binary.length; i++) {{
+            bytes[i] = binary.charCodeAt(i);
+          }}
+          apply(bytes.buffer, request);
+        }};
+        request();
+        return;
+      }}
+
       fetch(new Request("{EDITS_PATH}"))
           .then(response => {{
               response.arrayBuffer()
                   .then(bytes => {{
-                      // In headless mode, the requestAnimationFrame callback is never called, so we need to run the bytes directly
-                      if (headless) {{
-                        run_from_bytes(bytes);
-                      }}
-                      else {{
-                        requestAnimationFrame(() => {{
-                          run_from_bytes(bytes);
-                        }});
-                      }}
+                      apply(bytes);
                       window.interpreter.wait_for_request(headless);
                   }});
           }})
@@ -82,7 +116,18 @@ fn main() {
         interpreter.replace_range(import_start..import_end, "");
     }
 
-    let js = format!("{interpreter}\nconst config = new InterpreterConfig(false);");
+    // Starts the interpreter once the page is parsed. The snapshot of the first frame is already waiting in the
+    // edit queue, so it is applied by the first request for edits.
+    let start = r#"window.interpreter.start = (rootname, headless) => {
+      const root_element = window.document.getElementById(rootname);
+      if (root_element != null) {
+        window.interpreter.initialize(root_element);
+        window.ipc.postMessage(window.interpreter.serializeIpcMessage("initialize"));
+      }
+      window.interpreter.wait_for_request(headless);
+    }"#;
+
+    let js = format!("{interpreter}\n{start}\nconst config = new InterpreterConfig(false);");
 
     use minify_js::*;
     let session = Session::new();
@@ -0,0 +1,273 @@
+//! Asset directories packed into a compressed archive and embedded in the executable.
+//!
+//! A build script packs the directory into `OUT_DIR` with [`pack_assets`], and the app embeds and serves the archive
+//! with [`include_assets!`](crate::include_assets). The app is then a single file that doesn't need its assets
+//! installed next to it.
+//!
+//! Each file is compressed with gzip on its own, so a file is only decompressed when it is requested, and not at all
+//! when the webview accepts gzip: the compressed bytes are sent as they are stored.
+//!
+//! The archive starts with `DXAR` and a version byte, followed by the number of files as a `u32`. Each file is its
+//! path as a `u16` length and UTF-8 bytes with `/` separators, its size as a `u64`, a hash of its contents as a `u64`,
+//! and its gzip compressed contents as a `u32` length and the bytes. Every number is little endian.
+
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8] = b"DXAR";
+const VERSION: u8 = 1;
+
+/// Pack the files in `directory` into an archive at `out`. Symbolic links to directories are skipped. Call it from a
+/// build script:
+///
+/// ```rust, ignore
+/// // build.rs, with dioxus-desktop as a build dependency with the `archive` feature
+/// fn main() {
+///     let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("assets.dxar");
+///     dioxus_desktop::pack_assets("assets", out).unwrap();
+///     println!("cargo:rerun-if-changed=assets");
+/// }
+/// ```
+pub fn pack_assets(directory: impl AsRef<Path>, out: impl AsRef<Path>) -> io::Result<()> {
+    let directory = directory.as_ref();
+    let mut files = Vec::new();
+    collect_files(directory, directory, &mut files)?;
+    // Sorted files make the same archive from the same directory, so cargo doesn't rebuild the app for nothing
+    files.sort();
+
+    let mut archive = Vec::new();
+    archive.extend_from_slice(MAGIC);
+    archive.push(VERSION);
+    archive.extend_from_slice(&(files.len() as u32).to_le_bytes());
+    for relative in files {
+        let contents = std::fs::read(directory.join(&relative))?;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(&contents)?;
+        let compressed = encoder.finish()?;
+
+        let path = relative.as_bytes();
+        let path_len = u16::try_from(path.len()).map_err(|_| too_large(&relative))?;
+        let compressed_len = u32::try_from(compressed.len()).map_err(|_| too_large(&relative))?;
+        archive.extend_from_slice(&path_len.to_le_bytes());
+        archive.extend_from_slice(path);
+        archive.extend_from_slice(&(contents.len() as u64).to_le_bytes());
+        archive.extend_from_slice(&fnv1a(&contents).to_le_bytes());
+        archive.extend_from_slice(&compressed_len.to_le_bytes());
+        archive.extend_from_slice(&compressed);
+    }
+
+    std::fs::write(out, archive)
+}
+
+fn too_large(path: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("{path} is too large for an asset archive"),
+    )
+}
+
+/// The paths of the files under `directory`, relative to `root` and with `/` separators
+fn collect_files(root: &Path, directory: &Path, files: &mut Vec<String>) -> io::Result<()> {
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if file_type.is_symlink() && path.is_dir() {
+            // A linked directory can link to one of its parents, and following it would never end
+            continue;
+        } else {
+            let relative = path
+                .strip_prefix(root)
+                .expect("the file is under the root")
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push(relative);
+        }
+    }
+    Ok(())
+}
+
+/// A stable hash, so a file gets the same `ETag` in every build
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// A file in an [`AssetArchive`]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ArchiveEntry {
+    pub(crate) len: u64,
+    pub(crate) hash: u64,
+    pub(crate) compressed: &'static [u8],
+}
+
+impl ArchiveEntry {
+    pub(crate) fn decompress(&self) -> io::Result<Vec<u8>> {
+        let mut contents = Vec::with_capacity(self.len as usize);
+        flate2::read::GzDecoder::new(self.compressed).read_to_end(&mut contents)?;
+        Ok(contents)
+    }
+}
+
+/// The index of an archive made by [`pack_assets`]
+#[derive(Debug)]
+pub(crate) struct AssetArchive {
+    entries: HashMap<&'static str, ArchiveEntry>,
+}
+
+impl AssetArchive {
+    /// Read the index of an archive. The files are only decompressed when they are requested.
+    pub(crate) fn new(bytes: &'static [u8]) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid asset archive");
+        let mut reader = Reader { bytes };
+
+        if reader.take(MAGIC.len()).ok_or_else(invalid)? != MAGIC {
+            return Err(invalid());
+        }
+        if reader.take(1).ok_or_else(invalid)? != [VERSION] {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the asset archive was packed by another version of dioxus-desktop",
+            ));
+        }
+
+        let count = reader.u32().ok_or_else(invalid)?;
+        let mut entries = HashMap::with_capacity(count as usize);
+        for _ in 0..count {
+            let path_len = reader.u16().ok_or_else(invalid)?;
+            let path = std::str::from_utf8(reader.take(path_len as usize).ok_or_else(invalid)?)
+                .map_err(|_| invalid())?;
+            let len = reader.u64().ok_or_else(invalid)?;
+            let hash = reader.u64().ok_or_else(invalid)?;
+            let compressed_len = reader.u32().ok_or_else(invalid)?;
+            let compressed = reader.take(compressed_len as usize).ok_or_else(invalid)?;
+            entries.insert(
+                path,
+                ArchiveEntry {
+                    len,
+                    hash,
+                    compressed,
+                },
+            );
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// The file at `path`, with `/` separators
+    pub(crate) fn get(&self, path: &str) -> Option<&ArchiveEntry> {
+        self.entries.get(path)
+    }
+}
+
+/// Reads the numbers and byte strings of an archive
+struct Reader {
+    bytes: &'static [u8],
+}
+
+impl Reader {
+    fn take(&mut self, len: usize) -> Option<&'static [u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.take(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+}
+
+/// Embed an asset archive that a build script packed into `OUT_DIR` with [`pack_assets`](crate::pack_assets), and
+/// serve it under a URL prefix.
+///
+/// ```rust, ignore
+/// Config::new().with_asset_mount(include_assets!("assets", "assets.dxar"))
+/// // <img src="/assets/logo.png"> is now logo.png in the archive
+/// ```
+#[macro_export]
+macro_rules! include_assets {
+    ($prefix:expr, $archive:literal) => {
+        $crate::AssetMount::archive(
+            $prefix,
+            include_bytes!(concat!(env!("OUT_DIR"), "/", $archive)),
+        )
+        .expect("the asset archive was packed by pack_assets")
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(directory: &Path) -> &'static [u8] {
+        let out = tempfile::NamedTempFile::new().unwrap();
+        pack_assets(directory, out.path()).unwrap();
+        Box::leak(std::fs::read(out.path()).unwrap().into_boxed_slice())
+    }
+
+    #[test]
+    fn packed_files_round_trip() {
+        let assets = tempfile::tempdir().unwrap();
+        std::fs::create_dir(assets.path().join("img")).unwrap();
+        std::fs::write(assets.path().join("index.css"), "body {}").unwrap();
+        std::fs::write(assets.path().join("img").join("logo.svg"), "<svg/>").unwrap();
+        std::fs::write(assets.path().join("empty"), "").unwrap();
+
+        let archive = AssetArchive::new(pack(assets.path())).unwrap();
+        assert_eq!(archive.entries.len(), 3);
+        for (path, contents) in [
+            ("index.css", "body {}"),
+            ("img/logo.svg", "<svg/>"),
+            ("empty", ""),
+        ] {
+            let entry = archive.get(path).unwrap();
+            assert_eq!(entry.len, contents.len() as u64);
+            assert_eq!(entry.hash, fnv1a(contents.as_bytes()));
+            assert_eq!(entry.decompress().unwrap(), contents.as_bytes());
+        }
+        assert!(archive.get("img").is_none());
+    }
+
+    #[test]
+    fn truncated_archives_are_rejected() {
+        let assets = tempfile::tempdir().unwrap();
+        std::fs::write(assets.path().join("index.html"), "<html></html>").unwrap();
+        let bytes = pack(assets.path());
+
+        for len in 0..bytes.len() {
+            let error = AssetArchive::new(&bytes[..len]).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+        assert!(AssetArchive::new(bytes).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn linked_directories_are_skipped() {
+        let assets = tempfile::tempdir().unwrap();
+        std::fs::write(assets.path().join("index.html"), "<html></html>").unwrap();
+        std::os::unix::fs::symlink(assets.path(), assets.path().join("loop")).unwrap();
+
+        let archive = AssetArchive::new(pack(assets.path())).unwrap();
+        assert_eq!(archive.entries.len(), 1);
+        assert!(archive.get("index.html").is_some());
+    }
+}
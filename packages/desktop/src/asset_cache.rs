@@ -0,0 +1,348 @@
+//! A disk cache for remote assets, so pages keep their images, fonts and stylesheets when the app is offline.
+//!
+//! Remote assets are loaded through the asset protocol of the app with [`cached_asset_url`]:
+//!
+//! ```rust, ignore
+//! render! { img { src: "{cached_asset_url(\"https://example.com/cat.png\")}" } }
+//! ```
+//!
+//! The first request downloads the asset and stores it with its `ETag` and `Last-Modified` headers. Later requests
+//! revalidate the stored copy with a conditional request, and serve it without downloading it again if the server
+//! answers `304 Not Modified`. If the server can't be reached, the stored copy is served as is. Responses with
+//! `Cache-Control: no-store` are never stored.
+//!
+//! Only assets from the origins added with [`AssetCache::with_origin`] are loaded. Everything else is refused, so the
+//! page can't use the cache to reach other servers, like services on `localhost` or the local network.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use url::Url;
+use wry::http::{status::StatusCode, Response};
+
+use crate::protocol::AssetResponse;
+
+/// The path prefix of remote assets in the asset protocol
+pub(crate) const REMOTE_PREFIX: &str = "/remote/";
+
+/// Get the url that loads `url` through the asset cache of the window
+pub fn cached_asset_url(url: &str) -> String {
+    format!("{}{}", REMOTE_PREFIX, urlencoding::encode(url))
+}
+
+/// Caches remote assets on disk. Set it with [`Config::with_asset_cache`](crate::Config::with_asset_cache).
+///
+/// ```rust, ignore
+/// let cache = AssetCache::new().with_origin("https://images.example.com");
+/// dioxus_desktop::launch_cfg(app, Config::new().with_asset_cache(cache));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AssetCache {
+    directory: Option<PathBuf>,
+    origins: Vec<String>,
+    client: reqwest::Client,
+}
+
+/// What is stored next to the body of a cached asset
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    content_type: Option<String>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl AssetCache {
+    /// Create a cache that stores assets in the `asset-cache` folder of the data directory. It doesn't load anything until
+    /// an origin is allowed with [`AssetCache::with_origin`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store the cached assets in `directory` instead of the data directory
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Load assets from `origin`, like `https://images.example.com`. The scheme, host and port of an asset url must
+    /// match the origin exactly. This can be called multiple times to allow multiple origins.
+    pub fn with_origin(mut self, origin: impl Into<String>) -> Self {
+        self.origins.push(origin.into());
+        self
+    }
+
+    /// Use the data directory of the window, or the cache directory of the user, if no directory was set.
+    ///
+    /// The directory is never shared between users, because anyone who can write to it can change what the app loads.
+    pub(crate) fn resolve_directory(&mut self, data_dir: Option<&Path>) {
+        if self.directory.is_none() {
+            let root = data_dir
+                .map(Path::to_path_buf)
+                .or_else(user_cache_directory);
+            if root.is_none() {
+                tracing::warn!(
+                    "No cache directory found for the user. Remote assets are not cached."
+                );
+            }
+            self.directory = root.map(|root| root.join("asset-cache"));
+        }
+    }
+
+    fn allows(&self, url: &str) -> bool {
+        let url = match Url::parse(url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+            _ => return false,
+        };
+        self.origins.iter().any(|origin| match Url::parse(origin) {
+            Ok(origin) => {
+                origin.scheme() == url.scheme()
+                    && origin.host() == url.host()
+                    && origin.port_or_known_default() == url.port_or_known_default()
+            }
+            Err(_) => false,
+        })
+    }
+
+    /// Get the files the body and the entry of `url` are stored in, or `None` if there is no cache directory
+    fn paths(&self, url: &str) -> Option<(PathBuf, PathBuf)> {
+        let directory = self.directory.as_ref()?;
+        let key = cache_key(url);
+        Some((
+            directory.join(format!("{}.body", key)),
+            directory.join(format!("{}.json", key)),
+        ))
+    }
+
+    /// Serve the remote asset at the path of a request, from the cache if it is still fresh
+    pub(crate) async fn fetch(&self, path: &str) -> AssetResponse {
+        let url = match urlencoding::decode(&path[REMOTE_PREFIX.len()..]) {
+            Ok(url) => url.into_owned(),
+            Err(_) => return status_response(StatusCode::BAD_REQUEST),
+        };
+        if !self.allows(&url) {
+            return status_response(StatusCode::FORBIDDEN);
+        }
+
+        let paths = self.paths(&url);
+        let cached = match &paths {
+            Some((body_path, entry_path)) => read_entry(entry_path)
+                .await
+                .filter(|entry| entry.url == url && body_path.exists())
+                .map(|entry| (entry, body_path.clone())),
+            None => None,
+        };
+
+        let mut request = self.client.get(&url);
+        if let Some((entry, _)) = &cached {
+            if let Some(etag) = &entry.etag {
+                request = request.header("If-None-Match", etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header("If-Modified-Since", last_modified);
+            }
+        }
+
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(err) => {
+                tracing::debug!("Failed to fetch {}, serving the cached copy: {}", url, err);
+                return match cached {
+                    Some((entry, body_path)) => serve_cached(&entry, &body_path).await,
+                    None => status_response(StatusCode::BAD_GATEWAY),
+                };
+            }
+        };
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            if let Some((entry, body_path)) = cached {
+                return serve_cached(&entry, &body_path).await;
+            }
+        }
+
+        let status = response.status();
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let entry = CacheEntry {
+            url: url.clone(),
+            content_type: header("Content-Type"),
+            etag: header("ETag"),
+            last_modified: header("Last-Modified"),
+        };
+        let no_store = is_no_store(header("Cache-Control").as_deref());
+        let body = match response.bytes().await {
+            Ok(body) => body.to_vec(),
+            Err(err) => {
+                tracing::error!("Failed to download {}: {}", url, err);
+                return status_response(StatusCode::BAD_GATEWAY);
+            }
+        };
+
+        if let Some((body_path, entry_path)) = &paths {
+            if no_store {
+                // A copy stored before the server started sending no-store must not be served offline either
+                _ = tokio::fs::remove_file(entry_path).await;
+                _ = tokio::fs::remove_file(body_path).await;
+            } else if status.is_success() {
+                if let Err(err) = write_entry(&entry, &body, body_path, entry_path).await {
+                    tracing::error!("Failed to cache {}: {}", url, err);
+                }
+            }
+        }
+
+        build_response(status.as_u16(), entry.content_type.as_deref(), body)
+    }
+}
+
+async fn read_entry(path: &Path) -> Option<CacheEntry> {
+    let entry = tokio::fs::read(path).await.ok()?;
+    serde_json::from_slice(&entry).ok()
+}
+
+async fn write_entry(
+    entry: &CacheEntry,
+    body: &[u8],
+    body_path: &Path,
+    entry_path: &Path,
+) -> std::io::Result<()> {
+    if let Some(directory) = body_path.parent() {
+        tokio::fs::create_dir_all(directory).await?;
+    }
+    tokio::fs::write(body_path, body).await?;
+    // The entry is written last, so a body without an entry is never served
+    tokio::fs::write(entry_path, serde_json::to_vec(entry)?).await
+}
+
+async fn serve_cached(entry: &CacheEntry, body_path: &Path) -> AssetResponse {
+    match tokio::fs::read(body_path).await {
+        Ok(body) => build_response(200, entry.content_type.as_deref(), body),
+        Err(err) => {
+            tracing::error!("Failed to read the cached copy of {}: {}", entry.url, err);
+            status_response(StatusCode::BAD_GATEWAY)
+        }
+    }
+}
+
+fn build_response(status: u16, content_type: Option<&str>, body: Vec<u8>) -> AssetResponse {
+    let mut response = Response::builder().status(status);
+    if let Some(content_type) = content_type {
+        response = response.header("Content-Type", content_type);
+    }
+    response
+        .body(Cow::from(body))
+        .unwrap_or_else(|_| status_response(StatusCode::INTERNAL_SERVER_ERROR))
+}
+
+fn status_response(status: StatusCode) -> AssetResponse {
+    let mut response = Response::new(Cow::from(Vec::new()));
+    *response.status_mut() = status;
+    response
+}
+
+/// The name of the files `url` is cached in. The hash stays the same across builds, so cached files are found again
+/// after the app is updated.
+fn cache_key(url: &str) -> String {
+    Sha256::digest(url.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Check if a `Cache-Control` header forbids storing the response
+fn is_no_store(cache_control: Option<&str>) -> bool {
+    cache_control.map_or(false, |value| {
+        value
+            .split(',')
+            .any(|directive| directive.trim().eq_ignore_ascii_case("no-store"))
+    })
+}
+
+/// The cache directory of the current user for this app, like `~/.cache/<app>` on Linux
+fn user_cache_directory() -> Option<PathBuf> {
+    let app = std::env::current_exe()
+        .ok()?
+        .file_stem()?
+        .to_string_lossy()
+        .into_owned();
+    Some(dirs::cache_dir()?.join(app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_configured_origins_are_allowed() {
+        let cache = AssetCache::new()
+            .with_origin("https://images.example.com")
+            .with_origin("http://localhost:8080");
+
+        assert!(cache.allows("https://images.example.com/cat.png"));
+        assert!(cache.allows("https://images.example.com:443/cat.png"));
+        assert!(cache.allows("http://localhost:8080/logo.svg"));
+
+        assert!(!cache.allows("https://images.example.com.evil.com/cat.png"));
+        assert!(!cache.allows("https://images.example.community/cat.png"));
+        assert!(!cache.allows("https://images.example.com@evil.com/cat.png"));
+        assert!(!cache.allows("http://images.example.com/cat.png"));
+        assert!(!cache.allows("https://images.example.com:8443/cat.png"));
+        assert!(!cache.allows("http://localhost/logo.svg"));
+        assert!(!cache.allows("file:///etc/passwd"));
+        assert!(!cache.allows("not a url"));
+    }
+
+    #[test]
+    fn nothing_is_allowed_by_default() {
+        let cache = AssetCache::new();
+        assert!(!cache.allows("https://images.example.com/cat.png"));
+        assert!(!cache.allows("http://localhost:8080/"));
+        assert!(!cache.allows("http://192.168.1.1/"));
+    }
+
+    #[test]
+    fn cache_keys() {
+        let cache = AssetCache::new().with_directory("/cache");
+        let (body, entry) = cache.paths("https://example.com/a.png").unwrap();
+        assert_eq!(body.parent(), Some(Path::new("/cache")));
+        assert_eq!(body.with_extension("json"), entry);
+        // The same url is always stored in the same files
+        assert_eq!(
+            cache.paths("https://example.com/a.png"),
+            Some((body.clone(), entry))
+        );
+        assert_ne!(cache.paths("https://example.com/b.png").unwrap().0, body);
+
+        assert_eq!(cache_key("https://example.com/a.png").len(), 64);
+        assert!(AssetCache::new()
+            .paths("https://example.com/a.png")
+            .is_none());
+    }
+
+    #[test]
+    fn the_user_directory_is_used_without_a_data_directory() {
+        let mut cache = AssetCache::new();
+        cache.resolve_directory(None);
+        if let Some(directory) = &cache.directory {
+            assert!(!directory.starts_with(std::env::temp_dir()));
+        }
+
+        let mut cache = AssetCache::new();
+        cache.resolve_directory(Some(Path::new("/data")));
+        assert_eq!(cache.directory, Some(PathBuf::from("/data/asset-cache")));
+    }
+
+    #[test]
+    fn no_store_is_honored() {
+        assert!(is_no_store(Some("no-store")));
+        assert!(is_no_store(Some("private, No-Store, max-age=0")));
+        assert!(!is_no_store(Some("no-cache, max-age=60")));
+        assert!(!is_no_store(None));
+    }
+}
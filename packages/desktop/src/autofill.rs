@@ -0,0 +1,188 @@
+//! Form autofill and password saving.
+//!
+//! WebView2 offers to fill forms and to save passwords like Edge does, and has settings for both. WebKitGTK and
+//! WKWebView have no such settings, but WKWebView still fills passwords from the keychain, so on those webviews the
+//! page asks not to be filled: inputs are given `autocomplete="off"`, and password inputs
+//! `autocomplete="new-password"`. The original `autocomplete` of an input is restored when autofill is turned back on.
+
+use thiserror::Error;
+use wry::webview::WebView;
+
+/// Which kinds of autofill a window offers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct AutofillSettings {
+    pub(crate) autofill: bool,
+    pub(crate) password_saving: bool,
+}
+
+impl Default for AutofillSettings {
+    fn default() -> Self {
+        Self {
+            autofill: true,
+            password_saving: true,
+        }
+    }
+}
+
+/// An error that occurred while clearing saved form data
+#[derive(Error, Debug)]
+pub enum AutofillError {
+    /// The webview failed to clear the data
+    #[error("Failed to clear the saved form data: {0}")]
+    Clear(String),
+}
+
+#[cfg(not(target_os = "windows"))]
+const AUTOFILL_SCRIPT: &str = r#"(function () {
+    const ORIGINAL = "data-dioxus-autocomplete";
+    const state = { autofill: true, passwords: true };
+    const isPassword = (element) => element instanceof HTMLInputElement && element.type === "password";
+
+    const apply = (element) => {
+        if (!(element instanceof HTMLInputElement || element instanceof HTMLTextAreaElement
+            || element instanceof HTMLSelectElement || element instanceof HTMLFormElement)) {
+            return;
+        }
+        const blocked = isPassword(element) ? !state.passwords : !state.autofill;
+        if (blocked) {
+            if (!element.hasAttribute(ORIGINAL)) {
+                element.setAttribute(ORIGINAL, element.getAttribute("autocomplete") || "");
+            }
+            element.setAttribute("autocomplete", isPassword(element) ? "new-password" : "off");
+        } else if (element.hasAttribute(ORIGINAL)) {
+            const original = element.getAttribute(ORIGINAL);
+            element.removeAttribute(ORIGINAL);
+            original ? element.setAttribute("autocomplete", original) : element.removeAttribute("autocomplete");
+        }
+    };
+    const applyAll = (root) => {
+        apply(root);
+        if (root.querySelectorAll) {
+            root.querySelectorAll("input, textarea, select, form").forEach(apply);
+        }
+    };
+
+    new MutationObserver((mutations) => {
+        if (state.autofill && state.passwords) {
+            return;
+        }
+        for (const mutation of mutations) {
+            if (mutation.type === "attributes") {
+                apply(mutation.target);
+            } else {
+                mutation.addedNodes.forEach(applyAll);
+            }
+        }
+    }).observe(document, { childList: true, subtree: true, attributes: true, attributeFilter: ["type"] });
+
+    window.__dioxusAutofill = {
+        set(autofill, passwords) {
+            state.autofill = autofill;
+            state.passwords = passwords;
+            applyAll(document);
+        },
+    };
+})();"#;
+
+/// The script that blocks autofill in the page, starting with `settings`. WebView2 has its own settings instead.
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn script(settings: AutofillSettings) -> String {
+    format!(
+        "{}\nwindow.__dioxusAutofill.set({}, {});",
+        AUTOFILL_SCRIPT, settings.autofill, settings.password_saving
+    )
+}
+
+/// Apply `settings` to the webview of a window that is already open
+pub(crate) fn apply(webview: &WebView, settings: AutofillSettings) {
+    #[cfg(target_os = "windows")]
+    if let Err(err) = webview2::apply(webview, settings) {
+        tracing::warn!("Failed to change the autofill settings: {}", err.message());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    if let Err(err) = webview.evaluate_script(&format!(
+        "window.__dioxusAutofill && window.__dioxusAutofill.set({}, {});",
+        settings.autofill, settings.password_saving
+    )) {
+        tracing::warn!("Failed to change the autofill settings: {err}");
+    }
+}
+
+/// Forget the form entries and passwords the webview saved
+pub(crate) async fn clear(webview: &WebView) -> Result<(), AutofillError> {
+    #[cfg(target_os = "windows")]
+    {
+        webview2::clear(webview)?
+            .await
+            .unwrap_or_else(|_| Err(AutofillError::Clear("the webview was closed".to_string())))
+    }
+
+    // The other webviews don't save form data themselves
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = webview;
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod webview2 {
+    use super::*;
+    use webview2_com::ClearBrowsingDataCompletedHandler;
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        ICoreWebView2Profile2, ICoreWebView2Settings4, ICoreWebView2_13,
+        COREWEBVIEW2_BROWSING_DATA_KINDS_GENERAL_AUTOFILL,
+        COREWEBVIEW2_BROWSING_DATA_KINDS_PASSWORD_AUTOSAVE,
+    };
+    use windows::core::ComInterface;
+    use windows::Win32::Foundation::BOOL;
+    use wry::webview::WebviewExtWindows;
+
+    /// Turn the autofill settings of WebView2 on or off
+    pub(super) fn apply(
+        webview: &WebView,
+        settings: AutofillSettings,
+    ) -> windows::core::Result<()> {
+        unsafe {
+            let webview_settings = webview
+                .controller()
+                .CoreWebView2()?
+                .Settings()?
+                .cast::<ICoreWebView2Settings4>()?;
+            webview_settings.SetIsGeneralAutofillEnabled(BOOL::from(settings.autofill))?;
+            webview_settings.SetIsPasswordAutosaveEnabled(BOOL::from(settings.password_saving))?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn clear(
+        webview: &WebView,
+    ) -> Result<tokio::sync::oneshot::Receiver<Result<(), AutofillError>>, AutofillError> {
+        let error = |err: windows::core::Error| AutofillError::Clear(err.message().to_string());
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        unsafe {
+            let profile = webview
+                .controller()
+                .CoreWebView2()
+                .and_then(|core| core.cast::<ICoreWebView2_13>())
+                .and_then(|core| core.Profile())
+                .and_then(|profile| profile.cast::<ICoreWebView2Profile2>())
+                .map_err(error)?;
+            let handler = ClearBrowsingDataCompletedHandler::create(Box::new(move |result| {
+                _ = tx.send(result.map_err(error));
+                Ok(())
+            }));
+            profile
+                .ClearBrowsingData(
+                    COREWEBVIEW2_BROWSING_DATA_KINDS_GENERAL_AUTOFILL
+                        | COREWEBVIEW2_BROWSING_DATA_KINDS_PASSWORD_AUTOSAVE,
+                    &handler,
+                )
+                .map_err(error)?;
+        }
+
+        Ok(rx)
+    }
+}
@@ -0,0 +1,65 @@
+//! Battery status read from the operating system (sysfs on Linux, IOKit on macOS, and the power APIs on Windows).
+//!
+//! The operating system doesn't notify us when the battery changes, so [`use_battery`] polls the status. Without the
+//! `tokio_runtime` feature the status is only read when the component is first rendered.
+//!
+//! This needs the `battery` feature.
+
+use std::time::Duration;
+
+use dioxus_core::ScopeState;
+
+use crate::poll::use_polled;
+
+/// How often [`use_battery`] reads the battery status
+const BATTERY_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The status of the device's battery
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatteryStatus {
+    /// The charge level, from `0.0` (empty) to `1.0` (full)
+    pub level: f64,
+    /// If the device is plugged in. This is `true` when the battery is full and the device is still plugged in.
+    pub charging: bool,
+    /// How long until the battery is full, if it is charging and the operating system can estimate it
+    pub time_to_full: Option<Duration>,
+    /// How long until the battery is empty, if it is discharging and the operating system can estimate it
+    pub time_to_empty: Option<Duration>,
+}
+
+/// Read the status of the device's battery.
+///
+/// Returns `None` if the device has no battery or the status can't be read. If the device has more than one
+/// battery, this is the status of the first one.
+pub fn battery_status() -> Option<BatteryStatus> {
+    use starship_battery::State;
+
+    let manager = starship_battery::Manager::new().ok()?;
+    let battery = manager.batteries().ok()?.flatten().next()?;
+    let seconds = |time: starship_battery::units::Time| Duration::from_secs_f32(time.value);
+
+    Some(BatteryStatus {
+        level: battery.state_of_charge().value as f64,
+        charging: matches!(battery.state(), State::Charging | State::Full),
+        time_to_full: battery.time_to_full().map(seconds),
+        time_to_empty: battery.time_to_empty().map(seconds),
+    })
+}
+
+/// Get the status of the device's battery. The component rerenders when the status changes.
+///
+/// This is `None` on devices without a battery.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let battery = use_battery(cx);
+///     let power_saving = matches!(battery, Some(battery) if !battery.charging && battery.level < 0.2);
+///
+///     render! {
+///         Animations { enabled: !power_saving }
+///     }
+/// }
+/// ```
+pub fn use_battery(cx: &ScopeState) -> Option<BatteryStatus> {
+    use_polled(cx, BATTERY_POLL_INTERVAL, battery_status, battery_status)
+}
@@ -0,0 +1,122 @@
+//! The `Cache-Control` headers of the `dioxus://` protocol.
+//!
+//! In debug builds nothing is stored, so an asset edited during development shows up on the next reload. In release
+//! builds, urls from [`hashed_asset_url`] carry a hash of the file they point to. The url changes when the file does,
+//! so the webview can keep the file for as long as it likes without checking it again.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use wry::http::{header, HeaderMap, HeaderValue};
+
+/// The query parameter [`hashed_asset_url`] puts the hash of a file in
+const HASH_PARAM: &str = "v";
+
+/// How the webview caches the responses of the `dioxus://` protocol. Set it with
+/// [`Config::with_cache_policy`](crate::Config::with_cache_policy).
+///
+/// The policy only sets `Cache-Control` on responses that don't have one, so a header set by an asset handler, an
+/// [`AssetMount`](crate::AssetMount) or [`Compression`](crate::Compression) wins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Never store a response (`no-store`). The default in debug builds.
+    NoStore,
+    /// Store responses, but check they haven't changed with their `ETag` before using them (`no-cache`)
+    Revalidate,
+    /// Store the responses for urls from [`hashed_asset_url`] for a year without checking them again, and revalidate
+    /// everything else. The default in release builds.
+    ContentHash,
+    /// Send this `Cache-Control` header with every response
+    Custom(String),
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            Self::NoStore
+        } else {
+            Self::ContentHash
+        }
+    }
+}
+
+impl CachePolicy {
+    /// Set the `Cache-Control` header of a response to a request with `query`, unless it already has one
+    pub(crate) fn apply(&self, query: Option<&str>, headers: &mut HeaderMap) {
+        if headers.contains_key(header::CACHE_CONTROL) {
+            return;
+        }
+        let cache_control = match self {
+            CachePolicy::NoStore => HeaderValue::from_static("no-store"),
+            CachePolicy::Revalidate => HeaderValue::from_static("no-cache"),
+            CachePolicy::ContentHash if has_hash(query) => {
+                HeaderValue::from_static("public, max-age=31536000, immutable")
+            }
+            CachePolicy::ContentHash => HeaderValue::from_static("no-cache"),
+            CachePolicy::Custom(cache_control) => match HeaderValue::from_str(cache_control) {
+                Ok(cache_control) => cache_control,
+                Err(err) => {
+                    tracing::error!("invalid Cache-Control {:?}: {}", cache_control, err);
+                    return;
+                }
+            },
+        };
+        headers.insert(header::CACHE_CONTROL, cache_control);
+    }
+}
+
+fn has_hash(query: Option<&str>) -> bool {
+    query.map_or(false, |query| {
+        query
+            .split('&')
+            .any(|pair| pair.split('=').next() == Some(HASH_PARAM))
+    })
+}
+
+/// The hashes of the files [`hashed_asset_url`] has read, with the length and modification time they were read at
+static HASHES: Mutex<Option<HashMap<PathBuf, (u64, Option<SystemTime>, u64)>>> = Mutex::new(None);
+
+/// Get the url of the asset at `path` with a hash of its contents, like `/assets/main.css?v=3f2a9c…`.
+///
+/// With the [`CachePolicy::ContentHash`] policy, the webview keeps hashed urls without checking them again, and a
+/// changed file gets a new url. If the file can't be read, `path` is returned as is. Files are only read again when
+/// their length or modification time changes.
+///
+/// ```rust, ignore
+/// render! { link { rel: "stylesheet", href: "{hashed_asset_url(\"assets/main.css\")}" } }
+/// ```
+pub fn hashed_asset_url(path: &str) -> String {
+    let relative = path.trim_start_matches('/');
+    match content_hash(&crate::protocol::resolve_asset_path(Path::new(relative))) {
+        Some(hash) => {
+            let separator = if path.contains('?') { '&' } else { '?' };
+            format!("/{relative}{separator}{HASH_PARAM}={hash:016x}")
+        }
+        None => path.to_string(),
+    }
+}
+
+fn content_hash(path: &Path) -> Option<u64> {
+    let metadata = path.metadata().ok()?;
+    let (len, modified) = (metadata.len(), metadata.modified().ok());
+
+    let mut hashes = HASHES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let hashes = hashes.get_or_insert_with(HashMap::new);
+    if let Some((cached_len, cached_modified, hash)) = hashes.get(path) {
+        if *cached_len == len && *cached_modified == modified {
+            return Some(*hash);
+        }
+    }
+
+    let mut hasher = DefaultHasher::new();
+    std::fs::read(path).ok()?.hash(&mut hasher);
+    let hash = hasher.finish();
+    hashes.insert(path.to_path_buf(), (len, modified, hash));
+    Some(hash)
+}
@@ -0,0 +1,161 @@
+//! Screenshots of the page.
+//!
+//! None of the webviews wry wraps expose their snapshot API through it, so the page captures itself: the DOM is cloned
+//! with the state that isn't in the markup (input values, canvases and loaded images), drawn through an SVG
+//! `foreignObject` onto a canvas, and encoded as a PNG. This works the same in a hidden window. Content the page can't
+//! read back, like cross origin images and iframes, is left blank.
+
+use std::rc::Rc;
+
+use base64::Engine;
+use serde::Deserialize;
+use thiserror::Error;
+use wry::webview::WebView;
+
+use crate::query::{Query, QueryEngine};
+use crate::QueryError;
+
+/// A screenshot of the page, from [`DesktopService::capture_page`](crate::DesktopService::capture_page)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageCapture {
+    /// The width of the image in physical pixels
+    pub width: u32,
+    /// The height of the image in physical pixels
+    pub height: u32,
+    /// The image, encoded as a PNG
+    pub png: Vec<u8>,
+}
+
+/// An error that occurred while capturing the page
+#[derive(Error, Debug)]
+pub enum CaptureError {
+    /// The page could not be asked for the capture
+    #[error(transparent)]
+    Query(#[from] QueryError),
+    /// The page failed to draw itself
+    #[error("Failed to capture the page: {0}")]
+    Render(String),
+    /// The page returned an image that isn't valid base64
+    #[error("Failed to decode the captured image: {0}")]
+    Decode(#[from] base64::DecodeError),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CaptureResult {
+    Captured {
+        width: u32,
+        height: u32,
+        png: String,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+const CAPTURE_SCRIPT: &str = r#"
+try {
+    const root = document.documentElement;
+    const width = root.clientWidth;
+    const height = root.clientHeight;
+    const ratio = window.devicePixelRatio || 1;
+
+    const toDataUrl = (source, sourceWidth, sourceHeight) => {
+        const canvas = document.createElement("canvas");
+        canvas.width = sourceWidth;
+        canvas.height = sourceHeight;
+        canvas.getContext("2d").drawImage(source, 0, 0);
+        return canvas.toDataURL("image/png");
+    };
+
+    // The image of the SVG can't load anything, so everything it shows is inlined into the clone
+    const clone = root.cloneNode(true);
+    const originals = root.querySelectorAll("*");
+    const copies = clone.querySelectorAll("*");
+    for (let i = 0; i < originals.length; i++) {
+        const original = originals[i];
+        const copy = copies[i];
+        try {
+            if (original instanceof HTMLInputElement) {
+                if (original.type === "checkbox" || original.type === "radio") {
+                    original.checked ? copy.setAttribute("checked", "") : copy.removeAttribute("checked");
+                } else {
+                    copy.setAttribute("value", original.value);
+                }
+            } else if (original instanceof HTMLTextAreaElement) {
+                copy.textContent = original.value;
+            } else if (original instanceof HTMLImageElement && original.complete && original.naturalWidth) {
+                copy.setAttribute("src", toDataUrl(original, original.naturalWidth, original.naturalHeight));
+                copy.removeAttribute("srcset");
+            } else if (original instanceof HTMLCanvasElement) {
+                const image = document.createElement("img");
+                for (const attribute of copy.attributes) {
+                    image.setAttribute(attribute.name, attribute.value);
+                }
+                image.setAttribute("src", original.toDataURL("image/png"));
+                copy.replaceWith(image);
+            }
+        } catch (e) {
+            // Tainted canvases and cross origin images stay blank
+        }
+    }
+
+    let css = "";
+    for (const sheet of document.styleSheets) {
+        try {
+            for (const rule of sheet.cssRules) {
+                css += rule.cssText + "\n";
+            }
+        } catch (e) {
+            // Cross origin style sheets can't be read
+        }
+    }
+    clone.querySelectorAll("script, style, link[rel=stylesheet]").forEach((element) => element.remove());
+    const style = document.createElement("style");
+    style.textContent = css;
+    (clone.querySelector("head") || clone).appendChild(style);
+
+    const markup = new XMLSerializer().serializeToString(clone);
+    const svg = `<svg xmlns="http://www.w3.org/2000/svg" width="${width}" height="${height}">`
+        + `<foreignObject x="${-window.scrollX}" y="${-window.scrollY}" width="${root.scrollWidth}" height="${root.scrollHeight}">`
+        + markup
+        + `</foreignObject></svg>`;
+    const image = new Image();
+    await new Promise((resolve, reject) => {
+        image.onload = resolve;
+        image.onerror = () => reject(new Error("the page could not be drawn"));
+        image.src = "data:image/svg+xml;charset=utf-8," + encodeURIComponent(svg);
+    });
+
+    const canvas = document.createElement("canvas");
+    canvas.width = Math.round(width * ratio);
+    canvas.height = Math.round(height * ratio);
+    const context = canvas.getContext("2d");
+    context.scale(ratio, ratio);
+    const background = getComputedStyle(document.body).backgroundColor;
+    context.fillStyle = background === "rgba(0, 0, 0, 0)" ? getComputedStyle(root).backgroundColor : background;
+    context.fillRect(0, 0, width, height);
+    context.drawImage(image, 0, 0);
+
+    const png = canvas.toDataURL("image/png");
+    return { width: canvas.width, height: canvas.height, png: png.slice(png.indexOf(",") + 1) };
+} catch (e) {
+    return { error: String(e) };
+}
+"#;
+
+/// Ask the page to capture itself
+pub(crate) async fn capture_page(
+    engine: &QueryEngine,
+    webview: Rc<WebView>,
+) -> Result<PageCapture, CaptureError> {
+    let query: Query<CaptureResult> = engine.new_query(CAPTURE_SCRIPT, webview);
+    match query.resolve().await? {
+        CaptureResult::Captured { width, height, png } => Ok(PageCapture {
+            width,
+            height,
+            png: base64::engine::general_purpose::STANDARD.decode(png)?,
+        }),
+        CaptureResult::Failed { error } => Err(CaptureError::Render(error)),
+    }
+}
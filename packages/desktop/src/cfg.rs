@@ -1,10 +1,25 @@
 use std::borrow::Cow;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::cache_policy::CachePolicy;
+use crate::compression::Compression;
+use crate::console::ConsoleBridge;
+use crate::crash::CrashRecovery;
+use crate::download::{DownloadRequest, DownloadStartedHandler};
+use crate::edit_transport::EditTransport;
+use crate::engine::EngineSettings;
+use crate::event_loop_mode::EventLoopMode;
+use crate::menu::AppMenu;
+use crate::mount::AssetMount;
+use crate::navigation::{NavigationAction, NavigationHandler};
+use crate::proxy::ProxyConfig;
+use crate::request_filter::RequestFilter;
+use crate::response_headers::ResponseHeaders;
+use crate::secure_input::SecureInput;
 use wry::application::window::Icon;
 use wry::{
-    application::window::{Window, WindowBuilder},
-    http::{Request as HttpRequest, Response as HttpResponse},
+    application::window::{Theme, Window, WindowBuilder},
+    http::{HeaderMap, Request as HttpRequest, Response as HttpResponse},
     webview::FileDropEvent,
 };
 
@@ -25,21 +40,72 @@ pub enum WindowCloseBehaviour {
 pub struct Config {
     pub(crate) window: WindowBuilder,
     pub(crate) file_drop_handler: Option<DropHandler>,
+    pub(crate) download_started_handler: Option<DownloadStartedHandler>,
+    pub(crate) download_completed_handler: Option<DownloadCompletedHandler>,
+    pub(crate) download_directory: Option<PathBuf>,
     pub(crate) protocols: Vec<WryProtocol>,
+    pub(crate) asset_mounts: Vec<AssetMount>,
+    pub(crate) compression: Compression,
+    pub(crate) cache_policy: CachePolicy,
+    pub(crate) response_headers: ResponseHeaders,
     pub(crate) pre_rendered: Option<String>,
     pub(crate) disable_context_menu: bool,
     pub(crate) resource_dir: Option<PathBuf>,
     pub(crate) data_dir: Option<PathBuf>,
+    pub(crate) ephemeral: bool,
+    #[cfg(feature = "asset-cache")]
+    pub(crate) asset_cache: Option<crate::asset_cache::AssetCache>,
     pub(crate) custom_head: Option<String>,
     pub(crate) custom_index: Option<String>,
     pub(crate) root_name: String,
     pub(crate) background_color: Option<(u8, u8, u8, u8)>,
     pub(crate) last_window_close_behaviour: WindowCloseBehaviour,
     pub(crate) enable_default_menu_bar: bool,
+    pub(crate) menu: Option<AppMenu>,
+    pub(crate) request_filter: RequestFilter,
+    pub(crate) navigation_handler: Option<NavigationHandler>,
+    pub(crate) new_window_handler: Option<NavigationHandler>,
+    pub(crate) sanitize_inner_html: bool,
+    pub(crate) devtools_panel_shortcut: Option<String>,
+    pub(crate) command_palette_shortcut: Option<String>,
+    #[cfg(feature = "remote-inspector")]
+    pub(crate) remote_inspector_port: Option<u16>,
+    #[cfg(feature = "metrics-endpoint")]
+    pub(crate) metrics_port: Option<u16>,
+    pub(crate) app_id: Option<String>,
+    pub(crate) url_schemes: Vec<String>,
+    pub(crate) hardware_acceleration: bool,
+    pub(crate) user_agent: Option<String>,
+    pub(crate) engine: EngineSettings,
+    pub(crate) proxy: Option<ProxyConfig>,
+    pub(crate) headless: bool,
+    pub(crate) autofill: crate::autofill::AutofillSettings,
+    pub(crate) console_bridge: ConsoleBridge,
+    pub(crate) secure_input: SecureInput,
+    pub(crate) content_protected: bool,
+    pub(crate) crash_recovery: CrashRecovery,
+    pub(crate) event_loop_mode: EventLoopMode,
+    pub(crate) redraw_throttle: u32,
+    pub(crate) edit_transport: EditTransport,
+    pub(crate) theme: Option<Theme>,
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub(crate) window_state: Option<crate::window_state::WindowStatePersistence>,
+    #[cfg(all(target_os = "linux", feature = "wayland-layer-shell"))]
+    pub(crate) layer_shell: Option<crate::wayland::LayerShellConfig>,
+    #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+    pub(crate) portals: crate::portal::PortalMode,
+    #[cfg(target_os = "windows")]
+    pub(crate) dwm: crate::dwm::DwmAttributes,
+    #[cfg(target_os = "macos")]
+    pub(crate) titlebar: crate::titlebar::TitlebarOptions,
+    #[cfg(feature = "tray")]
+    pub(crate) tray: Option<crate::tray::TrayConfig>,
 }
 
 type DropHandler = Box<dyn Fn(&Window, FileDropEvent) -> bool>;
 
+pub(crate) type DownloadCompletedHandler = Box<dyn Fn(&str, Option<&Path>, bool)>;
+
 pub(crate) type WryProtocol = (
     String,
     Box<dyn Fn(HttpRequest<Vec<u8>>) -> HttpResponse<Cow<'static, [u8]>> + 'static>,
@@ -55,17 +121,67 @@ impl Config {
             // event_handler: None,
             window,
             protocols: Vec::new(),
+            asset_mounts: Vec::new(),
+            compression: Compression::default(),
+            cache_policy: CachePolicy::default(),
+            response_headers: ResponseHeaders::default(),
             file_drop_handler: None,
+            download_started_handler: None,
+            download_completed_handler: None,
+            download_directory: None,
             pre_rendered: None,
             disable_context_menu: !cfg!(debug_assertions),
             resource_dir: None,
             data_dir: None,
+            ephemeral: false,
+            #[cfg(feature = "asset-cache")]
+            asset_cache: None,
             custom_head: None,
             custom_index: None,
             root_name: "main".to_string(),
             background_color: None,
             last_window_close_behaviour: WindowCloseBehaviour::LastWindowExitsApp,
             enable_default_menu_bar: true,
+            menu: None,
+            request_filter: RequestFilter::new(),
+            navigation_handler: None,
+            new_window_handler: None,
+            sanitize_inner_html: false,
+            devtools_panel_shortcut: None,
+            command_palette_shortcut: None,
+            #[cfg(feature = "remote-inspector")]
+            remote_inspector_port: None,
+            #[cfg(feature = "metrics-endpoint")]
+            metrics_port: None,
+            app_id: None,
+            url_schemes: Vec::new(),
+            hardware_acceleration: std::env::var_os(crate::rendering::SOFTWARE_RENDERING_VAR)
+                .is_none(),
+            user_agent: None,
+            engine: EngineSettings::default(),
+            proxy: None,
+            headless: false,
+            autofill: Default::default(),
+            console_bridge: ConsoleBridge::default(),
+            secure_input: SecureInput::default(),
+            content_protected: false,
+            crash_recovery: CrashRecovery::default(),
+            event_loop_mode: EventLoopMode::default(),
+            redraw_throttle: 0,
+            edit_transport: EditTransport::default(),
+            theme: None,
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            window_state: None,
+            #[cfg(all(target_os = "linux", feature = "wayland-layer-shell"))]
+            layer_shell: None,
+            #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+            portals: Default::default(),
+            #[cfg(target_os = "windows")]
+            dwm: Default::default(),
+            #[cfg(target_os = "macos")]
+            titlebar: Default::default(),
+            #[cfg(feature = "tray")]
+            tray: None,
         }
     }
 
@@ -77,6 +193,15 @@ impl Config {
         self
     }
 
+    /// Show a custom menu bar instead of the default one.
+    ///
+    /// Selected items are sent to the handlers added with [`use_menu_event`](crate::use_menu_event). Use
+    /// [`DesktopService::set_menu`](crate::DesktopService::set_menu) to change the menu later.
+    pub fn with_menu(mut self, menu: AppMenu) -> Self {
+        self.menu = Some(menu);
+        self
+    }
+
     /// set the directory from which assets will be searched in release mode
     pub fn with_resource_directory(mut self, path: impl Into<PathBuf>) -> Self {
         self.resource_dir = Some(path.into());
@@ -91,6 +216,83 @@ impl Config {
         self
     }
 
+    /// Open the window in an ephemeral session, with cookies, storage and a cache that no other window shares and
+    /// that are deleted when the window closes. The data directory is ignored.
+    ///
+    /// ```rust, ignore
+    /// // A private window next to the main one
+    /// window.new_window(VirtualDom::new(app), Config::new().with_ephemeral(true));
+    /// ```
+    pub fn with_ephemeral(mut self, ephemeral: bool) -> Self {
+        self.ephemeral = ephemeral;
+        self
+    }
+
+    /// Cache remote assets loaded with [`cached_asset_url`](crate::cached_asset_url) on disk, so they are available
+    /// offline after they are loaded once.
+    ///
+    /// The cache is stored in the data directory, or the cache directory of the user if the window has no data
+    /// directory, unless [`AssetCache::with_directory`](crate::AssetCache::with_directory) is set.
+    #[cfg(feature = "asset-cache")]
+    pub fn with_asset_cache(mut self, cache: crate::asset_cache::AssetCache) -> Self {
+        self.asset_cache = Some(cache);
+        self
+    }
+
+    /// Serve a directory under a URL prefix, with MIME detection, ranges and cache headers.
+    ///
+    /// Mounts are checked in the order they were added, after the handlers from
+    /// [`use_asset_handler`](crate::use_asset_handler).
+    pub fn with_asset_mount(mut self, mount: AssetMount) -> Self {
+        self.asset_mounts.push(mount);
+        self
+    }
+
+    /// Set how the files served from disk are compressed and cached. By default, a `.br` or `.gz` copy next to a file
+    /// is sent in its place, and text files are compressed when they are requested if the `compression` feature is
+    /// enabled.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Set how the webview caches the responses of the app protocol. Defaults to [`CachePolicy::NoStore`] in debug
+    /// builds, so edited assets are never stale, and [`CachePolicy::ContentHash`] in release builds.
+    pub fn with_cache_policy(mut self, cache_policy: CachePolicy) -> Self {
+        self.cache_policy = cache_policy;
+        self
+    }
+
+    /// Set the Content-Security-Policy of the page.
+    ///
+    /// The script that starts the interpreter is allowed by its hash, so the policy doesn't need `'unsafe-inline'`.
+    ///
+    /// ```rust, ignore
+    /// Config::new().with_csp("default-src 'self' dioxus:; img-src *")
+    /// ```
+    pub fn with_csp(mut self, csp: impl Into<String>) -> Self {
+        self.response_headers.csp = Some(csp.into());
+        self
+    }
+
+    /// Add headers to the responses of the app protocol. The hook is called with the path of every request the
+    /// protocol answers, including `index.html` at `/`.
+    ///
+    /// ```rust, ignore
+    /// // Enable SharedArrayBuffer
+    /// Config::new().with_response_headers(|path, headers| {
+    ///     headers.insert("Cross-Origin-Opener-Policy", HeaderValue::from_static("same-origin"));
+    ///     headers.insert("Cross-Origin-Embedder-Policy", HeaderValue::from_static("require-corp"));
+    /// })
+    /// ```
+    pub fn with_response_headers(
+        mut self,
+        hook: impl Fn(&str, &mut HeaderMap) + Send + Sync + 'static,
+    ) -> Self {
+        self.response_headers.hook = Some(std::sync::Arc::new(hook));
+        self
+    }
+
     /// Set whether or not the right-click context menu should be disabled.
     pub fn with_disable_context_menu(mut self, disable: bool) -> Self {
         self.disable_context_menu = disable;
@@ -117,7 +319,11 @@ impl Config {
         self
     }
 
-    /// Set a file drop handler
+    /// Set a file drop handler. The handler runs before the drop is delivered to the element under the cursor as
+    /// `ondragenter`, `ondragover`, `ondragleave` and `ondrop` events. Return `false` to let the webview handle the drop
+    /// instead.
+    ///
+    /// Prefer the drag events of the elements, which get the dropped files in [`dioxus_html::DragData::files`].
     pub fn with_file_drop_handler(
         mut self,
         handler: impl Fn(&Window, FileDropEvent) -> bool + 'static,
@@ -126,7 +332,49 @@ impl Config {
         self
     }
 
+    /// Decide what happens to a download before it starts. The handler can change where the file is saved, and
+    /// cancels the download if it returns `false`. Without a handler, the user picks where to save every download in
+    /// a save dialog, unless there is a [download directory](Config::with_download_directory).
+    ///
+    /// ```rust, ignore
+    /// Config::new().with_download_started_handler(|download| {
+    ///     match rfd::FileDialog::new().save_file() {
+    ///         Some(path) => {
+    ///             download.destination = path;
+    ///             true
+    ///         }
+    ///         None => false,
+    ///     }
+    /// })
+    /// ```
+    pub fn with_download_started_handler(
+        mut self,
+        handler: impl FnMut(&mut DownloadRequest) -> bool + 'static,
+    ) -> Self {
+        self.download_started_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Call `handler` with the url, the path and the success of every download once it finishes
+    pub fn with_download_completed_handler(
+        mut self,
+        handler: impl Fn(&str, Option<&Path>, bool) + 'static,
+    ) -> Self {
+        self.download_completed_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Save downloads in `directory` without asking the user. A download gets a new name instead of replacing a file
+    /// that is already in the directory.
+    pub fn with_download_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.download_directory = Some(directory.into());
+        self
+    }
+
     /// Set a custom protocol
+    ///
+    /// Protocols can also be added and removed once the window is open with
+    /// [`DesktopService::register_protocol`](crate::DesktopService::register_protocol).
     pub fn with_custom_protocol<F>(mut self, name: String, handler: F) -> Self
     where
         F: Fn(HttpRequest<Vec<u8>>) -> HttpResponse<Cow<'static, [u8]>> + 'static,
@@ -168,6 +416,332 @@ impl Config {
         self
     }
 
+    /// Set the rules that decide which requests the webview is allowed to make.
+    ///
+    /// See [`RequestFilter`] for how the rules are matched.
+    pub fn with_request_filter(mut self, filter: RequestFilter) -> Self {
+        self.request_filter = filter;
+        self
+    }
+
+    /// Decide what happens when the webview navigates to another page, for example when a link is clicked.
+    ///
+    /// The handler is called with the url of the page, after the [`RequestFilter`]. Pages of the app itself are
+    /// passed to the handler as well, check them with [`is_app_url`](crate::is_app_url).
+    ///
+    /// ```rust, ignore
+    /// Config::new().with_navigation_handler(|url| {
+    ///     if is_app_url(url) {
+    ///         NavigationAction::Allow
+    ///     } else {
+    ///         NavigationAction::OpenExternal
+    ///     }
+    /// })
+    /// ```
+    pub fn with_navigation_handler(
+        mut self,
+        handler: impl Fn(&str) -> NavigationAction + 'static,
+    ) -> Self {
+        self.navigation_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Decide what happens when the page asks for a new window, for example with `window.open` or a link with
+    /// `target="_blank"`.
+    pub fn with_new_window_handler(
+        mut self,
+        handler: impl Fn(&str) -> NavigationAction + 'static,
+    ) -> Self {
+        self.new_window_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Remove every tag, attribute and url scheme that is not on the allowlist from `dangerous_inner_html` before it
+    /// is set.
+    ///
+    /// The allowlist is the same as the default one of `dioxus_html::Sanitizer`.
+    pub fn with_inner_html_sanitizer(mut self, sanitize: bool) -> Self {
+        self.sanitize_inner_html = sanitize;
+        self
+    }
+
+    /// Let the user toggle the devtools panel of the window with a keyboard shortcut like `ctrl+shift+d`.
+    ///
+    /// The panel shows the component tree, the values exposed with [`use_inspected_value`](crate::use_inspected_value),
+    /// the most recent events, and how much data is sent to the webview. It is disabled by default.
+    pub fn with_devtools_panel_shortcut(mut self, shortcut: impl Into<String>) -> Self {
+        self.devtools_panel_shortcut = Some(shortcut.into());
+        self
+    }
+
+    /// Let the user open the command palette with a keyboard shortcut like `ctrl+shift+p`.
+    ///
+    /// The palette lists the commands registered with [`use_command`](crate::use_command) and
+    /// [`DesktopService::register_command`](crate::DesktopService::register_command). The shortcut only works while
+    /// the window is focused.
+    pub fn with_command_palette_shortcut(mut self, shortcut: impl Into<String>) -> Self {
+        self.command_palette_shortcut = Some(shortcut.into());
+        self
+    }
+
+    /// Serve the inspector of the window on a WebSocket at `ws://127.0.0.1:{port}` so devtools running in another
+    /// process can attach to the app.
+    ///
+    /// This works in release builds too. The server only accepts connections from the same machine that know the
+    /// random token of this launch, and rejects connections from web pages. Get the url with the token from
+    /// [`DesktopService::remote_inspector_url`](crate::DesktopService::remote_inspector_url) or the log.
+    #[cfg(feature = "remote-inspector")]
+    pub fn with_remote_inspector(mut self, port: u16) -> Self {
+        self.remote_inspector_port = Some(port);
+        self
+    }
+
+    /// Serve the performance metrics of the window in the Prometheus text format on
+    /// `http://127.0.0.1:{port}/metrics`.
+    ///
+    /// Every window needs its own port.
+    #[cfg(feature = "metrics-endpoint")]
+    pub fn with_metrics_endpoint(mut self, port: u16) -> Self {
+        self.metrics_port = Some(port);
+        self
+    }
+
+    /// Set the application id, like `com.example.Notes`.
+    ///
+    /// On Linux this is the Wayland `app_id` of every window, which compositors use to find the desktop entry and
+    /// icon of the app and to match window rules. It must be a valid D-Bus name. It has no effect on other platforms.
+    pub fn with_app_id(mut self, app_id: impl Into<String>) -> Self {
+        self.app_id = Some(app_id.into());
+        self
+    }
+
+    /// Open the app with urls of `scheme`, like `myapp` for `myapp://login?code=1234`. The urls are passed to the
+    /// handlers added with [`use_deep_link`](crate::use_deep_link). This can be called multiple times to register
+    /// multiple schemes.
+    ///
+    /// On Windows and Linux the scheme is registered for the current user every time the app starts. On macOS and iOS
+    /// it must be declared in the `Info.plist` of the bundle instead.
+    pub fn with_url_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.url_schemes.push(scheme.into());
+        self
+    }
+
+    /// Let the webview draw the page with the GPU. This is on by default, unless the app is started with the
+    /// `DIOXUS_SOFTWARE_RENDERING` environment variable set.
+    ///
+    /// Turn it off if the window stays blank on some GPUs. This uses the flags of WebView2 on Windows and the
+    /// compositing settings of WebKitGTK on Linux, which apply to every window of the app, so set it on the config the
+    /// app is launched with. It has no effect on macOS and iOS.
+    pub fn with_hardware_acceleration(mut self, enabled: bool) -> Self {
+        self.hardware_acceleration = enabled;
+        self
+    }
+
+    /// Set which messages of the console of the webview, and which uncaught JavaScript errors, are logged with
+    /// `tracing`. By default every message is logged in debug builds, and warnings and errors in release builds.
+    pub fn with_console_bridge(mut self, console_bridge: ConsoleBridge) -> Self {
+        self.console_bridge = console_bridge;
+        self
+    }
+
+    /// Replace the `User-Agent` the webview sends and reports to the page with `navigator.userAgent`
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Set the settings of the webview engines that only some platforms have, like the browser arguments of WebView2
+    /// or the hardware acceleration policy of WebKitGTK. Settings for other platforms are ignored.
+    pub fn with_engine_settings(mut self, engine: EngineSettings) -> Self {
+        self.engine = engine;
+        self
+    }
+
+    /// Load remote resources through a proxy, or use [`ProxyConfig::from_env`] to follow the proxy environment
+    /// variables.
+    ///
+    /// On Windows the proxy is set when the browser process starts, so it applies to every window that shares the
+    /// data directory of the first one. WKWebView only supports proxies from macOS 14.
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Offer to fill forms with the entries the user typed before. This is on by default.
+    ///
+    /// Only WebView2 on Windows saves form entries itself. The other webviews ask the page not to be filled, which
+    /// stops WKWebView from filling in contacts from the system. It can be changed later with
+    /// [`DesktopService::set_autofill`](crate::DesktopService::set_autofill).
+    pub fn with_autofill(mut self, enabled: bool) -> Self {
+        self.autofill.autofill = enabled;
+        self
+    }
+
+    /// Offer to save passwords the user types, and to fill them in later. This is on by default.
+    ///
+    /// Turn it off for apps that manage credentials themselves. It can be changed later with
+    /// [`DesktopService::set_password_saving`](crate::DesktopService::set_password_saving).
+    pub fn with_password_saving(mut self, enabled: bool) -> Self {
+        self.autofill.password_saving = enabled;
+        self
+    }
+
+    /// Set how inputs that hold secrets are protected. This is off by default. With [`SecureInput::new`] password
+    /// inputs and inputs with the [`SECURE_INPUT_ATTRIBUTE`](crate::SECURE_INPUT_ATTRIBUTE) are kept from the
+    /// spellchecker and from form autofill, and on macOS the keys typed in them are hidden from other apps.
+    pub fn with_secure_input(mut self, secure_input: SecureInput) -> Self {
+        self.secure_input = secure_input;
+        self
+    }
+
+    /// Hide the contents of the window from screenshots, screen recordings and screen sharing from the moment it is
+    /// shown, for apps that show sensitive data. Other apps see the window as a black or empty rectangle.
+    ///
+    /// This only has an effect on Windows and macOS. It can be changed later with
+    /// [`DesktopService::set_content_protected`](crate::DesktopService::set_content_protected).
+    pub fn with_content_protection(mut self, protected: bool) -> Self {
+        self.content_protected = protected;
+        self
+    }
+
+    /// Set what the window does when the process that renders its page crashes. By default nothing is done, and the
+    /// window stays white until the app reacts to the crash with
+    /// [`use_crash_handler`](crate::use_crash_handler).
+    pub fn with_crash_recovery(mut self, crash_recovery: CrashRecovery) -> Self {
+        self.crash_recovery = crash_recovery;
+        self
+    }
+
+    /// Set how the event loop waits for events. Defaults to [`EventLoopMode::Poll`].
+    ///
+    /// There is one event loop for the whole app, so this is only read from the config the app is launched with. Use
+    /// [`DesktopService::set_event_loop_mode`](crate::DesktopService::set_event_loop_mode) to change it later.
+    pub fn with_event_loop_mode(mut self, mode: EventLoopMode) -> Self {
+        self.event_loop_mode = mode;
+        self
+    }
+
+    /// Render the work the VirtualDom of the window does on its own at most `hz` times per second. `0`, the default,
+    /// means no limit. See
+    /// [`DesktopService::request_redraw_throttle`](crate::DesktopService::request_redraw_throttle).
+    pub fn with_redraw_throttle(mut self, hz: u32) -> Self {
+        self.redraw_throttle = hz;
+        self
+    }
+
+    /// Set how the edits of the VirtualDom reach the page. Defaults to [`EditTransport::Request`]. Apps that render
+    /// many frames a second, like animations, spend less time and CPU per frame with [`EditTransport::Script`].
+    pub fn with_edit_transport(mut self, transport: EditTransport) -> Self {
+        self.edit_transport = transport;
+        self
+    }
+
+    /// Run the app without ever showing its window. The page is still loaded and rendered, so it can be driven by
+    /// scripts, like the [`DesktopTestDriver`](crate::DesktopTestDriver) of [`launch_test`](crate::launch_test) does.
+    pub fn with_headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        self
+    }
+
+    /// Force the window and the page to use `theme`, instead of following the theme of the system.
+    ///
+    /// This sets the theme of the window decorations, and the `prefers-color-scheme` media query of the page.
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Save the size, position and maximized state of the window in the file at `path` when it is moved, resized or
+    /// closed, and restore it the next time the app starts.
+    ///
+    /// If the monitor the window was on is no longer connected, the window is placed by the system instead.
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub fn with_window_state_persistence(mut self, path: impl Into<PathBuf>) -> Self {
+        self.window_state = Some(crate::window_state::WindowStatePersistence::new(
+            path.into(),
+        ));
+        self
+    }
+
+    /// Turn the window into a Wayland layer shell surface, like a panel, dock or overlay.
+    ///
+    /// If the compositor doesn't support the layer shell protocol, or the app is running on X11, the window is shown
+    /// as a normal window.
+    #[cfg(all(target_os = "linux", feature = "wayland-layer-shell"))]
+    pub fn with_layer_shell(mut self, layer_shell: crate::wayland::LayerShellConfig) -> Self {
+        self.layer_shell = Some(layer_shell);
+        self
+    }
+
+    /// Choose when to use the XDG desktop portals for file dialogs, screenshots and notifications.
+    ///
+    /// By default the portals are only used when the app is running in a Flatpak or Snap sandbox.
+    #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+    pub fn with_portals(mut self, mode: crate::portal::PortalMode) -> Self {
+        self.portals = mode;
+        self
+    }
+
+    /// Set how the corners of the window are rounded. Only supported on Windows 11.
+    #[cfg(target_os = "windows")]
+    pub fn with_corner_preference(mut self, preference: crate::dwm::CornerPreference) -> Self {
+        self.dwm.corner_preference = Some(preference);
+        self
+    }
+
+    /// Draw the titlebar in dark colors. Only supported on Windows 10 20H1 and later.
+    #[cfg(target_os = "windows")]
+    pub fn with_dark_titlebar(mut self, dark: bool) -> Self {
+        self.dwm.dark_titlebar = Some(dark);
+        self
+    }
+
+    /// Set the material drawn behind the window's content, like Mica or Acrylic. Only supported on Windows 11 22H2
+    /// and later.
+    ///
+    /// The material is only visible through transparent parts of the page.
+    #[cfg(target_os = "windows")]
+    pub fn with_backdrop(mut self, backdrop: crate::dwm::BackdropMaterial) -> Self {
+        self.dwm.backdrop = Some(backdrop);
+        self
+    }
+
+    /// Set how the titlebar is drawn. Use [`TitlebarStyle::Transparent`](crate::TitlebarStyle::Transparent) to draw
+    /// the page under the titlebar in apps with custom window chrome.
+    #[cfg(target_os = "macos")]
+    pub fn with_titlebar_style(mut self, style: crate::titlebar::TitlebarStyle) -> Self {
+        self.titlebar.style = style;
+        self
+    }
+
+    /// Move the traffic-light buttons. `position` is the distance of the close button from the top left corner of
+    /// the window.
+    #[cfg(target_os = "macos")]
+    pub fn with_traffic_light_position(
+        mut self,
+        position: wry::application::dpi::LogicalPosition<f64>,
+    ) -> Self {
+        self.titlebar.traffic_light_position = Some(position);
+        self
+    }
+
+    /// Hide the traffic-light buttons. The window can still be closed and minimized with the keyboard.
+    #[cfg(target_os = "macos")]
+    pub fn with_traffic_lights_hidden(mut self, hidden: bool) -> Self {
+        self.titlebar.traffic_lights_hidden = hidden;
+        self
+    }
+
+    /// Show an icon in the system tray while the app is running.
+    ///
+    /// Only the config the app is launched with can create the tray. Use
+    /// [`DesktopService::set_tray_menu`](crate::DesktopService::set_tray_menu) to change it later.
+    #[cfg(feature = "tray")]
+    pub fn with_tray(mut self, tray: crate::tray::TrayConfig) -> Self {
+        self.tray = Some(tray);
+        self
+    }
+
     /// Sets the background color of the WebView.
     /// This will be set before the HTML is rendered and can be used to prevent flashing when the page loads.
     /// Accepts a color in RGBA format
@@ -0,0 +1,125 @@
+//! Native access to the system clipboard.
+//!
+//! The clipboard API of the webview needs permissions that WebView2 and WebKit handle differently, and reading the
+//! clipboard often fails outside of a user gesture. This goes through the operating system instead.
+//!
+//! This needs the `clipboard` feature.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use dioxus_core::ScopeState;
+use thiserror::Error;
+
+use crate::use_window;
+
+/// An error that can occur when using the clipboard
+#[derive(Error, Debug)]
+pub enum ClipboardError {
+    /// The clipboard is empty, or holds a different kind of content than the one requested
+    #[error("The clipboard doesn't contain the requested content")]
+    Empty,
+    /// The clipboard could not be accessed
+    #[error("Failed to access the clipboard: {0}")]
+    Clipboard(arboard::Error),
+}
+
+impl From<arboard::Error> for ClipboardError {
+    fn from(err: arboard::Error) -> Self {
+        match err {
+            arboard::Error::ContentNotAvailable => ClipboardError::Empty,
+            err => ClipboardError::Clipboard(err),
+        }
+    }
+}
+
+/// An image on the clipboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardImage {
+    /// The width of the image in pixels
+    pub width: usize,
+    /// The height of the image in pixels
+    pub height: usize,
+    /// The pixels of the image, row by row, as 4 bytes of RGBA each
+    pub rgba: Vec<u8>,
+}
+
+/// The system clipboard.
+///
+/// Get it with [`DesktopService::clipboard`](crate::DesktopService::clipboard) or [`use_clipboard`].
+#[derive(Clone, Default)]
+pub struct Clipboard {
+    // Created on first use. On Linux the content the app copies is served by this connection, so it is kept open.
+    inner: Rc<RefCell<Option<arboard::Clipboard>>>,
+}
+
+impl Clipboard {
+    fn with<T>(
+        &self,
+        f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>,
+    ) -> Result<T, ClipboardError> {
+        let mut inner = self.inner.borrow_mut();
+        if inner.is_none() {
+            *inner = Some(arboard::Clipboard::new()?);
+        }
+        let clipboard = inner.as_mut().expect("the clipboard was just created");
+        Ok(f(clipboard)?)
+    }
+
+    /// Get the text on the clipboard
+    pub fn get_text(&self) -> Result<String, ClipboardError> {
+        self.with(|clipboard| clipboard.get_text())
+    }
+
+    /// Replace the content of the clipboard with `text`
+    pub fn set_text(&self, text: impl Into<String>) -> Result<(), ClipboardError> {
+        let text = text.into();
+        self.with(|clipboard| clipboard.set_text(text))
+    }
+
+    /// Get the image on the clipboard
+    pub fn get_image(&self) -> Result<ClipboardImage, ClipboardError> {
+        let image = self.with(|clipboard| clipboard.get_image())?;
+        Ok(ClipboardImage {
+            width: image.width,
+            height: image.height,
+            rgba: image.bytes.into_owned(),
+        })
+    }
+
+    /// Replace the content of the clipboard with `image`
+    pub fn set_image(&self, image: ClipboardImage) -> Result<(), ClipboardError> {
+        self.with(|clipboard| {
+            clipboard.set_image(arboard::ImageData {
+                width: image.width,
+                height: image.height,
+                bytes: Cow::Owned(image.rgba),
+            })
+        })
+    }
+
+    /// Remove everything from the clipboard
+    pub fn clear(&self) -> Result<(), ClipboardError> {
+        self.with(|clipboard| clipboard.clear())
+    }
+}
+
+/// Get the system clipboard.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let clipboard = use_clipboard(cx);
+///
+///     render! {
+///         button {
+///             onclick: move |_| _ = clipboard.set_text("Hello from Dioxus"),
+///             "Copy"
+///         }
+///     }
+/// }
+/// ```
+pub fn use_clipboard(cx: &ScopeState) -> &Clipboard {
+    let desktop = use_window(cx);
+    cx.use_hook(|| desktop.clipboard())
+}
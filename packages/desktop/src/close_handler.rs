@@ -0,0 +1,93 @@
+//! Intercepting requests to close a window.
+//!
+//! Before a window is closed by the user or by [`DesktopService::close`](crate::DesktopService::close), every close
+//! handler of the window is asked if it may close. If one of them prevents it, the window stays open, so the app can
+//! ask the user to save their changes first and close the window afterwards with
+//! [`DesktopService::force_close`](crate::DesktopService::force_close).
+
+use dioxus_core::ScopeState;
+
+use crate::{use_window, DesktopContext};
+
+/// What should happen to a window the user or the app asked to close
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseBehaviour {
+    /// Close the window
+    Close,
+    /// Keep the window open
+    Prevent,
+}
+
+/// The unique identifier of a close handler. This can be used to later remove the handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CloseHandlerId(pub(crate) usize);
+
+/// A close handler that is scoped to the current component.
+///
+/// This will automatically be removed when the component is unmounted.
+pub struct CloseHandle {
+    desktop: DesktopContext,
+    /// The unique identifier of the handler.
+    pub id: CloseHandlerId,
+}
+
+impl CloseHandle {
+    /// Remove the handler.
+    pub fn remove(&self) {
+        self.desktop.remove_close_handler(self.id);
+    }
+}
+
+impl Drop for CloseHandle {
+    fn drop(&mut self) {
+        self.remove()
+    }
+}
+
+/// Call `handler` every time the current window is asked to close. The window stays open if the handler returns
+/// [`CloseBehaviour::Prevent`].
+///
+/// The handler runs on the event loop, so it should only decide if the window may close. Show a dialog from the
+/// component instead, and call [`DesktopService::force_close`](crate::DesktopService::force_close) once the user
+/// confirms.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let window = use_window(cx);
+///     let unsaved = use_state(cx, || true);
+///     let confirming = use_state(cx, || false);
+///
+///     use_window_close_handler(cx, {
+///         to_owned![unsaved, confirming];
+///         move || {
+///             if *unsaved.current() {
+///                 confirming.set(true);
+///                 CloseBehaviour::Prevent
+///             } else {
+///                 CloseBehaviour::Close
+///             }
+///         }
+///     });
+///
+///     render! {
+///         if **confirming {
+///             rsx! {
+///                 "You have unsaved changes."
+///                 button { onclick: move |_| window.force_close(), "Discard and close" }
+///                 button { onclick: move |_| confirming.set(false), "Cancel" }
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn use_window_close_handler(
+    cx: &ScopeState,
+    handler: impl FnMut() -> CloseBehaviour + 'static,
+) -> &CloseHandle {
+    let desktop = use_window(cx);
+    cx.use_hook(move || {
+        let desktop = desktop.clone();
+        let id = desktop.on_close_requested(handler);
+        CloseHandle { desktop, id }
+    })
+}
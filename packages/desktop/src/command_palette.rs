@@ -0,0 +1,277 @@
+//! A command palette shared by every window of the app.
+//!
+//! Commands are registered with a name and an optional shortcut. The palette is an overlay drawn over the page that
+//! lets the user search the commands by name and run one. Commands with a shortcut also register it as a global
+//! shortcut, which is removed again with the command.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use dioxus_core::ScopeState;
+use serde::Serialize;
+use slab::Slab;
+
+use crate::shortcut::{HotKey, ShortcutId, ShortcutRegistry, ShortcutRegistryError};
+use crate::{use_window, DesktopContext};
+
+/// The unique identifier of a command. This can be used to later remove the command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CommandId(pub(crate) usize);
+
+type CommandAction = Rc<RefCell<Box<dyn FnMut()>>>;
+
+struct Command {
+    name: String,
+    shortcut: Option<(String, ShortcutId)>,
+    action: CommandAction,
+}
+
+/// A command as it is shown in the palette
+#[derive(Serialize)]
+struct PaletteEntry<'a> {
+    id: usize,
+    name: &'a str,
+    shortcut: Option<&'a str>,
+}
+
+/// The commands of the app
+#[derive(Clone)]
+pub(crate) struct CommandRegistry {
+    commands: Rc<RefCell<Slab<Command>>>,
+    shortcuts: ShortcutRegistry,
+}
+
+impl CommandRegistry {
+    pub(crate) fn new(shortcuts: ShortcutRegistry) -> Self {
+        Self {
+            commands: Default::default(),
+            shortcuts,
+        }
+    }
+
+    pub(crate) fn register(
+        &self,
+        name: String,
+        shortcut: Option<&str>,
+        action: Box<dyn FnMut()>,
+    ) -> Result<CommandId, ShortcutRegistryError> {
+        let action: CommandAction = Rc::new(RefCell::new(action));
+
+        let shortcut = match shortcut {
+            Some(shortcut) => {
+                let hotkey = HotKey::from_str(shortcut)
+                    .map_err(|_| ShortcutRegistryError::InvalidShortcut(shortcut.to_string()))?;
+                let action = action.clone();
+                let id = self
+                    .shortcuts
+                    .add_shortcut(hotkey, Box::new(move || (&mut *action.borrow_mut())()))?;
+                Some((shortcut.to_string(), id))
+            }
+            None => None,
+        };
+
+        let id = self.commands.borrow_mut().insert(Command {
+            name,
+            shortcut,
+            action,
+        });
+        Ok(CommandId(id))
+    }
+
+    pub(crate) fn remove(&self, id: CommandId) {
+        let command = self.commands.borrow_mut().try_remove(id.0);
+        if let Some((_, shortcut)) = command.and_then(|command| command.shortcut) {
+            self.shortcuts.remove_shortcut(shortcut);
+        }
+    }
+
+    pub(crate) fn run(&self, id: CommandId) {
+        // The action may register or remove commands, so the registry can't stay borrowed while it runs
+        let action = self
+            .commands
+            .borrow()
+            .get(id.0)
+            .map(|command| command.action.clone());
+        if let Some(action) = action {
+            (&mut *action.borrow_mut())();
+        }
+    }
+
+    /// A script that opens the palette with the current commands, or closes it if it is already open
+    pub(crate) fn palette_script(&self) -> String {
+        let commands = self.commands.borrow();
+        let entries: Vec<_> = commands
+            .iter()
+            .map(|(id, command)| PaletteEntry {
+                id,
+                name: &command.name,
+                shortcut: command
+                    .shortcut
+                    .as_ref()
+                    .map(|(shortcut, _)| shortcut.as_str()),
+            })
+            .collect();
+        PALETTE_SCRIPT.replace(
+            "{COMMANDS}",
+            &serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string()),
+        )
+    }
+}
+
+const PALETTE_SCRIPT: &str = r#"(function (commands) {
+    const existing = document.getElementById("dioxus-command-palette");
+    if (existing) {
+        existing.remove();
+        return;
+    }
+
+    const host = document.createElement("div");
+    host.id = "dioxus-command-palette";
+    const root = host.attachShadow({ mode: "closed" });
+    root.innerHTML = `<style>
+        .backdrop { position: fixed; inset: 0; z-index: 2147483647; display: flex; justify-content: center;
+            align-items: flex-start; padding-top: 15vh; background: rgba(0, 0, 0, 0.3); font: 14px system-ui, sans-serif; }
+        .palette { width: min(560px, 90vw); background: #252526; color: #ddd; border-radius: 8px; overflow: hidden;
+            box-shadow: 0 8px 32px rgba(0, 0, 0, 0.5); }
+        input { box-sizing: border-box; width: 100%; padding: 12px 16px; border: none; outline: none;
+            background: #3c3c3c; color: inherit; font: inherit; }
+        ul { list-style: none; margin: 0; padding: 4px 0; max-height: 50vh; overflow-y: auto; }
+        li { display: flex; justify-content: space-between; padding: 6px 16px; cursor: pointer; }
+        li.selected { background: #094771; }
+        kbd { color: #999; font: 12px ui-monospace, monospace; }
+    </style><div class="backdrop"><div class="palette"><input placeholder="Type a command" /><ul></ul></div></div>`;
+
+    const backdrop = root.querySelector(".backdrop");
+    const input = root.querySelector("input");
+    const list = root.querySelector("ul");
+    let matches = commands;
+    let selected = 0;
+
+    // The letters of the query must appear in the name in order. Names with fewer letters between them rank higher.
+    const score = (name, query) => {
+        name = name.toLowerCase();
+        let last = -1;
+        let gaps = 0;
+        for (const letter of query.toLowerCase()) {
+            const index = name.indexOf(letter, last + 1);
+            if (index < 0) return -1;
+            gaps += index - last - 1;
+            last = index;
+        }
+        return gaps;
+    };
+    const close = () => host.remove();
+    const run = (command) => {
+        close();
+        window.ipc.postMessage(JSON.stringify({ method: "command_palette_run", params: { id: command.id } }));
+    };
+    const render = () => {
+        list.replaceChildren(...matches.map((command, index) => {
+            const item = document.createElement("li");
+            if (index === selected) item.className = "selected";
+            const name = document.createElement("span");
+            name.textContent = command.name;
+            item.append(name);
+            if (command.shortcut) {
+                const shortcut = document.createElement("kbd");
+                shortcut.textContent = command.shortcut;
+                item.append(shortcut);
+            }
+            item.addEventListener("mousedown", (e) => {
+                e.preventDefault();
+                run(command);
+            });
+            return item;
+        }));
+        const item = list.children[selected];
+        if (item) item.scrollIntoView({ block: "nearest" });
+    };
+
+    input.addEventListener("input", () => {
+        const query = input.value.trim();
+        matches = commands
+            .map((command) => [command, score(command.name, query)])
+            .filter(([, score]) => score >= 0)
+            .sort((a, b) => a[1] - b[1])
+            .map(([command]) => command);
+        selected = 0;
+        render();
+    });
+    input.addEventListener("keydown", (e) => {
+        e.stopPropagation();
+        if (e.key === "ArrowDown") {
+            selected = Math.min(selected + 1, matches.length - 1);
+        } else if (e.key === "ArrowUp") {
+            selected = Math.max(selected - 1, 0);
+        } else if (e.key === "Enter") {
+            if (matches[selected]) run(matches[selected]);
+        } else if (e.key === "Escape") {
+            close();
+        } else {
+            return;
+        }
+        e.preventDefault();
+        render();
+    });
+    backdrop.addEventListener("mousedown", (e) => {
+        if (e.target === backdrop) close();
+    });
+
+    render();
+    document.body.append(host);
+    input.focus();
+})({COMMANDS});"#;
+
+/// A command registered with [`use_command`]. The command is removed when the handle is dropped.
+pub struct CommandHandle {
+    desktop: DesktopContext,
+    command_id: CommandId,
+}
+
+impl CommandHandle {
+    /// Remove the command
+    pub fn remove(&self) {
+        self.desktop.remove_command(self.command_id);
+    }
+}
+
+impl Drop for CommandHandle {
+    fn drop(&mut self) {
+        self.remove()
+    }
+}
+
+/// Add a command to the command palette while the component is mounted.
+///
+/// If `shortcut` is set, like `"ctrl+shift+k"`, the command also runs when the shortcut is pressed.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let count = use_state(cx, || 0);
+///     use_command(cx, "Reset counter", Some("ctrl+shift+r"), {
+///         to_owned![count];
+///         move || count.set(0)
+///     });
+///
+///     render! { "{count}" }
+/// }
+/// ```
+pub fn use_command<'a>(
+    cx: &'a ScopeState,
+    name: &str,
+    shortcut: Option<&str>,
+    action: impl FnMut() + 'static,
+) -> &'a Result<CommandHandle, ShortcutRegistryError> {
+    let desktop = use_window(cx);
+    cx.use_hook(move || {
+        let desktop = desktop.clone();
+
+        let id = desktop.register_command(name, shortcut, action);
+
+        Ok(CommandHandle {
+            desktop,
+            command_id: id?,
+        })
+    })
+}
@@ -0,0 +1,298 @@
+//! Compressed responses for the files the `dioxus://` protocol serves from disk.
+//!
+//! When the webview accepts it, a file is sent with `Content-Encoding: br` or `gzip`. A precompressed copy next to the
+//! file (`main.js.br` or `main.js.gz`) is sent as is. Without one, text files like scripts, styles and wasm are
+//! compressed when they are requested if the `compression` feature is enabled. Every response has an `ETag` so the
+//! webview only downloads, and the app only compresses, a file again when it changes.
+
+use std::borrow::Cow;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use wry::http::{header, status::StatusCode, HeaderValue, Response};
+
+use crate::mount::{file_version, is_fresh, status};
+use crate::protocol::{AssetRequest, AssetResponse};
+
+/// How the files served from disk are compressed. Set it with
+/// [`Config::with_compression`](crate::Config::with_compression).
+///
+/// ```rust, ignore
+/// let compression = Compression::new().with_cache_control("max-age=3600");
+/// dioxus_desktop::launch_cfg(app, Config::new().with_compression(compression));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Compression {
+    precompressed: bool,
+    #[cfg(feature = "compression")]
+    on_the_fly: bool,
+    min_size: u64,
+    cache_control: Option<String>,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self {
+            precompressed: true,
+            #[cfg(feature = "compression")]
+            on_the_fly: true,
+            min_size: 1024,
+            cache_control: None,
+        }
+    }
+}
+
+/// A `Content-Encoding` the webview can decode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Brotli,
+    Gzip,
+}
+
+impl Encoding {
+    /// The encodings in the order they are preferred. Brotli files are smaller, and decode as fast as gzip files.
+    const PREFERRED: [Encoding; 2] = [Encoding::Brotli, Encoding::Gzip];
+
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+        }
+    }
+
+    /// The extension of a precompressed copy of a file
+    fn extension(self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gz",
+        }
+    }
+}
+
+/// Where the compressed body comes from
+enum Source {
+    Precompressed(PathBuf),
+    #[cfg(feature = "compression")]
+    OnTheFly,
+}
+
+impl Compression {
+    /// Send precompressed copies of files, and compress text files of at least 1 KiB when the `compression` feature
+    /// is enabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Don't compress any response
+    pub fn disabled() -> Self {
+        Self::new().with_precompressed(false).with_on_the_fly(false)
+    }
+
+    /// Set whether a `.br` or `.gz` copy next to a file is sent in its place. Defaults to `true`.
+    pub fn with_precompressed(mut self, precompressed: bool) -> Self {
+        self.precompressed = precompressed;
+        self
+    }
+
+    /// Set whether text files without a precompressed copy are compressed when they are requested. Defaults to
+    /// `true`. This does nothing unless the `compression` feature is enabled.
+    pub fn with_on_the_fly(mut self, on_the_fly: bool) -> Self {
+        #[cfg(feature = "compression")]
+        {
+            self.on_the_fly = on_the_fly;
+        }
+        #[cfg(not(feature = "compression"))]
+        let _ = on_the_fly;
+        self
+    }
+
+    /// Set the smallest file, in bytes, that is compressed when it is requested. Defaults to 1 KiB.
+    pub fn with_min_size(mut self, min_size: u64) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Set the `Cache-Control` header of the responses. Defaults to the header of the
+    /// [`CachePolicy`](crate::CachePolicy) of the window.
+    pub fn with_cache_control(mut self, cache_control: impl Into<String>) -> Self {
+        self.cache_control = Some(cache_control.into());
+        self
+    }
+
+    /// Respond to `request` with the file at `path`, compressed if the webview accepts it
+    pub(crate) fn serve_file(
+        &self,
+        request: &AssetRequest,
+        path: &Path,
+    ) -> io::Result<AssetResponse> {
+        let metadata = path.metadata()?;
+        let version = file_version(&metadata);
+
+        let content_type = crate::protocol::get_mime_from_path(path)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+
+        // Ranges are offsets into the uncompressed file, so range requests for media are never compressed
+        let encoded = if request.headers().contains_key(header::RANGE) {
+            None
+        } else {
+            self.choose(request, path, content_type, metadata.len())
+        };
+
+        let Some((encoding, source)) = encoded else {
+            let etag = format!("\"{version}\"");
+            if is_fresh(request, &etag) {
+                return Ok(self.with_cache_headers(status(StatusCode::NOT_MODIFIED)?, &etag));
+            }
+            return Ok(self.with_cache_headers(crate::range::serve_file(request, path)?, &etag));
+        };
+
+        // Each encoding is a different body, so it needs its own tag
+        let etag = format!("\"{version}-{}\"", encoding.extension());
+        if is_fresh(request, &etag) {
+            return Ok(self.with_cache_headers(status(StatusCode::NOT_MODIFIED)?, &etag));
+        }
+
+        let body = match source {
+            Source::Precompressed(compressed) => std::fs::read(compressed)?,
+            #[cfg(feature = "compression")]
+            Source::OnTheFly => compress(encoding, &std::fs::read(path)?)?,
+        };
+        let response = Response::builder()
+            .header(header::CONTENT_TYPE, content_type)
+            .header(header::CONTENT_ENCODING, encoding.name())
+            .header(header::CONTENT_LENGTH, body.len())
+            .body(Cow::from(body))
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        Ok(self.with_cache_headers(response, &etag))
+    }
+
+    /// The encoding to send the file at `path` with, and where its compressed body comes from
+    fn choose(
+        &self,
+        request: &AssetRequest,
+        path: &Path,
+        content_type: &str,
+        len: u64,
+    ) -> Option<(Encoding, Source)> {
+        let accepted = accepted_encodings(request);
+
+        if self.precompressed {
+            for encoding in Encoding::PREFERRED {
+                if !accepted.contains(&encoding) {
+                    continue;
+                }
+                let mut compressed = path.as_os_str().to_owned();
+                compressed.push(".");
+                compressed.push(encoding.extension());
+                let compressed = PathBuf::from(compressed);
+                if compressed.is_file() {
+                    return Some((encoding, Source::Precompressed(compressed)));
+                }
+            }
+        }
+
+        #[cfg(feature = "compression")]
+        if self.on_the_fly && len >= self.min_size && is_compressible(content_type) {
+            if let Some(encoding) = Encoding::PREFERRED
+                .iter()
+                .copied()
+                .find(|encoding| accepted.contains(encoding))
+            {
+                return Some((encoding, Source::OnTheFly));
+            }
+        }
+        #[cfg(not(feature = "compression"))]
+        let _ = (content_type, len, self.min_size);
+
+        None
+    }
+
+    fn with_cache_headers(&self, mut response: AssetResponse, etag: &str) -> AssetResponse {
+        let headers = response.headers_mut();
+        if let Ok(etag) = HeaderValue::from_str(etag) {
+            headers.insert(header::ETAG, etag);
+        }
+        if let Some(Ok(cache_control)) = self.cache_control.as_deref().map(HeaderValue::from_str) {
+            headers.insert(header::CACHE_CONTROL, cache_control);
+        }
+        headers.insert(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+        response
+    }
+}
+
+/// Whether the webview can decode a gzip body for `request`
+#[cfg(feature = "archive")]
+pub(crate) fn accepts_gzip(request: &AssetRequest) -> bool {
+    accepted_encodings(request).contains(&Encoding::Gzip)
+}
+
+/// The encodings the `Accept-Encoding` header of `request` allows
+fn accepted_encodings(request: &AssetRequest) -> Vec<Encoding> {
+    let Some(accept) = request
+        .headers()
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return Vec::new();
+    };
+
+    accept
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let name = parts.next()?.trim();
+            // `gzip;q=0` means gzip must not be used
+            let refused = parts.any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.trim().parse::<f32>().ok())
+                    .map_or(false, |q| q <= 0.0)
+            });
+            if refused {
+                return None;
+            }
+            match name {
+                "br" => Some(Encoding::Brotli),
+                "gzip" | "x-gzip" => Some(Encoding::Gzip),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Whether files of `content_type` get smaller when they are compressed. Images, audio and video already are.
+#[cfg(feature = "compression")]
+fn is_compressible(content_type: &str) -> bool {
+    content_type.starts_with("text/")
+        || matches!(
+            content_type,
+            "application/javascript"
+                | "application/json"
+                | "application/ld+json"
+                | "application/wasm"
+                | "application/rtf"
+                | "image/svg+xml"
+                | "image/vnd.microsoft.icon"
+        )
+}
+
+#[cfg(feature = "compression")]
+fn compress(encoding: Encoding, body: &[u8]) -> io::Result<Vec<u8>> {
+    use std::io::Write;
+
+    match encoding {
+        Encoding::Brotli => {
+            // Quality 11 is several times slower for a few percent smaller files, too slow to run on every launch
+            let mut encoder = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+            encoder.write_all(body)?;
+            Ok(encoder.into_inner())
+        }
+        Encoding::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(body)?;
+            encoder.finish()
+        }
+    }
+}
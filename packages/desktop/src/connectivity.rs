@@ -0,0 +1,275 @@
+//! Network connectivity status backed by the operating system.
+//!
+//! The operating system notifies us every time a network interface gains or loses an address (netlink on Linux,
+//! the System Configuration framework on macOS, and `NotifyIpInterfaceChange` on Windows). The app is considered
+//! online while any interface has an address that can reach beyond the local machine.
+//!
+//! Without the `tokio_runtime` feature the interfaces are not watched and the app is always reported as online.
+
+use std::{cell::Cell, cell::RefCell, rc::Rc};
+
+use dioxus_core::ScopeState;
+use slab::Slab;
+use wry::application::window::WindowId;
+
+use crate::desktop_context::{EventData, ProxyType, UserWindowEvent};
+use crate::window;
+
+/// The unique identifier of a connectivity handler. This can be used to later remove the handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConnectivityHandlerId(usize);
+
+type ConnectivityCallback = Rc<RefCell<dyn FnMut(bool)>>;
+
+struct ConnectivityInner {
+    online: Cell<bool>,
+    handlers: RefCell<Slab<ConnectivityCallback>>,
+    #[cfg(feature = "tokio_runtime")]
+    watcher: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for ConnectivityInner {
+    fn drop(&mut self) {
+        #[cfg(feature = "tokio_runtime")]
+        self.watcher.abort();
+    }
+}
+
+/// The network connectivity of the device.
+///
+/// Get one with [`DesktopService::connectivity`](crate::DesktopService::connectivity).
+#[derive(Clone)]
+pub struct Connectivity {
+    inner: Rc<ConnectivityInner>,
+}
+
+impl Connectivity {
+    #[cfg_attr(not(feature = "tokio_runtime"), allow(unused_variables))]
+    pub(crate) fn new(proxy: ProxyType, window_id: WindowId) -> Self {
+        Self {
+            inner: Rc::new(ConnectivityInner {
+                // Assume we are online until the operating system reports the interfaces
+                online: Cell::new(true),
+                handlers: Default::default(),
+                #[cfg(feature = "tokio_runtime")]
+                watcher: tokio::spawn(watch_interfaces(proxy, window_id)),
+            }),
+        }
+    }
+
+    /// Check if the device has a network connection.
+    ///
+    /// This is `false` when no network interface has a routable address. A `true` value means the device is
+    /// connected to a network, not that any particular server is reachable.
+    pub fn is_online(&self) -> bool {
+        self.inner.online.get()
+    }
+
+    /// Call `handler` with the new status every time the device goes offline or comes back online.
+    ///
+    /// The id this function returns can be used to remove the handler with [`Connectivity::remove_connectivity_handler`]
+    pub fn create_connectivity_handler(
+        &self,
+        handler: impl FnMut(bool) + 'static,
+    ) -> ConnectivityHandlerId {
+        ConnectivityHandlerId(
+            self.inner
+                .handlers
+                .borrow_mut()
+                .insert(Rc::new(RefCell::new(handler))),
+        )
+    }
+
+    /// Remove a connectivity handler created with [`Connectivity::create_connectivity_handler`]
+    pub fn remove_connectivity_handler(&self, id: ConnectivityHandlerId) {
+        self.inner.handlers.borrow_mut().try_remove(id.0);
+    }
+
+    pub(crate) fn set_online(&self, online: bool) {
+        if self.inner.online.replace(online) == online {
+            return;
+        }
+        // Clone the handlers out of the slab so handlers can add or remove handlers while they run
+        let handlers: Vec<_> = self
+            .inner
+            .handlers
+            .borrow()
+            .iter()
+            .map(|(_, handler)| handler.clone())
+            .collect();
+        for handler in handlers {
+            (handler.borrow_mut())(online);
+        }
+    }
+}
+
+/// Watch the addresses of every network interface and send the connectivity status to the window when it changes.
+#[cfg(feature = "tokio_runtime")]
+async fn watch_interfaces(proxy: ProxyType, window_id: WindowId) {
+    let watcher = match if_watch::tokio::IfWatcher::new() {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            tracing::error!("Failed to watch the network interfaces: {}", err);
+            return;
+        }
+    };
+
+    report_connectivity(Box::pin(watcher), |online| {
+        let event = UserWindowEvent(EventData::Connectivity(online), window_id);
+        proxy.send_event(event).is_ok()
+    })
+    .await;
+}
+
+/// Track the addresses in `events` and call `report` every time the connectivity status changes. Stops when
+/// `report` returns `false`.
+///
+/// The watcher reports the existing addresses one at a time when it starts, so every event that is already
+/// queued is applied before the status is checked. Otherwise the first address (usually loopback) would briefly
+/// report the device as offline.
+#[cfg(feature = "tokio_runtime")]
+async fn report_connectivity(
+    mut events: impl futures_util::Stream<Item = std::io::Result<if_watch::IfEvent>> + Unpin,
+    mut report: impl FnMut(bool) -> bool,
+) {
+    use futures_util::{FutureExt, StreamExt};
+    use std::collections::HashSet;
+    use std::net::IpAddr;
+
+    let mut addresses = HashSet::<IpAddr>::new();
+    let apply =
+        |addresses: &mut HashSet<IpAddr>, event: std::io::Result<if_watch::IfEvent>| match event {
+            Ok(if_watch::IfEvent::Up(net)) => {
+                addresses.insert(net.addr());
+            }
+            Ok(if_watch::IfEvent::Down(net)) => {
+                addresses.remove(&net.addr());
+            }
+            Err(err) => {
+                tracing::error!("Failed to read a network interface change: {}", err);
+            }
+        };
+
+    // Matches the status `Connectivity` assumes before anything is reported
+    let mut online = true;
+    while let Some(event) = events.next().await {
+        apply(&mut addresses, event);
+        while let Some(Some(event)) = events.next().now_or_never() {
+            apply(&mut addresses, event);
+        }
+
+        let now_online = addresses.iter().any(is_routable);
+        if online != now_online {
+            online = now_online;
+            if !report(online) {
+                break;
+            }
+        }
+    }
+}
+
+/// Check if an address can reach beyond the local machine and link.
+#[cfg(feature = "tokio_runtime")]
+fn is_routable(address: &std::net::IpAddr) -> bool {
+    match address {
+        std::net::IpAddr::V4(address) => {
+            !address.is_loopback() && !address.is_link_local() && !address.is_unspecified()
+        }
+        std::net::IpAddr::V6(address) => {
+            // fe80::/10 is link local
+            !address.is_loopback()
+                && !address.is_unspecified()
+                && (address.segments()[0] & 0xffc0) != 0xfe80
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tokio_runtime"))]
+mod tests {
+    use super::*;
+    use futures_util::{stream, StreamExt};
+    use if_watch::{IfEvent, IpNet};
+
+    fn up(address: &str) -> IfEvent {
+        IfEvent::Up(address.parse::<IpNet>().unwrap())
+    }
+
+    fn down(address: &str) -> IfEvent {
+        IfEvent::Down(address.parse::<IpNet>().unwrap())
+    }
+
+    /// Run the events through `report_connectivity`. The events in a batch are ready together, and each batch
+    /// only becomes ready after the previous one was handled.
+    async fn reports(batches: Vec<Vec<IfEvent>>) -> Vec<bool> {
+        let events = stream::iter(batches)
+            .then(|batch| async move {
+                tokio::task::yield_now().await;
+                stream::iter(batch.into_iter().map(Ok))
+            })
+            .flatten();
+        let mut reports = Vec::new();
+        report_connectivity(Box::pin(events), |online| {
+            reports.push(online);
+            true
+        })
+        .await;
+        reports
+    }
+
+    #[tokio::test]
+    async fn initial_interfaces_do_not_report_offline_first() {
+        let initial = vec![up("127.0.0.1/8"), up("::1/128"), up("192.168.1.20/24")];
+        assert_eq!(reports(vec![initial]).await, Vec::<bool>::new());
+    }
+
+    #[tokio::test]
+    async fn only_local_interfaces_report_offline_once() {
+        let initial = vec![up("127.0.0.1/8"), up("fe80::1/64"), up("169.254.3.4/16")];
+        assert_eq!(reports(vec![initial]).await, vec![false]);
+    }
+
+    #[tokio::test]
+    async fn status_changes_are_reported() {
+        let batches = vec![
+            vec![up("127.0.0.1/8"), up("10.0.0.2/8")],
+            vec![down("10.0.0.2/8")],
+            vec![up("2001:db8::2/64")],
+            vec![up("10.0.0.2/8")],
+        ];
+        assert_eq!(reports(batches).await, vec![false, true]);
+    }
+}
+
+/// A connectivity handler that is scoped to the current component.
+///
+/// This will automatically be removed when the component is unmounted.
+pub struct ConnectivityHandle {
+    connectivity: Connectivity,
+    /// The unique identifier of the handler.
+    pub id: ConnectivityHandlerId,
+}
+
+impl ConnectivityHandle {
+    /// Remove the handler.
+    pub fn remove(&self) {
+        self.connectivity.remove_connectivity_handler(self.id);
+    }
+}
+
+impl Drop for ConnectivityHandle {
+    fn drop(&mut self) {
+        self.remove()
+    }
+}
+
+/// Get whether the device has a network connection. The component rerenders when the status changes.
+pub fn use_online_status(cx: &ScopeState) -> bool {
+    let handle = cx.use_hook(|| {
+        let connectivity = window().connectivity();
+        let update = cx.schedule_update();
+        let id = connectivity.create_connectivity_handler(move |_| update());
+
+        ConnectivityHandle { connectivity, id }
+    });
+    handle.connectivity.is_online()
+}
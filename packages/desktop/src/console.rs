@@ -0,0 +1,191 @@
+//! Messages from the console of the webview, forwarded to `tracing`.
+//!
+//! An initialization script wraps `console.debug`, `log`, `info`, `warn` and `error`, and listens for uncaught
+//! exceptions and unhandled promise rejections. Each message is sent over IPC with the location of the code that
+//! logged it, and logged on the `dioxus_desktop::console` target with the id of the window. The original console
+//! methods still run, so the devtools show the messages too.
+
+use serde::Deserialize;
+use wry::application::window::WindowId;
+
+/// The level of a console message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleLevel {
+    /// `console.debug`, logged at the `DEBUG` level
+    Debug,
+    /// `console.log` and `console.info`, logged at the `INFO` level
+    Info,
+    /// `console.warn`, logged at the `WARN` level
+    Warn,
+    /// `console.error`, uncaught exceptions and unhandled promise rejections, logged at the `ERROR` level
+    Error,
+}
+
+impl ConsoleLevel {
+    fn name(self) -> &'static str {
+        match self {
+            ConsoleLevel::Debug => "debug",
+            ConsoleLevel::Info => "info",
+            ConsoleLevel::Warn => "warn",
+            ConsoleLevel::Error => "error",
+        }
+    }
+}
+
+/// Which messages of the console of the webview are logged with `tracing`. Set it with
+/// [`Config::with_console_bridge`](crate::Config::with_console_bridge).
+///
+/// By default every message is logged in debug builds, and warnings and errors in release builds.
+///
+/// ```rust, ignore
+/// // Only forward uncaught exceptions and console.error
+/// let bridge = ConsoleBridge::new().with_min_level(ConsoleLevel::Error);
+/// dioxus_desktop::launch_cfg(app, Config::new().with_console_bridge(bridge));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConsoleBridge {
+    min_level: Option<ConsoleLevel>,
+    uncaught_errors: bool,
+}
+
+impl Default for ConsoleBridge {
+    fn default() -> Self {
+        Self {
+            min_level: Some(if cfg!(debug_assertions) {
+                ConsoleLevel::Debug
+            } else {
+                ConsoleLevel::Warn
+            }),
+            uncaught_errors: true,
+        }
+    }
+}
+
+impl ConsoleBridge {
+    /// Log console messages at the default level, and uncaught errors
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Don't log anything from the console
+    pub fn disabled() -> Self {
+        Self {
+            min_level: None,
+            uncaught_errors: false,
+        }
+    }
+
+    /// Only log console messages at `level` and above
+    pub fn with_min_level(mut self, level: ConsoleLevel) -> Self {
+        self.min_level = Some(level);
+        self
+    }
+
+    /// Set whether uncaught exceptions and unhandled promise rejections are logged. Defaults to `true`.
+    pub fn with_uncaught_errors(mut self, uncaught_errors: bool) -> Self {
+        self.uncaught_errors = uncaught_errors;
+        self
+    }
+
+    /// The script that forwards the console to the app, or `None` if nothing is forwarded
+    pub(crate) fn script(&self) -> Option<String> {
+        if self.min_level.is_none() && !self.uncaught_errors {
+            return None;
+        }
+        let min_level = match self.min_level {
+            Some(level) => format!("\"{}\"", level.name()),
+            None => "null".to_string(),
+        };
+        Some(format!(
+            "{}\nwindow.__dioxusConsole({}, {});",
+            CONSOLE_SCRIPT, min_level, self.uncaught_errors
+        ))
+    }
+}
+
+const CONSOLE_SCRIPT: &str = r#"window.__dioxusConsole = function (minLevel, uncaughtErrors) {
+    if (window !== window.top) return;
+    const LEVELS = ["debug", "info", "warn", "error"];
+    const send = (level, message, source, stack) => {
+        try {
+            window.ipc.postMessage(JSON.stringify({ method: "console", params: { level, message, source, stack } }));
+        } catch (_) {}
+    };
+    const format = (args) => args.map((arg) => {
+        if (typeof arg === "string") return arg;
+        if (arg instanceof Error) return arg.stack || String(arg);
+        try { return JSON.stringify(arg); } catch (_) { return String(arg); }
+    }).join(" ");
+    // The frame that called the console method: the stack starts with this function and the wrapper. Chromium adds
+    // an "Error" line before the frames.
+    const caller = () => {
+        const frames = (new Error().stack || "").split("\n").filter((line) => line && line !== "Error");
+        return frames[2] ? frames[2].trim().replace(/^at /, "") : null;
+    };
+
+    if (minLevel !== null) {
+        const methods = { debug: "debug", log: "info", info: "info", warn: "warn", error: "error" };
+        for (const [method, level] of Object.entries(methods)) {
+            if (LEVELS.indexOf(level) < LEVELS.indexOf(minLevel)) continue;
+            const original = console[method];
+            console[method] = function (...args) {
+                send(level, format(args), caller(), null);
+                return original.apply(console, args);
+            };
+        }
+    }
+
+    if (uncaughtErrors) {
+        window.addEventListener("error", (event) => {
+            const source = event.filename ? `${event.filename}:${event.lineno}:${event.colno}` : null;
+            send("error", `Uncaught ${event.message}`, source, event.error && event.error.stack || null);
+        });
+        window.addEventListener("unhandledrejection", (event) => {
+            const reason = event.reason;
+            send("error", `Unhandled promise rejection: ${format([reason])}`, null, reason && reason.stack || null);
+        });
+    }
+};"#;
+
+/// A message sent by the console script
+#[derive(Deserialize)]
+struct ConsoleMessage {
+    level: ConsoleLevel,
+    message: String,
+    source: Option<String>,
+    stack: Option<String>,
+}
+
+/// Log a message from the console of `window`
+pub(crate) fn log(window: WindowId, params: serde_json::Value) {
+    let ConsoleMessage {
+        level,
+        message,
+        source,
+        stack,
+    } = match serde_json::from_value(params) {
+        Ok(message) => message,
+        Err(err) => {
+            tracing::warn!("invalid console message: {}", err);
+            return;
+        }
+    };
+    let source = source.as_deref().unwrap_or("unknown");
+    let stack = stack.as_deref().unwrap_or_default();
+
+    match level {
+        ConsoleLevel::Debug => {
+            tracing::debug!(target: "dioxus_desktop::console", ?window, source, "{}", message)
+        }
+        ConsoleLevel::Info => {
+            tracing::info!(target: "dioxus_desktop::console", ?window, source, "{}", message)
+        }
+        ConsoleLevel::Warn => {
+            tracing::warn!(target: "dioxus_desktop::console", ?window, source, "{}", message)
+        }
+        ConsoleLevel::Error => {
+            tracing::error!(target: "dioxus_desktop::console", ?window, source, stack, "{}", message)
+        }
+    }
+}
@@ -0,0 +1,372 @@
+//! Detecting when the process that renders the page of a window stops, and recovering from it.
+//!
+//! WebView2 reports it with its `ProcessFailed` event, WebKitGTK with `web-process-terminated`, and WKWebView by
+//! calling `webViewWebContentProcessDidTerminate:` on its navigation delegate. The page is gone when that happens, and
+//! the window stays white. Handlers added with [`use_crash_handler`] are told about it, and with
+//! [`Config::with_crash_recovery`](crate::Config::with_crash_recovery) the page is loaded again and the VirtualDom is
+//! mounted in it again.
+//!
+//! Only the root component keeps its state across the reload. The components below it are created again, so their
+//! hooks start over, unless they have a global key. State the page held itself, like the value of an input or the
+//! scroll position, is lost.
+//!
+//! On Linux the crash is only detected with the `webkitgtk` feature.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use dioxus_core::ScopeState;
+use wry::application::window::WindowId;
+use wry::webview::WebView;
+
+use crate::desktop_context::{EventData, ProxyType, UserWindowEvent};
+use crate::{use_window, DesktopContext};
+
+/// Why the page of a window stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrashReason {
+    /// The process that renders the page crashed
+    Crashed,
+    /// The process that renders the page used more memory than it is allowed to
+    OutOfMemory,
+    /// The process that renders the page stopped responding. Only WebView2 reports this.
+    Unresponsive,
+    /// The process that renders the page was killed by the app or the operating system
+    Terminated,
+    /// The browser process of WebView2 exited. Every window that shares it is gone, and can't be recovered.
+    BrowserExited,
+}
+
+/// The page of a window stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrashEvent {
+    /// Why the page stopped
+    pub reason: CrashReason,
+    /// If the page is loaded again because of the [`CrashRecovery`] of the window
+    pub recovering: bool,
+}
+
+/// What a window does when its page stops. Set it with
+/// [`Config::with_crash_recovery`](crate::Config::with_crash_recovery).
+///
+/// By default nothing is done, and the window stays white until the app reacts to the [`CrashEvent`].
+///
+/// ```rust, ignore
+/// // Load the page again, unless it already stopped 5 times in the last 10 minutes
+/// let recovery = CrashRecovery::reload().with_max_reloads(5, Duration::from_secs(600));
+/// dioxus_desktop::launch_cfg(app, Config::new().with_crash_recovery(recovery));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CrashRecovery {
+    max_reloads: u32,
+    period: Duration,
+}
+
+impl CrashRecovery {
+    /// Leave the window as it is
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Load the page again and render the current state of the VirtualDom, unless it already stopped 3 times in the
+    /// last minute. A page that stops while it loads would otherwise be loaded forever.
+    pub fn reload() -> Self {
+        Self {
+            max_reloads: 3,
+            period: Duration::from_secs(60),
+        }
+    }
+
+    /// Load the page again at most `max_reloads` times in `period`
+    pub fn with_max_reloads(mut self, max_reloads: u32, period: Duration) -> Self {
+        self.max_reloads = max_reloads;
+        self.period = period;
+        self
+    }
+}
+
+/// The reloads of a window, to check them against its [`CrashRecovery`]
+#[derive(Debug, Default)]
+pub(crate) struct CrashRecoveryState {
+    policy: CrashRecovery,
+    reloads: VecDeque<Instant>,
+}
+
+impl CrashRecoveryState {
+    pub(crate) fn new(policy: CrashRecovery) -> Self {
+        Self {
+            policy,
+            reloads: VecDeque::new(),
+        }
+    }
+
+    /// Check if the page should be loaded again after it stopped for `reason`, and count the reload if it is
+    pub(crate) fn should_reload(&mut self, reason: CrashReason) -> bool {
+        if reason == CrashReason::BrowserExited || self.policy.max_reloads == 0 {
+            return false;
+        }
+        let now = Instant::now();
+        while let Some(&reload) = self.reloads.front() {
+            if now.duration_since(reload) < self.policy.period {
+                break;
+            }
+            self.reloads.pop_front();
+        }
+        if self.reloads.len() >= self.policy.max_reloads as usize {
+            return false;
+        }
+        self.reloads.push_back(now);
+        true
+    }
+}
+
+/// Load the page of a window that stopped again
+pub(crate) fn reload(webview: &WebView) {
+    let url = webview.url();
+    webview.load_url(url.as_str());
+}
+
+/// Send a [`EventData::WebviewCrashed`] event to the event loop when the page of `webview` stops
+pub(crate) fn watch(webview: &WebView, proxy: ProxyType, window_id: WindowId) {
+    platform::watch(webview, proxy, window_id);
+}
+
+fn send(proxy: &ProxyType, window_id: WindowId, reason: CrashReason) {
+    _ = proxy.send_event(UserWindowEvent(
+        EventData::WebviewCrashed(reason),
+        window_id,
+    ));
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        COREWEBVIEW2_PROCESS_FAILED_KIND, COREWEBVIEW2_PROCESS_FAILED_KIND_BROWSER_PROCESS_EXITED,
+        COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED,
+        COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_UNRESPONSIVE,
+    };
+    use webview2_com::ProcessFailedEventHandler;
+    use windows::Win32::System::WinRT::EventRegistrationToken;
+    use wry::webview::WebviewExtWindows;
+
+    pub(super) fn watch(webview: &WebView, proxy: ProxyType, window_id: WindowId) {
+        let handler = ProcessFailedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else {
+                return Ok(());
+            };
+            let mut kind = COREWEBVIEW2_PROCESS_FAILED_KIND::default();
+            unsafe { args.ProcessFailedKind(&mut kind)? };
+            // The other processes, like the GPU process, are restarted by WebView2 without losing the page
+            let reason = match kind {
+                COREWEBVIEW2_PROCESS_FAILED_KIND_BROWSER_PROCESS_EXITED => {
+                    CrashReason::BrowserExited
+                }
+                COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED => CrashReason::Crashed,
+                COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_UNRESPONSIVE => {
+                    CrashReason::Unresponsive
+                }
+                _ => return Ok(()),
+            };
+            send(&proxy, window_id, reason);
+            Ok(())
+        }));
+
+        let mut token = EventRegistrationToken::default();
+        let result = unsafe {
+            webview
+                .controller()
+                .CoreWebView2()
+                .and_then(|core| core.add_ProcessFailed(&handler, &mut token))
+        };
+        if let Err(err) = result {
+            tracing::warn!("Failed to watch the webview for crashes: {}", err.message());
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "webkitgtk"))]
+mod platform {
+    use super::*;
+    use webkit2gtk::{WebProcessTerminationReason, WebViewExt};
+    use wry::webview::WebviewExtUnix;
+
+    pub(super) fn watch(webview: &WebView, proxy: ProxyType, window_id: WindowId) {
+        webview
+            .webview()
+            .connect_web_process_terminated(move |_, reason| {
+                let reason = match reason {
+                    WebProcessTerminationReason::ExceededMemoryLimit => CrashReason::OutOfMemory,
+                    WebProcessTerminationReason::TerminatedByApi => CrashReason::Terminated,
+                    _ => CrashReason::Crashed,
+                };
+                send(&proxy, window_id, reason);
+            });
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use objc::runtime::{class_addMethod, Class, Imp, Object, Sel};
+    use objc::{msg_send, sel, sel_impl};
+    use std::sync::Mutex;
+    use wry::webview::WebviewExtMacOS;
+
+    /// The windows of the webviews that are watched, by the address of their `WKWebView`
+    static WATCHED: Mutex<Vec<(usize, ProxyType, WindowId)>> = Mutex::new(Vec::new());
+
+    pub(super) fn watch(webview: &WebView, proxy: ProxyType, window_id: WindowId) {
+        let webview: *mut Object = webview.webview();
+        {
+            let mut watched = WATCHED.lock().unwrap();
+            watched.retain(|(address, ..)| *address != webview as usize);
+            watched.push((webview as usize, proxy, window_id));
+        }
+
+        unsafe {
+            // wry's navigation delegate doesn't implement the method, so it is added to its class. Adding it again
+            // for the next window does nothing.
+            let delegate: *mut Object = msg_send![webview, navigationDelegate];
+            if delegate.is_null() {
+                tracing::warn!(
+                    "Failed to watch the webview for crashes: it has no navigation delegate"
+                );
+                return;
+            }
+            let class = (*delegate).class() as *const Class as *mut Class;
+            let imp: Imp =
+                std::mem::transmute(did_terminate as extern "C" fn(&Object, Sel, *mut Object));
+            class_addMethod(
+                class,
+                sel!(webViewWebContentProcessDidTerminate:),
+                imp,
+                b"v@:@\0".as_ptr() as *const _,
+            );
+        }
+    }
+
+    extern "C" fn did_terminate(_: &Object, _: Sel, webview: *mut Object) {
+        let watched = WATCHED.lock().unwrap();
+        if let Some((_, proxy, window_id)) = watched
+            .iter()
+            .find(|(address, ..)| *address == webview as usize)
+        {
+            // WKWebView doesn't say why the process stopped
+            send(proxy, *window_id, CrashReason::Crashed);
+        }
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(target_os = "linux", feature = "webkitgtk"),
+    target_os = "macos"
+)))]
+mod platform {
+    use super::*;
+
+    pub(super) fn watch(_: &WebView, _: ProxyType, _: WindowId) {}
+}
+
+pub(crate) type CrashCallback = Rc<RefCell<dyn FnMut(&CrashEvent)>>;
+
+/// The unique identifier of a crash handler. This can be used to later remove the handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CrashHandlerId(pub(crate) usize);
+
+/// A crash handler that is scoped to the current component.
+///
+/// This will automatically be removed when the component is unmounted.
+pub struct CrashHandle {
+    desktop: DesktopContext,
+    /// The unique identifier of the handler.
+    pub id: CrashHandlerId,
+}
+
+impl CrashHandle {
+    /// Remove the handler.
+    pub fn remove(&self) {
+        self.desktop.remove_crash_handler(self.id);
+    }
+}
+
+impl Drop for CrashHandle {
+    fn drop(&mut self) {
+        self.remove()
+    }
+}
+
+/// Call `handler` every time the page of the current window stops.
+///
+/// Components of a window whose page isn't [recovering](CrashEvent::recovering) can't be seen anymore, so the
+/// handler is the place to report the crash, and to close or recreate the window.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let window = use_window(cx);
+///     use_crash_handler(cx, {
+///         to_owned![window];
+///         move |event| {
+///             tracing::error!("the page stopped: {:?}", event.reason);
+///             if !event.recovering {
+///                 window.close();
+///             }
+///         }
+///     });
+///
+///     render! { "Hello" }
+/// }
+/// ```
+pub fn use_crash_handler(
+    cx: &ScopeState,
+    handler: impl FnMut(&CrashEvent) + 'static,
+) -> &CrashHandle {
+    let desktop = use_window(cx);
+    cx.use_hook(move || {
+        let desktop = desktop.clone();
+        let id = desktop.create_crash_handler(handler);
+        CrashHandle { desktop, id }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn browser_exits_are_not_recovered() {
+        let mut state = CrashRecoveryState::new(CrashRecovery::reload());
+        assert!(!state.should_reload(CrashReason::BrowserExited));
+        assert!(state.should_reload(CrashReason::Crashed));
+    }
+
+    #[test]
+    fn none_never_reloads() {
+        let mut state = CrashRecoveryState::new(CrashRecovery::none());
+        assert!(!state.should_reload(CrashReason::Crashed));
+        assert!(!state.should_reload(CrashReason::OutOfMemory));
+    }
+
+    #[test]
+    fn reloads_are_limited_in_the_period() {
+        let policy = CrashRecovery::reload().with_max_reloads(2, Duration::from_secs(600));
+        let mut state = CrashRecoveryState::new(policy);
+        assert!(state.should_reload(CrashReason::Crashed));
+        assert!(state.should_reload(CrashReason::Terminated));
+        assert!(!state.should_reload(CrashReason::Crashed));
+        assert!(!state.should_reload(CrashReason::Crashed));
+    }
+
+    #[test]
+    fn old_reloads_expire() {
+        let policy = CrashRecovery::reload().with_max_reloads(1, Duration::from_millis(20));
+        let mut state = CrashRecoveryState::new(policy);
+        assert!(state.should_reload(CrashReason::Crashed));
+        assert!(!state.should_reload(CrashReason::Crashed));
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(state.should_reload(CrashReason::Crashed));
+    }
+}
@@ -0,0 +1,132 @@
+//! The cursor and the tooltips shown over the page.
+//!
+//! The `cursor` attribute of an element is a CSS property, and the webview already draws it with the cursors of the
+//! operating system. [`DesktopService::set_cursor`](crate::DesktopService::set_cursor) overrides it for the whole
+//! window, for example while the app is busy or while something is dragged.
+//!
+//! The `native_tooltip` attribute shows its text in a tooltip of the operating system when the pointer rests on the
+//! element, instead of a tooltip drawn by the page:
+//!
+//! ```rust, ignore
+//! rsx! {
+//!     button { native_tooltip: "Save the document", "Save" }
+//! }
+//! ```
+
+use wry::application::window::CursorIcon;
+use wry::webview::WebView;
+
+/// Defines `window.__dioxusPointer` in the page, and reports the tooltip of the hovered element
+pub(crate) const POINTER_SCRIPT: &str = r#"(function () {
+    if (window !== window.top) return;
+    let style = null;
+    let hovered = null;
+    let tooltip = null;
+
+    window.__dioxusPointer = {
+        setCursor(cursor) {
+            if (cursor === null) {
+                if (style) style.remove();
+                style = null;
+                return;
+            }
+            if (!style) style = document.createElement("style");
+            style.textContent = `*, *::before, *::after { cursor: ${cursor} !important; }`;
+            (document.head || document.documentElement).appendChild(style);
+        },
+        // The platform has no native tooltip, so let the webview show the tooltip of the title attribute
+        useTitle() {
+            if (hovered && tooltip !== null && !hovered.hasAttribute("title")) {
+                hovered.setAttribute("title", tooltip);
+            }
+        },
+    };
+
+    document.addEventListener(
+        "mouseover",
+        (event) => {
+            const element =
+                event.target instanceof Element ? event.target.closest("[data-native-tooltip]") : null;
+            const text = element ? element.getAttribute("data-native-tooltip") : null;
+            if (element === hovered && text === tooltip) return;
+            hovered = element;
+            tooltip = text;
+            window.ipc.postMessage(JSON.stringify({ method: "native_tooltip", params: text }));
+        },
+        true
+    );
+})();"#;
+
+/// The CSS name of `cursor`, or `None` for the default cursor which gives control back to the page
+pub(crate) fn css_cursor(cursor: CursorIcon) -> Option<&'static str> {
+    Some(match cursor {
+        CursorIcon::Default => return None,
+        CursorIcon::Crosshair => "crosshair",
+        CursorIcon::Hand => "pointer",
+        CursorIcon::Arrow => "default",
+        CursorIcon::Move => "move",
+        CursorIcon::Text => "text",
+        CursorIcon::Wait => "wait",
+        CursorIcon::Help => "help",
+        CursorIcon::Progress => "progress",
+        CursorIcon::NotAllowed => "not-allowed",
+        CursorIcon::ContextMenu => "context-menu",
+        CursorIcon::Cell => "cell",
+        CursorIcon::VerticalText => "vertical-text",
+        CursorIcon::Alias => "alias",
+        CursorIcon::Copy => "copy",
+        CursorIcon::NoDrop => "no-drop",
+        CursorIcon::Grab => "grab",
+        CursorIcon::Grabbing => "grabbing",
+        CursorIcon::AllScroll => "all-scroll",
+        CursorIcon::ZoomIn => "zoom-in",
+        CursorIcon::ZoomOut => "zoom-out",
+        CursorIcon::EResize => "e-resize",
+        CursorIcon::NResize => "n-resize",
+        CursorIcon::NeResize => "ne-resize",
+        CursorIcon::NwResize => "nw-resize",
+        CursorIcon::SResize => "s-resize",
+        CursorIcon::SeResize => "se-resize",
+        CursorIcon::SwResize => "sw-resize",
+        CursorIcon::WResize => "w-resize",
+        CursorIcon::EwResize => "ew-resize",
+        CursorIcon::NsResize => "ns-resize",
+        CursorIcon::NeswResize => "nesw-resize",
+        CursorIcon::NwseResize => "nwse-resize",
+        CursorIcon::ColResize => "col-resize",
+        CursorIcon::RowResize => "row-resize",
+    })
+}
+
+/// Show `tooltip` with the tooltip of the operating system, or hide it. Returns false if the platform has no native
+/// tooltip for the webview.
+#[cfg(target_os = "macos")]
+pub(crate) fn set_native_tooltip(webview: &WebView, tooltip: Option<&str>) -> bool {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::CString;
+    use wry::webview::WebviewExtMacOS;
+
+    let tooltip = match tooltip.map(CString::new) {
+        Some(Ok(tooltip)) => Some(tooltip),
+        Some(Err(_)) => return false,
+        None => None,
+    };
+
+    unsafe {
+        let text: *mut Object = match &tooltip {
+            Some(tooltip) => msg_send![class!(NSString), stringWithUTF8String: tooltip.as_ptr()],
+            None => std::ptr::null_mut(),
+        };
+        let _: () = msg_send![webview.webview(), setToolTip: text];
+    }
+    true
+}
+
+/// Show `tooltip` with the tooltip of the operating system, or hide it. Returns false if the platform has no native
+/// tooltip for the webview.
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn set_native_tooltip(_webview: &WebView, _tooltip: Option<&str>) -> bool {
+    // WebView2 and WebKitGTK draw the tooltip of the title attribute with the native tooltip control
+    false
+}
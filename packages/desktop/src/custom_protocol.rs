@@ -0,0 +1,106 @@
+//! Custom protocols that can be added and removed while the app runs.
+//!
+//! A webview is given its schemes when it is built, and can't learn new ones later. Every window registers one
+//! dispatching scheme, [`DISPATCH_SCHEME`], and serves `dioxus-protocol://localhost/<name>/<path>` with the protocol
+//! registered under `name`, so plugins loaded at runtime can still serve their own content. The protocols from
+//! [`Config::with_custom_protocol`](crate::Config::with_custom_protocol) keep their own scheme, and look their handler
+//! up in the same registry so they can be replaced or removed too.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use wry::http::{status::StatusCode, Request, Response, Uri};
+
+/// The scheme every window serves the protocols registered at runtime under
+pub(crate) const DISPATCH_SCHEME: &str = "dioxus-protocol";
+
+pub(crate) type ProtocolHandler = Rc<dyn Fn(Request<Vec<u8>>) -> Response<Cow<'static, [u8]>>>;
+
+/// The custom protocols of a window
+#[derive(Clone, Default)]
+pub(crate) struct ProtocolRegistry {
+    handlers: Rc<RefCell<FxHashMap<String, ProtocolHandler>>>,
+    /// The protocols the webview was built with a scheme for
+    native: Rc<RefCell<FxHashSet<String>>>,
+}
+
+impl ProtocolRegistry {
+    /// Add a protocol the webview has its own scheme for
+    pub(crate) fn insert_native(&self, name: String, handler: ProtocolHandler) {
+        self.native.borrow_mut().insert(name.clone());
+        self.insert(name, handler);
+    }
+
+    /// Add or replace the protocol called `name`. Returns true if a protocol was replaced.
+    pub(crate) fn insert(&self, name: String, handler: ProtocolHandler) -> bool {
+        self.handlers.borrow_mut().insert(name, handler).is_some()
+    }
+
+    /// Remove the protocol called `name`. Returns true if there was one.
+    pub(crate) fn remove(&self, name: &str) -> bool {
+        self.handlers.borrow_mut().remove(name).is_some()
+    }
+
+    /// The URL the root of the protocol called `name` is served at
+    pub(crate) fn url(&self, name: &str) -> String {
+        if self.native.borrow().contains(name) {
+            scheme_root(name)
+        } else {
+            format!("{}{name}/", scheme_root(DISPATCH_SCHEME))
+        }
+    }
+
+    /// Respond to a request for the protocol called `name`
+    pub(crate) fn handle(
+        &self,
+        name: &str,
+        request: Request<Vec<u8>>,
+    ) -> Response<Cow<'static, [u8]>> {
+        // The handler is cloned out of the registry so it can register or remove protocols itself
+        let handler = self.handlers.borrow().get(name).cloned();
+        match handler {
+            Some(handler) => handler(request),
+            None => not_found(),
+        }
+    }
+
+    /// Respond to a request for the dispatching scheme. The first segment of the path names the protocol, which is
+    /// given the request as if it was made to its own scheme.
+    pub(crate) fn dispatch(&self, mut request: Request<Vec<u8>>) -> Response<Cow<'static, [u8]>> {
+        let uri = request.uri().clone();
+        let path = uri.path().trim_start_matches('/');
+        let (name, rest) = path.split_once('/').unwrap_or((path, ""));
+        if name.is_empty() {
+            return not_found();
+        }
+
+        let query = uri
+            .query()
+            .map(|query| format!("?{query}"))
+            .unwrap_or_default();
+        match format!("{name}://localhost/{rest}{query}").parse::<Uri>() {
+            Ok(rewritten) => *request.uri_mut() = rewritten,
+            Err(err) => tracing::warn!("Failed to rewrite the URL of a {name} request: {err}"),
+        }
+        self.handle(name, request)
+    }
+}
+
+/// The root of a custom scheme, as the webview loads it
+fn scheme_root(scheme: &str) -> String {
+    // WebView2 only loads http(s) URLs, so wry serves custom schemes from a subdomain of localhost
+    if cfg!(target_os = "windows") {
+        format!("https://{scheme}.localhost/")
+    } else {
+        format!("{scheme}://localhost/")
+    }
+}
+
+fn not_found() -> Response<Cow<'static, [u8]>> {
+    Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .body(Cow::from(Vec::new()))
+        .unwrap()
+}
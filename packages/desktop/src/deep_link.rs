@@ -0,0 +1,585 @@
+//! Custom url schemes like `myapp://`, which open the app from a browser or another app.
+//!
+//! The schemes are registered with [`Config::with_url_scheme`](crate::Config::with_url_scheme). Every url the app is
+//! opened with is passed to the handlers added with [`use_deep_link`], which makes them a good fit for the redirect of
+//! an OAuth login:
+//!
+//! ```rust, ignore
+//! fn app(cx: Scope) -> Element {
+//!     let token = use_state(cx, || None);
+//!
+//!     use_deep_link(cx, {
+//!         to_owned![token];
+//!         move |url| {
+//!             if let Some((_, code)) = url.query_pairs().find(|(key, _)| key == "code") {
+//!                 token.set(Some(code.into_owned()));
+//!             }
+//!         }
+//!     });
+//!
+//!     render! { "Logged in: {token.is_some()}" }
+//! }
+//! ```
+//!
+//! How the operating system opens the app differs by platform:
+//!
+//! - On Windows and Linux the scheme is registered for the current user when the app starts, and the operating system
+//!   starts a new process with the url as its argument. If the app is already running, the new process passes the url
+//!   to it and exits. The url is passed over a unix socket in the runtime directory of the user, or a named pipe on
+//!   Windows, and only processes of the same user are accepted on both ends. If the running app doesn't confirm that
+//!   it took the url, the new process opens it itself.
+//! - On macOS and iOS the scheme must be declared in the `CFBundleURLTypes` of the `Info.plist` of the bundle. The
+//!   running app receives the url, so only one process is ever started.
+//!
+//! Urls that start the app are delivered once the first window has a handler.
+
+use std::cell::RefCell;
+use std::io::{self, BufRead, BufReader, Write};
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use dioxus_core::ScopeState;
+use url::Url;
+use wry::application::window::WindowId;
+
+use crate::desktop_context::{EventData, ProxyType, UserWindowEvent};
+use crate::{use_window, DesktopContext};
+
+pub(crate) type DeepLinkCallback = Rc<RefCell<dyn FnMut(&Url)>>;
+
+/// The unique identifier of a deep link handler. This can be used to later remove the handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeepLinkHandlerId(pub(crate) usize);
+
+/// A deep link handler that is scoped to the current component.
+///
+/// This will automatically be removed when the component is unmounted.
+pub struct DeepLinkHandle {
+    desktop: DesktopContext,
+    /// The unique identifier of the handler.
+    pub id: DeepLinkHandlerId,
+}
+
+impl DeepLinkHandle {
+    /// Remove the handler.
+    pub fn remove(&self) {
+        self.desktop.remove_deep_link_handler(self.id);
+    }
+}
+
+impl Drop for DeepLinkHandle {
+    fn drop(&mut self) {
+        self.remove()
+    }
+}
+
+/// Call `handler` with every url of a [registered scheme](crate::Config::with_url_scheme) the app is opened with.
+///
+/// See the [module documentation](crate::deep_link) for an example.
+pub fn use_deep_link(cx: &ScopeState, handler: impl FnMut(&Url) + 'static) -> &DeepLinkHandle {
+    let desktop = use_window(cx);
+    cx.use_hook(move || {
+        let desktop = desktop.clone();
+        let id = desktop.create_deep_link_handler(handler);
+        DeepLinkHandle { desktop, id }
+    })
+}
+
+/// The urls of `schemes` the process was started with
+pub(crate) fn launch_urls(schemes: &[String]) -> Vec<Url> {
+    urls_in(std::env::args().skip(1), schemes)
+}
+
+/// The arguments that are urls of `schemes`
+fn urls_in(args: impl IntoIterator<Item = String>, schemes: &[String]) -> Vec<Url> {
+    args.into_iter()
+        .filter_map(|arg| Url::parse(&arg).ok())
+        .filter(|url| schemes.iter().any(|scheme| scheme == url.scheme()))
+        .collect()
+}
+
+/// The name of the socket or pipe the running instance listens on
+fn handoff_name(schemes: &[String]) -> String {
+    format!("{}.deep-link", schemes.join("+"))
+}
+
+/// Pass `urls` to an instance of the app that is already running. Returns `false` if no instance took them, and the
+/// process has to handle them itself.
+pub(crate) fn forward_to_running_instance(schemes: &[String], urls: &[Url]) -> bool {
+    let mut stream = match handoff::connect(&handoff_name(schemes)) {
+        Ok(stream) => stream,
+        Err(err) => {
+            if err.kind() == io::ErrorKind::PermissionDenied {
+                tracing::warn!("Not forwarding deep links: {}", err);
+            }
+            return false;
+        }
+    };
+
+    match forward(&mut stream, urls) {
+        Ok(()) => true,
+        Err(err) => {
+            tracing::warn!("Failed to forward deep links to the running app: {}", err);
+            false
+        }
+    }
+}
+
+fn forward(stream: &mut handoff::Stream, urls: &[Url]) -> io::Result<()> {
+    for url in urls {
+        writeln!(stream, "{}", url)?;
+    }
+    // An empty line ends the urls, and the running instance confirms it took them
+    writeln!(stream)?;
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    if reply.trim() == "ok" {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "the running app did not confirm the urls",
+        ))
+    }
+}
+
+/// Listen for the urls that later processes of the app forward, and send them to the event loop
+pub(crate) fn listen(schemes: Vec<String>, proxy: ProxyType) -> io::Result<()> {
+    let listener = handoff::listen(&handoff_name(&schemes))?;
+
+    std::thread::spawn(move || loop {
+        match listener.accept() {
+            Ok(stream) => receive(stream, &schemes, &proxy),
+            Err(err) if err.kind() == io::ErrorKind::PermissionDenied => {
+                tracing::warn!("Rejected a deep link handoff: {}", err);
+            }
+            Err(err) => {
+                tracing::error!("Stopped listening for deep links: {}", err);
+                return;
+            }
+        }
+    });
+    Ok(())
+}
+
+fn receive(mut stream: handoff::Stream, schemes: &[String], proxy: &ProxyType) {
+    let Ok(urls) = take_urls(&mut stream, schemes) else {
+        return;
+    };
+    for url in urls {
+        _ = proxy.send_event(UserWindowEvent(EventData::DeepLink(url), unsafe {
+            WindowId::dummy()
+        }));
+    }
+}
+
+/// Read the urls another process forwards, and confirm that they were taken
+fn take_urls(stream: &mut handoff::Stream, schemes: &[String]) -> io::Result<Vec<Url>> {
+    let mut urls = Vec::new();
+    for line in BufReader::new(&mut *stream).lines() {
+        let line = line?;
+        if line.is_empty() {
+            break;
+        }
+        // Only urls of the registered schemes are passed on
+        match Url::parse(&line) {
+            Ok(url) if schemes.iter().any(|scheme| scheme == url.scheme()) => urls.push(url),
+            _ => tracing::warn!("Ignoring a forwarded deep link that is not a url of the app"),
+        }
+    }
+
+    writeln!(stream, "ok")?;
+    // Unread data is lost when a pipe is closed, so wait until the other process read the reply
+    #[cfg(windows)]
+    stream.sync_all()?;
+    Ok(urls)
+}
+
+/// A unix socket in a directory only the current user can access
+#[cfg(unix)]
+mod handoff {
+    use std::io;
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    pub(super) type Stream = UnixStream;
+
+    pub(super) struct Listener(UnixListener);
+
+    impl Listener {
+        /// Accept the next connection of a process of the current user
+        pub(super) fn accept(&self) -> io::Result<UnixStream> {
+            let (stream, _) = self.0.accept()?;
+            verify_peer(&stream)?;
+            Ok(stream)
+        }
+    }
+
+    pub(super) fn connect(name: &str) -> io::Result<UnixStream> {
+        let stream = UnixStream::connect(socket_path(name)?)?;
+        verify_peer(&stream)?;
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        Ok(stream)
+    }
+
+    pub(super) fn listen(name: &str) -> io::Result<Listener> {
+        let path = socket_path(name)?;
+        let listener = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(err) if err.kind() == io::ErrorKind::AddrInUse => {
+                // Another instance is running and listens already
+                if UnixStream::connect(&path).is_ok() {
+                    return Err(err);
+                }
+                // The socket was left behind by an instance that crashed
+                std::fs::remove_file(&path)?;
+                UnixListener::bind(&path)?
+            }
+            Err(err) => return Err(err),
+        };
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        Ok(Listener(listener))
+    }
+
+    pub(super) fn socket_path(name: &str) -> io::Result<PathBuf> {
+        Ok(runtime_dir()?.join(format!("{}.sock", name)))
+    }
+
+    /// The runtime directory of the current user, or a directory in the temp dir that only the user can access
+    fn runtime_dir() -> io::Result<PathBuf> {
+        let uid = unsafe { libc::getuid() };
+        let dir = match std::env::var_os("XDG_RUNTIME_DIR") {
+            Some(dir) => PathBuf::from(dir),
+            None => {
+                let dir = std::env::temp_dir().join(format!("dioxus-{}", uid));
+                match std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+                    Err(err) => return Err(err),
+                }
+                dir
+            }
+        };
+
+        // Someone else could have created the directory first
+        let metadata = std::fs::symlink_metadata(&dir)?;
+        if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} can be accessed by other users", dir.display()),
+            ));
+        }
+        Ok(dir)
+    }
+
+    fn verify_peer(stream: &UnixStream) -> io::Result<()> {
+        if peer_uid(stream)? == unsafe { libc::getuid() } {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "the process on the other end of the socket belongs to another user",
+            ))
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn peer_uid(stream: &UnixStream) -> io::Result<libc::uid_t> {
+        let mut cred: libc::ucred = unsafe { std::mem::zeroed() };
+        let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+        let result = unsafe {
+            libc::getsockopt(
+                stream.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_PEERCRED,
+                &mut cred as *mut libc::ucred as *mut libc::c_void,
+                &mut len,
+            )
+        };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(cred.uid)
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    fn peer_uid(stream: &UnixStream) -> io::Result<libc::uid_t> {
+        let mut uid = 0;
+        let mut gid = 0;
+        if unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(uid)
+    }
+}
+
+/// A named pipe that only accepts processes of the current session.
+///
+/// The default security descriptor of a pipe only lets its owner, administrators and the system write to it.
+#[cfg(windows)]
+mod handoff {
+    use std::cell::Cell;
+    use std::fs::File;
+    use std::io;
+    use std::os::windows::io::{AsRawHandle, FromRawHandle};
+
+    use windows_sys::Win32::Foundation::{
+        GetLastError, ERROR_PIPE_CONNECTED, HANDLE, INVALID_HANDLE_VALUE,
+    };
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX,
+    };
+    use windows_sys::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, GetNamedPipeClientProcessId,
+        GetNamedPipeServerProcessId, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+        PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+    use windows_sys::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+    use windows_sys::Win32::System::Threading::GetCurrentProcessId;
+
+    pub(super) type Stream = File;
+
+    pub(super) struct Listener {
+        name: Vec<u16>,
+        // The first instance of the pipe is created up front, so a running instance is detected right away
+        first: Cell<Option<HANDLE>>,
+    }
+
+    impl Listener {
+        /// Accept the next connection of a process of the current session
+        pub(super) fn accept(&self) -> io::Result<File> {
+            let handle = match self.first.take() {
+                Some(handle) => handle,
+                None => create(&self.name, false)?,
+            };
+            // Closes the instance of the pipe when it is dropped
+            let pipe = unsafe { File::from_raw_handle(handle as _) };
+            if unsafe { ConnectNamedPipe(handle, std::ptr::null_mut()) } == 0
+                && unsafe { GetLastError() } != ERROR_PIPE_CONNECTED
+            {
+                return Err(io::Error::last_os_error());
+            }
+
+            let mut client = 0;
+            if unsafe { GetNamedPipeClientProcessId(handle, &mut client) } == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            verify_peer(client)?;
+            Ok(pipe)
+        }
+    }
+
+    pub(super) fn connect(name: &str) -> io::Result<File> {
+        let pipe = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(pipe_name(name))?;
+
+        let mut server = 0;
+        if unsafe { GetNamedPipeServerProcessId(pipe.as_raw_handle() as HANDLE, &mut server) } == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        verify_peer(server)?;
+        Ok(pipe)
+    }
+
+    pub(super) fn listen(name: &str) -> io::Result<Listener> {
+        let name: Vec<u16> = pipe_name(name)
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        // Fails if another process owns the pipe already
+        let first = create(&name, true)?;
+        Ok(Listener {
+            name,
+            first: Cell::new(Some(first)),
+        })
+    }
+
+    /// Pipes are shared by every user of the machine, so the name includes the user
+    fn pipe_name(name: &str) -> String {
+        format!(
+            r"\\.\pipe\{}-{}",
+            name,
+            std::env::var("USERNAME").unwrap_or_default()
+        )
+    }
+
+    fn create(name: &[u16], first: bool) -> io::Result<HANDLE> {
+        let mut open_mode = PIPE_ACCESS_DUPLEX;
+        if first {
+            open_mode |= FILE_FLAG_FIRST_PIPE_INSTANCE;
+        }
+        let handle = unsafe {
+            CreateNamedPipeW(
+                name.as_ptr(),
+                open_mode,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                4096,
+                4096,
+                0,
+                std::ptr::null(),
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(handle)
+    }
+
+    fn session_of(process_id: u32) -> io::Result<u32> {
+        let mut session = 0;
+        if unsafe { ProcessIdToSessionId(process_id, &mut session) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(session)
+    }
+
+    fn verify_peer(process_id: u32) -> io::Result<()> {
+        if session_of(process_id)? == session_of(unsafe { GetCurrentProcessId() })? {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "the process on the other end of the pipe runs in another session",
+            ))
+        }
+    }
+}
+
+/// Register the app as the handler of `scheme` for the current user
+#[cfg(target_os = "windows")]
+pub(crate) fn register(scheme: &str) -> std::io::Result<()> {
+    use windows_sys::Win32::System::Registry::{RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ};
+
+    fn wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    fn set(key: &str, name: Option<&str>, value: &str) -> std::io::Result<()> {
+        let key = wide(key);
+        let name = name.map(wide);
+        let value = wide(value);
+        let status = unsafe {
+            RegSetKeyValueW(
+                HKEY_CURRENT_USER,
+                key.as_ptr(),
+                name.as_ref().map_or(std::ptr::null(), |name| name.as_ptr()),
+                REG_SZ,
+                value.as_ptr().cast(),
+                (value.len() * 2) as u32,
+            )
+        };
+        match status {
+            0 => Ok(()),
+            status => Err(std::io::Error::from_raw_os_error(status as i32)),
+        }
+    }
+
+    let exe = std::env::current_exe()?;
+    let key = format!("Software\\Classes\\{}", scheme);
+    set(&key, None, &format!("URL:{}", scheme))?;
+    set(&key, Some("URL Protocol"), "")?;
+    set(
+        &format!("{}\\shell\\open\\command", key),
+        None,
+        &format!("\"{}\" \"%1\"", exe.display()),
+    )
+}
+
+/// Register the app as the handler of `scheme` for the current user
+#[cfg(target_os = "linux")]
+pub(crate) fn register(scheme: &str) -> std::io::Result<()> {
+    let applications = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "No home directory"))?
+        .join("applications");
+    std::fs::create_dir_all(&applications)?;
+
+    let exe = std::env::current_exe()?;
+    let name = format!("{}-url-handler.desktop", scheme);
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\" %u\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+        exe.file_stem().unwrap_or_default().to_string_lossy(),
+        exe.display(),
+        scheme
+    );
+    std::fs::write(applications.join(&name), entry)?;
+
+    let status = std::process::Command::new("xdg-mime")
+        .arg("default")
+        .arg(&name)
+        .arg(format!("x-scheme-handler/{}", scheme))
+        .status()?;
+    if !status.success() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "xdg-mime failed to set the handler of the scheme",
+        ));
+    }
+    Ok(())
+}
+
+/// The scheme is declared in the `Info.plist` of the bundle on macOS and iOS
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub(crate) fn register(_scheme: &str) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn launch_urls_only_include_registered_schemes() {
+        let schemes = vec!["myapp".to_string()];
+        let args = [
+            "--verbose",
+            "myapp://login?code=1",
+            "https://example.com",
+            "not a url",
+            "myapp:other",
+        ];
+        let urls = urls_in(args.iter().map(|arg| arg.to_string()), &schemes);
+        assert_eq!(
+            urls,
+            vec![
+                Url::parse("myapp://login?code=1").unwrap(),
+                Url::parse("myapp:other").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_is_forwarded_without_a_running_instance() {
+        let schemes = vec![format!("dioxus-test-none-{}", std::process::id())];
+        let url = Url::parse(&format!("{}://login", schemes[0])).unwrap();
+        assert!(!forward_to_running_instance(&schemes, &[url]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn urls_are_handed_off_to_the_running_instance() {
+        let schemes = vec![format!("dioxus-test-{}", std::process::id())];
+        let name = handoff_name(&schemes);
+        let listener = handoff::listen(&name).unwrap();
+        let running = std::thread::spawn({
+            let schemes = schemes.clone();
+            move || take_urls(&mut listener.accept().unwrap(), &schemes).unwrap()
+        });
+
+        let url = Url::parse(&format!("{}://login?code=1", schemes[0])).unwrap();
+        let other = Url::parse("https://example.com").unwrap();
+        assert!(forward_to_running_instance(&schemes, &[url.clone(), other]));
+        assert_eq!(running.join().unwrap(), vec![url]);
+
+        std::fs::remove_file(handoff::socket_path(&name).unwrap()).unwrap();
+    }
+}
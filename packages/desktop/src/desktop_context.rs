@@ -1,13 +1,57 @@
+use crate::autofill::{AutofillError, AutofillSettings};
+use crate::capture::{CaptureError, PageCapture};
+#[cfg(all(
+    feature = "clipboard",
+    not(any(target_os = "ios", target_os = "android"))
+))]
+use crate::clipboard::Clipboard;
+use crate::close_handler::{CloseBehaviour, CloseHandlerId};
+use crate::command_palette::{CommandId, CommandRegistry};
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+use crate::connectivity::Connectivity;
+use crate::crash::{CrashCallback, CrashEvent, CrashHandlerId, CrashReason, CrashRecoveryState};
 use crate::create_new_window;
+use crate::custom_protocol::ProtocolRegistry;
+use crate::deep_link::{DeepLinkCallback, DeepLinkHandlerId};
+use crate::download::{DownloadCallback, DownloadEvent, DownloadHandlerId};
+use crate::event_listener::{EventListenerId, GlobalEvent, ListenerTarget};
+use crate::event_loop_mode::EventLoopMode;
 use crate::events::IpcMessage;
-use crate::protocol::AssetFuture;
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+use crate::file_dialog::FileDialogOptions;
+use crate::find::{FindInPage, FindOptions, FindResult};
+use crate::inspector::Inspector;
+use crate::invoke::InvokeRegistry;
+use crate::ipc_channel::{IpcReceiver, IpcSender};
+#[cfg(all(
+    feature = "media-session",
+    not(any(target_os = "ios", target_os = "android"))
+))]
+use crate::media_session::{MediaSession, MediaSessionError};
+use crate::menu::{AppMenu, MenuHandlerId};
+use crate::metrics::{MetricKind, Metrics};
+#[cfg(all(
+    feature = "notifications",
+    not(any(target_os = "ios", target_os = "android"))
+))]
+use crate::notification::{NotificationBuilder, NotificationError, NotificationId};
+use crate::pdf::{PdfError, PrintOptions};
 use crate::protocol::AssetHandlerRegistry;
-use crate::query::QueryEngine;
-use crate::shortcut::{HotKey, ShortcutId, ShortcutRegistry, ShortcutRegistryError};
+use crate::protocol::{AssetFuture, AssetResponse};
+use crate::query::{QueryEngine, QueryError};
+use crate::render_thread::WindowRoot;
+use crate::rendering::RenderingInfo;
+use crate::sandbox::{Sandbox, SandboxError, SandboxReceiver, SandboxRegistry};
+use crate::secure_input::SecureEventInput;
+use crate::shortcut::{
+    HotKey, ShortcutId, ShortcutRegistry, ShortcutRegistryError, WindowShortcutId,
+};
+use crate::storage::{Cookies, DataTypes, StorageError};
 use crate::AssetHandler;
 use crate::Config;
 use crate::WebviewHandler;
 use dioxus_core::ScopeState;
+use dioxus_core::Template;
 use dioxus_core::VirtualDom;
 #[cfg(all(feature = "hot-reload", debug_assertions))]
 use dioxus_hot_reload::HotReloadMsg;
@@ -22,12 +66,16 @@ use std::rc::Weak;
 use std::sync::atomic::AtomicU16;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use wry::application::event::Event;
 use wry::application::event_loop::EventLoopProxy;
 use wry::application::event_loop::EventLoopWindowTarget;
+use wry::application::menu::MenuId;
 #[cfg(target_os = "ios")]
 use wry::application::platform::ios::WindowExtIOS;
+use wry::application::window::CursorIcon;
 use wry::application::window::Fullscreen as WryFullscreen;
+use wry::application::window::Theme;
 use wry::application::window::Window;
 use wry::application::window::WindowId;
 use wry::webview::WebView;
@@ -53,39 +101,150 @@ pub fn use_window(cx: &ScopeState) -> &DesktopContext {
 
 /// This handles communication between the requests that the webview makes and the interpreter. The interpreter constantly makes long running requests to the webview to get any edits that should be made to the DOM almost like server side events.
 /// It will hold onto the requests until the interpreter is ready to handle them and hold onto any pending edits until a new request is made.
+///
+/// Every edit batch rendered while the interpreter applies the last response is added to the next one, so the
+/// interpreter catches up in one request no matter how many renders happened. Each batch of the response is prefixed
+/// with its length as a little endian `u32`. If the batches waiting grow past [`MAX_PENDING_EDIT_BYTES`], the
+/// VirtualDom stops rendering until the interpreter takes them, and the components that change meanwhile are rendered
+/// together afterwards.
+///
+/// With the [script transport](crate::EditTransport::Script) the interpreter asks for edits over IPC instead. The
+/// queue then hands the edits back to the event loop, which pushes them to the page.
 #[derive(Default, Clone)]
 pub(crate) struct EditQueue {
-    queue: Arc<Mutex<Vec<Vec<u8>>>>,
-    responder: Arc<Mutex<Option<wry::webview::RequestAsyncResponder>>>,
+    inner: Arc<Mutex<EditQueueInner>>,
+    pub(crate) metrics: Metrics,
+}
+
+#[derive(Default)]
+struct EditQueueInner {
+    /// The edit batches waiting for the interpreter, each prefixed with its length
+    pending: Vec<u8>,
+    /// The number of batches in `pending`
+    pending_batches: usize,
+    responder: Option<EditResponder>,
+    /// When the last edits were sent to the interpreter
+    sent: Option<Instant>,
+    /// Wakes the VirtualDom once the interpreter took the pending edits
+    drained: Option<std::task::Waker>,
 }
 
+/// A request of the interpreter for edits
+enum EditResponder {
+    /// A request to the custom protocol
+    Request(wry::webview::RequestAsyncResponder),
+    /// A request over IPC, answered by evaluating the edits in the page
+    Script,
+}
+
+/// The size of the pending edits above which the VirtualDom waits for the interpreter before rendering again
+pub(crate) const MAX_PENDING_EDIT_BYTES: usize = 4 * 1024 * 1024;
+
 impl Debug for EditQueue {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let inner = self.inner.lock().unwrap();
         f.debug_struct("EditQueue")
-            .field("queue", &self.queue)
-            .field("responder", {
-                &self.responder.lock().unwrap().as_ref().map(|_| ())
-            })
+            .field("pending_bytes", &inner.pending.len())
+            .field("pending_batches", &inner.pending_batches)
+            .field("responder", &inner.responder.as_ref().map(|_| ()))
             .finish()
     }
 }
 
 impl EditQueue {
     pub fn handle_request(&self, responder: wry::webview::RequestAsyncResponder) {
-        let mut queue = self.queue.lock().unwrap();
-        if let Some(bytes) = queue.pop() {
-            responder.respond(wry::http::Response::new(bytes));
+        self.wait(EditResponder::Request(responder));
+    }
+
+    /// Handle a request for edits over IPC. Returns the edits to push to the page if there are any already.
+    pub(crate) fn handle_script_request(&self) -> Option<Vec<u8>> {
+        self.wait(EditResponder::Script)
+    }
+
+    fn wait(&self, responder: EditResponder) -> Option<Vec<u8>> {
+        let mut inner = self.inner.lock().unwrap();
+
+        // The interpreter only asks for more edits after it applied the last ones
+        if let Some(sent) = inner.sent.take() {
+            self.metrics
+                .record_duration(MetricKind::EditApply, sent.elapsed());
+        }
+
+        if inner.pending_batches > 0 {
+            self.respond(&mut inner, responder)
         } else {
-            *self.responder.lock().unwrap() = Some(responder);
+            inner.responder = Some(responder);
+            None
         }
     }
 
-    pub fn add_edits(&self, edits: Vec<u8>) {
-        let mut responder = self.responder.lock().unwrap();
-        if let Some(responder) = responder.take() {
-            responder.respond(wry::http::Response::new(edits));
-        } else {
-            self.queue.lock().unwrap().push(edits);
+    /// Add an edit batch. Returns the edits to push to the page if it waits for them over IPC.
+    #[must_use]
+    pub fn add_edits(&self, edits: Vec<u8>) -> Option<Vec<u8>> {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .pending
+            .extend_from_slice(&(edits.len() as u32).to_le_bytes());
+        inner.pending.extend_from_slice(&edits);
+        inner.pending_batches += 1;
+
+        let responder = inner.responder.take()?;
+        self.respond(&mut inner, responder)
+    }
+
+    /// Send every pending batch in one response, or return them if they are pushed with a script
+    fn respond(&self, inner: &mut EditQueueInner, responder: EditResponder) -> Option<Vec<u8>> {
+        let batches = std::mem::take(&mut inner.pending_batches);
+        let bytes = std::mem::take(&mut inner.pending);
+        self.metrics
+            .record(MetricKind::EditBatchesPerResponse, batches as f64);
+        self.metrics
+            .record(MetricKind::EditResponseBytes, bytes.len() as f64);
+        inner.sent = Some(Instant::now());
+
+        if let Some(waker) = inner.drained.take() {
+            waker.wake();
+        }
+
+        match responder {
+            EditResponder::Request(responder) => {
+                responder.respond(wry::http::Response::new(bytes));
+                None
+            }
+            EditResponder::Script => Some(bytes),
+        }
+    }
+
+    /// Check if so many edits are waiting for the interpreter that the VirtualDom should not render more. `waker` is
+    /// woken once the interpreter took them.
+    pub(crate) fn is_backed_up(&self, waker: &std::task::Waker) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let backed_up = inner.pending.len() >= MAX_PENDING_EDIT_BYTES;
+        if backed_up {
+            inner.drained = Some(waker.clone());
+        }
+        backed_up
+    }
+
+    /// Forget the edits that wait for the interpreter, and keep its request
+    pub(crate) fn clear_pending(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.pending = Vec::new();
+        inner.pending_batches = 0;
+        if let Some(waker) = inner.drained.take() {
+            waker.wake();
+        }
+    }
+
+    /// Forget the edits and the request of a page that stopped
+    pub fn reset(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.pending = Vec::new();
+        inner.pending_batches = 0;
+        inner.responder = None;
+        inner.sent = None;
+        if let Some(waker) = inner.drained.take() {
+            waker.wake();
         }
     }
 }
@@ -122,12 +281,96 @@ pub struct DesktopService {
 
     pub(crate) shortcut_manager: ShortcutRegistry,
 
+    pub(crate) commands: CommandRegistry,
+
     pub(crate) edit_queue: EditQueue,
     pub(crate) templates: RefCell<FxHashMap<String, u16>>,
+    /// Every template sent to the page, in order, to send them again to a page that was loaded again
+    pub(crate) sent_templates: RefCell<Vec<Template<'static>>>,
     pub(crate) max_template_count: AtomicU16,
 
     pub(crate) channel: RefCell<Channel>,
     pub(crate) asset_handlers: AssetHandlerRegistry,
+    pub(crate) protocols: ProtocolRegistry,
+
+    pub(crate) inspector: Inspector,
+    pub(crate) metrics: Metrics,
+    #[cfg(feature = "metrics-endpoint")]
+    pub(crate) metrics_endpoint: RefCell<Option<crate::metrics::MetricsEndpoint>>,
+    pub(crate) devtools_panel: RefCell<Option<Weak<DesktopService>>>,
+    pub(crate) menu_items: RefCell<std::collections::HashMap<MenuId, String>>,
+    pub(crate) menu_handlers: RefCell<Slab<Box<dyn FnMut(&str)>>>,
+    pub(crate) close_handlers: RefCell<Slab<Box<dyn FnMut() -> CloseBehaviour>>>,
+    pub(crate) invoke_handlers: InvokeRegistry,
+    pub(crate) sandboxes: SandboxRegistry,
+    pub(crate) download_handlers: RefCell<Slab<DownloadCallback>>,
+    pub(crate) find: FindInPage,
+    pub(crate) crash_handlers: RefCell<Slab<CrashCallback>>,
+    pub(crate) crash_recovery: RefCell<CrashRecoveryState>,
+    /// Set from when the page stopped until the page that replaces it starts. Edits are dropped meanwhile, because
+    /// the new page is sent the whole VirtualDom.
+    pub(crate) recovering: std::cell::Cell<bool>,
+    pub(crate) event_listeners: RefCell<Slab<Box<dyn FnMut(GlobalEvent)>>>,
+    pub(crate) window_shortcuts: RefCell<Slab<Box<dyn FnMut()>>>,
+    pub(crate) dropped_files: RefCell<Vec<std::path::PathBuf>>,
+    pub(crate) deep_link_handlers: RefCell<Slab<DeepLinkCallback>>,
+    pub(crate) pending_deep_links: RefCell<Vec<url::Url>>,
+    pub(crate) hardware_acceleration: std::cell::Cell<bool>,
+    pub(crate) autofill: std::cell::Cell<AutofillSettings>,
+    pub(crate) content_protected: std::cell::Cell<bool>,
+    /// Set while a secure input of the page has focus
+    pub(crate) secure_event_input: RefCell<Option<SecureEventInput>>,
+    /// If the first frame was rendered when the window was created, and the page hasn't asked for it yet
+    pub(crate) prerendered: std::cell::Cell<bool>,
+    /// Set while the VirtualDom of the window isn't polled for work
+    pub(crate) paused: std::cell::Cell<bool>,
+    /// The shortest time between two renders of the VirtualDom that woke up on its own
+    pub(crate) redraw_interval: std::cell::Cell<Option<Duration>>,
+    pub(crate) last_render: std::cell::Cell<Option<Instant>>,
+    /// When the VirtualDom has work that waits for the [`redraw_interval`](Self::redraw_interval) to pass
+    pub(crate) render_deadline: std::cell::Cell<Option<Instant>>,
+    pub(crate) ipc_channels:
+        RefCell<FxHashMap<String, tokio::sync::mpsc::UnboundedSender<serde_json::Value>>>,
+    #[cfg(feature = "remote-inspector")]
+    pub(crate) remote_inspector: RefCell<Option<crate::remote_inspector::RemoteInspector>>,
+
+    #[cfg(all(
+        feature = "media-session",
+        not(any(target_os = "ios", target_os = "android"))
+    ))]
+    pub(crate) media_session: RefCell<Option<MediaSession>>,
+
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub(crate) connectivity: RefCell<Option<Connectivity>>,
+
+    #[cfg(all(
+        feature = "clipboard",
+        not(any(target_os = "ios", target_os = "android"))
+    ))]
+    pub(crate) clipboard: Clipboard,
+
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub(crate) window_state: RefCell<Option<crate::window_state::WindowStatePersistence>>,
+
+    #[cfg(all(
+        feature = "notifications",
+        not(any(target_os = "ios", target_os = "android"))
+    ))]
+    pub(crate) notification_handlers: RefCell<FxHashMap<NotificationId, Box<dyn FnMut()>>>,
+    #[cfg(all(
+        feature = "notifications",
+        not(any(target_os = "ios", target_os = "android"))
+    ))]
+    pub(crate) next_notification: std::cell::Cell<usize>,
+
+    #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+    pub(crate) portals: std::cell::Cell<crate::portal::PortalMode>,
+
+    #[cfg(target_os = "macos")]
+    pub(crate) titlebar: std::cell::Cell<crate::titlebar::TitlebarOptions>,
+
+    #[cfg(feature = "tray")]
+    pub(crate) tray_handlers: RefCell<Slab<crate::tray::TrayCallback>>,
 
     #[cfg(target_os = "ios")]
     pub(crate) views: Rc<RefCell<Vec<*mut objc::runtime::Object>>>,
@@ -153,22 +396,90 @@ impl DesktopService {
         webviews: WebviewQueue,
         event_handlers: WindowEventHandlers,
         shortcut_manager: ShortcutRegistry,
+        commands: CommandRegistry,
         edit_queue: EditQueue,
         asset_handlers: AssetHandlerRegistry,
+        protocols: ProtocolRegistry,
     ) -> Self {
+        let metrics = edit_queue.metrics.clone();
         Self {
             webview: Rc::new(webview),
             proxy,
             event_loop,
-            query: Default::default(),
+            query: QueryEngine::new(metrics.clone()),
             pending_windows: webviews,
             event_handlers,
             shortcut_manager,
+            commands,
             edit_queue,
             templates: Default::default(),
+            sent_templates: Default::default(),
             max_template_count: Default::default(),
             channel: Default::default(),
             asset_handlers,
+            protocols,
+            inspector: Default::default(),
+            metrics,
+            #[cfg(feature = "metrics-endpoint")]
+            metrics_endpoint: Default::default(),
+            devtools_panel: Default::default(),
+            menu_items: Default::default(),
+            menu_handlers: Default::default(),
+            close_handlers: Default::default(),
+            invoke_handlers: Default::default(),
+            sandboxes: Default::default(),
+            download_handlers: Default::default(),
+            find: Default::default(),
+            crash_handlers: Default::default(),
+            crash_recovery: Default::default(),
+            recovering: Default::default(),
+            event_listeners: Default::default(),
+            window_shortcuts: Default::default(),
+            dropped_files: Default::default(),
+            deep_link_handlers: Default::default(),
+            pending_deep_links: Default::default(),
+            hardware_acceleration: std::cell::Cell::new(true),
+            autofill: Default::default(),
+            content_protected: Default::default(),
+            secure_event_input: Default::default(),
+            prerendered: Default::default(),
+            paused: Default::default(),
+            redraw_interval: Default::default(),
+            last_render: Default::default(),
+            render_deadline: Default::default(),
+            ipc_channels: Default::default(),
+            #[cfg(feature = "remote-inspector")]
+            remote_inspector: Default::default(),
+            #[cfg(all(
+                feature = "media-session",
+                not(any(target_os = "ios", target_os = "android"))
+            ))]
+            media_session: Default::default(),
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            connectivity: Default::default(),
+            #[cfg(all(
+                feature = "clipboard",
+                not(any(target_os = "ios", target_os = "android"))
+            ))]
+            clipboard: Default::default(),
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            window_state: Default::default(),
+            #[cfg(all(
+                feature = "notifications",
+                not(any(target_os = "ios", target_os = "android"))
+            ))]
+            notification_handlers: Default::default(),
+            #[cfg(all(
+                feature = "notifications",
+                not(any(target_os = "ios", target_os = "android"))
+            ))]
+            next_notification: Default::default(),
+            #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+            portals: Default::default(),
+            #[cfg(target_os = "macos")]
+            titlebar: Default::default(),
+            #[cfg(feature = "tray")]
+            tray_handlers: Default::default(),
             #[cfg(target_os = "ios")]
             views: Default::default(),
         }
@@ -181,22 +492,31 @@ impl DesktopService {
     /// You can use this to control other windows from the current window.
     ///
     /// Be careful to not create a cycle of windows, or you might leak memory.
+    ///
+    /// An [ephemeral](Config::with_ephemeral) window isn't created when its data directory can't be created. The
+    /// handle that is returned then never upgrades.
     pub fn new_window(&self, dom: VirtualDom, cfg: Config) -> Weak<DesktopService> {
-        let window = create_new_window(
+        let window = match create_new_window(
             cfg,
             &self.event_loop,
             &self.proxy,
-            dom,
+            WindowRoot::Local(dom),
             &self.pending_windows,
             &self.event_handlers,
             self.shortcut_manager.clone(),
-        );
+            self.commands.clone(),
+        ) {
+            Ok(window) => window,
+            Err(err) => {
+                tracing::error!(
+                    "failed to create the data directory of an ephemeral window: {}",
+                    err
+                );
+                return Weak::new();
+            }
+        };
 
-        let desktop_context = window
-            .dom
-            .base_scope()
-            .consume_context::<Rc<DesktopService>>()
-            .unwrap();
+        let desktop_context = window.desktop_context.clone();
 
         let id = window.desktop_context.webview.window().id();
 
@@ -237,7 +557,50 @@ impl DesktopService {
         window.set_maximized(!window.is_maximized())
     }
 
+    /// Get the inspector that records the state of this window for the devtools.
+    pub fn inspector(&self) -> Inspector {
+        self.inspector.clone()
+    }
+
+    /// The url devtools connect to when the window was created with
+    /// [`Config::with_remote_inspector`](crate::Config::with_remote_inspector). The url contains a token that changes
+    /// every launch.
+    #[cfg(feature = "remote-inspector")]
+    pub fn remote_inspector_url(&self) -> Option<String> {
+        self.remote_inspector
+            .borrow()
+            .as_ref()
+            .map(|remote| remote.url().to_string())
+    }
+
+    /// Get the performance metrics of this window.
+    ///
+    /// ```rust, ignore
+    /// let metrics = window().metrics();
+    /// metrics.on_sample(|sample| println!("{:?}: {}", sample.kind, sample.value));
+    /// ```
+    pub fn metrics(&self) -> Metrics {
+        self.metrics.clone()
+    }
+
+    /// Open the devtools panel for this window, or close it if it is already open.
+    pub fn toggle_devtools_panel(&self) {
+        let open_panel = self.devtools_panel.borrow_mut().take();
+        match open_panel.and_then(|panel| panel.upgrade()) {
+            Some(panel) => panel.close(),
+            None => {
+                *self.devtools_panel.borrow_mut() = Some(crate::devtools::open_panel(self));
+                // Render again so the panel gets the component tree
+                let _ = self
+                    .proxy
+                    .send_event(UserWindowEvent(EventData::Poll, self.id()));
+            }
+        }
+    }
+
     /// close window
+    ///
+    /// The window stays open if one of its close handlers prevents it. See [`DesktopService::on_close_requested`].
     pub fn close(&self) {
         let _ = self
             .proxy
@@ -245,12 +608,45 @@ impl DesktopService {
     }
 
     /// close window
+    ///
+    /// The window stays open if one of its close handlers prevents it. See [`DesktopService::on_close_requested`].
     pub fn close_window(&self, id: WindowId) {
         let _ = self
             .proxy
             .send_event(UserWindowEvent(EventData::CloseWindow, id));
     }
 
+    /// Close the window without asking its close handlers
+    pub fn force_close(&self) {
+        let _ = self
+            .proxy
+            .send_event(UserWindowEvent(EventData::ForceCloseWindow, self.id()));
+    }
+
+    /// Call `handler` every time the window is asked to close, by the user or by [`DesktopService::close`]. The
+    /// window stays open if any handler returns [`CloseBehaviour::Prevent`].
+    ///
+    /// The id this function returns can be used to remove the handler with [`DesktopService::remove_close_handler`]
+    pub fn on_close_requested(
+        &self,
+        handler: impl FnMut() -> CloseBehaviour + 'static,
+    ) -> CloseHandlerId {
+        CloseHandlerId(self.close_handlers.borrow_mut().insert(Box::new(handler)))
+    }
+
+    /// Remove a close handler created with [`DesktopService::on_close_requested`]
+    pub fn remove_close_handler(&self, id: CloseHandlerId) {
+        self.close_handlers.borrow_mut().try_remove(id.0);
+    }
+
+    /// Ask the close handlers if the window may close. Stops at the first handler that prevents it.
+    pub(crate) fn may_close(&self) -> bool {
+        self.close_handlers
+            .borrow_mut()
+            .iter_mut()
+            .all(|(_, handler)| handler() == CloseBehaviour::Close)
+    }
+
     /// change window to fullscreen
     pub fn set_fullscreen(&self, fullscreen: bool) {
         if let Some(handle) = self.webview.window().current_monitor() {
@@ -267,11 +663,257 @@ impl DesktopService {
         }
     }
 
+    /// Print the page to the PDF at `path` without showing the print dialog. Unlike [`Self::print`], the page is laid
+    /// out with the page size, margins and orientation of `options`.
+    ///
+    /// ```rust, ignore
+    /// let options = PrintOptions::new().with_page_size(PageSize::LETTER).with_landscape(true);
+    /// window().print_to_pdf("report.pdf", options).await?;
+    /// ```
+    pub async fn print_to_pdf(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        options: PrintOptions,
+    ) -> Result<(), PdfError> {
+        crate::pdf::print_to_pdf(&self.webview, path.as_ref(), &options).await
+    }
+
     /// Set the zoom level of the webview
     pub fn set_zoom_level(&self, level: f64) {
         self.webview.zoom(level);
     }
 
+    /// Get the theme of the window. This is the theme of the system, unless it was forced with
+    /// [`Config::with_theme`](crate::Config::with_theme).
+    ///
+    /// Use [`use_system_theme`](crate::use_system_theme) to re-render a component when the theme changes.
+    pub fn theme(&self) -> Theme {
+        self.webview.window().theme()
+    }
+
+    /// Show `cursor` over the whole window, whatever the `cursor` attribute of the hovered element is.
+    ///
+    /// Set it to [`CursorIcon::Default`] to let the page pick the cursor again.
+    pub fn set_cursor(&self, cursor: CursorIcon) {
+        self.webview.window().set_cursor_icon(cursor);
+
+        let css = match crate::cursor::css_cursor(cursor) {
+            Some(css) => format!("{:?}", css),
+            None => "null".to_string(),
+        };
+        if let Err(err) = self
+            .webview
+            .evaluate_script(&format!("window.__dioxusPointer.setCursor({});", css))
+        {
+            tracing::error!("Failed to set the cursor: {}", err);
+        }
+    }
+
+    /// Record the size and position of the window after it was moved or resized
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub(crate) fn track_window_state(&self) {
+        if let Some(window_state) = self.window_state.borrow_mut().as_mut() {
+            window_state.update(self.webview.window());
+        }
+    }
+
+    /// Write the state of the window to disk, if it is persisted
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub(crate) fn save_window_state(&self) {
+        if let Some(window_state) = self.window_state.borrow_mut().as_mut() {
+            window_state.save();
+        }
+    }
+
+    /// Show the tooltip of the hovered element, or hide it
+    pub(crate) fn set_native_tooltip(&self, tooltip: Option<&str>) {
+        if !crate::cursor::set_native_tooltip(&self.webview, tooltip) && tooltip.is_some() {
+            _ = self
+                .webview
+                .evaluate_script("window.__dioxusPointer.useTitle();");
+        }
+    }
+
+    /// Search the page for `query`, highlighting the matches and scrolling to the first one.
+    ///
+    /// Starting a new search replaces the previous one. The find API of the webview is used where there is one, see
+    /// [`FindResult`] for what each platform reports.
+    ///
+    /// ```rust, ignore
+    /// let result = window().find_in_page("dioxus", FindOptions::default()).await?;
+    /// if let Some(matches) = result.matches {
+    ///     println!("{matches} matches");
+    /// }
+    /// ```
+    pub async fn find_in_page(
+        &self,
+        query: &str,
+        options: FindOptions,
+    ) -> Result<FindResult, QueryError> {
+        self.find
+            .search(&self.query, &self.webview, query, options)
+            .await
+    }
+
+    /// Move to the next match of the current search
+    pub async fn find_next(&self) -> Result<FindResult, QueryError> {
+        self.find.step(&self.query, &self.webview, true).await
+    }
+
+    /// Move to the previous match of the current search
+    pub async fn find_previous(&self) -> Result<FindResult, QueryError> {
+        self.find.step(&self.query, &self.webview, false).await
+    }
+
+    /// End the current search and clear the highlighted matches
+    pub async fn clear_find(&self) -> Result<(), QueryError> {
+        self.find.clear(&self.query, &self.webview).await
+    }
+
+    /// Check if the webview draws the page with the GPU, and which renderer it uses
+    ///
+    /// ```rust, ignore
+    /// let info = window().rendering_info().await?;
+    /// if !info.hardware_accelerated {
+    ///     println!("Rendering in software with {:?}", info.renderer);
+    /// }
+    /// ```
+    pub async fn rendering_info(&self) -> Result<RenderingInfo, QueryError> {
+        crate::rendering::rendering_info(
+            &self.query,
+            self.webview.clone(),
+            self.hardware_acceleration.get(),
+        )
+        .await
+    }
+
+    /// Take a screenshot of the visible part of the page. It works in hidden and [headless](crate::Config::with_headless)
+    /// windows too, which makes it useful for visual regression tests.
+    ///
+    /// ```rust, ignore
+    /// let capture = window().capture_page().await?;
+    /// std::fs::write("screenshot.png", capture.png)?;
+    /// ```
+    pub async fn capture_page(&self) -> Result<PageCapture, CaptureError> {
+        crate::capture::capture_page(&self.query, self.webview.clone()).await
+    }
+
+    /// Turn form autofill of this window on or off. See [`Config::with_autofill`].
+    pub fn set_autofill(&self, enabled: bool) {
+        let mut settings = self.autofill.get();
+        settings.autofill = enabled;
+        self.autofill.set(settings);
+        crate::autofill::apply(&self.webview, settings);
+    }
+
+    /// Turn password saving of this window on or off. See [`Config::with_password_saving`].
+    pub fn set_password_saving(&self, enabled: bool) {
+        let mut settings = self.autofill.get();
+        settings.password_saving = enabled;
+        self.autofill.set(settings);
+        crate::autofill::apply(&self.webview, settings);
+    }
+
+    /// Hide the contents of this window from screenshots, screen recordings and screen sharing, or show them again.
+    /// Other apps see the window as a black or empty rectangle.
+    ///
+    /// This only has an effect on Windows and macOS.
+    pub fn set_content_protected(&self, protected: bool) {
+        self.content_protected.set(protected);
+        crate::secure_input::set_window_protected(&self.webview, protected);
+    }
+
+    /// Check if the contents of this window were hidden from screen capture with
+    /// [`Config::with_content_protection`] or [`DesktopService::set_content_protected`]
+    pub fn is_content_protected(&self) -> bool {
+        self.content_protected.get()
+    }
+
+    /// Called by the page when a secure input gains or loses focus
+    pub(crate) fn set_secure_input_focused(&self, focused: bool) {
+        let mut secure_event_input = self.secure_event_input.borrow_mut();
+        if secure_event_input.is_some() == focused {
+            return;
+        }
+        *secure_event_input = focused.then(SecureEventInput::enable);
+    }
+
+    /// Stop polling the VirtualDom of this window for work, like futures and tasks that wake up. Events from the page
+    /// are still handled and rendered. Use this to save power while the window is hidden or minimized.
+    pub fn pause(&self) {
+        self.paused.set(true);
+        self.render_deadline.set(None);
+    }
+
+    /// Poll the VirtualDom of this window for work again after [`DesktopService::pause`]
+    pub fn resume(&self) {
+        if self.paused.replace(false) {
+            _ = self
+                .proxy
+                .send_event(UserWindowEvent(EventData::Poll, self.id()));
+        }
+    }
+
+    /// Check if the VirtualDom of this window was paused with [`DesktopService::pause`]
+    pub fn is_paused(&self) -> bool {
+        self.paused.get()
+    }
+
+    /// Render the work the VirtualDom of this window does on its own at most `hz` times per second. The components
+    /// that change meanwhile are rendered together with the next frame. Events from the page are still rendered
+    /// right away. `0` removes the limit.
+    ///
+    /// ```rust, ignore
+    /// // A clock that ticks every millisecond doesn't need to be redrawn more than 10 times a second
+    /// window().request_redraw_throttle(10);
+    /// ```
+    pub fn request_redraw_throttle(&self, hz: u32) {
+        let interval = (hz > 0).then(|| Duration::from_secs(1) / hz);
+        self.redraw_interval.set(interval);
+        // The work that waits for the old limit may render now
+        if self.render_deadline.take().is_some() {
+            _ = self
+                .proxy
+                .send_event(UserWindowEvent(EventData::Poll, self.id()));
+        }
+    }
+
+    /// Change how the event loop of the app waits for events. This affects every window. See [`EventLoopMode`].
+    pub fn set_event_loop_mode(&self, mode: EventLoopMode) {
+        crate::event_loop_mode::set_mode(mode);
+    }
+
+    /// How the event loop of the app waits for events
+    pub fn event_loop_mode(&self) -> EventLoopMode {
+        crate::event_loop_mode::mode()
+    }
+
+    /// Forget the form entries and passwords the webview saved. The data is shared by every window with the same
+    /// [data directory](Config::with_data_directory).
+    pub async fn clear_autofill_data(&self) -> Result<(), AutofillError> {
+        crate::autofill::clear(&self.webview).await
+    }
+
+    /// The cookies of the webview, to list, set and delete them. They are shared by every window with the same
+    /// [data directory](Config::with_data_directory).
+    pub fn cookies(&self) -> Cookies {
+        Cookies {
+            webview: self.webview.clone(),
+        }
+    }
+
+    /// Remove the `types` of data the webview stored for every website, like cookies, `localStorage`, IndexedDB
+    /// databases and caches. The data is shared by every window with the same
+    /// [data directory](Config::with_data_directory).
+    ///
+    /// ```rust, ignore
+    /// // Log out everywhere
+    /// window().clear_browsing_data(DataTypes::ALL).await?;
+    /// ```
+    pub async fn clear_browsing_data(&self, types: DataTypes) -> Result<(), StorageError> {
+        crate::storage::clear_browsing_data(&self.webview, types).await
+    }
+
     /// opens DevTool window
     pub fn devtool(&self) {
         #[cfg(debug_assertions)]
@@ -319,6 +961,679 @@ impl DesktopService {
         self.shortcut_manager.remove_all()
     }
 
+    /// Get every global shortcut the app registered
+    pub fn registered_shortcuts(&self) -> Vec<HotKey> {
+        self.shortcut_manager.registered()
+    }
+
+    /// Check if the app registered `hotkey` as a global shortcut
+    pub fn is_shortcut_registered(&self, hotkey: &HotKey) -> bool {
+        self.shortcut_manager.is_registered(hotkey)
+    }
+
+    /// Check if `hotkey` can be registered as a global shortcut, or if another application already took it.
+    ///
+    /// This is always true for the shortcuts this app registered, because a shortcut can have multiple callbacks.
+    pub fn is_shortcut_available(&self, hotkey: HotKey) -> bool {
+        self.shortcut_manager.is_available(hotkey)
+    }
+
+    /// Create a shortcut that only runs while this window has focus.
+    ///
+    /// This fails if the app registered the same global shortcut, because the system doesn't send global shortcuts to
+    /// the window.
+    pub fn create_window_shortcut(
+        &self,
+        hotkey: HotKey,
+        callback: impl FnMut() + 'static,
+    ) -> Result<WindowShortcutId, ShortcutRegistryError> {
+        if self.shortcut_manager.is_registered(&hotkey) {
+            return Err(ShortcutRegistryError::AlreadyRegistered(hotkey));
+        }
+        let id = self
+            .window_shortcuts
+            .borrow_mut()
+            .insert(Box::new(callback));
+        if let Err(err) = self.webview.evaluate_script(&format!(
+            "window.__dioxusAccelerators.add({}, {});",
+            id,
+            crate::shortcut::accelerator_script_args(&hotkey)
+        )) {
+            tracing::error!("Failed to add the window shortcut: {}", err);
+        }
+        Ok(WindowShortcutId(id))
+    }
+
+    /// Remove a shortcut created with [`DesktopService::create_window_shortcut`]
+    pub fn remove_window_shortcut(&self, id: WindowShortcutId) {
+        if self
+            .window_shortcuts
+            .borrow_mut()
+            .try_remove(id.0)
+            .is_some()
+        {
+            _ = self
+                .webview
+                .evaluate_script(&format!("window.__dioxusAccelerators.remove({});", id.0));
+        }
+    }
+
+    pub(crate) fn call_window_shortcut(&self, id: usize) {
+        if let Some(callback) = self.window_shortcuts.borrow_mut().get_mut(id) {
+            callback();
+        }
+    }
+
+    /// Add a command to the command palette of the app. The command is shared by every window.
+    ///
+    /// If `shortcut` is set, like `"ctrl+shift+k"`, it is registered as a global shortcut that runs the command.
+    pub fn register_command(
+        &self,
+        name: impl Into<String>,
+        shortcut: Option<&str>,
+        action: impl FnMut() + 'static,
+    ) -> Result<CommandId, ShortcutRegistryError> {
+        self.commands
+            .register(name.into(), shortcut, Box::new(action))
+    }
+
+    /// Remove a command and its shortcut
+    pub fn remove_command(&self, id: CommandId) {
+        self.commands.remove(id)
+    }
+
+    /// Open the command palette over this window, or close it if it is already open
+    pub fn toggle_command_palette(&self) {
+        if let Err(err) = self
+            .webview
+            .evaluate_script(&self.commands.palette_script())
+        {
+            tracing::error!("Failed to open the command palette: {}", err);
+        }
+    }
+
+    /// Replace the menu of the tray icon
+    #[cfg(feature = "tray")]
+    pub fn set_tray_menu(&self, menu: crate::tray::TrayMenu) {
+        self.update_tray(crate::tray::TrayUpdate::Menu(menu));
+    }
+
+    /// Replace the tray icon
+    #[cfg(feature = "tray")]
+    pub fn set_tray_icon(&self, icon: wry::application::window::Icon) {
+        self.update_tray(crate::tray::TrayUpdate::Icon(icon));
+    }
+
+    /// Change the tooltip of the tray icon
+    #[cfg(feature = "tray")]
+    pub fn set_tray_tooltip(&self, tooltip: impl Into<String>) {
+        self.update_tray(crate::tray::TrayUpdate::Tooltip(tooltip.into()));
+    }
+
+    #[cfg(feature = "tray")]
+    fn update_tray(&self, update: crate::tray::TrayUpdate) {
+        let _ = self
+            .proxy
+            .send_event(UserWindowEvent(EventData::UpdateTray(update), self.id()));
+    }
+
+    /// Call `handler` every time the user clicks the tray icon or one of its menu items.
+    ///
+    /// The id this function returns can be used to remove the handler with [`DesktopService::remove_tray_handler`]
+    #[cfg(feature = "tray")]
+    pub fn create_tray_handler(
+        &self,
+        handler: impl FnMut(&crate::tray::TrayEvent) + 'static,
+    ) -> crate::tray::TrayHandlerId {
+        crate::tray::TrayHandlerId(
+            self.tray_handlers
+                .borrow_mut()
+                .insert(Rc::new(RefCell::new(handler))),
+        )
+    }
+
+    /// Remove a tray event handler created with [`DesktopService::create_tray_handler`]
+    #[cfg(feature = "tray")]
+    pub fn remove_tray_handler(&self, id: crate::tray::TrayHandlerId) {
+        self.tray_handlers.borrow_mut().try_remove(id.0);
+    }
+
+    #[cfg(feature = "tray")]
+    pub(crate) fn call_tray_handlers(&self, event: &crate::tray::TrayEvent) {
+        // Clone the handlers out of the slab so handlers can add or remove handlers while they run
+        let handlers: Vec<_> = self
+            .tray_handlers
+            .borrow()
+            .iter()
+            .map(|(_, handler)| handler.clone())
+            .collect();
+        for handler in handlers {
+            (handler.borrow_mut())(event);
+        }
+    }
+
+    /// Open a typed channel to the page. The page opens the other end with `window.ipc.channel(name)`.
+    ///
+    /// Opening a channel with the name of one that is already open closes the old receiver. See
+    /// [`use_ipc_channel`](crate::use_ipc_channel) for a hook that handles the messages in a component.
+    pub fn ipc_channel<T>(&self, name: &str) -> (IpcSender<T>, IpcReceiver<T>) {
+        let (sender, receiver, tx) =
+            crate::ipc_channel::channel(name.to_string(), self.webview.clone());
+        self.ipc_channels.borrow_mut().insert(name.to_string(), tx);
+        (sender, receiver)
+    }
+
+    /// Pass a message the page sent on a channel to its receiver
+    pub(crate) fn receive_channel_message(&self, params: serde_json::Value) {
+        let name = match params["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => return,
+        };
+        let data = params
+            .get("data")
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+
+        let mut channels = self.ipc_channels.borrow_mut();
+        match channels.get(&name) {
+            Some(tx) => {
+                if tx.send(data).is_err() {
+                    // The receiver was dropped, so nothing listens on the channel anymore
+                    channels.remove(&name);
+                }
+            }
+            None => tracing::debug!("Dropped a message on the unopened IPC channel {:?}", name),
+        }
+    }
+
+    /// Register a function the page can call with `dioxus.invoke(name, args)`. The call returns a promise that resolves
+    /// with the value the function returns, or rejects with its error.
+    ///
+    /// Registering a handler with the name of an existing one replaces it. See
+    /// [`use_invoke_handler`](crate::use_invoke_handler) for a hook that removes the handler when the component is
+    /// unmounted.
+    pub fn register_invoke_handler<Args, T, E, F, Fut>(&self, name: &str, handler: F)
+    where
+        Args: serde::de::DeserializeOwned + 'static,
+        T: serde::Serialize + 'static,
+        E: std::fmt::Display + 'static,
+        F: Fn(Args) -> Fut + 'static,
+        Fut: std::future::Future<Output = Result<T, E>> + 'static,
+    {
+        self.invoke_handlers.register(name.to_string(), handler);
+    }
+
+    /// Remove the invoke handler with the given name
+    pub fn remove_invoke_handler(&self, name: &str) {
+        self.invoke_handlers.remove(name)
+    }
+
+    /// Create an isolated JavaScript context in this window for untrusted scripts, like plugins.
+    ///
+    /// Scripts in the sandbox can't access the app. They send messages to the returned receiver with
+    /// `plugin.send(message)`. See [`use_sandbox`](crate::use_sandbox) for a hook that handles the messages in a
+    /// component.
+    pub fn create_sandbox<T>(&self) -> Result<(Sandbox, SandboxReceiver<T>), SandboxError> {
+        Sandbox::create(self.webview.clone(), self.sandboxes.clone())
+    }
+
+    /// Pass a message the page relayed from a sandbox to its receiver
+    pub(crate) fn receive_sandbox_message(&self, params: serde_json::Value) {
+        let (id, message) = match crate::sandbox::parse_message(&params) {
+            Some(message) => message,
+            None => return,
+        };
+
+        if let Some(tx) = self.sandboxes.borrow().get(id) {
+            _ = tx.send(message);
+        }
+    }
+
+    /// Create a handler that is called every time a download of this window starts or completes
+    ///
+    /// The id this function returns can be used to remove the handler with
+    /// [`DesktopService::remove_download_handler`]
+    pub fn create_download_handler(
+        &self,
+        handler: impl FnMut(&DownloadEvent) + 'static,
+    ) -> DownloadHandlerId {
+        DownloadHandlerId(
+            self.download_handlers
+                .borrow_mut()
+                .insert(Rc::new(RefCell::new(handler))),
+        )
+    }
+
+    /// Remove a download handler created with [`DesktopService::create_download_handler`]
+    pub fn remove_download_handler(&self, id: DownloadHandlerId) {
+        self.download_handlers.borrow_mut().try_remove(id.0);
+    }
+
+    pub(crate) fn call_download_handlers(&self, event: &DownloadEvent) {
+        // Clone the handlers out of the slab so handlers can add or remove handlers while they run
+        let handlers: Vec<_> = self
+            .download_handlers
+            .borrow()
+            .iter()
+            .map(|(_, handler)| handler.clone())
+            .collect();
+        for handler in handlers {
+            (handler.borrow_mut())(event);
+        }
+    }
+
+    /// Create a handler that is called every time the page of this window stops
+    ///
+    /// The id this function returns can be used to remove the handler with
+    /// [`DesktopService::remove_crash_handler`]
+    pub fn create_crash_handler(
+        &self,
+        handler: impl FnMut(&CrashEvent) + 'static,
+    ) -> CrashHandlerId {
+        CrashHandlerId(
+            self.crash_handlers
+                .borrow_mut()
+                .insert(Rc::new(RefCell::new(handler))),
+        )
+    }
+
+    /// Remove a crash handler created with [`DesktopService::create_crash_handler`]
+    pub fn remove_crash_handler(&self, id: CrashHandlerId) {
+        self.crash_handlers.borrow_mut().try_remove(id.0);
+    }
+
+    /// Called when the page of this window stopped. Loads it again if the crash recovery of the window allows it.
+    pub(crate) fn handle_crash(&self, reason: CrashReason) {
+        let recovering = self.crash_recovery.borrow_mut().should_reload(reason);
+        if recovering {
+            tracing::error!(
+                "The page of the window stopped ({:?}), loading it again",
+                reason
+            );
+        } else {
+            tracing::error!("The page of the window stopped ({:?})", reason);
+        }
+
+        let event = CrashEvent { reason, recovering };
+        // Clone the handlers out of the slab so handlers can add or remove handlers while they run
+        let handlers: Vec<_> = self
+            .crash_handlers
+            .borrow()
+            .iter()
+            .map(|(_, handler)| handler.clone())
+            .collect();
+        for handler in handlers {
+            (handler.borrow_mut())(&event);
+        }
+
+        if recovering {
+            self.recovering.set(true);
+            self.edit_queue.reset();
+            crate::crash::reload(&self.webview);
+        }
+    }
+
+    /// Call `handler` with every url of a registered scheme the app is opened with
+    ///
+    /// The id this function returns can be used to remove the handler with
+    /// [`DesktopService::remove_deep_link_handler`]
+    pub fn create_deep_link_handler(
+        &self,
+        handler: impl FnMut(&url::Url) + 'static,
+    ) -> DeepLinkHandlerId {
+        let id = self
+            .deep_link_handlers
+            .borrow_mut()
+            .insert(Rc::new(RefCell::new(handler)));
+        // The urls that arrived before the first handler was added, like the one that started the app
+        let pending = std::mem::take(&mut *self.pending_deep_links.borrow_mut());
+        for url in pending {
+            self.open_deep_link(url);
+        }
+        DeepLinkHandlerId(id)
+    }
+
+    /// Remove a deep link handler created with [`DesktopService::create_deep_link_handler`]
+    pub fn remove_deep_link_handler(&self, id: DeepLinkHandlerId) {
+        self.deep_link_handlers.borrow_mut().try_remove(id.0);
+    }
+
+    pub(crate) fn open_deep_link(&self, url: url::Url) {
+        // Clone the handlers out of the slab so handlers can add or remove handlers while they run
+        let handlers: Vec<_> = self
+            .deep_link_handlers
+            .borrow()
+            .iter()
+            .map(|(_, handler)| handler.clone())
+            .collect();
+        if handlers.is_empty() {
+            self.pending_deep_links.borrow_mut().push(url);
+            return;
+        }
+        for handler in handlers {
+            (handler.borrow_mut())(&url);
+        }
+    }
+
+    pub(crate) fn file_dropped(&self, event: wry::webview::FileDropEvent) {
+        let script = crate::file_drop::drop_script(&event);
+        match event {
+            wry::webview::FileDropEvent::Hovered { paths, .. }
+            | wry::webview::FileDropEvent::Dropped { paths, .. } => {
+                *self.dropped_files.borrow_mut() = paths;
+            }
+            _ => {}
+        }
+        if let Some(script) = script {
+            _ = self.webview.evaluate_script(&script);
+        }
+    }
+
+    /// Add a listener for `event` to the window or the document of the page
+    ///
+    /// The id this function returns can be used to remove the listener with
+    /// [`DesktopService::remove_event_listener`]
+    pub fn create_event_listener(
+        &self,
+        target: ListenerTarget,
+        event: &str,
+        handler: impl FnMut(GlobalEvent) + 'static,
+    ) -> EventListenerId {
+        let id = self.event_listeners.borrow_mut().insert(Box::new(handler));
+        if let Err(err) = self.webview.evaluate_script(&format!(
+            "window.__dioxusListeners.add({}, {:?}, {:?});",
+            id,
+            target.as_str(),
+            event
+        )) {
+            tracing::error!("Failed to add the {} event listener: {}", event, err);
+        }
+        EventListenerId(id)
+    }
+
+    /// Remove an event listener created with [`DesktopService::create_event_listener`]
+    pub fn remove_event_listener(&self, id: EventListenerId) {
+        if self.event_listeners.borrow_mut().try_remove(id.0).is_some() {
+            _ = self
+                .webview
+                .evaluate_script(&format!("window.__dioxusListeners.remove({});", id.0));
+        }
+    }
+
+    /// Pass an event the page sent to its listener
+    pub(crate) fn call_event_listener(&self, params: serde_json::Value) {
+        let id = match params["id"].as_u64() {
+            Some(id) => id as usize,
+            None => return,
+        };
+        let event = GlobalEvent::new(params["data"].clone());
+        if let Some(handler) = self.event_listeners.borrow_mut().get_mut(id) {
+            handler(event);
+        }
+    }
+
+    /// Replace the menu bar of this window. Pass `None` to remove the menu bar.
+    pub fn set_menu(&self, menu: Option<AppMenu>) {
+        let mut items = self.menu_items.borrow_mut();
+        items.clear();
+        let menu = menu.map(|menu| menu.build(&mut items));
+        self.webview.window().set_menu(menu);
+    }
+
+    /// Call `handler` with the id of every item the user selects in the menu bar of this window.
+    ///
+    /// The id this function returns can be used to remove the handler with [`DesktopService::remove_menu_handler`]
+    pub fn create_menu_handler(&self, handler: impl FnMut(&str) + 'static) -> MenuHandlerId {
+        MenuHandlerId(self.menu_handlers.borrow_mut().insert(Box::new(handler)))
+    }
+
+    /// Remove a menu event handler created with [`DesktopService::create_menu_handler`]
+    pub fn remove_menu_handler(&self, id: MenuHandlerId) {
+        self.menu_handlers.borrow_mut().try_remove(id.0);
+    }
+
+    pub(crate) fn call_menu_handlers(&self, menu_id: MenuId) {
+        let item = self.menu_items.borrow().get(&menu_id).cloned();
+        if let Some(item) = item {
+            for (_, handler) in self.menu_handlers.borrow_mut().iter_mut() {
+                handler(&item);
+            }
+        }
+    }
+
+    /// Get the operating system's media controls for this window.
+    ///
+    /// The media controls are created the first time this is called. Use the session to publish what is
+    /// currently playing and to listen for media key presses. This needs the `media-session` feature.
+    ///
+    /// Linux: Requires a D-Bus session bus.
+    #[cfg(all(
+        feature = "media-session",
+        not(any(target_os = "ios", target_os = "android"))
+    ))]
+    pub fn media_session(&self) -> Result<MediaSession, MediaSessionError> {
+        let mut session = self.media_session.borrow_mut();
+        if let Some(session) = session.as_ref() {
+            return Ok(session.clone());
+        }
+
+        let new_session = MediaSession::new(self.webview.window(), self.proxy.clone())?;
+        *session = Some(new_session.clone());
+        Ok(new_session)
+    }
+
+    /// Get the network connectivity of the device.
+    ///
+    /// The operating system is watched for network changes from the first time this is called. Use it to check if
+    /// the device is online and to listen for the device going offline or coming back online.
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub fn connectivity(&self) -> Connectivity {
+        self.connectivity
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                Connectivity::new(self.proxy.clone(), self.webview.window().id())
+            })
+            .clone()
+    }
+
+    /// Get the system clipboard.
+    ///
+    /// This reads and writes the clipboard of the operating system directly, so it works without the clipboard
+    /// permissions of the webview.
+    #[cfg(all(
+        feature = "clipboard",
+        not(any(target_os = "ios", target_os = "android"))
+    ))]
+    pub fn clipboard(&self) -> Clipboard {
+        self.clipboard.clone()
+    }
+
+    /// Show a notification in the operating system's notification center.
+    ///
+    /// The click callback of the notification runs on the event loop and is removed after the first click. See
+    /// [`use_notification`](crate::use_notification) for a hook that removes the callbacks when the component is
+    /// unmounted.
+    #[cfg(all(
+        feature = "notifications",
+        not(any(target_os = "ios", target_os = "android"))
+    ))]
+    pub fn send_notification(
+        &self,
+        mut notification: NotificationBuilder,
+    ) -> Result<NotificationId, NotificationError> {
+        let id = NotificationId(self.next_notification.get());
+        self.next_notification.set(id.0 + 1);
+
+        #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+        if self.uses_portals() {
+            let title = notification.title;
+            let body = notification.body;
+            tokio::spawn(async move {
+                let result = crate::portal::send_portal_notification(
+                    &id.0.to_string(),
+                    &title,
+                    body.as_deref(),
+                )
+                .await;
+                if let Err(err) = result {
+                    tracing::error!("Failed to show the notification: {}", err);
+                }
+            });
+            return Ok(id);
+        }
+
+        let on_click = notification.on_click.take().map(|handler| {
+            self.notification_handlers.borrow_mut().insert(id, handler);
+            let proxy = self.proxy.clone();
+            let window_id = self.id();
+            move || {
+                _ = proxy.send_event(UserWindowEvent(
+                    EventData::NotificationClicked(id),
+                    window_id,
+                ));
+            }
+        });
+
+        if let Err(err) = crate::notification::show(&notification, on_click) {
+            self.notification_handlers.borrow_mut().remove(&id);
+            return Err(err);
+        }
+        Ok(id)
+    }
+
+    /// Remove the click callback of a notification sent with [`DesktopService::send_notification`]
+    #[cfg(all(
+        feature = "notifications",
+        not(any(target_os = "ios", target_os = "android"))
+    ))]
+    pub fn remove_notification_handler(&self, id: NotificationId) {
+        self.notification_handlers.borrow_mut().remove(&id);
+    }
+
+    #[cfg(all(
+        feature = "notifications",
+        not(any(target_os = "ios", target_os = "android"))
+    ))]
+    pub(crate) fn notification_clicked(&self, id: NotificationId) {
+        // The handler may send another notification, so the handlers can't stay borrowed while it runs
+        let handler = self.notification_handlers.borrow_mut().remove(&id);
+        if let Some(mut handler) = handler {
+            handler();
+        }
+    }
+
+    /// Set how the corners of the window are rounded. Only supported on Windows 11.
+    #[cfg(target_os = "windows")]
+    pub fn set_corner_preference(
+        &self,
+        preference: crate::CornerPreference,
+    ) -> Result<(), crate::WindowAttributeError> {
+        crate::dwm::set_corner_preference(self.webview.window(), preference)
+    }
+
+    /// Draw the titlebar in dark or light colors. Only supported on Windows 10 20H1 and later.
+    ///
+    /// ```rust, ignore
+    /// // Follow the theme of the system
+    /// let window = use_window(cx);
+    /// window.set_dark_titlebar(window.theme() == Theme::Dark)?;
+    /// ```
+    #[cfg(target_os = "windows")]
+    pub fn set_dark_titlebar(&self, dark: bool) -> Result<(), crate::WindowAttributeError> {
+        crate::dwm::set_dark_titlebar(self.webview.window(), dark)
+    }
+
+    /// Set the material drawn behind the window's content. Only supported on Windows 11 22H2 and later.
+    #[cfg(target_os = "windows")]
+    pub fn set_backdrop(
+        &self,
+        backdrop: crate::BackdropMaterial,
+    ) -> Result<(), crate::WindowAttributeError> {
+        crate::dwm::set_backdrop(self.webview.window(), backdrop)
+    }
+
+    /// Change how the titlebar is drawn
+    #[cfg(target_os = "macos")]
+    pub fn set_titlebar_style(&self, style: crate::TitlebarStyle) {
+        self.update_titlebar(|titlebar| titlebar.style = style);
+    }
+
+    /// Move the traffic-light buttons, or move them back to their default position with `None`.
+    ///
+    /// `position` is the distance of the close button from the top left corner of the window.
+    #[cfg(target_os = "macos")]
+    pub fn set_traffic_light_position(
+        &self,
+        position: Option<wry::application::dpi::LogicalPosition<f64>>,
+    ) {
+        self.update_titlebar(|titlebar| titlebar.traffic_light_position = position);
+    }
+
+    /// Hide or show the traffic-light buttons
+    #[cfg(target_os = "macos")]
+    pub fn set_traffic_lights_hidden(&self, hidden: bool) {
+        self.update_titlebar(|titlebar| titlebar.traffic_lights_hidden = hidden);
+    }
+
+    #[cfg(target_os = "macos")]
+    fn update_titlebar(&self, update: impl FnOnce(&mut crate::titlebar::TitlebarOptions)) {
+        let mut titlebar = self.titlebar.get();
+        update(&mut titlebar);
+        self.titlebar.set(titlebar);
+        titlebar.apply(self.webview.window());
+    }
+
+    /// Let the user pick files to open. Resolves to `None` if the dialog was cancelled.
+    ///
+    /// The dialog is modal to this window and doesn't block the event loop while it is open.
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub fn open_file_dialog(
+        &self,
+        options: FileDialogOptions,
+    ) -> impl std::future::Future<Output = Option<Vec<std::path::PathBuf>>> {
+        options.pick(self.webview.window(), false, self.portal_file_dialogs())
+    }
+
+    /// Let the user pick folders. Resolves to `None` if the dialog was cancelled.
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub fn open_folder_dialog(
+        &self,
+        options: FileDialogOptions,
+    ) -> impl std::future::Future<Output = Option<Vec<std::path::PathBuf>>> {
+        options.pick(self.webview.window(), true, self.portal_file_dialogs())
+    }
+
+    /// Let the user pick where to save a file. Resolves to `None` if the dialog was cancelled.
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub fn save_file_dialog(
+        &self,
+        options: FileDialogOptions,
+    ) -> impl std::future::Future<Output = Option<std::path::PathBuf>> {
+        options.save(self.webview.window(), self.portal_file_dialogs())
+    }
+
+    #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+    fn portal_file_dialogs(&self) -> bool {
+        self.uses_portals()
+    }
+
+    #[cfg(not(any(
+        target_os = "ios",
+        target_os = "android",
+        all(target_os = "linux", feature = "xdg-portal")
+    )))]
+    fn portal_file_dialogs(&self) -> bool {
+        false
+    }
+
+    /// Check if this window uses the XDG desktop portals for file dialogs, screenshots and notifications.
+    ///
+    /// See [`Config::with_portals`] for when the portals are used.
+    #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+    pub fn uses_portals(&self) -> bool {
+        self.portals.get().enabled()
+    }
+
     /// Provide a callback to handle asset loading yourself.
     ///
     /// See [`use_asset_handle`](crate::use_asset_handle) for a convenient hook.
@@ -333,6 +1648,41 @@ impl DesktopService {
         self.asset_handlers.remove_handler(id).await
     }
 
+    /// Serve a custom protocol called `name` from this window, replacing the protocol with that name if there is one.
+    ///
+    /// A webview can only be given new schemes before it is built, so protocols registered while the window is open
+    /// are served under a scheme the window already has. Use [`DesktopService::protocol_url`] to get the URL the
+    /// protocol is served at. The protocols from [`Config::with_custom_protocol`] can be replaced this way too, and keep
+    /// their own scheme. The handler is given the request as if it was made to `name://localhost/`.
+    ///
+    /// ```rust, ignore
+    /// window().register_protocol("plugin", |request| {
+    ///     Response::builder()
+    ///         .header("Content-Type", "text/html")
+    ///         .body(format!("<h1>{}</h1>", request.uri().path()).into_bytes().into())
+    ///         .unwrap()
+    /// });
+    /// let url = window().protocol_url("plugin");
+    /// ```
+    pub fn register_protocol<F>(&self, name: impl Into<String>, handler: F)
+    where
+        F: Fn(wry::http::Request<Vec<u8>>) -> AssetResponse + 'static,
+    {
+        self.protocols.insert(name.into(), Rc::new(handler));
+    }
+
+    /// Stop serving the custom protocol called `name`. Requests for it get a 404 response.
+    ///
+    /// Returns false if there was no protocol with that name.
+    pub fn remove_protocol(&self, name: &str) -> bool {
+        self.protocols.remove(name)
+    }
+
+    /// The URL the root of the custom protocol called `name` is served at in this window
+    pub fn protocol_url(&self, name: &str) -> String {
+        self.protocols.url(name)
+    }
+
     /// Push an objc view to the window
     #[cfg(target_os = "ios")]
     pub fn push_view(&self, view: objc_id::ShareId<objc::runtime::Object>) {
@@ -385,6 +1735,45 @@ pub enum EventData {
     NewWindow,
 
     CloseWindow,
+
+    ForceCloseWindow,
+
+    Navigate(String),
+
+    /// Edits from a render thread for a page that waits for them over IPC
+    Edits(Vec<u8>),
+
+    FileDrop(wry::webview::FileDropEvent),
+
+    DeepLink(url::Url),
+
+    Download(crate::download::DownloadEvent),
+
+    WebviewCrashed(crate::crash::CrashReason),
+
+    #[cfg(all(
+        feature = "media-session",
+        not(any(target_os = "ios", target_os = "android"))
+    ))]
+    MediaKey(crate::media_session::MediaKeyEvent),
+
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    Connectivity(bool),
+
+    #[cfg(all(
+        feature = "notifications",
+        not(any(target_os = "ios", target_os = "android"))
+    ))]
+    NotificationClicked(crate::notification::NotificationId),
+
+    #[cfg(feature = "remote-inspector")]
+    RemoteInspector(usize),
+
+    #[cfg(feature = "tray")]
+    Tray(crate::tray::TrayEvent),
+
+    #[cfg(feature = "tray")]
+    UpdateTray(crate::tray::TrayUpdate),
 }
 
 #[cfg(target_os = "ios")]
@@ -500,3 +1889,69 @@ impl Drop for WryEventHandler {
         self.handlers.remove(self.id);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::task::ArcWake;
+    use std::convert::TryInto;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[derive(Default)]
+    struct WakeFlag(AtomicBool);
+
+    impl ArcWake for WakeFlag {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Split a response into its length prefixed batches
+    fn batches(mut bytes: &[u8]) -> Vec<&[u8]> {
+        let mut batches = Vec::new();
+        while !bytes.is_empty() {
+            let (len, rest) = bytes.split_at(4);
+            let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+            let (batch, rest) = rest.split_at(len);
+            batches.push(batch);
+            bytes = rest;
+        }
+        batches
+    }
+
+    #[test]
+    fn pending_batches_are_sent_in_one_response() {
+        let queue = EditQueue::default();
+        assert_eq!(queue.add_edits(vec![1, 2, 3]), None);
+        assert_eq!(queue.add_edits(Vec::new()), None);
+        assert_eq!(queue.add_edits(vec![4]), None);
+
+        let response = queue.handle_script_request().unwrap();
+        assert_eq!(batches(&response), [&[1u8, 2, 3][..], &[], &[4]]);
+
+        // The next request waits for the next batch
+        assert_eq!(queue.handle_script_request(), None);
+        let response = queue.add_edits(vec![5]).unwrap();
+        assert_eq!(batches(&response), [&[5u8][..]]);
+    }
+
+    #[test]
+    fn rendering_waits_while_the_queue_is_backed_up() {
+        let queue = EditQueue::default();
+        let drained = Arc::new(WakeFlag::default());
+        let waker = futures_util::task::waker(drained.clone());
+
+        // Just below the limit with the length prefix
+        assert_eq!(queue.add_edits(vec![0; MAX_PENDING_EDIT_BYTES - 5]), None);
+        assert!(!queue.is_backed_up(&waker));
+        assert_eq!(queue.add_edits(vec![1]), None);
+        assert!(queue.is_backed_up(&waker));
+        assert!(!drained.0.load(Ordering::SeqCst));
+
+        let response = queue.handle_script_request().unwrap();
+        assert_eq!(response.len(), MAX_PENDING_EDIT_BYTES + 4);
+        assert_eq!(batches(&response).len(), 2);
+        assert!(drained.0.load(Ordering::SeqCst));
+        assert!(!queue.is_backed_up(&waker));
+    }
+}
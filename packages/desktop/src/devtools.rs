@@ -0,0 +1,313 @@
+//! An in-app devtools panel that shows what the [`Inspector`] of a window knows.
+//!
+//! The panel is a Dioxus app of its own, rendered from hand written templates so the renderer does not depend on the
+//! rsx macro. Wry can only show one webview per window, so the panel is opened in a separate window next to the app.
+
+use std::collections::HashMap;
+use std::rc::Weak;
+
+use dioxus_core::exports::bumpalo;
+use dioxus_core::{Element, Scope, Template, TemplateAttribute, TemplateNode, VNode, VirtualDom};
+
+use crate::inspector::{
+    ComponentInfo, EventInfo, Inspector, InspectorSubscriptionId, WatchedValue,
+};
+use crate::{Config, DesktopService, LogicalSize, WindowBuilder};
+
+/// Open the devtools panel for a window
+pub(crate) fn open_panel(window: &DesktopService) -> Weak<DesktopService> {
+    let dom = VirtualDom::new_with_props(devtools_panel, window.inspector());
+    let cfg = Config::new().with_default_menu_bar(false).with_window(
+        WindowBuilder::new()
+            .with_title(format!("Devtools - {}", window.title()))
+            .with_inner_size(LogicalSize::new(480.0, 720.0)),
+    );
+    window.new_window(dom, cfg)
+}
+
+/// A script that sends the IPC message `method` to the app when the shortcut is pressed.
+///
+/// Shortcuts are written like `ctrl+shift+d`. `cmd` and `super` are aliases of `meta`.
+pub(crate) fn shortcut_script(shortcut: &str, method: &str) -> String {
+    let mut conditions = Vec::new();
+    let mut key = String::new();
+    for part in shortcut.split('+').map(|part| part.trim().to_lowercase()) {
+        match part.as_str() {
+            "ctrl" | "control" => conditions.push("e.ctrlKey".to_string()),
+            "shift" => conditions.push("e.shiftKey".to_string()),
+            "alt" | "option" => conditions.push("e.altKey".to_string()),
+            "meta" | "cmd" | "super" => conditions.push("e.metaKey".to_string()),
+            _ => key = part,
+        }
+    }
+    conditions.push(format!(
+        "e.key.toLowerCase() === {}",
+        serde_json::to_string(&key).unwrap_or_default()
+    ));
+
+    format!(
+        r#"window.addEventListener("keydown", function (e) {{
+            if (window !== window.top) return;
+            if ({}) {{
+                e.preventDefault();
+                window.ipc.postMessage(JSON.stringify({{ method: {}, params: null }}));
+            }}
+        }}, true);"#,
+        conditions.join(" && "),
+        serde_json::to_string(method).unwrap_or_default()
+    )
+}
+
+const STYLE: &str = r#"
+body { margin: 0; font: 13px system-ui, sans-serif; background: #1e1e1e; color: #ddd; }
+main { padding: 8px 12px; }
+h2 { font-size: 12px; text-transform: uppercase; color: #888; margin: 16px 0 4px; }
+ul, ol { list-style: none; margin: 0; padding: 0; }
+li { padding: 2px 0; white-space: nowrap; }
+.name { color: #9cdcfe; margin-right: 8px; }
+.meta { color: #888; }
+"#;
+
+/// A section of the panel with a title and a list of dynamic rows
+macro_rules! section {
+    ($title:literal, $list:literal, $id:literal) => {
+        TemplateNode::Element {
+            tag: "section",
+            namespace: None,
+            attrs: &[],
+            children: &[
+                TemplateNode::Element {
+                    tag: "h2",
+                    namespace: None,
+                    attrs: &[],
+                    children: &[TemplateNode::Text { text: $title }],
+                },
+                TemplateNode::Element {
+                    tag: $list,
+                    namespace: None,
+                    attrs: &[],
+                    children: &[TemplateNode::Dynamic { id: $id }],
+                },
+            ],
+        }
+    };
+}
+
+/// A row with a name and a description
+macro_rules! row {
+    ($attrs:expr) => {
+        TemplateNode::Element {
+            tag: "li",
+            namespace: None,
+            attrs: $attrs,
+            children: &[
+                TemplateNode::Element {
+                    tag: "span",
+                    namespace: None,
+                    attrs: &[TemplateAttribute::Static {
+                        name: "class",
+                        value: "name",
+                        namespace: None,
+                    }],
+                    children: &[TemplateNode::DynamicText { id: 0 }],
+                },
+                TemplateNode::Element {
+                    tag: "span",
+                    namespace: None,
+                    attrs: &[TemplateAttribute::Static {
+                        name: "class",
+                        value: "meta",
+                        namespace: None,
+                    }],
+                    children: &[TemplateNode::DynamicText { id: 1 }],
+                },
+            ],
+        }
+    };
+}
+
+static PANEL: Template = Template {
+    name: concat!(file!(), ":", line!(), ":", column!(), ":0"),
+    roots: &[TemplateNode::Element {
+        tag: "main",
+        namespace: None,
+        attrs: &[],
+        children: &[
+            TemplateNode::Element {
+                tag: "style",
+                namespace: None,
+                attrs: &[],
+                children: &[TemplateNode::Text { text: STYLE }],
+            },
+            section!("Components", "ul", 0),
+            section!("Values", "ul", 1),
+            section!("Events", "ol", 2),
+            TemplateNode::Element {
+                tag: "section",
+                namespace: None,
+                attrs: &[],
+                children: &[
+                    TemplateNode::Element {
+                        tag: "h2",
+                        namespace: None,
+                        attrs: &[],
+                        children: &[TemplateNode::Text { text: "Edits" }],
+                    },
+                    TemplateNode::DynamicText { id: 3 },
+                ],
+            },
+        ],
+    }],
+    node_paths: &[&[0, 1, 1, 0], &[0, 2, 1, 0], &[0, 3, 1, 0], &[0, 4, 1]],
+    attr_paths: &[],
+};
+
+static INDENTED_ROW: Template = Template {
+    name: concat!(file!(), ":", line!(), ":", column!(), ":0"),
+    roots: &[row!(&[TemplateAttribute::Dynamic { id: 0 }])],
+    node_paths: &[&[0, 0, 0], &[0, 1, 0]],
+    attr_paths: &[&[0]],
+};
+
+static ROW: Template = Template {
+    name: concat!(file!(), ":", line!(), ":", column!(), ":0"),
+    roots: &[row!(&[])],
+    node_paths: &[&[0, 0, 0], &[0, 1, 0]],
+    attr_paths: &[],
+};
+
+/// Removes the subscription of the panel when it is closed
+struct PanelSubscription {
+    inspector: Inspector,
+    id: InspectorSubscriptionId,
+}
+
+impl Drop for PanelSubscription {
+    fn drop(&mut self) {
+        self.inspector.unsubscribe(self.id);
+    }
+}
+
+fn devtools_panel(cx: Scope<Inspector>) -> Element {
+    cx.use_hook(|| {
+        let update = cx.schedule_update();
+        PanelSubscription {
+            inspector: cx.props.clone(),
+            id: cx.props.subscribe(move || update()),
+        }
+    });
+
+    let snapshot = cx.props.snapshot();
+
+    let components = sort_tree(&snapshot.components)
+        .into_iter()
+        .map(|(depth, component)| component_row(cx, depth, component));
+    let values = snapshot.values.iter().map(|value| value_row(cx, value));
+    let events = snapshot
+        .events
+        .iter()
+        .rev()
+        .map(|event| event_row(cx, event));
+    let edits = snapshot.edits;
+
+    Some(VNode::new(
+        None,
+        PANEL,
+        bumpalo::collections::Vec::with_capacity_in(1, cx.bump()),
+        cx.bump().alloc([
+            cx.make_node(components),
+            cx.make_node(values),
+            cx.make_node(events),
+            cx.text_node(format_args!(
+                "{} batches, {} bytes total, {} bytes in the last second",
+                edits.batches, edits.bytes, edits.bytes_per_second
+            )),
+        ]),
+        &[],
+    ))
+}
+
+fn component_row<'a>(
+    cx: Scope<'a, Inspector>,
+    depth: usize,
+    component: &ComponentInfo,
+) -> VNode<'a> {
+    VNode::new(
+        None,
+        INDENTED_ROW,
+        bumpalo::collections::Vec::with_capacity_in(1, cx.bump()),
+        cx.bump().alloc([
+            cx.text_node(format_args!("{}", component.name)),
+            cx.text_node(format_args!(
+                "{} renders {}",
+                component.renders,
+                component.location.as_deref().unwrap_or_default()
+            )),
+        ]),
+        cx.bump().alloc([cx.attr(
+            "padding-left",
+            format_args!("{}px", depth * 12),
+            Some("style"),
+            false,
+        )]),
+    )
+}
+
+fn value_row<'a>(cx: Scope<'a, Inspector>, value: &WatchedValue) -> VNode<'a> {
+    VNode::new(
+        None,
+        ROW,
+        bumpalo::collections::Vec::with_capacity_in(1, cx.bump()),
+        cx.bump().alloc([
+            cx.text_node(format_args!("{}", value.name)),
+            cx.text_node(format_args!("{}", value.value)),
+        ]),
+        &[],
+    )
+}
+
+fn event_row<'a>(cx: Scope<'a, Inspector>, event: &EventInfo) -> VNode<'a> {
+    VNode::new(
+        None,
+        ROW,
+        bumpalo::collections::Vec::with_capacity_in(1, cx.bump()),
+        cx.bump().alloc([
+            cx.text_node(format_args!("{}", event.name)),
+            cx.text_node(format_args!(
+                "element {} at {}ms",
+                event.element, event.timestamp_ms
+            )),
+        ]),
+        &[],
+    )
+}
+
+/// Sort the components so every component comes right after its parent, with the depth of the component
+fn sort_tree(components: &[ComponentInfo]) -> Vec<(usize, &ComponentInfo)> {
+    let mut children: HashMap<Option<usize>, Vec<&ComponentInfo>> = HashMap::new();
+    for component in components {
+        children
+            .entry(component.parent)
+            .or_default()
+            .push(component);
+    }
+    for siblings in children.values_mut() {
+        siblings.sort_by_key(|component| component.id);
+    }
+
+    let mut sorted = Vec::with_capacity(components.len());
+    let mut stack: Vec<_> = children
+        .get(&None)
+        .into_iter()
+        .flatten()
+        .rev()
+        .map(|component| (0, *component))
+        .collect();
+    while let Some((depth, component)) = stack.pop() {
+        sorted.push((depth, component));
+        if let Some(siblings) = children.get(&Some(component.id)) {
+            stack.extend(siblings.iter().rev().map(|child| (depth + 1, *child)));
+        }
+    }
+    sorted
+}
@@ -0,0 +1,217 @@
+//! Downloads started by links, `<a download>` elements and blobs in the webview.
+//!
+//! [`Config::with_download_started_handler`](crate::Config::with_download_started_handler) decides where a download
+//! is saved, or cancels it before it starts. Without a handler, downloads are saved in the
+//! [download directory](crate::Config::with_download_directory), or the user picks where to save them in a save
+//! dialog. A download never replaces a file that is already in the download directory. Every window also reports
+//! its downloads to the handlers added with [`use_download_handler`], so components can show them in the UI.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use dioxus_core::ScopeState;
+
+use crate::{use_window, DesktopContext};
+
+/// A download the webview is about to start
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadRequest {
+    /// The url of the file
+    pub url: String,
+    /// Where the file will be saved. It starts as a path in the download directory that no file uses yet. Change
+    /// it to save the file somewhere else.
+    pub destination: PathBuf,
+}
+
+/// Something that happened to a download of the window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadEvent {
+    /// The download was allowed and started
+    Started {
+        /// The url of the file
+        url: String,
+        /// Where the file is saved
+        destination: PathBuf,
+    },
+    /// The download finished or failed
+    Completed {
+        /// The url of the file
+        url: String,
+        /// Where the file was saved, if the webview reports it
+        path: Option<PathBuf>,
+        /// If the whole file was saved
+        success: bool,
+    },
+}
+
+pub(crate) type DownloadStartedHandler = Box<dyn FnMut(&mut DownloadRequest) -> bool>;
+
+pub(crate) type DownloadCallback = Rc<RefCell<dyn FnMut(&DownloadEvent)>>;
+
+/// Pick a path in `directory` for the file at `url` that no file uses yet
+pub(crate) fn default_destination(url: &str, directory: Option<&Path>) -> PathBuf {
+    let name = file_name(url);
+    let directory = directory
+        .map(Path::to_path_buf)
+        .unwrap_or_else(std::env::temp_dir);
+    let destination = directory.join(&name);
+    if !destination.exists() {
+        return destination;
+    }
+
+    // Number the file like browsers do, instead of replacing the file that is already there
+    let name = Path::new(&name);
+    let stem = name.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = name
+        .extension()
+        .map(|extension| extension.to_string_lossy());
+    (1..)
+        .map(|n| match &extension {
+            Some(extension) => directory.join(format!("{} ({}).{}", stem, n, extension)),
+            None => directory.join(format!("{} ({})", stem, n)),
+        })
+        .find(|destination| !destination.exists())
+        .unwrap()
+}
+
+/// Ask the user where to save a download. Returns `None` if they cancelled the download.
+pub(crate) fn prompt_destination(suggested: &Path) -> Option<PathBuf> {
+    let mut dialog = rfd::FileDialog::new();
+    if let Some(directory) = suggested.parent() {
+        dialog = dialog.set_directory(directory);
+    }
+    if let Some(name) = suggested.file_name() {
+        dialog = dialog.set_file_name(&*name.to_string_lossy());
+    }
+    dialog.save_file()
+}
+
+/// The name the file at `url` is saved as
+fn file_name(url: &str) -> String {
+    url.split(['?', '#'].as_ref())
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .map(|name| urlencoding::decode(name).map(|name| name.into_owned()))
+        .and_then(Result::ok)
+        // Don't let a crafted url escape the download directory
+        .filter(|name| !name.is_empty() && !name.contains(['/', '\\'].as_ref()) && name != "..")
+        .unwrap_or_else(|| "download".to_string())
+}
+
+/// The unique identifier of a download handler. This can be used to later remove the handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DownloadHandlerId(pub(crate) usize);
+
+/// A download handler that is scoped to the current component.
+///
+/// This will automatically be removed when the component is unmounted.
+pub struct DownloadHandle {
+    desktop: DesktopContext,
+    /// The unique identifier of the handler.
+    pub id: DownloadHandlerId,
+}
+
+impl DownloadHandle {
+    /// Remove the handler.
+    pub fn remove(&self) {
+        self.desktop.remove_download_handler(self.id);
+    }
+}
+
+impl Drop for DownloadHandle {
+    fn drop(&mut self) {
+        self.remove()
+    }
+}
+
+/// Call `handler` every time a download of the current window starts or completes.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let downloads = use_ref(cx, Vec::new);
+///
+///     use_download_handler(cx, {
+///         to_owned![downloads];
+///         move |event| downloads.write().push(event.clone())
+///     });
+///
+///     render! {
+///         for event in downloads.read().iter() {
+///             match event {
+///                 DownloadEvent::Started { url, .. } => rsx! { p { "Downloading {url}" } },
+///                 DownloadEvent::Completed { url, success, .. } => rsx! { p { "{url}: {success}" } },
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn use_download_handler(
+    cx: &ScopeState,
+    handler: impl FnMut(&DownloadEvent) + 'static,
+) -> &DownloadHandle {
+    let desktop = use_window(cx);
+    cx.use_hook(move || {
+        let desktop = desktop.clone();
+        let id = desktop.create_download_handler(handler);
+        DownloadHandle { desktop, id }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn destination_is_named_after_the_url() {
+        let directory = Path::new("/nonexistent/downloads");
+        assert_eq!(
+            default_destination("https://example.com/files/report.pdf", Some(directory)),
+            directory.join("report.pdf")
+        );
+        assert_eq!(
+            default_destination(
+                "https://example.com/a%20b.txt?version=2#top",
+                Some(directory)
+            ),
+            directory.join("a b.txt")
+        );
+    }
+
+    #[test]
+    fn destination_stays_in_the_directory() {
+        let directory = Path::new("/nonexistent/downloads");
+        for url in [
+            "https://example.com/",
+            "https://example.com/..",
+            "https://example.com/%2E%2E",
+            "https://example.com/..%2Fsecret",
+            "https://example.com/..%5Csecret",
+            "blob:https://example.com/",
+        ] {
+            assert_eq!(
+                default_destination(url, Some(directory)),
+                directory.join("download"),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn destination_does_not_replace_existing_files() {
+        let directory = tempfile::tempdir().unwrap();
+        std::fs::write(directory.path().join("report.pdf"), "old").unwrap();
+        std::fs::write(directory.path().join("report (1).pdf"), "old").unwrap();
+        std::fs::write(directory.path().join("notes"), "old").unwrap();
+
+        assert_eq!(
+            default_destination("https://example.com/report.pdf", Some(directory.path())),
+            directory.path().join("report (2).pdf")
+        );
+        assert_eq!(
+            default_destination("https://example.com/notes", Some(directory.path())),
+            directory.path().join("notes (1)")
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! Windows 11 window attributes.
+//!
+//! The desktop window manager (DWM) draws the frame around every window. On Windows 11 it can round the corners of
+//! the frame, draw a dark titlebar and fill the window with a translucent backdrop material. Older versions of
+//! Windows ignore the attributes they don't know about.
+
+use thiserror::Error;
+use windows_sys::Win32::Graphics::Dwm::{
+    DwmSetWindowAttribute, DWMSBT_AUTO, DWMSBT_MAINWINDOW, DWMSBT_NONE, DWMSBT_TABBEDWINDOW,
+    DWMSBT_TRANSIENTWINDOW, DWMWA_SYSTEMBACKDROP_TYPE, DWMWA_USE_IMMERSIVE_DARK_MODE,
+    DWMWA_WINDOW_CORNER_PREFERENCE, DWMWCP_DEFAULT, DWMWCP_DONOTROUND, DWMWCP_ROUND,
+    DWMWCP_ROUNDSMALL,
+};
+use wry::application::platform::windows::WindowExtWindows;
+use wry::application::window::Window;
+
+/// How the corners of a window are rounded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CornerPreference {
+    /// Let the system decide
+    Default,
+    /// Never round the corners
+    DoNotRound,
+    /// Round the corners if appropriate
+    Round,
+    /// Round the corners with a small radius if appropriate
+    RoundSmall,
+}
+
+/// The material the system draws behind the window's content.
+///
+/// The material is only visible through transparent parts of the page, so the window should be built with
+/// `with_transparent(true)` and the page should not set a background color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BackdropMaterial {
+    /// Let the system decide
+    Auto,
+    /// Don't draw a backdrop
+    None,
+    /// The Mica material used for long lived windows
+    Mica,
+    /// The Acrylic material used for transient windows like popups and dialogs
+    Acrylic,
+    /// The Mica Alt material used for windows with tabs in the titlebar
+    Tabbed,
+}
+
+/// The window manager rejected a window attribute.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("Failed to set the window attribute: HRESULT {hresult:#010x}")]
+pub struct WindowAttributeError {
+    hresult: i32,
+}
+
+impl WindowAttributeError {
+    /// Get the error code the window manager returned
+    pub fn hresult(&self) -> i32 {
+        self.hresult
+    }
+}
+
+/// The window attributes set with [`Config`](crate::Config)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct DwmAttributes {
+    pub(crate) corner_preference: Option<CornerPreference>,
+    pub(crate) dark_titlebar: Option<bool>,
+    pub(crate) backdrop: Option<BackdropMaterial>,
+}
+
+impl DwmAttributes {
+    /// Set the attributes on a window that was just created
+    pub(crate) fn apply(&self, window: &Window) {
+        let results = [
+            self.corner_preference
+                .map(|preference| set_corner_preference(window, preference)),
+            self.dark_titlebar
+                .map(|dark| set_dark_titlebar(window, dark)),
+            self.backdrop.map(|backdrop| set_backdrop(window, backdrop)),
+        ];
+        for result in results.iter().flatten() {
+            if let Err(err) = result {
+                tracing::warn!("{}", err);
+            }
+        }
+    }
+}
+
+fn set_attribute(window: &Window, attribute: u32, value: u32) -> Result<(), WindowAttributeError> {
+    let hresult = unsafe {
+        DwmSetWindowAttribute(
+            window.hwnd() as _,
+            attribute as _,
+            &value as *const u32 as *const _,
+            std::mem::size_of::<u32>() as u32,
+        )
+    };
+    if hresult < 0 {
+        Err(WindowAttributeError { hresult })
+    } else {
+        Ok(())
+    }
+}
+
+pub(crate) fn set_corner_preference(
+    window: &Window,
+    preference: CornerPreference,
+) -> Result<(), WindowAttributeError> {
+    let value = match preference {
+        CornerPreference::Default => DWMWCP_DEFAULT,
+        CornerPreference::DoNotRound => DWMWCP_DONOTROUND,
+        CornerPreference::Round => DWMWCP_ROUND,
+        CornerPreference::RoundSmall => DWMWCP_ROUNDSMALL,
+    };
+    set_attribute(window, DWMWA_WINDOW_CORNER_PREFERENCE as u32, value as u32)
+}
+
+pub(crate) fn set_dark_titlebar(window: &Window, dark: bool) -> Result<(), WindowAttributeError> {
+    set_attribute(window, DWMWA_USE_IMMERSIVE_DARK_MODE as u32, dark as u32)
+}
+
+pub(crate) fn set_backdrop(
+    window: &Window,
+    backdrop: BackdropMaterial,
+) -> Result<(), WindowAttributeError> {
+    let value = match backdrop {
+        BackdropMaterial::Auto => DWMSBT_AUTO,
+        BackdropMaterial::None => DWMSBT_NONE,
+        BackdropMaterial::Mica => DWMSBT_MAINWINDOW,
+        BackdropMaterial::Acrylic => DWMSBT_TRANSIENTWINDOW,
+        BackdropMaterial::Tabbed => DWMSBT_TABBEDWINDOW,
+    };
+    set_attribute(window, DWMWA_SYSTEMBACKDROP_TYPE as u32, value as u32)
+}
@@ -0,0 +1,41 @@
+//! How the edits of the VirtualDom reach the interpreter in the page.
+//!
+//! By default the interpreter long-polls the custom protocol: it fetches `/edits`, and the request is answered once
+//! there are edits. Every frame pays for a request through the custom protocol handler. With the script transport
+//! the interpreter asks for edits over IPC instead, and the edits are pushed to it with a script, which is a lot
+//! cheaper on every platform. The edits are coalesced and held back in the same way for both transports.
+
+use base64::Engine;
+use wry::webview::WebView;
+
+/// How the edits of the VirtualDom reach the interpreter in the page. Set it with
+/// [`Config::with_edit_transport`](crate::Config::with_edit_transport).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EditTransport {
+    /// The interpreter long-polls the custom protocol for edits. The default.
+    #[default]
+    Request,
+    /// The interpreter asks for edits over IPC, and they are evaluated in the page as a script. This cuts the time
+    /// and CPU every frame takes, for apps that render many frames a second.
+    Script,
+}
+
+impl EditTransport {
+    /// The script that tells the interpreter how to wait for edits, or `None` for the default
+    pub(crate) fn script(&self) -> Option<&'static str> {
+        match self {
+            EditTransport::Request => None,
+            EditTransport::Script => Some("window.__dioxusEditTransport = \"script\";"),
+        }
+    }
+}
+
+/// Push edit batches, framed like the response to a request for edits, to the interpreter of `webview`
+pub(crate) fn push(webview: &WebView, bytes: &[u8]) {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    if let Err(err) =
+        webview.evaluate_script(&format!("window.interpreter.push_edits(\"{encoded}\");"))
+    {
+        tracing::error!("Failed to push edits to the webview: {}", err);
+    }
+}
@@ -1,9 +1,15 @@
 use dioxus_core::ElementId;
 use dioxus_html::{geometry::euclid::Rect, MountedResult, RenderedElementBacking};
+use serde::de::DeserializeOwned;
 
-use crate::{desktop_context::DesktopContext, query::QueryEngine};
+use crate::{
+    desktop_context::DesktopContext,
+    query::{QueryEngine, QueryError},
+};
 
 /// A mounted element passed to onmounted events
+///
+/// Get it from the event with [`MountedData::downcast`](dioxus_html::MountedData::downcast).
 pub struct DesktopElement {
     id: ElementId,
     webview: DesktopContext,
@@ -14,6 +20,54 @@ impl DesktopElement {
     pub(crate) fn new(id: ElementId, webview: DesktopContext, query: QueryEngine) -> Self {
         Self { id, webview, query }
     }
+
+    /// The id of the element in the virtual dom
+    pub fn id(&self) -> ElementId {
+        self.id
+    }
+
+    /// The window the element is in
+    pub fn window(&self) -> &DesktopContext {
+        &self.webview
+    }
+
+    /// Run `script` in the webview with the element bound to `element`, and deserialize the value it returns.
+    ///
+    /// ```rust, ignore
+    /// let width: f64 = element.query("return element.scrollWidth;").await?;
+    /// ```
+    pub async fn query<V: DeserializeOwned>(&self, script: &str) -> Result<V, QueryError> {
+        let script = format!(
+            "const element = window.interpreter.getNode({});\n{}",
+            self.id.0, script
+        );
+        self.query
+            .new_query::<V>(&script, self.webview.webview.clone())
+            .resolve()
+            .await
+    }
+
+    /// Run an interpreter function that returns false if it can't be applied to the element
+    fn run_element_query(
+        &self,
+        script: &str,
+    ) -> std::pin::Pin<Box<dyn futures_util::Future<Output = dioxus_html::MountedResult<()>>>> {
+        let fut = self
+            .query
+            .new_query::<bool>(script, self.webview.webview.clone())
+            .resolve();
+        Box::pin(async move {
+            match fut.await {
+                Ok(true) => Ok(()),
+                Ok(false) => MountedResult::Err(dioxus_html::MountedError::OperationFailed(
+                    Box::new(DesktopQueryError::FailedToQuery),
+                )),
+                Err(err) => {
+                    MountedResult::Err(dioxus_html::MountedError::OperationFailed(Box::new(err)))
+                }
+            }
+        })
+    }
 }
 
 impl RenderedElementBacking for DesktopElement {
@@ -34,7 +88,7 @@ impl RenderedElementBacking for DesktopElement {
 
         let fut = self
             .query
-            .new_query::<Option<Rect<f64, f64>>>(&script, self.webview.clone())
+            .new_query::<Option<Rect<f64, f64>>>(&script, self.webview.webview.clone())
             .resolve();
         Box::pin(async move {
             match fut.await {
@@ -61,7 +115,7 @@ impl RenderedElementBacking for DesktopElement {
 
         let fut = self
             .query
-            .new_query::<bool>(&script, self.webview.clone())
+            .new_query::<bool>(&script, self.webview.webview.clone())
             .resolve();
         Box::pin(async move {
             match fut.await {
@@ -87,7 +141,7 @@ impl RenderedElementBacking for DesktopElement {
 
         let fut = self
             .query
-            .new_query::<bool>(&script, self.webview.clone())
+            .new_query::<bool>(&script, self.webview.webview.clone())
             .resolve();
 
         Box::pin(async move {
@@ -102,6 +156,48 @@ impl RenderedElementBacking for DesktopElement {
             }
         })
     }
+
+    fn show_dialog(
+        &self,
+        modal: bool,
+    ) -> std::pin::Pin<Box<dyn futures_util::Future<Output = dioxus_html::MountedResult<()>>>> {
+        self.run_element_query(&format!(
+            "return window.interpreter.showDialog({}, {});",
+            self.id.0, modal
+        ))
+    }
+
+    fn close_dialog(
+        &self,
+        return_value: Option<String>,
+    ) -> std::pin::Pin<Box<dyn futures_util::Future<Output = dioxus_html::MountedResult<()>>>> {
+        self.run_element_query(&format!(
+            "return window.interpreter.closeDialog({}, {});",
+            self.id.0,
+            serde_json::to_string(&return_value).expect("Failed to serialize the return value")
+        ))
+    }
+
+    fn toggle_popover(
+        &self,
+        open: bool,
+    ) -> std::pin::Pin<Box<dyn futures_util::Future<Output = dioxus_html::MountedResult<()>>>> {
+        self.run_element_query(&format!(
+            "return window.interpreter.togglePopover({}, {});",
+            self.id.0, open
+        ))
+    }
+
+    fn set_pointer_capture(
+        &self,
+        pointer_id: i32,
+        capture: bool,
+    ) -> std::pin::Pin<Box<dyn futures_util::Future<Output = dioxus_html::MountedResult<()>>>> {
+        self.run_element_query(&format!(
+            "return window.interpreter.setPointerCapture({}, {}, {});",
+            self.id.0, pointer_id, capture
+        ))
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,159 @@
+//! Settings of the webview engines that only some platforms have.
+//!
+//! Each setting is ignored on the platforms whose webview doesn't have it, so one config can tune every engine the app
+//! runs on.
+
+use wry::webview::WebView;
+
+/// When WebKitGTK draws the page with the GPU
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebKitHardwareAcceleration {
+    /// Use the GPU when the page needs it, like for 3D transforms and WebGL. The default of WebKitGTK.
+    OnDemand,
+    /// Always draw the page with the GPU
+    Always,
+    /// Never draw the page with the GPU
+    Never,
+}
+
+/// Settings of the webview engines that only some platforms have. Set them with
+/// [`Config::with_engine_settings`](crate::Config::with_engine_settings).
+///
+/// ```rust, ignore
+/// let engine = EngineSettings::new()
+///     .with_browser_arg("--enable-features=msOverlayScrollbarWinStyle")
+///     .with_inspectable(true)
+///     .with_webkit_hardware_acceleration(WebKitHardwareAcceleration::Never);
+/// dioxus_desktop::launch_cfg(app, Config::new().with_engine_settings(engine));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EngineSettings {
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    browser_args: Vec<String>,
+    autoplay: Option<bool>,
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    inspectable: Option<bool>,
+    #[cfg_attr(not(all(target_os = "linux", feature = "webkitgtk")), allow(dead_code))]
+    webkit_hardware_acceleration: Option<WebKitHardwareAcceleration>,
+}
+
+/// The arguments wry gives WebView2 when no other arguments are set. Setting arguments replaces them, so they are
+/// repeated before the arguments of the app.
+#[cfg(target_os = "windows")]
+const DEFAULT_BROWSER_ARGS: &str = "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
+
+impl EngineSettings {
+    /// Keep the defaults of every engine
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start WebView2 with an additional command line argument, like `--enable-features=...`. This can be called
+    /// multiple times. It only has an effect on Windows, and only the arguments of the first window of the app are
+    /// used, because every window shares one browser process.
+    pub fn with_browser_arg(mut self, arg: impl Into<String>) -> Self {
+        self.browser_args.push(arg.into());
+        self
+    }
+
+    /// Let media play without a user gesture. By default WKWebView and WebView2 wait for the user to interact with
+    /// the page before playing media with sound.
+    pub fn with_autoplay(mut self, autoplay: bool) -> Self {
+        self.autoplay = Some(autoplay);
+        self
+    }
+
+    /// Let Safari's Web Inspector attach to the webview in release builds. It only has an effect on macOS 13.3 and
+    /// later, where WKWebView can't be inspected unless this is set.
+    pub fn with_inspectable(mut self, inspectable: bool) -> Self {
+        self.inspectable = Some(inspectable);
+        self
+    }
+
+    /// Set when WebKitGTK draws the page with the GPU. It only has an effect on Linux with the `webkitgtk` feature,
+    /// and unlike [`Config::with_hardware_acceleration`](crate::Config::with_hardware_acceleration) it applies to
+    /// this window only.
+    pub fn with_webkit_hardware_acceleration(mut self, policy: WebKitHardwareAcceleration) -> Self {
+        self.webkit_hardware_acceleration = Some(policy);
+        self
+    }
+
+    pub(crate) fn autoplay(&self) -> Option<bool> {
+        self.autoplay
+    }
+
+    /// The arguments to start WebView2 with, followed by `extra`, or `None` to keep the defaults of wry
+    #[cfg(target_os = "windows")]
+    pub(crate) fn browser_args(
+        &self,
+        hardware_acceleration: bool,
+        extra: &[String],
+    ) -> Option<String> {
+        if hardware_acceleration && self.browser_args.is_empty() && extra.is_empty() {
+            return None;
+        }
+        let base = if hardware_acceleration {
+            DEFAULT_BROWSER_ARGS
+        } else {
+            crate::rendering::SOFTWARE_BROWSER_ARGS
+        };
+        let mut args = vec![base];
+        args.extend(self.browser_args.iter().chain(extra).map(String::as_str));
+        Some(args.join(" "))
+    }
+
+    /// Apply the settings that are set on the webview after it is created
+    pub(crate) fn apply(&self, webview: &WebView) {
+        #[cfg(target_os = "macos")]
+        if let Some(inspectable) = self.inspectable {
+            platform::set_inspectable(webview, inspectable);
+        }
+
+        #[cfg(all(target_os = "linux", feature = "webkitgtk"))]
+        if let Some(policy) = self.webkit_hardware_acceleration {
+            platform::set_hardware_acceleration(webview, policy);
+        }
+
+        #[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "webkitgtk"))))]
+        let _ = webview;
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc::runtime::{Object, BOOL, NO, YES};
+    use objc::{msg_send, sel, sel_impl};
+    use wry::webview::{WebView, WebviewExtMacOS};
+
+    pub(super) fn set_inspectable(webview: &WebView, inspectable: bool) {
+        let webview: *mut Object = webview.webview();
+        unsafe {
+            // `inspectable` was added in macOS 13.3. Before it, every webview can be inspected in debug builds.
+            let supported: BOOL = msg_send![webview, respondsToSelector: sel!(setInspectable:)];
+            if supported == NO {
+                tracing::debug!("WKWebView can't be made inspectable before macOS 13.3");
+                return;
+            }
+            let inspectable: BOOL = if inspectable { YES } else { NO };
+            let _: () = msg_send![webview, setInspectable: inspectable];
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "webkitgtk"))]
+mod platform {
+    use super::WebKitHardwareAcceleration;
+    use webkit2gtk::{HardwareAccelerationPolicy, SettingsExt, WebViewExt};
+    use wry::webview::{WebView, WebviewExtUnix};
+
+    pub(super) fn set_hardware_acceleration(webview: &WebView, policy: WebKitHardwareAcceleration) {
+        let webview = webview.webview();
+        if let Some(settings) = WebViewExt::settings(&*webview) {
+            settings.set_hardware_acceleration_policy(match policy {
+                WebKitHardwareAcceleration::OnDemand => HardwareAccelerationPolicy::OnDemand,
+                WebKitHardwareAcceleration::Always => HardwareAccelerationPolicy::Always,
+                WebKitHardwareAcceleration::Never => HardwareAccelerationPolicy::Never,
+            });
+        }
+    }
+}
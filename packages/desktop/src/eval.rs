@@ -32,8 +32,9 @@ pub struct DesktopEvaluator {
 impl DesktopEvaluator {
     /// Creates a new evaluator for desktop-based targets.
     pub fn new(desktop_ctx: DesktopContext, js: String) -> Self {
-        let ctx = desktop_ctx.clone();
-        let query = desktop_ctx.query.new_query(&js, ctx);
+        let query = desktop_ctx
+            .query
+            .new_query(&js, desktop_ctx.webview.clone());
 
         Self {
             query: Rc::new(RefCell::new(query)),
@@ -0,0 +1,164 @@
+//! Listeners for events of the window or the document of the page, like `keydown`, `visibilitychange` and `paste`.
+//!
+//! The page adds the listener and sends every event to Rust. Events can't be passed across the bridge, so the
+//! handler receives a [`GlobalEvent`] with the properties of the event that are strings, numbers or booleans.
+
+use dioxus_core::ScopeState;
+use serde_json::Value;
+
+use crate::{use_window, DesktopContext};
+
+/// Defines `window.__dioxusListeners` in the page, which adds and removes the listeners
+pub(crate) const EVENT_LISTENER_SCRIPT: &str = r#"(function () {
+    if (window !== window.top) return;
+    const listeners = new Map();
+
+    const serialize = (event) => {
+        const data = {};
+        for (const key in event) {
+            const value = event[key];
+            if (value === null || ["string", "number", "boolean"].includes(typeof value)) {
+                data[key] = value;
+            }
+        }
+        if (event.clipboardData) {
+            data.text = event.clipboardData.getData("text/plain");
+        }
+        if (event.type === "visibilitychange") {
+            data.visibilityState = document.visibilityState;
+        }
+        return data;
+    };
+
+    window.__dioxusListeners = {
+        add(id, target, name) {
+            const element = target === "document" ? document : window;
+            const listener = (event) =>
+                window.ipc.postMessage(
+                    JSON.stringify({ method: "global_event", params: { id, data: serialize(event) } })
+                );
+            element.addEventListener(name, listener);
+            listeners.set(id, { element, name, listener });
+        },
+        remove(id) {
+            const entry = listeners.get(id);
+            if (!entry) return;
+            listeners.delete(id);
+            entry.element.removeEventListener(entry.name, entry.listener);
+        },
+    };
+})();"#;
+
+/// The object a listener is added to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ListenerTarget {
+    /// `window`
+    Window,
+    /// `document`
+    Document,
+}
+
+impl ListenerTarget {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ListenerTarget::Window => "window",
+            ListenerTarget::Document => "document",
+        }
+    }
+}
+
+/// An event of the window or the document of the page
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobalEvent {
+    data: Value,
+}
+
+impl GlobalEvent {
+    pub(crate) fn new(data: Value) -> Self {
+        Self { data }
+    }
+
+    /// The type of the event, like `keydown`
+    pub fn event_type(&self) -> &str {
+        self.data["type"].as_str().unwrap_or_default()
+    }
+
+    /// Get a property of the event, like `ctrlKey`
+    pub fn get(&self, property: &str) -> Option<&Value> {
+        self.data.get(property)
+    }
+
+    /// The key of a keyboard event
+    pub fn key(&self) -> Option<&str> {
+        self.data["key"].as_str()
+    }
+
+    /// The text of a `paste`, `copy` or `cut` event
+    pub fn text(&self) -> Option<&str> {
+        self.data["text"].as_str()
+    }
+
+    /// The visibility state of the document after a `visibilitychange` event, like `hidden`
+    pub fn visibility_state(&self) -> Option<&str> {
+        self.data["visibilityState"].as_str()
+    }
+
+    /// All the properties of the event that could be sent from the page
+    pub fn data(&self) -> &Value {
+        &self.data
+    }
+}
+
+/// The unique identifier of an event listener. This can be used to later remove the listener.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventListenerId(pub(crate) usize);
+
+/// An event listener that is scoped to the current component.
+///
+/// This will automatically be removed when the component is unmounted.
+pub struct EventListenerHandle {
+    desktop: DesktopContext,
+    /// The unique identifier of the listener.
+    pub id: EventListenerId,
+}
+
+impl EventListenerHandle {
+    /// Remove the listener.
+    pub fn remove(&self) {
+        self.desktop.remove_event_listener(self.id);
+    }
+}
+
+impl Drop for EventListenerHandle {
+    fn drop(&mut self) {
+        self.remove()
+    }
+}
+
+/// Call `handler` with every `event` of the window or the document of the page, for the lifetime of the component.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let pasted = use_state(cx, String::new);
+///
+///     use_event_listener(cx, ListenerTarget::Document, "paste", {
+///         to_owned![pasted];
+///         move |event| pasted.set(event.text().unwrap_or_default().to_string())
+///     });
+///
+///     render! { "Pasted: {pasted}" }
+/// }
+/// ```
+pub fn use_event_listener(
+    cx: &ScopeState,
+    target: ListenerTarget,
+    event: &str,
+    handler: impl FnMut(GlobalEvent) + 'static,
+) -> &EventListenerHandle {
+    let desktop = use_window(cx);
+    cx.use_hook(move || {
+        let desktop = desktop.clone();
+        let id = desktop.create_event_listener(target, event, handler);
+        EventListenerHandle { desktop, id }
+    })
+}
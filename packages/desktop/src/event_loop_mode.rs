@@ -0,0 +1,97 @@
+//! How the event loop waits for events, and how often windows render.
+//!
+//! By default the event loop polls: it runs again as soon as it handled the last events, which keeps the latency low
+//! but keeps a CPU core busy. Apps that are idle most of the time can let it sleep until the next event instead, and
+//! windows can be paused, or limited to a number of renders per second.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Instant;
+
+use wry::application::event_loop::ControlFlow;
+use wry::application::window::WindowId;
+
+use crate::WebviewHandler;
+
+/// How the event loop waits for events. Set it with
+/// [`Config::with_event_loop_mode`](crate::Config::with_event_loop_mode), or change it while the app runs with
+/// [`DesktopService::set_event_loop_mode`](crate::DesktopService::set_event_loop_mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EventLoopMode {
+    /// Run the event loop again as soon as it handled the last events. This has the lowest latency, and keeps a CPU
+    /// core busy. The default.
+    #[default]
+    Poll,
+    /// Sleep until the next event, like input, a task of a VirtualDom waking up, or a throttled render being due.
+    /// This saves power, and global shortcuts registered through the XDG portal are only seen with the next event.
+    Wait,
+    /// Poll while a window of the app has focus, and sleep until the next event while none has
+    Adaptive,
+}
+
+impl EventLoopMode {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => EventLoopMode::Wait,
+            2 => EventLoopMode::Adaptive,
+            _ => EventLoopMode::Poll,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            EventLoopMode::Poll => 0,
+            EventLoopMode::Wait => 1,
+            EventLoopMode::Adaptive => 2,
+        }
+    }
+}
+
+/// The mode of the event loop. There is only one event loop per app.
+static MODE: AtomicU8 = AtomicU8::new(0);
+
+pub(crate) fn mode() -> EventLoopMode {
+    EventLoopMode::from_u8(MODE.load(Ordering::Relaxed))
+}
+
+pub(crate) fn set_mode(mode: EventLoopMode) {
+    MODE.store(mode.to_u8(), Ordering::Relaxed);
+}
+
+/// The control flow of the event loop for the current mode and the throttled renders of `webviews`
+pub(crate) fn control_flow(webviews: &HashMap<WindowId, WebviewHandler>) -> ControlFlow {
+    let poll = match mode() {
+        EventLoopMode::Poll => true,
+        EventLoopMode::Wait => false,
+        EventLoopMode::Adaptive => webviews
+            .values()
+            .any(|view| view.desktop_context.webview.window().is_focused()),
+    };
+    if poll {
+        return ControlFlow::Poll;
+    }
+
+    let next_render = webviews
+        .values()
+        .filter_map(|view| view.desktop_context.render_deadline.get())
+        .min();
+    match next_render {
+        Some(deadline) => ControlFlow::WaitUntil(deadline),
+        None => ControlFlow::Wait,
+    }
+}
+
+/// The windows whose throttled render is due
+pub(crate) fn due_renders(webviews: &HashMap<WindowId, WebviewHandler>) -> Vec<WindowId> {
+    let now = Instant::now();
+    webviews
+        .iter()
+        .filter(|(_, view)| {
+            view.desktop_context
+                .render_deadline
+                .get()
+                .map_or(false, |deadline| deadline <= now)
+        })
+        .map(|(id, _)| *id)
+        .collect()
+}
@@ -0,0 +1,175 @@
+//! Native dialogs to open files and folders, and to pick where to save a file.
+//!
+//! The dialogs are modal to the window that opened them and don't block the event loop, so the app keeps rendering
+//! while they are open. They are shown with [`DesktopService::open_file_dialog`](crate::DesktopService::open_file_dialog),
+//! [`DesktopService::open_folder_dialog`](crate::DesktopService::open_folder_dialog) and
+//! [`DesktopService::save_file_dialog`](crate::DesktopService::save_file_dialog):
+//!
+//! ```rust, ignore
+//! let window = use_window(cx);
+//! let open = move |_| {
+//!     let window = window.clone();
+//!     cx.spawn(async move {
+//!         let options = FileDialogOptions::new().with_filter("Images", &["png", "jpg"]);
+//!         if let Some(files) = window.open_file_dialog(options).await {
+//!             println!("Picked {:?}", files);
+//!         }
+//!     });
+//! };
+//! ```
+
+use std::future::Future;
+use std::path::PathBuf;
+
+use wry::application::window::Window;
+
+/// The options of a file dialog
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileDialogOptions {
+    title: Option<String>,
+    directory: Option<PathBuf>,
+    file_name: Option<String>,
+    filters: Vec<(String, Vec<String>)>,
+    multiple: bool,
+}
+
+impl FileDialogOptions {
+    /// Create options for a dialog without a title or filters, that picks a single file or folder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the title of the dialog
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Open the dialog in `directory`
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Suggest a name for the file in a save dialog
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Only show files with one of `extensions`, like `&["png", "jpg"]`. This can be called multiple times to let the
+    /// user choose between filters.
+    pub fn with_filter(mut self, name: impl Into<String>, extensions: &[&str]) -> Self {
+        let extensions = extensions.iter().map(|ext| ext.to_string()).collect();
+        self.filters.push((name.into(), extensions));
+        self
+    }
+
+    /// Let the user pick multiple files or folders
+    pub fn with_multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+
+    fn dialog(&self, parent: &Window) -> rfd::AsyncFileDialog {
+        let mut dialog = rfd::AsyncFileDialog::new().set_parent(parent);
+        if let Some(title) = &self.title {
+            dialog = dialog.set_title(title);
+        }
+        if let Some(directory) = &self.directory {
+            dialog = dialog.set_directory(directory);
+        }
+        if let Some(file_name) = &self.file_name {
+            dialog = dialog.set_file_name(file_name);
+        }
+        for (name, extensions) in &self.filters {
+            dialog = dialog.add_filter(name, extensions.as_slice());
+        }
+        dialog
+    }
+
+    #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+    fn portal_filters(&self) -> Vec<ashpd::desktop::file_chooser::FileFilter> {
+        self.filters
+            .iter()
+            .map(|(name, extensions)| {
+                extensions.iter().fold(
+                    ashpd::desktop::file_chooser::FileFilter::new(name),
+                    |filter, extension| filter.glob(&format!("*.{}", extension)),
+                )
+            })
+            .collect()
+    }
+
+    /// Show a dialog that picks files, or folders if `folders` is true
+    #[cfg_attr(
+        not(all(target_os = "linux", feature = "xdg-portal")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn pick(
+        &self,
+        parent: &Window,
+        folders: bool,
+        use_portal: bool,
+    ) -> impl Future<Output = Option<Vec<PathBuf>>> {
+        let multiple = self.multiple;
+        #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+        let portal =
+            use_portal.then(|| crate::portal::pick_files(folders, multiple, self.portal_filters()));
+        let dialog = self.dialog(parent);
+
+        async move {
+            #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+            if let Some(portal) = portal {
+                return from_portal(portal.await).filter(|files| !files.is_empty());
+            }
+
+            let files = match (folders, multiple) {
+                (false, false) => dialog.pick_file().await.map(|file| vec![file]),
+                (false, true) => dialog.pick_files().await,
+                (true, false) => dialog.pick_folder().await.map(|folder| vec![folder]),
+                (true, true) => dialog.pick_folders().await,
+            };
+            files.map(|files| files.iter().map(|file| file.path().to_path_buf()).collect())
+        }
+    }
+
+    /// Show a dialog that picks where to save a file
+    #[cfg_attr(
+        not(all(target_os = "linux", feature = "xdg-portal")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn save(
+        &self,
+        parent: &Window,
+        use_portal: bool,
+    ) -> impl Future<Output = Option<PathBuf>> {
+        #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+        let portal = use_portal.then(|| crate::portal::save_file(self.file_name.clone()));
+        let dialog = self.dialog(parent);
+
+        async move {
+            #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+            if let Some(portal) = portal {
+                return from_portal(portal.await);
+            }
+
+            dialog
+                .save_file()
+                .await
+                .map(|file| file.path().to_path_buf())
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+fn from_portal<T>(result: Result<T, crate::portal::PortalError>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(crate::portal::PortalError::Cancelled) => None,
+        Err(err) => {
+            tracing::error!("Failed to open the file chooser portal: {}", err);
+            None
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Files dropped on the window, delivered as drag events to the element under the cursor.
+//!
+//! The webview doesn't give the page the paths of the files the operating system drops on the window, so the drop is
+//! intercepted before it reaches the page. The element under the cursor gets `ondragenter`, `ondragover`,
+//! `ondragleave` and `ondrop` events like it would in a browser, and [`DragData::files`] holds the dropped files:
+//!
+//! ```rust, ignore
+//! rsx! {
+//!     div {
+//!         ondragover: move |_| hovered.set(true),
+//!         ondragleave: move |_| hovered.set(false),
+//!         ondrop: move |evt| {
+//!             if let Some(files) = &evt.files {
+//!                 println!("Dropped {:?}", files.files());
+//!             }
+//!         },
+//!         "Drop files here"
+//!     }
+//! }
+//! ```
+//!
+//! [`DragData::files`]: dioxus_html::DragData::files
+
+use wry::webview::FileDropEvent;
+
+/// Defines `window.__dioxusFileDrop` in the page, which finds the element under the cursor and sends it drag events
+pub(crate) const FILE_DROP_SCRIPT: &str = r#"(function () {
+    if (window !== window.top) return;
+    let hovered = null;
+
+    function target(x, y) {
+        const element = document.elementFromPoint(x, y);
+        return element ? element.closest("[data-dioxus-id]") : null;
+    }
+
+    function send(name, element, x, y) {
+        if (!element) return;
+        const rect = element.getBoundingClientRect();
+        const mouse = {
+            alt_key: false,
+            button: 0,
+            buttons: 1,
+            client_x: x,
+            client_y: y,
+            ctrl_key: false,
+            meta_key: false,
+            offset_x: Math.round(x - rect.left),
+            offset_y: Math.round(y - rect.top),
+            page_x: Math.round(x + window.scrollX),
+            page_y: Math.round(y + window.scrollY),
+            screen_x: Math.round(x + window.screenX),
+            screen_y: Math.round(y + window.screenY),
+            shift_key: false,
+        };
+        window.ipc.postMessage(
+            JSON.stringify({
+                method: "file_drop",
+                params: {
+                    name,
+                    element: parseInt(element.getAttribute("data-dioxus-id")),
+                    bubbles: name !== "dragenter",
+                    data: { mouse },
+                },
+            })
+        );
+    }
+
+    // The operating system reports the position in physical pixels
+    function position(x, y) {
+        return [Math.round(x / window.devicePixelRatio), Math.round(y / window.devicePixelRatio)];
+    }
+
+    window.__dioxusFileDrop = {
+        over(x, y) {
+            [x, y] = position(x, y);
+            const element = target(x, y);
+            if (element !== hovered) {
+                send("dragleave", hovered, x, y);
+                send("dragenter", element, x, y);
+                hovered = element;
+            }
+            send("dragover", element, x, y);
+        },
+        drop(x, y) {
+            [x, y] = position(x, y);
+            send("drop", target(x, y), x, y);
+            hovered = null;
+        },
+        leave() {
+            send("dragleave", hovered, 0, 0);
+            hovered = null;
+        },
+    };
+})();"#;
+
+/// The script that turns `event` into drag events in the page
+pub(crate) fn drop_script(event: &FileDropEvent) -> Option<String> {
+    match event {
+        FileDropEvent::Hovered { position, .. } => Some(format!(
+            "window.__dioxusFileDrop.over({}, {});",
+            position.x, position.y
+        )),
+        FileDropEvent::Dropped { position, .. } => Some(format!(
+            "window.__dioxusFileDrop.drop({}, {});",
+            position.x, position.y
+        )),
+        FileDropEvent::Cancelled => Some("window.__dioxusFileDrop.leave();".to_string()),
+        _ => None,
+    }
+}
@@ -52,13 +52,7 @@ pub(crate) fn get_file_event(request: &FileDialogRequest) -> Vec<PathBuf> {
         request: &FileDialogRequest,
         mut dialog: rfd::FileDialog,
     ) -> Vec<PathBuf> {
-        let filters: Vec<_> = request
-            .accept
-            .as_deref()
-            .unwrap_or_default()
-            .split(',')
-            .filter_map(|s| Filters::from_str(s).ok())
-            .collect();
+        let filters = request.filters();
 
         let file_extensions: Vec<_> = filters
             .iter()
@@ -85,6 +79,54 @@ pub(crate) fn get_file_event(request: &FileDialogRequest) -> Vec<PathBuf> {
     }
 }
 
+/// Let the user pick files with the XDG file chooser portal
+#[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+pub(crate) fn get_file_event_from_portal(request: &FileDialogRequest) -> Vec<PathBuf> {
+    let mut filter = None;
+    if !request.directory {
+        for f in request.filters() {
+            let current =
+                filter.unwrap_or_else(|| ashpd::desktop::file_chooser::FileFilter::new("name"));
+            filter = Some(match &f {
+                // The portal understands mime types, so they don't need to be turned into extensions
+                Filters::Mime(mime) => current.mimetype(mime),
+                _ => f
+                    .as_extensions()
+                    .into_iter()
+                    .fold(current, |filter, extension| {
+                        filter.glob(&format!("*.{}", extension))
+                    }),
+            });
+        }
+    }
+
+    let files = crate::portal::pick_files(
+        request.directory,
+        request.multiple,
+        filter.into_iter().collect(),
+    );
+    match tokio::runtime::Handle::current().block_on(files) {
+        Ok(files) => files,
+        Err(crate::portal::PortalError::Cancelled) => Vec::new(),
+        Err(err) => {
+            tracing::error!("Failed to open the file chooser portal: {}", err);
+            Vec::new()
+        }
+    }
+}
+
+impl FileDialogRequest {
+    fn filters(&self) -> Vec<Filters> {
+        self.accept
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|s| !s.is_empty())
+            .filter_map(|s| Filters::from_str(s).ok())
+            .collect()
+    }
+}
+
 enum Filters {
     Extension(String),
     Mime(String),
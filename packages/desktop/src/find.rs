@@ -0,0 +1,572 @@
+//! Finding text in the page.
+//!
+//! Each platform searches with the find API of its webview where it has one: `WebKitFindController` on Linux, and
+//! `findString:withConfiguration:completionHandler:` of WKWebView on macOS 13 and later. On Linux this needs the
+//! `webkitgtk` feature. These find text the way the find bar of a browser does, including matches that span several
+//! elements.
+//!
+//! The WebView2 SDK that wry builds on has no find API, so on Windows, and on older macOS or Linux without the
+//! feature, the DOM is searched with a script instead. Neither WebKit API can match whole words, so searches with
+//! [`FindOptions::with_whole_word`] use the script on every platform. The script only finds text in the DOM, so text
+//! in canvases, images and cross-origin iframes is not found, and neither are matches that span several text nodes.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+use wry::webview::WebView;
+
+use crate::query::{Query, QueryEngine, QueryError};
+
+/// Installs the find engine into the webview if it is not already there.
+///
+/// Every match is tracked as a DOM `Range` so the page itself is never mutated. Matches are painted with the
+/// CSS Custom Highlight API (`::highlight(dioxus-find)` and `::highlight(dioxus-find-active)`) when the webview
+/// supports it, and the active match is always selected and scrolled into view.
+const FIND_ENGINE: &str = r#"
+if (!window.__dioxus_find) {
+    const style = document.createElement("style");
+    style.textContent = `
+        ::highlight(dioxus-find) { background-color: #fff176; color: inherit; }
+        ::highlight(dioxus-find-active) { background-color: #ff9632; color: inherit; }
+    `;
+    document.head.appendChild(style);
+
+    const isWordChar = (text, index) => index >= 0 && index < text.length && /[\p{L}\p{N}_]/u.test(text[index]);
+    const hasHighlights = () => window.CSS && CSS.highlights && typeof Highlight === "function";
+
+    window.__dioxus_find = {
+        ranges: [],
+        active: -1,
+        options: { case_sensitive: false, whole_word: false, wrap_around: true },
+
+        result() {
+            return {
+                found: this.ranges.length > 0,
+                matches: this.ranges.length,
+                active_match: this.active >= 0 ? this.active : null
+            };
+        },
+
+        clear() {
+            this.ranges = [];
+            this.active = -1;
+            if (hasHighlights()) {
+                CSS.highlights.delete("dioxus-find");
+                CSS.highlights.delete("dioxus-find-active");
+            }
+            window.getSelection().removeAllRanges();
+            return this.result();
+        },
+
+        search(query, options) {
+            this.clear();
+            this.options = options;
+            if (!query) {
+                return this.result();
+            }
+
+            const walker = document.createTreeWalker(document.body, NodeFilter.SHOW_TEXT, {
+                acceptNode(node) {
+                    const parent = node.parentElement;
+                    if (!parent || ["SCRIPT", "STYLE", "NOSCRIPT", "TEMPLATE"].includes(parent.tagName)) {
+                        return NodeFilter.FILTER_REJECT;
+                    }
+                    // Skip text that is not rendered
+                    if (parent.getClientRects().length === 0) {
+                        return NodeFilter.FILTER_REJECT;
+                    }
+                    return NodeFilter.FILTER_ACCEPT;
+                }
+            });
+
+            const needle = options.case_sensitive ? query : query.toLowerCase();
+            let node;
+            while ((node = walker.nextNode())) {
+                const text = options.case_sensitive ? node.data : node.data.toLowerCase();
+                let index = text.indexOf(needle);
+                while (index !== -1) {
+                    const end = index + needle.length;
+                    if (!options.whole_word || (!isWordChar(text, index - 1) && !isWordChar(text, end))) {
+                        const range = document.createRange();
+                        range.setStart(node, index);
+                        range.setEnd(node, end);
+                        this.ranges.push(range);
+                    }
+                    index = text.indexOf(needle, end);
+                }
+            }
+
+            if (hasHighlights() && this.ranges.length > 0) {
+                CSS.highlights.set("dioxus-find", new Highlight(...this.ranges));
+            }
+
+            if (this.ranges.length > 0) {
+                this.activate(0);
+            }
+            return this.result();
+        },
+
+        step(delta) {
+            const count = this.ranges.length;
+            if (count === 0) {
+                return this.result();
+            }
+
+            let next = this.active + delta;
+            if (next < 0 || next >= count) {
+                if (!this.options.wrap_around) {
+                    return this.result();
+                }
+                next = (next + count) % count;
+            }
+            this.activate(next);
+            return this.result();
+        },
+
+        activate(index) {
+            this.active = index;
+            const range = this.ranges[index];
+            if (hasHighlights()) {
+                CSS.highlights.set("dioxus-find-active", new Highlight(range));
+            }
+            const selection = window.getSelection();
+            selection.removeAllRanges();
+            selection.addRange(range);
+            range.startContainer.parentElement.scrollIntoView({ block: "center", inline: "nearest" });
+        }
+    };
+}
+"#;
+
+/// Options that control how [`DesktopService::find_in_page`](crate::DesktopService::find_in_page) matches text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FindOptions {
+    case_sensitive: bool,
+    whole_word: bool,
+    wrap_around: bool,
+}
+
+impl Default for FindOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive: false,
+            whole_word: false,
+            wrap_around: true,
+        }
+    }
+}
+
+impl FindOptions {
+    /// Create the default find options: case insensitive, matching inside words, and wrapping around the page.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether matches must have the same case as the query.
+    ///
+    /// > Note: `case_sensitive` is `false` by default.
+    pub fn with_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// Set whether matches must start and end on a word boundary.
+    ///
+    /// The find APIs of the webviews can't match whole words, so these searches always search the DOM with a script.
+    ///
+    /// > Note: `whole_word` is `false` by default.
+    pub fn with_whole_word(mut self, whole_word: bool) -> Self {
+        self.whole_word = whole_word;
+        self
+    }
+
+    /// Set whether moving past the last (or first) match jumps back to the other end of the page.
+    ///
+    /// > Note: `wrap_around` is `true` by default.
+    pub fn with_wrap_around(mut self, wrap_around: bool) -> Self {
+        self.wrap_around = wrap_around;
+        self
+    }
+}
+
+/// The state of a search in the page after a find operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct FindResult {
+    /// Whether the page has a match
+    pub found: bool,
+    /// The number of matches in the page, or `None` if the webview doesn't count them. WKWebView on macOS doesn't.
+    pub matches: Option<usize>,
+    /// The index of the selected match, or `None` if there are no matches or the webview doesn't say which match is
+    /// selected. WKWebView on macOS doesn't.
+    pub active_match: Option<usize>,
+}
+
+type FindReceiver = tokio::sync::oneshot::Receiver<FindResult>;
+
+/// What runs the current search of a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FindEngine {
+    /// The find API of the webview
+    Native,
+    /// The script in [`FIND_ENGINE`]
+    Script,
+}
+
+/// The search in the page of a window
+#[derive(Default)]
+pub(crate) struct FindInPage {
+    // The next and previous matches are asked from the engine that started the search
+    engine: Cell<Option<FindEngine>>,
+    native: platform::NativeFind,
+}
+
+impl FindInPage {
+    /// Start a new search for `query`, replacing the current one
+    pub(crate) async fn search(
+        &self,
+        query_engine: &QueryEngine,
+        webview: &Rc<WebView>,
+        query: &str,
+        options: FindOptions,
+    ) -> Result<FindResult, QueryError> {
+        self.clear(query_engine, webview).await?;
+        match self.native.search(webview, query, options) {
+            Some(result) => {
+                self.engine.set(Some(FindEngine::Native));
+                native_result(result).await
+            }
+            None => {
+                self.engine.set(Some(FindEngine::Script));
+                find_query(query_engine, webview.clone(), &search_call(query, options))
+                    .resolve()
+                    .await
+            }
+        }
+    }
+
+    /// Move to the next match of the current search, or the previous one if `forward` is false
+    pub(crate) async fn step(
+        &self,
+        query_engine: &QueryEngine,
+        webview: &Rc<WebView>,
+        forward: bool,
+    ) -> Result<FindResult, QueryError> {
+        if self.engine.get() == Some(FindEngine::Native) {
+            if let Some(result) = self.native.step(webview, forward) {
+                return native_result(result).await;
+            }
+        }
+        let call = if forward { "step(1)" } else { "step(-1)" };
+        find_query(query_engine, webview.clone(), call)
+            .resolve()
+            .await
+    }
+
+    /// End the current search and clear the highlighted matches
+    pub(crate) async fn clear(
+        &self,
+        query_engine: &QueryEngine,
+        webview: &Rc<WebView>,
+    ) -> Result<(), QueryError> {
+        match self.engine.take() {
+            Some(FindEngine::Native) => {
+                self.native.clear(webview);
+                Ok(())
+            }
+            Some(FindEngine::Script) => find_query(query_engine, webview.clone(), "clear()")
+                .resolve()
+                .await
+                .map(|_| ()),
+            None => Ok(()),
+        }
+    }
+}
+
+async fn native_result(result: FindReceiver) -> Result<FindResult, QueryError> {
+    result
+        .await
+        .map_err(|_| QueryError::Recv(RecvError::Closed))
+}
+
+/// Run a method of the find engine in the webview and return its result.
+fn find_query(engine: &QueryEngine, webview: Rc<WebView>, call: &str) -> Query<FindResult> {
+    let script = format!("{FIND_ENGINE}\nreturn window.__dioxus_find.{call};");
+    engine.new_query(&script, webview)
+}
+
+/// Build the call that starts a new search for `query`.
+fn search_call(query: &str, options: FindOptions) -> String {
+    format!(
+        "search({}, {})",
+        serde_json::to_string(query).expect("Failed to serialize find query"),
+        serde_json::to_string(&options).expect("Failed to serialize FindOptions")
+    )
+}
+
+#[cfg(all(target_os = "linux", feature = "webkitgtk"))]
+mod platform {
+    use super::*;
+    use std::cell::RefCell;
+    use webkit2gtk::{FindController, FindControllerExt, WebViewExt};
+    use wry::webview::WebviewExtUnix;
+
+    /// The search of a `WebKitFindController`. WebKit counts the matches, but doesn't say which one is selected, so
+    /// the selected match is followed here as the search moves through them.
+    #[derive(Default)]
+    pub(crate) struct NativeFind {
+        state: Rc<RefCell<SearchState>>,
+        // The controller of the webview, created with its signals connected on the first search
+        controller: RefCell<Option<FindController>>,
+    }
+
+    #[derive(Default)]
+    struct SearchState {
+        matches: usize,
+        active: Option<usize>,
+        // The step the controller is running, and where its result is sent
+        pending: Option<(Step, tokio::sync::oneshot::Sender<FindResult>)>,
+    }
+
+    impl SearchState {
+        fn result(&self) -> FindResult {
+            FindResult {
+                found: self.matches > 0,
+                matches: Some(self.matches),
+                active_match: self.active,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    enum Step {
+        Search,
+        Next,
+        Previous,
+    }
+
+    impl NativeFind {
+        pub(super) fn search(
+            &self,
+            webview: &WebView,
+            query: &str,
+            options: FindOptions,
+        ) -> Option<FindReceiver> {
+            // WebKit can only match the start of a word
+            if query.is_empty() || options.whole_word {
+                return None;
+            }
+            let controller = self.controller(webview)?;
+
+            let mut find_options = webkit2gtk::FindOptions::empty();
+            if !options.case_sensitive {
+                find_options |= webkit2gtk::FindOptions::CASE_INSENSITIVE;
+            }
+            if options.wrap_around {
+                find_options |= webkit2gtk::FindOptions::WRAP_AROUND;
+            }
+
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            *self.state.borrow_mut() = SearchState {
+                pending: Some((Step::Search, tx)),
+                ..Default::default()
+            };
+            controller.search(query, find_options.bits(), u32::MAX);
+            Some(rx)
+        }
+
+        pub(super) fn step(&self, _webview: &WebView, forward: bool) -> Option<FindReceiver> {
+            let controller = self.controller.borrow().clone()?;
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let step = if forward { Step::Next } else { Step::Previous };
+            self.state.borrow_mut().pending = Some((step, tx));
+            if forward {
+                controller.search_next();
+            } else {
+                controller.search_previous();
+            }
+            Some(rx)
+        }
+
+        pub(super) fn clear(&self, _webview: &WebView) {
+            if let Some(controller) = self.controller.borrow().as_ref() {
+                controller.search_finish();
+            }
+            *self.state.borrow_mut() = SearchState::default();
+        }
+
+        fn controller(&self, webview: &WebView) -> Option<FindController> {
+            let mut controller = self.controller.borrow_mut();
+            if controller.is_none() {
+                let find_controller = webview.webview().find_controller()?;
+                find_controller.connect_found_text({
+                    let state = self.state.clone();
+                    move |_, matches| {
+                        let mut state = state.borrow_mut();
+                        let Some((step, tx)) = state.pending.take() else {
+                            return;
+                        };
+                        let matches = (matches as usize).max(1);
+                        // The controller wraps around only if the search asked for it, otherwise it fails instead
+                        let active = match (step, state.active) {
+                            (Step::Next, Some(active)) => (active + 1) % matches,
+                            (Step::Previous, Some(active)) => (active + matches - 1) % matches,
+                            _ => 0,
+                        };
+                        state.matches = matches;
+                        state.active = Some(active);
+                        _ = tx.send(state.result());
+                    }
+                });
+                find_controller.connect_failed_to_find_text({
+                    let state = self.state.clone();
+                    move |_| {
+                        let mut state = state.borrow_mut();
+                        let Some((step, tx)) = state.pending.take() else {
+                            return;
+                        };
+                        // Stepping past the last match without wrapping around keeps the selected match
+                        if let Step::Search = step {
+                            state.matches = 0;
+                            state.active = None;
+                        }
+                        _ = tx.send(state.result());
+                    }
+                });
+                *controller = Some(find_controller);
+            }
+            controller.clone()
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use block::ConcreteBlock;
+    use objc::runtime::{Object, BOOL, NO, YES};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::cell::RefCell;
+    use std::ffi::CString;
+    use wry::webview::WebviewExtMacOS;
+
+    unsafe fn ns_string(value: &str) -> *mut Object {
+        let value = CString::new(value).unwrap_or_default();
+        msg_send![class!(NSString), stringWithUTF8String: value.as_ptr()]
+    }
+
+    fn ns_bool(value: bool) -> BOOL {
+        if value {
+            YES
+        } else {
+            NO
+        }
+    }
+
+    /// The search of a WKWebView. WKWebView keeps no state for a search, so the query is searched again from the
+    /// selected match to move to the next or previous one.
+    #[derive(Default)]
+    pub(crate) struct NativeFind {
+        search: RefCell<Option<(String, FindOptions)>>,
+    }
+
+    impl NativeFind {
+        pub(super) fn search(
+            &self,
+            webview: &WebView,
+            query: &str,
+            options: FindOptions,
+        ) -> Option<FindReceiver> {
+            if query.is_empty() || options.whole_word {
+                return None;
+            }
+            let result = find(webview, query, options, false)?;
+            *self.search.borrow_mut() = Some((query.to_string(), options));
+            Some(result)
+        }
+
+        pub(super) fn step(&self, webview: &WebView, forward: bool) -> Option<FindReceiver> {
+            let (query, options) = self.search.borrow().clone()?;
+            find(webview, &query, options, !forward)
+        }
+
+        pub(super) fn clear(&self, webview: &WebView) {
+            self.search.take();
+            // WKWebView shows the match it found as the selection of the page
+            _ = webview.evaluate_script("window.getSelection().removeAllRanges();");
+        }
+    }
+
+    fn find(
+        webview: &WebView,
+        query: &str,
+        options: FindOptions,
+        backwards: bool,
+    ) -> Option<FindReceiver> {
+        let wk_webview = webview.webview();
+        unsafe {
+            // WKWebView can only search the page since macOS 13
+            let supported: BOOL = msg_send![
+                wk_webview,
+                respondsToSelector: sel!(findString:withConfiguration:completionHandler:)
+            ];
+            if supported != YES {
+                return None;
+            }
+
+            let configuration: *mut Object = msg_send![class!(WKFindConfiguration), new];
+            let _: () = msg_send![configuration, setBackwards: ns_bool(backwards)];
+            let _: () = msg_send![configuration, setCaseSensitive: ns_bool(options.case_sensitive)];
+            let _: () = msg_send![configuration, setWraps: ns_bool(options.wrap_around)];
+
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let tx = std::cell::Cell::new(Some(tx));
+            let handler = ConcreteBlock::new(move |result: *mut Object| {
+                let found: BOOL = unsafe { msg_send![result, matchFound] };
+                if let Some(tx) = tx.take() {
+                    _ = tx.send(FindResult {
+                        found: found == YES,
+                        matches: None,
+                        active_match: None,
+                    });
+                }
+            })
+            .copy();
+            let _: () = msg_send![
+                wk_webview,
+                findString: ns_string(query)
+                withConfiguration: configuration
+                completionHandler: &*handler
+            ];
+            let _: () = msg_send![configuration, release];
+            Some(rx)
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", all(target_os = "linux", feature = "webkitgtk"))))]
+mod platform {
+    use super::*;
+
+    /// The webview has no find API, so every search runs the script
+    #[derive(Default)]
+    pub(crate) struct NativeFind;
+
+    impl NativeFind {
+        pub(super) fn search(
+            &self,
+            _webview: &WebView,
+            _query: &str,
+            _options: FindOptions,
+        ) -> Option<FindReceiver> {
+            None
+        }
+
+        pub(super) fn step(&self, _webview: &WebView, _forward: bool) -> Option<FindReceiver> {
+            None
+        }
+
+        pub(super) fn clear(&self, _webview: &WebView) {}
+    }
+}
@@ -0,0 +1,50 @@
+//! Detect when the user stops using the device.
+//!
+//! The idle time is read from the operating system, so input to any app counts as activity. [`use_idle`] polls the
+//! idle time. Without the `tokio_runtime` feature the user is never reported as idle.
+//!
+//! This needs the `idle` feature. On Linux the idle time is read from the X screensaver extension, so it links to
+//! libXss.
+
+use std::time::Duration;
+
+use dioxus_core::ScopeState;
+
+use crate::poll::use_polled;
+
+/// How often [`use_idle`] reads the idle time
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Get how long it has been since the user last pressed a key, moved the mouse or touched the screen.
+///
+/// Returns `None` if the operating system doesn't report the idle time.
+pub fn idle_time() -> Option<Duration> {
+    user_idle::UserIdle::get_time()
+        .ok()
+        .map(|idle| Duration::from_secs(idle.as_seconds()))
+}
+
+/// Get whether the user has not used the device for at least `threshold`. The component rerenders when the user
+/// goes idle and when they become active again.
+///
+/// The threshold is read when the component is first rendered. Changing it later has no effect.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let idle = use_idle(cx, Duration::from_secs(5 * 60));
+///
+///     render! {
+///         if idle {
+///             rsx! { LockScreen {} }
+///         }
+///     }
+/// }
+/// ```
+pub fn use_idle(cx: &ScopeState, threshold: Duration) -> bool {
+    use_polled(
+        cx,
+        IDLE_POLL_INTERVAL,
+        || false,
+        move || matches!(idle_time(), Some(time) if time >= threshold),
+    )
+}
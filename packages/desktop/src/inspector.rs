@@ -0,0 +1,305 @@
+//! The inspector records what the devtools need to know about a window: the component tree, values the app chose to
+//! expose, the events the window handled, and how much data was sent over the edit channel.
+//!
+//! Recording is cheap, and the component tree is only collected while something is subscribed to the inspector.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::{Rc, Weak};
+use std::time::{Duration, Instant};
+
+use dioxus_core::{ElementId, ScopeState, VirtualDom};
+use serde::Serialize;
+use slab::Slab;
+
+use crate::use_window;
+
+/// The number of events the inspector keeps around
+const MAX_EVENTS: usize = 100;
+
+/// The window edit throughput is measured over
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(1);
+
+/// A mounted component
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentInfo {
+    /// The id of the scope of the component
+    pub id: usize,
+    /// The id of the scope of the parent component
+    pub parent: Option<usize>,
+    /// The name of the component
+    pub name: String,
+    /// The location of the `rsx!` call that rendered the component
+    pub location: Option<String>,
+    /// The depth of the component in the tree
+    pub height: u32,
+    /// The number of times the component rendered
+    pub renders: usize,
+}
+
+/// An event that was dispatched to the VirtualDom
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventInfo {
+    /// The name of the event, like `click`
+    pub name: String,
+    /// The element the event was dispatched to
+    pub element: usize,
+    /// If the event bubbles
+    pub bubbles: bool,
+    /// The time the event was dispatched, in milliseconds since the window was created
+    pub timestamp_ms: u64,
+}
+
+/// Statistics about the edits sent to the webview
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct EditStats {
+    /// The number of edit batches sent
+    pub batches: u64,
+    /// The total number of bytes sent
+    pub bytes: u64,
+    /// The number of bytes sent over the last second
+    pub bytes_per_second: u64,
+}
+
+/// A value the app exposes to the devtools
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WatchedValue {
+    /// The name the value was registered with
+    pub name: String,
+    /// The current value
+    pub value: String,
+}
+
+/// Everything the inspector knows about a window at one point in time
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct InspectorSnapshot {
+    /// The mounted components, in no particular order
+    pub components: Vec<ComponentInfo>,
+    /// The values the app exposes
+    pub values: Vec<WatchedValue>,
+    /// The most recent events, oldest first
+    pub events: Vec<EventInfo>,
+    /// Statistics about the edit channel
+    pub edits: EditStats,
+}
+
+/// The unique identifier of a watched value. This can be used to later remove the value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WatchId(usize);
+
+/// The unique identifier of an inspector subscription. This can be used to later remove the subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InspectorSubscriptionId(usize);
+
+type WatchedValueFn = (String, Box<dyn Fn() -> String>);
+
+struct InspectorInner {
+    created: Instant,
+    components: RefCell<Vec<ComponentInfo>>,
+    events: RefCell<VecDeque<EventInfo>>,
+    edits: Cell<EditStats>,
+    recent_edits: RefCell<VecDeque<(Instant, usize)>>,
+    values: RefCell<Slab<WatchedValueFn>>,
+    subscribers: RefCell<Slab<Box<dyn Fn()>>>,
+}
+
+impl Default for InspectorInner {
+    fn default() -> Self {
+        Self {
+            created: Instant::now(),
+            components: Default::default(),
+            events: Default::default(),
+            edits: Default::default(),
+            recent_edits: Default::default(),
+            values: Default::default(),
+            subscribers: Default::default(),
+        }
+    }
+}
+
+/// Records the state of a window for the devtools.
+///
+/// Get the inspector of a window with [`DesktopService::inspector`](crate::DesktopService::inspector).
+#[derive(Clone, Default)]
+pub struct Inspector {
+    inner: Rc<InspectorInner>,
+}
+
+impl Inspector {
+    /// Expose a value to the devtools. `value` is called every time the devtools update.
+    ///
+    /// The id this function returns can be used to remove the value with [`Inspector::unwatch`]
+    pub fn watch(&self, name: impl Into<String>, value: impl Fn() -> String + 'static) -> WatchId {
+        let id = self
+            .inner
+            .values
+            .borrow_mut()
+            .insert((name.into(), Box::new(value)));
+        WatchId(id)
+    }
+
+    /// Remove a value created with [`Inspector::watch`]
+    pub fn unwatch(&self, id: WatchId) {
+        self.inner.values.borrow_mut().try_remove(id.0);
+    }
+
+    /// Call `callback` every time the window renders or handles an event.
+    ///
+    /// The component tree is only collected while the inspector has subscribers.
+    pub fn subscribe(&self, callback: impl Fn() + 'static) -> InspectorSubscriptionId {
+        let id = self
+            .inner
+            .subscribers
+            .borrow_mut()
+            .insert(Box::new(callback));
+        InspectorSubscriptionId(id)
+    }
+
+    /// Remove a subscription created with [`Inspector::subscribe`]
+    pub fn unsubscribe(&self, id: InspectorSubscriptionId) {
+        self.inner.subscribers.borrow_mut().try_remove(id.0);
+    }
+
+    /// Get everything the inspector knows about the window.
+    pub fn snapshot(&self) -> InspectorSnapshot {
+        let values = self
+            .inner
+            .values
+            .borrow()
+            .iter()
+            .map(|(_, (name, value))| WatchedValue {
+                name: name.clone(),
+                value: value(),
+            })
+            .collect();
+
+        InspectorSnapshot {
+            components: self.inner.components.borrow().clone(),
+            values,
+            events: self.inner.events.borrow().iter().cloned().collect(),
+            edits: self.edit_stats(),
+        }
+    }
+
+    fn edit_stats(&self) -> EditStats {
+        let mut recent = self.inner.recent_edits.borrow_mut();
+        let now = Instant::now();
+        while matches!(recent.front(), Some((sent, _)) if now.duration_since(*sent) > THROUGHPUT_WINDOW)
+        {
+            recent.pop_front();
+        }
+
+        EditStats {
+            bytes_per_second: recent.iter().map(|(_, bytes)| *bytes as u64).sum(),
+            ..self.inner.edits.get()
+        }
+    }
+
+    pub(crate) fn downgrade(&self) -> WeakInspector {
+        WeakInspector(Rc::downgrade(&self.inner))
+    }
+
+    pub(crate) fn is_observed(&self) -> bool {
+        !self.inner.subscribers.borrow().is_empty()
+    }
+
+    pub(crate) fn record_event(&self, name: &str, element: ElementId, bubbles: bool) {
+        let mut events = self.inner.events.borrow_mut();
+        if events.len() == MAX_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(EventInfo {
+            name: name.to_string(),
+            element: element.0,
+            bubbles,
+            timestamp_ms: self.inner.created.elapsed().as_millis() as u64,
+        });
+    }
+
+    pub(crate) fn record_edits(&self, bytes: usize) {
+        let mut stats = self.inner.edits.get();
+        stats.batches += 1;
+        stats.bytes += bytes as u64;
+        self.inner.edits.set(stats);
+
+        if self.is_observed() {
+            self.inner
+                .recent_edits
+                .borrow_mut()
+                .push_back((Instant::now(), bytes));
+        }
+    }
+
+    /// Collect the component tree after the VirtualDom rendered and let the subscribers know
+    pub(crate) fn record_render(&self, dom: &VirtualDom) {
+        if !self.is_observed() {
+            return;
+        }
+
+        *self.inner.components.borrow_mut() = dom
+            .scopes()
+            .map(|scope| ComponentInfo {
+                id: scope.scope_id().0,
+                parent: scope.parent().map(|parent| parent.0),
+                name: scope.name().to_string(),
+                location: scope.location().map(ToString::to_string),
+                height: scope.height(),
+                renders: scope.generation(),
+            })
+            .collect();
+
+        for (_, subscriber) in self.inner.subscribers.borrow().iter() {
+            subscriber();
+        }
+    }
+}
+
+/// An inspector that does not keep the window state alive
+pub(crate) struct WeakInspector(Weak<InspectorInner>);
+
+impl WeakInspector {
+    pub(crate) fn upgrade(&self) -> Option<Inspector> {
+        self.0.upgrade().map(|inner| Inspector { inner })
+    }
+}
+
+/// A value exposed to the devtools that is scoped to the current component.
+///
+/// This will automatically be removed when the component is unmounted.
+pub struct WatchHandle {
+    inspector: Inspector,
+    /// The unique identifier of the value.
+    pub id: WatchId,
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.inspector.unwatch(self.id);
+    }
+}
+
+/// Expose a value to the devtools while the component is mounted. `value` is called every time the devtools update.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let count = use_state(cx, || 0);
+///     use_inspected_value(cx, "count", {
+///         let count = count.clone();
+///         move || count.current().to_string()
+///     });
+///
+///     render! { button { onclick: move |_| count += 1, "{count}" } }
+/// }
+/// ```
+pub fn use_inspected_value(
+    cx: &ScopeState,
+    name: impl Into<String>,
+    value: impl Fn() -> String + 'static,
+) -> &WatchHandle {
+    let window = use_window(cx);
+    cx.use_hook(|| {
+        let inspector = window.inspector();
+        let id = inspector.watch(name, value);
+        WatchHandle { inspector, id }
+    })
+}
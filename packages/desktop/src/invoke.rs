@@ -0,0 +1,294 @@
+//! Rust functions the page can call and await.
+//!
+//! Handlers are registered by name with
+//! [`DesktopService::register_invoke_handler`](crate::DesktopService::register_invoke_handler) or
+//! [`use_invoke_handler`], and the page calls them with `dioxus.invoke`:
+//!
+//! ```js
+//! try {
+//!     const path = await dioxus.invoke("save_file", { name: "notes.txt", contents });
+//! } catch (error) {
+//!     console.error(error.message);
+//! }
+//! ```
+//!
+//! Arguments and results are serialized as JSON. The promise is rejected with the error of the handler, or if no
+//! handler is registered with the name.
+
+use std::cell::{Cell, RefCell};
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use dioxus_core::ScopeState;
+use rustc_hash::FxHashMap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use wry::webview::WebView;
+
+use crate::{use_window, DesktopContext};
+
+/// Defines `window.dioxus.invoke` in the page
+pub(crate) const INVOKE_SCRIPT: &str = r#"(function () {
+    if (window !== window.top) return;
+    const pending = new Map();
+    let nextId = 0;
+
+    window.__dioxusInvoke = {
+        settle(id, ok, value) {
+            const call = pending.get(id);
+            if (!call) return;
+            pending.delete(id);
+            if (ok) {
+                call.resolve(value);
+            } else {
+                call.reject(new Error(value));
+            }
+        },
+    };
+
+    window.dioxus = window.dioxus || {};
+    window.dioxus.invoke = (name, args) =>
+        new Promise((resolve, reject) => {
+            const id = nextId++;
+            pending.set(id, { resolve, reject });
+            window.ipc.postMessage(
+                JSON.stringify({ method: "invoke", params: { id, name, args: args === undefined ? null : args } })
+            );
+        });
+})();"#;
+
+/// An error that rejects a call from the page
+#[derive(Error, Debug)]
+pub enum InvokeError {
+    /// No handler is registered with the name the page called
+    #[error("No invoke handler is registered with the name `{0}`")]
+    UnknownHandler(String),
+    /// The page passed arguments that don't match the type the handler expects
+    #[error("Failed to deserialize the arguments: {0}")]
+    Arguments(serde_json::Error),
+    /// The value the handler returned could not be serialized to JSON
+    #[error("Failed to serialize the result: {0}")]
+    Result(serde_json::Error),
+    /// The handler returned an error
+    #[error("{0}")]
+    Handler(String),
+}
+
+type InvokeFuture = Pin<Box<dyn Future<Output = Result<Value, InvokeError>>>>;
+
+type InvokeHandler = Rc<dyn Fn(Value) -> InvokeFuture>;
+
+/// The invoke handlers of a window, by name
+#[derive(Default)]
+pub(crate) struct InvokeRegistry {
+    /// The handler of every name, and the id of the registration that added it
+    handlers: RefCell<FxHashMap<String, (usize, InvokeHandler)>>,
+    next_registration: Cell<usize>,
+}
+
+#[derive(Deserialize)]
+struct InvokeCall {
+    id: u64,
+    name: String,
+    #[serde(default)]
+    args: Value,
+}
+
+impl InvokeRegistry {
+    /// Register `handler` under `name`, replacing the handler registered with it before. Returns an id for this
+    /// registration that never repeats.
+    pub(crate) fn register<Args, T, E, F, Fut>(&self, name: String, handler: F) -> usize
+    where
+        Args: DeserializeOwned + 'static,
+        T: Serialize + 'static,
+        E: Display + 'static,
+        F: Fn(Args) -> Fut + 'static,
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        let handler: InvokeHandler = Rc::new(move |args| {
+            let args = serde_json::from_value::<Args>(args);
+            let result = args.map(&handler);
+            Box::pin(async move {
+                let value = result
+                    .map_err(InvokeError::Arguments)?
+                    .await
+                    .map_err(|err| InvokeError::Handler(err.to_string()))?;
+                serde_json::to_value(value).map_err(InvokeError::Result)
+            })
+        });
+        let registration = self.next_registration.get();
+        self.next_registration.set(registration + 1);
+        self.handlers
+            .borrow_mut()
+            .insert(name, (registration, handler));
+        registration
+    }
+
+    pub(crate) fn remove(&self, name: &str) {
+        self.handlers.borrow_mut().remove(name);
+    }
+
+    /// Remove the handler of `name` if it is still the one `registration` added
+    pub(crate) fn remove_registration(&self, name: &str, registration: usize) {
+        let mut handlers = self.handlers.borrow_mut();
+        if matches!(handlers.get(name), Some((id, _)) if *id == registration) {
+            handlers.remove(name);
+        }
+    }
+
+    /// Start a call the page made with `dioxus.invoke`.
+    ///
+    /// The returned future runs the handler and settles the promise in the page with its result.
+    pub(crate) fn call(
+        &self,
+        params: Value,
+        webview: Rc<WebView>,
+    ) -> Option<impl Future<Output = ()>> {
+        let call = match serde_json::from_value::<InvokeCall>(params) {
+            Ok(call) => call,
+            Err(err) => {
+                tracing::error!("Invalid invoke call from the page: {}", err);
+                return None;
+            }
+        };
+
+        // Clone the handler out of the registry so handlers can register or remove handlers while they run
+        let handler = self
+            .handlers
+            .borrow()
+            .get(&call.name)
+            .map(|(_, handler)| handler.clone());
+        let future = handler.map(|handler| handler(call.args));
+
+        Some(async move {
+            let result = match future {
+                Some(future) => future.await,
+                None => Err(InvokeError::UnknownHandler(call.name)),
+            };
+            settle(&webview, call.id, result);
+        })
+    }
+}
+
+/// Resolve or reject the promise of a call in the page
+fn settle(webview: &WebView, id: u64, result: Result<Value, InvokeError>) {
+    let (ok, value) = match result {
+        Ok(value) => (true, value),
+        Err(err) => (false, Value::String(err.to_string())),
+    };
+    if let Err(err) = webview.evaluate_script(&format!(
+        "window.__dioxusInvoke.settle({}, {}, {});",
+        id, ok, value
+    )) {
+        tracing::error!(
+            "Failed to send the result of an invoke call to the page: {}",
+            err
+        );
+    }
+}
+
+/// An invoke handler that is scoped to the current component.
+///
+/// This will automatically be removed when the component is unmounted.
+pub struct InvokeHandle {
+    desktop: DesktopContext,
+    /// The name the page calls the handler with
+    pub name: String,
+    registration: usize,
+}
+
+impl InvokeHandle {
+    /// Remove the handler. A handler another component registered with the same name since is left alone.
+    pub fn remove(&self) {
+        self.desktop
+            .invoke_handlers
+            .remove_registration(&self.name, self.registration);
+    }
+}
+
+impl Drop for InvokeHandle {
+    fn drop(&mut self) {
+        self.remove()
+    }
+}
+
+/// Register a handler the page can call with `dioxus.invoke(name, args)` for the lifetime of the component.
+///
+/// ```rust, ignore
+/// #[derive(Deserialize)]
+/// struct SaveArgs {
+///     name: String,
+///     contents: String,
+/// }
+///
+/// fn app(cx: Scope) -> Element {
+///     use_invoke_handler(cx, "save_file", |args: SaveArgs| async move {
+///         let path = std::env::temp_dir().join(args.name);
+///         tokio::fs::write(&path, args.contents).await?;
+///         Ok::<_, std::io::Error>(path)
+///     });
+///
+///     render! { "..." }
+/// }
+/// ```
+pub fn use_invoke_handler<Args, T, E, F, Fut>(
+    cx: &ScopeState,
+    name: &str,
+    handler: F,
+) -> &InvokeHandle
+where
+    Args: DeserializeOwned + 'static,
+    T: Serialize + 'static,
+    E: Display + 'static,
+    F: Fn(Args) -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+{
+    let desktop = use_window(cx);
+    cx.use_hook(move || {
+        let desktop = desktop.clone();
+        let registration = desktop.invoke_handlers.register(name.to_string(), handler);
+        InvokeHandle {
+            desktop,
+            name: name.to_string(),
+            registration,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::FutureExt;
+
+    fn returns(value: u32) -> impl Fn(Value) -> std::future::Ready<Result<u32, String>> {
+        move |_| std::future::ready(Ok(value))
+    }
+
+    fn call(registry: &InvokeRegistry, name: &str) -> Option<Value> {
+        let handler = registry
+            .handlers
+            .borrow()
+            .get(name)
+            .map(|(_, handler)| handler.clone())?;
+        handler(Value::Null).now_or_never()?.ok()
+    }
+
+    #[test]
+    fn removing_a_replaced_registration_keeps_the_new_handler() {
+        let registry = InvokeRegistry::default();
+        let first = registry.register("load".to_string(), returns(1));
+        let second = registry.register("load".to_string(), returns(2));
+        assert_ne!(first, second);
+
+        // The component that registered first unmounts after the second one mounted
+        registry.remove_registration("load", first);
+        assert_eq!(call(&registry, "load"), Some(Value::from(2)));
+
+        registry.remove_registration("load", second);
+        assert_eq!(call(&registry, "load"), None);
+    }
+}
@@ -0,0 +1,209 @@
+//! Typed message channels between Rust and the page.
+//!
+//! A channel is identified by its name. Rust opens it with
+//! [`DesktopService::ipc_channel`](crate::DesktopService::ipc_channel) or [`use_ipc_channel`], and the page with
+//! `window.ipc.channel(name)`:
+//!
+//! ```js
+//! const progress = window.ipc.channel("progress");
+//! progress.send({ done: 3, total: 10 });
+//! const reply = await progress.recv();
+//! const unsubscribe = progress.subscribe((message) => console.log(message));
+//! ```
+//!
+//! Messages are serialized as JSON. Messages the page sends before Rust opens the channel are dropped, and messages
+//! Rust sends before the page listens are queued until it calls `recv` or `subscribe`.
+//!
+//! Some webviews don't allow `window.ipc` to be extended, so the function is also available as
+//! `window.dioxusIpcChannel(name)`.
+
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use dioxus_core::ScopeState;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use wry::webview::WebView;
+
+use crate::use_window;
+
+/// Defines `window.ipc.channel` in the page
+pub(crate) const CHANNEL_SCRIPT: &str = r#"(function () {
+    if (window !== window.top) return;
+    const channels = new Map();
+    const state = (name) => {
+        let channel = channels.get(name);
+        if (!channel) {
+            channel = { queue: [], waiting: [], listeners: new Set() };
+            channels.set(name, channel);
+        }
+        return channel;
+    };
+
+    window.__dioxusIpcChannels = {
+        deliver(name, message) {
+            const channel = state(name);
+            if (channel.listeners.size) {
+                channel.listeners.forEach((listener) => listener(message));
+            } else if (channel.waiting.length) {
+                channel.waiting.shift()(message);
+            } else {
+                channel.queue.push(message);
+            }
+        },
+    };
+
+    const open = (name) => {
+        const channel = state(name);
+        return {
+            send(message) {
+                window.ipc.postMessage(JSON.stringify({ method: "ipc_channel", params: { name, data: message } }));
+            },
+            recv() {
+                if (channel.queue.length) return Promise.resolve(channel.queue.shift());
+                return new Promise((resolve) => channel.waiting.push(resolve));
+            },
+            subscribe(listener) {
+                channel.queue.splice(0).forEach((message) => listener(message));
+                channel.listeners.add(listener);
+                return () => channel.listeners.delete(listener);
+            },
+        };
+    };
+
+    window.dioxusIpcChannel = open;
+    if (window.ipc && Object.isExtensible(window.ipc)) {
+        window.ipc.channel = open;
+    }
+})();"#;
+
+/// An error that can occur when sending or receiving a message on an IPC channel.
+#[derive(Error, Debug)]
+pub enum IpcChannelError {
+    /// The message could not be serialized to JSON
+    #[error("Failed to serialize the message: {0}")]
+    Serialize(serde_json::Error),
+    /// The page sent a message that doesn't match the type of the channel
+    #[error("Failed to deserialize the message: {0}")]
+    Deserialize(serde_json::Error),
+    /// The webview could not run the script that delivers the message
+    #[error("Failed to send the message to the page: {0}")]
+    Send(String),
+}
+
+/// Sends messages of type `T` to the page
+pub struct IpcSender<T> {
+    name: String,
+    webview: Rc<WebView>,
+    _marker: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for IpcSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            webview: self.webview.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Serialize> IpcSender<T> {
+    /// Send a message to the channel in the page
+    pub fn send(&self, message: &T) -> Result<(), IpcChannelError> {
+        let name = serde_json::to_string(&self.name).map_err(IpcChannelError::Serialize)?;
+        let message = serde_json::to_string(message).map_err(IpcChannelError::Serialize)?;
+        self.webview
+            .evaluate_script(&format!(
+                "window.__dioxusIpcChannels.deliver({}, {});",
+                name, message
+            ))
+            .map_err(|err| IpcChannelError::Send(err.to_string()))
+    }
+}
+
+/// Receives messages of type `T` from the page
+pub struct IpcReceiver<T> {
+    receiver: UnboundedReceiver<serde_json::Value>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> IpcReceiver<T> {
+    /// Wait for the next message from the page.
+    ///
+    /// Returns `None` once the channel is closed, which happens when the window closes or the channel is opened again
+    /// with the same name.
+    pub async fn recv(&mut self) -> Option<Result<T, IpcChannelError>> {
+        let message = self.receiver.recv().await?;
+        Some(serde_json::from_value(message).map_err(IpcChannelError::Deserialize))
+    }
+}
+
+pub(crate) fn channel<T>(
+    name: String,
+    webview: Rc<WebView>,
+) -> (
+    IpcSender<T>,
+    IpcReceiver<T>,
+    UnboundedSender<serde_json::Value>,
+) {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let sender = IpcSender {
+        name,
+        webview,
+        _marker: PhantomData,
+    };
+    let receiver = IpcReceiver {
+        receiver: rx,
+        _marker: PhantomData,
+    };
+    (sender, receiver, tx)
+}
+
+/// Open an IPC channel for the lifetime of the component and call `handler` with every message the page sends.
+///
+/// Messages that don't match `T` are logged and skipped.
+///
+/// ```rust, ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct Progress {
+///     done: u32,
+///     total: u32,
+/// }
+///
+/// fn app(cx: Scope) -> Element {
+///     let progress = use_state(cx, || None);
+///     let channel = use_ipc_channel(cx, "progress", {
+///         to_owned![progress];
+///         move |message: Progress| progress.set(Some(message.done))
+///     });
+///
+///     render! {
+///         button {
+///             onclick: move |_| _ = channel.send(&Progress { done: 0, total: 10 }),
+///             "Restart"
+///         }
+///     }
+/// }
+/// ```
+pub fn use_ipc_channel<T: Serialize + DeserializeOwned + 'static>(
+    cx: &ScopeState,
+    name: &str,
+    mut handler: impl FnMut(T) + 'static,
+) -> &IpcSender<T> {
+    let desktop = use_window(cx);
+    cx.use_hook(|| {
+        let (sender, mut receiver) = desktop.ipc_channel::<T>(name);
+        cx.spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    Ok(message) => handler(message),
+                    Err(err) => tracing::error!("Invalid message on an IPC channel: {}", err),
+                }
+            }
+        });
+        sender
+    })
+}
@@ -3,24 +3,145 @@
 #![doc(html_favicon_url = "https://avatars.githubusercontent.com/u/79236386")]
 #![deny(missing_docs)]
 
+#[cfg(feature = "archive")]
+mod archive;
+#[cfg(feature = "asset-cache")]
+mod asset_cache;
+mod autofill;
+#[cfg(all(
+    feature = "battery",
+    not(any(target_os = "ios", target_os = "android"))
+))]
+mod battery;
+mod cache_policy;
+mod capture;
 mod cfg;
+#[cfg(all(
+    feature = "clipboard",
+    not(any(target_os = "ios", target_os = "android"))
+))]
+mod clipboard;
+mod close_handler;
+mod command_palette;
+mod compression;
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+mod connectivity;
+mod console;
+mod crash;
+mod cursor;
+mod custom_protocol;
+mod deep_link;
 mod desktop_context;
+mod devtools;
+mod download;
+#[cfg(target_os = "windows")]
+mod dwm;
+mod edit_transport;
 mod element;
+mod engine;
 mod escape;
 mod eval;
+mod event_listener;
+mod event_loop_mode;
 mod events;
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+mod file_dialog;
+mod file_drop;
 mod file_upload;
+mod find;
+#[cfg(all(feature = "idle", not(any(target_os = "ios", target_os = "android"))))]
+mod idle;
+mod inspector;
+mod invoke;
+mod ipc_channel;
+#[cfg(all(
+    feature = "media-session",
+    not(any(target_os = "ios", target_os = "android"))
+))]
+mod media_session;
+mod menu;
+mod metrics;
 #[cfg(any(target_os = "ios", target_os = "android"))]
 mod mobile_shortcut;
+mod mount;
+mod navigation;
+#[cfg(all(
+    feature = "notifications",
+    not(any(target_os = "ios", target_os = "android"))
+))]
+mod notification;
+mod pdf;
+#[cfg(all(
+    any(feature = "battery", feature = "idle"),
+    not(any(target_os = "ios", target_os = "android"))
+))]
+mod poll;
+mod polyfill;
+#[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+mod portal;
+#[cfg(all(target_os = "linux", feature = "wayland-portal-shortcuts"))]
+mod portal_shortcuts;
 mod protocol;
+mod proxy;
 mod query;
+mod range;
+#[cfg(feature = "remote-inspector")]
+mod remote_inspector;
+mod render_thread;
+mod rendering;
+mod request_filter;
+mod response_headers;
+mod sandbox;
+mod secure_input;
+mod session;
 mod shortcut;
+mod storage;
+mod testing;
+#[cfg(target_os = "macos")]
+mod titlebar;
+#[cfg(feature = "tray")]
+mod tray;
 mod waker;
+#[cfg(target_os = "linux")]
+mod wayland;
 mod webview;
+mod window_metrics;
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+mod window_state;
 
 use crate::query::QueryResult;
 use crate::shortcut::GlobalHotKeyEvent;
+#[cfg(feature = "archive")]
+pub use archive::pack_assets;
+#[cfg(feature = "asset-cache")]
+pub use asset_cache::{cached_asset_url, AssetCache};
+pub use autofill::AutofillError;
+#[cfg(all(
+    feature = "battery",
+    not(any(target_os = "ios", target_os = "android"))
+))]
+pub use battery::{battery_status, use_battery, BatteryStatus};
+pub use cache_policy::{hashed_asset_url, CachePolicy};
+pub use capture::{CaptureError, PageCapture};
 pub use cfg::{Config, WindowCloseBehaviour};
+#[cfg(all(
+    feature = "clipboard",
+    not(any(target_os = "ios", target_os = "android"))
+))]
+pub use clipboard::{use_clipboard, Clipboard, ClipboardError, ClipboardImage};
+pub use close_handler::{use_window_close_handler, CloseBehaviour, CloseHandle, CloseHandlerId};
+use command_palette::CommandRegistry;
+pub use command_palette::{use_command, CommandHandle, CommandId};
+pub use compression::Compression;
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub use connectivity::{
+    use_online_status, Connectivity, ConnectivityHandle, ConnectivityHandlerId,
+};
+pub use console::{ConsoleBridge, ConsoleLevel};
+pub use crash::{
+    use_crash_handler, CrashEvent, CrashHandle, CrashHandlerId, CrashReason, CrashRecovery,
+};
+pub use deep_link::{use_deep_link, DeepLinkHandle, DeepLinkHandlerId};
 pub use desktop_context::DesktopContext;
 pub use desktop_context::{
     use_window, use_wry_event_handler, window, DesktopService, WryEventHandler, WryEventHandlerId,
@@ -30,26 +151,103 @@ use dioxus_core::*;
 use dioxus_html::{event_bubbles, MountedData};
 use dioxus_html::{native_bind::NativeFileEngine, FormData, HtmlEvent};
 use dioxus_interpreter_js::binary_protocol::Channel;
-use element::DesktopElement;
+pub use download::{
+    use_download_handler, DownloadEvent, DownloadHandle, DownloadHandlerId, DownloadRequest,
+};
+#[cfg(target_os = "windows")]
+pub use dwm::{BackdropMaterial, CornerPreference, WindowAttributeError};
+pub use edit_transport::EditTransport;
+pub use element::DesktopElement;
+pub use engine::{EngineSettings, WebKitHardwareAcceleration};
 use eval::init_eval;
+pub use event_listener::{
+    use_event_listener, EventListenerHandle, EventListenerId, GlobalEvent, ListenerTarget,
+};
+pub use event_loop_mode::EventLoopMode;
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub use file_dialog::FileDialogOptions;
+pub use find::{FindOptions, FindResult};
 use futures_util::{pin_mut, FutureExt};
-use rustc_hash::FxHashMap;
+#[cfg(all(feature = "idle", not(any(target_os = "ios", target_os = "android"))))]
+pub use idle::{idle_time, use_idle};
+pub use inspector::{
+    use_inspected_value, ComponentInfo, EditStats, EventInfo, Inspector, InspectorSnapshot,
+    InspectorSubscriptionId, WatchHandle, WatchId, WatchedValue,
+};
+pub use invoke::{use_invoke_handler, InvokeError, InvokeHandle};
+pub use ipc_channel::{use_ipc_channel, IpcChannelError, IpcReceiver, IpcSender};
+#[cfg(all(
+    feature = "media-session",
+    not(any(target_os = "ios", target_os = "android"))
+))]
+pub use media_session::{
+    use_media_key, MediaKeyEvent, MediaKeyHandle, MediaKeyHandlerId, MediaSession,
+    MediaSessionError, NowPlaying, PlaybackState,
+};
+pub use menu::{use_menu_event, AppMenu, AppMenuItem, MenuHandle, MenuHandlerId};
+pub use metrics::{
+    Histogram, MetricKind, MetricSample, Metrics, MetricsCallbackId, MetricsSnapshot,
+};
+pub use mount::AssetMount;
+pub use navigation::{is_app_url, NavigationAction};
+#[cfg(all(
+    feature = "notifications",
+    not(any(target_os = "ios", target_os = "android"))
+))]
+pub use notification::{
+    use_notification, NotificationBuilder, NotificationError, NotificationId, UseNotification,
+};
+pub use pdf::{PageMargins, PageSize, PdfError, PrintOptions};
+#[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+pub use portal::{
+    is_sandboxed, send_portal_notification, take_screenshot, withdraw_portal_notification,
+    PortalError, PortalMode,
+};
 pub use protocol::{use_asset_handler, AssetFuture, AssetHandler, AssetRequest, AssetResponse};
+pub use proxy::ProxyConfig;
+pub use query::QueryError;
+pub use range::{serve_file, serve_reader};
+use render_thread::{RenderMessage, RenderThread, WindowDom, WindowRoot};
+pub use rendering::RenderingInfo;
+pub use request_filter::{FilterAction, FilterRule, RequestFilter};
+use rustc_hash::FxHashMap;
+pub use sandbox::{use_sandbox, Sandbox, SandboxError, SandboxReceiver};
+pub use secure_input::{SecureInput, SECURE_INPUT_ATTRIBUTE};
 use shortcut::ShortcutRegistry;
-pub use shortcut::{use_global_shortcut, ShortcutHandle, ShortcutId, ShortcutRegistryError};
+pub use shortcut::{
+    use_global_shortcut, use_window_shortcut, ShortcutHandle, ShortcutId, ShortcutRegistryError,
+    WindowShortcutHandle, WindowShortcutId,
+};
+use std::any::Any;
 use std::cell::Cell;
 use std::rc::Rc;
 use std::sync::atomic::AtomicU16;
 use std::task::Waker;
+use std::time::Instant;
 use std::{collections::HashMap, sync::Arc};
-pub use tao::dpi::{LogicalSize, PhysicalSize};
+pub use storage::{Cookie, Cookies, DataTypes, StorageError};
+pub use tao::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
 use tao::event_loop::{EventLoopProxy, EventLoopWindowTarget};
-pub use tao::window::WindowBuilder;
+pub use tao::window::{Theme, WindowBuilder};
 use tao::{
     event::{Event, StartCause, WindowEvent},
     event_loop::ControlFlow,
 };
+pub use testing::{launch_test, launch_test_with_timeout, DesktopTestDriver, TestError};
+#[cfg(target_os = "macos")]
+pub use titlebar::TitlebarStyle;
+#[cfg(feature = "tray")]
+pub use tray::{
+    use_tray_event, TrayConfig, TrayEvent, TrayHandle, TrayHandlerId, TrayMenu, TrayMenuItem,
+};
+#[cfg(target_os = "linux")]
+pub use wayland::is_wayland;
+#[cfg(all(target_os = "linux", feature = "wayland-layer-shell"))]
+pub use wayland::{KeyboardInteractivity, LayerShellConfig, SurfaceEdge, SurfaceLayer};
 pub use webview::build_default_menu_bar;
+pub use window_metrics::{
+    use_scale_factor, use_system_theme, use_window_position, use_window_size,
+};
 pub use wry;
 pub use wry::application as tao;
 use wry::application::event_loop::EventLoopBuilder;
@@ -126,11 +324,83 @@ pub fn launch_cfg(root: Component, config_builder: Config) {
 /// }
 /// ```
 pub fn launch_with_props<P: 'static>(root: Component<P>, props: P, cfg: Config) {
-    let event_loop = EventLoopBuilder::<UserWindowEvent>::with_user_event().build();
+    launch_with_root(
+        Box::new(move || WindowRoot::Local(VirtualDom::new_with_props(root, props))),
+        cfg,
+    )
+}
+
+/// Launch the WebView and run the event loop, and render the VirtualDom on a thread of its own.
+///
+/// Components that take long to render don't block the window: it can still be moved and resized, and its menus still
+/// open. The VirtualDom is created on the render thread, so the props have to be [`Send`].
+///
+/// The webview of the window can only be used on the thread of the event loop, so components on the render thread
+/// can't use [`window`] and the hooks built on it, like `use_eval`, and don't get mounted events.
+///
+/// ```rust, no_run
+/// use dioxus::prelude::*;
+/// use dioxus_desktop::Config;
+///
+/// fn main() {
+///     dioxus_desktop::launch_with_render_thread(app, (), Config::default());
+/// }
+///
+/// fn app(cx: Scope) -> Element {
+///     cx.render(rsx!{
+///         h1 {"hello world!"}
+///     })
+/// }
+/// ```
+pub fn launch_with_render_thread<P: Send + 'static>(root: Component<P>, props: P, cfg: Config) {
+    launch_with_root(
+        Box::new(move || {
+            WindowRoot::Thread(Box::new(move || VirtualDom::new_with_props(root, props)))
+        }),
+        cfg,
+    )
+}
+
+fn launch_with_root(root: Box<dyn FnOnce() -> WindowRoot>, cfg: Config) {
+    let mut event_loop_builder = EventLoopBuilder::<UserWindowEvent>::with_user_event();
+
+    // GTK uses the application id as the Wayland app_id, which compositors use to match windows to desktop entries
+    #[cfg(target_os = "linux")]
+    if let Some(app_id) = &cfg.app_id {
+        use wry::application::platform::unix::EventLoopBuilderExtUnix;
+        event_loop_builder.with_app_id(app_id.clone());
+    }
+
+    // WebKitGTK reads its rendering settings when the first webview is created
+    #[cfg(target_os = "linux")]
+    if !cfg.hardware_acceleration {
+        rendering::disable_hardware_acceleration();
+    }
+
+    let event_loop = event_loop_builder.build();
 
     let proxy = event_loop.create_proxy();
 
+    // A process started with a deep link passes it to the running app instead of opening another window
+    let mut launch_urls = deep_link::launch_urls(&cfg.url_schemes);
+    if !cfg.url_schemes.is_empty() {
+        if !launch_urls.is_empty()
+            && deep_link::forward_to_running_instance(&cfg.url_schemes, &launch_urls)
+        {
+            return;
+        }
+        for scheme in &cfg.url_schemes {
+            if let Err(err) = deep_link::register(scheme) {
+                tracing::error!("Failed to register the url scheme {}: {}", scheme, err);
+            }
+        }
+        if let Err(err) = deep_link::listen(cfg.url_schemes.clone(), proxy.clone()) {
+            tracing::error!("Failed to listen for deep links: {}", err);
+        }
+    }
+
     let window_behaviour = cfg.last_window_close_behaviour;
+    event_loop_mode::set_mode(cfg.event_loop_mode);
 
     // Intialize hot reloading if it is enabled
     #[cfg(all(feature = "hot-reload", debug_assertions))]
@@ -164,16 +434,19 @@ pub fn launch_with_props<P: 'static>(root: Component<P>, props: P, cfg: Config)
     let queue = WebviewQueue::default();
 
     let shortcut_manager = ShortcutRegistry::new();
+    let command_registry = CommandRegistry::new(shortcut_manager.clone());
     let global_hotkey_channel = GlobalHotKeyEvent::receiver();
 
-    // move the props into a cell so we can pop it out later to create the first window
+    // move the root into a cell so we can pop it out later to create the first window
     // iOS panics if we create a window before the event loop is started
-    let props = Rc::new(Cell::new(Some(props)));
+    let root = Rc::new(Cell::new(Some(root)));
     let cfg = Rc::new(Cell::new(Some(cfg)));
     let mut is_visible_before_start = true;
+    #[cfg(feature = "tray")]
+    let mut tray: Option<tray::Tray> = None;
 
     event_loop.run(move |window_event, event_loop, control_flow| {
-        *control_flow = ControlFlow::Poll;
+        *control_flow = event_loop_mode::control_flow(&webviews);
 
         event_handlers.apply_event(&window_event, event_loop);
 
@@ -181,10 +454,29 @@ pub fn launch_with_props<P: 'static>(root: Component<P>, props: P, cfg: Config)
             shortcut_manager.call_handlers(event);
         }
 
+        #[cfg(all(target_os = "linux", feature = "wayland-portal-shortcuts"))]
+        shortcut_manager.poll_portal();
+
+        #[cfg(not(any(target_os = "ios", target_os = "android")))]
+        if let Event::WindowEvent {
+            event: WindowEvent::Moved(_) | WindowEvent::Resized(_),
+            window_id,
+            ..
+        } = &window_event
+        {
+            if let Some(view) = webviews.get(window_id) {
+                view.desktop_context.track_window_state();
+            }
+        }
+
         match window_event {
             Event::WindowEvent {
                 event, window_id, ..
             } => match event {
+                WindowEvent::CloseRequested
+                    if webviews
+                        .get(&window_id)
+                        .map_or(false, |view| !view.desktop_context.may_close()) => {}
                 WindowEvent::CloseRequested => match window_behaviour {
                     cfg::WindowCloseBehaviour::LastWindowExitsApp => {
                         webviews.remove(&window_id);
@@ -203,6 +495,14 @@ pub fn launch_with_props<P: 'static>(root: Component<P>, props: P, cfg: Config)
                         webviews.remove(&window_id);
                     }
                 },
+                // AppKit moves the traffic lights back to their default position when the window is resized
+                #[cfg(target_os = "macos")]
+                WindowEvent::Resized(_) => {
+                    if let Some(view) = webviews.get(&window_id) {
+                        let titlebar = view.desktop_context.titlebar.get();
+                        titlebar.apply_traffic_lights(view.desktop_context.webview.window());
+                    }
+                }
                 WindowEvent::Destroyed { .. } => {
                     webviews.remove(&window_id);
 
@@ -218,15 +518,21 @@ pub fn launch_with_props<P: 'static>(root: Component<P>, props: P, cfg: Config)
             },
 
             Event::NewEvents(StartCause::Init) => {
-                let props = props.take().unwrap();
-                let cfg = cfg.take().unwrap();
+                let root = root.take().unwrap();
+                #[cfg_attr(not(feature = "tray"), allow(unused_mut))]
+                let mut cfg = cfg.take().unwrap();
+
+                #[cfg(feature = "tray")]
+                if let Some(tray_config) = cfg.tray.take() {
+                    tray = tray::Tray::new(tray_config, event_loop);
+                }
 
                 // Create a dom
-                let dom = VirtualDom::new_with_props(root, props);
+                let dom = root();
 
-                is_visible_before_start = cfg.window.window.visible;
+                is_visible_before_start = cfg.window.window.visible && !cfg.headless;
 
-                let handler = create_new_window(
+                let handler = match create_new_window(
                     cfg,
                     event_loop,
                     &proxy,
@@ -234,13 +540,82 @@ pub fn launch_with_props<P: 'static>(root: Component<P>, props: P, cfg: Config)
                     &queue,
                     &event_handlers,
                     shortcut_manager.clone(),
-                );
+                    command_registry.clone(),
+                ) {
+                    Ok(handler) => handler,
+                    Err(err) => {
+                        tracing::error!(
+                            "failed to create the data directory of an ephemeral window: {}",
+                            err
+                        );
+                        *control_flow = ControlFlow::Exit;
+                        return;
+                    }
+                };
 
                 let id = handler.desktop_context.webview.window().id();
+                for url in launch_urls.drain(..) {
+                    handler.desktop_context.open_deep_link(url);
+                }
                 webviews.insert(id, handler);
                 _ = proxy.send_event(UserWindowEvent(EventData::Poll, id));
             }
 
+            // Render the windows whose redraw throttle held back work that is due now
+            Event::NewEvents(_) => {
+                for id in event_loop_mode::due_renders(&webviews) {
+                    if let Some(view) = webviews.get_mut(&id) {
+                        poll_vdom(view);
+                    }
+                }
+            }
+
+            // macOS and iOS open the running app with the urls of the schemes in its Info.plist
+            Event::Opened { urls } => {
+                for url in urls {
+                    for view in webviews.values() {
+                        view.desktop_context.open_deep_link(url.clone());
+                    }
+                }
+            }
+
+            // The tray belongs to the app, so every window hears about it
+            #[cfg(feature = "tray")]
+            Event::TrayEvent {
+                event, position, ..
+            } => {
+                if let Some(event) = tray::TrayEvent::from_tao(event, position) {
+                    for id in webviews.keys() {
+                        _ = proxy.send_event(UserWindowEvent(EventData::Tray(event.clone()), *id));
+                    }
+                }
+            }
+
+            Event::MenuEvent {
+                window_id: Some(window_id),
+                menu_id,
+                origin: tao::menu::MenuType::MenuBar,
+                ..
+            } => {
+                if let Some(view) = webviews.get(&window_id) {
+                    view.desktop_context.call_menu_handlers(menu_id);
+                }
+            }
+
+            #[cfg(feature = "tray")]
+            Event::MenuEvent {
+                menu_id,
+                origin: tao::menu::MenuType::ContextMenu,
+                ..
+            } => {
+                if let Some(item) = tray.as_ref().and_then(|tray| tray.menu_item(menu_id)) {
+                    let event = tray::TrayEvent::MenuItem(item);
+                    for id in webviews.keys() {
+                        _ = proxy.send_event(UserWindowEvent(EventData::Tray(event.clone()), *id));
+                    }
+                }
+            }
+
             Event::UserEvent(UserWindowEvent(EventData::NewWindow, _)) => {
                 for handler in queue.borrow_mut().drain(..) {
                     let id = handler.desktop_context.webview.window().id();
@@ -254,7 +629,12 @@ pub fn launch_with_props<P: 'static>(root: Component<P>, props: P, cfg: Config)
                 EventData::HotReloadEvent(msg) => match msg {
                     dioxus_hot_reload::HotReloadMsg::UpdateTemplate(template) => {
                         for webview in webviews.values_mut() {
-                            webview.dom.replace_template(template);
+                            match &mut webview.dom {
+                                WindowDom::Local(dom) => dom.replace_template(template),
+                                WindowDom::Thread(thread) => {
+                                    thread.send(RenderMessage::Template(template))
+                                }
+                            }
 
                             poll_vdom(webview);
                         }
@@ -264,7 +644,12 @@ pub fn launch_with_props<P: 'static>(root: Component<P>, props: P, cfg: Config)
                     }
                 },
 
-                EventData::CloseWindow => {
+                EventData::CloseWindow
+                    if webviews
+                        .get(&event.1)
+                        .map_or(false, |view| !view.desktop_context.may_close()) => {}
+
+                EventData::CloseWindow | EventData::ForceCloseWindow => {
                     webviews.remove(&event.1);
 
                     if webviews.is_empty() {
@@ -274,13 +659,167 @@ pub fn launch_with_props<P: 'static>(root: Component<P>, props: P, cfg: Config)
 
                 EventData::Poll => {
                     if let Some(view) = webviews.get_mut(&event.1) {
-                        poll_vdom(view);
+                        if !view.desktop_context.paused.get() {
+                            poll_vdom(view);
+                        }
+                    }
+                }
+
+                #[cfg(all(
+                    feature = "media-session",
+                    not(any(target_os = "ios", target_os = "android"))
+                ))]
+                EventData::MediaKey(key) => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        // Clone the session out so handlers can use the media session themselves
+                        let session = view.desktop_context.media_session.borrow().clone();
+                        if let Some(session) = session {
+                            session.call_handlers(&key);
+                        }
+                    }
+                }
+
+                #[cfg(not(any(target_os = "ios", target_os = "android")))]
+                EventData::Connectivity(online) => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        // Clone the monitor out so handlers can use the connectivity themselves
+                        let connectivity = view.desktop_context.connectivity.borrow().clone();
+                        if let Some(connectivity) = connectivity {
+                            connectivity.set_online(online);
+                        }
+                    }
+                }
+
+                EventData::Download(download) => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        view.desktop_context.call_download_handlers(&download);
+                    }
+                }
+
+                EventData::WebviewCrashed(reason) => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        view.desktop_context.handle_crash(reason);
+                    }
+                }
+
+                #[cfg(all(
+                    feature = "notifications",
+                    not(any(target_os = "ios", target_os = "android"))
+                ))]
+                EventData::NotificationClicked(id) => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        view.desktop_context.notification_clicked(id);
+                    }
+                }
+
+                #[cfg(feature = "tray")]
+                EventData::Tray(tray_event) => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        view.desktop_context.call_tray_handlers(&tray_event);
+                    }
+                }
+
+                #[cfg(feature = "tray")]
+                EventData::UpdateTray(update) => {
+                    if let Some(tray) = &mut tray {
+                        tray.update(update);
+                    }
+                }
+
+                #[cfg(feature = "remote-inspector")]
+                EventData::RemoteInspector(clients) => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        if let Some(remote) = &*view.desktop_context.remote_inspector.borrow() {
+                            remote.set_clients(clients, &view.desktop_context.inspector);
+                        }
+                        // Collect the component tree for the client that just connected
+                        if let WindowDom::Local(dom) = &view.dom {
+                            view.desktop_context.inspector.record_render(dom);
+                        }
+                    }
+                }
+
+                // Deep links belong to the app, so every window hears about them
+                EventData::DeepLink(url) => {
+                    for view in webviews.values() {
+                        view.desktop_context.open_deep_link(url.clone());
+                    }
+                }
+
+                EventData::FileDrop(drop_event) => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        view.desktop_context.file_dropped(drop_event);
+                    }
+                }
+
+                EventData::Ipc(msg) if msg.method() == "file_drop" => {
+                    let view = webviews.get_mut(&event.1).unwrap();
+                    if let WindowDom::Thread(thread) = &view.dom {
+                        let files = view.desktop_context.dropped_files.borrow().clone();
+                        thread.send(RenderMessage::Event {
+                            params: msg.params(),
+                            files: Some(files),
+                        });
+                        return;
+                    }
+                    if let Ok(evt) = serde_json::from_value::<HtmlEvent>(msg.params()) {
+                        let WebviewHandler {
+                            dom: WindowDom::Local(dom),
+                            desktop_context,
+                            ..
+                        } = view
+                        else {
+                            return;
+                        };
+                        let HtmlEvent {
+                            element,
+                            name,
+                            bubbles,
+                            data,
+                        } = evt;
+                        let data: Rc<dyn Any> = match data {
+                            dioxus_html::EventData::Drag(mut drag) => {
+                                let files = desktop_context.dropped_files.borrow().clone();
+                                drag.files = Some(Arc::new(NativeFileEngine::new(files)));
+                                Rc::new(drag)
+                            }
+                            data => data.into_any(),
+                        };
+
+                        desktop_context
+                            .inspector
+                            .record_event(&name, element, bubbles);
+                        dom.handle_event(&name, data, element, bubbles);
+                        render_event(view);
+                    }
+                }
+
+                EventData::Navigate(url) => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        view.desktop_context.webview.load_url(&url);
                     }
                 }
 
                 EventData::Ipc(msg) if msg.method() == "user_event" => {
                     let params = msg.params();
 
+                    let view = webviews.get_mut(&event.1).unwrap();
+                    let WebviewHandler {
+                        dom,
+                        desktop_context,
+                        ..
+                    } = view;
+                    let dom = match dom {
+                        WindowDom::Local(dom) => dom,
+                        WindowDom::Thread(thread) => {
+                            thread.send(RenderMessage::Event {
+                                params,
+                                files: None,
+                            });
+                            return;
+                        }
+                    };
+
                     let evt = match serde_json::from_value::<HtmlEvent>(params) {
                         Ok(value) => value,
                         Err(err) => {
@@ -296,29 +835,23 @@ pub fn launch_with_props<P: 'static>(root: Component<P>, props: P, cfg: Config)
                         data,
                     } = evt;
 
-                    let view = webviews.get_mut(&event.1).unwrap();
-
                     // check for a mounted event placeholder and replace it with a desktop specific element
                     let as_any = if let dioxus_html::EventData::Mounted = &data {
-                        let query = view
-                            .dom
-                            .base_scope()
-                            .consume_context::<DesktopContext>()
-                            .unwrap()
-                            .query
-                            .clone();
+                        let query = desktop_context.query.clone();
 
-                        let element =
-                            DesktopElement::new(element, view.desktop_context.clone(), query);
+                        let element = DesktopElement::new(element, desktop_context.clone(), query);
 
                         Rc::new(MountedData::new(element))
                     } else {
                         data.into_any()
                     };
 
-                    view.dom.handle_event(&name, as_any, element, bubbles);
+                    desktop_context
+                        .inspector
+                        .record_event(&name, element, bubbles);
+                    dom.handle_event(&name, as_any, element, bubbles);
 
-                    send_edits(view.dom.render_immediate(), &view.desktop_context);
+                    render_event(view);
                 }
 
                 // When the webview sends a query, we need to send it to the query manager which handles dispatching the data to the correct pending query
@@ -327,27 +860,132 @@ pub fn launch_with_props<P: 'static>(root: Component<P>, props: P, cfg: Config)
 
                     if let Ok(result) = serde_json::from_value::<QueryResult>(params) {
                         let view = webviews.get(&event.1).unwrap();
-                        let query = view
-                            .dom
-                            .base_scope()
-                            .consume_context::<DesktopContext>()
-                            .unwrap()
-                            .query
-                            .clone();
-
-                        query.send(result);
+                        view.desktop_context.query.send(result);
                     }
                 }
 
                 EventData::Ipc(msg) if msg.method() == "initialize" => {
                     let view = webviews.get_mut(&event.1).unwrap();
-                    send_edits(view.dom.rebuild(), &view.desktop_context);
+                    // The first frame was rendered while the page loaded. A reloaded page needs a new one.
+                    if !view.desktop_context.prerendered.replace(false) {
+                        view.desktop_context.recovering.set(false);
+                        match &mut view.dom {
+                            WindowDom::Local(dom) => {
+                                resend_templates(&view.desktop_context);
+                                let start = Instant::now();
+                                let edits = dom.remount();
+                                view.desktop_context
+                                    .metrics
+                                    .record_duration(MetricKind::Render, start.elapsed());
+                                send_edits(edits, &view.desktop_context);
+                                dom.flush_layout_effects();
+                                view.desktop_context.inspector.record_render(dom);
+                            }
+                            WindowDom::Thread(thread) => thread.send(RenderMessage::Reload),
+                        }
+                    }
                     view.desktop_context
                         .webview
                         .window()
                         .set_visible(is_visible_before_start);
                 }
 
+                EventData::Ipc(msg) if msg.method() == "ipc_channel" => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        view.desktop_context.receive_channel_message(msg.params());
+                    }
+                }
+
+                EventData::Ipc(msg) if msg.method() == "invoke" => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        let desktop = &view.desktop_context;
+                        if let Some(call) = desktop
+                            .invoke_handlers
+                            .call(msg.params(), desktop.webview.clone())
+                        {
+                            match &view.dom {
+                                WindowDom::Local(dom) => {
+                                    dom.base_scope().spawn_forever(call);
+                                }
+                                // Components on the render thread can't register invoke handlers, so the call is
+                                // rejected right away
+                                WindowDom::Thread(_) => _ = call.now_or_never(),
+                            }
+                        }
+                    }
+                }
+
+                EventData::Ipc(msg) if msg.method() == "sandbox_message" => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        view.desktop_context.receive_sandbox_message(msg.params());
+                    }
+                }
+
+                EventData::Ipc(msg) if msg.method() == "global_event" => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        view.desktop_context.call_event_listener(msg.params());
+                    }
+                }
+
+                EventData::Ipc(msg) if msg.method() == "window_shortcut" => {
+                    if let (Some(view), Some(id)) = (webviews.get(&event.1), msg.params().as_u64())
+                    {
+                        view.desktop_context.call_window_shortcut(id as usize);
+                    }
+                }
+
+                EventData::Ipc(msg) if msg.method() == "native_tooltip" => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        view.desktop_context
+                            .set_native_tooltip(msg.params().as_str());
+                    }
+                }
+
+                EventData::Edits(bytes) => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        edit_transport::push(&view.desktop_context.webview, &bytes);
+                    }
+                }
+
+                // The interpreter asks for edits over IPC with the script transport
+                EventData::Ipc(msg) if msg.method() == "edits" => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        let context = &view.desktop_context;
+                        if let Some(bytes) = context.edit_queue.handle_script_request() {
+                            edit_transport::push(&context.webview, &bytes);
+                        }
+                    }
+                }
+
+                EventData::Ipc(msg) if msg.method() == "secure_input" => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        view.desktop_context
+                            .set_secure_input_focused(msg.params().as_bool().unwrap_or_default());
+                    }
+                }
+
+                EventData::Ipc(msg) if msg.method() == "console" => {
+                    console::log(event.1, msg.params());
+                }
+
+                EventData::Ipc(msg) if msg.method() == "devtools_toggle" => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        view.desktop_context.toggle_devtools_panel();
+                    }
+                }
+
+                EventData::Ipc(msg) if msg.method() == "command_palette_toggle" => {
+                    if let Some(view) = webviews.get(&event.1) {
+                        view.desktop_context.toggle_command_palette();
+                    }
+                }
+
+                EventData::Ipc(msg) if msg.method() == "command_palette_run" => {
+                    if let Some(id) = msg.params()["id"].as_u64() {
+                        command_registry.run(command_palette::CommandId(id as usize));
+                    }
+                }
+
                 EventData::Ipc(msg) if msg.method() == "browser_open" => {
                     if let Some(temp) = msg.params().as_object() {
                         if temp.contains_key("href") {
@@ -366,29 +1004,58 @@ pub fn launch_with_props<P: 'static>(root: Component<P>, props: P, cfg: Config)
                         let id = ElementId(file_diolog.target);
                         let event_name = &file_diolog.event;
                         let event_bubbles = file_diolog.bubbles;
+                        let view = webviews.get_mut(&event.1).unwrap();
+
+                        #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+                        let files = if view.desktop_context.uses_portals() {
+                            file_upload::get_file_event_from_portal(&file_diolog)
+                        } else {
+                            file_upload::get_file_event(&file_diolog)
+                        };
+                        #[cfg(not(all(target_os = "linux", feature = "xdg-portal")))]
                         let files = file_upload::get_file_event(&file_diolog);
+
+                        let dom = match &mut view.dom {
+                            WindowDom::Local(dom) => dom,
+                            WindowDom::Thread(thread) => {
+                                thread.send(RenderMessage::Files {
+                                    name: event_name.clone(),
+                                    element: id,
+                                    bubbles: event_bubbles,
+                                    files,
+                                });
+                                return;
+                            }
+                        };
                         let data = Rc::new(FormData {
                             value: Default::default(),
                             values: Default::default(),
                             files: Some(Arc::new(NativeFileEngine::new(files))),
                         });
 
-                        let view = webviews.get_mut(&event.1).unwrap();
-
+                        view.desktop_context
+                            .inspector
+                            .record_event(event_name, id, event_bubbles);
                         if event_name == "change&input" {
-                            view.dom
-                                .handle_event("input", data.clone(), id, event_bubbles);
-                            view.dom.handle_event("change", data, id, event_bubbles);
+                            dom.handle_event("input", data.clone(), id, event_bubbles);
+                            dom.handle_event("change", data, id, event_bubbles);
                         } else {
-                            view.dom.handle_event(event_name, data, id, event_bubbles);
+                            dom.handle_event(event_name, data, id, event_bubbles);
                         }
 
-                        send_edits(view.dom.render_immediate(), &view.desktop_context);
+                        render_event(view);
                     }
                 }
 
                 _ => {}
             },
+            // The windows that are still open are never dropped when the app exits
+            #[cfg(not(any(target_os = "ios", target_os = "android")))]
+            Event::LoopDestroyed => {
+                for view in webviews.values() {
+                    view.desktop_context.save_window_state();
+                }
+            }
             _ => {}
         }
     })
@@ -398,12 +1065,23 @@ fn create_new_window(
     mut cfg: Config,
     event_loop: &EventLoopWindowTarget<UserWindowEvent>,
     proxy: &EventLoopProxy<UserWindowEvent>,
-    dom: VirtualDom,
+    root: WindowRoot,
     queue: &WebviewQueue,
     event_handlers: &WindowEventHandlers,
     shortcut_manager: ShortcutRegistry,
-) -> WebviewHandler {
-    let (webview, web_context, asset_handlers, edit_queue) =
+    command_registry: CommandRegistry,
+) -> std::io::Result<WebviewHandler> {
+    // An ephemeral window keeps its data in a directory of its own, which is deleted when the window closes. Without
+    // that directory the window would share the data of the other windows, so it isn't created at all.
+    let session = if cfg.ephemeral {
+        let session = session::EphemeralSession::new()?;
+        cfg.data_dir = Some(session.directory().to_path_buf());
+        Some(session)
+    } else {
+        None
+    };
+
+    let (webview, web_context, asset_handlers, protocols, edit_queue) =
         webview::build(&mut cfg, event_loop, proxy.clone());
     let desktop_context = Rc::from(DesktopService::new(
         webview,
@@ -412,33 +1090,103 @@ fn create_new_window(
         queue.clone(),
         event_handlers.clone(),
         shortcut_manager,
-        asset_handlers,
+        command_registry,
         edit_queue,
+        asset_handlers,
+        protocols,
     ));
 
-    let cx = dom.base_scope();
-    cx.provide_context(desktop_context.clone());
+    #[cfg(feature = "remote-inspector")]
+    if let Some(port) = cfg.remote_inspector_port {
+        *desktop_context.remote_inspector.borrow_mut() =
+            Some(remote_inspector::RemoteInspector::start(
+                port,
+                proxy.clone(),
+                desktop_context.webview.window().id(),
+            ));
+    }
 
-    // Init eval
-    init_eval(cx);
+    if let Some(menu) = cfg.menu.take() {
+        desktop_context.set_menu(Some(menu));
+    }
+
+    desktop_context
+        .hardware_acceleration
+        .set(cfg.hardware_acceleration);
+    desktop_context.autofill.set(cfg.autofill);
+    desktop_context.content_protected.set(cfg.content_protected);
+    *desktop_context.crash_recovery.borrow_mut() =
+        crash::CrashRecoveryState::new(cfg.crash_recovery);
+    desktop_context.request_redraw_throttle(cfg.redraw_throttle);
+    #[cfg(target_os = "windows")]
+    autofill::apply(&desktop_context.webview, cfg.autofill);
+    #[cfg(all(target_os = "linux", feature = "xdg-portal"))]
+    desktop_context.portals.set(cfg.portals);
+    #[cfg(target_os = "macos")]
+    desktop_context.titlebar.set(cfg.titlebar);
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    {
+        *desktop_context.window_state.borrow_mut() = cfg.window_state.take();
+    }
+
+    #[cfg(feature = "metrics-endpoint")]
+    if let Some(port) = cfg.metrics_port {
+        *desktop_context.metrics_endpoint.borrow_mut() =
+            Some(metrics::serve_prometheus(desktop_context.metrics(), port));
+    }
 
-    WebviewHandler {
+    let id = desktop_context.webview.window().id();
+    let dom = match root {
+        WindowRoot::Local(mut dom) => {
+            let cx = dom.base_scope();
+            cx.provide_context(desktop_context.clone());
+
+            // Init eval
+            init_eval(cx);
+
+            // Render the first frame while the webview loads the page. The edits wait in the edit queue until the
+            // interpreter starts, so the page doesn't have to ask for them and wait for the render.
+            let start = Instant::now();
+            let edits = dom.rebuild();
+            desktop_context
+                .metrics
+                .record_duration(MetricKind::Render, start.elapsed());
+            send_edits(edits, &desktop_context);
+            dom.flush_layout_effects();
+            desktop_context.inspector.record_render(&dom);
+            WindowDom::Local(dom)
+        }
+        // The render thread renders the first frame as soon as it created the VirtualDom
+        WindowRoot::Thread(make_dom) => WindowDom::Thread(RenderThread::spawn(
+            make_dom,
+            desktop_context.edit_queue.clone(),
+            proxy.clone(),
+            id,
+        )),
+    };
+    desktop_context.prerendered.set(true);
+
+    Ok(WebviewHandler {
         // We want to poll the virtualdom and the event loop at the same time, so the waker will be connected to both
-        waker: waker::tao_waker(proxy, desktop_context.webview.window().id()),
+        waker: waker::tao_waker(proxy, id),
         desktop_context,
         dom,
         _web_context: web_context,
-    }
+        _session: session,
+    })
 }
 
 struct WebviewHandler {
-    dom: VirtualDom,
+    dom: WindowDom,
     desktop_context: DesktopContext,
     waker: Waker,
 
     // Wry assumes the webcontext is alive for the lifetime of the webview.
     // We need to keep the webcontext alive, otherwise the webview will crash
     _web_context: WebContext,
+
+    // The data directory of an ephemeral window is deleted after the webview and its context are dropped
+    _session: Option<session::EphemeralSession>,
 }
 
 /// Poll the virtualdom until it's pending
@@ -447,11 +1195,26 @@ struct WebviewHandler {
 ///
 /// All IO is done on the tokio runtime we started earlier
 fn poll_vdom(view: &mut WebviewHandler) {
-    let mut cx = std::task::Context::from_waker(&view.waker);
+    let WebviewHandler {
+        dom,
+        desktop_context,
+        waker,
+        ..
+    } = view;
+    // The render thread polls its VirtualDom itself
+    let WindowDom::Local(dom) = dom else {
+        return;
+    };
+    let mut cx = std::task::Context::from_waker(waker);
 
     loop {
+        // The webview is behind. The components that change meanwhile are rendered together once it catches up.
+        if desktop_context.edit_queue.is_backed_up(waker) {
+            break;
+        }
+
         {
-            let fut = view.dom.wait_for_work();
+            let fut = dom.wait_for_work();
             pin_mut!(fut);
 
             match fut.poll_unpin(&mut cx) {
@@ -460,12 +1223,66 @@ fn poll_vdom(view: &mut WebviewHandler) {
             }
         }
 
-        send_edits(view.dom.render_immediate(), &view.desktop_context);
+        // The VirtualDom has work, but the window was rendered too recently. The event loop polls it again once
+        // the deadline passes.
+        if let (Some(interval), Some(last_render)) = (
+            desktop_context.redraw_interval.get(),
+            desktop_context.last_render.get(),
+        ) {
+            let deadline = last_render + interval;
+            if deadline > Instant::now() {
+                desktop_context.render_deadline.set(Some(deadline));
+                break;
+            }
+        }
+
+        render_immediate(dom, desktop_context);
     }
+
+    desktop_context.inspector.record_render(dom);
+}
+
+/// Render the updates of an event right away, or leave them to the next poll when the VirtualDom batches renders
+fn render_event(view: &mut WebviewHandler) {
+    let WebviewHandler {
+        dom: WindowDom::Local(dom),
+        desktop_context,
+        ..
+    } = view
+    else {
+        return;
+    };
+    if dom.should_render_after_event() {
+        render_immediate(dom, desktop_context);
+        desktop_context.inspector.record_render(dom);
+    } else {
+        poll_vdom(view);
+    }
+}
+
+/// Render the dirty components of a window and send the edits to the webview
+fn render_immediate(dom: &mut VirtualDom, desktop_context: &DesktopContext) {
+    let start = Instant::now();
+    let edits = dom.render_immediate();
+    desktop_context
+        .metrics
+        .record_duration(MetricKind::Render, start.elapsed());
+    send_edits(edits, desktop_context);
+    dom.flush_layout_effects();
+    desktop_context.last_render.set(Some(Instant::now()));
+    desktop_context.render_deadline.set(None);
 }
 
 /// Send a list of mutations to the webview
 fn send_edits(edits: Mutations, desktop_context: &DesktopContext) {
+    desktop_context
+        .sent_templates
+        .borrow_mut()
+        .extend(edits.templates.iter().copied());
+    // The page stopped, and the page that replaces it will get the whole VirtualDom
+    if desktop_context.recovering.get() {
+        return;
+    }
     let mut channel = desktop_context.channel.borrow_mut();
     let mut templates = desktop_context.templates.borrow_mut();
     if let Some(bytes) = apply_edits(
@@ -474,7 +1291,40 @@ fn send_edits(edits: Mutations, desktop_context: &DesktopContext) {
         &mut templates,
         &desktop_context.max_template_count,
     ) {
-        desktop_context.edit_queue.add_edits(bytes)
+        desktop_context.inspector.record_edits(bytes.len());
+        desktop_context
+            .metrics
+            .record(MetricKind::EditBytes, bytes.len() as f64);
+        if let Some(bytes) = desktop_context.edit_queue.add_edits(bytes) {
+            edit_transport::push(&desktop_context.webview, &bytes);
+        }
+    }
+}
+
+/// Send every template again to a page that was loaded again, which lost the templates of the previous page
+fn resend_templates(desktop_context: &DesktopContext) {
+    let sent_templates = desktop_context.sent_templates.borrow();
+    if sent_templates.is_empty() {
+        return;
+    }
+    let mut channel = desktop_context.channel.borrow_mut();
+    let mut templates = desktop_context.templates.borrow_mut();
+    templates.clear();
+    desktop_context
+        .max_template_count
+        .store(0, std::sync::atomic::Ordering::Relaxed);
+    for template in sent_templates.iter() {
+        add_template(
+            template,
+            &mut channel,
+            &mut templates,
+            &desktop_context.max_template_count,
+        );
+    }
+    let bytes: Vec<_> = channel.export_memory().collect();
+    channel.reset();
+    if let Some(bytes) = desktop_context.edit_queue.add_edits(bytes) {
+        edit_transport::push(&desktop_context.webview, &bytes);
     }
 }
 
@@ -496,6 +1346,7 @@ fn apply_edits(
             AppendChildren { id, m } => channel.append_children(id.0 as u32, m as u16),
             AssignId { path, id } => channel.assign_id(path, id.0 as u32),
             CreatePlaceholder { id } => channel.create_placeholder(id.0 as u32),
+            CreateRawHtml { value, id } => channel.create_raw_html(value, id.0 as u32),
             CreateTextNode { value, id } => channel.create_text_node(value, id.0 as u32),
             HydrateText { path, value, id } => channel.hydrate_text(path, value, id.0 as u32),
             LoadTemplate { name, index, id } => {
@@ -534,6 +1385,7 @@ fn apply_edits(
                 _ => unreachable!(),
             },
             SetText { value, id } => channel.set_text(id.0 as u32, value),
+            SetRawHtml { value, id } => channel.set_raw_html(id.0 as u32, value),
             NewEventListener { name, id, .. } => {
                 channel.new_event_listener(name, id.0 as u32, event_bubbles(name) as u8)
             }
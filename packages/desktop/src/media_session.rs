@@ -0,0 +1,283 @@
+//! Integration with the operating system's media controls.
+//!
+//! This publishes now playing information to MPRIS on Linux, the System Media Transport Controls on Windows,
+//! and `MPNowPlayingInfoCenter` on macOS, and forwards the media keys the user presses back to the app.
+//!
+//! This needs the `media-session` feature. On Linux it talks to MPRIS over D-Bus, so it links to libdbus.
+
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use dioxus_core::ScopeState;
+use slab::Slab;
+use souvlaki::{
+    MediaControlEvent, MediaControls, MediaMetadata, MediaPlayback, MediaPosition, PlatformConfig,
+    SeekDirection,
+};
+use thiserror::Error;
+use wry::application::window::Window;
+
+use crate::desktop_context::{EventData, ProxyType, UserWindowEvent};
+use crate::window;
+
+/// A media key press or media control request from the operating system.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaKeyEvent {
+    /// Start playback
+    Play,
+    /// Pause playback
+    Pause,
+    /// Toggle between playing and paused
+    Toggle,
+    /// Skip to the next track
+    Next,
+    /// Go back to the previous track
+    Previous,
+    /// Stop playback
+    Stop,
+    /// Seek forward by the given amount, or by a step of the app's choosing if `None`
+    SeekForward(Option<Duration>),
+    /// Seek backward by the given amount, or by a step of the app's choosing if `None`
+    SeekBackward(Option<Duration>),
+    /// Jump to the given position in the current track
+    SetPosition(Duration),
+    /// Change the volume, between `0.0` and `1.0`
+    SetVolume(f64),
+    /// Open a uri
+    OpenUri(String),
+    /// Bring the window to the front
+    Raise,
+    /// Quit the app
+    Quit,
+}
+
+impl From<MediaControlEvent> for MediaKeyEvent {
+    fn from(event: MediaControlEvent) -> Self {
+        match event {
+            MediaControlEvent::Play => Self::Play,
+            MediaControlEvent::Pause => Self::Pause,
+            MediaControlEvent::Toggle => Self::Toggle,
+            MediaControlEvent::Next => Self::Next,
+            MediaControlEvent::Previous => Self::Previous,
+            MediaControlEvent::Stop => Self::Stop,
+            MediaControlEvent::Seek(SeekDirection::Forward) => Self::SeekForward(None),
+            MediaControlEvent::Seek(SeekDirection::Backward) => Self::SeekBackward(None),
+            MediaControlEvent::SeekBy(SeekDirection::Forward, by) => Self::SeekForward(Some(by)),
+            MediaControlEvent::SeekBy(SeekDirection::Backward, by) => Self::SeekBackward(Some(by)),
+            MediaControlEvent::SetPosition(MediaPosition(position)) => Self::SetPosition(position),
+            MediaControlEvent::SetVolume(volume) => Self::SetVolume(volume),
+            MediaControlEvent::OpenUri(uri) => Self::OpenUri(uri),
+            MediaControlEvent::Raise => Self::Raise,
+            MediaControlEvent::Quit => Self::Quit,
+        }
+    }
+}
+
+/// Information about the media that is currently playing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NowPlaying {
+    /// The title of the track
+    pub title: Option<String>,
+    /// The artist of the track
+    pub artist: Option<String>,
+    /// The album the track is from
+    pub album: Option<String>,
+    /// A url to the cover art of the track
+    pub cover_url: Option<String>,
+    /// The length of the track
+    pub duration: Option<Duration>,
+}
+
+/// Whether media is currently playing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackState {
+    /// Media is playing, optionally at a known position
+    Playing(Option<Duration>),
+    /// Media is paused, optionally at a known position
+    Paused(Option<Duration>),
+    /// Nothing is playing
+    Stopped,
+}
+
+impl From<PlaybackState> for MediaPlayback {
+    fn from(state: PlaybackState) -> Self {
+        match state {
+            PlaybackState::Playing(position) => MediaPlayback::Playing {
+                progress: position.map(MediaPosition),
+            },
+            PlaybackState::Paused(position) => MediaPlayback::Paused {
+                progress: position.map(MediaPosition),
+            },
+            PlaybackState::Stopped => MediaPlayback::Stopped,
+        }
+    }
+}
+
+/// An error that can occur when talking to the operating system's media controls.
+#[derive(Error, Debug)]
+pub enum MediaSessionError {
+    /// The media controls could not be created
+    #[error("Failed to create the media session: {0}")]
+    Create(String),
+    /// The operating system rejected an update to the media controls
+    #[error("Failed to update the media session: {0}")]
+    Update(String),
+}
+
+/// The unique identifier of a media key handler. This can be used to later remove the handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MediaKeyHandlerId(usize);
+
+type MediaKeyCallback = Rc<RefCell<dyn FnMut(&MediaKeyEvent)>>;
+
+struct MediaSessionInner {
+    controls: RefCell<MediaControls>,
+    handlers: RefCell<Slab<MediaKeyCallback>>,
+}
+
+/// A handle to the operating system's media controls for a window.
+///
+/// Get one with [`DesktopService::media_session`](crate::DesktopService::media_session).
+#[derive(Clone)]
+pub struct MediaSession {
+    inner: Rc<MediaSessionInner>,
+}
+
+impl MediaSession {
+    pub(crate) fn new(window: &Window, proxy: ProxyType) -> Result<Self, MediaSessionError> {
+        let display_name = window.title();
+        // The dbus name needs to be stable and unique to the app, so use the name of the executable
+        let dbus_name = std::env::current_exe()
+            .ok()
+            .and_then(|exe| {
+                exe.file_stem()
+                    .map(|name| name.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| "dioxus".to_string());
+
+        #[cfg(target_os = "windows")]
+        let hwnd = {
+            use wry::application::platform::windows::WindowExtWindows;
+            Some(window.hwnd() as *mut std::ffi::c_void)
+        };
+        #[cfg(not(target_os = "windows"))]
+        let hwnd = None;
+
+        let mut controls = MediaControls::new(PlatformConfig {
+            dbus_name: &dbus_name,
+            display_name: &display_name,
+            hwnd,
+        })
+        .map_err(|err| MediaSessionError::Create(format!("{err:?}")))?;
+
+        // The controls call us back from their own thread, so forward the event to the event loop
+        let window_id = window.id();
+        controls
+            .attach(move |event| {
+                _ = proxy.send_event(UserWindowEvent(
+                    EventData::MediaKey(event.into()),
+                    window_id,
+                ));
+            })
+            .map_err(|err| MediaSessionError::Create(format!("{err:?}")))?;
+
+        Ok(Self {
+            inner: Rc::new(MediaSessionInner {
+                controls: RefCell::new(controls),
+                handlers: Default::default(),
+            }),
+        })
+    }
+
+    /// Publish information about the media that is currently playing.
+    pub fn set_now_playing(&self, now_playing: &NowPlaying) -> Result<(), MediaSessionError> {
+        self.inner
+            .controls
+            .borrow_mut()
+            .set_metadata(MediaMetadata {
+                title: now_playing.title.as_deref(),
+                artist: now_playing.artist.as_deref(),
+                album: now_playing.album.as_deref(),
+                cover_url: now_playing.cover_url.as_deref(),
+                duration: now_playing.duration,
+            })
+            .map_err(|err| MediaSessionError::Update(format!("{err:?}")))
+    }
+
+    /// Publish whether media is playing, paused, or stopped.
+    pub fn set_playback(&self, state: PlaybackState) -> Result<(), MediaSessionError> {
+        self.inner
+            .controls
+            .borrow_mut()
+            .set_playback(state.into())
+            .map_err(|err| MediaSessionError::Update(format!("{err:?}")))
+    }
+
+    /// Call `handler` every time the user presses a media key.
+    ///
+    /// The id this function returns can be used to remove the handler with [`MediaSession::remove_media_key_handler`]
+    pub fn create_media_key_handler(
+        &self,
+        handler: impl FnMut(&MediaKeyEvent) + 'static,
+    ) -> MediaKeyHandlerId {
+        MediaKeyHandlerId(
+            self.inner
+                .handlers
+                .borrow_mut()
+                .insert(Rc::new(RefCell::new(handler))),
+        )
+    }
+
+    /// Remove a media key handler created with [`MediaSession::create_media_key_handler`]
+    pub fn remove_media_key_handler(&self, id: MediaKeyHandlerId) {
+        self.inner.handlers.borrow_mut().try_remove(id.0);
+    }
+
+    pub(crate) fn call_handlers(&self, event: &MediaKeyEvent) {
+        // Clone the handlers out of the slab so handlers can add or remove handlers while they run
+        let handlers: Vec<_> = self
+            .inner
+            .handlers
+            .borrow()
+            .iter()
+            .map(|(_, handler)| handler.clone())
+            .collect();
+        for handler in handlers {
+            (handler.borrow_mut())(event);
+        }
+    }
+}
+
+/// A media key handler that is scoped to the current component.
+///
+/// This will automatically be removed when the component is unmounted.
+pub struct MediaKeyHandle {
+    session: MediaSession,
+    /// The unique identifier of the handler.
+    pub id: MediaKeyHandlerId,
+}
+
+impl MediaKeyHandle {
+    /// Remove the handler.
+    pub fn remove(&self) {
+        self.session.remove_media_key_handler(self.id);
+    }
+}
+
+impl Drop for MediaKeyHandle {
+    fn drop(&mut self) {
+        self.remove()
+    }
+}
+
+/// Listen for media key presses for as long as the component is mounted.
+pub fn use_media_key(
+    cx: &ScopeState,
+    handler: impl FnMut(&MediaKeyEvent) + 'static,
+) -> &Result<MediaKeyHandle, MediaSessionError> {
+    cx.use_hook(move || {
+        let session = window().media_session()?;
+        let id = session.create_media_key_handler(handler);
+
+        Ok(MediaKeyHandle { session, id })
+    })
+}
@@ -0,0 +1,201 @@
+//! Custom menu bars.
+//!
+//! A menu bar is made of items with ids. When the user selects an item, its id is sent to the handlers of the window
+//! the menu belongs to, which components can add with [`use_menu_event`].
+//!
+//! macOS: The menu bar belongs to the app, so the menu of the focused window is shown.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use dioxus_core::ScopeState;
+use wry::application::accelerator::Accelerator;
+use wry::application::menu::{MenuBar, MenuId, MenuItem, MenuItemAttributes};
+
+use crate::{use_window, DesktopContext};
+
+/// An item in an [`AppMenu`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppMenuItem {
+    id: String,
+    title: String,
+    enabled: bool,
+    checked: bool,
+    shortcut: Option<String>,
+}
+
+impl AppMenuItem {
+    /// Create an enabled item. `id` is sent to the menu handlers when the item is selected.
+    pub fn new(id: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            enabled: true,
+            checked: false,
+            shortcut: None,
+        }
+    }
+
+    /// Set if the item can be selected
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Show a check mark next to the item
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    /// Select the item when the shortcut is pressed while the window is focused, like `"ctrl+s"`. The shortcut is
+    /// shown next to the item.
+    pub fn with_shortcut(mut self, shortcut: impl Into<String>) -> Self {
+        self.shortcut = Some(shortcut.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum AppMenuEntry {
+    Item(AppMenuItem),
+    Native(MenuItem),
+    Submenu(String, AppMenu),
+}
+
+/// A menu bar, or one of the menus in it.
+///
+/// ```rust, ignore
+/// let menu = AppMenu::new()
+///     .with_submenu(
+///         "File",
+///         AppMenu::new()
+///             .with_item(AppMenuItem::new("open", "Open...").with_shortcut("ctrl+o"))
+///             .with_item(AppMenuItem::new("save", "Save").with_shortcut("ctrl+s"))
+///             .with_native_item(MenuItem::Separator)
+///             .with_native_item(MenuItem::Quit),
+///     )
+///     .with_submenu(
+///         "Edit",
+///         AppMenu::new()
+///             .with_native_item(MenuItem::Copy)
+///             .with_native_item(MenuItem::Paste),
+///     );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AppMenu {
+    entries: Vec<AppMenuEntry>,
+}
+
+impl AppMenu {
+    /// Create an empty menu
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an item to the end of the menu
+    pub fn with_item(mut self, item: AppMenuItem) -> Self {
+        self.entries.push(AppMenuEntry::Item(item));
+        self
+    }
+
+    /// Add an item the operating system implements, like [`MenuItem::Copy`] or [`MenuItem::Separator`]
+    pub fn with_native_item(mut self, item: MenuItem) -> Self {
+        self.entries.push(AppMenuEntry::Native(item));
+        self
+    }
+
+    /// Add a nested menu to the end of the menu
+    pub fn with_submenu(mut self, title: impl Into<String>, menu: AppMenu) -> Self {
+        self.entries.push(AppMenuEntry::Submenu(title.into(), menu));
+        self
+    }
+
+    /// Build the native menu and remember the id of every item
+    pub(crate) fn build(&self, items: &mut HashMap<MenuId, String>) -> MenuBar {
+        let mut menu = MenuBar::new();
+        for entry in &self.entries {
+            match entry {
+                AppMenuEntry::Item(item) => {
+                    let mut attributes = MenuItemAttributes::new(&item.title)
+                        .with_enabled(item.enabled)
+                        .with_selected(item.checked);
+                    if let Some(shortcut) = &item.shortcut {
+                        match Accelerator::from_str(shortcut) {
+                            Ok(accelerator) => {
+                                attributes = attributes.with_accelerators(&accelerator)
+                            }
+                            Err(err) => tracing::error!(
+                                "Invalid shortcut {:?} for the menu item {:?}: {}",
+                                shortcut,
+                                item.id,
+                                err
+                            ),
+                        }
+                    }
+                    let native = menu.add_item(attributes);
+                    items.insert(native.id(), item.id.clone());
+                }
+                AppMenuEntry::Native(item) => {
+                    menu.add_native_item(item.clone());
+                }
+                AppMenuEntry::Submenu(title, submenu) => {
+                    menu.add_submenu(title, true, submenu.build(items));
+                }
+            }
+        }
+        menu
+    }
+}
+
+/// The unique identifier of a menu event handler. This can be used to later remove the handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MenuHandlerId(pub(crate) usize);
+
+/// A menu event handler that is scoped to the current component.
+///
+/// This will automatically be removed when the component is unmounted.
+pub struct MenuHandle {
+    desktop: DesktopContext,
+    /// The unique identifier of the handler.
+    pub id: MenuHandlerId,
+}
+
+impl MenuHandle {
+    /// Remove the handler.
+    pub fn remove(&self) {
+        self.desktop.remove_menu_handler(self.id);
+    }
+}
+
+impl Drop for MenuHandle {
+    fn drop(&mut self) {
+        self.remove()
+    }
+}
+
+/// Call `handler` with the id of every item the user selects in the menu bar of the current window.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let saved = use_state(cx, || false);
+///     use_menu_event(cx, {
+///         to_owned![saved];
+///         move |id| {
+///             if id == "save" {
+///                 saved.set(true);
+///             }
+///         }
+///     });
+///
+///     render! { "saved: {saved}" }
+/// }
+/// ```
+pub fn use_menu_event(cx: &ScopeState, handler: impl FnMut(&str) + 'static) -> &MenuHandle {
+    let desktop = use_window(cx);
+    cx.use_hook(move || {
+        let desktop = desktop.clone();
+        let id = desktop.create_menu_handler(handler);
+        MenuHandle { desktop, id }
+    })
+}
@@ -0,0 +1,334 @@
+//! Performance metrics for a window: how long the VirtualDom takes to render, how long the webview takes to apply
+//! edits, how long queries to the webview take to come back, how large the edit batches are, and how many of them are
+//! sent to the webview at once.
+//!
+//! Read the metrics with [`Metrics::snapshot`], get every sample as it is recorded with [`Metrics::on_sample`], or
+//! export them in the Prometheus text format with [`Metrics::to_prometheus`].
+
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use slab::Slab;
+
+/// Upper bounds of the duration buckets, in seconds
+const DURATION_BUCKETS: &[f64] = &[
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0,
+];
+
+/// Upper bounds of the size buckets, in bytes
+const SIZE_BUCKETS: &[f64] = &[256.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0];
+
+/// Upper bounds of the count buckets
+const COUNT_BUCKETS: &[f64] = &[1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0];
+
+/// The kind of a metric sample
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricKind {
+    /// The time the VirtualDom took to render the dirty components, in seconds
+    Render,
+    /// The time between sending an edit batch to the webview and the webview asking for the next one, in seconds
+    EditApply,
+    /// The time between starting a query in the webview and getting its result back, in seconds
+    IpcRoundTrip,
+    /// The size of an edit batch, in bytes
+    EditBytes,
+    /// The number of edit batches sent to the webview in one response. More than one means the VirtualDom rendered
+    /// faster than the webview applied the edits.
+    EditBatchesPerResponse,
+    /// The size of a response with edit batches, in bytes
+    EditResponseBytes,
+}
+
+impl MetricKind {
+    /// The name of the metric in the Prometheus format
+    pub fn prometheus_name(&self) -> &'static str {
+        match self {
+            Self::Render => "dioxus_desktop_render_duration_seconds",
+            Self::EditApply => "dioxus_desktop_edit_apply_duration_seconds",
+            Self::IpcRoundTrip => "dioxus_desktop_ipc_round_trip_seconds",
+            Self::EditBytes => "dioxus_desktop_edit_batch_bytes",
+            Self::EditBatchesPerResponse => "dioxus_desktop_edit_batches_per_response",
+            Self::EditResponseBytes => "dioxus_desktop_edit_response_bytes",
+        }
+    }
+
+    fn help(&self) -> &'static str {
+        match self {
+            Self::Render => "Time the VirtualDom took to render dirty components",
+            Self::EditApply => "Time the webview took to apply a batch of edits",
+            Self::IpcRoundTrip => "Time a query to the webview took to return",
+            Self::EditBytes => "Size of the edit batches sent to the webview",
+            Self::EditBatchesPerResponse => "Number of edit batches sent to the webview at once",
+            Self::EditResponseBytes => {
+                "Size of the responses with edit batches sent to the webview"
+            }
+        }
+    }
+
+    fn buckets(&self) -> &'static [f64] {
+        match self {
+            Self::EditBytes | Self::EditResponseBytes => SIZE_BUCKETS,
+            Self::EditBatchesPerResponse => COUNT_BUCKETS,
+            _ => DURATION_BUCKETS,
+        }
+    }
+}
+
+/// One recorded measurement
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MetricSample {
+    /// What was measured
+    pub kind: MetricKind,
+    /// The measured value, in seconds for durations and bytes for sizes
+    pub value: f64,
+}
+
+/// The distribution of the samples of one metric
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Histogram {
+    /// The upper bound of each bucket and the number of samples that are less than or equal to it
+    pub buckets: Vec<(f64, u64)>,
+    /// The number of samples
+    pub count: u64,
+    /// The sum of all samples
+    pub sum: f64,
+}
+
+impl Histogram {
+    fn new(bounds: &[f64]) -> Self {
+        Self {
+            buckets: bounds.iter().map(|bound| (*bound, 0)).collect(),
+            count: 0,
+            sum: 0.0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (bound, count) in &mut self.buckets {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+
+    /// The average of the samples
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+}
+
+/// The metrics of a window at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricsSnapshot {
+    /// How long renders took
+    pub render: Histogram,
+    /// How long the webview took to apply edits
+    pub edit_apply: Histogram,
+    /// How long queries to the webview took
+    pub ipc_round_trip: Histogram,
+    /// How large the edit batches were
+    pub edit_bytes: Histogram,
+    /// How many edit batches were sent at once
+    pub edit_batches_per_response: Histogram,
+    /// How large the responses with edit batches were
+    pub edit_response_bytes: Histogram,
+}
+
+impl MetricsSnapshot {
+    fn new() -> Self {
+        Self {
+            render: Histogram::new(MetricKind::Render.buckets()),
+            edit_apply: Histogram::new(MetricKind::EditApply.buckets()),
+            ipc_round_trip: Histogram::new(MetricKind::IpcRoundTrip.buckets()),
+            edit_bytes: Histogram::new(MetricKind::EditBytes.buckets()),
+            edit_batches_per_response: Histogram::new(MetricKind::EditBatchesPerResponse.buckets()),
+            edit_response_bytes: Histogram::new(MetricKind::EditResponseBytes.buckets()),
+        }
+    }
+
+    fn histogram_mut(&mut self, kind: MetricKind) -> &mut Histogram {
+        match kind {
+            MetricKind::Render => &mut self.render,
+            MetricKind::EditApply => &mut self.edit_apply,
+            MetricKind::IpcRoundTrip => &mut self.ipc_round_trip,
+            MetricKind::EditBytes => &mut self.edit_bytes,
+            MetricKind::EditBatchesPerResponse => &mut self.edit_batches_per_response,
+            MetricKind::EditResponseBytes => &mut self.edit_response_bytes,
+        }
+    }
+
+    fn histograms(&self) -> [(MetricKind, &Histogram); 6] {
+        [
+            (MetricKind::Render, &self.render),
+            (MetricKind::EditApply, &self.edit_apply),
+            (MetricKind::IpcRoundTrip, &self.ipc_round_trip),
+            (MetricKind::EditBytes, &self.edit_bytes),
+            (
+                MetricKind::EditBatchesPerResponse,
+                &self.edit_batches_per_response,
+            ),
+            (MetricKind::EditResponseBytes, &self.edit_response_bytes),
+        ]
+    }
+}
+
+/// The unique identifier of a metrics callback. This can be used to later remove the callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MetricsCallbackId(usize);
+
+type SampleCallback = Arc<dyn Fn(&MetricSample) + Send + Sync>;
+
+struct MetricsInner {
+    snapshot: MetricsSnapshot,
+    callbacks: Slab<SampleCallback>,
+}
+
+/// Performance metrics for a window.
+///
+/// Get the metrics of a window with [`DesktopService::metrics`](crate::DesktopService::metrics). Metrics can be read
+/// from any thread.
+#[derive(Clone)]
+pub struct Metrics {
+    inner: Arc<Mutex<MetricsInner>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(MetricsInner {
+                snapshot: MetricsSnapshot::new(),
+                callbacks: Slab::new(),
+            })),
+        }
+    }
+}
+
+impl Metrics {
+    /// Get the metrics recorded so far
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.inner.lock().unwrap().snapshot.clone()
+    }
+
+    /// Call `callback` with every sample as it is recorded.
+    ///
+    /// The callback may be called from any thread. The id this function returns can be used to remove the callback
+    /// with [`Metrics::remove_callback`]
+    pub fn on_sample(
+        &self,
+        callback: impl Fn(&MetricSample) + Send + Sync + 'static,
+    ) -> MetricsCallbackId {
+        let mut inner = self.inner.lock().unwrap();
+        MetricsCallbackId(inner.callbacks.insert(Arc::new(callback)))
+    }
+
+    /// Remove a callback created with [`Metrics::on_sample`]
+    pub fn remove_callback(&self, id: MetricsCallbackId) {
+        self.inner.lock().unwrap().callbacks.try_remove(id.0);
+    }
+
+    /// Export the metrics in the Prometheus text format
+    pub fn to_prometheus(&self) -> String {
+        let snapshot = self.snapshot();
+        let mut out = String::new();
+        for (kind, histogram) in snapshot.histograms() {
+            let name = kind.prometheus_name();
+            let _ = writeln!(out, "# HELP {} {}", name, kind.help());
+            let _ = writeln!(out, "# TYPE {} histogram", name);
+            for (bound, count) in &histogram.buckets {
+                let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, count);
+            }
+            let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, histogram.count);
+            let _ = writeln!(out, "{}_sum {}", name, histogram.sum);
+            let _ = writeln!(out, "{}_count {}", name, histogram.count);
+        }
+        out
+    }
+
+    pub(crate) fn record_duration(&self, kind: MetricKind, duration: Duration) {
+        self.record(kind, duration.as_secs_f64());
+    }
+
+    pub(crate) fn record(&self, kind: MetricKind, value: f64) {
+        let sample = MetricSample { kind, value };
+        let callbacks: Vec<_> = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.snapshot.histogram_mut(kind).observe(value);
+            inner
+                .callbacks
+                .iter()
+                .map(|(_, callback)| callback.clone())
+                .collect()
+        };
+
+        // Call the callbacks without holding the lock so they can read the metrics
+        for callback in callbacks {
+            callback(&sample);
+        }
+    }
+}
+
+/// Serves the metrics of a window until it is dropped
+#[cfg(feature = "metrics-endpoint")]
+pub(crate) struct MetricsEndpoint(tokio::task::JoinHandle<()>);
+
+#[cfg(feature = "metrics-endpoint")]
+impl Drop for MetricsEndpoint {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Serve the metrics in the Prometheus text format on `http://127.0.0.1:{port}/metrics`
+#[cfg(feature = "metrics-endpoint")]
+pub(crate) fn serve_prometheus(metrics: Metrics, port: u16) -> MetricsEndpoint {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    MetricsEndpoint(tokio::spawn(async move {
+        let address = std::net::SocketAddr::from((std::net::Ipv4Addr::LOCALHOST, port));
+        let listener = match tokio::net::TcpListener::bind(address).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                tracing::error!(
+                    "Failed to start the metrics endpoint on {}: {}",
+                    address,
+                    err
+                );
+                return;
+            }
+        };
+        tracing::info!("Serving metrics on http://{}/metrics", address);
+
+        loop {
+            let mut stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    tracing::error!("Failed to accept a metrics connection: {}", err);
+                    continue;
+                }
+            };
+
+            let metrics = metrics.clone();
+            tokio::spawn(async move {
+                // Every path returns the metrics, so we only need to wait for the request before we answer
+                let mut request = [0; 1024];
+                if stream.read(&mut request).await.is_err() {
+                    return;
+                }
+
+                let body = metrics.to_prometheus();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    }))
+}
@@ -9,12 +9,18 @@ use dioxus_html::input_data::keyboard_types::Modifiers;
 #[derive(Clone, Debug)]
 pub struct Accelerator;
 
-#[derive(Clone, Copy)]
-pub struct HotKey;
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HotKey {
+    pub mods: Modifiers,
+    pub key: Code,
+}
 
 impl HotKey {
     pub fn new(mods: Option<Modifiers>, key: Code) -> Self {
-        Self
+        Self {
+            mods: mods.unwrap_or_else(Modifiers::empty),
+            key,
+        }
     }
 
     pub fn id(&self) -> u32 {
@@ -26,7 +32,7 @@ impl FromStr for HotKey {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(HotKey)
+        Ok(HotKey::new(None, Code::Unidentified))
     }
 }
 
@@ -38,7 +44,7 @@ impl GlobalHotKeyManager {
     }
 
     pub fn register(&mut self, accelerator: HotKey) -> Result<HotKey, HotkeyError> {
-        Ok(HotKey)
+        Ok(accelerator)
     }
 
     pub fn unregister(&mut self, id: HotKey) -> Result<(), HotkeyError> {
@@ -59,6 +65,8 @@ pub enum HotkeyError {
     AcceleratorAlreadyRegistered(Accelerator),
     AcceleratorNotRegistered(Accelerator),
     HotKeyParseError(String),
+    AlreadyRegistered(HotKey),
+    FailedToRegister(String),
 }
 
 impl error::Error for HotkeyError {}
@@ -72,6 +80,10 @@ impl fmt::Display for HotkeyError {
                 f.pad(&format!("hotkey not registered: {:?}", e))
             }
             HotkeyError::HotKeyParseError(e) => e.fmt(f),
+            HotkeyError::AlreadyRegistered(e) => {
+                f.pad(&format!("hotkey already registered: {:?}", e))
+            }
+            HotkeyError::FailedToRegister(e) => e.fmt(f),
         }
     }
 }
@@ -0,0 +1,400 @@
+//! Directories served under a URL prefix.
+//!
+//! A mount serves the files of a directory on disk, or of a directory embedded in the binary with `include_dir` or an
+//! [asset archive](crate::include_assets), at `dioxus://index.html/<prefix>/<path>`. Paths that would leave the directory are refused, files are sent in ranges
+//! so media can be seeked, and every response has an `ETag` so the webview can revalidate its cache instead of
+//! downloading the file again.
+
+use std::borrow::Cow;
+use std::fs::Metadata;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use wry::http::{header, status::StatusCode, HeaderValue, Response};
+
+use crate::protocol::{AssetRequest, AssetResponse};
+
+/// A directory served under a URL prefix
+///
+/// Add it to a window with [`Config::with_asset_mount`](crate::Config::with_asset_mount).
+///
+/// ```rust, ignore
+/// Config::new().with_asset_mount(AssetMount::directory("assets", "./assets"))
+/// // <img src="/assets/logo.png"> is now ./assets/logo.png
+/// ```
+#[derive(Debug, Clone)]
+pub struct AssetMount {
+    prefix: String,
+    source: MountSource,
+    cache_control: Option<String>,
+    index: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+enum MountSource {
+    Directory(PathBuf),
+    #[cfg(feature = "include-dir")]
+    Embedded(&'static include_dir::Dir<'static>),
+    #[cfg(feature = "archive")]
+    Archive(std::sync::Arc<crate::archive::AssetArchive>),
+}
+
+impl AssetMount {
+    /// Serve the files in `directory` under `prefix`
+    pub fn directory(prefix: impl Into<String>, directory: impl Into<PathBuf>) -> Self {
+        Self::new(prefix.into(), MountSource::Directory(directory.into()))
+    }
+
+    /// Serve the files of a directory embedded in the binary with [`include_dir!`](include_dir::include_dir) under
+    /// `prefix`
+    #[cfg(feature = "include-dir")]
+    pub fn embedded(
+        prefix: impl Into<String>,
+        directory: &'static include_dir::Dir<'static>,
+    ) -> Self {
+        Self::new(prefix.into(), MountSource::Embedded(directory))
+    }
+
+    /// Serve the files of an archive made by [`pack_assets`](crate::pack_assets) under `prefix`. Use
+    /// [`include_assets!`](crate::include_assets) to embed the archive and call this.
+    #[cfg(feature = "archive")]
+    pub fn archive(prefix: impl Into<String>, archive: &'static [u8]) -> io::Result<Self> {
+        let archive = crate::archive::AssetArchive::new(archive)?;
+        Ok(Self::new(
+            prefix.into(),
+            MountSource::Archive(std::sync::Arc::new(archive)),
+        ))
+    }
+
+    fn new(prefix: String, source: MountSource) -> Self {
+        Self {
+            prefix: prefix.trim_matches('/').to_string(),
+            source,
+            cache_control: None,
+            index: Some("index.html".to_string()),
+        }
+    }
+
+    /// Set the `Cache-Control` header of the responses. Defaults to the header of the
+    /// [`CachePolicy`](crate::CachePolicy) of the window.
+    pub fn with_cache_control(mut self, cache_control: impl Into<String>) -> Self {
+        self.cache_control = Some(cache_control.into());
+        self
+    }
+
+    /// Set the file served for a request for a directory, or `None` to not serve directories. Defaults to `index.html`.
+    pub fn with_index(mut self, index: Option<String>) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// The path inside the mount that `path` asks for, or `None` if it isn't under the prefix
+    fn relative_path<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        if self.prefix.is_empty() {
+            return Some(path);
+        }
+        path.strip_prefix(&self.prefix).ok()
+    }
+
+    /// Respond to `request` if it is for a path under the prefix
+    pub(crate) fn serve(&self, request: &AssetRequest) -> Option<io::Result<AssetResponse>> {
+        let relative = self.relative_path(request.path())?;
+        let relative = match sanitize(relative) {
+            Some(relative) => relative,
+            None => return Some(status(StatusCode::FORBIDDEN)),
+        };
+
+        let response = match &self.source {
+            MountSource::Directory(root) => self.serve_directory(request, root, &relative),
+            #[cfg(feature = "include-dir")]
+            MountSource::Embedded(root) => self.serve_embedded(request, root, &relative),
+            #[cfg(feature = "archive")]
+            MountSource::Archive(archive) => self.serve_archive(request, archive, &relative),
+        };
+        Some(response)
+    }
+
+    fn serve_directory(
+        &self,
+        request: &AssetRequest,
+        root: &Path,
+        relative: &Path,
+    ) -> io::Result<AssetResponse> {
+        let mut path = root.join(relative);
+        if path.is_dir() {
+            match &self.index {
+                Some(index) => path.push(index),
+                None => return status(StatusCode::NOT_FOUND),
+            }
+        }
+        if !path.is_file() {
+            return status(StatusCode::NOT_FOUND);
+        }
+
+        // A symlink in the directory can still point out of it
+        let root = dunce::canonicalize(root)?;
+        let path = dunce::canonicalize(path)?;
+        if !path.starts_with(&root) {
+            return status(StatusCode::FORBIDDEN);
+        }
+
+        let etag = format!("\"{}\"", file_version(&path.metadata()?));
+        if is_fresh(request, &etag) {
+            return self.not_modified(&etag);
+        }
+
+        let response = crate::range::serve_file(request, &path)?;
+        Ok(self.with_cache_headers(response, &etag))
+    }
+
+    #[cfg(feature = "include-dir")]
+    fn serve_embedded(
+        &self,
+        request: &AssetRequest,
+        root: &'static include_dir::Dir<'static>,
+        relative: &Path,
+    ) -> io::Result<AssetResponse> {
+        use std::hash::{Hash, Hasher};
+
+        let dir = if relative.as_os_str().is_empty() {
+            Some(root)
+        } else {
+            root.get_dir(relative)
+        };
+        let file = match (dir, &self.index) {
+            (Some(dir), Some(index)) => dir.get_file(dir.path().join(index)),
+            (Some(_), None) => None,
+            (None, _) => root.get_file(relative),
+        };
+        let file = match file {
+            Some(file) => file,
+            None => return status(StatusCode::NOT_FOUND),
+        };
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        file.contents().hash(&mut hasher);
+        let etag = format!("\"{:x}\"", hasher.finish());
+        if is_fresh(request, &etag) {
+            return self.not_modified(&etag);
+        }
+
+        let content_type = match infer::get(file.contents()).map(|kind| kind.mime_type()) {
+            Some(mime) if mime != "text/plain" => mime,
+            _ => crate::protocol::get_mime_by_ext(file.path()),
+        };
+        let response =
+            crate::range::serve_reader(request, io::Cursor::new(file.contents()), content_type)?;
+        Ok(self.with_cache_headers(response, &etag))
+    }
+
+    #[cfg(feature = "archive")]
+    fn serve_archive(
+        &self,
+        request: &AssetRequest,
+        archive: &crate::archive::AssetArchive,
+        relative: &Path,
+    ) -> io::Result<AssetResponse> {
+        let relative = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let index = self.index.as_ref().map(|index| match relative.as_str() {
+            "" => index.clone(),
+            directory => format!("{directory}/{index}"),
+        });
+        let (path, entry) = match archive.get(&relative) {
+            Some(entry) => (relative, entry),
+            None => match index.and_then(|index| Some((index.clone(), archive.get(&index)?))) {
+                Some(found) => found,
+                None => return status(StatusCode::NOT_FOUND),
+            },
+        };
+
+        let content_type = crate::protocol::get_mime_by_ext(Path::new(&path));
+        let gzip = !request.headers().contains_key(header::RANGE)
+            && crate::compression::accepts_gzip(request);
+        // The gzip body is a different representation of the file, so it needs its own tag
+        let etag = if gzip {
+            format!("\"{:x}-gz\"", entry.hash)
+        } else {
+            format!("\"{:x}\"", entry.hash)
+        };
+        if is_fresh(request, &etag) {
+            return self.not_modified(&etag);
+        }
+
+        let response = if gzip {
+            Response::builder()
+                .header(header::CONTENT_TYPE, content_type)
+                .header(header::CONTENT_ENCODING, "gzip")
+                .header(header::CONTENT_LENGTH, entry.compressed.len())
+                .header(header::VARY, "Accept-Encoding")
+                .body(Cow::from(entry.compressed))
+                .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?
+        } else {
+            crate::range::serve_reader(request, io::Cursor::new(entry.decompress()?), content_type)?
+        };
+        Ok(self.with_cache_headers(response, &etag))
+    }
+
+    fn with_cache_headers(&self, mut response: AssetResponse, etag: &str) -> AssetResponse {
+        let headers = response.headers_mut();
+        if let Ok(etag) = HeaderValue::from_str(etag) {
+            headers.insert(header::ETAG, etag);
+        }
+        if let Some(Ok(cache_control)) = self.cache_control.as_deref().map(HeaderValue::from_str) {
+            headers.insert(header::CACHE_CONTROL, cache_control);
+        }
+        response
+    }
+
+    fn not_modified(&self, etag: &str) -> io::Result<AssetResponse> {
+        Ok(self.with_cache_headers(status(StatusCode::NOT_MODIFIED)?, etag))
+    }
+}
+
+/// The path with its `.` segments removed, or `None` if it has segments that could leave the mounted directory
+fn sanitize(path: &Path) -> Option<PathBuf> {
+    let mut sanitized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(segment) => sanitized.push(segment),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(sanitized)
+}
+
+/// A tag that changes when the file with `metadata` is changed, made of its length and modification time
+pub(crate) fn file_version(metadata: &Metadata) -> String {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|modified| modified.as_nanos())
+        .unwrap_or_default();
+    format!("{:x}-{:x}", metadata.len(), modified)
+}
+
+/// Whether the webview already has the version of the file with `etag`
+pub(crate) fn is_fresh(request: &AssetRequest, etag: &str) -> bool {
+    request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| {
+            value
+                .split(',')
+                .any(|tag| tag.trim().trim_start_matches("W/") == etag || tag.trim() == "*")
+        })
+        .unwrap_or(false)
+}
+
+pub(crate) fn status(status: StatusCode) -> io::Result<AssetResponse> {
+    Response::builder()
+        .status(status)
+        .body(Cow::from(Vec::new()))
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wry::http::Request;
+
+    fn request(uri: &str, if_none_match: Option<&str>) -> AssetRequest {
+        let mut request = Request::builder().uri(uri);
+        if let Some(etag) = if_none_match {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        request.body(Vec::new()).unwrap().into()
+    }
+
+    #[test]
+    fn sanitize_keeps_relative_paths() {
+        assert_eq!(
+            sanitize(Path::new("images/logo.png")),
+            Some(PathBuf::from("images/logo.png"))
+        );
+        assert_eq!(
+            sanitize(Path::new("./images/./logo.png")),
+            Some(PathBuf::from("images/logo.png"))
+        );
+        assert_eq!(sanitize(Path::new("")), Some(PathBuf::new()));
+    }
+
+    #[test]
+    fn sanitize_refuses_parent_directories() {
+        assert_eq!(sanitize(Path::new("..")), None);
+        assert_eq!(sanitize(Path::new("../secret")), None);
+        assert_eq!(sanitize(Path::new("images/../../secret")), None);
+        // Even a parent directory that stays inside the mount is refused
+        assert_eq!(sanitize(Path::new("images/../logo.png")), None);
+    }
+
+    #[test]
+    fn sanitize_refuses_absolute_paths() {
+        assert_eq!(sanitize(Path::new("/etc/passwd")), None);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn sanitize_refuses_windows_prefixes() {
+        assert_eq!(sanitize(Path::new(r"C:\Windows\win.ini")), None);
+        assert_eq!(sanitize(Path::new(r"C:secret")), None);
+        assert_eq!(sanitize(Path::new(r"\\server\share\secret")), None);
+        assert_eq!(sanitize(Path::new(r"\\?\C:\secret")), None);
+        assert_eq!(sanitize(Path::new(r"images\..\..\secret")), None);
+    }
+
+    #[test]
+    fn encoded_traversal_is_forbidden() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("logo.png"), b"logo").unwrap();
+        let mount = AssetMount::directory("assets", root.path());
+
+        for uri in [
+            "dioxus://index.html/assets/%2e%2e/secret",
+            "dioxus://index.html/assets/images/%2E%2E/%2E%2E/secret",
+            "dioxus://index.html/assets/..%2Fsecret",
+        ] {
+            let response = mount.serve(&request(uri, None)).unwrap().unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{uri}");
+        }
+
+        let response = mount
+            .serve(&request("dioxus://index.html/assets/logo.png", None))
+            .unwrap()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(mount
+            .serve(&request("dioxus://index.html/other/logo.png", None))
+            .is_none());
+    }
+
+    #[test]
+    fn fresh_etags() {
+        let etag = "\"3-1a\"";
+        let uri = "dioxus://index.html/assets/logo.png";
+        assert!(is_fresh(&request(uri, Some("\"3-1a\"")), etag));
+        assert!(is_fresh(&request(uri, Some("W/\"3-1a\"")), etag));
+        assert!(is_fresh(&request(uri, Some("\"old\", \"3-1a\"")), etag));
+        assert!(is_fresh(&request(uri, Some("\"old\",W/\"3-1a\"")), etag));
+        assert!(is_fresh(&request(uri, Some("*")), etag));
+    }
+
+    #[test]
+    fn stale_etags() {
+        let etag = "\"3-1a\"";
+        let uri = "dioxus://index.html/assets/logo.png";
+        assert!(!is_fresh(&request(uri, None), etag));
+        assert!(!is_fresh(&request(uri, Some("\"old\"")), etag));
+        assert!(!is_fresh(&request(uri, Some("\"old\", \"older\"")), etag));
+        // The tag must match exactly, including its quotes
+        assert!(!is_fresh(&request(uri, Some("3-1a")), etag));
+        assert!(!is_fresh(&request(uri, Some("\"3-1a")), etag));
+    }
+}
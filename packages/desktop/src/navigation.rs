@@ -0,0 +1,115 @@
+//! Policies for the pages the webview navigates to and the new windows it is asked to open.
+//!
+//! Without them, a link to an external site navigates the app webview away from the app, and there is no way back.
+//! Set them with [`Config::with_navigation_handler`](crate::Config::with_navigation_handler) and
+//! [`Config::with_new_window_handler`](crate::Config::with_new_window_handler):
+//!
+//! ```rust, ignore
+//! Config::new().with_navigation_handler(|url| {
+//!     if is_app_url(url) {
+//!         NavigationAction::Allow
+//!     } else {
+//!         NavigationAction::OpenExternal
+//!     }
+//! })
+//! ```
+//!
+//! [`NavigationAction::OpenExternal`] only opens `http`, `https` and `mailto` urls. Other schemes, like `file:` or
+//! handlers registered by other applications, are denied.
+
+/// What happens to a navigation or a new window the page asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationAction {
+    /// Let the webview load the url
+    Allow,
+    /// Cancel the navigation
+    Deny,
+    /// Cancel the navigation and open the url in the default browser of the system
+    OpenExternal,
+}
+
+pub(crate) type NavigationHandler = Box<dyn Fn(&str) -> NavigationAction>;
+
+/// The host the `dioxus://` protocol serves the app from
+const APP_HOST: &str = "index.html";
+
+/// The host Windows serves the app from. Windows serves custom protocols from `http(s)://<protocol>.<host>`.
+const WINDOWS_APP_HOST: &str = "dioxus.index.html";
+
+/// The schemes [`NavigationAction::OpenExternal`] passes to the browser of the system
+const EXTERNAL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Check if `url` is a page or an asset of the app, and not an external site
+pub fn is_app_url(url: &str) -> bool {
+    let url = match url::Url::parse(url) {
+        Ok(url) => url,
+        Err(_) => return false,
+    };
+    match url.scheme() {
+        "dioxus" => url.host_str() == Some(APP_HOST),
+        "http" | "https" => url.host_str() == Some(WINDOWS_APP_HOST) && url.port().is_none(),
+        _ => false,
+    }
+}
+
+/// Check if `url` may be opened in the default browser of the system
+fn can_open_externally(url: &str) -> bool {
+    url::Url::parse(url).map_or(false, |url| EXTERNAL_SCHEMES.contains(&url.scheme()))
+}
+
+/// Carry out the action of a handler, and return if the webview should load the url
+pub(crate) fn apply(action: NavigationAction, url: &str) -> bool {
+    match action {
+        NavigationAction::Allow => true,
+        NavigationAction::Deny => false,
+        NavigationAction::OpenExternal => {
+            if !can_open_externally(url) {
+                tracing::warn!("Refusing to open {} outside of the app", url);
+            } else if let Err(err) = webbrowser::open(url) {
+                tracing::error!("Failed to open {} in the browser: {}", url, err);
+            }
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn app_urls() {
+        assert!(is_app_url("dioxus://index.html/"));
+        assert!(is_app_url("dioxus://index.html/assets/logo.png"));
+        assert!(is_app_url("https://dioxus.index.html/"));
+        assert!(is_app_url("http://dioxus.index.html/settings?tab=1"));
+
+        assert!(!is_app_url("https://dioxus.evil.com/"));
+        assert!(!is_app_url("https://dioxus.index.html.evil.com/"));
+        assert!(!is_app_url("https://dioxus.index.html:8080/"));
+        assert!(!is_app_url("https://evil.com/dioxus://index.html/"));
+        assert!(!is_app_url("dioxus://evil.com/"));
+        assert!(!is_app_url("ftp://dioxus.index.html/"));
+        assert!(!is_app_url("not a url"));
+    }
+
+    #[test]
+    fn external_schemes() {
+        assert!(can_open_externally("https://dioxuslabs.com/"));
+        assert!(can_open_externally("http://example.com/page"));
+        assert!(can_open_externally("mailto:someone@example.com"));
+
+        assert!(!can_open_externally("file:///etc/passwd"));
+        assert!(!can_open_externally("ms-settings:privacy"));
+        assert!(!can_open_externally("javascript:alert(1)"));
+        assert!(!can_open_externally("dioxus://index.html/"));
+        assert!(!can_open_externally("not a url"));
+    }
+
+    #[test]
+    fn disallowed_schemes_are_not_loaded_or_opened() {
+        assert!(!apply(NavigationAction::OpenExternal, "file:///etc/passwd"));
+        assert!(!apply(NavigationAction::Deny, "https://dioxuslabs.com/"));
+        assert!(apply(NavigationAction::Allow, "dioxus://index.html/"));
+    }
+}
@@ -0,0 +1,193 @@
+//! Operating system notifications.
+//!
+//! Notifications are shown by the operating system's notification center. Clicks on a notification are sent back to
+//! the window that showed it, so click callbacks run on the event loop like any other event handler.
+//!
+//! This needs the `notifications` feature. On Linux it talks to the notification server over D-Bus.
+//!
+//! Click callbacks are only supported on Linux. Windows and macOS show the notification but don't report clicks.
+//! When the app uses the XDG desktop portals (see [`Config::with_portals`](crate::Config)), notifications are sent
+//! through the notification portal, which doesn't report clicks either.
+
+use std::cell::RefCell;
+
+use dioxus_core::ScopeState;
+use thiserror::Error;
+
+use crate::{use_window, DesktopContext};
+
+/// A notification to show with [`DesktopService::send_notification`](crate::DesktopService::send_notification).
+///
+/// ```rust, ignore
+/// window.send_notification(
+///     NotificationBuilder::new("Download finished")
+///         .with_body("notes.pdf was saved to your downloads")
+///         .on_click(move || open_downloads()),
+/// )?;
+/// ```
+pub struct NotificationBuilder {
+    pub(crate) title: String,
+    pub(crate) body: Option<String>,
+    pub(crate) icon: Option<String>,
+    pub(crate) on_click: Option<Box<dyn FnMut()>>,
+}
+
+impl NotificationBuilder {
+    /// Create a notification with a title
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            body: None,
+            icon: None,
+            on_click: None,
+        }
+    }
+
+    /// Set the text shown under the title
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Set the icon of the notification. This is the path of an image, or on Linux the name of an icon from the
+    /// icon theme, like `"mail-unread"`.
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Call `handler` when the user clicks the notification. Only supported on Linux.
+    pub fn on_click(mut self, handler: impl FnMut() + 'static) -> Self {
+        self.on_click = Some(Box::new(handler));
+        self
+    }
+}
+
+/// The unique identifier of a notification sent by a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NotificationId(pub(crate) usize);
+
+/// An error that can occur when showing a notification.
+#[derive(Error, Debug)]
+pub enum NotificationError {
+    /// The operating system could not show the notification
+    #[error("Failed to show the notification: {0}")]
+    Show(String),
+}
+
+/// Show a notification and call `on_click` from another thread when it is clicked
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+pub(crate) fn show(
+    notification: &NotificationBuilder,
+    on_click: Option<impl FnOnce() + Send + 'static>,
+) -> Result<(), NotificationError> {
+    let mut native = native_notification(notification);
+    if on_click.is_some() {
+        // The "default" action is triggered by clicking the notification itself
+        native.action("default", "Open");
+    }
+    let handle = native
+        .show()
+        .map_err(|err| NotificationError::Show(err.to_string()))?;
+
+    if let Some(on_click) = on_click {
+        // Waiting for the action blocks until the notification is closed
+        std::thread::spawn(move || {
+            handle.wait_for_action(|action| {
+                if action == "default" {
+                    on_click();
+                }
+            })
+        });
+    }
+    Ok(())
+}
+
+/// Show a notification. Clicks are not reported on this platform.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub(crate) fn show(
+    notification: &NotificationBuilder,
+    _on_click: Option<impl FnOnce() + Send + 'static>,
+) -> Result<(), NotificationError> {
+    native_notification(notification)
+        .show()
+        .map(|_| ())
+        .map_err(|err| NotificationError::Show(err.to_string()))
+}
+
+fn native_notification(notification: &NotificationBuilder) -> notify_rust::Notification {
+    let mut native = notify_rust::Notification::new();
+    native.summary(&notification.title);
+    if let Some(body) = &notification.body {
+        native.body(body);
+    }
+    if let Some(icon) = &notification.icon {
+        native.icon(icon);
+    }
+    native
+}
+
+/// Sends notifications from a component. Click callbacks of the notifications are removed when the component is
+/// unmounted.
+pub struct UseNotification {
+    desktop: DesktopContext,
+    sent: RefCell<Vec<NotificationId>>,
+}
+
+impl UseNotification {
+    /// Show a notification
+    pub fn send(
+        &self,
+        notification: NotificationBuilder,
+    ) -> Result<NotificationId, NotificationError> {
+        let id = self.desktop.send_notification(notification)?;
+        self.sent.borrow_mut().push(id);
+        Ok(id)
+    }
+}
+
+impl Drop for UseNotification {
+    fn drop(&mut self) {
+        for id in self.sent.get_mut().drain(..) {
+            self.desktop.remove_notification_handler(id);
+        }
+    }
+}
+
+/// Get a handle that shows operating system notifications.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let notifications = use_notification(cx);
+///     let count = use_state(cx, || 0);
+///
+///     render! {
+///         button {
+///             onclick: move |_| {
+///                 let _ = notifications.send(
+///                     NotificationBuilder::new("Reminder")
+///                         .with_body("Time to take a break")
+///                         .on_click({
+///                             to_owned![count];
+///                             move || count += 1
+///                         }),
+///                 );
+///             },
+///             "Remind me ({count} clicked)"
+///         }
+///     }
+/// }
+/// ```
+pub fn use_notification(cx: &ScopeState) -> &UseNotification {
+    let desktop = use_window(cx);
+    cx.use_hook(|| UseNotification {
+        desktop: desktop.clone(),
+        sent: Default::default(),
+    })
+}
@@ -0,0 +1,422 @@
+//! Printing the page to a PDF without the print dialog.
+//!
+//! Each platform prints with its own webview: `PrintToPdf` of WebView2 on Windows, a `WebKitPrintOperation` that
+//! prints to a file on Linux, and an `NSPrintOperation` that saves the job on macOS. On Linux this needs the
+//! `webkitgtk` feature.
+
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+use wry::webview::WebView;
+
+/// The size of a printed page, in millimetres
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageSize {
+    /// The width of the page in portrait orientation
+    pub width: f64,
+    /// The height of the page in portrait orientation
+    pub height: f64,
+}
+
+impl PageSize {
+    /// ISO A4, 210 × 297 mm
+    pub const A4: PageSize = PageSize::new(210.0, 297.0);
+    /// ISO A3, 297 × 420 mm
+    pub const A3: PageSize = PageSize::new(297.0, 420.0);
+    /// ISO A5, 148 × 210 mm
+    pub const A5: PageSize = PageSize::new(148.0, 210.0);
+    /// US Letter, 8.5 × 11 in
+    pub const LETTER: PageSize = PageSize::new(215.9, 279.4);
+    /// US Legal, 8.5 × 14 in
+    pub const LEGAL: PageSize = PageSize::new(215.9, 355.6);
+
+    /// A page of `width` × `height` millimetres
+    pub const fn new(width: f64, height: f64) -> Self {
+        Self { width, height }
+    }
+}
+
+/// The margins of a printed page, in millimetres
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageMargins {
+    /// The margin above the content
+    pub top: f64,
+    /// The margin right of the content
+    pub right: f64,
+    /// The margin below the content
+    pub bottom: f64,
+    /// The margin left of the content
+    pub left: f64,
+}
+
+impl PageMargins {
+    /// The same margin on every side
+    pub const fn uniform(margin: f64) -> Self {
+        Self {
+            top: margin,
+            right: margin,
+            bottom: margin,
+            left: margin,
+        }
+    }
+}
+
+impl Default for PageMargins {
+    fn default() -> Self {
+        Self::uniform(10.0)
+    }
+}
+
+/// How [`DesktopService::print_to_pdf`](crate::DesktopService::print_to_pdf) lays out the page
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintOptions {
+    pub(crate) page_size: PageSize,
+    pub(crate) margins: PageMargins,
+    pub(crate) landscape: bool,
+    pub(crate) print_background: bool,
+    pub(crate) scale: f64,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            page_size: PageSize::A4,
+            margins: PageMargins::default(),
+            landscape: false,
+            print_background: true,
+            scale: 1.0,
+        }
+    }
+}
+
+impl PrintOptions {
+    /// Print on portrait A4 pages with 10 mm margins and the backgrounds of the page
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the size of the pages
+    pub fn with_page_size(mut self, page_size: PageSize) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Set the margins of the pages
+    pub fn with_margins(mut self, margins: PageMargins) -> Self {
+        self.margins = margins;
+        self
+    }
+
+    /// Turn the pages sideways
+    pub fn with_landscape(mut self, landscape: bool) -> Self {
+        self.landscape = landscape;
+        self
+    }
+
+    /// Print the background colors and images of the page. On by default.
+    pub fn with_print_background(mut self, print_background: bool) -> Self {
+        self.print_background = print_background;
+        self
+    }
+
+    /// Scale the content, where `1.0` is the size it has on screen
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+}
+
+/// An error that occurred while printing to a PDF
+#[derive(Error, Debug)]
+pub enum PdfError {
+    /// The webview failed to print the page
+    #[error("Failed to print the page: {0}")]
+    Print(String),
+    /// The path of the PDF is not valid
+    #[error("Invalid path for the PDF: {0}")]
+    InvalidPath(PathBuf),
+    /// The webview of this platform can't print to a PDF
+    #[error("Printing to a PDF is not supported on this platform")]
+    Unsupported,
+}
+
+/// Print the page of `webview` to the PDF at `path`. The PDF is written when the returned future resolves.
+pub(crate) async fn print_to_pdf(
+    webview: &WebView,
+    path: &Path,
+    options: &PrintOptions,
+) -> Result<(), PdfError> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map_err(|_| PdfError::InvalidPath(path.to_path_buf()))?
+            .join(path)
+    };
+
+    let result = platform::print(webview, &path, options)?;
+    result.await.unwrap_or_else(|_| {
+        Err(PdfError::Print(
+            "the webview was closed before the PDF was written".to_string(),
+        ))
+    })
+}
+
+type PrintResult = tokio::sync::oneshot::Receiver<Result<(), PdfError>>;
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        ICoreWebView2Environment6, ICoreWebView2_2, ICoreWebView2_7,
+        COREWEBVIEW2_PRINT_ORIENTATION_LANDSCAPE, COREWEBVIEW2_PRINT_ORIENTATION_PORTRAIT,
+    };
+    use webview2_com::PrintToPdfCompletedHandler;
+    use windows::core::{ComInterface, HSTRING};
+    use windows::Win32::Foundation::BOOL;
+    use wry::webview::WebviewExtWindows;
+
+    const MM_PER_INCH: f64 = 25.4;
+
+    pub(super) fn print(
+        webview: &WebView,
+        path: &Path,
+        options: &PrintOptions,
+    ) -> Result<PrintResult, PdfError> {
+        let error = |err: windows::core::Error| PdfError::Print(err.message().to_string());
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        unsafe {
+            let core = webview.controller().CoreWebView2().map_err(error)?;
+            let environment = core
+                .cast::<ICoreWebView2_2>()
+                .and_then(|core| core.Environment())
+                .and_then(|environment| environment.cast::<ICoreWebView2Environment6>())
+                .map_err(error)?;
+            let core = core.cast::<ICoreWebView2_7>().map_err(error)?;
+
+            // WebView2 measures pages in inches, and turns the upright page size by the orientation
+            let PageSize { width, height } = options.page_size;
+            let margins = options.margins;
+            let settings = environment.CreatePrintSettings().map_err(error)?;
+            settings
+                .SetOrientation(if options.landscape {
+                    COREWEBVIEW2_PRINT_ORIENTATION_LANDSCAPE
+                } else {
+                    COREWEBVIEW2_PRINT_ORIENTATION_PORTRAIT
+                })
+                .and_then(|_| settings.SetPageWidth(width / MM_PER_INCH))
+                .and_then(|_| settings.SetPageHeight(height / MM_PER_INCH))
+                .and_then(|_| settings.SetMarginTop(margins.top / MM_PER_INCH))
+                .and_then(|_| settings.SetMarginRight(margins.right / MM_PER_INCH))
+                .and_then(|_| settings.SetMarginBottom(margins.bottom / MM_PER_INCH))
+                .and_then(|_| settings.SetMarginLeft(margins.left / MM_PER_INCH))
+                .and_then(|_| settings.SetScaleFactor(options.scale))
+                .and_then(|_| {
+                    settings.SetShouldPrintBackgrounds(BOOL::from(options.print_background))
+                })
+                .map_err(error)?;
+
+            let handler = PrintToPdfCompletedHandler::create(Box::new(move |result, printed| {
+                _ = tx.send(match result {
+                    Ok(()) if printed => Ok(()),
+                    Ok(()) => Err(PdfError::Print("the PDF could not be written".to_string())),
+                    Err(err) => Err(error(err)),
+                });
+                Ok(())
+            }));
+            core.PrintToPdf(&HSTRING::from(path.as_os_str()), &settings, &handler)
+                .map_err(error)?;
+        }
+
+        Ok(rx)
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "webkitgtk"))]
+mod platform {
+    use super::*;
+    use gtk::prelude::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use webkit2gtk::{PrintOperation, PrintOperationExt, SettingsExt, WebViewExt};
+    use wry::webview::WebviewExtUnix;
+
+    pub(super) fn print(
+        webview: &WebView,
+        path: &Path,
+        options: &PrintOptions,
+    ) -> Result<PrintResult, PdfError> {
+        let uri = url::Url::from_file_path(path)
+            .map_err(|_| PdfError::InvalidPath(path.to_path_buf()))?;
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        let webview = webview.webview();
+        if let Some(settings) = WebViewExt::settings(&*webview) {
+            settings.set_print_backgrounds(options.print_background);
+        }
+
+        // Printing to a file with the output settings skips the print dialog
+        let settings = gtk::PrintSettings::new();
+        settings.set_printer("Print to File");
+        settings.set("output-file-format", Some("pdf"));
+        settings.set("output-uri", Some(uri.as_str()));
+        settings.set_scale(options.scale * 100.0);
+
+        let PageSize { width, height } = options.page_size;
+        let margins = options.margins;
+        let page_setup = gtk::PageSetup::new();
+        page_setup.set_paper_size(&gtk::PaperSize::new_custom(
+            "dioxus-pdf",
+            "PDF",
+            width,
+            height,
+            gtk::Unit::Mm,
+        ));
+        page_setup.set_orientation(if options.landscape {
+            gtk::PageOrientation::Landscape
+        } else {
+            gtk::PageOrientation::Portrait
+        });
+        page_setup.set_top_margin(margins.top, gtk::Unit::Mm);
+        page_setup.set_right_margin(margins.right, gtk::Unit::Mm);
+        page_setup.set_bottom_margin(margins.bottom, gtk::Unit::Mm);
+        page_setup.set_left_margin(margins.left, gtk::Unit::Mm);
+
+        let operation = PrintOperation::new(&*webview);
+        operation.set_print_settings(&settings);
+        operation.set_page_setup(&page_setup);
+
+        // `failed` is emitted before `finished`, so the first result wins
+        let tx = Rc::new(RefCell::new(Some(tx)));
+        operation.connect_failed({
+            let tx = tx.clone();
+            move |_, err| {
+                if let Some(tx) = tx.borrow_mut().take() {
+                    _ = tx.send(Err(PdfError::Print(err.to_string())));
+                }
+            }
+        });
+        operation.connect_finished(move |_| {
+            if let Some(tx) = tx.borrow_mut().take() {
+                _ = tx.send(Ok(()));
+            }
+        });
+        operation.print();
+
+        Ok(rx)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use crate::titlebar::{NSRect, NSSize};
+    use objc::runtime::{Object, BOOL, NO, YES};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::CString;
+    use wry::webview::WebviewExtMacOS;
+
+    const POINTS_PER_MM: f64 = 72.0 / 25.4;
+    const NS_PAPER_ORIENTATION_PORTRAIT: isize = 0;
+    const NS_PAPER_ORIENTATION_LANDSCAPE: isize = 1;
+
+    unsafe fn ns_string(value: &str) -> *mut Object {
+        let value = CString::new(value).unwrap_or_default();
+        msg_send![class!(NSString), stringWithUTF8String: value.as_ptr()]
+    }
+
+    pub(super) fn print(
+        webview: &WebView,
+        path: &Path,
+        options: &PrintOptions,
+    ) -> Result<PrintResult, PdfError> {
+        let path_str = path
+            .to_str()
+            .ok_or_else(|| PdfError::InvalidPath(path.to_path_buf()))?;
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        unsafe {
+            let shared: *mut Object = msg_send![class!(NSPrintInfo), sharedPrintInfo];
+            let info: *mut Object = msg_send![shared, copy];
+
+            // Saving the job writes it to the URL instead of a printer, without showing the print panel
+            let url: *mut Object = msg_send![class!(NSURL), fileURLWithPath: ns_string(path_str)];
+            let _: () = msg_send![info, setJobDisposition: ns_string("NSPrintSaveJob")];
+            let dictionary: *mut Object = msg_send![info, dictionary];
+            let _: () =
+                msg_send![dictionary, setObject: url forKey: ns_string("NSPrintJobSavingURL")];
+            let scale: *mut Object = msg_send![class!(NSNumber), numberWithDouble: options.scale];
+            let _: () =
+                msg_send![dictionary, setObject: scale forKey: ns_string("NSPrintScalingFactor")];
+
+            let PageSize { width, height } = options.page_size;
+            let margins = options.margins;
+            let paper_size = NSSize {
+                width: width * POINTS_PER_MM,
+                height: height * POINTS_PER_MM,
+            };
+            // Setting the orientation after the upright paper size turns it
+            let _: () = msg_send![info, setPaperSize: paper_size];
+            let _: () = msg_send![info, setOrientation: if options.landscape {
+                NS_PAPER_ORIENTATION_LANDSCAPE
+            } else {
+                NS_PAPER_ORIENTATION_PORTRAIT
+            }];
+            let _: () = msg_send![info, setTopMargin: margins.top * POINTS_PER_MM];
+            let _: () = msg_send![info, setRightMargin: margins.right * POINTS_PER_MM];
+            let _: () = msg_send![info, setBottomMargin: margins.bottom * POINTS_PER_MM];
+            let _: () = msg_send![info, setLeftMargin: margins.left * POINTS_PER_MM];
+            let _: () = msg_send![info, setHorizontallyCentered: NO];
+            let _: () = msg_send![info, setVerticallyCentered: NO];
+
+            // WebKit only has a setting for backgrounds since macOS 13.3
+            let wk_webview = webview.webview();
+            let configuration: *mut Object = msg_send![wk_webview, configuration];
+            let preferences: *mut Object = msg_send![configuration, preferences];
+            let can_set_backgrounds: BOOL =
+                msg_send![preferences, respondsToSelector: sel!(setShouldPrintBackgrounds:)];
+            if can_set_backgrounds == YES {
+                let print_background = if options.print_background { YES } else { NO };
+                let _: () = msg_send![preferences, setShouldPrintBackgrounds: print_background];
+            }
+
+            let operation: *mut Object = msg_send![wk_webview, printOperationWithPrintInfo: info];
+            let _: () = msg_send![operation, setShowsPrintPanel: NO];
+            let _: () = msg_send![operation, setShowsProgressPanel: NO];
+
+            // The print view of WKWebView is empty until it is given the size of the webview
+            let bounds: NSRect = msg_send![wk_webview, bounds];
+            let view: *mut Object = msg_send![operation, view];
+            let _: () = msg_send![view, setFrame: bounds];
+
+            let printed: BOOL = msg_send![operation, runOperation];
+            let _: () = msg_send![info, release];
+
+            _ = tx.send(if printed == YES {
+                Ok(())
+            } else {
+                Err(PdfError::Print("the PDF could not be written".to_string()))
+            });
+        }
+
+        Ok(rx)
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(target_os = "linux", feature = "webkitgtk"),
+    target_os = "macos"
+)))]
+mod platform {
+    use super::*;
+
+    pub(super) fn print(
+        _webview: &WebView,
+        _path: &Path,
+        _options: &PrintOptions,
+    ) -> Result<PrintResult, PdfError> {
+        Err(PdfError::Unsupported)
+    }
+}
@@ -0,0 +1,41 @@
+//! Values the operating system doesn't notify us about when they change, like the battery status and the idle time.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use dioxus_core::ScopeState;
+
+/// Read a value with `read` every `interval`, and rerender the component when it changes.
+///
+/// The value starts as `initial`. Without the `tokio_runtime` feature it is never read again.
+#[cfg_attr(not(feature = "tokio_runtime"), allow(unused_variables))]
+pub(crate) fn use_polled<T: Copy + PartialEq + 'static>(
+    cx: &ScopeState,
+    interval: Duration,
+    initial: impl FnOnce() -> T,
+    read: impl FnMut() -> T + 'static,
+) -> T {
+    let value = cx.use_hook(|| {
+        let value = Rc::new(Cell::new(initial()));
+
+        #[cfg(feature = "tokio_runtime")]
+        {
+            let value = value.clone();
+            let update = cx.schedule_update();
+            let mut read = read;
+            cx.spawn(async move {
+                loop {
+                    tokio::time::sleep(interval).await;
+                    let new_value = read();
+                    if value.replace(new_value) != new_value {
+                        update();
+                    }
+                }
+            });
+        }
+
+        value
+    });
+    value.get()
+}
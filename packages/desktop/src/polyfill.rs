@@ -0,0 +1,232 @@
+//! Polyfills for the HTML features older system webviews lack.
+//!
+//! WebKitGTK and WKWebView follow the version of the system, so an app can run on a webview that is years older than
+//! the one it was developed with. `<dialog>` is missing before WebKit 15.4, the `popover` attribute before WebKit 17
+//! and `inert` before WebKit 15.5. The polyfills only install themselves when the webview lacks the feature, and cover
+//! the behaviour apps rely on: opening and closing, the `close`, `cancel`, `beforetoggle` and `toggle` events, escape
+//! and light dismiss, and blocking the input of inert content. They don't implement the top layer, so an open dialog or
+//! popover is only drawn above the page by its `z-index`.
+
+/// Installs the polyfills the webview needs
+pub(crate) const POLYFILL_SCRIPT: &str = r#"(function () {
+    const addStyle = (css) => {
+        const append = () => {
+            const style = document.createElement("style");
+            style.textContent = css;
+            document.head.appendChild(style);
+        };
+        if (document.head) {
+            append();
+        } else {
+            document.addEventListener("DOMContentLoaded", append);
+        }
+    };
+    const defineMethod = (prototype, name, method) => {
+        Object.defineProperty(prototype, name, { value: method, configurable: true, writable: true });
+    };
+    const TOP = 2147483647;
+
+    if (!("inert" in HTMLElement.prototype)) {
+        Object.defineProperty(HTMLElement.prototype, "inert", {
+            get() { return this.hasAttribute("inert"); },
+            set(value) { value ? this.setAttribute("inert", "") : this.removeAttribute("inert"); },
+            configurable: true,
+        });
+        addStyle("[inert] { pointer-events: none; cursor: default; user-select: none; -webkit-user-select: none; }");
+        // Focus can still reach inert content with the keyboard, so it is moved back out
+        document.addEventListener("focusin", (event) => {
+            if (event.target.closest && event.target.closest("[inert]")) {
+                event.target.blur();
+            }
+        }, true);
+    }
+
+    if (typeof HTMLDialogElement === "undefined" || typeof HTMLDialogElement.prototype.showModal !== "function") {
+        addStyle(`
+            dialog { position: absolute; left: 0; right: 0; width: fit-content; height: fit-content; margin: auto;
+                border: solid; padding: 1em; background: white; color: black; }
+            dialog:not([open]) { display: none; }
+            dialog.__dioxus-modal { position: fixed; top: 0; bottom: 0; z-index: ${TOP}; }
+            .__dioxus-backdrop { position: fixed; top: 0; right: 0; bottom: 0; left: 0; z-index: ${TOP - 1};
+                background: rgba(0, 0, 0, 0.1); }
+        `);
+        const prototype = (typeof HTMLDialogElement !== "undefined" ? HTMLDialogElement : HTMLElement).prototype;
+        const checkDialog = (element) => {
+            if (element.tagName !== "DIALOG") {
+                throw new TypeError("The element is not a dialog");
+            }
+        };
+        const modals = [];
+
+        Object.defineProperty(prototype, "open", {
+            get() { return this.hasAttribute("open"); },
+            set(value) { value ? this.setAttribute("open", "") : this.removeAttribute("open"); },
+            configurable: true,
+        });
+        defineMethod(prototype, "show", function () {
+            checkDialog(this);
+            this.setAttribute("open", "");
+        });
+        defineMethod(prototype, "showModal", function () {
+            checkDialog(this);
+            if (this.open) {
+                throw new DOMException("The dialog is already open", "InvalidStateError");
+            }
+            const backdrop = document.createElement("div");
+            backdrop.className = "__dioxus-backdrop";
+            this.parentNode.insertBefore(backdrop, this);
+            this.__dioxusBackdrop = backdrop;
+            this.classList.add("__dioxus-modal");
+            this.setAttribute("open", "");
+            modals.push(this);
+            const focus = this.querySelector("[autofocus]") || this.querySelector("button, input, select, textarea, [tabindex]");
+            if (focus) {
+                focus.focus();
+            }
+        });
+        defineMethod(prototype, "close", function (returnValue) {
+            checkDialog(this);
+            if (!this.open) {
+                return;
+            }
+            if (returnValue !== undefined) {
+                this.returnValue = returnValue;
+            }
+            this.removeAttribute("open");
+            this.classList.remove("__dioxus-modal");
+            if (this.__dioxusBackdrop) {
+                this.__dioxusBackdrop.remove();
+                this.__dioxusBackdrop = null;
+            }
+            const index = modals.indexOf(this);
+            if (index !== -1) {
+                modals.splice(index, 1);
+            }
+            this.dispatchEvent(new Event("close"));
+        });
+        if (!("returnValue" in prototype)) {
+            prototype.returnValue = "";
+        }
+
+        document.addEventListener("keydown", (event) => {
+            const modal = modals[modals.length - 1];
+            if (event.key === "Escape" && modal) {
+                event.preventDefault();
+                if (modal.dispatchEvent(new Event("cancel", { cancelable: true }))) {
+                    modal.close();
+                }
+            }
+        });
+        // `method="dialog"` forms close their dialog with the value of the submit button
+        document.addEventListener("submit", (event) => {
+            const form = event.target;
+            if (form.getAttribute("method") !== "dialog") {
+                return;
+            }
+            const dialog = form.closest("dialog");
+            if (dialog) {
+                event.preventDefault();
+                dialog.close(event.submitter ? event.submitter.value : undefined);
+            }
+        });
+    }
+
+    if (!HTMLElement.prototype.hasOwnProperty("popover")) {
+        addStyle(`
+            [popover] { position: fixed; top: 0; right: 0; bottom: 0; left: 0; width: fit-content;
+                height: fit-content; margin: auto; border: solid; padding: 0.25em; overflow: auto; z-index: ${TOP};
+                background: white; color: black; }
+            [popover]:not(.__dioxus-popover-open) { display: none; }
+        `);
+        const isAuto = (element) => element.getAttribute("popover") !== "manual";
+        const isOpen = (element) => element.classList.contains("__dioxus-popover-open");
+        const toggleEvent = (type, oldState, newState, cancelable) => {
+            const event = new Event(type, { cancelable });
+            event.oldState = oldState;
+            event.newState = newState;
+            return event;
+        };
+        const checkPopover = (element) => {
+            if (!element.hasAttribute("popover")) {
+                throw new DOMException("The element is not a popover", "NotSupportedError");
+            }
+        };
+
+        Object.defineProperty(HTMLElement.prototype, "popover", {
+            get() { return this.hasAttribute("popover") ? (isAuto(this) ? "auto" : "manual") : null; },
+            set(value) { value === null ? this.removeAttribute("popover") : this.setAttribute("popover", value); },
+            configurable: true,
+        });
+        defineMethod(HTMLElement.prototype, "showPopover", function () {
+            checkPopover(this);
+            if (isOpen(this)) {
+                throw new DOMException("The popover is already open", "InvalidStateError");
+            }
+            if (!this.dispatchEvent(toggleEvent("beforetoggle", "closed", "open", true))) {
+                return;
+            }
+            // Opening an auto popover closes the other auto popovers that don't contain it
+            if (isAuto(this)) {
+                document.querySelectorAll(".__dioxus-popover-open").forEach((open) => {
+                    if (isAuto(open) && !open.contains(this)) {
+                        open.hidePopover();
+                    }
+                });
+            }
+            this.classList.add("__dioxus-popover-open");
+            this.dispatchEvent(toggleEvent("toggle", "closed", "open", false));
+        });
+        defineMethod(HTMLElement.prototype, "hidePopover", function () {
+            checkPopover(this);
+            if (!isOpen(this)) {
+                throw new DOMException("The popover is not open", "InvalidStateError");
+            }
+            this.dispatchEvent(toggleEvent("beforetoggle", "open", "closed", false));
+            this.classList.remove("__dioxus-popover-open");
+            this.dispatchEvent(toggleEvent("toggle", "open", "closed", false));
+        });
+        defineMethod(HTMLElement.prototype, "togglePopover", function (force) {
+            checkPopover(this);
+            const open = force === undefined ? !isOpen(this) : force;
+            if (open && !isOpen(this)) {
+                this.showPopover();
+            } else if (!open && isOpen(this)) {
+                this.hidePopover();
+            }
+            return isOpen(this);
+        });
+
+        document.addEventListener("click", (event) => {
+            const invoker = event.target.closest && event.target.closest("[popovertarget]");
+            if (invoker && !invoker.disabled) {
+                const target = document.getElementById(invoker.getAttribute("popovertarget"));
+                if (target && target.hasAttribute("popover")) {
+                    const action = invoker.getAttribute("popovertargetaction") || "toggle";
+                    if (action === "show") {
+                        target.togglePopover(true);
+                    } else if (action === "hide") {
+                        target.togglePopover(false);
+                    } else {
+                        target.togglePopover();
+                    }
+                    return;
+                }
+            }
+            // Clicking outside of an auto popover dismisses it
+            document.querySelectorAll(".__dioxus-popover-open").forEach((open) => {
+                if (isAuto(open) && !open.contains(event.target)) {
+                    open.hidePopover();
+                }
+            });
+        });
+        document.addEventListener("keydown", (event) => {
+            if (event.key === "Escape") {
+                document.querySelectorAll(".__dioxus-popover-open").forEach((open) => {
+                    if (isAuto(open)) {
+                        open.hidePopover();
+                    }
+                });
+            }
+        });
+    }
+})();"#;
@@ -0,0 +1,153 @@
+//! XDG desktop portals.
+//!
+//! Apps in a Flatpak or Snap sandbox can't read the user's files, capture the screen or talk to the notification
+//! daemon directly. Instead they ask the portals, D-Bus services outside the sandbox that show dialogs on behalf of
+//! the app and hand back only what the user picked.
+//!
+//! Portals are used automatically when the app is sandboxed. This can be changed with [`Config::with_portals`].
+//!
+//! [`Config::with_portals`]: crate::Config::with_portals
+
+use std::path::{Path, PathBuf};
+
+use ashpd::desktop::file_chooser::{FileFilter, SelectedFiles};
+use ashpd::desktop::notification::{Notification, NotificationProxy};
+use ashpd::desktop::screenshot::Screenshot;
+use thiserror::Error;
+
+/// When the app talks to the XDG desktop portals instead of the system directly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PortalMode {
+    /// Use the portals when the app is running in a Flatpak or Snap sandbox
+    #[default]
+    Auto,
+    /// Always use the portals
+    Always,
+    /// Never use the portals
+    Never,
+}
+
+impl PortalMode {
+    /// Check if the portals should be used in this mode
+    pub(crate) fn enabled(self) -> bool {
+        match self {
+            PortalMode::Auto => is_sandboxed(),
+            PortalMode::Always => true,
+            PortalMode::Never => false,
+        }
+    }
+}
+
+/// Check if the app is running in a Flatpak or Snap sandbox.
+pub fn is_sandboxed() -> bool {
+    Path::new("/.flatpak-info").exists()
+        || std::env::var_os("SNAP").is_some()
+        || std::env::var_os("container").map_or(false, |container| container == "flatpak")
+}
+
+/// An error that can occur when talking to the XDG desktop portals.
+#[derive(Error, Debug)]
+pub enum PortalError {
+    /// The user closed the portal dialog
+    #[error("The portal request was cancelled")]
+    Cancelled,
+    /// The portal returned a location that is not a local file
+    #[error("The portal returned a location that is not a local file: {0}")]
+    NotAFile(String),
+    /// The portal is not available, or it failed to handle the request
+    #[error("Portal error: {0}")]
+    Portal(ashpd::Error),
+}
+
+impl From<ashpd::Error> for PortalError {
+    fn from(err: ashpd::Error) -> Self {
+        match err {
+            ashpd::Error::Response(ashpd::desktop::ResponseError::Cancelled) => {
+                PortalError::Cancelled
+            }
+            err => PortalError::Portal(err),
+        }
+    }
+}
+
+/// Let the user pick files or folders with the file chooser portal
+pub(crate) async fn pick_files(
+    directory: bool,
+    multiple: bool,
+    filters: Vec<FileFilter>,
+) -> Result<Vec<PathBuf>, PortalError> {
+    let files = SelectedFiles::open_file()
+        .modal(true)
+        .multiple(multiple)
+        .directory(directory)
+        .filters(filters)
+        .send()
+        .await?
+        .response()?;
+
+    Ok(files
+        .uris()
+        .iter()
+        .filter_map(|uri| uri.to_file_path().ok())
+        .collect())
+}
+
+/// Let the user pick where to save a file with the file chooser portal
+pub(crate) async fn save_file(name: Option<String>) -> Result<PathBuf, PortalError> {
+    let files = SelectedFiles::save_file()
+        .modal(true)
+        .current_name(name.as_deref())
+        .send()
+        .await?
+        .response()?;
+
+    let uri = files.uris().first().ok_or(PortalError::Cancelled)?;
+    uri.to_file_path()
+        .map_err(|_| PortalError::NotAFile(uri.to_string()))
+}
+
+/// Capture the screen with the screenshot portal and get the path of the image.
+///
+/// If `interactive` is true, the portal lets the user pick the part of the screen to capture. Otherwise the whole
+/// screen is captured, although the portal may still ask the user for permission.
+///
+/// ```rust, ignore
+/// let path = dioxus_desktop::take_screenshot(true).await?;
+/// ```
+pub async fn take_screenshot(interactive: bool) -> Result<PathBuf, PortalError> {
+    let screenshot = Screenshot::request()
+        .interactive(interactive)
+        .modal(true)
+        .send()
+        .await?
+        .response()?;
+
+    let uri = screenshot.uri();
+    uri.to_file_path()
+        .map_err(|_| PortalError::NotAFile(uri.to_string()))
+}
+
+/// Show a notification with the notification portal.
+///
+/// Sending a notification with the `id` of one that is still shown replaces it.
+pub async fn send_portal_notification(
+    id: &str,
+    title: &str,
+    body: Option<&str>,
+) -> Result<(), PortalError> {
+    let mut notification = Notification::new(title);
+    if let Some(body) = body {
+        notification = notification.body(body);
+    }
+
+    let proxy = NotificationProxy::new().await?;
+    proxy.add_notification(id, notification).await?;
+    Ok(())
+}
+
+/// Remove a notification shown with [`send_portal_notification`]
+pub async fn withdraw_portal_notification(id: &str) -> Result<(), PortalError> {
+    let proxy = NotificationProxy::new().await?;
+    proxy.remove_notification(id).await?;
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! Global shortcuts through the XDG desktop portal.
+//!
+//! Wayland compositors don't let apps grab keys for themselves, so on Wayland the shortcuts are bound through the
+//! `org.freedesktop.portal.GlobalShortcuts` portal instead. The portal may show a dialog that lets the user confirm
+//! or change the keys the first time the shortcuts are bound.
+
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use tokio::sync::watch;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PortalBinding {
+    id: u32,
+    trigger: String,
+}
+
+/// The shortcuts bound through the portal
+pub(crate) struct PortalShortcuts {
+    bindings: watch::Sender<Vec<PortalBinding>>,
+    activated: crossbeam_channel::Receiver<u32>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for PortalShortcuts {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl PortalShortcuts {
+    pub(crate) fn new() -> Self {
+        let (bindings, bindings_rx) = watch::channel(Vec::new());
+        let (activated_tx, activated) = crossbeam_channel::unbounded();
+        Self {
+            bindings,
+            activated,
+            task: tokio::spawn(run_portal(bindings_rx, activated_tx)),
+        }
+    }
+
+    /// Ask the portal to bind a shortcut. The portal is asked again for every shortcut each time one is added.
+    pub(crate) fn bind(&self, id: u32, hotkey: &HotKey) {
+        let trigger = trigger(hotkey);
+        self.bindings.send_modify(|bindings| {
+            if !bindings.iter().any(|binding| binding.id == id) {
+                bindings.push(PortalBinding { id, trigger });
+            }
+        });
+    }
+
+    /// Ask the portal to remove a shortcut
+    pub(crate) fn unbind(&self, id: u32) {
+        self.bindings
+            .send_modify(|bindings| bindings.retain(|binding| binding.id != id));
+    }
+
+    /// Ask the portal to remove every shortcut
+    pub(crate) fn unbind_all(&self) {
+        self.bindings.send_modify(|bindings| bindings.clear());
+    }
+
+    /// Get the id of the next shortcut the user activated
+    pub(crate) fn try_recv(&self) -> Option<u32> {
+        self.activated.try_recv().ok()
+    }
+}
+
+/// Bind the shortcuts in a new portal session every time they change, and report the activated shortcuts.
+async fn run_portal(
+    mut bindings: watch::Receiver<Vec<PortalBinding>>,
+    activated: crossbeam_channel::Sender<u32>,
+) {
+    use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut};
+    use futures_util::StreamExt;
+
+    let portal = match GlobalShortcuts::new().await {
+        Ok(portal) => portal,
+        Err(err) => {
+            tracing::error!("The global shortcuts portal is not available: {}", err);
+            return;
+        }
+    };
+    let mut activations = match portal.receive_activated().await {
+        Ok(activations) => Box::pin(activations),
+        Err(err) => {
+            tracing::error!("Failed to listen for global shortcuts: {}", err);
+            return;
+        }
+    };
+
+    loop {
+        let current = bindings.borrow_and_update().clone();
+
+        // Sessions bind a fixed set of shortcuts, so we start a new one every time the set changes
+        let mut session = None;
+        if !current.is_empty() {
+            match portal.create_session().await {
+                Ok(new_session) => {
+                    let shortcuts: Vec<_> = current
+                        .iter()
+                        .map(|binding| {
+                            NewShortcut::new(binding.id.to_string(), binding.trigger.clone())
+                                .preferred_trigger(binding.trigger.as_str())
+                        })
+                        .collect();
+                    let identifier = ashpd::WindowIdentifier::default();
+                    if let Err(err) = portal
+                        .bind_shortcuts(&new_session, &shortcuts, &identifier)
+                        .await
+                    {
+                        tracing::error!("Failed to bind global shortcuts: {}", err);
+                    }
+                    session = Some(new_session);
+                }
+                Err(err) => tracing::error!("Failed to create a global shortcuts session: {}", err),
+            }
+        }
+
+        loop {
+            tokio::select! {
+                changed = bindings.changed() => {
+                    if changed.is_err() {
+                        return;
+                    }
+                    break;
+                }
+                Some(activation) = activations.next() => {
+                    if let Ok(id) = activation.shortcut_id().parse() {
+                        let _ = activated.send(id);
+                    }
+                }
+            }
+        }
+
+        if let Some(session) = session {
+            let _ = session.close().await;
+        }
+    }
+}
+
+/// Format a hotkey in the trigger format of the XDG shortcuts specification, like `CTRL+SHIFT+a`
+fn trigger(hotkey: &HotKey) -> String {
+    let mut trigger = String::new();
+    for (modifiers, name) in [
+        (Modifiers::CONTROL, "CTRL"),
+        (Modifiers::ALT, "ALT"),
+        (Modifiers::SHIFT, "SHIFT"),
+        (Modifiers::SUPER | Modifiers::META, "LOGO"),
+    ] {
+        if hotkey.mods.intersects(modifiers) {
+            trigger.push_str(name);
+            trigger.push('+');
+        }
+    }
+
+    let key = format!("{:?}", hotkey.key);
+    match hotkey.key {
+        Code::Space => trigger.push_str("space"),
+        Code::Enter => trigger.push_str("Return"),
+        Code::Backspace => trigger.push_str("BackSpace"),
+        Code::ArrowUp => trigger.push_str("Up"),
+        Code::ArrowDown => trigger.push_str("Down"),
+        Code::ArrowLeft => trigger.push_str("Left"),
+        Code::ArrowRight => trigger.push_str("Right"),
+        _ => {
+            // Letters and digits use their keysym name, everything else already matches it
+            let key = key
+                .strip_prefix("Key")
+                .map(|letter| letter.to_lowercase())
+                .or_else(|| key.strip_prefix("Digit").map(str::to_string))
+                .unwrap_or_else(|| key.clone());
+            trigger.push_str(&key);
+        }
+    }
+    trigger
+}
@@ -1,5 +1,4 @@
 use dioxus_core::ScopeState;
-use dioxus_interpreter_js::{COMMON_JS, INTERPRETER_JS};
 use slab::Slab;
 use std::{
     borrow::Cow,
@@ -21,53 +20,33 @@ use wry::{
 };
 use crate::{use_window, DesktopContext};
 
+use crate::cache_policy::CachePolicy;
+use crate::compression::Compression;
 use crate::desktop_context::EditQueue;
+use crate::mount::AssetMount;
+use crate::request_filter::{FilterAction, RequestFilter};
+use crate::response_headers::ResponseHeaders;
 
 static MINIFIED: &str = include_str!("./minified.js");
 
+/// The script that starts the interpreter. The interpreter and the code that starts it are minified by the build
+/// script, so the page only has to call it. Module scripts run as soon as the document is parsed, so the first frame
+/// doesn't wait for the images and stylesheets of the page like `window.onload` would.
 fn module_loader(root_name: &str, headless: bool) -> String {
-    let js = INTERPRETER_JS.replace(
-        "/*POST_HANDLE_EDITS*/",
-        r#"// Prevent file inputs from opening the file dialog on click
-    let inputs = document.querySelectorAll("input");
-    for (let input of inputs) {
-      if (!input.getAttribute("data-dioxus-file-listener")) {
-        // prevent file inputs from opening the file dialog on click
-        const type = input.getAttribute("type");
-        if (type === "file") {
-          input.setAttribute("data-dioxus-file-listener", true);
-          input.addEventListener("click", (event) => {
-            let target = event.target;
-            let target_id = find_real_id(target);
-            if (target_id !== null) {
-              const send = (event_name) => {
-                const message = serializeIpcMessage("file_diolog", { accept: target.getAttribute("accept"), directory: target.getAttribute("webkitdirectory") === "true", multiple: target.hasAttribute("multiple"), target: parseInt(target_id), bubbles: event_bubbles(event_name), event: event_name });
-                window.ipc.postMessage(message);
-              };
-              send("change&input");
-            }
-            event.preventDefault();
-          });
-        }
-      }
-    }"#,
-    );
-  
     format!(
         r#"
-<script type="module">
+<script type="module">{}</script>
+"#,
+        loader_script(root_name, headless)
+    )
+}
+
+/// The contents of the module loader, which a Content-Security-Policy allows by its hash
+fn loader_script(root_name: &str, headless: bool) -> String {
+    format!(
+        r#"
     {MINIFIED}
-    // Wait for the page to load
-    window.onload = function() {{
-        let rootname = "{root_name}";
-        let root_element = window.document.getElementById(rootname);
-        if (root_element != null) {{
-            window.interpreter.initialize(root_element);
-            window.ipc.postMessage(window.interpreter.serializeIpcMessage("initialize"));
-        }}
-        window.interpreter.wait_for_request({headless});
-    }}
-</script>
+    window.interpreter.start("{root_name}", {headless});
 "#
     )
 }
@@ -193,6 +172,9 @@ impl Drop for AssetHandlerHandle {
 ///
 /// The callback takes a path as requested by the web view, and it should return `Some(response)`
 /// if you want to load the asset, and `None` if you want to fallback on the default behavior.
+///
+/// Large files, like videos, should be answered with [`serve_file`](crate::serve_file) or
+/// [`serve_reader`](crate::serve_reader), which only read the range of the file the webview asks for.
 pub fn use_asset_handler<F: AssetFuture>(
     cx: &ScopeState,
     handler: impl AssetHandler<F>,
@@ -213,17 +195,81 @@ pub fn use_asset_handler<F: AssetFuture>(
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn desktop_handler(
     request: Request<Vec<u8>>,
+    responder: RequestAsyncResponder,
     custom_head: Option<String>,
     custom_index: Option<String>,
     root_name: &str,
     asset_handlers: &AssetHandlerRegistry,
+    asset_mounts: &[AssetMount],
+    compression: &Compression,
+    cache_policy: &CachePolicy,
+    response_headers: &ResponseHeaders,
+    request_filter: &RequestFilter,
     edit_queue: &EditQueue,
     headless: bool,
-) -> Result<AssetResponse> {
-    let request = AssetRequest::from(request);
+    #[cfg(feature = "asset-cache")] asset_cache: Option<&crate::asset_cache::AssetCache>,
+) {
+    // The edits are answered by the edit queue when there are edits to send
+    if request.uri().path().trim_matches('/') == "edits" {
+        edit_queue.handle_request(responder);
+        return;
+    }
 
+    let path = request.uri().path().to_string();
+    let query = request.uri().query().map(str::to_string);
+
+    match serve(
+        request,
+        custom_head,
+        custom_index,
+        root_name,
+        asset_handlers,
+        asset_mounts,
+        compression,
+        response_headers,
+        request_filter,
+        headless,
+        #[cfg(feature = "asset-cache")]
+        asset_cache,
+    )
+    .await
+    {
+        Ok(mut response) => {
+            cache_policy.apply(query.as_deref(), response.headers_mut());
+            response_headers.apply(&path, response.headers_mut());
+            responder.respond(response)
+        }
+        Err(err) => {
+            tracing::error!("error serving asset: {}", err);
+            if let Ok(response) = Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Cow::from(
+                    String::from("Internal Server Error").into_bytes(),
+                ))
+            {
+                responder.respond(response);
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn serve(
+    request: Request<Vec<u8>>,
+    custom_head: Option<String>,
+    custom_index: Option<String>,
+    root_name: &str,
+    asset_handlers: &AssetHandlerRegistry,
+    asset_mounts: &[AssetMount],
+    compression: &Compression,
+    response_headers: &ResponseHeaders,
+    request_filter: &RequestFilter,
+    headless: bool,
+    #[cfg(feature = "asset-cache")] asset_cache: Option<&crate::asset_cache::AssetCache>,
+) -> Result<AssetResponse> {
     // If the request is for the root, we'll serve the index.html file.
     if request.uri().path() == "/" {
         // If a custom index is provided, just defer to that, expecting the user to know what they're doing.
@@ -253,74 +299,80 @@ pub(super) async fn desktop_handler(
             }
         };
 
-        match Response::builder()
+        let mut response = Response::builder()
             .header("Content-Type", "text/html")
             .header("Access-Control-Allow-Origin", "*")
-            .body(Cow::from(body))
-        {
-            Ok(response) => {
-                responder.respond(response);
-                return;
-            }
-            Err(err) => tracing::error!("error building response: {}", err),
+            .body(Cow::from(body))?;
+        response_headers.apply_index(response.headers_mut(), &loader_script(root_name, headless));
+        return Ok(response);
+    }
+
+    // Apply the request filter before serving anything else. The index and the edits are part of the app, so a
+    // filter that blocks or redirects the app's own urls doesn't break it.
+    match request_filter.action_for(&request.uri().to_string()) {
+        Some(FilterAction::Block) => {
+            return Ok(Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .body(Cow::from(String::from("Blocked").into_bytes()))?);
+        }
+        Some(FilterAction::Redirect(to)) => {
+            return Ok(Response::builder()
+                .status(StatusCode::TEMPORARY_REDIRECT)
+                .header("Location", to.as_str())
+                .body(Cow::from(Vec::new()))?);
+        }
+        _ => {}
+    }
+
+    // Remote assets are served from the cache, which revalidates them with the server first
+    #[cfg(feature = "asset-cache")]
+    if let Some(cache) = asset_cache {
+        let path = request.uri().path();
+        if path.starts_with(crate::asset_cache::REMOTE_PREFIX) {
+            return Ok(cache.fetch(path).await);
         }
-    } else if request.uri().path().trim_matches('/') == "edits" {
-        edit_queue.handle_request(responder);
-        return;
     }
 
+    let request = AssetRequest::from(request);
+
     // If the user provided a custom asset handler, then call it and return the response
     // if the request was handled.
     if let Some(response) = asset_handlers.try_handlers(&request).await {
         return Ok(response);
     }
 
+    // Then the directories mounted in the config
+    for mount in asset_mounts {
+        if let Some(response) = mount.serve(&request) {
+            return Ok(response?);
+        }
+    }
+
     // Else, try to serve a file from the filesystem.
 
+    let asset = resolve_asset_path(&request.path);
+    if asset.is_file() {
+        // Only the range the webview asks for is read, so large media files are never loaded whole. Whole files are
+        // compressed if the webview accepts it.
+        return Ok(compression.serve_file(&request, &asset)?);
+    }
+
+    Ok(Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .body(Cow::from(String::from("Not Found").into_bytes()))?)
+}
+
+/// The file on disk a request for `path` is served from
+pub(crate) fn resolve_asset_path(path: &Path) -> PathBuf {
     // If the path is relative, we'll try to serve it from the assets directory.
-    let mut asset = get_asset_root()
+    let asset = get_asset_root()
         .unwrap_or_else(|| Path::new(".").to_path_buf())
-        .join(&request.path);
-
-    if !asset.exists() {
-        asset = PathBuf::from("/").join(request.path);
-    }
+        .join(path);
 
     if asset.exists() {
-        let content_type = match get_mime_from_path(&asset) {
-            Ok(content_type) => content_type,
-            Err(err) => {
-                tracing::error!("error getting mime type: {}", err);
-                return;
-            }
-        };
-        let asset = match std::fs::read(asset) {
-            Ok(asset) => asset,
-            Err(err) => {
-                tracing::error!("error reading asset: {}", err);
-                return;
-            }
-        };
-        match Response::builder()
-            .header("Content-Type", content_type)
-            .body(Cow::from(asset))
-        {
-            Ok(response) => {
-                responder.respond(response);
-                return;
-            }
-            Err(err) => tracing::error!("error building response: {}", err),
-        }
-    }
-
-    match Response::builder()
-        .status(StatusCode::NOT_FOUND)
-        .body(Cow::from(String::from("Not Found").into_bytes()))
-    {
-        Ok(response) => {
-            responder.respond(response);
-        }
-        Err(err) => tracing::error!("error building response: {}", err),
+        asset
+    } else {
+        PathBuf::from("/").join(path)
     }
 }
 
@@ -358,7 +410,7 @@ fn get_asset_root() -> Option<PathBuf> {
 }
 
 /// Get the mime type from a path-like string
-fn get_mime_from_path(trimmed: &Path) -> Result<&'static str> {
+pub(crate) fn get_mime_from_path(trimmed: &Path) -> Result<&'static str> {
     if trimmed.extension().is_some_and(|ext| ext == "svg") {
         return Ok("image/svg+xml");
     }
@@ -378,7 +430,7 @@ fn get_mime_from_path(trimmed: &Path) -> Result<&'static str> {
 }
 
 /// Get the mime type from a URI using its extension
-fn get_mime_by_ext(trimmed: &Path) -> &'static str {
+pub(crate) fn get_mime_by_ext(trimmed: &Path) -> &'static str {
     match trimmed.extension().and_then(|e| e.to_str()) {
         Some("bin") => "application/octet-stream",
         Some("css") => "text/css",
@@ -392,6 +444,11 @@ fn get_mime_by_ext(trimmed: &Path) -> &'static str {
         Some("rtf") => "application/rtf",
         Some("svg") => "image/svg+xml",
         Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        Some("mp3") => "audio/mpeg",
+        Some("ogg") => "audio/ogg",
+        Some("wav") => "audio/wav",
+        Some("wasm") => "application/wasm",
         // Assume HTML when a TLD is found for eg. `dioxus:://dioxuslabs.app` | `dioxus://hello.com`
         Some(_) => "text/html",
         // https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types/Common_types
@@ -399,3 +456,52 @@ fn get_mime_by_ext(trimmed: &Path) -> &'static str {
         None => "application/octet-stream",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn serve_with_filter(url: &str, request_filter: &RequestFilter) -> StatusCode {
+        let request = Request::get(url).body(Vec::new()).unwrap();
+        let response = serve(
+            request,
+            None,
+            None,
+            "main",
+            &AssetHandlerRegistry::new(),
+            &[],
+            &Compression::default(),
+            &ResponseHeaders::default(),
+            request_filter,
+            false,
+            #[cfg(feature = "asset-cache")]
+            None,
+        )
+        .await
+        .unwrap();
+        response.status()
+    }
+
+    #[tokio::test]
+    async fn filters_do_not_apply_to_the_index() {
+        let block = RequestFilter::new().block("dioxus://*");
+        assert_eq!(
+            serve_with_filter("dioxus://index.html/", &block).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            serve_with_filter("dioxus://index.html/assets/app.js", &block).await,
+            StatusCode::FORBIDDEN
+        );
+
+        let redirect = RequestFilter::new().redirect("dioxus://*", "https://example.com/");
+        assert_eq!(
+            serve_with_filter("dioxus://index.html/", &redirect).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            serve_with_filter("dioxus://index.html/assets/app.js", &redirect).await,
+            StatusCode::TEMPORARY_REDIRECT
+        );
+    }
+}
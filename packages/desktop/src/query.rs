@@ -1,11 +1,13 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::Rc, time::Instant};
 
-use crate::DesktopContext;
 use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::Value;
 use slab::Slab;
 use thiserror::Error;
 use tokio::sync::broadcast::error::RecvError;
+use wry::webview::WebView;
+
+use crate::metrics::{MetricKind, Metrics};
 
 const DIOXUS_CODE: &str = r#"
 let dioxus = {
@@ -66,6 +68,7 @@ impl<T> Default for SharedSlab<T> {
 struct QueryEntry {
     channel_sender: tokio::sync::mpsc::UnboundedSender<Value>,
     return_sender: Option<tokio::sync::oneshot::Sender<Value>>,
+    started: Instant,
 }
 
 const QUEUE_NAME: &str = "__msg_queues";
@@ -74,25 +77,31 @@ const QUEUE_NAME: &str = "__msg_queues";
 #[derive(Clone, Default)]
 pub(crate) struct QueryEngine {
     active_requests: SharedSlab<QueryEntry>,
+    metrics: Metrics,
 }
 
 impl QueryEngine {
+    /// Creates a query engine that records how long queries take in `metrics`
+    pub fn new(metrics: Metrics) -> Self {
+        Self {
+            active_requests: Default::default(),
+            metrics,
+        }
+    }
+
     /// Creates a new query and returns a handle to it. The query will be resolved when the webview returns a result with the same id.
-    pub fn new_query<V: DeserializeOwned>(
-        &self,
-        script: &str,
-        context: DesktopContext,
-    ) -> Query<V> {
+    pub fn new_query<V: DeserializeOwned>(&self, script: &str, webview: Rc<WebView>) -> Query<V> {
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
         let (return_tx, return_rx) = tokio::sync::oneshot::channel();
         let request_id = self.active_requests.slab.borrow_mut().insert(QueryEntry {
             channel_sender: tx,
             return_sender: Some(return_tx),
+            started: Instant::now(),
         });
 
         // start the query
         // We embed the return of the eval in a function so we can send it back to the main thread
-        if let Err(err) = context.webview.evaluate_script(&format!(
+        if let Err(err) = webview.evaluate_script(&format!(
             r#"(function(){{
                 (async (resolve, _reject) => {{
                     {DIOXUS_CODE}
@@ -131,7 +140,7 @@ impl QueryEngine {
             id: request_id,
             receiver: rx,
             return_receiver: Some(return_rx),
-            desktop: context,
+            webview,
             phantom: std::marker::PhantomData,
         }
     }
@@ -147,6 +156,8 @@ impl QueryEngine {
         if let Some(entry) = slab.get_mut(id) {
             if returned_value {
                 if let Some(sender) = entry.return_sender.take() {
+                    self.metrics
+                        .record_duration(MetricKind::IpcRoundTrip, entry.started.elapsed());
                     let _ = sender.send(data);
                 }
             } else {
@@ -157,7 +168,7 @@ impl QueryEngine {
 }
 
 pub(crate) struct Query<V: DeserializeOwned> {
-    desktop: DesktopContext,
+    webview: Rc<WebView>,
     slab: SharedSlab<QueryEntry>,
     receiver: tokio::sync::mpsc::UnboundedReceiver<Value>,
     return_receiver: Option<tokio::sync::oneshot::Receiver<Value>>,
@@ -190,8 +201,7 @@ impl<V: DeserializeOwned> Query<V> {
             "#
         );
 
-        self.desktop
-            .webview
+        self.webview
             .evaluate_script(&script)
             .map_err(|e| QueryError::Send(e.to_string()))?;
 
@@ -222,7 +232,7 @@ impl<V: DeserializeOwned> Drop for Query<V> {
         self.slab.slab.borrow_mut().remove(self.id);
         let queue_id = self.id;
 
-        _ = self.desktop.webview.evaluate_script(&format!(
+        _ = self.webview.evaluate_script(&format!(
             r#"
             if (!window.{QUEUE_NAME}) {{
                 window.{QUEUE_NAME} = [];
@@ -236,14 +246,19 @@ impl<V: DeserializeOwned> Drop for Query<V> {
     }
 }
 
+/// An error that occurred while running JavaScript in the webview and waiting for its result.
 #[derive(Error, Debug)]
 pub enum QueryError {
+    /// The webview stopped responding before the result was received
     #[error("Error receiving query result: {0}")]
     Recv(RecvError),
+    /// The script could not be sent to the webview
     #[error("Error sending message to query: {0}")]
     Send(String),
+    /// The result of the script did not have the expected shape
     #[error("Error deserializing query result: {0}")]
     Deserialize(serde_json::Error),
+    /// The result of the query was already taken
     #[error("Query has already been resolved")]
     Finished,
 }
@@ -0,0 +1,223 @@
+//! Partial responses to `Range` requests.
+//!
+//! wry hands the webview a response with the whole body at once, so a large file can't be streamed in a single
+//! response. Media elements don't need it to be: they ask for the file in ranges, and ask for the next range as they
+//! play or seek. Serving only the range that was asked for, and at most [`MAX_CHUNK`] bytes of it, lets a `<video>`
+//! play a file of any size while only a few megabytes of it are in memory.
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use wry::http::{header, status::StatusCode, Response};
+
+use crate::protocol::{AssetRequest, AssetResponse};
+
+/// The most bytes sent in the response to a single range request. The webview asks for the rest in later requests.
+pub(crate) const MAX_CHUNK: u64 = 4 * 1024 * 1024;
+
+/// An inclusive range of bytes of a body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ByteRange {
+    pub(crate) start: u64,
+    pub(crate) end: u64,
+}
+
+/// The range a `Range` header asks for in a body of `len` bytes.
+///
+/// Returns `None` if the header can't be parsed, in which case the whole body is sent, and `Some(Err(()))` if the
+/// range is outside of the body. Only the first range of a multipart request is served.
+pub(crate) fn parse_range(header: &str, len: u64) -> Option<Result<ByteRange, ()>> {
+    let ranges = header.trim().strip_prefix("bytes=")?;
+    let range = ranges.split(',').next()?.trim();
+    let (start, end) = range.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+
+    let range = if start.is_empty() {
+        // `bytes=-500` is the last 500 bytes
+        let suffix: u64 = end.parse().ok()?;
+        if suffix == 0 || len == 0 {
+            return Some(Err(()));
+        }
+        ByteRange {
+            start: len.saturating_sub(suffix),
+            end: len - 1,
+        }
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = match end {
+            "" => len.saturating_sub(1),
+            end => end.parse::<u64>().ok()?.min(len.saturating_sub(1)),
+        };
+        if start >= len || end < start {
+            return Some(Err(()));
+        }
+        ByteRange { start, end }
+    };
+
+    Some(Ok(ByteRange {
+        start: range.start,
+        end: range.end.min(range.start + MAX_CHUNK - 1),
+    }))
+}
+
+/// Respond to `request` with the file at `path`, or the range of it the request asks for.
+///
+/// Use this in an [asset handler](crate::use_asset_handler) to serve large local files, like videos, without reading
+/// them into memory.
+pub fn serve_file(request: &AssetRequest, path: impl AsRef<Path>) -> io::Result<AssetResponse> {
+    let path = path.as_ref();
+    let content_type = crate::protocol::get_mime_from_path(path)
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+    serve_reader(request, File::open(path)?, content_type)
+}
+
+/// Respond to `request` with the body `reader` reads, or the range of it the request asks for. Only the bytes of the
+/// response are read.
+pub fn serve_reader(
+    request: &AssetRequest,
+    mut reader: impl Read + Seek,
+    content_type: &str,
+) -> io::Result<AssetResponse> {
+    let len = reader.seek(SeekFrom::End(0))?;
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|range| range.to_str().ok())
+        .and_then(|range| parse_range(range, len));
+
+    let response = Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::ACCEPT_RANGES, "bytes");
+    let response = match range {
+        None => {
+            let mut body = Vec::with_capacity(len as usize);
+            reader.seek(SeekFrom::Start(0))?;
+            reader.read_to_end(&mut body)?;
+            response
+                .header(header::CONTENT_LENGTH, len)
+                .body(Cow::from(body))
+        }
+        Some(Ok(ByteRange { start, end })) => {
+            let mut body = vec![0; (end - start + 1) as usize];
+            reader.seek(SeekFrom::Start(start))?;
+            reader.read_exact(&mut body)?;
+            response
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))
+                .header(header::CONTENT_LENGTH, body.len())
+                .body(Cow::from(body))
+        }
+        Some(Err(())) => response
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{len}"))
+            .body(Cow::from(Vec::new())),
+    };
+
+    response.map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use wry::http::Request;
+
+    fn range(start: u64, end: u64) -> Option<Result<ByteRange, ()>> {
+        Some(Ok(ByteRange { start, end }))
+    }
+
+    #[test]
+    fn suffix_ranges() {
+        assert_eq!(parse_range("bytes=-500", 1000), range(500, 999));
+        // A suffix longer than the body is the whole body
+        assert_eq!(parse_range("bytes=-5000", 1000), range(0, 999));
+        assert_eq!(parse_range("bytes=-0", 1000), Some(Err(())));
+        assert_eq!(parse_range("bytes=-10", 0), Some(Err(())));
+    }
+
+    #[test]
+    fn open_ended_ranges() {
+        assert_eq!(parse_range("bytes=100-", 1000), range(100, 999));
+        assert_eq!(parse_range("bytes=0-", 1000), range(0, 999));
+        assert_eq!(
+            parse_range("bytes=0-", 3 * MAX_CHUNK),
+            range(0, MAX_CHUNK - 1)
+        );
+    }
+
+    #[test]
+    fn multiple_ranges_serve_the_first() {
+        assert_eq!(parse_range("bytes=0-99, 200-299", 1000), range(0, 99));
+        assert_eq!(parse_range(" bytes=-100,0-10", 1000), range(900, 999));
+    }
+
+    #[test]
+    fn out_of_bounds_ranges() {
+        assert_eq!(parse_range("bytes=1000-", 1000), Some(Err(())));
+        assert_eq!(parse_range("bytes=2000-3000", 1000), Some(Err(())));
+        assert_eq!(parse_range("bytes=500-100", 1000), Some(Err(())));
+        assert_eq!(parse_range("bytes=0-", 0), Some(Err(())));
+        // The end is clamped to the body
+        assert_eq!(parse_range("bytes=900-5000", 1000), range(900, 999));
+        assert_eq!(
+            parse_range(&format!("bytes=0-{}", u64::MAX), 10),
+            range(0, 9)
+        );
+    }
+
+    #[test]
+    fn malformed_ranges_are_ignored() {
+        for header in [
+            "",
+            "bytes",
+            "bytes=",
+            "bytes=-",
+            "bytes=abc-def",
+            "bytes=1-2-3",
+            "bytes=--5",
+            "items=0-10",
+            "bytes=0x10-",
+            "bytes=99999999999999999999-",
+        ] {
+            assert_eq!(parse_range(header, 1000), None, "{header:?}");
+        }
+    }
+
+    fn request(range: Option<&str>) -> AssetRequest {
+        let mut request = Request::builder().uri("dioxus://index.html/video.mp4");
+        if let Some(range) = range {
+            request = request.header(header::RANGE, range);
+        }
+        request.body(Vec::new()).unwrap().into()
+    }
+
+    #[test]
+    fn serves_the_requested_range() {
+        let body: Vec<u8> = (0..100).collect();
+
+        let response = serve_reader(
+            &request(Some("bytes=10-19")),
+            Cursor::new(&body),
+            "video/mp4",
+        )
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 10-19/100");
+        assert_eq!(&response.body()[..], &body[10..20]);
+
+        let response = serve_reader(&request(None), Cursor::new(&body), "video/mp4").unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(&response.body()[..], &body[..]);
+
+        let response = serve_reader(
+            &request(Some("bytes=200-")),
+            Cursor::new(&body),
+            "video/mp4",
+        )
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes */100");
+    }
+}
@@ -0,0 +1,272 @@
+//! Serve the inspector of a window on a localhost WebSocket so devtools running in another process can attach.
+//!
+//! Every message the server sends is a JSON encoded [`InspectorSnapshot`](crate::InspectorSnapshot). A client gets
+//! a snapshot when it connects and every time the window renders after that. Messages from the client are ignored.
+//!
+//! The server only listens on the loopback interface, and the component tree is only collected while a client is
+//! connected.
+//!
+//! Every launch picks a random token, and clients must connect to `ws://127.0.0.1:{port}/?token={token}`. The url is
+//! logged when the server starts and returned by [`DesktopService::remote_inspector_url`](crate::DesktopService::remote_inspector_url).
+//! Handshakes with an `Origin` header are rejected, so web pages open in a browser on the same machine can't connect.
+
+use std::cell::Cell;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::{header::ORIGIN, StatusCode};
+use tokio_tungstenite::tungstenite::Message;
+use wry::application::window::WindowId;
+
+use crate::desktop_context::{EventData, ProxyType, UserWindowEvent};
+use crate::inspector::{Inspector, InspectorSubscriptionId};
+
+pub(crate) struct RemoteInspector {
+    url: String,
+    snapshots: Arc<watch::Sender<Option<String>>>,
+    subscription: Cell<Option<InspectorSubscriptionId>>,
+    server: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for RemoteInspector {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+impl RemoteInspector {
+    /// Start serving the inspector of a window on `127.0.0.1:{port}`
+    pub(crate) fn start(port: u16, proxy: ProxyType, window_id: WindowId) -> Self {
+        let snapshots = Arc::new(watch::channel(None).0);
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let token = new_token();
+        let url = format!("ws://{}/?token={}", address, token);
+        let server = tokio::spawn(serve(
+            address,
+            url.clone(),
+            token,
+            snapshots.clone(),
+            proxy,
+            window_id,
+        ));
+
+        Self {
+            url,
+            snapshots,
+            subscription: Cell::new(None),
+            server,
+        }
+    }
+
+    /// The url clients connect to, including the token
+    pub(crate) fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Only watch the inspector while clients are connected
+    pub(crate) fn set_clients(&self, clients: usize, inspector: &Inspector) {
+        match (clients, self.subscription.get()) {
+            (0, Some(id)) => {
+                inspector.unsubscribe(id);
+                self.subscription.set(None);
+            }
+            (clients, None) if clients > 0 => {
+                // The inspector owns the subscription, so only hold a weak reference to it
+                let weak = inspector.downgrade();
+                let snapshots = self.snapshots.clone();
+                let id = inspector.subscribe(move || {
+                    if let Some(inspector) = weak.upgrade() {
+                        match serde_json::to_string(&inspector.snapshot()) {
+                            Ok(json) => {
+                                snapshots.send_replace(Some(json));
+                            }
+                            Err(err) => tracing::error!("Failed to serialize snapshot: {}", err),
+                        }
+                    }
+                });
+                self.subscription.set(Some(id));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A random token for this launch, hex encoded
+fn new_token() -> String {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).expect("the system random number generator is unavailable");
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Accept a handshake only if it comes from outside of a browser and carries the token of this launch
+fn check_handshake(request: &Request, token: &str) -> Result<(), StatusCode> {
+    // Browsers always send an Origin header with WebSocket handshakes, other clients don't need to
+    if request.headers().contains_key(ORIGIN) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let authorized = request
+        .uri()
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .any(|(key, value)| key == "token" && constant_time_eq(value.as_bytes(), token.as_bytes()));
+    if authorized {
+        Ok(())
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+/// Compare two byte strings without returning early on the first difference
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+async fn serve(
+    address: SocketAddr,
+    url: String,
+    token: String,
+    snapshots: Arc<watch::Sender<Option<String>>>,
+    proxy: ProxyType,
+    window_id: WindowId,
+) {
+    let listener = match TcpListener::bind(address).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            tracing::error!(
+                "Failed to start the remote inspector on {}: {}",
+                address,
+                err
+            );
+            return;
+        }
+    };
+    tracing::info!("Remote inspector listening on {}", url);
+    let token = Arc::<str>::from(token);
+
+    let clients = Arc::new(AtomicUsize::new(0));
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                tracing::error!("Failed to accept a remote inspector connection: {}", err);
+                continue;
+            }
+        };
+
+        let mut updates = snapshots.subscribe();
+        let clients = clients.clone();
+        let proxy = proxy.clone();
+        let token = token.clone();
+        tokio::spawn(async move {
+            let callback = |request: &Request, response: Response| {
+                check_handshake(request, &token)
+                    .map(|()| response)
+                    .map_err(|status| {
+                        let mut response = ErrorResponse::new(None);
+                        *response.status_mut() = status;
+                        response
+                    })
+            };
+            let socket = match tokio_tungstenite::accept_hdr_async(stream, callback).await {
+                Ok(socket) => socket,
+                Err(err) => {
+                    tracing::warn!("Remote inspector handshake failed: {}", err);
+                    return;
+                }
+            };
+            let (mut sink, mut messages) = socket.split();
+
+            let connected = clients.fetch_add(1, Ordering::SeqCst) + 1;
+            _ = proxy.send_event(UserWindowEvent(
+                EventData::RemoteInspector(connected),
+                window_id,
+            ));
+
+            // Send the latest snapshot right away if another client already has one
+            let latest = updates.borrow_and_update().clone();
+            let mut open = match latest {
+                Some(json) => sink.send(Message::Text(json)).await.is_ok(),
+                None => true,
+            };
+
+            while open {
+                tokio::select! {
+                    changed = updates.changed() => {
+                        let latest = updates.borrow_and_update().clone();
+                        open = match (changed, latest) {
+                            (Ok(()), Some(json)) => sink.send(Message::Text(json)).await.is_ok(),
+                            (Ok(()), None) => true,
+                            (Err(_), _) => false,
+                        };
+                    }
+                    message = messages.next() => {
+                        open = matches!(message, Some(Ok(message)) if !message.is_close());
+                    }
+                }
+            }
+
+            let connected = clients.fetch_sub(1, Ordering::SeqCst) - 1;
+            _ = proxy.send_event(UserWindowEvent(
+                EventData::RemoteInspector(connected),
+                window_id,
+            ));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handshake(uri: &str, origin: Option<&str>) -> Result<(), StatusCode> {
+        let mut request = Request::builder().uri(uri);
+        if let Some(origin) = origin {
+            request = request.header(ORIGIN, origin);
+        }
+        check_handshake(&request.body(()).unwrap(), "0123abcd")
+    }
+
+    #[test]
+    fn handshakes_need_the_token() {
+        assert_eq!(handshake("/?token=0123abcd", None), Ok(()));
+        assert_eq!(handshake("/?client=cli&token=0123abcd", None), Ok(()));
+
+        assert_eq!(handshake("/", None), Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(handshake("/?token=", None), Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(
+            handshake("/?token=0123abce", None),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            handshake("/?token=0123abcd0", None),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+    }
+
+    #[test]
+    fn browser_handshakes_are_rejected() {
+        assert_eq!(
+            handshake("/?token=0123abcd", Some("https://evil.com")),
+            Err(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(
+            handshake("/?token=0123abcd", Some("null")),
+            Err(StatusCode::FORBIDDEN)
+        );
+    }
+
+    #[test]
+    fn tokens_are_random() {
+        let token = new_token();
+        assert_eq!(token.len(), 32);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, new_token());
+    }
+}
@@ -0,0 +1,301 @@
+//! Rendering the VirtualDom of a window on a thread of its own.
+//!
+//! By default the VirtualDom of a window lives on the thread of the event loop, so a component that takes long to
+//! render blocks the window: it can't be moved or resized, and its menus don't open. With
+//! [`launch_with_render_thread`](crate::launch_with_render_thread) the VirtualDom is created on a thread of its own
+//! instead. The event loop forwards the events of the page to it, and the thread turns the mutations into edits and
+//! adds them to the edit queue of the window, which answers the requests of the page.
+//!
+//! The [`DesktopContext`](crate::DesktopContext) of a window holds its webview, which can only be used on the thread
+//! of the event loop. Components on the render thread can't get it, so [`window`](crate::window) and the hooks built
+//! on it, like `use_eval`, aren't available to them. Mounted events aren't sent to them either, because their element
+//! queries the webview. The window can't be [paused](crate::DesktopService::pause) or
+//! [throttled](crate::DesktopService::request_redraw_throttle), and the [`Inspector`](crate::Inspector) doesn't see
+//! its components.
+
+use std::any::Any;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use dioxus_core::{ElementId, Mutations, Template, VirtualDom};
+use dioxus_html::{native_bind::NativeFileEngine, FormData, HtmlEvent};
+use dioxus_interpreter_js::binary_protocol::Channel;
+use futures_channel::mpsc::{UnboundedReceiver, UnboundedSender};
+use futures_util::future::{select, Either};
+use futures_util::{pin_mut, StreamExt};
+use rustc_hash::FxHashMap;
+use wry::application::window::WindowId;
+
+use crate::desktop_context::{EditQueue, EventData, ProxyType, UserWindowEvent};
+use crate::metrics::MetricKind;
+
+/// Creates the VirtualDom of a window on its render thread
+pub(crate) type MakeDom = Box<dyn FnOnce() -> VirtualDom + Send>;
+
+/// The VirtualDom a new window renders
+pub(crate) enum WindowRoot {
+    /// A VirtualDom on the thread of the event loop
+    Local(VirtualDom),
+    /// A VirtualDom that is created on a render thread
+    Thread(MakeDom),
+}
+
+/// The VirtualDom of a window
+pub(crate) enum WindowDom {
+    Local(VirtualDom),
+    Thread(RenderThread),
+}
+
+/// A message from the event loop to a render thread
+pub(crate) enum RenderMessage {
+    /// An event from the page, with the params of its IPC message. The files are set for the files dropped on the
+    /// element.
+    Event {
+        params: serde_json::Value,
+        files: Option<Vec<PathBuf>>,
+    },
+    /// Files picked with the file dialog of a file input
+    Files {
+        name: String,
+        element: ElementId,
+        bubbles: bool,
+        files: Vec<PathBuf>,
+    },
+    /// The page was loaded again, and needs every template and the whole VirtualDom
+    Reload,
+    /// A template changed while hot reloading
+    #[cfg(all(feature = "hot-reload", debug_assertions))]
+    Template(Template<'static>),
+}
+
+/// The handle of the event loop to the thread that renders a window. The thread stops once this is dropped.
+pub(crate) struct RenderThread {
+    sender: UnboundedSender<RenderMessage>,
+}
+
+impl RenderThread {
+    /// Create the VirtualDom with `make_dom` on a new thread, and render it into `edit_queue`
+    pub(crate) fn spawn(
+        make_dom: MakeDom,
+        edit_queue: EditQueue,
+        proxy: ProxyType,
+        window_id: WindowId,
+    ) -> Self {
+        let (sender, receiver) = futures_channel::mpsc::unbounded();
+        // The futures of the components run on the thread, and their IO on the runtime of the app
+        let runtime = tokio::runtime::Handle::current();
+        let spawned = std::thread::Builder::new()
+            .name("dioxus-render".to_string())
+            .spawn(move || {
+                let dom = make_dom();
+                let renderer = Renderer {
+                    edit_queue,
+                    proxy,
+                    window_id,
+                    channel: Channel::default(),
+                    templates: FxHashMap::default(),
+                    max_template_count: AtomicU16::new(0),
+                    sent_templates: Vec::new(),
+                };
+                runtime.block_on(run(dom, receiver, renderer));
+            });
+        if let Err(err) = spawned {
+            tracing::error!("Failed to spawn the render thread: {}", err);
+        }
+        Self { sender }
+    }
+
+    pub(crate) fn send(&self, message: RenderMessage) {
+        _ = self.sender.unbounded_send(message);
+    }
+}
+
+async fn run(
+    mut dom: VirtualDom,
+    mut receiver: UnboundedReceiver<RenderMessage>,
+    mut renderer: Renderer,
+) {
+    // The first frame waits in the edit queue until the page asks for it
+    renderer.rebuild(&mut dom);
+
+    loop {
+        // The page is behind. The components that change meanwhile are rendered together once it catches up.
+        futures_util::future::poll_fn(|cx| {
+            if renderer.edit_queue.is_backed_up(cx.waker()) {
+                std::task::Poll::Pending
+            } else {
+                std::task::Poll::Ready(())
+            }
+        })
+        .await;
+
+        let message = {
+            let work = dom.wait_for_work();
+            pin_mut!(work);
+            match select(work, receiver.next()).await {
+                Either::Left(_) => None,
+                Either::Right((Some(message), _)) => Some(message),
+                // The window was closed
+                Either::Right((None, _)) => return,
+            }
+        };
+
+        let handled_event = matches!(
+            message,
+            Some(RenderMessage::Event { .. }) | Some(RenderMessage::Files { .. })
+        );
+        match message {
+            Some(RenderMessage::Event { params, files }) => handle_event(&mut dom, params, files),
+            Some(RenderMessage::Files {
+                name,
+                element,
+                bubbles,
+                files,
+            }) => {
+                let data = Rc::new(FormData {
+                    value: Default::default(),
+                    values: Default::default(),
+                    files: Some(Arc::new(NativeFileEngine::new(files))),
+                });
+                if name == "change&input" {
+                    dom.handle_event("input", data.clone(), element, bubbles);
+                    dom.handle_event("change", data, element, bubbles);
+                } else {
+                    dom.handle_event(&name, data, element, bubbles);
+                }
+            }
+            Some(RenderMessage::Reload) => renderer.reload(&mut dom),
+            #[cfg(all(feature = "hot-reload", debug_assertions))]
+            Some(RenderMessage::Template(template)) => dom.replace_template(template),
+            None => {}
+        }
+
+        // With render batching the updates of the event wait for the other events of the tick
+        if handled_event && !dom.should_render_after_event() {
+            continue;
+        }
+        renderer.render(&mut dom);
+    }
+}
+
+fn handle_event(dom: &mut VirtualDom, params: serde_json::Value, files: Option<Vec<PathBuf>>) {
+    let HtmlEvent {
+        element,
+        name,
+        bubbles,
+        data,
+    } = match serde_json::from_value::<HtmlEvent>(params) {
+        Ok(value) => value,
+        Err(err) => {
+            tracing::error!("Error parsing user_event: {:?}", err);
+            return;
+        }
+    };
+
+    let data: Rc<dyn Any> = match data {
+        // The element of a mounted event queries the webview, which only the event loop can use
+        dioxus_html::EventData::Mounted => return,
+        dioxus_html::EventData::Drag(mut drag) => {
+            if let Some(files) = files {
+                drag.files = Some(Arc::new(NativeFileEngine::new(files)));
+            }
+            Rc::new(drag)
+        }
+        data => data.into_any(),
+    };
+
+    dom.handle_event(&name, data, element, bubbles);
+}
+
+/// Turns the mutations of the VirtualDom on the render thread into edits for the page
+struct Renderer {
+    edit_queue: EditQueue,
+    proxy: ProxyType,
+    window_id: WindowId,
+    channel: Channel,
+    templates: FxHashMap<String, u16>,
+    max_template_count: AtomicU16,
+    /// Every template sent to the page, in order, to send them again to a page that was loaded again
+    sent_templates: Vec<Template<'static>>,
+}
+
+impl Renderer {
+    fn rebuild(&mut self, dom: &mut VirtualDom) {
+        let start = Instant::now();
+        let edits = dom.rebuild();
+        self.edit_queue
+            .metrics
+            .record_duration(MetricKind::Render, start.elapsed());
+        self.send(edits);
+        dom.flush_layout_effects();
+    }
+
+    fn remount(&mut self, dom: &mut VirtualDom) {
+        let start = Instant::now();
+        let edits = dom.remount();
+        self.edit_queue
+            .metrics
+            .record_duration(MetricKind::Render, start.elapsed());
+        self.send(edits);
+        dom.flush_layout_effects();
+    }
+
+    fn render(&mut self, dom: &mut VirtualDom) {
+        let start = Instant::now();
+        let edits = dom.render_immediate();
+        self.edit_queue
+            .metrics
+            .record_duration(MetricKind::Render, start.elapsed());
+        self.send(edits);
+        dom.flush_layout_effects();
+    }
+
+    /// Send every template again, and the whole VirtualDom, to a page that was loaded again
+    fn reload(&mut self, dom: &mut VirtualDom) {
+        // The edits for the page that stopped would break the new one
+        self.edit_queue.clear_pending();
+        self.templates.clear();
+        self.max_template_count.store(0, Ordering::Relaxed);
+        if !self.sent_templates.is_empty() {
+            for template in self.sent_templates.iter() {
+                crate::add_template(
+                    template,
+                    &mut self.channel,
+                    &mut self.templates,
+                    &self.max_template_count,
+                );
+            }
+            let bytes: Vec<_> = self.channel.export_memory().collect();
+            self.channel.reset();
+            self.push(bytes);
+        }
+        self.remount(dom);
+    }
+
+    fn send(&mut self, edits: Mutations) {
+        self.sent_templates.extend(edits.templates.iter().copied());
+        if let Some(bytes) = crate::apply_edits(
+            edits,
+            &mut self.channel,
+            &mut self.templates,
+            &self.max_template_count,
+        ) {
+            self.edit_queue
+                .metrics
+                .record(MetricKind::EditBytes, bytes.len() as f64);
+            self.push(bytes);
+        }
+    }
+
+    fn push(&self, bytes: Vec<u8>) {
+        // The page waits for edits over IPC, which only the event loop can answer
+        if let Some(bytes) = self.edit_queue.add_edits(bytes) {
+            _ = self
+                .proxy
+                .send_event(UserWindowEvent(EventData::Edits(bytes), self.window_id));
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Hardware acceleration of the webview.
+//!
+//! Some GPUs and drivers make the webview draw a blank or corrupted window. Rendering in software avoids them at the
+//! cost of speed. It is turned on with [`Config::with_hardware_acceleration`](crate::Config::with_hardware_acceleration),
+//! or without rebuilding the app by starting it with the `DIOXUS_SOFTWARE_RENDERING` environment variable set.
+//!
+//! [`DesktopService::rendering_info`](crate::DesktopService::rendering_info) reports the renderer the webview ended up
+//! with, which is useful in bug reports.
+
+use std::rc::Rc;
+
+use serde::Deserialize;
+use wry::webview::WebView;
+
+use crate::query::{Query, QueryEngine};
+
+/// The environment variable that turns off hardware acceleration without rebuilding the app
+pub(crate) const SOFTWARE_RENDERING_VAR: &str = "DIOXUS_SOFTWARE_RENDERING";
+
+/// How the webview renders the page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderingInfo {
+    /// If the page is drawn by the GPU
+    pub hardware_accelerated: bool,
+    /// If hardware acceleration was turned off with
+    /// [`Config::with_hardware_acceleration`](crate::Config::with_hardware_acceleration) or the environment
+    pub software_rendering_forced: bool,
+    /// The name of the WebGL renderer, like `ANGLE (NVIDIA GeForce RTX 3070 Direct3D11)`, or `None` if WebGL is not
+    /// available
+    pub renderer: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RendererQuery {
+    renderer: Option<String>,
+}
+
+/// The renderers browsers fall back to when they can't use the GPU
+const SOFTWARE_RENDERERS: &[&str] = &[
+    "swiftshader",
+    "llvmpipe",
+    "softpipe",
+    "software",
+    "microsoft basic render driver",
+];
+
+const RENDERER_SCRIPT: &str = r#"
+const canvas = document.createElement("canvas");
+const gl = canvas.getContext("webgl") || canvas.getContext("experimental-webgl");
+if (!gl) return { renderer: null };
+const info = gl.getExtension("WEBGL_debug_renderer_info");
+return { renderer: gl.getParameter(info ? info.UNMASKED_RENDERER_WEBGL : gl.RENDERER) };
+"#;
+
+/// Ask the webview which renderer it uses
+pub(crate) async fn rendering_info(
+    engine: &QueryEngine,
+    webview: Rc<WebView>,
+    hardware_acceleration: bool,
+) -> Result<RenderingInfo, crate::QueryError> {
+    let query: Query<RendererQuery> = engine.new_query(RENDERER_SCRIPT, webview);
+    let RendererQuery { renderer } = query.resolve().await?;
+    let software = renderer.as_ref().map_or(true, |renderer| {
+        let renderer = renderer.to_lowercase();
+        SOFTWARE_RENDERERS
+            .iter()
+            .any(|software| renderer.contains(software))
+    });
+
+    Ok(RenderingInfo {
+        hardware_accelerated: hardware_acceleration && !software,
+        software_rendering_forced: !hardware_acceleration,
+        renderer,
+    })
+}
+
+/// Turn off hardware acceleration for every webview of the process. This must run before the first webview is
+/// created.
+#[cfg(target_os = "linux")]
+pub(crate) fn disable_hardware_acceleration() {
+    // WebKitGTK reads these when it starts its web process
+    std::env::set_var("WEBKIT_DISABLE_COMPOSITING_MODE", "1");
+    std::env::set_var("WEBKIT_DISABLE_DMABUF_RENDERER", "1");
+    std::env::set_var("LIBGL_ALWAYS_SOFTWARE", "1");
+}
+
+/// The browser arguments of WebView2 without hardware acceleration. These replace the default arguments of wry, so
+/// they are repeated here.
+#[cfg(target_os = "windows")]
+pub(crate) const SOFTWARE_BROWSER_ARGS: &str = "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection --disable-gpu --disable-gpu-compositing";
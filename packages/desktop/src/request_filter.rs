@@ -0,0 +1,299 @@
+//! URL filter rules that are applied to the requests a webview makes.
+//!
+//! Rules are matched against the full, absolute URL of a request with `*` matching any run of characters, so
+//! `https://*.doubleclick.net/*` blocks every request to that domain. Rules are checked in the order they were
+//! added and the first matching rule wins, which lets an early `allow` rule carve an exception out of a later,
+//! broader `block` rule. Requests that match no rule are allowed.
+//!
+//! The target of a redirect goes through the rules again, so a redirect to a blocked URL is blocked. Redirects that
+//! lead back to a URL they already passed, or that take more than 8 hops, are a loop and block the request.
+//!
+//! Requests for the app's own assets are filtered in the asset protocol before they are served. Top level
+//! navigations are filtered by the webview's navigation handler. Subresources the page loads from other origins
+//! (`fetch`, `XMLHttpRequest`, and the `src`/`href` of images, scripts, frames, media, and stylesheets) are
+//! filtered by a script that is installed before any page script runs.
+
+use serde::Serialize;
+
+/// How many redirects a request follows before it is treated as a redirect loop
+const MAX_REDIRECTS: usize = 8;
+
+/// What happens to a request that matches a [`FilterRule`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "to", rename_all = "snake_case")]
+pub enum FilterAction {
+    /// Let the request through, even if a later rule would block it
+    Allow,
+    /// Drop the request
+    Block,
+    /// Load a different URL instead
+    Redirect(String),
+}
+
+/// A single URL pattern and the action to take when a request matches it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FilterRule {
+    pattern: String,
+    action: FilterAction,
+}
+
+impl FilterRule {
+    /// Create a rule that applies `action` to every URL that matches `pattern`.
+    pub fn new(pattern: impl Into<String>, action: FilterAction) -> Self {
+        Self {
+            pattern: pattern.into(),
+            action,
+        }
+    }
+
+    /// The pattern this rule matches
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// The action this rule applies
+    pub fn action(&self) -> &FilterAction {
+        &self.action
+    }
+
+    /// Check if `url` matches the pattern of this rule.
+    pub fn matches(&self, url: &str) -> bool {
+        wildcard_match(&self.pattern, url)
+    }
+}
+
+/// An ordered list of [`FilterRule`]s that is applied to the requests a webview makes.
+///
+/// ```rust, ignore
+/// let filter = RequestFilter::new()
+///     .allow("https://cdn.example.com/*")
+///     .block("https://*.example.com/*")
+///     .redirect("https://fonts.googleapis.com/*", "dioxus://index.html/fonts.css");
+///
+/// dioxus_desktop::launch_cfg(app, Config::new().with_request_filter(filter));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestFilter {
+    rules: Vec<FilterRule>,
+}
+
+impl RequestFilter {
+    /// Create an empty filter that allows every request.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule to the end of the filter.
+    pub fn with_rule(mut self, rule: FilterRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Allow requests that match `pattern`.
+    pub fn allow(self, pattern: impl Into<String>) -> Self {
+        self.with_rule(FilterRule::new(pattern, FilterAction::Allow))
+    }
+
+    /// Block requests that match `pattern`.
+    pub fn block(self, pattern: impl Into<String>) -> Self {
+        self.with_rule(FilterRule::new(pattern, FilterAction::Block))
+    }
+
+    /// Load `to` instead of any request that matches `pattern`.
+    pub fn redirect(self, pattern: impl Into<String>, to: impl Into<String>) -> Self {
+        self.with_rule(FilterRule::new(pattern, FilterAction::Redirect(to.into())))
+    }
+
+    /// The rules in this filter, in the order they are checked
+    pub fn rules(&self) -> &[FilterRule] {
+        &self.rules
+    }
+
+    /// Check if the filter has no rules
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Find the action for a request to `url`, or `None` if no rule matches it.
+    ///
+    /// Redirects are followed through the rules, so a redirect is only returned with a target the filter lets through.
+    /// A request that is redirected in a loop is blocked.
+    pub fn action_for(&self, url: &str) -> Option<FilterAction> {
+        let mut visited = vec![url];
+        let mut action = self.rule_for(url)?;
+        while let FilterAction::Redirect(to) = action {
+            if visited.contains(&to.as_str()) || visited.len() > MAX_REDIRECTS {
+                tracing::warn!(
+                    "The request filter redirects {} in a loop. The request is blocked.",
+                    url
+                );
+                return Some(FilterAction::Block);
+            }
+            visited.push(to);
+            match self.rule_for(to) {
+                Some(FilterAction::Allow) | None => break,
+                Some(next) => action = next,
+            }
+        }
+        Some(action.clone())
+    }
+
+    /// The action of the first rule that matches `url`
+    fn rule_for(&self, url: &str) -> Option<&FilterAction> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(url))
+            .map(|rule| &rule.action)
+    }
+
+    /// The script that applies this filter to the subresources the page loads itself.
+    pub(crate) fn initialization_script(&self) -> String {
+        let rules = serde_json::to_string(&self.rules).expect("Failed to serialize FilterRule");
+        FILTER_SCRIPT
+            .replace("/*RULES*/", &rules)
+            .replace("/*MAX_REDIRECTS*/", &MAX_REDIRECTS.to_string())
+    }
+}
+
+/// Match `text` against a pattern where `*` matches any (possibly empty) run of characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.as_bytes();
+    let text = text.as_bytes();
+
+    let (mut p, mut t) = (0, 0);
+    // The position of the last `*` in the pattern and the text position it was tried at
+    let mut backtrack = None;
+
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` swallow one more character and try again
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+const FILTER_SCRIPT: &str = r#"
+(function () {
+    const rules = /*RULES*/.map((rule) => ({
+        action: rule.action,
+        regex: new RegExp("^" + rule.pattern.split("*").map((part) => part.replace(/[.+?^${}()|[\]\\]/g, "\\$&")).join(".*") + "$"),
+    }));
+
+    const resolve = (url) => {
+        try {
+            return new URL(url, document.baseURI).href;
+        } catch (_) {
+            return null;
+        }
+    };
+
+    // Returns the url to load instead of `url`, or null if the request should be dropped. Redirects are followed
+    // through the rules like in `RequestFilter::action_for`.
+    const filter = (url) => {
+        let target = url;
+        const visited = [];
+        while (true) {
+            const absolute = resolve(String(target));
+            if (absolute === null) {
+                return target;
+            }
+            const rule = rules.find((rule) => rule.regex.test(absolute));
+            if (!rule || rule.action.type === "allow") {
+                return target;
+            }
+            if (rule.action.type === "block") {
+                return null;
+            }
+            visited.push(absolute);
+            target = rule.action.to;
+            if (visited.includes(resolve(target)) || visited.length > /*MAX_REDIRECTS*/) {
+                console.warn("The request filter redirects " + url + " in a loop. The request is blocked.");
+                return null;
+            }
+        }
+    };
+
+    const originalFetch = window.fetch;
+    window.fetch = function (input, init) {
+        const url = input instanceof Request ? input.url : input;
+        const filtered = filter(url);
+        if (filtered === null) {
+            return Promise.reject(new TypeError("Request to " + url + " was blocked by the request filter"));
+        }
+        if (filtered !== url) {
+            input = input instanceof Request ? new Request(filtered, input) : filtered;
+        }
+        return originalFetch.call(this, input, init);
+    };
+
+    const originalOpen = XMLHttpRequest.prototype.open;
+    XMLHttpRequest.prototype.open = function (method, url, ...rest) {
+        const filtered = filter(url);
+        if (filtered === null) {
+            // Send the request nowhere so it fails with a network error like any other blocked request
+            return originalOpen.call(this, method, "about:blank", ...rest);
+        }
+        return originalOpen.call(this, method, filtered, ...rest);
+    };
+
+    const urlAttributes = {
+        IMG: "src",
+        SCRIPT: "src",
+        IFRAME: "src",
+        SOURCE: "src",
+        VIDEO: "src",
+        AUDIO: "src",
+        EMBED: "src",
+        LINK: "href",
+    };
+
+    const originalSetAttribute = Element.prototype.setAttribute;
+    Element.prototype.setAttribute = function (name, value) {
+        if (urlAttributes[this.tagName] === name.toLowerCase()) {
+            const filtered = filter(value);
+            if (filtered === null) {
+                return;
+            }
+            value = filtered;
+        }
+        return originalSetAttribute.call(this, name, value);
+    };
+
+    const elements = [
+        [HTMLImageElement, "src"],
+        [HTMLScriptElement, "src"],
+        [HTMLIFrameElement, "src"],
+        [HTMLSourceElement, "src"],
+        [HTMLMediaElement, "src"],
+        [HTMLEmbedElement, "src"],
+        [HTMLLinkElement, "href"],
+    ];
+    for (const [element, property] of elements) {
+        const descriptor = Object.getOwnPropertyDescriptor(element.prototype, property);
+        if (!descriptor || !descriptor.set) {
+            continue;
+        }
+        Object.defineProperty(element.prototype, property, {
+            ...descriptor,
+            set(value) {
+                const filtered = filter(value);
+                if (filtered !== null) {
+                    descriptor.set.call(this, filtered);
+                }
+            },
+        });
+    }
+})();
+"#;
@@ -0,0 +1,140 @@
+//! Headers added to the responses of the `dioxus://` protocol.
+//!
+//! Headers like `Content-Security-Policy` or `Cross-Origin-Embedder-Policy` only work when the response carries them,
+//! so they can't be set with a `<meta>` tag in a custom head. The CSP is set on `index.html`, and the hook can add any
+//! header to any response.
+
+use std::sync::Arc;
+
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use wry::http::{HeaderMap, HeaderValue};
+
+/// Adds headers to the response for a path. See [`Config::with_response_headers`](crate::Config::with_response_headers).
+pub(crate) type ResponseHeaderHook = Arc<dyn Fn(&str, &mut HeaderMap) + Send + Sync + 'static>;
+
+#[derive(Clone, Default)]
+pub(crate) struct ResponseHeaders {
+    pub(crate) csp: Option<String>,
+    pub(crate) hook: Option<ResponseHeaderHook>,
+}
+
+impl ResponseHeaders {
+    /// Add the `Content-Security-Policy` of `index.html`, which must allow the inline script that starts the interpreter
+    pub(crate) fn apply_index(&self, headers: &mut HeaderMap, loader_script: &str) {
+        let Some(csp) = &self.csp else {
+            return;
+        };
+        let hash = base64::engine::general_purpose::STANDARD.encode(Sha256::digest(loader_script));
+        let csp = allow_script(csp, &format!("'sha256-{hash}'"));
+        match HeaderValue::from_str(&csp) {
+            Ok(csp) => {
+                headers.insert("Content-Security-Policy", csp);
+            }
+            Err(err) => tracing::error!("invalid Content-Security-Policy {:?}: {}", csp, err),
+        }
+    }
+
+    /// Let the hook add its headers to the response for `path`
+    pub(crate) fn apply(&self, path: &str, headers: &mut HeaderMap) {
+        if let Some(hook) = &self.hook {
+            hook(path, headers);
+        }
+    }
+}
+
+/// Add `source` to the sources scripts can be loaded from in `csp`.
+///
+/// Scripts use the `script-src` directive, or `default-src` if there is none. If neither is set, every script is
+/// already allowed. A directive with `'unsafe-inline'` already allows the script, and is left alone: browsers ignore
+/// `'unsafe-inline'` once a hash is added, which would block the other inline scripts of the page.
+fn allow_script(csp: &str, source: &str) -> String {
+    let directives: Vec<&str> = csp
+        .split(';')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .collect();
+    let target = if directives
+        .iter()
+        .any(|directive| directive_name(directive) == "script-src")
+    {
+        "script-src"
+    } else {
+        "default-src"
+    };
+
+    directives
+        .iter()
+        .map(|directive| {
+            if directive_name(directive) == target && !allows_inline_scripts(directive) {
+                format!("{directive} {source}")
+            } else {
+                directive.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn directive_name(directive: &str) -> &str {
+    directive.split_whitespace().next().unwrap_or_default()
+}
+
+/// Whether the directive allows every inline script. A hash or nonce in it turns `'unsafe-inline'` off.
+fn allows_inline_scripts(directive: &str) -> bool {
+    let sources: Vec<String> = directive
+        .split_whitespace()
+        .skip(1)
+        .map(str::to_ascii_lowercase)
+        .collect();
+    sources.iter().any(|source| source == "'unsafe-inline'")
+        && !sources.iter().any(|source| {
+            ["'sha256-", "'sha384-", "'sha512-", "'nonce-"]
+                .iter()
+                .any(|prefix| source.starts_with(prefix))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "'sha256-abc'";
+
+    #[test]
+    fn the_hash_is_added_to_script_src() {
+        assert_eq!(
+            allow_script("default-src 'self'; script-src 'self'", HASH),
+            "default-src 'self'; script-src 'self' 'sha256-abc'"
+        );
+    }
+
+    #[test]
+    fn the_hash_is_added_to_default_src_without_script_src() {
+        assert_eq!(
+            allow_script(" default-src 'self' ;img-src *;", HASH),
+            "default-src 'self' 'sha256-abc'; img-src *"
+        );
+    }
+
+    #[test]
+    fn policies_without_script_sources_allow_every_script() {
+        assert_eq!(allow_script("img-src *", HASH), "img-src *");
+    }
+
+    #[test]
+    fn unsafe_inline_is_left_alone() {
+        let csp = "default-src 'self'; script-src 'self' 'UNSAFE-INLINE'";
+        assert_eq!(allow_script(csp, HASH), csp);
+        let csp = "default-src 'self' 'unsafe-inline'";
+        assert_eq!(allow_script(csp, HASH), csp);
+    }
+
+    #[test]
+    fn unsafe_inline_next_to_a_nonce_gets_the_hash() {
+        assert_eq!(
+            allow_script("script-src 'unsafe-inline' 'nonce-xyz'", HASH),
+            "script-src 'unsafe-inline' 'nonce-xyz' 'sha256-abc'"
+        );
+    }
+}
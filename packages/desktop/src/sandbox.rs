@@ -0,0 +1,359 @@
+//! Running untrusted JavaScript, like user plugins, away from the app.
+//!
+//! Every sandbox is a hidden `<iframe sandbox="allow-scripts">` with its own JavaScript context and an opaque origin,
+//! so scripts in it can't reach the DOM of the app or the interpreter. The only way out is the message bridge: scripts
+//! in the sandbox call `plugin.send(message)` to send a message to Rust, and receive the messages Rust sends with
+//! `plugin.subscribe(listener)`.
+//!
+//! WebView2 runs the initialization scripts of the webview in every frame, sandboxes included. Every initialization
+//! script that talks to Rust starts with `if (window !== window.top) return;`, so none of them are set up in a sandbox.
+//! The `window.ipc` object wry defines is still there on WebView2, but WebView2 only delivers messages from the top
+//! level document to the app.
+//!
+//! ```js
+//! plugin.subscribe((message) => {
+//!     plugin.send({ word_count: message.text.split(/\s+/).length });
+//! });
+//! ```
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use dioxus_core::ScopeState;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use slab::Slab;
+use thiserror::Error;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use wry::webview::WebView;
+
+use crate::use_window;
+
+/// Defines `window.__dioxusSandboxes` in the page, which creates the frames and relays their messages
+pub(crate) const SANDBOX_SCRIPT: &str = r#"(function () {
+    if (window !== window.top) return;
+    const bootstrap = `<script>
+        (function () {
+            const listeners = [];
+            const queue = [];
+            const post = (kind, data) => parent.postMessage({ __dioxusSandbox: true, kind, data }, "*");
+            window.plugin = {
+                send(message) {
+                    post("message", message);
+                },
+                subscribe(listener) {
+                    queue.splice(0).forEach((message) => listener(message));
+                    listeners.push(listener);
+                },
+            };
+            window.addEventListener("error", (event) => post("error", String(event.message)));
+            window.addEventListener("message", (event) => {
+                if (event.source !== parent || !event.data) return;
+                const { kind, data } = event.data;
+                if (kind === "eval") {
+                    try {
+                        (0, eval)(data);
+                    } catch (error) {
+                        post("error", String(error));
+                    }
+                } else if (kind === "message") {
+                    if (listeners.length) {
+                        listeners.forEach((listener) => listener(data));
+                    } else {
+                        queue.push(data);
+                    }
+                }
+            });
+        })();
+    <\/script>`;
+
+    const sandboxes = new Map();
+
+    window.__dioxusSandboxes = {
+        create(id) {
+            const frame = document.createElement("iframe");
+            frame.setAttribute("sandbox", "allow-scripts");
+            frame.style.display = "none";
+            const sandbox = { frame, ready: false, pending: [] };
+            frame.addEventListener("load", () => {
+                sandbox.ready = true;
+                sandbox.pending.splice(0).forEach((message) => frame.contentWindow.postMessage(message, "*"));
+            });
+            frame.srcdoc = bootstrap;
+            sandboxes.set(id, sandbox);
+            document.body.appendChild(frame);
+        },
+        post(id, kind, data) {
+            const sandbox = sandboxes.get(id);
+            if (!sandbox) return;
+            if (sandbox.ready) {
+                sandbox.frame.contentWindow.postMessage({ kind, data }, "*");
+            } else {
+                sandbox.pending.push({ kind, data });
+            }
+        },
+        destroy(id) {
+            const sandbox = sandboxes.get(id);
+            if (!sandbox) return;
+            sandboxes.delete(id);
+            sandbox.frame.remove();
+        },
+    };
+
+    window.addEventListener("message", (event) => {
+        if (!event.data || !event.data.__dioxusSandbox) return;
+        for (const [id, sandbox] of sandboxes) {
+            if (event.source === sandbox.frame.contentWindow) {
+                const { kind, data } = event.data;
+                window.ipc.postMessage(
+                    JSON.stringify({ method: "sandbox_message", params: { id, kind, data: data === undefined ? null : data } })
+                );
+                return;
+            }
+        }
+    });
+})();"#;
+
+/// An error that can occur when talking to a sandbox
+#[derive(Error, Debug)]
+pub enum SandboxError {
+    /// The message could not be serialized to JSON
+    #[error("Failed to serialize the message: {0}")]
+    Serialize(serde_json::Error),
+    /// The sandbox sent a message that doesn't match the type of the receiver
+    #[error("Failed to deserialize the message: {0}")]
+    Deserialize(serde_json::Error),
+    /// The webview could not run the script that talks to the sandbox
+    #[error("Failed to send the message to the sandbox: {0}")]
+    Send(String),
+    /// A script in the sandbox threw an error
+    #[error("Error in the sandbox: {0}")]
+    Script(String),
+}
+
+/// What the page relays from a sandbox
+pub(crate) type SandboxMessage = Result<Value, String>;
+
+/// Get the id of the sandbox and the message from the parameters of a `sandbox_message` IPC message
+pub(crate) fn parse_message(params: &Value) -> Option<(usize, SandboxMessage)> {
+    let id = params["id"].as_u64()? as usize;
+    let data = params.get("data").cloned().unwrap_or(Value::Null);
+    let message = match params["kind"].as_str() {
+        Some("error") => Err(data.as_str().unwrap_or_default().to_string()),
+        _ => Ok(data),
+    };
+    Some((id, message))
+}
+
+pub(crate) type SandboxRegistry = Rc<RefCell<Slab<UnboundedSender<SandboxMessage>>>>;
+
+/// An isolated JavaScript context. The frame of the sandbox is removed when this is dropped.
+pub struct Sandbox {
+    id: usize,
+    webview: Rc<WebView>,
+    registry: SandboxRegistry,
+}
+
+impl Sandbox {
+    pub(crate) fn create<T>(
+        webview: Rc<WebView>,
+        registry: SandboxRegistry,
+    ) -> Result<(Self, SandboxReceiver<T>), SandboxError> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let id = registry.borrow_mut().insert(tx);
+        let sandbox = Self {
+            id,
+            webview,
+            registry,
+        };
+        sandbox.run(&format!("window.__dioxusSandboxes.create({});", id))?;
+        let receiver = SandboxReceiver {
+            receiver: rx,
+            _marker: PhantomData,
+        };
+        Ok((sandbox, receiver))
+    }
+
+    /// Run a script in the sandbox. Errors it throws are sent to the receiver of the sandbox.
+    pub fn eval(&self, script: &str) -> Result<(), SandboxError> {
+        self.post("eval", &script)
+    }
+
+    /// Send a message to the listeners the scripts in the sandbox added with `plugin.subscribe`
+    pub fn send<T: Serialize>(&self, message: &T) -> Result<(), SandboxError> {
+        self.post("message", message)
+    }
+
+    fn post<T: Serialize + ?Sized>(&self, kind: &str, data: &T) -> Result<(), SandboxError> {
+        let data = serde_json::to_string(data).map_err(SandboxError::Serialize)?;
+        self.run(&format!(
+            "window.__dioxusSandboxes.post({}, {:?}, {});",
+            self.id, kind, data
+        ))
+    }
+
+    fn run(&self, script: &str) -> Result<(), SandboxError> {
+        self.webview
+            .evaluate_script(script)
+            .map_err(|err| SandboxError::Send(err.to_string()))
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        self.registry.borrow_mut().try_remove(self.id);
+        _ = self.run(&format!("window.__dioxusSandboxes.destroy({});", self.id));
+    }
+}
+
+/// Receives the messages the scripts in a sandbox send with `plugin.send`
+pub struct SandboxReceiver<T> {
+    receiver: UnboundedReceiver<SandboxMessage>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> SandboxReceiver<T> {
+    /// Wait for the next message from the sandbox, or the next error a script in it threw.
+    ///
+    /// Returns `None` once the sandbox is dropped.
+    pub async fn recv(&mut self) -> Option<Result<T, SandboxError>> {
+        let message = self.receiver.recv().await?;
+        Some(match message {
+            Ok(message) => serde_json::from_value(message).map_err(SandboxError::Deserialize),
+            Err(err) => Err(SandboxError::Script(err)),
+        })
+    }
+}
+
+/// Create a sandbox for the lifetime of the component and call `handler` with every message its scripts send.
+///
+/// Returns an error if the webview could not create the frame of the sandbox.
+///
+/// ```rust, ignore
+/// #[derive(Deserialize)]
+/// struct Stats {
+///     word_count: usize,
+/// }
+///
+/// fn app(cx: Scope) -> Element {
+///     let words = use_state(cx, || 0);
+///     let sandbox = use_sandbox(cx, {
+///         to_owned![words];
+///         move |message: Result<Stats, SandboxError>| match message {
+///             Ok(stats) => words.set(stats.word_count),
+///             Err(err) => tracing::warn!("The plugin failed: {}", err),
+///         }
+///     });
+///     let sandbox = match sandbox {
+///         Ok(sandbox) => sandbox,
+///         Err(err) => return render! { "Plugins are unavailable: {err}" },
+///     };
+///
+///     use_effect(cx, (), |_| {
+///         to_owned![sandbox];
+///         async move { _ = sandbox.eval(&load_plugin_source()) }
+///     });
+///
+///     render! {
+///         textarea { oninput: move |event| _ = sandbox.send(&json!({ "text": event.value })) }
+///         "{words} words"
+///     }
+/// }
+/// ```
+pub fn use_sandbox<T: DeserializeOwned + 'static>(
+    cx: &ScopeState,
+    mut handler: impl FnMut(Result<T, SandboxError>) + 'static,
+) -> &Result<Rc<Sandbox>, SandboxError> {
+    let desktop = use_window(cx);
+    cx.use_hook(|| {
+        let (sandbox, mut receiver) = desktop.create_sandbox::<T>()?;
+        cx.spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                handler(message);
+            }
+        });
+        Ok(Rc::new(sandbox))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn relayed_messages() {
+        let (id, message) =
+            parse_message(&json!({ "id": 3, "kind": "message", "data": { "a": 1 } })).unwrap();
+        assert_eq!(id, 3);
+        assert_eq!(message, Ok(json!({ "a": 1 })));
+
+        let (_, message) = parse_message(&json!({ "id": 3, "kind": "message" })).unwrap();
+        assert_eq!(message, Ok(Value::Null));
+
+        let (_, message) =
+            parse_message(&json!({ "id": 0, "kind": "error", "data": "boom" })).unwrap();
+        assert_eq!(message, Err("boom".to_string()));
+
+        assert!(parse_message(&json!({ "kind": "message", "data": 1 })).is_none());
+        assert!(parse_message(&json!({ "id": "3", "kind": "message" })).is_none());
+    }
+
+    #[tokio::test]
+    async fn receiver_deserializes_messages() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Stats {
+            word_count: usize,
+        }
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut receiver = SandboxReceiver::<Stats> {
+            receiver: rx,
+            _marker: PhantomData,
+        };
+        tx.send(Ok(json!({ "word_count": 2 }))).unwrap();
+        tx.send(Ok(json!({ "words": 2 }))).unwrap();
+        tx.send(Err("ReferenceError: x is not defined".to_string()))
+            .unwrap();
+        drop(tx);
+
+        assert_eq!(
+            receiver.recv().await.unwrap().unwrap(),
+            Stats { word_count: 2 }
+        );
+        assert!(matches!(
+            receiver.recv().await,
+            Some(Err(SandboxError::Deserialize(_)))
+        ));
+        assert!(matches!(
+            receiver.recv().await,
+            Some(Err(SandboxError::Script(err))) if err.starts_with("ReferenceError")
+        ));
+        assert!(receiver.recv().await.is_none());
+    }
+
+    #[test]
+    fn ipc_scripts_are_not_set_up_in_frames() {
+        for script in [
+            SANDBOX_SCRIPT,
+            crate::ipc_channel::CHANNEL_SCRIPT,
+            crate::invoke::INVOKE_SCRIPT,
+            crate::event_listener::EVENT_LISTENER_SCRIPT,
+            crate::cursor::POINTER_SCRIPT,
+            crate::shortcut::ACCELERATOR_SCRIPT,
+            crate::file_drop::FILE_DROP_SCRIPT,
+        ] {
+            let body = script
+                .strip_prefix("(function () {")
+                .expect("initialization scripts run in their own function");
+            assert!(
+                body.trim_start()
+                    .starts_with("if (window !== window.top) return;"),
+                "{}",
+                &script[..80]
+            );
+        }
+    }
+}
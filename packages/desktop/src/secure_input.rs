@@ -0,0 +1,165 @@
+//! Inputs that hold secrets, like passwords, recovery codes and API keys.
+//!
+//! An input is secure when it has the `data-dioxus-secure` attribute, or when it is a password input. Secure inputs
+//! are given `spellcheck="false"`, `autocorrect="off"` and `autocapitalize="off"` so their text isn't sent to the
+//! spellchecker, and an `autocomplete` that stops the webview from saving it. The page reports when a secure input
+//! has focus: on macOS the keyboard is then put in secure event input mode, which hides the keys typed from other
+//! apps like autotype tools.
+//!
+//! The mode is off unless it is turned on with [`Config::with_secure_input`](crate::Config::with_secure_input),
+//! because it changes the `autocomplete` of password inputs, which password managers rely on.
+
+use wry::webview::WebView;
+
+/// The attribute that makes an element a secure input
+pub const SECURE_INPUT_ATTRIBUTE: &str = "data-dioxus-secure";
+
+/// How a window protects inputs that hold secrets. Turn it on with
+/// [`Config::with_secure_input`](crate::Config::with_secure_input):
+///
+/// ```rust, ignore
+/// Config::new().with_secure_input(SecureInput::new())
+/// ```
+///
+/// Mark an input as secure with the [`SECURE_INPUT_ATTRIBUTE`]:
+///
+/// ```rust, ignore
+/// render! {
+///     input { r#type: "text", "data-dioxus-secure": "true", placeholder: "Recovery code" }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecureInput {
+    enabled: bool,
+    password_fields: bool,
+}
+
+impl Default for SecureInput {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+impl SecureInput {
+    /// Protect inputs with the [`SECURE_INPUT_ATTRIBUTE`] and password inputs
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            password_fields: true,
+        }
+    }
+
+    /// Leave every input as the page made it. The default.
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            password_fields: true,
+        }
+    }
+
+    /// Set whether password inputs are secure without the [`SECURE_INPUT_ATTRIBUTE`]. Defaults to `true`.
+    pub fn with_password_fields(mut self, password_fields: bool) -> Self {
+        self.password_fields = password_fields;
+        self
+    }
+
+    /// The script that protects the secure inputs of the page, or `None` if they are left alone
+    pub(crate) fn script(&self) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        Some(format!(
+            "{}\nwindow.__dioxusSecureInput({:?}, {});",
+            SECURE_INPUT_SCRIPT, SECURE_INPUT_ATTRIBUTE, self.password_fields
+        ))
+    }
+}
+
+const SECURE_INPUT_SCRIPT: &str = r#"window.__dioxusSecureInput = function (attribute, passwordFields) {
+    if (window !== window.top) return;
+    const isSecure = (element) => element instanceof HTMLElement && (element.hasAttribute(attribute)
+        || (passwordFields && element instanceof HTMLInputElement && element.type === "password"));
+    const protect = (element) => {
+        if (!isSecure(element)) return;
+        element.setAttribute("spellcheck", "false");
+        element.setAttribute("autocorrect", "off");
+        element.setAttribute("autocapitalize", "off");
+        const password = element instanceof HTMLInputElement && element.type === "password";
+        element.setAttribute("autocomplete", password ? "new-password" : "off");
+    };
+    const protectAll = (root) => {
+        protect(root);
+        if (root.querySelectorAll) {
+            root.querySelectorAll(`[${attribute}], input[type=password]`).forEach(protect);
+        }
+    };
+    new MutationObserver((mutations) => {
+        for (const mutation of mutations) {
+            if (mutation.type === "attributes") {
+                protect(mutation.target);
+            } else {
+                mutation.addedNodes.forEach(protectAll);
+            }
+        }
+    }).observe(document, { childList: true, subtree: true, attributes: true, attributeFilter: ["type", attribute] });
+
+    // Tell the app when a secure input gains or loses focus, including when the window does
+    let focused = false;
+    const update = () => {
+        const now = document.hasFocus() && isSecure(document.activeElement);
+        if (now !== focused) {
+            focused = now;
+            window.ipc.postMessage(JSON.stringify({ method: "secure_input", params: now }));
+        }
+    };
+    document.addEventListener("focusin", update);
+    document.addEventListener("focusout", () => setTimeout(update, 0));
+    window.addEventListener("focus", update);
+    window.addEventListener("blur", update);
+};"#;
+
+/// Keeps the keyboard in secure event input mode while it is alive, so other apps can't read the keys that are
+/// typed. Only macOS has this mode.
+pub(crate) struct SecureEventInput {
+    _private: (),
+}
+
+impl SecureEventInput {
+    pub(crate) fn enable() -> Self {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            carbon::EnableSecureEventInput();
+        }
+        Self { _private: () }
+    }
+}
+
+impl Drop for SecureEventInput {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        unsafe {
+            carbon::DisableSecureEventInput();
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod carbon {
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        pub(super) fn EnableSecureEventInput() -> i32;
+        pub(super) fn DisableSecureEventInput() -> i32;
+    }
+}
+
+/// Hide the contents of the window of `webview` from screen capture, or show them again
+pub(crate) fn set_window_protected(webview: &WebView, protected: bool) {
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    webview.window().set_content_protection(protected);
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let _ = (webview, protected);
+        tracing::debug!("content protection is only supported on Windows and macOS");
+    }
+}
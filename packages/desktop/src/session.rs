@@ -0,0 +1,56 @@
+//! Ephemeral sessions, for windows that shouldn't share or keep cookies and storage.
+//!
+//! Every window has its own `WebContext`, but windows with the same data directory share the cookies, storage and
+//! cache kept in it. An ephemeral window runs incognito in a data directory of its own, which is deleted when the
+//! window closes.
+
+use std::io;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// The data directory of an ephemeral window, deleted when it is dropped
+pub(crate) struct EphemeralSession {
+    directory: TempDir,
+}
+
+impl EphemeralSession {
+    /// Create an empty data directory with a random name in the temporary directory. Only the current user can
+    /// access it, so other users can't read the session or swap the directory out.
+    pub(crate) fn new() -> io::Result<Self> {
+        let directory = tempfile::Builder::new()
+            .prefix("dioxus-session-")
+            .tempdir()?;
+        Ok(Self { directory })
+    }
+
+    pub(crate) fn directory(&self) -> &Path {
+        self.directory.path()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_get_a_private_directory_that_is_deleted_with_them() {
+        let first = EphemeralSession::new().unwrap();
+        let second = EphemeralSession::new().unwrap();
+        assert_ne!(first.directory(), second.directory());
+        assert!(first.directory().is_dir());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(first.directory())
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o077, 0);
+        }
+
+        let directory = first.directory().to_path_buf();
+        drop(first);
+        assert!(!directory.exists());
+    }
+}
@@ -5,6 +5,8 @@ use dioxus_html::input_data::keyboard_types::Modifiers;
 use slab::Slab;
 use wry::application::keyboard::ModifiersState;
 
+#[cfg(all(target_os = "linux", feature = "wayland-portal-shortcuts"))]
+use crate::portal_shortcuts::PortalShortcuts;
 use crate::{desktop_context::DesktopContext, use_window};
 
 #[cfg(any(
@@ -24,16 +26,52 @@ pub use global_hotkey::{
 #[cfg(any(target_os = "ios", target_os = "android"))]
 pub use crate::mobile_shortcut::*;
 
+/// Defines `window.__dioxusAccelerators` in the page, which runs the shortcuts of the window while it has focus
+pub(crate) const ACCELERATOR_SCRIPT: &str = r#"(function () {
+    if (window !== window.top) return;
+    const accelerators = new Map();
+
+    window.__dioxusAccelerators = {
+        add(id, code, shift, ctrl, alt, meta) {
+            accelerators.set(id, { code, shift, ctrl, alt, meta });
+        },
+        remove(id) {
+            accelerators.delete(id);
+        },
+    };
+
+    window.addEventListener(
+        "keydown",
+        (event) => {
+            for (const [id, accelerator] of accelerators) {
+                if (
+                    event.code === accelerator.code &&
+                    event.shiftKey === accelerator.shift &&
+                    event.ctrlKey === accelerator.ctrl &&
+                    event.altKey === accelerator.alt &&
+                    event.metaKey === accelerator.meta
+                ) {
+                    event.preventDefault();
+                    window.ipc.postMessage(JSON.stringify({ method: "window_shortcut", params: id }));
+                }
+            }
+        },
+        true
+    );
+})();"#;
+
 #[derive(Clone)]
 pub(crate) struct ShortcutRegistry {
     manager: Rc<RefCell<GlobalHotKeyManager>>,
     shortcuts: ShortcutMap,
+    /// On Wayland the shortcuts are bound through the desktop portal instead of the manager
+    #[cfg(all(target_os = "linux", feature = "wayland-portal-shortcuts"))]
+    portal: Option<Rc<PortalShortcuts>>,
 }
 
 type ShortcutMap = Rc<RefCell<HashMap<u32, Shortcut>>>;
 
 struct Shortcut {
-    #[allow(unused)]
     shortcut: HotKey,
     callbacks: Slab<Box<dyn FnMut()>>,
 }
@@ -57,17 +95,81 @@ impl ShortcutRegistry {
         Self {
             manager: Rc::new(RefCell::new(GlobalHotKeyManager::new().unwrap())),
             shortcuts: Rc::new(RefCell::new(HashMap::new())),
+            #[cfg(all(target_os = "linux", feature = "wayland-portal-shortcuts"))]
+            portal: crate::wayland::is_wayland().then(|| Rc::new(PortalShortcuts::new())),
         }
     }
 
     pub(crate) fn call_handlers(&self, id: GlobalHotKeyEvent) {
-        if let Some(Shortcut { callbacks, .. }) = self.shortcuts.borrow_mut().get_mut(&id.id) {
+        self.call_handlers_for(id.id);
+    }
+
+    fn call_handlers_for(&self, id: u32) {
+        if let Some(Shortcut { callbacks, .. }) = self.shortcuts.borrow_mut().get_mut(&id) {
             for (_, callback) in callbacks.iter_mut() {
                 (callback)();
             }
         }
     }
 
+    /// Call the handlers of the shortcuts the user activated through the desktop portal
+    #[cfg(all(target_os = "linux", feature = "wayland-portal-shortcuts"))]
+    pub(crate) fn poll_portal(&self) {
+        if let Some(portal) = &self.portal {
+            while let Some(id) = portal.try_recv() {
+                self.call_handlers_for(id);
+            }
+        }
+    }
+
+    fn register(&self, hotkey: HotKey) -> Result<(), HotkeyError> {
+        #[cfg(all(target_os = "linux", feature = "wayland-portal-shortcuts"))]
+        if let Some(portal) = &self.portal {
+            portal.bind(hotkey.id(), &hotkey);
+            return Ok(());
+        }
+        self.manager.borrow_mut().register(hotkey).map(|_| ())
+    }
+
+    fn unregister(&self, hotkey: HotKey) {
+        #[cfg(all(target_os = "linux", feature = "wayland-portal-shortcuts"))]
+        if let Some(portal) = &self.portal {
+            portal.unbind(hotkey.id());
+            return;
+        }
+        let _ = self.manager.borrow_mut().unregister(hotkey);
+    }
+
+    /// Check if the app can register `hotkey`, either because it already registered it, or because no other
+    /// application did
+    pub(crate) fn is_available(&self, hotkey: HotKey) -> bool {
+        if self.is_registered(&hotkey) {
+            return true;
+        }
+        // The only way to find out if another application took the shortcut is to try to register it
+        match self.register(hotkey) {
+            Ok(()) => {
+                self.unregister(hotkey);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Check if the app registered `hotkey`
+    pub(crate) fn is_registered(&self, hotkey: &HotKey) -> bool {
+        self.shortcuts.borrow().contains_key(&hotkey.id())
+    }
+
+    /// Get every shortcut the app registered
+    pub(crate) fn registered(&self) -> Vec<HotKey> {
+        self.shortcuts
+            .borrow()
+            .values()
+            .map(|shortcut| shortcut.shortcut)
+            .collect()
+    }
+
     pub(crate) fn add_shortcut(
         &self,
         hotkey: HotKey,
@@ -83,7 +185,7 @@ impl ShortcutRegistry {
                     number: id,
                 }
             } else {
-                match self.manager.borrow_mut().register(hotkey) {
+                match self.register(hotkey) {
                     Ok(_) => {
                         let mut slab = Slab::new();
                         let id = slab.insert(callback);
@@ -100,6 +202,9 @@ impl ShortcutRegistry {
                     Err(HotkeyError::HotKeyParseError(shortcut)) => {
                         return Err(ShortcutRegistryError::InvalidShortcut(shortcut))
                     }
+                    Err(HotkeyError::AlreadyRegistered(_) | HotkeyError::FailedToRegister(_)) => {
+                        return Err(ShortcutRegistryError::AlreadyRegistered(hotkey))
+                    }
                     Err(err) => return Err(ShortcutRegistryError::Other(Box::new(err))),
                 }
             },
@@ -112,7 +217,7 @@ impl ShortcutRegistry {
             callbacks.remove(id.number);
             if callbacks.is_empty() {
                 if let Some(_shortcut) = shortcuts.remove(&id.id) {
-                    let _ = self.manager.borrow_mut().unregister(_shortcut.shortcut);
+                    self.unregister(_shortcut.shortcut);
                 }
             }
         }
@@ -121,17 +226,28 @@ impl ShortcutRegistry {
     pub(crate) fn remove_all(&self) {
         let mut shortcuts = self.shortcuts.borrow_mut();
         let hotkeys: Vec<_> = shortcuts.drain().map(|(_, v)| v.shortcut).collect();
+        #[cfg(all(target_os = "linux", feature = "wayland-portal-shortcuts"))]
+        if let Some(portal) = &self.portal {
+            portal.unbind_all();
+            return;
+        }
         let _ = self.manager.borrow_mut().unregister_all(&hotkeys);
     }
 }
 
 #[non_exhaustive]
-#[derive(Debug)]
+#[derive(Debug, thiserror::Error)]
 /// An error that can occur when registering a shortcut.
 pub enum ShortcutRegistryError {
     /// The shortcut is invalid.
+    #[error("Invalid shortcut: {0}")]
     InvalidShortcut(String),
+    /// The shortcut is already used by another application, or by a global shortcut of this app if a window shortcut
+    /// was registered.
+    #[error("The shortcut {0:?} is already registered")]
+    AlreadyRegistered(HotKey),
     /// An unknown error occurred.
+    #[error("Failed to register the shortcut: {0}")]
     Other(Box<dyn std::error::Error>),
 }
 
@@ -209,6 +325,76 @@ impl Drop for ShortcutHandle {
     }
 }
 
+/// The id of a shortcut that only runs while its window has focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WindowShortcutId(pub(crate) usize);
+
+/// A shortcut of a window. This will be automatically removed when it is dropped.
+pub struct WindowShortcutHandle {
+    desktop: DesktopContext,
+    /// The id of the shortcut
+    pub shortcut_id: WindowShortcutId,
+}
+
+impl WindowShortcutHandle {
+    /// Remove the shortcut.
+    pub fn remove(&self) {
+        self.desktop.remove_window_shortcut(self.shortcut_id);
+    }
+}
+
+impl Drop for WindowShortcutHandle {
+    fn drop(&mut self) {
+        self.remove()
+    }
+}
+
+/// Call `handler` when the accelerator is pressed while the window of the component has focus. Unlike
+/// [`use_global_shortcut`], other applications keep receiving the accelerator.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let saved = use_state(cx, || 0);
+///
+///     _ = use_window_shortcut(cx, "ctrl+s", {
+///         to_owned![saved];
+///         move || saved.modify(|saved| saved + 1)
+///     });
+///
+///     render! { "Saved {saved} times" }
+/// }
+/// ```
+pub fn use_window_shortcut(
+    cx: &ScopeState,
+    accelerator: impl IntoAccelerator,
+    handler: impl FnMut() + 'static,
+) -> &Result<WindowShortcutHandle, ShortcutRegistryError> {
+    let desktop = use_window(cx);
+    cx.use_hook(move || {
+        let desktop = desktop.clone();
+
+        let id = desktop.create_window_shortcut(accelerator.accelerator(), handler);
+
+        Ok(WindowShortcutHandle {
+            desktop,
+            shortcut_id: id?,
+        })
+    })
+}
+
+/// The arguments of `window.__dioxusAccelerators.add` that match the key and modifiers of `hotkey`
+pub(crate) fn accelerator_script_args(hotkey: &HotKey) -> String {
+    let mods = hotkey.mods;
+    format!(
+        "{:?}, {}, {}, {}, {}",
+        hotkey.key.to_string(),
+        mods.contains(Modifiers::SHIFT),
+        mods.contains(Modifiers::CONTROL),
+        mods.contains(Modifiers::ALT),
+        mods.contains(Modifiers::META) || mods.contains(Modifiers::SUPER),
+    )
+}
+
 pub trait IntoModifersState {
     fn into_modifiers_state(self) -> Modifiers;
 }
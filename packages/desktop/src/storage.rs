@@ -0,0 +1,855 @@
+//! Cookies and the other data the webview stores for websites.
+//!
+//! The data belongs to the data directory of the window, so it is shared by every window with the same
+//! [data directory](crate::Config::with_data_directory). Each platform manages it with its own webview: the
+//! `ICoreWebView2CookieManager` and `ClearBrowsingData` of WebView2 on Windows, the `WebKitCookieManager` and
+//! `WebKitWebsiteDataManager` on Linux, and the `WKHTTPCookieStore` and `WKWebsiteDataStore` on macOS. On Linux this
+//! needs the `webkitgtk` feature.
+
+use std::ops::{BitOr, BitOrAssign};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use thiserror::Error;
+use wry::webview::WebView;
+
+/// A cookie stored by the webview
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cookie {
+    /// The name of the cookie
+    pub name: String,
+    /// The value of the cookie
+    pub value: String,
+    /// The domain the cookie is sent to. A domain that starts with `.` includes its subdomains.
+    pub domain: String,
+    /// The path the cookie is sent to, including the paths below it
+    pub path: String,
+    /// When the cookie expires, or `None` for a cookie that is deleted when the app closes
+    pub expires: Option<SystemTime>,
+    /// Only send the cookie over https
+    pub secure: bool,
+    /// Hide the cookie from JavaScript
+    pub http_only: bool,
+}
+
+impl Cookie {
+    /// A session cookie for `domain` on every path
+    pub fn new(
+        name: impl Into<String>,
+        value: impl Into<String>,
+        domain: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            domain: domain.into(),
+            path: "/".to_string(),
+            expires: None,
+            secure: false,
+            http_only: false,
+        }
+    }
+
+    /// Set the path the cookie is sent to
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Set when the cookie expires
+    pub fn with_expires(mut self, expires: SystemTime) -> Self {
+        self.expires = Some(expires);
+        self
+    }
+
+    /// Only send the cookie over https
+    pub fn with_secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Hide the cookie from JavaScript
+    pub fn with_http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    /// Whether the cookie is sent with requests to `url`
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub(crate) fn matches(&self, url: &url::Url) -> bool {
+        let host = url.host_str().unwrap_or_default();
+        let domain = self.domain.trim_start_matches('.');
+        let domain_matches = host.eq_ignore_ascii_case(domain)
+            || (host.len() > domain.len()
+                && host.ends_with(domain)
+                && host.as_bytes()[host.len() - domain.len() - 1] == b'.');
+        let path = url.path();
+        let path_matches = path == self.path
+            || (path.starts_with(&self.path)
+                && (self.path.ends_with('/') || path[self.path.len()..].starts_with('/')));
+        domain_matches && path_matches && (!self.secure || url.scheme() == "https")
+    }
+}
+
+/// The kinds of data [`DesktopService::clear_browsing_data`](crate::DesktopService::clear_browsing_data) removes.
+/// Combine them with `|`.
+///
+/// ```rust, ignore
+/// window().clear_browsing_data(DataTypes::COOKIES | DataTypes::LOCAL_STORAGE).await?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DataTypes(u32);
+
+impl DataTypes {
+    /// Cookies
+    pub const COOKIES: DataTypes = DataTypes(1);
+    /// `localStorage`
+    pub const LOCAL_STORAGE: DataTypes = DataTypes(1 << 1);
+    /// `sessionStorage`
+    ///
+    /// WebView2 on Windows can only clear it together with the rest of the DOM storage. Clearing it there fails with
+    /// [`StorageError::UnsupportedTypes`] unless [`LOCAL_STORAGE`](Self::LOCAL_STORAGE),
+    /// [`INDEXED_DB`](Self::INDEXED_DB), [`CACHE`](Self::CACHE) and [`SERVICE_WORKERS`](Self::SERVICE_WORKERS) are
+    /// cleared with it.
+    pub const SESSION_STORAGE: DataTypes = DataTypes(1 << 2);
+    /// IndexedDB databases
+    pub const INDEXED_DB: DataTypes = DataTypes(1 << 3);
+    /// The HTTP cache, and the caches of the Cache API
+    pub const CACHE: DataTypes = DataTypes(1 << 4);
+    /// Service worker registrations
+    pub const SERVICE_WORKERS: DataTypes = DataTypes(1 << 5);
+    /// Every kind of data
+    pub const ALL: DataTypes = DataTypes((1 << 6) - 1);
+
+    /// Whether every kind of data in `other` is in `self`
+    pub fn contains(self, other: DataTypes) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for DataTypes {
+    type Output = DataTypes;
+
+    fn bitor(self, rhs: DataTypes) -> DataTypes {
+        DataTypes(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for DataTypes {
+    fn bitor_assign(&mut self, rhs: DataTypes) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// An error that occurred while reading or changing the data of the webview
+#[derive(Error, Debug)]
+pub enum StorageError {
+    /// The webview failed to read or change the data
+    #[error("Failed to access the webview data: {0}")]
+    Failed(String),
+    /// The url is not valid
+    #[error("Invalid url: {0}")]
+    InvalidUrl(String),
+    /// The webview of this platform doesn't expose its data
+    #[error("The webview data is not available on this platform")]
+    Unsupported,
+    /// The webview of this platform can't clear these kinds of data without clearing others with them
+    #[error("The webview can't clear {0:?} without the rest of its storage on this platform")]
+    UnsupportedTypes(DataTypes),
+}
+
+type StorageResult<T> = tokio::sync::oneshot::Receiver<Result<T, StorageError>>;
+
+/// Wait for the webview to answer, which fails if it is closed first
+async fn answer<T>(receiver: StorageResult<T>) -> Result<T, StorageError> {
+    receiver.await.unwrap_or_else(|_| {
+        Err(StorageError::Failed(
+            "the webview was closed before it answered".to_string(),
+        ))
+    })
+}
+
+/// The cookies of a window. Get it with [`DesktopService::cookies`](crate::DesktopService::cookies).
+///
+/// ```rust, ignore
+/// let cookies = window().cookies();
+/// cookies.set(Cookie::new("session", token, "api.example.com").with_secure(true)).await?;
+/// let sent = cookies.get("https://api.example.com/").await?;
+/// ```
+#[derive(Clone)]
+pub struct Cookies {
+    pub(crate) webview: Rc<WebView>,
+}
+
+impl Cookies {
+    /// The cookies that are sent with requests to `url`
+    pub async fn get(&self, url: &str) -> Result<Vec<Cookie>, StorageError> {
+        let url = url::Url::parse(url).map_err(|_| StorageError::InvalidUrl(url.to_string()))?;
+        answer(platform::get_cookies(&self.webview, &url)?).await
+    }
+
+    /// Add `cookie`, or replace the cookie with the same name, domain and path
+    pub async fn set(&self, cookie: Cookie) -> Result<(), StorageError> {
+        answer(platform::set_cookie(&self.webview, &cookie)?).await
+    }
+
+    /// Delete the cookie with the name, domain and path of `cookie`
+    pub async fn delete(&self, cookie: &Cookie) -> Result<(), StorageError> {
+        answer(platform::delete_cookie(&self.webview, cookie)?).await
+    }
+
+    /// Delete every cookie
+    pub async fn clear(&self) -> Result<(), StorageError> {
+        clear_browsing_data(&self.webview, DataTypes::COOKIES).await
+    }
+}
+
+/// Remove the `types` of data the webview stored for every website
+pub(crate) async fn clear_browsing_data(
+    webview: &WebView,
+    types: DataTypes,
+) -> Result<(), StorageError> {
+    answer(platform::clear(webview, types)?).await
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+    use webview2_com::Microsoft::Web::WebView2::Win32::*;
+    use webview2_com::{ClearBrowsingDataCompletedHandler, GetCookiesCompletedHandler};
+    use windows::core::{ComInterface, HSTRING, PWSTR};
+    use windows::Win32::Foundation::BOOL;
+    use wry::webview::WebviewExtWindows;
+
+    fn error(err: windows::core::Error) -> StorageError {
+        StorageError::Failed(err.message().to_string())
+    }
+
+    unsafe fn cookie_manager(
+        webview: &WebView,
+    ) -> windows::core::Result<ICoreWebView2CookieManager> {
+        webview
+            .controller()
+            .CoreWebView2()?
+            .cast::<ICoreWebView2_2>()?
+            .CookieManager()
+    }
+
+    unsafe fn read_cookie(cookie: &ICoreWebView2Cookie) -> windows::core::Result<Cookie> {
+        let string = |get: &dyn Fn(*mut PWSTR) -> windows::core::Result<()>| {
+            let mut value = PWSTR::null();
+            get(&mut value)?;
+            Ok::<_, windows::core::Error>(webview2_com::take_pwstr(value))
+        };
+        let mut expires = 0.0;
+        let mut is_session = BOOL::default();
+        let mut secure = BOOL::default();
+        let mut http_only = BOOL::default();
+        cookie.Expires(&mut expires)?;
+        cookie.IsSession(&mut is_session)?;
+        cookie.IsSecure(&mut secure)?;
+        cookie.IsHttpOnly(&mut http_only)?;
+
+        Ok(Cookie {
+            name: string(&|value| cookie.Name(value))?,
+            value: string(&|value| cookie.Value(value))?,
+            domain: string(&|value| cookie.Domain(value))?,
+            path: string(&|value| cookie.Path(value))?,
+            // WebView2 measures expiry in seconds since the Unix epoch
+            expires: (!is_session.as_bool())
+                .then(|| UNIX_EPOCH + Duration::from_secs_f64(expires.max(0.0))),
+            secure: secure.as_bool(),
+            http_only: http_only.as_bool(),
+        })
+    }
+
+    pub(super) fn get_cookies(
+        webview: &WebView,
+        url: &url::Url,
+    ) -> Result<StorageResult<Vec<Cookie>>, StorageError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        unsafe {
+            let manager = cookie_manager(webview).map_err(error)?;
+            let handler = GetCookiesCompletedHandler::create(Box::new(move |result, list| {
+                let cookies = result.and_then(|_| {
+                    let mut cookies = Vec::new();
+                    if let Some(list) = list {
+                        let mut count = 0;
+                        list.Count(&mut count)?;
+                        for index in 0..count {
+                            cookies.push(read_cookie(&list.GetValueAtIndex(index)?)?);
+                        }
+                    }
+                    Ok(cookies)
+                });
+                _ = tx.send(cookies.map_err(error));
+                Ok(())
+            }));
+            manager
+                .GetCookies(&HSTRING::from(url.as_str()), &handler)
+                .map_err(error)?;
+        }
+        Ok(rx)
+    }
+
+    pub(super) fn set_cookie(
+        webview: &WebView,
+        cookie: &Cookie,
+    ) -> Result<StorageResult<()>, StorageError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        unsafe {
+            let manager = cookie_manager(webview).map_err(error)?;
+            let created = manager
+                .CreateCookie(
+                    &HSTRING::from(cookie.name.as_str()),
+                    &HSTRING::from(cookie.value.as_str()),
+                    &HSTRING::from(cookie.domain.as_str()),
+                    &HSTRING::from(cookie.path.as_str()),
+                )
+                .map_err(error)?;
+            if let Some(expires) = cookie.expires {
+                let seconds = expires
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs_f64();
+                created.SetExpires(seconds).map_err(error)?;
+            }
+            created
+                .SetIsSecure(BOOL::from(cookie.secure))
+                .and_then(|_| created.SetIsHttpOnly(BOOL::from(cookie.http_only)))
+                .and_then(|_| manager.AddOrUpdateCookie(&created))
+                .map_err(error)?;
+        }
+        // Adding a cookie takes effect immediately
+        _ = tx.send(Ok(()));
+        Ok(rx)
+    }
+
+    pub(super) fn delete_cookie(
+        webview: &WebView,
+        cookie: &Cookie,
+    ) -> Result<StorageResult<()>, StorageError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        unsafe {
+            cookie_manager(webview)
+                .and_then(|manager| {
+                    manager.DeleteCookiesWithDomainAndPath(
+                        &HSTRING::from(cookie.name.as_str()),
+                        &HSTRING::from(cookie.domain.as_str()),
+                        &HSTRING::from(cookie.path.as_str()),
+                    )
+                })
+                .map_err(error)?;
+        }
+        _ = tx.send(Ok(()));
+        Ok(rx)
+    }
+
+    pub(super) fn clear(
+        webview: &WebView,
+        types: DataTypes,
+    ) -> Result<StorageResult<()>, StorageError> {
+        // sessionStorage is only cleared with ALL_DOM_STORAGE, which includes every kind of data in `dom_storage`
+        let dom_storage = DataTypes::SESSION_STORAGE
+            | DataTypes::LOCAL_STORAGE
+            | DataTypes::INDEXED_DB
+            | DataTypes::CACHE
+            | DataTypes::SERVICE_WORKERS;
+        if types.contains(DataTypes::SESSION_STORAGE) && !types.contains(dom_storage) {
+            return Err(StorageError::UnsupportedTypes(DataTypes::SESSION_STORAGE));
+        }
+
+        let mut kinds = COREWEBVIEW2_BROWSING_DATA_KINDS(0);
+        let mapping = [
+            (DataTypes::COOKIES, COREWEBVIEW2_BROWSING_DATA_KINDS_COOKIES),
+            (
+                DataTypes::LOCAL_STORAGE,
+                COREWEBVIEW2_BROWSING_DATA_KINDS_LOCAL_STORAGE,
+            ),
+            (
+                DataTypes::SESSION_STORAGE,
+                COREWEBVIEW2_BROWSING_DATA_KINDS_ALL_DOM_STORAGE,
+            ),
+            (
+                DataTypes::INDEXED_DB,
+                COREWEBVIEW2_BROWSING_DATA_KINDS_INDEXED_DB,
+            ),
+            (
+                DataTypes::CACHE,
+                COREWEBVIEW2_BROWSING_DATA_KINDS_DISK_CACHE
+                    | COREWEBVIEW2_BROWSING_DATA_KINDS_CACHE_STORAGE,
+            ),
+            (
+                DataTypes::SERVICE_WORKERS,
+                COREWEBVIEW2_BROWSING_DATA_KINDS_SERVICE_WORKERS,
+            ),
+        ];
+        for (data_type, kind) in mapping {
+            if types.contains(data_type) {
+                kinds |= kind;
+            }
+        }
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        unsafe {
+            let profile = webview
+                .controller()
+                .CoreWebView2()
+                .and_then(|core| core.cast::<ICoreWebView2_13>())
+                .and_then(|core| core.Profile())
+                .and_then(|profile| profile.cast::<ICoreWebView2Profile2>())
+                .map_err(error)?;
+            let handler = ClearBrowsingDataCompletedHandler::create(Box::new(move |result| {
+                _ = tx.send(result.map_err(error));
+                Ok(())
+            }));
+            profile.ClearBrowsingData(kinds, &handler).map_err(error)?;
+        }
+        Ok(rx)
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "webkitgtk"))]
+mod platform {
+    use super::*;
+    use gtk::glib;
+    use std::time::{Duration, UNIX_EPOCH};
+    use webkit2gtk::soup;
+    use webkit2gtk::{
+        CookieManager, CookieManagerExt, WebViewExt, WebsiteDataManager, WebsiteDataManagerExt,
+        WebsiteDataTypes,
+    };
+    use wry::webview::WebviewExtUnix;
+
+    fn error(err: glib::Error) -> StorageError {
+        StorageError::Failed(err.to_string())
+    }
+
+    fn data_manager(webview: &WebView) -> Result<WebsiteDataManager, StorageError> {
+        webview
+            .webview()
+            .website_data_manager()
+            .ok_or(StorageError::Unsupported)
+    }
+
+    fn cookie_manager(webview: &WebView) -> Result<CookieManager, StorageError> {
+        data_manager(webview)?
+            .cookie_manager()
+            .ok_or(StorageError::Unsupported)
+    }
+
+    fn to_soup(cookie: &Cookie) -> soup::Cookie {
+        // A max age of -1 makes a session cookie
+        let mut soup_cookie = soup::Cookie::new(
+            &cookie.name,
+            &cookie.value,
+            &cookie.domain,
+            &cookie.path,
+            -1,
+        );
+        if let Some(expires) = cookie
+            .expires
+            .and_then(|expires| expires.duration_since(UNIX_EPOCH).ok())
+            .and_then(|expires| glib::DateTime::from_unix_utc(expires.as_secs() as i64).ok())
+        {
+            soup_cookie.set_expires(&expires);
+        }
+        soup_cookie.set_secure(cookie.secure);
+        soup_cookie.set_http_only(cookie.http_only);
+        soup_cookie
+    }
+
+    fn from_soup(cookie: &mut soup::Cookie) -> Cookie {
+        Cookie {
+            name: cookie
+                .name()
+                .map(|name| name.to_string())
+                .unwrap_or_default(),
+            value: cookie
+                .value()
+                .map(|value| value.to_string())
+                .unwrap_or_default(),
+            domain: cookie
+                .domain()
+                .map(|domain| domain.to_string())
+                .unwrap_or_default(),
+            path: cookie
+                .path()
+                .map(|path| path.to_string())
+                .unwrap_or_default(),
+            expires: cookie
+                .expires()
+                .map(|expires| UNIX_EPOCH + Duration::from_secs(expires.to_unix().max(0) as u64)),
+            secure: cookie.is_secure(),
+            http_only: cookie.is_http_only(),
+        }
+    }
+
+    pub(super) fn get_cookies(
+        webview: &WebView,
+        url: &url::Url,
+    ) -> Result<StorageResult<Vec<Cookie>>, StorageError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        cookie_manager(webview)?.cookies(
+            url.as_str(),
+            None::<&gtk::gio::Cancellable>,
+            move |result| {
+                _ = tx.send(
+                    result
+                        .map(|mut cookies| cookies.iter_mut().map(from_soup).collect())
+                        .map_err(error),
+                );
+            },
+        );
+        Ok(rx)
+    }
+
+    pub(super) fn set_cookie(
+        webview: &WebView,
+        cookie: &Cookie,
+    ) -> Result<StorageResult<()>, StorageError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        cookie_manager(webview)?.add_cookie(
+            &mut to_soup(cookie),
+            None::<&gtk::gio::Cancellable>,
+            move |result| {
+                _ = tx.send(result.map_err(error));
+            },
+        );
+        Ok(rx)
+    }
+
+    pub(super) fn delete_cookie(
+        webview: &WebView,
+        cookie: &Cookie,
+    ) -> Result<StorageResult<()>, StorageError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        cookie_manager(webview)?.delete_cookie(
+            &mut to_soup(cookie),
+            None::<&gtk::gio::Cancellable>,
+            move |result| {
+                _ = tx.send(result.map_err(error));
+            },
+        );
+        Ok(rx)
+    }
+
+    pub(super) fn clear(
+        webview: &WebView,
+        types: DataTypes,
+    ) -> Result<StorageResult<()>, StorageError> {
+        let mut data_types = WebsiteDataTypes::empty();
+        let mapping = [
+            (DataTypes::COOKIES, WebsiteDataTypes::COOKIES),
+            (DataTypes::LOCAL_STORAGE, WebsiteDataTypes::LOCAL_STORAGE),
+            (
+                DataTypes::SESSION_STORAGE,
+                WebsiteDataTypes::SESSION_STORAGE,
+            ),
+            (DataTypes::INDEXED_DB, WebsiteDataTypes::INDEXEDDB_DATABASES),
+            (
+                DataTypes::CACHE,
+                WebsiteDataTypes::DISK_CACHE
+                    | WebsiteDataTypes::MEMORY_CACHE
+                    | WebsiteDataTypes::DOM_CACHE,
+            ),
+            (
+                DataTypes::SERVICE_WORKERS,
+                WebsiteDataTypes::SERVICE_WORKER_REGISTRATIONS,
+            ),
+        ];
+        for (data_type, website_data_type) in mapping {
+            if types.contains(data_type) {
+                data_types |= website_data_type;
+            }
+        }
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        // A timespan of 0 removes the data no matter when it was stored
+        data_manager(webview)?.clear(
+            data_types,
+            glib::TimeSpan(0),
+            None::<&gtk::gio::Cancellable>,
+            move |result| {
+                _ = tx.send(result.map_err(error));
+            },
+        );
+        Ok(rx)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use block::ConcreteBlock;
+    use objc::runtime::{Object, BOOL, YES};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::cell::Cell;
+    use std::ffi::{CStr, CString};
+    use std::time::{Duration, UNIX_EPOCH};
+    use wry::webview::WebviewExtMacOS;
+
+    unsafe fn ns_string(value: &str) -> *mut Object {
+        let value = CString::new(value).unwrap_or_default();
+        msg_send![class!(NSString), stringWithUTF8String: value.as_ptr()]
+    }
+
+    unsafe fn string(value: *mut Object) -> String {
+        if value.is_null() {
+            return String::new();
+        }
+        let utf8: *const std::os::raw::c_char = msg_send![value, UTF8String];
+        CStr::from_ptr(utf8).to_string_lossy().into_owned()
+    }
+
+    unsafe fn data_store(webview: &WebView) -> *mut Object {
+        let configuration: *mut Object = msg_send![webview.webview(), configuration];
+        msg_send![configuration, websiteDataStore]
+    }
+
+    unsafe fn cookie_store(webview: &WebView) -> *mut Object {
+        msg_send![data_store(webview), httpCookieStore]
+    }
+
+    unsafe fn read_cookie(cookie: *mut Object) -> Cookie {
+        let expires: *mut Object = msg_send![cookie, expiresDate];
+        let expires = (!expires.is_null()).then(|| {
+            let seconds: f64 = msg_send![expires, timeIntervalSince1970];
+            UNIX_EPOCH + Duration::from_secs_f64(seconds.max(0.0))
+        });
+        let secure: BOOL = msg_send![cookie, isSecure];
+        let http_only: BOOL = msg_send![cookie, isHTTPOnly];
+        Cookie {
+            name: string(msg_send![cookie, name]),
+            value: string(msg_send![cookie, value]),
+            domain: string(msg_send![cookie, domain]),
+            path: string(msg_send![cookie, path]),
+            expires,
+            secure: secure == YES,
+            http_only: http_only == YES,
+        }
+    }
+
+    /// An `NSHTTPCookie` with the properties of `cookie`
+    unsafe fn ns_cookie(cookie: &Cookie) -> Result<*mut Object, StorageError> {
+        let properties: *mut Object = msg_send![class!(NSMutableDictionary), dictionary];
+        let mut set = |key: &str, value: *mut Object| {
+            let _: () = msg_send![properties, setObject: value forKey: ns_string(key)];
+        };
+        set("Name", ns_string(&cookie.name));
+        set("Value", ns_string(&cookie.value));
+        set("Domain", ns_string(&cookie.domain));
+        set("Path", ns_string(&cookie.path));
+        if let Some(expires) = cookie.expires {
+            let seconds = expires
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64();
+            set(
+                "Expires",
+                msg_send![class!(NSDate), dateWithTimeIntervalSince1970: seconds],
+            );
+        }
+        if cookie.secure {
+            set("Secure", ns_string("TRUE"));
+        }
+        if cookie.http_only {
+            set("HttpOnly", ns_string("TRUE"));
+        }
+
+        let ns_cookie: *mut Object =
+            msg_send![class!(NSHTTPCookie), cookieWithProperties: properties];
+        if ns_cookie.is_null() {
+            return Err(StorageError::Failed(format!(
+                "the cookie {:?} is not valid",
+                cookie.name
+            )));
+        }
+        Ok(ns_cookie)
+    }
+
+    /// A completion handler that answers with `Ok(())`
+    fn done(tx: tokio::sync::oneshot::Sender<Result<(), StorageError>>) -> block::RcBlock<(), ()> {
+        let tx = Cell::new(Some(tx));
+        ConcreteBlock::new(move || {
+            if let Some(tx) = tx.take() {
+                _ = tx.send(Ok(()));
+            }
+        })
+        .copy()
+    }
+
+    pub(super) fn get_cookies(
+        webview: &WebView,
+        url: &url::Url,
+    ) -> Result<StorageResult<Vec<Cookie>>, StorageError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = Cell::new(Some(tx));
+        let url = url.clone();
+        // WKHTTPCookieStore can only list every cookie, so they are filtered by the url here
+        let handler = ConcreteBlock::new(move |cookies: *mut Object| {
+            let cookies = unsafe {
+                let count: usize = msg_send![cookies, count];
+                (0..count)
+                    .map(|index| read_cookie(msg_send![cookies, objectAtIndex: index]))
+                    .filter(|cookie| cookie.matches(&url))
+                    .collect()
+            };
+            if let Some(tx) = tx.take() {
+                _ = tx.send(Ok(cookies));
+            }
+        })
+        .copy();
+        unsafe {
+            let _: () = msg_send![cookie_store(webview), getAllCookies: &*handler];
+        }
+        Ok(rx)
+    }
+
+    pub(super) fn set_cookie(
+        webview: &WebView,
+        cookie: &Cookie,
+    ) -> Result<StorageResult<()>, StorageError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        unsafe {
+            let cookie = ns_cookie(cookie)?;
+            let handler = done(tx);
+            let _: () =
+                msg_send![cookie_store(webview), setCookie: cookie completionHandler: &*handler];
+        }
+        Ok(rx)
+    }
+
+    pub(super) fn delete_cookie(
+        webview: &WebView,
+        cookie: &Cookie,
+    ) -> Result<StorageResult<()>, StorageError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        unsafe {
+            let cookie = ns_cookie(cookie)?;
+            let handler = done(tx);
+            let _: () =
+                msg_send![cookie_store(webview), deleteCookie: cookie completionHandler: &*handler];
+        }
+        Ok(rx)
+    }
+
+    pub(super) fn clear(
+        webview: &WebView,
+        types: DataTypes,
+    ) -> Result<StorageResult<()>, StorageError> {
+        // The WKWebsiteDataType constants are strings with the same contents as their names
+        let mapping: [(DataTypes, &[&str]); 6] = [
+            (DataTypes::COOKIES, &["WKWebsiteDataTypeCookies"]),
+            (DataTypes::LOCAL_STORAGE, &["WKWebsiteDataTypeLocalStorage"]),
+            (
+                DataTypes::SESSION_STORAGE,
+                &["WKWebsiteDataTypeSessionStorage"],
+            ),
+            (
+                DataTypes::INDEXED_DB,
+                &["WKWebsiteDataTypeIndexedDBDatabases"],
+            ),
+            (
+                DataTypes::CACHE,
+                &[
+                    "WKWebsiteDataTypeDiskCache",
+                    "WKWebsiteDataTypeMemoryCache",
+                    "WKWebsiteDataTypeFetchCache",
+                ],
+            ),
+            (
+                DataTypes::SERVICE_WORKERS,
+                &["WKWebsiteDataTypeServiceWorkerRegistrations"],
+            ),
+        ];
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        unsafe {
+            let data_types: *mut Object = msg_send![class!(NSMutableSet), set];
+            for (data_type, names) in mapping {
+                if types.contains(data_type) {
+                    for name in names {
+                        let _: () = msg_send![data_types, addObject: ns_string(name)];
+                    }
+                }
+            }
+            let since: *mut Object = msg_send![class!(NSDate), distantPast];
+            let handler = done(tx);
+            let _: () = msg_send![data_store(webview), removeDataOfTypes: data_types modifiedSince: since completionHandler: &*handler];
+        }
+        Ok(rx)
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(target_os = "linux", feature = "webkitgtk"),
+    target_os = "macos"
+)))]
+mod platform {
+    use super::*;
+
+    pub(super) fn get_cookies(
+        _: &WebView,
+        _: &url::Url,
+    ) -> Result<StorageResult<Vec<Cookie>>, StorageError> {
+        Err(StorageError::Unsupported)
+    }
+
+    pub(super) fn set_cookie(_: &WebView, _: &Cookie) -> Result<StorageResult<()>, StorageError> {
+        Err(StorageError::Unsupported)
+    }
+
+    pub(super) fn delete_cookie(
+        _: &WebView,
+        _: &Cookie,
+    ) -> Result<StorageResult<()>, StorageError> {
+        Err(StorageError::Unsupported)
+    }
+
+    pub(super) fn clear(_: &WebView, _: DataTypes) -> Result<StorageResult<()>, StorageError> {
+        Err(StorageError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sent(cookie: &Cookie, url: &str) -> bool {
+        cookie.matches(&url::Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn cookies_match_their_domain_and_subdomains() {
+        let cookie = Cookie::new("session", "1", ".example.com");
+        assert!(sent(&cookie, "http://example.com/"));
+        assert!(sent(&cookie, "http://EXAMPLE.com/"));
+        assert!(sent(&cookie, "http://api.example.com/"));
+        assert!(!sent(&cookie, "http://badexample.com/"));
+        assert!(!sent(&cookie, "http://example.com.evil.com/"));
+        assert!(!sent(&cookie, "http://com/"));
+    }
+
+    #[test]
+    fn cookies_match_their_path_and_the_paths_below_it() {
+        let cookie = Cookie::new("session", "1", "example.com").with_path("/app");
+        assert!(sent(&cookie, "http://example.com/app"));
+        assert!(sent(&cookie, "http://example.com/app/settings"));
+        assert!(!sent(&cookie, "http://example.com/application"));
+        assert!(!sent(&cookie, "http://example.com/"));
+
+        let cookie = cookie.with_path("/app/");
+        assert!(sent(&cookie, "http://example.com/app/settings"));
+        assert!(!sent(&cookie, "http://example.com/app"));
+    }
+
+    #[test]
+    fn secure_cookies_only_match_https() {
+        let cookie = Cookie::new("session", "1", "example.com").with_secure(true);
+        assert!(sent(&cookie, "https://example.com/"));
+        assert!(!sent(&cookie, "http://example.com/"));
+    }
+}
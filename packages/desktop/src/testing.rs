@@ -0,0 +1,318 @@
+//! End to end tests of a desktop app in a headless window.
+//!
+//! The test drives the page with a [`DesktopTestDriver`]: it dispatches mouse and keyboard events to elements, waits
+//! for the app to re-render, and reads the rendered DOM. The events go through the same listeners as events of a real
+//! user.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use dioxus_core::prelude::*;
+use futures_util::FutureExt;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::{Config, DesktopContext, QueryError};
+
+/// An error that fails a test
+#[derive(Error, Debug)]
+pub enum TestError {
+    /// No element in the page matches the selector
+    #[error("No element matches `{0}`")]
+    ElementNotFound(String),
+    /// The page didn't reach the expected state before the timeout
+    #[error("Timed out waiting for {0}")]
+    Timeout(String),
+    /// An assertion about the page failed
+    #[error("Assertion failed: {0}")]
+    AssertionFailed(String),
+    /// The script could not be evaluated in the page
+    #[error("Failed to evaluate a script in the page: {0}")]
+    Query(#[from] QueryError),
+}
+
+/// Drives the page of an app launched with [`launch_test`]
+#[derive(Clone)]
+pub struct DesktopTestDriver {
+    desktop: DesktopContext,
+    timeout: Duration,
+}
+
+impl DesktopTestDriver {
+    /// The window the app runs in
+    pub fn window(&self) -> &DesktopContext {
+        &self.desktop
+    }
+
+    /// Set how long the `wait_for` methods wait before they fail. The default is 5 seconds.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Evaluate `script` in the page, and deserialize the value it returns.
+    ///
+    /// The script is the body of an async function, so it can `await` and must `return` its value.
+    pub async fn eval<T: DeserializeOwned>(&self, script: &str) -> Result<T, TestError> {
+        Ok(self
+            .desktop
+            .query
+            .new_query::<T>(script, self.desktop.webview.clone())
+            .resolve()
+            .await?)
+    }
+
+    /// Evaluate `script` on the first element that matches `selector`, which the script can use as `element`
+    async fn eval_on<T: DeserializeOwned>(
+        &self,
+        selector: &str,
+        script: &str,
+    ) -> Result<T, TestError> {
+        let found: Option<T> = self
+            .eval(&format!(
+                "const element = document.querySelector({});\nif (!element) return null;\n{}",
+                js_string(selector),
+                script
+            ))
+            .await?;
+        found.ok_or_else(|| TestError::ElementNotFound(selector.to_string()))
+    }
+
+    /// Check if an element matches `selector`
+    pub async fn exists(&self, selector: &str) -> Result<bool, TestError> {
+        self.eval(&format!(
+            "return document.querySelector({}) !== null;",
+            js_string(selector)
+        ))
+        .await
+    }
+
+    /// Click the first element that matches `selector`
+    pub async fn click(&self, selector: &str) -> Result<(), TestError> {
+        self.dispatch(
+            selector,
+            r#"new MouseEvent("click", { view: window, bubbles: true, cancelable: true, button: 0 })"#,
+        )
+        .await
+    }
+
+    /// Type `text` into the input that matches `selector`. The value is replaced, and `input` and `change` events are
+    /// dispatched like a user typing it.
+    pub async fn type_text(&self, selector: &str, text: &str) -> Result<(), TestError> {
+        self.eval_on(
+            selector,
+            &format!(
+                r#"element.focus();
+element.value = {};
+element.dispatchEvent(new Event("input", {{ bubbles: true }}));
+element.dispatchEvent(new Event("change", {{ bubbles: true }}));
+return true;"#,
+                js_string(text)
+            ),
+        )
+        .await
+        .map(|_: bool| ())
+    }
+
+    /// Press and release `key`, like `"Enter"` or `"a"`, on the first element that matches `selector`
+    pub async fn press_key(&self, selector: &str, key: &str) -> Result<(), TestError> {
+        for event in ["keydown", "keypress", "keyup"] {
+            self.dispatch(
+                selector,
+                &format!(
+                    r#"new KeyboardEvent("{}", {{ key: {}, bubbles: true, cancelable: true }})"#,
+                    event,
+                    js_string(key)
+                ),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Dispatch the event `event`, a JavaScript expression like `new MouseEvent("dblclick", { bubbles: true })`, to the
+    /// first element that matches `selector`
+    pub async fn dispatch(&self, selector: &str, event: &str) -> Result<(), TestError> {
+        self.eval_on(
+            selector,
+            &format!("element.dispatchEvent({});\nreturn true;", event),
+        )
+        .await
+        .map(|_: bool| ())
+    }
+
+    /// The text of the first element that matches `selector`
+    pub async fn text(&self, selector: &str) -> Result<String, TestError> {
+        self.eval_on(selector, "return element.textContent;").await
+    }
+
+    /// The HTML of the first element that matches `selector`, including the element itself
+    pub async fn html(&self, selector: &str) -> Result<String, TestError> {
+        self.eval_on(selector, "return element.outerHTML;").await
+    }
+
+    /// The HTML of the whole rendered page
+    pub async fn dom(&self) -> Result<String, TestError> {
+        self.eval("return document.documentElement.outerHTML;")
+            .await
+    }
+
+    /// Fail with [`TestError::AssertionFailed`] if the JavaScript expression `condition` is not true
+    pub async fn assert(&self, condition: &str) -> Result<(), TestError> {
+        let passed: bool = self
+            .eval(&format!("return Boolean({});", condition))
+            .await?;
+        if passed {
+            Ok(())
+        } else {
+            Err(TestError::AssertionFailed(condition.to_string()))
+        }
+    }
+
+    /// Wait until the JavaScript expression `condition` is true. The app renders asynchronously, so tests wait for the
+    /// result of an event instead of checking it right away.
+    pub async fn wait_for(&self, condition: &str) -> Result<(), TestError> {
+        let start = Instant::now();
+        loop {
+            let passed: bool = self
+                .eval(&format!("return Boolean({});", condition))
+                .await?;
+            if passed {
+                return Ok(());
+            }
+            if start.elapsed() > self.timeout {
+                return Err(TestError::Timeout(condition.to_string()));
+            }
+            tokio::time::sleep(Duration::from_millis(16)).await;
+        }
+    }
+
+    /// Wait until an element matches `selector`
+    pub async fn wait_for_element(&self, selector: &str) -> Result<(), TestError> {
+        self.wait_for(&format!(
+            "document.querySelector({}) !== null",
+            js_string(selector)
+        ))
+        .await
+    }
+
+    /// Wait until the text of the first element that matches `selector` is `text`
+    pub async fn wait_for_text(&self, selector: &str, text: &str) -> Result<(), TestError> {
+        self.wait_for(&format!(
+            "document.querySelector({})?.textContent === {}",
+            js_string(selector),
+            js_string(text)
+        ))
+        .await
+    }
+}
+
+fn js_string(value: &str) -> String {
+    serde_json::to_string(value).expect("a string always serializes")
+}
+
+type TestFn =
+    Box<dyn FnOnce(DesktopTestDriver) -> Pin<Box<dyn Future<Output = Result<(), TestError>>>>>;
+
+struct TestProps {
+    app: Component,
+    test: RefCell<Option<TestFn>>,
+    timeout: Duration,
+}
+
+/// Launch `app` in a headless window and run `test` against it. The process exits when the test is done, with `0` if
+/// it passed and `1` if it failed, panicked, or didn't finish within a minute. Use [`launch_test_with_timeout`] to
+/// give the test more or less time.
+///
+/// The event loop must run on the main thread and ends the process when it stops, so every test is a separate binary
+/// without the test harness:
+///
+/// ```toml
+/// [[test]]
+/// name = "counter"
+/// path = "tests/counter.rs"
+/// harness = false
+/// ```
+///
+/// ```rust, ignore
+/// fn main() {
+///     dioxus_desktop::launch_test(app, Config::new(), |driver| async move {
+///         driver.click("#increment").await?;
+///         driver.wait_for_text("#count", "1").await?;
+///         Ok(())
+///     });
+/// }
+/// ```
+///
+/// Linux CI machines without a display need a virtual one, like `xvfb-run cargo test`.
+pub fn launch_test<F>(
+    app: Component,
+    cfg: Config,
+    test: impl FnOnce(DesktopTestDriver) -> F + 'static,
+) where
+    F: Future<Output = Result<(), TestError>> + 'static,
+{
+    launch_test_with_timeout(app, cfg, Duration::from_secs(60), test)
+}
+
+/// Launch `app` in a headless window and run `test` against it like [`launch_test`]. The test fails with
+/// [`TestError::Timeout`] if it doesn't finish within `timeout`.
+pub fn launch_test_with_timeout<F>(
+    app: Component,
+    cfg: Config,
+    timeout: Duration,
+    test: impl FnOnce(DesktopTestDriver) -> F + 'static,
+) where
+    F: Future<Output = Result<(), TestError>> + 'static,
+{
+    let props = TestProps {
+        app,
+        test: RefCell::new(Some(Box::new(move |driver| Box::pin(test(driver))))),
+        timeout,
+    };
+    crate::launch_with_props(test_root, props, cfg.with_headless(true));
+}
+
+fn test_root(cx: Scope<TestProps>) -> Element {
+    cx.use_hook(|| {
+        let desktop = cx
+            .consume_context::<DesktopContext>()
+            .expect("the test runs in a desktop window");
+        let test = cx.props.test.borrow_mut().take();
+        if let Some(test) = test {
+            let driver = DesktopTestDriver {
+                desktop: desktop.clone(),
+                timeout: Duration::from_secs(5),
+            };
+            let timeout = cx.props.timeout;
+            cx.spawn_forever(async move {
+                // The page may never load, so the whole test is limited instead of only the `wait_for` methods
+                let test = async move {
+                    tokio::time::timeout(timeout, test(driver))
+                        .await
+                        .unwrap_or_else(|_| {
+                            Err(TestError::Timeout(format!(
+                                "the test to finish within {:?}",
+                                timeout
+                            )))
+                        })
+                };
+                match AssertUnwindSafe(test).catch_unwind().await {
+                    Ok(Ok(())) => desktop.close(),
+                    Ok(Err(err)) => {
+                        eprintln!("The test failed: {}", err);
+                        std::process::exit(1);
+                    }
+                    // The panic message was already printed by the panic hook
+                    Err(_) => std::process::exit(1),
+                }
+            });
+        }
+    });
+
+    #[allow(non_snake_case)]
+    let App = cx.props.app;
+    render! { App {} }
+}
@@ -0,0 +1,153 @@
+//! macOS titlebar customization.
+//!
+//! Apps that draw their own window chrome usually extend the page under a transparent titlebar and move the
+//! traffic-light buttons (close, minimize and zoom) so they line up with the app's own toolbar. AppKit moves the
+//! buttons back whenever the window is resized, so the position is reapplied after every resize.
+
+use objc::runtime::{Object, BOOL, NO, YES};
+use objc::{class, msg_send, sel, sel_impl};
+use wry::application::dpi::LogicalPosition;
+use wry::application::platform::macos::WindowExtMacOS;
+use wry::application::window::Window;
+
+/// How the titlebar of a window is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TitlebarStyle {
+    /// The standard opaque titlebar with the window title
+    #[default]
+    Visible,
+    /// A transparent titlebar without a title. The page extends under the titlebar, so the traffic lights are drawn
+    /// over the page.
+    Transparent,
+    /// Like [`TitlebarStyle::Transparent`], but the titlebar is as tall as a unified toolbar, which leaves more room
+    /// for the app's own controls next to the traffic lights.
+    Unified,
+}
+
+/// The titlebar options of a window
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct TitlebarOptions {
+    pub(crate) style: TitlebarStyle,
+    pub(crate) traffic_light_position: Option<LogicalPosition<f64>>,
+    pub(crate) traffic_lights_hidden: bool,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct NSPoint {
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct NSSize {
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct NSRect {
+    pub(crate) origin: NSPoint,
+    pub(crate) size: NSSize,
+}
+
+const NS_WINDOW_STYLE_MASK_FULL_SIZE_CONTENT_VIEW: usize = 1 << 15;
+const NS_WINDOW_TITLE_VISIBLE: isize = 0;
+const NS_WINDOW_TITLE_HIDDEN: isize = 1;
+const NS_WINDOW_TOOLBAR_STYLE_AUTOMATIC: isize = 0;
+const NS_WINDOW_TOOLBAR_STYLE_UNIFIED: isize = 3;
+
+/// The close, minimize and zoom buttons, in the order they are drawn
+const TRAFFIC_LIGHTS: [usize; 3] = [0, 1, 2];
+
+impl TitlebarOptions {
+    /// Apply every option to the window
+    pub(crate) fn apply(&self, window: &Window) {
+        set_titlebar_style(window, self.style);
+        self.apply_traffic_lights(window);
+    }
+
+    /// Hide or move the traffic lights. This must be called again after the window is resized.
+    pub(crate) fn apply_traffic_lights(&self, window: &Window) {
+        let ns_window = window.ns_window() as *mut Object;
+        unsafe {
+            let buttons: Vec<*mut Object> = TRAFFIC_LIGHTS
+                .iter()
+                .map(|button| -> *mut Object {
+                    msg_send![ns_window, standardWindowButton: *button]
+                })
+                .collect();
+            if buttons.iter().any(|button| button.is_null()) {
+                return;
+            }
+
+            let hidden: BOOL = if self.traffic_lights_hidden { YES } else { NO };
+            for button in &buttons {
+                let _: () = msg_send![*button, setHidden: hidden];
+            }
+
+            let position = match self.traffic_light_position {
+                Some(position) if !self.traffic_lights_hidden => position,
+                _ => return,
+            };
+
+            // Grow the titlebar so the buttons stay vertically centered in it
+            let close_frame: NSRect = msg_send![buttons[0], frame];
+            let minimize_frame: NSRect = msg_send![buttons[1], frame];
+            let superview: *mut Object = msg_send![buttons[0], superview];
+            let container: *mut Object = msg_send![superview, superview];
+            let window_frame: NSRect = msg_send![ns_window, frame];
+            let mut container_frame: NSRect = msg_send![container, frame];
+            container_frame.size.height = close_frame.size.height + position.y * 2.0;
+            container_frame.origin.y = window_frame.size.height - container_frame.size.height;
+            let _: () = msg_send![container, setFrame: container_frame];
+
+            let spacing = minimize_frame.origin.x - close_frame.origin.x;
+            for (index, button) in buttons.iter().enumerate() {
+                let origin = NSPoint {
+                    x: position.x + spacing * index as f64,
+                    y: position.y,
+                };
+                let _: () = msg_send![*button, setFrameOrigin: origin];
+            }
+        }
+    }
+}
+
+/// Change how the titlebar of a window is drawn
+pub(crate) fn set_titlebar_style(window: &Window, style: TitlebarStyle) {
+    let ns_window = window.ns_window() as *mut Object;
+    let custom = style != TitlebarStyle::Visible;
+    unsafe {
+        let mut mask: usize = msg_send![ns_window, styleMask];
+        if custom {
+            mask |= NS_WINDOW_STYLE_MASK_FULL_SIZE_CONTENT_VIEW;
+        } else {
+            mask &= !NS_WINDOW_STYLE_MASK_FULL_SIZE_CONTENT_VIEW;
+        }
+        let _: () = msg_send![ns_window, setStyleMask: mask];
+
+        let transparent: BOOL = if custom { YES } else { NO };
+        let _: () = msg_send![ns_window, setTitlebarAppearsTransparent: transparent];
+        let visibility = if custom {
+            NS_WINDOW_TITLE_HIDDEN
+        } else {
+            NS_WINDOW_TITLE_VISIBLE
+        };
+        let _: () = msg_send![ns_window, setTitleVisibility: visibility];
+
+        // An empty toolbar makes the titlebar as tall as a unified toolbar
+        if style == TitlebarStyle::Unified {
+            let toolbar: *mut Object = msg_send![class!(NSToolbar), new];
+            let _: () = msg_send![ns_window, setToolbar: toolbar];
+            let _: () = msg_send![toolbar, release];
+            let _: () = msg_send![ns_window, setToolbarStyle: NS_WINDOW_TOOLBAR_STYLE_UNIFIED];
+        } else {
+            let nil = std::ptr::null_mut::<Object>();
+            let _: () = msg_send![ns_window, setToolbar: nil];
+            let _: () = msg_send![ns_window, setToolbarStyle: NS_WINDOW_TOOLBAR_STYLE_AUTOMATIC];
+        }
+    }
+}
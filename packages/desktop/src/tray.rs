@@ -0,0 +1,295 @@
+//! A system tray icon with a context menu.
+//!
+//! The tray is shared by every window of the app. Clicks on the icon and its menu items are sent to every window as
+//! [`TrayEvent`]s, which components can listen for with [`use_tray_event`].
+//!
+//! Linux: The tray is drawn with libappindicator, which doesn't report clicks on the icon or show tooltips. Only menu
+//! items send events.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use dioxus_core::ScopeState;
+use wry::application::dpi::PhysicalPosition;
+use wry::application::event::TrayEvent as TaoTrayEvent;
+use wry::application::event_loop::EventLoopWindowTarget;
+use wry::application::menu::{ContextMenu, MenuId, MenuItem, MenuItemAttributes};
+use wry::application::system_tray::{SystemTray, SystemTrayBuilder};
+use wry::application::window::Icon;
+
+use crate::desktop_context::UserWindowEvent;
+use crate::{use_window, DesktopContext};
+
+/// An item in a [`TrayMenu`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrayMenuItem {
+    id: String,
+    title: String,
+    enabled: bool,
+    checked: bool,
+}
+
+impl TrayMenuItem {
+    /// Create an enabled item. `id` is sent in [`TrayEvent::MenuItem`] when the item is clicked.
+    pub fn new(id: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            enabled: true,
+            checked: false,
+        }
+    }
+
+    /// Set if the item can be clicked
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Show a check mark next to the item
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TrayMenuEntry {
+    Item(TrayMenuItem),
+    Separator,
+    Submenu(String, TrayMenu),
+}
+
+/// The context menu of the tray icon.
+///
+/// ```rust, ignore
+/// let menu = TrayMenu::new()
+///     .with_item(TrayMenuItem::new("show", "Show window"))
+///     .with_separator()
+///     .with_item(TrayMenuItem::new("quit", "Quit"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrayMenu {
+    entries: Vec<TrayMenuEntry>,
+}
+
+impl TrayMenu {
+    /// Create an empty menu
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an item to the end of the menu
+    pub fn with_item(mut self, item: TrayMenuItem) -> Self {
+        self.entries.push(TrayMenuEntry::Item(item));
+        self
+    }
+
+    /// Add a separator line to the end of the menu
+    pub fn with_separator(mut self) -> Self {
+        self.entries.push(TrayMenuEntry::Separator);
+        self
+    }
+
+    /// Add a nested menu to the end of the menu
+    pub fn with_submenu(mut self, title: impl Into<String>, menu: TrayMenu) -> Self {
+        self.entries
+            .push(TrayMenuEntry::Submenu(title.into(), menu));
+        self
+    }
+
+    /// Build the native menu and remember the id of every item
+    fn build(&self, items: &mut HashMap<MenuId, String>) -> ContextMenu {
+        let mut menu = ContextMenu::new();
+        for entry in &self.entries {
+            match entry {
+                TrayMenuEntry::Item(item) => {
+                    let native = menu.add_item(
+                        MenuItemAttributes::new(&item.title)
+                            .with_enabled(item.enabled)
+                            .with_selected(item.checked),
+                    );
+                    items.insert(native.id(), item.id.clone());
+                }
+                TrayMenuEntry::Separator => {
+                    menu.add_native_item(MenuItem::Separator);
+                }
+                TrayMenuEntry::Submenu(title, submenu) => {
+                    menu.add_submenu(title, true, submenu.build(items));
+                }
+            }
+        }
+        menu
+    }
+}
+
+/// Options for the tray icon of the app
+#[derive(Debug, Clone)]
+pub struct TrayConfig {
+    icon: Icon,
+    tooltip: Option<String>,
+    menu: Option<TrayMenu>,
+}
+
+impl TrayConfig {
+    /// Show `icon` in the system tray
+    pub fn new(icon: Icon) -> Self {
+        Self {
+            icon,
+            tooltip: None,
+            menu: None,
+        }
+    }
+
+    /// Show a tooltip when the user hovers over the icon
+    pub fn with_tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+
+    /// Show a menu when the user clicks the icon
+    pub fn with_menu(mut self, menu: TrayMenu) -> Self {
+        self.menu = Some(menu);
+        self
+    }
+}
+
+/// Something the user did with the tray icon
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrayEvent {
+    /// The icon was clicked with the left mouse button
+    Click {
+        /// Where the mouse was on the screen
+        position: PhysicalPosition<f64>,
+    },
+    /// The icon was clicked with the right mouse button
+    RightClick {
+        /// Where the mouse was on the screen
+        position: PhysicalPosition<f64>,
+    },
+    /// The icon was double clicked with the left mouse button
+    DoubleClick {
+        /// Where the mouse was on the screen
+        position: PhysicalPosition<f64>,
+    },
+    /// A menu item was clicked. This is the id the item was created with.
+    MenuItem(String),
+}
+
+impl TrayEvent {
+    pub(crate) fn from_tao(event: TaoTrayEvent, position: PhysicalPosition<f64>) -> Option<Self> {
+        match event {
+            TaoTrayEvent::LeftClick => Some(TrayEvent::Click { position }),
+            TaoTrayEvent::RightClick => Some(TrayEvent::RightClick { position }),
+            TaoTrayEvent::DoubleClick => Some(TrayEvent::DoubleClick { position }),
+            _ => None,
+        }
+    }
+}
+
+/// A change to the tray that is applied by the event loop
+#[derive(Debug, Clone)]
+pub(crate) enum TrayUpdate {
+    Menu(TrayMenu),
+    Icon(Icon),
+    Tooltip(String),
+}
+
+/// The tray icon of the app
+pub(crate) struct Tray {
+    tray: SystemTray,
+    items: HashMap<MenuId, String>,
+}
+
+impl Tray {
+    pub(crate) fn new(
+        config: TrayConfig,
+        event_loop: &EventLoopWindowTarget<UserWindowEvent>,
+    ) -> Option<Self> {
+        let mut items = HashMap::new();
+        let menu = config.menu.map(|menu| menu.build(&mut items));
+        let mut builder = SystemTrayBuilder::new(config.icon, menu);
+        if let Some(tooltip) = &config.tooltip {
+            builder = builder.with_tooltip(tooltip);
+        }
+
+        match builder.build(event_loop) {
+            Ok(tray) => Some(Self { tray, items }),
+            Err(err) => {
+                tracing::error!("Failed to create the tray icon: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Get the id of a menu item that was clicked
+    pub(crate) fn menu_item(&self, id: MenuId) -> Option<String> {
+        self.items.get(&id).cloned()
+    }
+
+    pub(crate) fn update(&mut self, update: TrayUpdate) {
+        match update {
+            TrayUpdate::Menu(menu) => {
+                self.items.clear();
+                self.tray.set_menu(&menu.build(&mut self.items));
+            }
+            TrayUpdate::Icon(icon) => self.tray.set_icon(icon),
+            TrayUpdate::Tooltip(tooltip) => self.tray.set_tooltip(&tooltip),
+        }
+    }
+}
+
+pub(crate) type TrayCallback = Rc<RefCell<dyn FnMut(&TrayEvent)>>;
+
+/// The unique identifier of a tray event handler. This can be used to later remove the handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TrayHandlerId(pub(crate) usize);
+
+/// A tray event handler that is scoped to the current component.
+///
+/// This will automatically be removed when the component is unmounted.
+pub struct TrayHandle {
+    desktop: DesktopContext,
+    /// The unique identifier of the handler.
+    pub id: TrayHandlerId,
+}
+
+impl TrayHandle {
+    /// Remove the handler.
+    pub fn remove(&self) {
+        self.desktop.remove_tray_handler(self.id);
+    }
+}
+
+impl Drop for TrayHandle {
+    fn drop(&mut self) {
+        self.remove()
+    }
+}
+
+/// Call `handler` every time the user clicks the tray icon or one of its menu items.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let window = use_window(cx);
+///     use_tray_event(cx, {
+///         to_owned![window];
+///         move |event| match event {
+///             TrayEvent::MenuItem(id) if id == "show" => window.set_visible(true),
+///             TrayEvent::MenuItem(id) if id == "quit" => window.close(),
+///             _ => {}
+///         }
+///     });
+///
+///     render! { "Running in the tray" }
+/// }
+/// ```
+pub fn use_tray_event(cx: &ScopeState, handler: impl FnMut(&TrayEvent) + 'static) -> &TrayHandle {
+    let desktop = use_window(cx);
+    cx.use_hook(move || {
+        let desktop = desktop.clone();
+        let id = desktop.create_tray_handler(handler);
+        TrayHandle { desktop, id }
+    })
+}
@@ -0,0 +1,204 @@
+//! Wayland specific features.
+//!
+//! Windows can be turned into layer shell surfaces (panels, docks, overlays and wallpapers) with the
+//! `wayland-layer-shell` feature. This needs a compositor that implements the `wlr-layer-shell` protocol, like
+//! Sway, Hyprland or KDE Plasma. GNOME does not.
+
+/// Check if the app is running on a Wayland compositor instead of X11.
+pub fn is_wayland() -> bool {
+    // GTK prefers Wayland when it is available unless the backend is forced
+    match std::env::var("GDK_BACKEND") {
+        Ok(backend) if !backend.is_empty() => backend.split(',').next() == Some("wayland"),
+        _ => std::env::var_os("WAYLAND_DISPLAY").is_some(),
+    }
+}
+
+/// The layer a layer shell surface is drawn on
+#[cfg(feature = "wayland-layer-shell")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SurfaceLayer {
+    /// Below every other surface, like a wallpaper
+    Background,
+    /// Below normal windows, like a desktop widget
+    Bottom,
+    /// Above normal windows, like a panel or dock
+    Top,
+    /// Above everything, including fullscreen windows, like a lock screen or notification
+    Overlay,
+}
+
+/// An edge of the output a layer shell surface can be anchored to
+#[cfg(feature = "wayland-layer-shell")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SurfaceEdge {
+    /// The top edge
+    Top,
+    /// The bottom edge
+    Bottom,
+    /// The left edge
+    Left,
+    /// The right edge
+    Right,
+}
+
+/// If a layer shell surface can receive keyboard input
+#[cfg(feature = "wayland-layer-shell")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyboardInteractivity {
+    /// The surface never receives keyboard input
+    None,
+    /// The surface receives all keyboard input while it is mapped, like a lock screen
+    Exclusive,
+    /// The surface receives keyboard input when the user focuses it, like a normal window
+    OnDemand,
+}
+
+/// How much space a layer shell surface reserves for itself
+#[cfg(feature = "wayland-layer-shell")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ExclusiveZone {
+    Fixed(i32),
+    Auto,
+}
+
+/// Options for turning a window into a layer shell surface.
+///
+/// ```rust, ignore
+/// // A 32 pixel tall panel along the top of the screen
+/// let panel = LayerShellConfig::new(SurfaceLayer::Top)
+///     .with_anchor(SurfaceEdge::Top)
+///     .with_anchor(SurfaceEdge::Left)
+///     .with_anchor(SurfaceEdge::Right)
+///     .with_auto_exclusive_zone()
+///     .with_namespace("my-panel");
+///
+/// dioxus_desktop::launch_cfg(
+///     app,
+///     Config::new()
+///         .with_window(WindowBuilder::new().with_inner_size(LogicalSize::new(800, 32)))
+///         .with_layer_shell(panel),
+/// );
+/// ```
+#[cfg(feature = "wayland-layer-shell")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerShellConfig {
+    layer: SurfaceLayer,
+    anchors: Vec<SurfaceEdge>,
+    exclusive_zone: Option<ExclusiveZone>,
+    margins: Vec<(SurfaceEdge, i32)>,
+    keyboard: KeyboardInteractivity,
+    namespace: Option<String>,
+}
+
+#[cfg(feature = "wayland-layer-shell")]
+impl LayerShellConfig {
+    /// Create a surface on `layer` that is not anchored to any edge and doesn't receive keyboard input
+    pub fn new(layer: SurfaceLayer) -> Self {
+        Self {
+            layer,
+            anchors: Vec::new(),
+            exclusive_zone: None,
+            margins: Vec::new(),
+            keyboard: KeyboardInteractivity::None,
+            namespace: None,
+        }
+    }
+
+    /// Anchor the surface to an edge of the output. Anchoring to two opposite edges stretches the surface between
+    /// them.
+    pub fn with_anchor(mut self, edge: SurfaceEdge) -> Self {
+        if !self.anchors.contains(&edge) {
+            self.anchors.push(edge);
+        }
+        self
+    }
+
+    /// Reserve `size` pixels along the anchored edge so other windows are not placed under the surface. `-1` lets
+    /// the surface cover the space other surfaces have reserved.
+    pub fn with_exclusive_zone(mut self, size: i32) -> Self {
+        self.exclusive_zone = Some(ExclusiveZone::Fixed(size));
+        self
+    }
+
+    /// Reserve as much space along the anchored edge as the surface is tall or wide
+    pub fn with_auto_exclusive_zone(mut self) -> Self {
+        self.exclusive_zone = Some(ExclusiveZone::Auto);
+        self
+    }
+
+    /// Keep `margin` pixels between the surface and an edge it is anchored to
+    pub fn with_margin(mut self, edge: SurfaceEdge, margin: i32) -> Self {
+        self.margins.retain(|(e, _)| *e != edge);
+        self.margins.push((edge, margin));
+        self
+    }
+
+    /// Set if the surface can receive keyboard input
+    pub fn with_keyboard_interactivity(mut self, keyboard: KeyboardInteractivity) -> Self {
+        self.keyboard = keyboard;
+        self
+    }
+
+    /// Set the namespace compositors use to identify the surface in their rules, like `"panel"`
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+}
+
+/// Turn a window into a layer shell surface. This must be called before the window is shown.
+#[cfg(feature = "wayland-layer-shell")]
+pub(crate) fn apply_layer_shell(
+    window: &wry::application::window::Window,
+    config: &LayerShellConfig,
+) {
+    use gtk::prelude::WidgetExt;
+    use gtk_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
+    use wry::application::platform::unix::WindowExtUnix;
+
+    if !is_wayland() || !gtk_layer_shell::is_supported() {
+        tracing::warn!(
+            "The compositor doesn't support the layer shell protocol. The window is shown as a normal window."
+        );
+        return;
+    }
+
+    let edge = |edge: SurfaceEdge| match edge {
+        SurfaceEdge::Top => Edge::Top,
+        SurfaceEdge::Bottom => Edge::Bottom,
+        SurfaceEdge::Left => Edge::Left,
+        SurfaceEdge::Right => Edge::Right,
+    };
+
+    let window = window.gtk_window();
+    // The layer shell role can only be given to a surface that hasn't been created yet
+    if window.is_realized() {
+        window.unrealize();
+    }
+    window.init_layer_shell();
+    window.set_layer(match config.layer {
+        SurfaceLayer::Background => Layer::Background,
+        SurfaceLayer::Bottom => Layer::Bottom,
+        SurfaceLayer::Top => Layer::Top,
+        SurfaceLayer::Overlay => Layer::Overlay,
+    });
+    for anchor in &config.anchors {
+        window.set_anchor(edge(*anchor), true);
+    }
+    match config.exclusive_zone {
+        Some(ExclusiveZone::Fixed(size)) => window.set_exclusive_zone(size),
+        Some(ExclusiveZone::Auto) => window.auto_exclusive_zone_enable(),
+        None => {}
+    }
+    for (margin_edge, margin) in &config.margins {
+        window.set_layer_shell_margin(edge(*margin_edge), *margin);
+    }
+    window.set_keyboard_mode(match config.keyboard {
+        KeyboardInteractivity::None => KeyboardMode::None,
+        KeyboardInteractivity::Exclusive => KeyboardMode::Exclusive,
+        KeyboardInteractivity::OnDemand => KeyboardMode::OnDemand,
+    });
+    if let Some(namespace) = &config.namespace {
+        window.set_namespace(namespace);
+    }
+}
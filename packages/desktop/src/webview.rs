@@ -1,30 +1,49 @@
+use crate::custom_protocol::{ProtocolRegistry, DISPATCH_SCHEME};
 use crate::desktop_context::{EditQueue, EventData};
+use crate::download::{self, DownloadEvent, DownloadRequest};
+use crate::navigation;
 use crate::protocol::{self, AssetHandlerRegistry};
+use crate::request_filter::FilterAction;
 use crate::{desktop_context::UserWindowEvent, Config};
+use std::rc::Rc;
 use tao::event_loop::{EventLoopProxy, EventLoopWindowTarget};
 pub use wry;
 pub use wry::application as tao;
 use wry::application::menu::{MenuBar, MenuItem};
 use wry::application::window::Window;
-use wry::http::Response;
 use wry::webview::{WebContext, WebView, WebViewBuilder};
 
 pub(crate) fn build(
     cfg: &mut Config,
     event_loop: &EventLoopWindowTarget<UserWindowEvent>,
     proxy: EventLoopProxy<UserWindowEvent>,
-) -> (WebView, WebContext, AssetHandlerRegistry, EditQueue) {
-    let builder = cfg.window.clone();
-    let window = builder.with_visible(false).build(event_loop).unwrap();
+) -> (
+    WebView,
+    WebContext,
+    AssetHandlerRegistry,
+    ProtocolRegistry,
+    EditQueue,
+) {
+    let mut builder = cfg.window.clone();
     let file_handler = cfg.file_drop_handler.take();
     let custom_head = cfg.custom_head.clone();
     let index_file = cfg.custom_index.clone();
     let root_name = cfg.root_name.clone();
+    let request_filter = cfg.request_filter.clone();
 
-    if cfg.enable_default_menu_bar {
+    // A custom menu is set once the window is created, so its item ids can be stored with the window
+    if cfg.enable_default_menu_bar && cfg.menu.is_none() {
         builder = builder.with_menu(build_default_menu_bar());
     }
 
+    if let Some(theme) = cfg.theme {
+        builder = builder.with_theme(Some(theme));
+    }
+
+    if cfg.content_protected {
+        builder = builder.with_content_protection(true);
+    }
+
     let window = builder.with_visible(false).build(event_loop).unwrap();
 
     // We assume that if the icon is None in cfg, then the user just didnt set it
@@ -39,11 +58,43 @@ pub(crate) fn build(
         ));
     }
 
+    #[cfg(target_os = "windows")]
+    cfg.dwm.apply(&window);
+    #[cfg(target_os = "macos")]
+    cfg.titlebar.apply(&window);
+
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    if let Some(window_state) = &mut cfg.window_state {
+        window_state.restore(&window);
+    }
+
+    // The window is still hidden, so it can be given the layer shell role before it is shown
+    #[cfg(all(target_os = "linux", feature = "wayland-layer-shell"))]
+    if let Some(layer_shell) = &cfg.layer_shell {
+        crate::wayland::apply_layer_shell(&window, layer_shell);
+    }
+
     let mut web_context = WebContext::new(cfg.data_dir.clone());
     let edit_queue = EditQueue::default();
-    let headless = !cfg.window.window.visible;
+    let headless = cfg.headless || !cfg.window.window.visible;
+    let window_id = window.id();
+    let navigation_proxy = proxy.clone();
+    let download_proxy = proxy.clone();
+    let drop_proxy = proxy.clone();
+    let crash_proxy = proxy.clone();
     let asset_handlers = AssetHandlerRegistry::new();
     let asset_handlers_ref = asset_handlers.clone();
+    let protocol_filter = request_filter.clone();
+    let protocol_edit_queue = edit_queue.clone();
+    let asset_mounts: std::sync::Arc<[crate::AssetMount]> = cfg.asset_mounts.clone().into();
+    let compression = std::sync::Arc::new(cfg.compression.clone());
+    let cache_policy = std::sync::Arc::new(cfg.cache_policy.clone());
+    let response_headers = std::sync::Arc::new(cfg.response_headers.clone());
+    #[cfg(feature = "asset-cache")]
+    let asset_cache = cfg.asset_cache.clone().map(|mut cache| {
+        cache.resolve_directory(cfg.data_dir.as_deref());
+        std::sync::Arc::new(cache)
+    });
 
     let mut webview = WebViewBuilder::new(window)
         .unwrap()
@@ -61,31 +112,207 @@ pub(crate) fn build(
             let index_file = index_file.clone();
             let root_name = root_name.clone();
             let asset_handlers_ref = asset_handlers_ref.clone();
+            let protocol_filter = protocol_filter.clone();
+            let edit_queue = protocol_edit_queue.clone();
+            let asset_mounts = asset_mounts.clone();
+            let compression = compression.clone();
+            let cache_policy = cache_policy.clone();
+            let response_headers = response_headers.clone();
+            #[cfg(feature = "asset-cache")]
+            let asset_cache = asset_cache.clone();
             tokio::spawn(async move {
-                let response_res = protocol::desktop_handler(
+                protocol::desktop_handler(
                     request,
-                    custom_head.clone(),
-                    index_file.clone(),
+                    responder,
+                    custom_head,
+                    index_file,
                     &root_name,
                     &asset_handlers_ref,
+                    &asset_mounts,
+                    &compression,
+                    &cache_policy,
+                    &response_headers,
+                    &protocol_filter,
+                    &edit_queue,
+                    headless,
+                    #[cfg(feature = "asset-cache")]
+                    asset_cache.as_deref(),
                 )
                 .await;
-                responder.respond(response);
             });
         })
-        .with_file_drop_handler(move |window, evet| {
+        .with_file_drop_handler(move |window, event| {
+            // The drop becomes drag events on the element under the cursor
+            _ = drop_proxy.send_event(UserWindowEvent(
+                EventData::FileDrop(event.clone()),
+                window.id(),
+            ));
+            // The webview would open the file if the drop went through to the page
             file_handler
                 .as_ref()
-                .map(|handler| handler(window, evet))
-                .unwrap_or_default()
+                .map(|handler| handler(window, event))
+                .unwrap_or(true)
         })
+        .with_incognito(cfg.ephemeral)
         .with_web_context(&mut web_context);
 
+    if let Some(user_agent) = &cfg.user_agent {
+        webview = webview.with_user_agent(user_agent);
+    }
+
+    if let Some(autoplay) = cfg.engine.autoplay() {
+        webview = webview.with_autoplay(autoplay);
+    }
+
+    #[cfg(target_os = "macos")]
+    if let Some(proxy) = cfg.proxy.as_ref().and_then(crate::ProxyConfig::wry_config) {
+        webview = webview.with_proxy_config(proxy);
+    }
+
     #[cfg(windows)]
     {
         // Windows has a platform specific settings to disable the browser shortcut keys
         use wry::webview::WebViewBuilderExtWindows;
         webview = webview.with_browser_accelerator_keys(false);
+
+        let proxy_args = cfg
+            .proxy
+            .as_ref()
+            .map(crate::ProxyConfig::browser_args)
+            .unwrap_or_default();
+        if let Some(args) = cfg
+            .engine
+            .browser_args(cfg.hardware_acceleration, &proxy_args)
+        {
+            webview = webview.with_additional_browser_args(&args);
+        }
+
+        // WebView2 doesn't follow the theme of the window, so a forced theme has to be set on the webview too
+        if let Some(theme) = cfg.theme {
+            webview = webview.with_theme(match theme {
+                tao::window::Theme::Dark => wry::webview::Theme::Dark,
+                _ => wry::webview::Theme::Light,
+            });
+        }
+    }
+
+    if !request_filter.is_empty() {
+        webview = webview.with_initialization_script(&request_filter.initialization_script());
+    }
+
+    let navigation_handler = cfg.navigation_handler.take();
+    if !request_filter.is_empty() || navigation_handler.is_some() {
+        webview = webview.with_navigation_handler(move |url| {
+            match request_filter.action_for(&url) {
+                Some(FilterAction::Block) => return false,
+                Some(FilterAction::Redirect(to)) => {
+                    // The webview can't change the target of a navigation, so cancel it and load the new url instead
+                    _ = navigation_proxy
+                        .send_event(UserWindowEvent(EventData::Navigate(to), window_id));
+                    return false;
+                }
+                _ => {}
+            }
+            match &navigation_handler {
+                Some(handler) => navigation::apply(handler(&url), &url),
+                None => true,
+            }
+        });
+    }
+
+    if let Some(handler) = cfg.new_window_handler.take() {
+        webview =
+            webview.with_new_window_req_handler(move |url| navigation::apply(handler(&url), &url));
+    }
+
+    let download_directory = cfg.download_directory.clone();
+    let mut download_started = cfg.download_started_handler.take();
+    let started_proxy = download_proxy.clone();
+    webview = webview.with_download_started_handler(move |url, destination| {
+        // Some webviews don't pick a path, and then the file goes in the temporary directory
+        let directory = download_directory.clone().or_else(|| {
+            destination
+                .parent()
+                .filter(|directory| !directory.as_os_str().is_empty())
+                .map(|directory| directory.to_path_buf())
+        });
+        let mut request = DownloadRequest {
+            destination: download::default_destination(&url, directory.as_deref()),
+            url,
+        };
+        let allowed = match &mut download_started {
+            Some(handler) => handler(&mut request),
+            None if download_directory.is_some() => true,
+            // The page doesn't get to write files the user didn't ask for
+            None => match download::prompt_destination(&request.destination) {
+                Some(path) => {
+                    request.destination = path;
+                    true
+                }
+                None => false,
+            },
+        };
+        if allowed {
+            *destination = request.destination.clone();
+            let event = DownloadEvent::Started {
+                url: request.url,
+                destination: request.destination,
+            };
+            _ = started_proxy.send_event(UserWindowEvent(EventData::Download(event), window_id));
+        }
+        allowed
+    });
+
+    let download_completed = cfg.download_completed_handler.take();
+    webview = webview.with_download_completed_handler(move |url, path, success| {
+        if let Some(handler) = &download_completed {
+            handler(&url, path.as_deref(), success);
+        }
+        let event = DownloadEvent::Completed { url, path, success };
+        _ = download_proxy.send_event(UserWindowEvent(EventData::Download(event), window_id));
+    });
+
+    webview = webview.with_initialization_script(crate::ipc_channel::CHANNEL_SCRIPT);
+    webview = webview.with_initialization_script(crate::invoke::INVOKE_SCRIPT);
+    webview = webview.with_initialization_script(crate::sandbox::SANDBOX_SCRIPT);
+    webview = webview.with_initialization_script(crate::event_listener::EVENT_LISTENER_SCRIPT);
+    webview = webview.with_initialization_script(crate::cursor::POINTER_SCRIPT);
+    webview = webview.with_initialization_script(crate::shortcut::ACCELERATOR_SCRIPT);
+    webview = webview.with_initialization_script(crate::file_drop::FILE_DROP_SCRIPT);
+    webview = webview.with_initialization_script(crate::polyfill::POLYFILL_SCRIPT);
+    if let Some(script) = cfg.secure_input.script() {
+        webview = webview.with_initialization_script(&script);
+    }
+    if let Some(script) = cfg.console_bridge.script() {
+        webview = webview.with_initialization_script(&script);
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        webview = webview.with_initialization_script(&crate::autofill::script(cfg.autofill));
+    }
+
+    if let Some(script) = cfg.edit_transport.script() {
+        // The interpreter reads this when it starts, to ask for edits over IPC instead of the custom protocol
+        webview = webview.with_initialization_script(script);
+    }
+
+    if cfg.sanitize_inner_html {
+        // The interpreter checks this flag every time it sets dangerous_inner_html
+        webview = webview.with_initialization_script("window.__dioxusSanitizeInnerHtml = true;");
+    }
+
+    if let Some(shortcut) = &cfg.devtools_panel_shortcut {
+        webview = webview.with_initialization_script(&crate::devtools::shortcut_script(
+            shortcut,
+            "devtools_toggle",
+        ));
+    }
+
+    if let Some(shortcut) = &cfg.command_palette_shortcut {
+        webview = webview.with_initialization_script(&crate::devtools::shortcut_script(
+            shortcut,
+            "command_palette_toggle",
+        ));
     }
 
     if let Some(color) = cfg.background_color {
@@ -96,18 +323,19 @@ pub(crate) fn build(
     // let mut web_context = WebContext::new(cfg.data_dir.clone());
     // .with_web_context(&mut web_context);
 
+    // The protocols go through the registry of the window, so they can be replaced and removed while it is open
+    let protocols = ProtocolRegistry::default();
     for (name, handler) in cfg.protocols.drain(..) {
-        webview = webview.with_custom_protocol(name, move |r| match handler(&r) {
-            Ok(response) => response,
-            Err(err) => {
-                tracing::error!("Error: {}", err);
-                Response::builder()
-                    .status(500)
-                    .body(err.to_string().into_bytes().into())
-                    .unwrap()
-            }
+        protocols.insert_native(name.clone(), Rc::from(handler));
+        let protocols = protocols.clone();
+        webview = webview.with_custom_protocol(name.clone(), move |request| {
+            protocols.handle(&name, request)
         })
     }
+    let dispatch_protocols = protocols.clone();
+    webview = webview.with_custom_protocol(DISPATCH_SCHEME.to_string(), move |request| {
+        dispatch_protocols.dispatch(request)
+    });
 
     if cfg.disable_context_menu {
         // in release mode, we don't want to show the dev tool or reload menus
@@ -129,7 +357,15 @@ pub(crate) fn build(
         webview = webview.with_devtools(true);
     }
 
-    (webview.build().unwrap(), web_context, asset_handlers, edit_queue)
+    let webview = webview.build().unwrap();
+    cfg.engine.apply(&webview);
+    crate::crash::watch(&webview, crash_proxy, window_id);
+    #[cfg(all(target_os = "linux", feature = "webkitgtk"))]
+    if let Some(proxy) = &cfg.proxy {
+        proxy.apply(&webview);
+    }
+
+    (webview, web_context, asset_handlers, protocols, edit_queue)
 }
 
 /// Builds a standard menu bar depending on the users platform. It may be used as a starting point
@@ -0,0 +1,107 @@
+//! Hooks that re-render the component when the window is resized, moved, changes its scale factor or its theme.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use dioxus_core::ScopeState;
+use wry::application::dpi::{PhysicalPosition, PhysicalSize};
+use wry::application::event::{Event, WindowEvent};
+use wry::application::window::{Theme, Window};
+
+use crate::{use_window, use_wry_event_handler};
+
+/// Get the size of the content of the window, in physical pixels. The component re-renders when the window is
+/// resized.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let size = use_window_size(cx);
+///
+///     render! { "The window is {size.width}x{size.height}" }
+/// }
+/// ```
+pub fn use_window_size(cx: &ScopeState) -> PhysicalSize<u32> {
+    use_window_metric(
+        cx,
+        |window| window.inner_size(),
+        |event| {
+            matches!(
+                event,
+                WindowEvent::Resized(_) | WindowEvent::ScaleFactorChanged { .. }
+            )
+        },
+    )
+}
+
+/// Get the position of the top left corner of the window on the desktop, in physical pixels. The component
+/// re-renders when the window is moved.
+///
+/// This is `None` on platforms where windows can't know where they are, like Wayland.
+pub fn use_window_position(cx: &ScopeState) -> Option<PhysicalPosition<i32>> {
+    use_window_metric(
+        cx,
+        |window| window.outer_position().ok(),
+        |event| matches!(event, WindowEvent::Moved(_)),
+    )
+}
+
+/// Get the number of physical pixels per logical pixel of the monitor the window is on. The component re-renders
+/// when the window moves to a monitor with a different scale factor, or the scale factor of the monitor changes.
+pub fn use_scale_factor(cx: &ScopeState) -> f64 {
+    use_window_metric(
+        cx,
+        |window| window.scale_factor(),
+        |event| matches!(event, WindowEvent::ScaleFactorChanged { .. }),
+    )
+}
+
+/// Get the theme of the window, which follows the dark or light theme of the system unless it was forced with
+/// [`Config::with_theme`](crate::Config::with_theme). The component re-renders when the theme changes.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let theme = use_system_theme(cx);
+///     let background = if theme == Theme::Dark { "black" } else { "white" };
+///
+///     render! { div { background: background, "Hello" } }
+/// }
+/// ```
+pub fn use_system_theme(cx: &ScopeState) -> Theme {
+    use_window_metric(
+        cx,
+        |window| window.theme(),
+        |event| matches!(event, WindowEvent::ThemeChanged(_)),
+    )
+}
+
+/// Read a value of the window, and read it again after every event that can change it
+fn use_window_metric<T: Copy + PartialEq + 'static>(
+    cx: &ScopeState,
+    read: fn(&Window) -> T,
+    changes_on: fn(&WindowEvent) -> bool,
+) -> T {
+    let desktop = use_window(cx);
+    let value = cx.use_hook(|| Rc::new(Cell::new(read(desktop.webview.window()))));
+
+    use_wry_event_handler(cx, {
+        let value = value.clone();
+        let desktop = Rc::downgrade(desktop);
+        let update = cx.schedule_update();
+        move |event, _| {
+            let Event::WindowEvent { event, .. } = event else {
+                return;
+            };
+            let Some(desktop) = desktop.upgrade() else {
+                return;
+            };
+            if changes_on(event) {
+                let new_value = read(desktop.webview.window());
+                if value.replace(new_value) != new_value {
+                    update();
+                }
+            }
+        }
+    });
+
+    value.get()
+}
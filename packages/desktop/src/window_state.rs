@@ -0,0 +1,186 @@
+//! Save the size, position and maximized state of a window, and restore it the next time the app starts.
+//!
+//! Turn it on with [`Config::with_window_state_persistence`](crate::Config::with_window_state_persistence). The state
+//! is kept in memory while the window is moved or resized, and written to the file at most once every
+//! [`SAVE_INTERVAL`] and when the window closes.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use wry::application::dpi::{PhysicalPosition, PhysicalSize};
+use wry::application::monitor::MonitorHandle;
+use wry::application::window::Window;
+
+/// How often the state is written while the window is moved or resized
+const SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How much of the window must be on a monitor to restore its position, so the titlebar can still be grabbed
+const MIN_VISIBLE: i32 = 64;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct WindowState {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    maximized: bool,
+    monitor: Option<String>,
+}
+
+impl WindowState {
+    /// Check if enough of the window is on `monitor` to grab it
+    fn is_visible_on(&self, monitor: &MonitorHandle) -> bool {
+        let position = monitor.position();
+        let size = monitor.size();
+
+        let left = self.x.max(position.x);
+        let right = (self.x + self.width as i32).min(position.x + size.width as i32);
+        let top = self.y.max(position.y);
+        let bottom = (self.y + self.height as i32).min(position.y + size.height as i32);
+
+        right - left >= MIN_VISIBLE && bottom - top >= MIN_VISIBLE
+    }
+}
+
+/// Keeps the state of a window in sync with the file it is saved in
+pub(crate) struct WindowStatePersistence {
+    path: PathBuf,
+    state: Option<WindowState>,
+    dirty: bool,
+    last_saved: Option<Instant>,
+}
+
+impl WindowStatePersistence {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            state: None,
+            dirty: false,
+            last_saved: None,
+        }
+    }
+
+    /// Move and resize the window to the state saved in the last launch. The window should still be hidden.
+    pub(crate) fn restore(&mut self, window: &Window) {
+        let Some(state) = load(&self.path) else {
+            return;
+        };
+
+        let monitors: Vec<_> = window.available_monitors().collect();
+        // Prefer the monitor the window was on, but it may have been unplugged or rearranged since
+        let monitor = monitors
+            .iter()
+            .filter(|monitor| state.monitor.is_some() && monitor.name() == state.monitor)
+            .chain(monitors.iter())
+            .find(|monitor| state.is_visible_on(monitor));
+
+        let mut size = PhysicalSize::new(state.width, state.height);
+        match monitor {
+            Some(_) => window.set_outer_position(PhysicalPosition::new(state.x, state.y)),
+            // The window would be off screen, so let the system place it, and keep it smaller than the monitor
+            None => {
+                if let Some(monitor) = window
+                    .primary_monitor()
+                    .or_else(|| monitors.first().cloned())
+                {
+                    size.width = size.width.min(monitor.size().width);
+                    size.height = size.height.min(monitor.size().height);
+                }
+            }
+        }
+        window.set_inner_size(size);
+        if state.maximized {
+            window.set_maximized(true);
+        }
+
+        self.state = Some(state);
+    }
+
+    /// Read the state of the window after it was moved or resized
+    pub(crate) fn update(&mut self, window: &Window) {
+        if window.is_minimized() {
+            return;
+        }
+
+        let maximized = window.is_maximized();
+        let state = match (&self.state, maximized) {
+            // Keep the size and position from before the window was maximized, to restore them when it is unmaximized
+            (Some(state), true) => WindowState {
+                maximized,
+                ..state.clone()
+            },
+            _ => {
+                let Ok(position) = window.outer_position() else {
+                    return;
+                };
+                let size = window.inner_size();
+                WindowState {
+                    x: position.x,
+                    y: position.y,
+                    width: size.width,
+                    height: size.height,
+                    maximized,
+                    monitor: window.current_monitor().and_then(|monitor| monitor.name()),
+                }
+            }
+        };
+
+        if self.state.as_ref() != Some(&state) {
+            self.state = Some(state);
+            self.dirty = true;
+        }
+
+        if self
+            .last_saved
+            .map_or(true, |saved| saved.elapsed() >= SAVE_INTERVAL)
+        {
+            self.save();
+        }
+    }
+
+    /// Write the state to the file if it changed
+    pub(crate) fn save(&mut self) {
+        let Some(state) = self.state.as_ref().filter(|_| self.dirty) else {
+            return;
+        };
+
+        if let Err(err) = store(&self.path, state) {
+            tracing::error!(
+                "Failed to save the window state to {}: {}",
+                self.path.display(),
+                err
+            );
+        }
+        self.dirty = false;
+        self.last_saved = Some(Instant::now());
+    }
+}
+
+impl Drop for WindowStatePersistence {
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+fn load(path: &Path) -> Option<WindowState> {
+    let contents = std::fs::read(path).ok()?;
+    match serde_json::from_slice(&contents) {
+        Ok(state) => Some(state),
+        Err(err) => {
+            tracing::warn!(
+                "Ignoring the invalid window state in {}: {}",
+                path.display(),
+                err
+            );
+            None
+        }
+    }
+}
+
+fn store(path: &Path, state: &WindowState) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(state)?)
+}
@@ -0,0 +1,76 @@
+use dioxus_desktop::{FilterAction, RequestFilter};
+
+#[test]
+fn first_matching_rule_wins() {
+    let filter = RequestFilter::new()
+        .allow("https://cdn.example.com/*")
+        .block("https://*.example.com/*");
+
+    assert_eq!(
+        filter.action_for("https://cdn.example.com/app.js"),
+        Some(FilterAction::Allow)
+    );
+    assert_eq!(
+        filter.action_for("https://ads.example.com/ad.js"),
+        Some(FilterAction::Block)
+    );
+    assert_eq!(filter.action_for("https://dioxuslabs.com/"), None);
+}
+
+#[test]
+fn redirects_are_followed_through_the_rules() {
+    let filter = RequestFilter::new()
+        .redirect("https://old.example.com/*", "https://new.example.com/")
+        .redirect("https://new.example.com/*", "https://newer.example.com/")
+        .redirect("https://tracker.example.com/*", "https://ads.example.com/")
+        .block("https://ads.example.com/*");
+
+    assert_eq!(
+        filter.action_for("https://old.example.com/page"),
+        Some(FilterAction::Redirect(
+            "https://newer.example.com/".to_string()
+        ))
+    );
+    // A redirect to a blocked url is blocked
+    assert_eq!(
+        filter.action_for("https://tracker.example.com/pixel.gif"),
+        Some(FilterAction::Block)
+    );
+}
+
+#[test]
+fn redirect_loops_are_blocked() {
+    // A rule that matches its own target
+    let filter = RequestFilter::new().redirect("https://example.com/*", "https://example.com/home");
+    assert_eq!(
+        filter.action_for("https://example.com/page"),
+        Some(FilterAction::Block)
+    );
+
+    // Two rules that redirect to each other
+    let filter = RequestFilter::new()
+        .redirect("https://a.example.com/*", "https://b.example.com/")
+        .redirect("https://b.example.com/*", "https://a.example.com/");
+    assert_eq!(
+        filter.action_for("https://a.example.com/"),
+        Some(FilterAction::Block)
+    );
+
+    // A chain that never comes back to the same url, but doesn't end either
+    let mut filter = RequestFilter::new();
+    for hop in 0..20 {
+        filter = filter.redirect(
+            format!("https://example.com/{hop}"),
+            format!("https://example.com/{}", hop + 1),
+        );
+    }
+    assert_eq!(
+        filter.action_for("https://example.com/0"),
+        Some(FilterAction::Block)
+    );
+    // A short chain of the same rules ends normally
+    assert_eq!(
+        filter.action_for("https://example.com/18"),
+        Some(FilterAction::Redirect("https://example.com/20".to_string()))
+    );
+}
@@ -12,6 +12,7 @@ use dioxus_html::{
 
 use dioxus_native_core::NodeId;
 use plasmo::query::{ElementRef, Query};
+use plasmo::Layout;
 
 pub(crate) fn find_mount_events(mutations: &Mutations) -> Vec<ElementId> {
     let mut mount_events = Vec::new();
@@ -46,7 +47,10 @@ pub(crate) fn create_mounted_events(
     }
 }
 
-struct TuiElement {
+/// A mounted element passed to onmounted events
+///
+/// Get it from the event with [`MountedData::downcast`].
+pub struct TuiElement {
     query: Query,
     id: NodeId,
 }
@@ -55,6 +59,16 @@ impl TuiElement {
     pub(crate) fn element(&self) -> ElementRef {
         self.query.get(self.id)
     }
+
+    /// The id of the node in the native dom
+    pub fn node_id(&self) -> NodeId {
+        self.id
+    }
+
+    /// The layout of the node, relative to the terminal. Returns `None` if the node has not been laid out yet.
+    pub fn layout(&self) -> Option<Layout> {
+        self.element().layout()
+    }
 }
 
 impl RenderedElementBacking for TuiElement {
@@ -15,6 +15,7 @@ use dioxus_core::{Component, ElementId, VirtualDom};
 use dioxus_native_core::dioxus::{DioxusState, NodeImmutableDioxusExt};
 use dioxus_native_core::prelude::*;
 
+pub use element::TuiElement;
 use element::{create_mounted_events, find_mount_events};
 pub use plasmo::{query::Query, Config, RenderingMode, Size, TuiContext};
 use plasmo::{render, Driver};
@@ -62,6 +63,8 @@ pub fn launch_cfg_with_props<Props: 'static>(app: Component<Props>, props: Props
                     .map(|id| (*dbg!(id), dioxus_state.element_to_node_id(*id))),
             );
         }
+        // The first frame is in the real dom but not drawn yet
+        vdom.flush_layout_effects();
 
         DioxusRenderer {
             vdom,
@@ -113,6 +116,7 @@ impl Driver for DioxusRenderer {
                 );
             }
         }
+        self.vdom.flush_layout_effects();
     }
 
     fn handle_event(
@@ -68,8 +68,8 @@ use std::sync::Arc;
 use std::sync::RwLock;
 
 use crate::{
-    prelude::*, render::SSRState, serve_config::ServeConfig, server_context::DioxusServerContext,
-    server_fn::DioxusServerFnRegistry,
+    compression::encode_page, prelude::*, render::SSRState, serve_config::ServeConfig,
+    server_context::DioxusServerContext, server_fn::DioxusServerFnRegistry,
 };
 
 /// A extension trait with utilities for integrating Dioxus with your Axum router.
@@ -239,7 +239,7 @@ where
         let mut router = self;
         for server_fn_path in DioxusServerFnRegistry::paths_registered() {
             let func = DioxusServerFnRegistry::get(server_fn_path).unwrap();
-            let full_route = format!("{server_fn_route}/{server_fn_path}");
+            let full_route = super::server_fn_full_route(server_fn_route, server_fn_path);
             match func.encoding() {
                 Encoding::Url | Encoding::Cbor => {
                     router = router.route(&full_route, post(handler(func)));
@@ -358,16 +358,6 @@ where
     }
 }
 
-fn apply_request_parts_to_response<B>(
-    headers: hyper::header::HeaderMap,
-    response: &mut axum::response::Response<B>,
-) {
-    let mut_headers = response.headers_mut();
-    for (key, value) in headers.iter() {
-        mut_headers.insert(key, value.clone());
-    }
-}
-
 /// SSR renderer handler for Axum with added context injection.
 ///
 /// # Example
@@ -424,18 +414,21 @@ pub async fn render_handler_with_context<
 ) -> impl IntoResponse {
     let (parts, _) = request.into_parts();
     let url = parts.uri.path_and_query().unwrap().to_string();
+    let request_headers = parts.headers.clone();
     let parts: Arc<RwLock<http::request::Parts>> = Arc::new(RwLock::new(parts.into()));
     let mut server_context = DioxusServerContext::new(parts.clone());
     inject_context(&mut server_context);
 
     match ssr_state.render(url, &cfg, &server_context).await {
         Ok(rendered) => {
-            let crate::render::RenderResponse { html, freshness } = rendered;
-            let mut response = axum::response::Html::from(html).into_response();
-            freshness.write(response.headers_mut());
-            let headers = server_context.response_parts().unwrap().headers.clone();
-            apply_request_parts_to_response(headers, &mut response);
-            response
+            let encoded = encode_page(&request_headers, rendered, cfg.encoding, &server_context);
+            let mut builder = Response::builder().status(encoded.status);
+            if let Some(headers) = builder.headers_mut() {
+                encoded.write_headers(headers);
+            }
+            builder
+                .body(body::boxed(body::Full::from(encoded.body)))
+                .unwrap()
         }
         Err(e) => {
             tracing::error!("Failed to render page: {}", e);
@@ -14,6 +14,8 @@
 pub mod axum_adapter;
 #[cfg(feature = "salvo")]
 pub mod salvo_adapter;
+#[cfg(feature = "serverless")]
+pub mod serverless_adapter;
 #[cfg(feature = "warp")]
 pub mod warp_adapter;
 
@@ -22,10 +24,16 @@ use server_fn::{Encoding, Payload};
 use std::sync::{Arc, RwLock};
 
 use crate::{
+    compression::{encode_body, EncodingOptions},
     layer::{BoxedService, Service},
     prelude::{DioxusServerContext, ProvideServerContext},
 };
 
+/// The route a server function is mounted at when server functions are registered at `server_fn_route`
+pub(crate) fn server_fn_full_route(server_fn_route: &str, server_fn_path: &str) -> String {
+    format!("{server_fn_route}/{server_fn_path}")
+}
+
 /// Create a server function handler with the given server context and server function.
 pub fn server_fn_service(
     context: DioxusServerContext,
@@ -82,33 +90,42 @@ impl Service for ServerFnHandler {
         } = self.clone();
         Box::pin(async move {
             let query = req.uri().query().unwrap_or_default().as_bytes().to_vec();
-            let (parts, body) = req.into_parts();
-            let body = hyper::body::to_bytes(body).await?.to_vec();
-            let headers = &parts.headers;
-            let accept_header = headers.get("Accept").cloned();
+            let (mut parts, body) = req.into_parts();
+            let body = match crate::multipart::multipart_boundary(&parts.headers) {
+                // File uploads are streamed into the server function, so only the arguments are read up front
+                Some(boundary) => {
+                    crate::multipart::read_multipart_args(
+                        &server_context,
+                        &mut parts,
+                        body,
+                        boundary,
+                    )
+                    .await?
+                }
+                None => hyper::body::to_bytes(body).await?.to_vec(),
+            };
+            let request_headers = parts.headers.clone();
+            let accept_header = request_headers.get("Accept").cloned();
             let parts = Arc::new(RwLock::new(parts));
 
-            // Because the future returned by `server_fn_handler` is `Send`, and the future returned by this function must be send, we need to spawn a new runtime
-            let pool = get_local_pool();
-            let result = pool
-                .spawn_pinned({
-                    let function = function.clone();
-                    let mut server_context = server_context.clone();
-                    server_context.parts = parts;
-                    move || async move {
-                        let data = match function.encoding() {
-                            Encoding::Url | Encoding::Cbor => &body,
-                            Encoding::GetJSON | Encoding::GetCBOR => &query,
-                        };
-                        let server_function_future = function.call((), data);
-                        let server_function_future = ProvideServerContext::new(
-                            server_function_future,
-                            server_context.clone(),
-                        );
-                        server_function_future.await
-                    }
-                })
-                .await?;
+            // Because the future returned by `server_fn_handler` is not `Send`, and the future returned by this function must be send, it runs on the runtime of the server
+            let result = crate::runtime::run_local({
+                let function = function.clone();
+                let mut server_context = server_context.clone();
+                server_context.parts = parts;
+                move || async move {
+                    let data = match function.encoding() {
+                        Encoding::Url | Encoding::Cbor => &body,
+                        Encoding::GetJSON | Encoding::GetCBOR => &query,
+                    };
+                    let server_function_future = function.call((), data);
+                    let server_function_future =
+                        ProvideServerContext::new(server_function_future, server_context.clone());
+                    server_function_future.await
+                }
+            })
+            .await
+            .map_err(|err| server_fn::ServerFnError::ServerError(err.to_string()))?;
             let mut res = http::Response::builder();
 
             // Set the headers from the server context
@@ -129,10 +146,10 @@ impl Service for ServerFnHandler {
                 res = res.status(StatusCode::OK);
             }
 
-            Ok(match serialized {
+            let body = match serialized {
                 Payload::Binary(data) => {
                     res = res.header("Content-Type", "application/cbor");
-                    res.body(data.into())?
+                    data
                 }
                 Payload::Url(data) => {
                     res = res.header(
@@ -140,27 +157,26 @@ impl Service for ServerFnHandler {
                         "application/\
                                     x-www-form-urlencoded",
                     );
-                    res.body(data.into())?
+                    data.into_bytes()
                 }
                 Payload::Json(data) => {
                     res = res.header("Content-Type", "application/json");
-                    res.body(data.into())?
+                    data.into_bytes()
                 }
-            })
-        })
-    }
-}
+            };
 
-fn get_local_pool() -> tokio_util::task::LocalPoolHandle {
-    use once_cell::sync::OnceCell;
-    static LOCAL_POOL: OnceCell<tokio_util::task::LocalPoolHandle> = OnceCell::new();
-    LOCAL_POOL
-        .get_or_init(|| {
-            tokio_util::task::LocalPoolHandle::new(
-                std::thread::available_parallelism()
-                    .map(Into::into)
-                    .unwrap_or(1),
-            )
+            // Only GET requests can be cached, so only they get an ETag
+            let options = EncodingOptions {
+                compression: true,
+                etag: matches!(function.encoding(), Encoding::GetJSON | Encoding::GetCBOR),
+            };
+            let encoded = encode_body(&request_headers, body, options);
+            res = res.status(encoded.status);
+            if let Some(headers) = res.headers_mut() {
+                encoded.write_headers(headers);
+            }
+
+            Ok(res.body(encoded.body.into())?)
         })
-        .clone()
+    }
 }
@@ -67,8 +67,8 @@ use std::sync::Arc;
 use std::sync::RwLock;
 
 use crate::{
-    layer::Service, prelude::*, render::SSRState, serve_config::ServeConfig,
-    server_fn::DioxusServerFnRegistry, server_fn_service,
+    compression::encode_page, layer::Service, prelude::*, render::SSRState,
+    serve_config::ServeConfig, server_fn::DioxusServerFnRegistry, server_fn_service,
 };
 
 type HyperRequest = hyper::Request<hyper::Body>;
@@ -220,7 +220,7 @@ impl DioxusRouterExt for Router {
         let mut router = self;
         for server_fn_path in DioxusServerFnRegistry::paths_registered() {
             let func = DioxusServerFnRegistry::get(server_fn_path).unwrap();
-            let full_route = format!("{server_fn_route}/{server_fn_path}");
+            let full_route = super::server_fn_full_route(server_fn_route, server_fn_path);
             match func.encoding() {
                 Encoding::Url | Encoding::Cbor => {
                     router = router.push(Router::with_path(&full_route).post(handler(func)));
@@ -318,16 +318,6 @@ pub fn extract_parts(req: &mut Request) -> http::request::Parts {
     parts
 }
 
-fn apply_request_parts_to_response(
-    headers: hyper::header::HeaderMap,
-    response: &mut salvo::prelude::Response,
-) {
-    let mut_headers = response.headers_mut();
-    for (key, value) in headers.iter() {
-        mut_headers.insert(key, value.clone());
-    }
-}
-
 #[inline]
 async fn convert_request(req: &mut Request) -> Result<HyperRequest, SalvoError> {
     let forward_url: hyper::Uri = TryFrom::try_from(req.uri()).map_err(SalvoError::other)?;
@@ -406,7 +396,9 @@ impl<P: Clone + serde::Serialize + Send + Sync + 'static> Handler for SSRHandler
         };
 
         let route = req.uri().path().to_string();
-        let parts: Arc<RwLock<http::request::Parts>> = Arc::new(RwLock::new(extract_parts(req)));
+        let parts = extract_parts(req);
+        let request_headers = parts.headers.clone();
+        let parts: Arc<RwLock<http::request::Parts>> = Arc::new(RwLock::new(parts));
         let server_context = DioxusServerContext::new(parts);
 
         match renderer_pool
@@ -414,13 +406,15 @@ impl<P: Clone + serde::Serialize + Send + Sync + 'static> Handler for SSRHandler
             .await
         {
             Ok(rendered) => {
-                let crate::render::RenderResponse { html, freshness } = rendered;
-
-                res.write_body(html).unwrap();
-
-                let headers = server_context.response_parts().unwrap().headers.clone();
-                apply_request_parts_to_response(headers, res);
-                freshness.write(res.headers_mut());
+                let encoded = encode_page(
+                    &request_headers,
+                    rendered,
+                    self.cfg.encoding,
+                    &server_context,
+                );
+                res.status_code(encoded.status);
+                encoded.write_headers(res.headers_mut());
+                res.write_body(encoded.body).unwrap();
             }
             Err(err) => {
                 tracing::error!("Error rendering SSR: {}", err);
@@ -0,0 +1,164 @@
+//! Dioxus utilities for serverless platforms like [Cloudflare Workers](https://developers.cloudflare.com/workers/) and
+//! WASI hosts.
+//!
+//! These platforms don't let the app bind a server. Instead, they call a function with every request and send the
+//! response it returns. [`DioxusHandler`] turns an [`http::Request`] into an [`http::Response`], by calling a server
+//! function or server side rendering a page. Static assets are not served, because serverless platforms serve them
+//! from their own storage.
+//!
+//! The futures of the server run on the [`Runtime`](crate::runtime::Runtime) of the server. On
+//! `wasm32-unknown-unknown` this is [`WasmRuntime`](crate::runtime::WasmRuntime), which runs them on the event loop of
+//! the host. On `wasm32-wasi`, set a runtime with [`set_runtime`](crate::runtime::set_runtime) first.
+//!
+//! Server functions are collected automatically on native targets, but not on `wasm32`. Register every server function
+//! with [`DioxusHandler::register_server_fn`] there. Middleware added to server functions with `#[middleware]` is
+//! not collected on `wasm32` either.
+//!
+//! # Example
+//! ```rust, ignore
+//! use dioxus::prelude::*;
+//! use dioxus_fullstack::prelude::*;
+//!
+//! thread_local! {
+//!     static HANDLER: DioxusHandler<()> = DioxusHandler::new(
+//!         "",
+//!         ServeConfigBuilder::new(app, ()).index_html(include_str!("../dist/index.html")),
+//!     )
+//!     .register_server_fn::<GetPosts>();
+//! }
+//!
+//! // `convert_request` and `convert_response` convert between the types of the `worker` crate and the `http` crate
+//! #[worker::event(fetch)]
+//! async fn fetch(req: worker::Request, _env: worker::Env, _ctx: worker::Context) -> worker::Result<worker::Response> {
+//!     let req: http::Request<hyper::Body> = convert_request(req).await?;
+//!     let handler = HANDLER.with(|handler| handler.clone());
+//!     let res = handler.handle(req).await;
+//!     convert_response(res).await
+//! }
+//! ```
+
+use http::{Method, Request, Response, StatusCode};
+use hyper::Body;
+use server_fn::{Encoding, ServerFunctionRegistry};
+use std::sync::{Arc, RwLock};
+
+use crate::{
+    compression::encode_page, layer::Service, prelude::*, render::SSRState,
+    serve_config::ServeConfig, server_fn::DioxusServerFnRegistry, server_fn_service,
+};
+
+/// Handles the requests of a Dioxus application on a serverless platform.
+#[derive(Clone)]
+pub struct DioxusHandler<P: Clone + serde::Serialize + Send + Sync + 'static> {
+    server_fn_route: &'static str,
+    cfg: ServeConfig<P>,
+    ssr_state: SSRState,
+}
+
+impl<P: Clone + serde::Serialize + Send + Sync + 'static> DioxusHandler<P> {
+    /// Create a handler that calls the server functions registered under `server_fn_route`, and server side renders
+    /// every other page.
+    pub fn new(server_fn_route: &'static str, cfg: impl Into<ServeConfig<P>>) -> Self {
+        let cfg = cfg.into();
+        let ssr_state = SSRState::new(&cfg);
+        Self {
+            server_fn_route,
+            cfg,
+            ssr_state,
+        }
+    }
+
+    /// Register the server function `F`, so requests can call it.
+    ///
+    /// This is only needed on `wasm32`, where server functions are not collected automatically.
+    pub fn register_server_fn<F: DioxusServerFn>(self) -> Self {
+        // The server function is already registered on native targets, or by another handler
+        let _ = F::register_explicit();
+        self
+    }
+
+    /// Handle a request, and create the response to send back.
+    pub async fn handle(&self, req: Request<Body>) -> Response<Body> {
+        self.handle_with_context(req, |_| ()).await
+    }
+
+    /// Handle a request with a custom [`DioxusServerContext`]. This allows you to pass the bindings of the platform,
+    /// like a key-value store, to your server functions.
+    pub async fn handle_with_context(
+        &self,
+        req: Request<Body>,
+        inject_context: impl FnOnce(&mut DioxusServerContext),
+    ) -> Response<Body> {
+        let server_fn = req
+            .uri()
+            .path()
+            .strip_prefix(self.server_fn_route)
+            .and_then(|path| path.strip_prefix('/'))
+            .and_then(DioxusServerFnRegistry::get);
+
+        match server_fn {
+            Some(function) => {
+                let method = match function.encoding() {
+                    Encoding::Url | Encoding::Cbor => Method::POST,
+                    Encoding::GetJSON | Encoding::GetCBOR => Method::GET,
+                };
+                if req.method() != method {
+                    return report_err(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed");
+                }
+
+                let mut server_context = DioxusServerContext::default();
+                inject_context(&mut server_context);
+                let mut service = server_fn_service(server_context, function);
+                match service.run(req).await {
+                    Ok(res) => res,
+                    Err(e) => report_err(StatusCode::INTERNAL_SERVER_ERROR, e),
+                }
+            }
+            None => self.render(req, inject_context).await,
+        }
+    }
+
+    async fn render(
+        &self,
+        req: Request<Body>,
+        inject_context: impl FnOnce(&mut DioxusServerContext),
+    ) -> Response<Body> {
+        let (parts, _) = req.into_parts();
+        let url = parts
+            .uri
+            .path_and_query()
+            .map(|path| path.to_string())
+            .unwrap_or_else(|| "/".to_string());
+        let request_headers = parts.headers.clone();
+        let parts = Arc::new(RwLock::new(parts));
+        let mut server_context = DioxusServerContext::new(parts);
+        inject_context(&mut server_context);
+
+        match self.ssr_state.render(url, &self.cfg, &server_context).await {
+            Ok(rendered) => {
+                let encoded = encode_page(
+                    &request_headers,
+                    rendered,
+                    self.cfg.encoding,
+                    &server_context,
+                );
+                let mut builder = Response::builder().status(encoded.status);
+                if let Some(headers) = builder.headers_mut() {
+                    encoded.write_headers(headers);
+                }
+                builder.body(Body::from(encoded.body)).unwrap()
+            }
+            Err(e) => {
+                tracing::error!("Failed to render page: {}", e);
+                report_err(StatusCode::INTERNAL_SERVER_ERROR, e)
+            }
+        }
+    }
+}
+
+fn report_err<E: std::fmt::Display>(status: StatusCode, e: E) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::from(format!("Error: {}", e)))
+        .unwrap()
+}
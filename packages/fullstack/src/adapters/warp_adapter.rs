@@ -48,7 +48,8 @@
 
 use crate::layer::Service;
 use crate::{
-    prelude::*, render::SSRState, serve_config::ServeConfig, server_fn::DioxusServerFnRegistry,
+    compression::encode_page, prelude::*, render::SSRState, serve_config::ServeConfig,
+    server_fn::DioxusServerFnRegistry,
 };
 
 use crate::server_fn_service;
@@ -106,7 +107,7 @@ where
     let mut filter: Option<BoxedFilter<F::Extract>> = None;
     for server_fn_path in DioxusServerFnRegistry::paths_registered() {
         let func = DioxusServerFnRegistry::get(server_fn_path).unwrap();
-        let full_route = format!("{server_fn_route}/{server_fn_path}")
+        let full_route = super::server_fn_full_route(server_fn_route, server_fn_path)
             .trim_start_matches('/')
             .to_string();
         let route = handler(full_route, func).boxed();
@@ -208,6 +209,7 @@ pub fn render_ssr<P: Clone + serde::Serialize + Send + Sync + 'static>(
         .and(with_ssr_state(&cfg))
         .then(move |parts: http::request::Parts, renderer: SSRState| {
             let route = parts.uri.path().to_string();
+            let request_headers = parts.headers.clone();
             let parts = Arc::new(RwLock::new(parts));
             let cfg = cfg.clone();
             async move {
@@ -215,21 +217,13 @@ pub fn render_ssr<P: Clone + serde::Serialize + Send + Sync + 'static>(
 
                 match renderer.render(route, &cfg, &server_context).await {
                     Ok(rendered) => {
-                        let crate::render::RenderResponse { html, freshness } = rendered;
-
-                        let mut res = Response::builder()
-                            .header("Content-Type", "text/html")
-                            .body(html)
-                            .unwrap();
-
-                        let headers_mut = res.headers_mut();
-                        let headers = server_context.response_parts().unwrap().headers.clone();
-                        for (key, value) in headers.iter() {
-                            headers_mut.insert(key, value.clone());
+                        let encoded =
+                            encode_page(&request_headers, rendered, cfg.encoding, &server_context);
+                        let mut builder = Response::builder().status(encoded.status);
+                        if let Some(headers) = builder.headers_mut() {
+                            encoded.write_headers(headers);
                         }
-                        freshness.write(headers_mut);
-
-                        res
+                        builder.body(warp::hyper::Body::from(encoded.body)).unwrap()
                     }
                     Err(err) => {
                         tracing::error!("Failed to render ssr: {}", err);
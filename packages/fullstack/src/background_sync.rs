@@ -0,0 +1,256 @@
+//! A queue of server function calls that are retried when the client comes back online.
+//!
+//! Calls that fail because the server could not be reached stay in the queue and are sent again, in order, when the
+//! renderer reports that the network is back. Calls that fail for any other reason are dropped, because sending the
+//! same request again would fail the same way.
+//!
+//! ```rust, ignore
+//! use dioxus::prelude::*;
+//! use dioxus_fullstack::prelude::*;
+//!
+//! #[server]
+//! async fn save_note(note: String) -> Result<(), ServerFnError> {
+//!     // ...
+//!     Ok(())
+//! }
+//!
+//! fn Editor(cx: Scope) -> Element {
+//!     let sync = use_background_sync(cx);
+//!
+//!     render! {
+//!         textarea {
+//!             onchange: move |event| {
+//!                 let note = event.value.clone();
+//!                 sync.push(move || save_note(note.clone()));
+//!             }
+//!         }
+//!         if sync.pending() > 0 {
+//!             rsx! { "{sync.pending()} changes waiting to sync" }
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+
+use dioxus::prelude::*;
+use futures_channel::mpsc::{unbounded, UnboundedSender};
+use futures_util::StreamExt;
+use server_fn::ServerFnError;
+
+type SyncFuture = Pin<Box<dyn Future<Output = Result<(), ServerFnError>>>>;
+type SyncJob = Box<dyn FnMut() -> SyncFuture>;
+type Subscriber = Arc<dyn Fn()>;
+
+struct BackgroundSyncInner {
+    queue: RefCell<VecDeque<SyncJob>>,
+    subscribers: RefCell<Vec<Weak<Subscriber>>>,
+    wake: UnboundedSender<()>,
+    // Keeps the connectivity listener alive for as long as the queue exists
+    connectivity: RefCell<Option<ConnectivityWatch>>,
+}
+
+/// A queue of server function calls that are retried when the client comes back online.
+#[derive(Clone)]
+pub struct BackgroundSync {
+    inner: Rc<BackgroundSyncInner>,
+}
+
+impl BackgroundSync {
+    fn new(cx: &ScopeState) -> Self {
+        let (wake, mut wake_rx) = unbounded();
+        let sync = Self {
+            inner: Rc::new(BackgroundSyncInner {
+                queue: Default::default(),
+                subscribers: Default::default(),
+                wake: wake.clone(),
+                connectivity: Default::default(),
+            }),
+        };
+
+        *sync.inner.connectivity.borrow_mut() = watch_connectivity(move |online| {
+            if online {
+                let _ = wake.unbounded_send(());
+            }
+        });
+
+        // The queue is flushed from a task so it can be woken from outside of the virtual dom
+        let weak = Rc::downgrade(&sync.inner);
+        cx.spawn_forever(async move {
+            while wake_rx.next().await.is_some() {
+                let Some(inner) = weak.upgrade() else {
+                    break;
+                };
+                BackgroundSync { inner }.flush().await;
+            }
+        });
+
+        sync
+    }
+
+    /// Add a server function call to the queue.
+    ///
+    /// `call` is called again for every retry, so it should clone the arguments it passes to the server function.
+    pub fn push<T, Fut>(&self, mut call: impl FnMut() -> Fut + 'static)
+    where
+        T: 'static,
+        Fut: Future<Output = Result<T, ServerFnError>> + 'static,
+    {
+        let job: SyncJob = Box::new(move || {
+            let future = call();
+            Box::pin(async move { future.await.map(|_| ()) })
+        });
+        self.inner.queue.borrow_mut().push_back(job);
+        self.notify();
+        self.retry();
+    }
+
+    /// The number of calls that are waiting to be sent
+    pub fn pending(&self) -> usize {
+        self.inner.queue.borrow().len()
+    }
+
+    /// Try to send the queued calls now, even if the renderer has not reported that the network is back.
+    pub fn retry(&self) {
+        let _ = self.inner.wake.unbounded_send(());
+    }
+
+    async fn flush(&self) {
+        if !self.is_online() {
+            return;
+        }
+
+        loop {
+            // Take the job out of the queue while it runs so new calls can be pushed in the meantime
+            let Some(mut job) = self.inner.queue.borrow_mut().pop_front() else {
+                break;
+            };
+            match job().await {
+                Ok(()) => {}
+                Err(ServerFnError::Request(err)) => {
+                    tracing::info!(
+                        "The server is unreachable, keeping the call queued: {}",
+                        err
+                    );
+                    self.inner.queue.borrow_mut().push_front(job);
+                    break;
+                }
+                Err(err) => {
+                    tracing::error!("A queued server function call failed: {}", err);
+                }
+            }
+            self.notify();
+        }
+    }
+
+    fn is_online(&self) -> bool {
+        self.inner
+            .connectivity
+            .borrow()
+            .as_ref()
+            .map_or(true, |watch| (watch.is_online)())
+    }
+
+    fn notify(&self) {
+        self.inner.subscribers.borrow_mut().retain(|subscriber| {
+            if let Some(subscriber) = subscriber.upgrade() {
+                subscriber();
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
+/// A handle to the background sync queue of the app.
+pub struct UseBackgroundSync {
+    sync: BackgroundSync,
+    _subscriber: Rc<Subscriber>,
+}
+
+impl std::ops::Deref for UseBackgroundSync {
+    type Target = BackgroundSync;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sync
+    }
+}
+
+/// Get the background sync queue of the app. The component rerenders when the number of pending calls changes.
+pub fn use_background_sync(cx: &ScopeState) -> &UseBackgroundSync {
+    cx.use_hook(|| {
+        let sync = cx
+            .consume_context::<BackgroundSync>()
+            .unwrap_or_else(|| cx.provide_root_context(BackgroundSync::new(cx)));
+        let subscriber = Rc::new(cx.schedule_update());
+        sync.inner
+            .subscribers
+            .borrow_mut()
+            .push(Rc::downgrade(&subscriber));
+        UseBackgroundSync {
+            sync,
+            _subscriber: subscriber,
+        }
+    })
+}
+
+/// Reports connectivity changes to the queue until it is dropped
+struct ConnectivityWatch {
+    is_online: Box<dyn Fn() -> bool>,
+    _listener: Box<dyn std::any::Any>,
+}
+
+#[cfg(feature = "web")]
+fn watch_connectivity(callback: impl FnMut(bool) + 'static) -> Option<ConnectivityWatch> {
+    Some(ConnectivityWatch {
+        is_online: Box::new(dioxus_web::is_online),
+        _listener: Box::new(dioxus_web::watch_online_status(callback)),
+    })
+}
+
+#[cfg(all(
+    feature = "desktop",
+    not(feature = "web"),
+    not(any(target_os = "ios", target_os = "android"))
+))]
+fn watch_connectivity(callback: impl FnMut(bool) + 'static) -> Option<ConnectivityWatch> {
+    struct Listener(
+        dioxus_desktop::Connectivity,
+        dioxus_desktop::ConnectivityHandlerId,
+    );
+
+    impl Drop for Listener {
+        fn drop(&mut self) {
+            self.0.remove_connectivity_handler(self.1);
+        }
+    }
+
+    let connectivity = dioxus_desktop::window().connectivity();
+    let id = connectivity.create_connectivity_handler(callback);
+    Some(ConnectivityWatch {
+        is_online: Box::new({
+            let connectivity = connectivity.clone();
+            move || connectivity.is_online()
+        }),
+        _listener: Box::new(Listener(connectivity, id)),
+    })
+}
+
+#[cfg(not(any(
+    feature = "web",
+    all(
+        feature = "desktop",
+        not(any(target_os = "ios", target_os = "android"))
+    )
+)))]
+fn watch_connectivity(_: impl FnMut(bool) + 'static) -> Option<ConnectivityWatch> {
+    // Mobile renderers do not report connectivity changes yet, so calls are only retried when new calls are pushed
+    // or `retry` is called
+    None
+}
@@ -0,0 +1,371 @@
+//! Compression and ETag handling for server rendered pages and server function responses.
+
+use std::io::Write;
+
+use base64::Engine;
+use http::header::{
+    HeaderMap, HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE, ETAG, IF_NONE_MATCH,
+    VARY,
+};
+use http::StatusCode;
+use sha2::{Digest, Sha256};
+
+use crate::prelude::DioxusServerContext;
+use crate::render::RenderResponse;
+
+/// Bodies smaller than this are sent uncompressed because the framing overhead outweighs the savings.
+const MIN_COMPRESS_SIZE: usize = 860;
+
+/// How the body of a response is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ContentEncoding {
+    Brotli,
+    Gzip,
+    Identity,
+}
+
+impl ContentEncoding {
+    /// Pick the best encoding the client accepts, preferring brotli over gzip.
+    pub(crate) fn negotiate(request_headers: &HeaderMap) -> Self {
+        let mut brotli = false;
+        let mut gzip = false;
+
+        for header in request_headers.get_all(ACCEPT_ENCODING) {
+            let Ok(header) = header.to_str() else {
+                continue;
+            };
+            for encoding in header.split(',') {
+                let mut parts = encoding.split(';');
+                let name = parts.next().unwrap_or_default().trim();
+                // An encoding with q=0 is explicitly not accepted
+                let rejected = parts.any(|param| {
+                    param
+                        .trim()
+                        .strip_prefix("q=")
+                        .and_then(|q| q.parse::<f32>().ok())
+                        .map_or(false, |q| q == 0.0)
+                });
+                if rejected {
+                    continue;
+                }
+                match name {
+                    "br" => brotli = true,
+                    "gzip" => gzip = true,
+                    _ => {}
+                }
+            }
+        }
+
+        if brotli {
+            Self::Brotli
+        } else if gzip {
+            Self::Gzip
+        } else {
+            Self::Identity
+        }
+    }
+
+    fn header_value(self) -> Option<HeaderValue> {
+        match self {
+            Self::Brotli => Some(HeaderValue::from_static("br")),
+            Self::Gzip => Some(HeaderValue::from_static("gzip")),
+            Self::Identity => None,
+        }
+    }
+
+    fn compress(self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::Brotli => {
+                let mut output = Vec::new();
+                {
+                    let mut writer = brotli::CompressorWriter::new(&mut output, 4096, 5, 22);
+                    writer.write_all(body)?;
+                }
+                Ok(output)
+            }
+            Self::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+            Self::Identity => Ok(body.to_vec()),
+        }
+    }
+}
+
+/// Options for how responses are encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct EncodingOptions {
+    pub(crate) compression: bool,
+    pub(crate) etag: bool,
+}
+
+impl Default for EncodingOptions {
+    fn default() -> Self {
+        Self {
+            compression: true,
+            etag: true,
+        }
+    }
+}
+
+/// A response body that has been compressed and tagged for the request it answers.
+pub(crate) struct EncodedBody {
+    pub(crate) status: StatusCode,
+    pub(crate) headers: HeaderMap,
+    pub(crate) body: Vec<u8>,
+}
+
+impl EncodedBody {
+    /// Copy the headers of the encoded body into a response
+    pub(crate) fn write_headers(&self, headers: &mut HeaderMap) {
+        crate::session::merge_response_headers(&self.headers, headers);
+    }
+}
+
+/// Add a header name to the `Vary` header of a response, keeping the names that are already there
+pub(crate) fn append_vary(headers: &mut HeaderMap, value: &HeaderValue) {
+    let Ok(name) = value.to_str() else {
+        return;
+    };
+    let present = headers
+        .get_all(VARY)
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .flat_map(|header| header.split(','))
+        .map(|existing| existing.trim())
+        .any(|existing| existing == "*" || existing.eq_ignore_ascii_case(name));
+    if !present {
+        headers.append(VARY, value.clone());
+    }
+}
+
+/// Compute a weak ETag for a body. The tag is weak because the same body may be sent with different encodings.
+pub(crate) fn etag(body: &[u8]) -> String {
+    let hash = Sha256::digest(body);
+    let hash = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(&hash[..16]);
+    format!("W/\"{hash}\"")
+}
+
+/// Check if the `If-None-Match` header of a request matches an ETag.
+fn etag_matches(request_headers: &HeaderMap, etag: &str) -> bool {
+    // Weak comparison ignores the W/ prefix
+    let opaque = etag.trim_start_matches("W/");
+    request_headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .flat_map(|header| header.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == opaque)
+}
+
+/// Tag and compress a response body for the request with the given headers.
+///
+/// If the request already has the current version of the body, this returns an empty `304 Not Modified` response.
+pub(crate) fn encode_body(
+    request_headers: &HeaderMap,
+    body: Vec<u8>,
+    options: EncodingOptions,
+) -> EncodedBody {
+    let mut headers = HeaderMap::new();
+
+    // A 304 varies with the request just like the full response
+    if options.compression {
+        headers.insert(VARY, HeaderValue::from_static("Accept-Encoding"));
+    }
+
+    if options.etag {
+        let etag = etag(&body);
+        let not_modified = etag_matches(request_headers, &etag);
+        if let Ok(value) = HeaderValue::from_str(&etag) {
+            headers.insert(ETAG, value);
+        }
+        if not_modified {
+            return EncodedBody {
+                status: StatusCode::NOT_MODIFIED,
+                headers,
+                body: Vec::new(),
+            };
+        }
+    }
+
+    if options.compression {
+        let encoding = ContentEncoding::negotiate(request_headers);
+        if body.len() >= MIN_COMPRESS_SIZE {
+            if let Some(value) = encoding.header_value() {
+                match encoding.compress(&body) {
+                    Ok(compressed) => {
+                        headers.insert(CONTENT_ENCODING, value);
+                        return EncodedBody {
+                            status: StatusCode::OK,
+                            headers,
+                            body: compressed,
+                        };
+                    }
+                    Err(err) => tracing::error!("Failed to compress response: {}", err),
+                }
+            }
+        }
+    }
+
+    EncodedBody {
+        status: StatusCode::OK,
+        headers,
+        body,
+    }
+}
+
+/// Encode a rendered page for the request it answers. The headers of the result hold everything the adapters send
+/// with the page: the content type, the encoding and ETag, the freshness of the page and the headers set through the
+/// server context while rendering.
+pub(crate) fn encode_page(
+    request_headers: &HeaderMap,
+    rendered: RenderResponse,
+    options: EncodingOptions,
+    server_context: &DioxusServerContext,
+) -> EncodedBody {
+    let RenderResponse { html, freshness } = rendered;
+    let mut encoded = encode_body(request_headers, html.into_bytes(), options);
+    encoded.headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/html; charset=utf-8"),
+    );
+    freshness.write(&mut encoded.headers);
+    if let Ok(parts) = server_context.response_parts() {
+        crate::session::merge_response_headers(&parts.headers, &mut encoded.headers);
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn request(headers: &[(http::header::HeaderName, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.append(name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    fn vary(headers: &HeaderMap) -> Vec<&str> {
+        headers
+            .get_all(VARY)
+            .iter()
+            .map(|value| value.to_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn negotiation_prefers_brotli() {
+        let negotiate =
+            |accept: &str| ContentEncoding::negotiate(&request(&[(ACCEPT_ENCODING, accept)]));
+        assert_eq!(negotiate("gzip, deflate, br"), ContentEncoding::Brotli);
+        assert_eq!(negotiate("gzip"), ContentEncoding::Gzip);
+        assert_eq!(negotiate("br;q=0, gzip;q=0.5"), ContentEncoding::Gzip);
+        assert_eq!(negotiate("br;q=0, gzip;q=0"), ContentEncoding::Identity);
+        assert_eq!(negotiate("deflate"), ContentEncoding::Identity);
+        assert_eq!(
+            ContentEncoding::negotiate(&HeaderMap::new()),
+            ContentEncoding::Identity
+        );
+    }
+
+    #[test]
+    fn large_bodies_are_compressed() {
+        let body = "hello world ".repeat(200).into_bytes();
+        let encoded = encode_body(
+            &request(&[(ACCEPT_ENCODING, "gzip")]),
+            body.clone(),
+            EncodingOptions::default(),
+        );
+        assert_eq!(encoded.status, StatusCode::OK);
+        assert_eq!(encoded.headers[CONTENT_ENCODING], "gzip");
+        assert_eq!(vary(&encoded.headers), ["Accept-Encoding"]);
+
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(&encoded.body[..])
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+    }
+
+    #[test]
+    fn small_bodies_are_not_compressed() {
+        let encoded = encode_body(
+            &request(&[(ACCEPT_ENCODING, "br")]),
+            b"hello".to_vec(),
+            EncodingOptions::default(),
+        );
+        assert!(encoded.headers.get(CONTENT_ENCODING).is_none());
+        assert_eq!(encoded.body, b"hello");
+        assert_eq!(vary(&encoded.headers), ["Accept-Encoding"]);
+    }
+
+    #[test]
+    fn matching_etags_are_not_modified() {
+        let body = b"hello".to_vec();
+        let tag = etag(&body);
+
+        for if_none_match in [
+            tag.clone(),
+            tag.trim_start_matches("W/").to_string(),
+            "*".to_string(),
+        ] {
+            let encoded = encode_body(
+                &request(&[(IF_NONE_MATCH, &if_none_match)]),
+                body.clone(),
+                EncodingOptions::default(),
+            );
+            assert_eq!(encoded.status, StatusCode::NOT_MODIFIED, "{if_none_match}");
+            assert!(encoded.body.is_empty());
+            assert_eq!(encoded.headers[ETAG], tag.as_str());
+            assert_eq!(vary(&encoded.headers), ["Accept-Encoding"]);
+        }
+
+        let encoded = encode_body(
+            &request(&[(IF_NONE_MATCH, "W/\"something else\"")]),
+            body.clone(),
+            EncodingOptions::default(),
+        );
+        assert_eq!(encoded.status, StatusCode::OK);
+        assert_eq!(encoded.body, body);
+        assert_eq!(encoded.headers[ETAG], tag.as_str());
+    }
+
+    #[test]
+    fn etags_can_be_turned_off() {
+        let body = b"hello".to_vec();
+        let encoded = encode_body(
+            &request(&[(IF_NONE_MATCH, "*")]),
+            body,
+            EncodingOptions {
+                compression: true,
+                etag: false,
+            },
+        );
+        assert_eq!(encoded.status, StatusCode::OK);
+        assert!(encoded.headers.get(ETAG).is_none());
+    }
+
+    #[test]
+    fn vary_is_appended() {
+        let encoded = encode_body(
+            &HeaderMap::new(),
+            b"hello".to_vec(),
+            EncodingOptions::default(),
+        );
+
+        let mut response = request(&[(VARY, "Origin")]);
+        encoded.write_headers(&mut response);
+        assert_eq!(vary(&response), ["Origin", "Accept-Encoding"]);
+
+        let mut response = request(&[(VARY, "origin, accept-encoding")]);
+        encoded.write_headers(&mut response);
+        assert_eq!(vary(&response), ["origin, accept-encoding"]);
+    }
+}
@@ -0,0 +1,108 @@
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// The name of the cookie that holds the id of the logged in user. This cookie is readable from the client.
+///
+/// The value is the percent encoded user id followed by a `.` and a signature. Only the server can check the signature,
+/// so the client treats the cookie as a hint for rendering and the server only trusts it if the signature is valid.
+///
+/// It lives here rather than in `session` because the client reads it too, and the session module is server only.
+pub const LOGIN_COOKIE: &str = "dioxus_login";
+
+/// The login state of the current user, as set by `Session::login` on the server.
+pub struct UseLoginState {
+    user_id: Rc<RefCell<Option<String>>>,
+    update: Arc<dyn Fn()>,
+}
+
+impl UseLoginState {
+    /// The id of the logged in user
+    pub fn user_id(&self) -> Option<String> {
+        self.user_id.borrow().clone()
+    }
+
+    /// Check if a user is logged in
+    pub fn is_logged_in(&self) -> bool {
+        self.user_id.borrow().is_some()
+    }
+
+    /// Read the login state again. Call this after a server function that logs the user in or out.
+    pub fn refresh(&self) {
+        let user_id = read_login_cookie();
+        if *self.user_id.borrow() != user_id {
+            *self.user_id.borrow_mut() = user_id;
+            (self.update)();
+        }
+    }
+}
+
+/// Get the login state of the current user.
+///
+/// On the server this reads the login cookie from the request being rendered and ignores it unless it was signed with
+/// the session key. In the browser it reads the cookie from the document, so the state is the same while hydrating.
+/// Never use the client side state to authorize anything; check [`Session::user_id`](crate::prelude::Session::user_id)
+/// in your server functions instead.
+///
+/// # Example
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_fullstack::prelude::*;
+///
+/// fn app(cx: Scope) -> Element {
+///     let login = use_login_state(cx);
+///
+///     match login.user_id() {
+///         Some(user) => render! { "Welcome back {user}" },
+///         None => render! { "Please log in" },
+///     }
+/// }
+/// ```
+pub fn use_login_state(cx: &ScopeState) -> &UseLoginState {
+    cx.use_hook(|| UseLoginState {
+        user_id: Rc::new(RefCell::new(read_login_cookie())),
+        update: cx.schedule_update(),
+    })
+}
+
+#[cfg(feature = "ssr")]
+fn read_login_cookie() -> Option<String> {
+    crate::session::verified_login(&crate::prelude::server_context())
+}
+
+#[cfg(all(target_arch = "wasm32", not(feature = "ssr")))]
+fn read_login_cookie() -> Option<String> {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window()?
+        .document()?
+        .dyn_into::<web_sys::HtmlDocument>()
+        .ok()?;
+    let cookies = document.cookie().ok()?;
+    let value = cookies
+        .split(';')
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(key, _)| *key == LOGIN_COOKIE)
+        .map(|(_, value)| value.to_string())?;
+    decode_login_cookie(&value)
+}
+
+#[cfg(not(any(target_arch = "wasm32", feature = "ssr")))]
+fn read_login_cookie() -> Option<String> {
+    // Native clients don't share the browser's cookie jar
+    None
+}
+
+/// Get the user id from a login cookie without checking the signature
+#[cfg(all(target_arch = "wasm32", not(feature = "ssr")))]
+fn decode_login_cookie(value: &str) -> Option<String> {
+    let (user_id, _signature) = value.rsplit_once('.')?;
+    if user_id.is_empty() {
+        return None;
+    }
+    percent_encoding::percent_decode_str(user_id)
+        .decode_utf8()
+        .ok()
+        .map(|value| value.into_owned())
+}
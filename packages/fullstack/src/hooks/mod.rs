@@ -1,2 +1,4 @@
+pub mod login_state;
 pub mod server_cached;
 pub mod server_future;
+pub mod upload;
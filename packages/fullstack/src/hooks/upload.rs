@@ -0,0 +1,217 @@
+use crate::multipart::{UploadFile, UploadProgress};
+use dioxus::prelude::*;
+use serde::de::DeserializeOwned;
+use server_fn::{ServerFn, ServerFnError};
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// A handle to upload files with a server function that takes a
+/// [`ServerFnMultipart`](crate::multipart::ServerFnMultipart) argument.
+pub struct UseUpload<F> {
+    progress: Rc<Cell<Option<UploadProgress>>>,
+    update: Arc<dyn Fn()>,
+    _server_fn: PhantomData<F>,
+}
+
+impl<F> UseUpload<F>
+where
+    F: ServerFn<()>,
+    F::Output: DeserializeOwned,
+{
+    /// The progress of the current upload, or `None` if nothing is being uploaded
+    pub fn progress(&self) -> Option<UploadProgress> {
+        self.progress.get()
+    }
+
+    /// Call the server function with `args` and stream `files` to it.
+    ///
+    /// The component rerenders every time the progress of the upload changes.
+    pub async fn upload(
+        &self,
+        args: F,
+        files: Vec<UploadFile>,
+    ) -> Result<F::Output, ServerFnError> {
+        if !matches!(F::encoding(), server_fn::Encoding::Url) {
+            return Err(ServerFnError::Request(
+                "Only server functions with the Url encoding can upload files".to_string(),
+            ));
+        }
+
+        let set_progress = {
+            let progress = self.progress.clone();
+            let update = self.update.clone();
+            move |new_progress| {
+                progress.set(new_progress);
+                update();
+            }
+        };
+
+        set_progress(Some(UploadProgress {
+            loaded: 0,
+            total: None,
+        }));
+        let url = format!("{}/{}", F::prefix(), F::url());
+        let result = send(&url, &args, files, {
+            let set_progress = set_progress.clone();
+            move |progress| set_progress(Some(progress))
+        })
+        .await;
+        set_progress(None);
+
+        result
+    }
+}
+
+/// Upload files with a server function that takes a [`ServerFnMultipart`](crate::multipart::ServerFnMultipart)
+/// argument, and track the progress of the upload.
+///
+/// # Example
+/// ```rust, ignore
+/// use dioxus::prelude::*;
+/// use dioxus_fullstack::prelude::*;
+///
+/// fn app(cx: Scope) -> Element {
+///     let upload = use_upload::<UploadPhotos>(cx);
+///
+///     render! {
+///         input {
+///             r#type: "file",
+///             multiple: true,
+///             onchange: move |event| {
+///                 to_owned![upload];
+///                 async move {
+///                     let Some(engine) = event.files.clone() else { return };
+///                     let mut files = Vec::new();
+///                     for name in engine.files() {
+///                         if let Some(data) = engine.read_file(&name).await {
+///                             files.push(UploadFile::new(name, data));
+///                         }
+///                     }
+///                     let args = UploadPhotos { album: "holiday".into(), files: ServerFnMultipart::default() };
+///                     let _ = upload.upload(args, files).await;
+///                 }
+///             }
+///         }
+///         if let Some(fraction) = upload.progress().and_then(|progress| progress.fraction()) {
+///             rsx! { progress { value: fraction } }
+///         }
+///     }
+/// }
+/// ```
+pub fn use_upload<F>(cx: &ScopeState) -> &UseUpload<F>
+where
+    F: ServerFn<()>,
+    F::Output: DeserializeOwned,
+{
+    cx.use_hook(|| UseUpload {
+        progress: Rc::new(Cell::new(None)),
+        update: cx.schedule_update(),
+        _server_fn: PhantomData,
+    })
+}
+
+impl<F> Clone for UseUpload<F> {
+    fn clone(&self) -> Self {
+        Self {
+            progress: self.progress.clone(),
+            update: self.update.clone(),
+            _server_fn: PhantomData,
+        }
+    }
+}
+
+/// Send the arguments and files as a multipart request and decode the response.
+#[cfg(feature = "web")]
+async fn send<F>(
+    url: &str,
+    args: &F,
+    files: Vec<UploadFile>,
+    mut on_progress: impl FnMut(UploadProgress) + 'static,
+) -> Result<F::Output, ServerFnError>
+where
+    F: ServerFn<()>,
+    F::Output: DeserializeOwned,
+{
+    use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+    fn request_error(err: JsValue) -> ServerFnError {
+        ServerFnError::Request(format!("{err:?}"))
+    }
+
+    let args =
+        serde_qs::to_string(args).map_err(|err| ServerFnError::Serialization(err.to_string()))?;
+
+    // The arguments must come before the files so the server can decode them before it streams the files
+    let form = web_sys::FormData::new().map_err(request_error)?;
+    form.append_with_str(crate::multipart::ARGS_FIELD, &args)
+        .map_err(request_error)?;
+    for file in files {
+        let data = js_sys::Array::of1(&js_sys::Uint8Array::from(file.data.as_slice()));
+        let mut options = web_sys::BlobPropertyBag::new();
+        options.type_(
+            file.content_type
+                .as_deref()
+                .unwrap_or("application/octet-stream"),
+        );
+        let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&data, &options)
+            .map_err(request_error)?;
+        form.append_with_blob_and_filename(&file.name, &blob, &file.file_name)
+            .map_err(request_error)?;
+    }
+
+    let xhr = web_sys::XmlHttpRequest::new().map_err(request_error)?;
+    xhr.open("POST", url).map_err(request_error)?;
+    xhr.set_request_header("Accept", "application/json")
+        .map_err(request_error)?;
+
+    let on_progress =
+        Closure::<dyn FnMut(web_sys::ProgressEvent)>::new(move |event: web_sys::ProgressEvent| {
+            on_progress(UploadProgress {
+                loaded: event.loaded() as u64,
+                total: event.length_computable().then(|| event.total() as u64),
+            })
+        });
+    xhr.upload()
+        .map_err(request_error)?
+        .set_onprogress(Some(on_progress.as_ref().unchecked_ref()));
+
+    let done = js_sys::Promise::new(&mut |resolve, reject| {
+        xhr.set_onload(Some(&resolve));
+        xhr.set_onerror(Some(&reject));
+        xhr.set_onabort(Some(&reject));
+    });
+    xhr.send_with_opt_form_data(Some(&form))
+        .map_err(request_error)?;
+    wasm_bindgen_futures::JsFuture::from(done)
+        .await
+        .map_err(|_| ServerFnError::Request("The upload failed".to_string()))?;
+    // The progress handler must live until the request is done
+    drop(on_progress);
+
+    let status = xhr.status().map_err(request_error)?;
+    let body = xhr
+        .response_text()
+        .map_err(request_error)?
+        .unwrap_or_default();
+    if !(200..300).contains(&status) {
+        return Err(ServerFnError::ServerError(body));
+    }
+    serde_json::from_str(&body).map_err(|err| ServerFnError::Deserialization(err.to_string()))
+}
+
+#[cfg(not(feature = "web"))]
+async fn send<F>(
+    _: &str,
+    _: &F,
+    _: Vec<UploadFile>,
+    _: impl FnMut(UploadProgress) + 'static,
+) -> Result<F::Output, ServerFnError>
+where
+    F: ServerFn<()>,
+{
+    Err(ServerFnError::Request(
+        "File uploads are only supported in the browser".to_string(),
+    ))
+}
@@ -58,6 +58,40 @@ pub(crate) fn take_server_data<T: DeserializeOwned>() -> Option<T> {
     SERVER_DATA.as_ref()?.take()
 }
 
+/// Check if the server sent the page without its body, so it has to be rendered instead of hydrated
+#[allow(unused)]
+pub(crate) fn is_client_rendered() -> bool {
+    #[cfg(target_arch = "wasm32")]
+    {
+        web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id("dioxus-client-rendered"))
+            .is_some()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        false
+    }
+}
+
+/// Get the suspense boundaries that timed out while the server rendered the page
+#[allow(unused)]
+pub(crate) fn timed_out_suspense_from_document() -> Vec<usize> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id("dioxus-suspense-timed-out"))
+            .and_then(|element| element.get_attribute("data-serialized"))
+            .and_then(|attribute| serde_from_bytes(attribute.as_bytes()))
+            .unwrap_or_default()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Vec::new()
+    }
+}
+
 #[cfg(not(feature = "ssr"))]
 /// Get the props from the document. This is only available in the browser.
 ///
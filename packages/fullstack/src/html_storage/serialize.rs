@@ -26,6 +26,25 @@ pub(crate) fn encode_props_in_element<T: Serialize>(
     write_to.write_all(r#"" />"#.as_bytes())
 }
 
+#[cfg(feature = "ssr")]
+/// Mark the page as rendered by the client, so it isn't hydrated
+pub(crate) fn encode_client_rendered(write_to: &mut impl std::io::Write) -> std::io::Result<()> {
+    write_to.write_all(r#"<meta hidden="true" id="dioxus-client-rendered" />"#.as_bytes())
+}
+
+#[cfg(feature = "ssr")]
+/// Send the suspense boundaries that timed out to the client, so it starts them timed out too
+pub(crate) fn encode_timed_out_suspense(
+    timed_out: &[usize],
+    write_to: &mut impl std::io::Write,
+) -> std::io::Result<()> {
+    write_to.write_all(
+        r#"<meta hidden="true" id="dioxus-suspense-timed-out" data-serialized=""#.as_bytes(),
+    )?;
+    serde_to_writable(&timed_out, write_to)?;
+    write_to.write_all(r#"" />"#.as_bytes())
+}
+
 #[cfg(feature = "ssr")]
 /// Encode data into a element. This is inteded to be used in the server to send data to the client.
 pub(crate) fn encode_in_element(
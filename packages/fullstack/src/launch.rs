@@ -123,7 +123,12 @@ impl<Props: Clone + serde::Serialize + serde::de::DeserializeOwned + Send + Sync
     pub fn launch_web(self) {
         #[cfg(not(feature = "ssr"))]
         {
-            let cfg = self.web_cfg.hydrate(true);
+            let cfg = self
+                .web_cfg
+                .hydrate(!crate::html_storage::deserialize::is_client_rendered())
+                .with_timed_out_suspense(
+                    crate::html_storage::deserialize::timed_out_suspense_from_document(),
+                );
             dioxus_web::launch_with_props(
                 self.component,
                 get_root_props_from_document().unwrap(),
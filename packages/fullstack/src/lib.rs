@@ -12,6 +12,10 @@ pub mod router;
 
 #[cfg(feature = "ssr")]
 mod adapters;
+#[cfg(any(feature = "web", feature = "desktop"))]
+pub mod background_sync;
+#[cfg(feature = "ssr")]
+mod compression;
 #[cfg(feature = "ssr")]
 pub use adapters::*;
 mod hooks;
@@ -20,13 +24,21 @@ mod hot_reload;
 pub mod launch;
 #[cfg(feature = "ssr")]
 mod layer;
+pub mod multipart;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 #[cfg(feature = "ssr")]
 mod render;
+pub mod resource_hints;
+#[cfg(feature = "ssr")]
+pub mod runtime;
 #[cfg(feature = "ssr")]
 mod serve_config;
 #[cfg(feature = "ssr")]
 mod server_context;
 mod server_fn;
+#[cfg(feature = "ssr")]
+pub mod session;
 
 /// A prelude of commonly used items in dioxus-fullstack.
 pub mod prelude {
@@ -34,22 +46,32 @@ pub mod prelude {
     pub use crate::adapters::axum_adapter::*;
     #[cfg(feature = "salvo")]
     pub use crate::adapters::salvo_adapter::*;
+    #[cfg(feature = "serverless")]
+    pub use crate::adapters::serverless_adapter::*;
     #[cfg(feature = "warp")]
     pub use crate::adapters::warp_adapter::*;
+    #[cfg(any(feature = "web", feature = "desktop"))]
+    pub use crate::background_sync::{use_background_sync, BackgroundSync, UseBackgroundSync};
     use crate::hooks;
     #[cfg(not(feature = "ssr"))]
     pub use crate::html_storage::deserialize::get_root_props_from_document;
     pub use crate::launch::LaunchBuilder;
     #[cfg(feature = "ssr")]
     pub use crate::layer::{Layer, Service};
+    pub use crate::multipart::{ServerFnMultipart, UploadFile, UploadProgress};
+    #[cfg(feature = "ssr")]
+    pub use crate::multipart::{UploadLimits, UploadedFile};
     #[cfg(all(feature = "ssr", feature = "router"))]
     pub use crate::render::pre_cache_static_routes_with_props;
     #[cfg(feature = "ssr")]
     pub use crate::render::SSRState;
+    pub use crate::resource_hints::{
+        use_resource_hint, HintRel, Preconnect, Prefetch, Preload, ResourceHint,
+    };
     #[cfg(feature = "router")]
-    pub use crate::router::FullstackRouterConfig;
+    pub use crate::router::{use_prefetch_route, FullstackRouterConfig};
     #[cfg(feature = "ssr")]
-    pub use crate::serve_config::{ServeConfig, ServeConfigBuilder};
+    pub use crate::serve_config::{RenderStrategy, ServeConfig, ServeConfigBuilder};
     #[cfg(all(feature = "ssr", feature = "axum"))]
     pub use crate::server_context::Axum;
     #[cfg(feature = "ssr")]
@@ -59,12 +81,21 @@ pub mod prelude {
     pub use crate::server_fn::DioxusServerFn;
     #[cfg(feature = "ssr")]
     pub use crate::server_fn::{ServerFnMiddleware, ServerFnTraitObj, ServerFunction};
+    #[cfg(feature = "ssr")]
+    pub use crate::session::{
+        use_session, MemoryStore, SameSite, Session, SessionConfig, SessionError, SessionStore,
+    };
     pub use dioxus_server_macro::*;
     #[cfg(feature = "ssr")]
     pub use dioxus_ssr::incremental::IncrementalRendererConfig;
     pub use server_fn::{self, ServerFn as _, ServerFnError};
 
-    pub use hooks::{server_cached::server_cached, server_future::use_server_future};
+    pub use hooks::{
+        login_state::{use_login_state, UseLoginState},
+        server_cached::server_cached,
+        server_future::use_server_future,
+        upload::{use_upload, UseUpload},
+    };
 }
 
 // Warn users about overlapping features
@@ -0,0 +1,486 @@
+//! File uploads for server functions.
+//!
+//! A server function that takes a [`ServerFnMultipart`] argument receives its arguments and files as a
+//! `multipart/form-data` request. The arguments are decoded before the function is called, and the files are streamed
+//! into the function as it reads them, so a large upload is never buffered in memory on the server.
+//!
+//! Call the server function from the client with [`use_upload`](crate::prelude::use_upload), which reports the
+//! progress of the upload.
+//!
+//! ```rust, ignore
+//! use dioxus_fullstack::prelude::*;
+//! use tokio::io::AsyncWriteExt;
+//!
+//! #[server]
+//! async fn upload_photos(album: String, files: ServerFnMultipart) -> Result<usize, ServerFnError> {
+//!     let mut count = 0;
+//!     while let Some(mut file) = files.next_file().await? {
+//!         let path = format!("uploads/{album}/{}", file.file_name().unwrap_or("photo"));
+//!         let mut output = tokio::fs::File::create(path).await?;
+//!         while let Some(chunk) = file.chunk().await? {
+//!             output.write_all(&chunk).await?;
+//!         }
+//!         count += 1;
+//!     }
+//!     Ok(count)
+//! }
+//! ```
+//!
+//! Only server functions with the default `Url` encoding can take a [`ServerFnMultipart`].
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The name of the multipart field that holds the encoded arguments of the server function. This field must come
+/// before any files.
+pub const ARGS_FIELD: &str = "__dioxus_args";
+
+/// A server function argument that streams the files uploaded with the request.
+///
+/// On the client this is a placeholder: pass `ServerFnMultipart::default()` in the arguments and the files to
+/// [`UseUpload::upload`](crate::prelude::UseUpload::upload).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServerFnMultipart {
+    _private: (),
+}
+
+// The arguments are encoded with serde, so the placeholder is written as a marker value that is ignored when it is read
+impl Serialize for ServerFnMultipart {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str("multipart")
+    }
+}
+
+impl<'de> Deserialize<'de> for ServerFnMultipart {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde::de::IgnoredAny::deserialize(deserializer)?;
+        Ok(Self::default())
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl schemars::JsonSchema for ServerFnMultipart {
+    fn schema_name() -> String {
+        "ServerFnMultipart".to_string()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            format: Some("binary".to_string()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// A file to upload with a server function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadFile {
+    pub(crate) name: String,
+    pub(crate) file_name: String,
+    pub(crate) content_type: Option<String>,
+    pub(crate) data: Vec<u8>,
+}
+
+impl UploadFile {
+    /// Create a file to upload with the given file name and contents.
+    pub fn new(file_name: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            name: "file".to_string(),
+            file_name: file_name.into(),
+            content_type: None,
+            data: data.into(),
+        }
+    }
+
+    /// Set the name of the form field the file is sent as. Defaults to `file`.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Set the MIME type of the file. Defaults to `application/octet-stream`.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+}
+
+/// The progress of an upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadProgress {
+    /// The number of bytes sent so far
+    pub loaded: u64,
+    /// The total number of bytes in the request, if known
+    pub total: Option<u64>,
+}
+
+impl UploadProgress {
+    /// The fraction of the upload that has been sent, between `0.0` and `1.0`
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total {
+            Some(0) => Some(1.0),
+            Some(total) => Some(self.loaded as f64 / total as f64),
+            None => None,
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub use server::*;
+
+#[cfg(feature = "ssr")]
+mod server {
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use futures_util::TryStreamExt;
+    use once_cell::sync::OnceCell;
+    use server_fn::ServerFnError;
+    use tokio::sync::Mutex;
+
+    use super::{ServerFnMultipart, ARGS_FIELD};
+    use crate::prelude::{server_context, DioxusServerContext};
+
+    static UPLOAD_LIMITS: OnceCell<UploadLimits> = OnceCell::new();
+
+    /// Limits on the size of multipart server function requests.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct UploadLimits {
+        max_request_size: u64,
+        max_file_size: Option<u64>,
+    }
+
+    impl Default for UploadLimits {
+        fn default() -> Self {
+            Self {
+                max_request_size: 64 * 1024 * 1024,
+                max_file_size: None,
+            }
+        }
+    }
+
+    impl UploadLimits {
+        /// Create the default limits. Requests may be up to 64 MiB and a single file may use all of it.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Set the maximum size of a whole request in bytes.
+        pub fn max_request_size(mut self, bytes: u64) -> Self {
+            self.max_request_size = bytes;
+            self
+        }
+
+        /// Set the maximum size of a single file in bytes.
+        pub fn max_file_size(mut self, bytes: u64) -> Self {
+            self.max_file_size = Some(bytes);
+            self
+        }
+
+        /// Use these limits for every server function that does not have limits in its server context.
+        pub fn install(self) {
+            if UPLOAD_LIMITS.set(self).is_err() {
+                tracing::warn!("Upload limits were already installed. Ignoring the new limits.");
+            }
+        }
+
+        fn for_context(context: &DioxusServerContext) -> Self {
+            context
+                .get::<UploadLimits>()
+                .or_else(|| UPLOAD_LIMITS.get().copied())
+                .unwrap_or_default()
+        }
+
+        fn constraints(&self) -> multer::Constraints {
+            let mut size_limit = multer::SizeLimit::new().whole_stream(self.max_request_size);
+            if let Some(max_file_size) = self.max_file_size {
+                size_limit = size_limit.per_field(max_file_size);
+            }
+            multer::Constraints::new().size_limit(size_limit)
+        }
+    }
+
+    /// The files of a multipart request that have not been read yet, stored in the request extensions.
+    #[derive(Clone)]
+    struct PendingFiles(Arc<Mutex<multer::Multipart<'static>>>);
+
+    /// Get the boundary of a `multipart/form-data` request, or `None` if the request is not a multipart request.
+    pub(crate) fn multipart_boundary(headers: &http::HeaderMap) -> Option<String> {
+        let content_type = headers.get(http::header::CONTENT_TYPE)?.to_str().ok()?;
+        multer::parse_boundary(content_type).ok()
+    }
+
+    /// Read the encoded arguments of a multipart request and store the files in the request so the server function
+    /// can stream them.
+    pub(crate) async fn read_multipart_args(
+        context: &DioxusServerContext,
+        parts: &mut http::request::Parts,
+        body: hyper::Body,
+        boundary: String,
+    ) -> Result<Vec<u8>, ServerFnError> {
+        let limits = UploadLimits::for_context(context);
+        let mut multipart =
+            multer::Multipart::with_constraints(body, boundary, limits.constraints());
+
+        // The arguments come first so the files can be streamed straight into the server function
+        let args = match multipart.next_field().await.map_err(upload_error)? {
+            Some(field) if field.name() == Some(ARGS_FIELD) => {
+                field.bytes().await.map_err(upload_error)?.to_vec()
+            }
+            _ => {
+                return Err(ServerFnError::Args(format!(
+                    "The first field of a multipart server function request must be `{ARGS_FIELD}`"
+                )))
+            }
+        };
+
+        parts
+            .extensions
+            .insert(PendingFiles(Arc::new(Mutex::new(multipart))));
+
+        Ok(args)
+    }
+
+    fn upload_error(err: multer::Error) -> ServerFnError {
+        ServerFnError::ServerError(format!("Failed to read the upload: {err}"))
+    }
+
+    impl ServerFnMultipart {
+        /// Wait for the next file in the request, or `None` if every file has been read.
+        ///
+        /// Read the file before asking for the next one. Any part of a file that has not been read is skipped.
+        pub async fn next_file(&self) -> Result<Option<UploadedFile>, ServerFnError> {
+            let pending = server_context()
+                .request_parts()
+                .ok()
+                .and_then(|parts| parts.extensions.get::<PendingFiles>().cloned());
+            let Some(PendingFiles(multipart)) = pending else {
+                return Ok(None);
+            };
+
+            let field = multipart
+                .lock()
+                .await
+                .next_field()
+                .await
+                .map_err(upload_error)?;
+
+            Ok(field.map(|field| UploadedFile {
+                name: field.name().map(ToString::to_string),
+                file_name: field.file_name().map(ToString::to_string),
+                content_type: field.content_type().map(ToString::to_string),
+                field,
+            }))
+        }
+    }
+
+    /// A file that is being uploaded to a server function.
+    pub struct UploadedFile {
+        name: Option<String>,
+        file_name: Option<String>,
+        content_type: Option<String>,
+        field: multer::Field<'static>,
+    }
+
+    impl UploadedFile {
+        /// The name of the form field the file was sent as
+        pub fn name(&self) -> Option<&str> {
+            self.name.as_deref()
+        }
+
+        /// The name of the file on the client
+        pub fn file_name(&self) -> Option<&str> {
+            self.file_name.as_deref()
+        }
+
+        /// The MIME type of the file
+        pub fn content_type(&self) -> Option<&str> {
+            self.content_type.as_deref()
+        }
+
+        /// Read the next chunk of the file, or `None` if the whole file has been read.
+        pub async fn chunk(&mut self) -> Result<Option<Bytes>, ServerFnError> {
+            self.field.chunk().await.map_err(upload_error)
+        }
+
+        /// Read the rest of the file into memory.
+        pub async fn bytes(self) -> Result<Bytes, ServerFnError> {
+            self.field.bytes().await.map_err(upload_error)
+        }
+
+        /// Read the file with an async reader.
+        pub fn into_reader(self) -> impl tokio::io::AsyncRead + Send + Unpin {
+            tokio_util::io::StreamReader::new(
+                self.field
+                    .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err)),
+            )
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const BOUNDARY: &str = "dioxus-boundary";
+
+        /// A multipart body with a field for each `(name, file name, data)`
+        fn body(fields: &[(&str, Option<&str>, &str)]) -> String {
+            let mut body = String::new();
+            for (name, file_name, data) in fields {
+                body += &format!("--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"");
+                if let Some(file_name) = file_name {
+                    body += &format!("; filename=\"{file_name}\"");
+                }
+                body += &format!("\r\n\r\n{data}\r\n");
+            }
+            body + &format!("--{BOUNDARY}--\r\n")
+        }
+
+        async fn read(
+            limits: Option<UploadLimits>,
+            body: String,
+        ) -> (Result<Vec<u8>, ServerFnError>, http::request::Parts) {
+            let mut context = DioxusServerContext::default();
+            if let Some(limits) = limits {
+                context.insert(limits).unwrap();
+            }
+            let (mut parts, _) = http::Request::new(()).into_parts();
+            let args =
+                read_multipart_args(&context, &mut parts, body.into(), BOUNDARY.to_string()).await;
+            (args, parts)
+        }
+
+        async fn read_file(parts: &http::request::Parts) -> Result<Option<Bytes>, ServerFnError> {
+            let PendingFiles(multipart) = parts.extensions.get::<PendingFiles>().unwrap().clone();
+            let mut multipart = multipart.lock().await;
+            match multipart.next_field().await.map_err(upload_error)? {
+                Some(field) => Ok(Some(field.bytes().await.map_err(upload_error)?)),
+                None => Ok(None),
+            }
+        }
+
+        #[test]
+        fn boundaries() {
+            let mut headers = http::HeaderMap::new();
+            assert_eq!(multipart_boundary(&headers), None);
+
+            headers.insert(
+                http::header::CONTENT_TYPE,
+                "multipart/form-data; boundary=abc123".parse().unwrap(),
+            );
+            assert_eq!(multipart_boundary(&headers), Some("abc123".to_string()));
+
+            headers.insert(
+                http::header::CONTENT_TYPE,
+                "multipart/form-data; boundary=\"quoted boundary\""
+                    .parse()
+                    .unwrap(),
+            );
+            assert_eq!(
+                multipart_boundary(&headers),
+                Some("quoted boundary".to_string())
+            );
+
+            for content_type in [
+                "multipart/form-data",
+                "application/x-www-form-urlencoded",
+                "application/cbor",
+            ] {
+                headers.insert(http::header::CONTENT_TYPE, content_type.parse().unwrap());
+                assert_eq!(multipart_boundary(&headers), None, "{content_type}");
+            }
+        }
+
+        #[tokio::test]
+        async fn arguments_are_read_before_the_files() {
+            let (args, parts) = read(
+                None,
+                body(&[
+                    (ARGS_FIELD, None, "album=holiday"),
+                    ("file", Some("beach.png"), "beach"),
+                    ("file", Some("sunset.png"), "sunset"),
+                ]),
+            )
+            .await;
+            assert_eq!(args.unwrap(), b"album=holiday");
+
+            assert_eq!(read_file(&parts).await.unwrap().unwrap(), "beach");
+            assert_eq!(read_file(&parts).await.unwrap().unwrap(), "sunset");
+            assert_eq!(read_file(&parts).await.unwrap(), None);
+        }
+
+        #[tokio::test]
+        async fn arguments_must_come_first() {
+            let (args, parts) = read(
+                None,
+                body(&[
+                    ("file", Some("beach.png"), "beach"),
+                    (ARGS_FIELD, None, "album=holiday"),
+                ]),
+            )
+            .await;
+            assert!(matches!(args, Err(ServerFnError::Args(_))));
+            assert!(parts.extensions.get::<PendingFiles>().is_none());
+
+            let (args, _) = read(None, body(&[])).await;
+            assert!(matches!(args, Err(ServerFnError::Args(_))));
+        }
+
+        #[tokio::test]
+        async fn requests_over_the_size_limit_are_rejected() {
+            let limits = UploadLimits::new().max_request_size(64);
+            let (args, _) = read(
+                Some(limits),
+                body(&[
+                    (ARGS_FIELD, None, "a=1"),
+                    ("file", Some("big.bin"), &"x".repeat(256)),
+                ]),
+            )
+            .await;
+            assert!(matches!(args, Err(ServerFnError::ServerError(_))));
+        }
+
+        #[tokio::test]
+        async fn files_over_the_size_limit_are_rejected() {
+            let limits = UploadLimits::new().max_file_size(16);
+            let (args, parts) = read(
+                Some(limits),
+                body(&[
+                    (ARGS_FIELD, None, "a=1"),
+                    ("file", Some("small.bin"), "small"),
+                    ("file", Some("big.bin"), &"x".repeat(256)),
+                ]),
+            )
+            .await;
+            assert_eq!(args.unwrap(), b"a=1");
+            assert_eq!(read_file(&parts).await.unwrap().unwrap(), "small");
+            assert!(read_file(&parts).await.is_err());
+        }
+
+        #[tokio::test]
+        async fn malformed_requests_are_rejected() {
+            // No boundary at all
+            let (args, _) = read(None, "album=holiday".to_string()).await;
+            assert!(args.is_err());
+
+            // The body ends in the middle of the arguments
+            let truncated = format!(
+                "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{ARGS_FIELD}\"\r\n\r\nalbum=hol"
+            );
+            let (args, _) = read(None, truncated).await;
+            assert!(args.is_err());
+
+            // The body ends in the middle of a file
+            let body = body(&[
+                (ARGS_FIELD, None, "a=1"),
+                ("file", Some("beach.png"), "beach"),
+            ]);
+            let truncated =
+                body[..body.len() - format!("\r\n--{BOUNDARY}--\r\n").len() - 2].to_string();
+            let (args, parts) = read(None, truncated).await;
+            assert_eq!(args.unwrap(), b"a=1");
+            assert!(read_file(&parts).await.is_err());
+        }
+    }
+}
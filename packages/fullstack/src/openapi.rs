@@ -0,0 +1,209 @@
+//! Generate an [OpenAPI](https://spec.openapis.org/oas/v3.0.3) document that describes your server functions.
+//!
+//! With the `openapi` feature enabled, the `#[server]` macro records the argument and return types of every server
+//! function. [`OpenApi`] turns those records into an OpenAPI 3 document, so clients that are not written with
+//! Dioxus and API gateways can call the same endpoints.
+//!
+//! Every argument and the `Ok` type of every server function must implement [`schemars::JsonSchema`].
+//!
+//! ```rust, ignore
+//! use axum::{routing::get, Json};
+//! use dioxus_fullstack::openapi::OpenApi;
+//!
+//! let document = OpenApi::new("My App", "1.0.0").build();
+//! let router = axum::Router::new()
+//!     .register_server_fns("")
+//!     .route("/openapi.json", get(move || async move { Json(document) }));
+//! ```
+
+pub use schemars;
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::Schema;
+use serde_json::{json, Map, Value};
+use server_fn::{Encoding, ServerFunctionRegistry};
+
+use crate::adapters::server_fn_full_route;
+use crate::server_fn::DioxusServerFnRegistry;
+
+/// The schemas of the arguments and return type of a server function. This is submitted by the `#[server]` macro.
+pub struct ServerFnSchema {
+    /// The prefix of the server function.
+    pub prefix: &'static str,
+    /// The url of the server function.
+    pub url: &'static str,
+    /// The name of the rust function.
+    pub name: &'static str,
+    /// The doc comment of the rust function.
+    pub description: &'static str,
+    /// The name and schema of each argument, in order. The server context argument is not included.
+    pub arguments: fn(&mut SchemaGenerator) -> Vec<(&'static str, Schema)>,
+    /// The schema of the value the server function returns when it succeeds.
+    pub output: fn(&mut SchemaGenerator) -> Schema,
+}
+
+server_fn::inventory::collect!(ServerFnSchema);
+
+/// A builder for an OpenAPI document that describes every registered server function.
+#[derive(Debug, Clone)]
+pub struct OpenApi {
+    title: String,
+    version: String,
+    description: Option<String>,
+    server_fn_route: &'static str,
+}
+
+impl OpenApi {
+    /// Create a new document for the API with the given title and version.
+    pub fn new(title: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            version: version.into(),
+            description: None,
+            server_fn_route: "",
+        }
+    }
+
+    /// Set the description of the API.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the route the server functions are registered at. This must match the route passed to `register_server_fns`.
+    pub fn server_fn_route(mut self, server_fn_route: &'static str) -> Self {
+        self.server_fn_route = server_fn_route;
+        self
+    }
+
+    /// Build the OpenAPI document.
+    pub fn build(&self) -> Value {
+        let mut generator = SchemaSettings::openapi3().into_generator();
+
+        // Describe the server functions the same way the adapters mount them, so every path in the document is a
+        // route the server handles
+        let mut server_fn_paths = DioxusServerFnRegistry::paths_registered();
+        // Sort the functions so the document is the same every time it is generated
+        server_fn_paths.sort_unstable();
+
+        let mut paths = Map::new();
+        for server_fn_path in server_fn_paths {
+            let Some(server_fn) = DioxusServerFnRegistry::get(server_fn_path) else {
+                continue;
+            };
+            let Some(schema) = server_fn::inventory::iter::<ServerFnSchema>
+                .into_iter()
+                .find(|schema| {
+                    schema.prefix == server_fn.prefix() && schema.url == server_fn.url()
+                })
+            else {
+                continue;
+            };
+            let path = server_fn_full_route(self.server_fn_route, server_fn_path);
+            paths.insert(
+                path,
+                operation(&mut generator, schema, server_fn.encoding()),
+            );
+        }
+
+        let mut info = json!({
+            "title": self.title,
+            "version": self.version,
+        });
+        if let Some(description) = &self.description {
+            info["description"] = json!(description);
+        }
+
+        json!({
+            "openapi": "3.0.3",
+            "info": info,
+            "paths": paths,
+            "components": {
+                "schemas": generator.take_definitions(),
+            },
+        })
+    }
+}
+
+/// Describe a single server function as an OpenAPI path item.
+fn operation(
+    generator: &mut SchemaGenerator,
+    schema: &ServerFnSchema,
+    encoding: Encoding,
+) -> Value {
+    let arguments = (schema.arguments)(generator);
+    let output = (schema.output)(generator);
+
+    let mut operation = json!({
+        "operationId": schema.name,
+        "responses": {
+            "200": {
+                "description": "The value the server function returned",
+                "content": {
+                    response_content_type(encoding): { "schema": output },
+                },
+            },
+            "default": {
+                "description": "The server function failed",
+                "content": {
+                    "text/plain": { "schema": { "type": "string" } },
+                },
+            },
+        },
+    });
+    if !schema.description.is_empty() {
+        operation["description"] = json!(schema.description);
+    }
+
+    match encoding {
+        Encoding::GetJSON | Encoding::GetCBOR => {
+            let parameters: Vec<_> = arguments
+                .into_iter()
+                .map(|(name, schema)| {
+                    json!({
+                        "name": name,
+                        "in": "query",
+                        "required": true,
+                        "schema": schema,
+                    })
+                })
+                .collect();
+            operation["parameters"] = json!(parameters);
+            json!({ "get": operation })
+        }
+        Encoding::Url | Encoding::Cbor => {
+            let required: Vec<_> = arguments.iter().map(|(name, _)| *name).collect();
+            let properties: Map<String, Value> = arguments
+                .into_iter()
+                .map(|(name, schema)| (name.to_string(), json!(schema)))
+                .collect();
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": {
+                    request_content_type(encoding): {
+                        "schema": {
+                            "type": "object",
+                            "properties": properties,
+                            "required": required,
+                        },
+                    },
+                },
+            });
+            json!({ "post": operation })
+        }
+    }
+}
+
+fn request_content_type(encoding: Encoding) -> &'static str {
+    match encoding {
+        Encoding::Url | Encoding::GetJSON => "application/x-www-form-urlencoded",
+        Encoding::Cbor | Encoding::GetCBOR => "application/cbor",
+    }
+}
+
+fn response_content_type(encoding: Encoding) -> &'static str {
+    match encoding {
+        Encoding::Url | Encoding::GetJSON => "application/json",
+        Encoding::Cbor | Encoding::GetCBOR => "application/cbor",
+    }
+}
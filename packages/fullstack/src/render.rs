@@ -1,6 +1,7 @@
 //! A shared pool of renderers for efficient server side rendering.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::server_context::SERVER_CONTEXT;
 use dioxus::prelude::VirtualDom;
@@ -10,14 +11,16 @@ use dioxus_ssr::{
 };
 use serde::Serialize;
 use std::sync::RwLock;
-use tokio::task::spawn_blocking;
 
 use crate::prelude::*;
 use dioxus::prelude::*;
 
-enum SsrRendererPool {
-    Renderer(RwLock<Vec<Renderer>>),
-    Incremental(RwLock<Vec<dioxus_ssr::incremental::IncrementalRenderer>>),
+struct SsrRendererPool {
+    renderers: RwLock<Vec<Renderer>>,
+    incremental: Option<RwLock<Vec<dioxus_ssr::incremental::IncrementalRenderer>>>,
+    // The invalidation the incremental renderers were configured with. Pooled renderers are reset to it after a route
+    // overrides it
+    invalidate_after: Option<Duration>,
 }
 
 impl SsrRendererPool {
@@ -29,129 +32,128 @@ impl SsrRendererPool {
         props: P,
         server_context: &DioxusServerContext,
     ) -> Result<(RenderFreshness, String), dioxus_ssr::incremental::IncrementalRendererError> {
+        let (strategy, cache_key) = match cfg.render_strategy(&route) {
+            Some((strategy, cache_key)) => (Some(strategy), cache_key),
+            None => (None, route),
+        };
         let wrapper = FullstackRenderer {
             cfg: cfg.clone(),
             server_context: server_context.clone(),
+            client_rendered: strategy == Some(RenderStrategy::Csr),
+        };
+
+        // The invalidation of the incremental renderer is overridden by the strategy of the route
+        let incremental = match (strategy, &self.incremental) {
+            (Some(RenderStrategy::Csr), _) => {
+                let mut to = WriteBuffer { buffer: Vec::new() };
+                wrapper.render_before_body(&mut *to)?;
+                wrapper.render_after_body(&mut *to)?;
+                let html = String::from_utf8(to.buffer).map_err(|err| {
+                    dioxus_ssr::incremental::IncrementalRendererError::Other(Box::new(err))
+                })?;
+                return Ok((RenderFreshness::now(None), html));
+            }
+            (Some(RenderStrategy::Ssr), _) | (_, None) => None,
+            (Some(RenderStrategy::Ssg), Some(pool)) => Some((pool, None)),
+            (Some(RenderStrategy::Isr(invalidate_after)), Some(pool)) => {
+                Some((pool, Some(invalidate_after)))
+            }
+            (None, Some(pool)) => Some((pool, self.invalidate_after)),
         };
-        match self {
-            Self::Renderer(pool) => {
+
+        match incremental {
+            None => {
+                let pool = &self.renderers;
                 let server_context = Box::new(server_context.clone());
                 let mut renderer = pool.write().unwrap().pop().unwrap_or_else(pre_renderer);
 
-                let (tx, rx) = tokio::sync::oneshot::channel();
-
-                spawn_blocking(move || {
-                    tokio::runtime::Runtime::new()
-                        .expect("couldn't spawn runtime")
-                        .block_on(async move {
-                            let mut vdom = VirtualDom::new_with_props(component, props);
-                            // Make sure the evaluator is initialized
-                            dioxus_ssr::eval::init_eval(vdom.base_scope());
-                            let mut to = WriteBuffer { buffer: Vec::new() };
-                            // before polling the future, we need to set the context
-                            let prev_context =
-                                SERVER_CONTEXT.with(|ctx| ctx.replace(server_context));
-                            // poll the future, which may call server_context()
-                            tracing::info!("Rebuilding vdom");
-                            let _ = vdom.rebuild();
-                            vdom.wait_for_suspense().await;
-                            tracing::info!("Suspense resolved");
-                            // after polling the future, we need to restore the context
-                            SERVER_CONTEXT.with(|ctx| ctx.replace(prev_context));
-
-                            if let Err(err) = wrapper.render_before_body(&mut *to) {
-                                let _ = tx.send(Err(err));
-                                return;
-                            }
-                            if let Err(err) = renderer.render_to(&mut to, &vdom) {
-                                let _ = tx.send(Err(
-                                    dioxus_router::prelude::IncrementalRendererError::RenderError(
-                                        err,
-                                    ),
-                                ));
-                                return;
-                            }
-                            if let Err(err) = wrapper.render_after_body(&mut *to) {
-                                let _ = tx.send(Err(err));
-                                return;
-                            }
-                            match String::from_utf8(to.buffer) {
-                                Ok(html) => {
-                                    let _ =
-                                        tx.send(Ok((renderer, RenderFreshness::now(None), html)));
-                                }
-                                Err(err) => {
-                                    dioxus_ssr::incremental::IncrementalRendererError::Other(
-                                        Box::new(err),
-                                    );
-                                }
-                            }
-                        });
-                });
-                let (renderer, freshness, html) = rx.await.unwrap()?;
+                let (renderer, freshness, html) = crate::runtime::run_local(move || async move {
+                    let mut vdom = VirtualDom::new_with_props(component, props);
+                    // Make sure the evaluator is initialized
+                    dioxus_ssr::eval::init_eval(vdom.base_scope());
+                    let mut to = WriteBuffer { buffer: Vec::new() };
+                    // before polling the future, we need to set the context
+                    let prev_context = SERVER_CONTEXT.with(|ctx| ctx.replace(server_context));
+                    // poll the future, which may call server_context()
+                    tracing::info!("Rebuilding vdom");
+                    let _ = vdom.rebuild();
+                    vdom.wait_for_suspense_with_timeouts(|duration| {
+                        crate::runtime::runtime().sleep(duration)
+                    })
+                    .await;
+                    tracing::info!("Suspense resolved");
+                    crate::server_context::server_context()
+                        .set_timed_out_suspense(vdom.timed_out_suspense_boundaries());
+                    // after polling the future, we need to restore the context
+                    SERVER_CONTEXT.with(|ctx| ctx.replace(prev_context));
+
+                    wrapper.render_before_body(&mut *to)?;
+                    renderer
+                        .render_to(&mut to, &vdom)
+                        .map_err(dioxus_router::prelude::IncrementalRendererError::RenderError)?;
+                    wrapper.render_after_body(&mut *to)?;
+                    let html = String::from_utf8(to.buffer).map_err(|err| {
+                        dioxus_ssr::incremental::IncrementalRendererError::Other(Box::new(err))
+                    })?;
+                    Ok((renderer, RenderFreshness::now(None), html))
+                })
+                .await
+                .map_err(|err| {
+                    dioxus_ssr::incremental::IncrementalRendererError::Other(Box::new(err))
+                })??;
                 pool.write().unwrap().push(renderer);
                 Ok((freshness, html))
             }
-            Self::Incremental(pool) => {
-                let mut renderer =
-                    pool.write().unwrap().pop().unwrap_or_else(|| {
-                        incremental_pre_renderer(cfg.incremental.as_ref().unwrap())
-                    });
-
-                let (tx, rx) = tokio::sync::oneshot::channel();
+            Some((pool, invalidate_after)) => {
+                let mut renderer = pool
+                    .write()
+                    .unwrap()
+                    .pop()
+                    .unwrap_or_else(|| incremental_pre_renderer(&incremental_config(cfg)));
+                renderer.set_invalidate_after(invalidate_after);
 
                 let server_context = server_context.clone();
-                spawn_blocking(move || {
-                    tokio::runtime::Runtime::new()
-                        .expect("couldn't spawn runtime")
-                        .block_on(async move {
-                            let mut to = WriteBuffer { buffer: Vec::new() };
-                            match renderer
-                                .render(
-                                    route,
-                                    component,
-                                    props,
-                                    &mut *to,
-                                    |vdom| {
-                                        Box::pin(async move {
-                                            // before polling the future, we need to set the context
-                                            let prev_context = SERVER_CONTEXT
-                                                .with(|ctx| ctx.replace(Box::new(server_context)));
-                                            // poll the future, which may call server_context()
-                                            tracing::info!("Rebuilding vdom");
-                                            let _ = vdom.rebuild();
-                                            vdom.wait_for_suspense().await;
-                                            tracing::info!("Suspense resolved");
-                                            // after polling the future, we need to restore the context
-                                            SERVER_CONTEXT.with(|ctx| ctx.replace(prev_context));
-                                        })
-                                    },
-                                    &wrapper,
-                                )
-                                .await
-                            {
-                                Ok(freshness) => {
-                                    match String::from_utf8(to.buffer).map_err(|err| {
-                                        dioxus_ssr::incremental::IncrementalRendererError::Other(
-                                            Box::new(err),
-                                        )
-                                    }) {
-                                        Ok(html) => {
-                                            let _ = tx.send(Ok((freshness, html)));
-                                        }
-                                        Err(err) => {
-                                            let _ = tx.send(Err(err));
-                                        }
-                                    }
-                                }
-                                Err(err) => {
-                                    let _ = tx.send(Err(err));
-                                }
-                            }
-                        })
-                });
-                let (freshness, html) = rx.await.unwrap()?;
-
+                let (renderer, freshness, html) = crate::runtime::run_local(move || async move {
+                    let mut to = WriteBuffer { buffer: Vec::new() };
+                    let freshness = renderer
+                        .render(
+                            cache_key,
+                            component,
+                            props,
+                            &mut *to,
+                            |vdom| {
+                                Box::pin(async move {
+                                    // before polling the future, we need to set the context
+                                    let prev_context = SERVER_CONTEXT
+                                        .with(|ctx| ctx.replace(Box::new(server_context)));
+                                    // poll the future, which may call server_context()
+                                    tracing::info!("Rebuilding vdom");
+                                    let _ = vdom.rebuild();
+                                    vdom.wait_for_suspense_with_timeouts(|duration| {
+                                        crate::runtime::runtime().sleep(duration)
+                                    })
+                                    .await;
+                                    tracing::info!("Suspense resolved");
+                                    crate::server_context::server_context().set_timed_out_suspense(
+                                        vdom.timed_out_suspense_boundaries(),
+                                    );
+                                    // after polling the future, we need to restore the context
+                                    SERVER_CONTEXT.with(|ctx| ctx.replace(prev_context));
+                                })
+                            },
+                            &wrapper,
+                        )
+                        .await?;
+                    let html = String::from_utf8(to.buffer).map_err(|err| {
+                        dioxus_ssr::incremental::IncrementalRendererError::Other(Box::new(err))
+                    })?;
+                    Ok((renderer, freshness, html))
+                })
+                .await
+                .map_err(|err| {
+                    dioxus_ssr::incremental::IncrementalRendererError::Other(Box::new(err))
+                })??;
+                pool.write().unwrap().push(renderer);
                 Ok((freshness, html))
             }
         }
@@ -168,24 +170,31 @@ pub struct SSRState {
 impl SSRState {
     /// Create a new [`SSRState`].
     pub fn new<P: Clone>(cfg: &ServeConfig<P>) -> Self {
-        if cfg.incremental.is_some() {
-            return Self {
-                renderers: Arc::new(SsrRendererPool::Incremental(RwLock::new(vec![
-                    incremental_pre_renderer(cfg.incremental.as_ref().unwrap()),
-                    incremental_pre_renderer(cfg.incremental.as_ref().unwrap()),
-                    incremental_pre_renderer(cfg.incremental.as_ref().unwrap()),
-                    incremental_pre_renderer(cfg.incremental.as_ref().unwrap()),
-                ]))),
-            };
-        }
+        // Routes are only cached if the app is incremental, or if some of its routes are statically generated
+        let incremental = (cfg.incremental.is_some() || cfg.render_strategy.is_some()).then(|| {
+            let incremental = incremental_config(cfg);
+            vec![
+                incremental_pre_renderer(&incremental),
+                incremental_pre_renderer(&incremental),
+                incremental_pre_renderer(&incremental),
+                incremental_pre_renderer(&incremental),
+            ]
+        });
+        let invalidate_after = incremental
+            .as_ref()
+            .and_then(|renderers| renderers[0].invalidate_after());
 
         Self {
-            renderers: Arc::new(SsrRendererPool::Renderer(RwLock::new(vec![
-                pre_renderer(),
-                pre_renderer(),
-                pre_renderer(),
-                pre_renderer(),
-            ]))),
+            renderers: Arc::new(SsrRendererPool {
+                renderers: RwLock::new(vec![
+                    pre_renderer(),
+                    pre_renderer(),
+                    pre_renderer(),
+                    pre_renderer(),
+                ]),
+                incremental: incremental.map(RwLock::new),
+                invalidate_after,
+            }),
         }
     }
 
@@ -215,6 +224,8 @@ impl SSRState {
 struct FullstackRenderer<P: Clone + Send + Sync + 'static> {
     cfg: ServeConfig<P>,
     server_context: DioxusServerContext,
+    // The page is sent without its body, so the client renders it instead of hydrating it
+    client_rendered: bool,
 }
 
 impl<P: Clone + Serialize + Send + Sync + 'static> dioxus_ssr::incremental::WrapBody
@@ -226,7 +237,22 @@ impl<P: Clone + Serialize + Send + Sync + 'static> dioxus_ssr::incremental::Wrap
     ) -> Result<(), dioxus_ssr::incremental::IncrementalRendererError> {
         let ServeConfig { index, .. } = &self.cfg;
 
-        to.write_all(index.pre_main.as_bytes())?;
+        // Resource hints go at the end of the head so the browser sees them before it parses the body
+        let hints = self.server_context.resource_hints();
+        match index.pre_main.find("</head>") {
+            Some(head_end) if !hints.is_empty() => {
+                let mut links = String::new();
+                for hint in &hints {
+                    hint.write_html(&mut links).map_err(|err| {
+                        dioxus_ssr::incremental::IncrementalRendererError::Other(Box::new(err))
+                    })?;
+                }
+                to.write_all(index.pre_main[..head_end].as_bytes())?;
+                to.write_all(links.as_bytes())?;
+                to.write_all(index.pre_main[head_end..].as_bytes())?;
+            }
+            _ => to.write_all(index.pre_main.as_bytes())?,
+        }
 
         Ok(())
     }
@@ -259,6 +285,11 @@ impl<P: Clone + Serialize + Send + Sync + 'static> dioxus_ssr::incremental::Wrap
             })?,
             to,
         )?;
+        // tell the client which suspense boundaries show their fallback
+        crate::html_storage::serialize::encode_timed_out_suspense(
+            &self.server_context.timed_out_suspense(),
+            to,
+        )?;
 
         #[cfg(all(debug_assertions, feature = "hot-reload"))]
         {
@@ -294,6 +325,10 @@ impl<P: Clone + Serialize + Send + Sync + 'static> dioxus_ssr::incremental::Wrap
             to.write_all(r#"</script>"#.as_bytes())?;
         }
 
+        if self.client_rendered {
+            crate::html_storage::serialize::encode_client_rendered(to)?;
+        }
+
         let ServeConfig { index, .. } = &self.cfg;
 
         to.write_all(index.post_main.as_bytes())?;
@@ -327,6 +362,14 @@ fn pre_renderer() -> Renderer {
     renderer.into()
 }
 
+/// The incremental config of the app, or the default one if only some of its routes are cached
+fn incremental_config<P: Clone>(cfg: &ServeConfig<P>) -> IncrementalRendererConfig {
+    cfg.incremental
+        .as_deref()
+        .cloned()
+        .unwrap_or_else(IncrementalRendererConfig::new)
+}
+
 fn incremental_pre_renderer(
     cfg: &IncrementalRendererConfig,
 ) -> dioxus_ssr::incremental::IncrementalRenderer {
@@ -347,6 +390,7 @@ where
     let wrapper = FullstackRenderer {
         cfg: cfg.clone(),
         server_context: Default::default(),
+        client_rendered: false,
     };
     let mut renderer = incremental_pre_renderer(
         cfg.incremental
@@ -381,3 +425,86 @@ impl std::ops::DerefMut for WriteBuffer {
         &mut self.buffer
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const INDEX: &str = r#"<html><head></head><body><div id="main"></div></body></html>"#;
+
+    fn app(cx: Scope) -> Element {
+        render! { "Hello from the server" }
+    }
+
+    static STATIC_RENDERS: AtomicUsize = AtomicUsize::new(0);
+
+    fn static_app(cx: Scope) -> Element {
+        STATIC_RENDERS.fetch_add(1, Ordering::SeqCst);
+        render! { "Hello from the server" }
+    }
+
+    fn config(
+        app: Component,
+        static_dir: &tempfile::TempDir,
+        strategy: impl Fn(&str) -> RenderStrategy + Send + Sync + 'static,
+    ) -> ServeConfig<()> {
+        ServeConfigBuilder::new(app, ())
+            .index_html(INDEX)
+            .incremental(IncrementalRendererConfig::new().static_dir(static_dir.path()))
+            .render_strategy(strategy)
+            .build()
+    }
+
+    async fn render(state: &SSRState, cfg: &ServeConfig<()>, route: &str) -> RenderResponse {
+        state
+            .render(route.to_string(), cfg, &DioxusServerContext::default())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn client_rendered_pages_have_no_body() {
+        let static_dir = tempfile::tempdir().unwrap();
+        let cfg = config(app, &static_dir, |_| RenderStrategy::Csr);
+        let state = SSRState::new(&cfg);
+
+        let response = render(&state, &cfg, "/").await;
+        assert!(!response.html().contains("Hello from the server"));
+        assert!(response.html().contains(r#"id="dioxus-client-rendered""#));
+        assert_eq!(response.freshness().max_age(), None);
+    }
+
+    #[tokio::test]
+    async fn static_pages_are_cached_by_path() {
+        let static_dir = tempfile::tempdir().unwrap();
+        let cfg = config(static_app, &static_dir, |_| RenderStrategy::Ssg);
+        let state = SSRState::new(&cfg);
+
+        let first = render(&state, &cfg, "/static?page=1").await;
+        let second = render(&state, &cfg, "/static?page=2").await;
+        assert!(first.html().contains("Hello from the server"));
+        assert_eq!(first.html(), second.html());
+        assert_eq!(STATIC_RENDERS.load(Ordering::SeqCst), 1);
+        assert!(static_dir.path().join("static").exists());
+        assert!(!static_dir.path().join("static?page=1").exists());
+    }
+
+    #[tokio::test]
+    async fn regenerated_pages_expire_without_changing_other_routes() {
+        let static_dir = tempfile::tempdir().unwrap();
+        let cfg = config(app, &static_dir, |url| match url {
+            "/regenerated" => RenderStrategy::Isr(Duration::from_secs(60)),
+            _ => RenderStrategy::Ssg,
+        });
+        let state = SSRState::new(&cfg);
+
+        // Render more pages than there are pooled renderers, so the renderer the regenerated page used is reused
+        let regenerated = render(&state, &cfg, "/regenerated").await;
+        assert_eq!(regenerated.freshness().max_age(), Some(60));
+        for page in 0..8 {
+            let response = render(&state, &cfg, &format!("/static/{page}")).await;
+            assert_eq!(response.freshness().max_age(), None);
+        }
+    }
+}
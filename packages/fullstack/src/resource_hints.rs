@@ -0,0 +1,250 @@
+//! Resource hints that tell the browser what it will need soon.
+//!
+//! Hints declared while rendering on the server are collected and written into the `<head>` of the page, so the
+//! browser starts fetching before it has parsed the body. Hints declared in the browser are added to the document
+//! as `<link>` elements when the component that declares them is first rendered. A hint is only added once, no
+//! matter how many components declare it.
+//!
+//! ```rust
+//! use dioxus::prelude::*;
+//! use dioxus_fullstack::prelude::*;
+//!
+//! fn Gallery(cx: Scope) -> Element {
+//!     use_resource_hint(cx, ResourceHint::preconnect("https://images.example.com"));
+//!
+//!     render! {
+//!         Preload { href: "/hero.avif", kind: "image" }
+//!         img { src: "/hero.avif" }
+//!     }
+//! }
+//! ```
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+
+/// The kind of a [`ResourceHint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HintRel {
+    /// Fetch a resource the current page needs with a high priority
+    Preload,
+    /// Fetch a javascript module the current page needs and compile it
+    ModulePreload,
+    /// Fetch a resource a future navigation will probably need with a low priority
+    Prefetch,
+    /// Open a connection to an origin the page will fetch from
+    Preconnect,
+    /// Resolve the DNS of an origin the page will fetch from
+    DnsPrefetch,
+}
+
+impl HintRel {
+    /// The value of the `rel` attribute for this kind of hint
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Preload => "preload",
+            Self::ModulePreload => "modulepreload",
+            Self::Prefetch => "prefetch",
+            Self::Preconnect => "preconnect",
+            Self::DnsPrefetch => "dns-prefetch",
+        }
+    }
+}
+
+/// A `<link>` that hints to the browser that it should fetch a resource or connect to an origin early.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResourceHint {
+    rel: HintRel,
+    href: String,
+    kind: Option<String>,
+    mime_type: Option<String>,
+    crossorigin: Option<String>,
+}
+
+impl ResourceHint {
+    /// Create a new hint with the given kind and url.
+    pub fn new(rel: HintRel, href: impl Into<String>) -> Self {
+        Self {
+            rel,
+            href: href.into(),
+            kind: None,
+            mime_type: None,
+            crossorigin: None,
+        }
+    }
+
+    /// Preload a resource the current page needs. Set the kind of resource with [`ResourceHint::kind`].
+    pub fn preload(href: impl Into<String>) -> Self {
+        Self::new(HintRel::Preload, href)
+    }
+
+    /// Preload a javascript module the current page needs.
+    pub fn module_preload(href: impl Into<String>) -> Self {
+        Self::new(HintRel::ModulePreload, href)
+    }
+
+    /// Prefetch a resource a future navigation will probably need.
+    pub fn prefetch(href: impl Into<String>) -> Self {
+        Self::new(HintRel::Prefetch, href)
+    }
+
+    /// Open a connection to an origin early.
+    pub fn preconnect(origin: impl Into<String>) -> Self {
+        Self::new(HintRel::Preconnect, origin)
+    }
+
+    /// Resolve the DNS of an origin early.
+    pub fn dns_prefetch(origin: impl Into<String>) -> Self {
+        Self::new(HintRel::DnsPrefetch, origin)
+    }
+
+    /// Set the kind of resource that is fetched (the `as` attribute), like `image`, `script`, `style`, `font`, or
+    /// `fetch`.
+    pub fn kind(mut self, kind: impl Into<String>) -> Self {
+        self.kind = Some(kind.into());
+        self
+    }
+
+    /// Set the MIME type of the resource, so the browser can skip resources it does not support.
+    pub fn mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    /// Set the CORS mode of the request, either `anonymous` or `use-credentials`. Fonts must always be fetched with
+    /// CORS.
+    pub fn crossorigin(mut self, crossorigin: impl Into<String>) -> Self {
+        self.crossorigin = Some(crossorigin.into());
+        self
+    }
+
+    /// The kind of hint
+    pub fn rel(&self) -> HintRel {
+        self.rel
+    }
+
+    /// The url of the resource or origin
+    pub fn href(&self) -> &str {
+        &self.href
+    }
+
+    /// The attributes of the `<link>` element for this hint
+    fn attributes(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            Some(("rel", self.rel.as_str())),
+            Some(("href", self.href.as_str())),
+            self.kind.as_deref().map(|value| ("as", value)),
+            self.mime_type.as_deref().map(|value| ("type", value)),
+            self.crossorigin
+                .as_deref()
+                .map(|value| ("crossorigin", value)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Write the hint as a `<link>` element.
+    #[cfg(feature = "ssr")]
+    pub(crate) fn write_html(&self, to: &mut impl std::fmt::Write) -> std::fmt::Result {
+        write!(to, "<link")?;
+        for (name, value) in self.attributes() {
+            write!(to, " {name}=\"")?;
+            for c in value.chars() {
+                match c {
+                    '&' => to.write_str("&amp;")?,
+                    '"' => to.write_str("&quot;")?,
+                    '<' => to.write_str("&lt;")?,
+                    '>' => to.write_str("&gt;")?,
+                    c => to.write_char(c)?,
+                }
+            }
+            write!(to, "\"")?;
+        }
+        write!(to, ">")
+    }
+}
+
+/// Declare a resource hint for the current page.
+///
+/// The hint is only declared when the component is first rendered.
+pub fn use_resource_hint(cx: &ScopeState, hint: ResourceHint) {
+    cx.use_hook(move || add_resource_hint(hint));
+}
+
+#[cfg(feature = "ssr")]
+fn add_resource_hint(hint: ResourceHint) {
+    crate::prelude::server_context().push_resource_hint(hint);
+}
+
+#[cfg(all(feature = "web", not(feature = "ssr")))]
+fn add_resource_hint(hint: ResourceHint) {
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return;
+    };
+    let Some(head) = document.head() else {
+        return;
+    };
+
+    // The server may have already written this hint into the page
+    let selector = format!(
+        "link[rel=\"{}\"][href=\"{}\"]",
+        hint.rel.as_str(),
+        hint.href.replace('\\', "\\\\").replace('"', "\\\"")
+    );
+    if let Ok(Some(_)) = head.query_selector(&selector) {
+        return;
+    }
+
+    let Ok(link) = document.create_element("link") else {
+        return;
+    };
+    for (name, value) in hint.attributes() {
+        let _ = link.set_attribute(name, value);
+    }
+    let _ = head.append_child(&link);
+}
+
+#[cfg(not(any(feature = "ssr", feature = "web")))]
+fn add_resource_hint(_: ResourceHint) {
+    // Other renderers load resources from the local filesystem, so there is nothing to hint
+}
+
+/// The props for the resource hint components
+#[derive(Props, PartialEq)]
+pub struct ResourceHintProps<'a> {
+    /// The url of the resource
+    href: &'a str,
+    /// The kind of resource that is fetched, like `image`, `script`, `style`, `font`, or `fetch`
+    kind: Option<&'a str>,
+    /// The MIME type of the resource
+    mime_type: Option<&'a str>,
+    /// The CORS mode of the request, either `anonymous` or `use-credentials`
+    crossorigin: Option<&'a str>,
+}
+
+impl ResourceHintProps<'_> {
+    fn hint(&self, rel: HintRel) -> ResourceHint {
+        let mut hint = ResourceHint::new(rel, self.href);
+        hint.kind = self.kind.map(ToString::to_string);
+        hint.mime_type = self.mime_type.map(ToString::to_string);
+        hint.crossorigin = self.crossorigin.map(ToString::to_string);
+        hint
+    }
+}
+
+/// Preload a resource the current page needs.
+pub fn Preload<'a>(cx: Scope<'a, ResourceHintProps<'a>>) -> Element<'a> {
+    use_resource_hint(cx, cx.props.hint(HintRel::Preload));
+    None
+}
+
+/// Prefetch a resource a future navigation will probably need.
+pub fn Prefetch<'a>(cx: Scope<'a, ResourceHintProps<'a>>) -> Element<'a> {
+    use_resource_hint(cx, cx.props.hint(HintRel::Prefetch));
+    None
+}
+
+/// Open a connection to an origin the page will fetch from.
+pub fn Preconnect<'a>(cx: Scope<'a, ResourceHintProps<'a>>) -> Element<'a> {
+    use_resource_hint(cx, cx.props.hint(HintRel::Preconnect));
+    None
+}
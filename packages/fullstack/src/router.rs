@@ -98,3 +98,19 @@ where
         }
     }
 }
+
+/// Prefetch the server rendered page for a route the user will probably visit next.
+///
+/// This declares a [`Prefetch`](crate::prelude::Prefetch) hint for the route, so it shares the same deduplication as
+/// every other resource hint on the page. Opening the route in a new tab or reloading after navigating to it is then
+/// served from the browser's cache.
+pub fn use_prefetch_route<R>(cx: &ScopeState, route: R)
+where
+    R: dioxus_router::prelude::Routable,
+    <R as std::str::FromStr>::Err: std::fmt::Display,
+{
+    crate::prelude::use_resource_hint(
+        cx,
+        crate::prelude::ResourceHint::prefetch(route.to_string()).kind("document"),
+    );
+}
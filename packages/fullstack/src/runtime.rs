@@ -0,0 +1,161 @@
+//! The async runtime the server renders pages and runs server functions on.
+//!
+//! Rendering a [`VirtualDom`](dioxus::prelude::VirtualDom) and calling a server function produce futures that are not
+//! `Send`, so they need a runtime that can run them on a single thread. On native targets this is tokio. Serverless
+//! platforms like Cloudflare Workers or WASI hosts run on a single threaded wasm runtime instead, which can be set with
+//! [`set_runtime`] before the first request is handled.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// A boxed future that is not `Send`
+pub type LocalBoxFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+/// A task that runs on a single thread. The closure is sent to that thread, and creates the future there.
+pub type LocalTask = Box<dyn FnOnce() -> LocalBoxFuture<()> + Send>;
+
+/// An async runtime that can run the futures of the server, which are not `Send`
+pub trait Runtime: Send + Sync + 'static {
+    /// Run `task` to completion in the background. The future the task creates doesn't need to be `Send`, so it must
+    /// be polled on the thread it was created on.
+    fn spawn_local(&self, task: LocalTask);
+
+    /// Create a future that resolves after `duration`. This is polled from inside the tasks passed to
+    /// [`Runtime::spawn_local`].
+    fn sleep(&self, duration: Duration) -> LocalBoxFuture<()>;
+}
+
+static RUNTIME: once_cell::sync::OnceCell<Box<dyn Runtime>> = once_cell::sync::OnceCell::new();
+
+/// Set the runtime the server uses. This must be called before the first page is rendered or server function is
+/// called, and can only be called once. Returns `false` if a runtime was already set.
+///
+/// On native targets the server uses [`TokioRuntime`] by default, and on `wasm32-unknown-unknown` it uses
+/// [`WasmRuntime`]. Other targets, like `wasm32-wasi`, must set a runtime.
+pub fn set_runtime(runtime: impl Runtime) -> bool {
+    RUNTIME.set(Box::new(runtime)).is_ok()
+}
+
+/// Get the runtime the server uses
+pub(crate) fn runtime() -> &'static dyn Runtime {
+    RUNTIME
+        .get_or_init(|| {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                Box::new(TokioRuntime::default())
+            }
+            #[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
+            {
+                Box::new(WasmRuntime)
+            }
+            #[cfg(all(target_arch = "wasm32", target_os = "wasi"))]
+            {
+                panic!("No runtime was set for the server. Set one with `dioxus_fullstack::runtime::set_runtime`")
+            }
+        })
+        .as_ref()
+}
+
+/// Run `task` on the runtime of the server and wait for its output
+pub(crate) async fn run_local<T, F, Fut>(task: F) -> Result<T, RuntimeError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = T> + 'static,
+{
+    let (tx, rx) = futures_channel::oneshot::channel();
+    runtime().spawn_local(Box::new(move || {
+        Box::pin(async move {
+            let _ = tx.send(task().await);
+        })
+    }));
+    rx.await.map_err(|_| RuntimeError::Cancelled)
+}
+
+/// An error that can occur when running a task on the runtime of the server
+#[derive(Debug, thiserror::Error)]
+pub enum RuntimeError {
+    /// The task was dropped before it finished, usually because it panicked
+    #[error("The task was cancelled before it finished")]
+    Cancelled,
+}
+
+/// A runtime that runs tasks on a pool of threads, each with its own single threaded tokio runtime
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone)]
+pub struct TokioRuntime {
+    pool: tokio_util::task::LocalPoolHandle,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl TokioRuntime {
+    /// Create a runtime with `threads` threads
+    pub fn new(threads: usize) -> Self {
+        Self {
+            pool: tokio_util::task::LocalPoolHandle::new(threads),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for TokioRuntime {
+    /// Create a runtime with a thread for each core
+    fn default() -> Self {
+        Self::new(
+            std::thread::available_parallelism()
+                .map(Into::into)
+                .unwrap_or(1),
+        )
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Runtime for TokioRuntime {
+    fn spawn_local(&self, task: LocalTask) {
+        let _ = self.pool.spawn_pinned(task);
+    }
+
+    fn sleep(&self, duration: Duration) -> LocalBoxFuture<()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// A runtime for JavaScript hosts like Cloudflare Workers and Deno. Tasks run on the event loop of the host, and
+/// timers use `setTimeout`.
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WasmRuntime;
+
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
+impl Runtime for WasmRuntime {
+    fn spawn_local(&self, task: LocalTask) {
+        wasm_bindgen_futures::spawn_local(task());
+    }
+
+    fn sleep(&self, duration: Duration) -> LocalBoxFuture<()> {
+        use wasm_bindgen::JsCast;
+
+        let promise = js_sys::Promise::new(&mut |resolve, _| {
+            // Workers don't have a window, so setTimeout is read from the global object
+            let set_timeout = js_sys::Reflect::get(&js_sys::global(), &"setTimeout".into())
+                .ok()
+                .and_then(|set_timeout| set_timeout.dyn_into::<js_sys::Function>().ok());
+            match set_timeout {
+                Some(set_timeout) => {
+                    let _ = set_timeout.call2(
+                        &wasm_bindgen::JsValue::UNDEFINED,
+                        &resolve,
+                        &(duration.as_millis() as f64).into(),
+                    );
+                }
+                None => {
+                    let _ = resolve.call0(&wasm_bindgen::JsValue::UNDEFINED);
+                }
+            }
+        });
+        Box::pin(async move {
+            let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+        })
+    }
+}
@@ -6,9 +6,36 @@ use crate::router::*;
 use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 use dioxus::prelude::*;
 
+use crate::compression::EncodingOptions;
+
+/// How the server renders a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderStrategy {
+    /// Render the page on every request (server side rendering)
+    Ssr,
+    /// Send the page without rendering it, and let the client render it (client side rendering)
+    Csr,
+    /// Render the page once and serve it from the incremental cache until it is invalidated (static site generation)
+    Ssg,
+    /// Render the page and serve it from the incremental cache until it is older than the duration (incremental
+    /// static regeneration)
+    Isr(Duration),
+}
+
+impl Default for RenderStrategy {
+    fn default() -> Self {
+        Self::Ssr
+    }
+}
+
+/// How the page at a url is rendered, and the key it is cached under if it is statically generated or regenerated
+pub(crate) type RenderStrategyFn = Arc<dyn Fn(&str) -> (RenderStrategy, String) + Send + Sync>;
+
 /// A ServeConfig is used to configure how to serve a Dioxus application. It contains information about how to serve static assets, and what content to render with [`dioxus-ssr`].
 #[derive(Clone)]
 pub struct ServeConfigBuilder<P: Clone> {
@@ -16,9 +43,12 @@ pub struct ServeConfigBuilder<P: Clone> {
     pub(crate) props: P,
     pub(crate) root_id: Option<&'static str>,
     pub(crate) index_path: Option<&'static str>,
+    pub(crate) index_html: Option<&'static str>,
     pub(crate) assets_path: Option<&'static str>,
     pub(crate) incremental:
         Option<std::sync::Arc<dioxus_ssr::incremental::IncrementalRendererConfig>>,
+    pub(crate) encoding: EncodingOptions,
+    pub(crate) render_strategy: Option<RenderStrategyFn>,
 }
 
 /// A template for incremental rendering that does nothing.
@@ -51,6 +81,29 @@ where
     pub fn new_with_router(cfg: FullstackRouterConfig<R>) -> Self {
         Self::new(RouteWithCfg::<R>, cfg)
     }
+
+    /// Choose how each route is rendered, so one app can mix statically generated, server rendered and client
+    /// rendered pages. Urls that don't parse as a route are server rendered, so the router can show its error page.
+    ///
+    /// Statically generated and regenerated pages are cached by their route, so query parameters the route doesn't
+    /// have don't create more copies of the page.
+    ///
+    /// ```rust, ignore
+    /// ServeConfigBuilder::new_with_router(FullstackRouterConfig::<Route>::default())
+    ///     .route_render_strategy(|route| match route {
+    ///         Route::Home {} | Route::Pricing {} => RenderStrategy::Ssg,
+    ///         Route::Blog { .. } => RenderStrategy::Isr(Duration::from_secs(60)),
+    ///         Route::Dashboard { .. } => RenderStrategy::Csr,
+    ///         _ => RenderStrategy::Ssr,
+    ///     })
+    /// ```
+    pub fn route_render_strategy(mut self, strategy: fn(&R) -> RenderStrategy) -> Self {
+        self.render_strategy = Some(Arc::new(move |url| match url.parse::<R>() {
+            Ok(route) => (strategy(&route), route.to_string()),
+            Err(_) => (RenderStrategy::Ssr, url.to_string()),
+        }));
+        self
+    }
 }
 
 impl<P: Clone> ServeConfigBuilder<P> {
@@ -61,8 +114,11 @@ impl<P: Clone> ServeConfigBuilder<P> {
             props,
             root_id: None,
             index_path: None,
+            index_html: None,
             assets_path: None,
             incremental: None,
+            encoding: EncodingOptions::default(),
+            render_strategy: None,
         }
     }
 
@@ -72,12 +128,41 @@ impl<P: Clone> ServeConfigBuilder<P> {
         self
     }
 
+    /// Choose how the page at each url is rendered. Urls are passed with their query string.
+    ///
+    /// Pages that are statically generated or regenerated are cached by the incremental renderer under their path,
+    /// without the query string. If [`ServeConfigBuilder::incremental`] isn't set, the default [`IncrementalRendererConfig`](dioxus_ssr::incremental::IncrementalRendererConfig) is used.
+    pub fn render_strategy(
+        mut self,
+        strategy: impl Fn(&str) -> RenderStrategy + Send + Sync + 'static,
+    ) -> Self {
+        self.render_strategy = Some(Arc::new(move |url| {
+            let path = url
+                .split(|c| c == '?' || c == '#')
+                .next()
+                .unwrap_or_default();
+            (strategy(url), path.to_string())
+        }));
+        self
+    }
+
     /// Set the path of the index.html file to be served. (defaults to {assets_path}/index.html)
     pub fn index_path(mut self, index_path: &'static str) -> Self {
         self.index_path = Some(index_path);
         self
     }
 
+    /// Set the contents of the index.html file, instead of reading it from [`ServeConfigBuilder::index_path`]. This is
+    /// useful on platforms without a file system, like Cloudflare Workers.
+    ///
+    /// ```rust, ignore
+    /// ServeConfigBuilder::new(app, ()).index_html(include_str!("../dist/index.html"))
+    /// ```
+    pub fn index_html(mut self, contents: &'static str) -> Self {
+        self.index_html = Some(contents);
+        self
+    }
+
     /// Set the id of the root element in the index.html file to place the prerendered content into. (defaults to main)
     pub fn root_id(mut self, root_id: &'static str) -> Self {
         self.root_id = Some(root_id);
@@ -90,6 +175,18 @@ impl<P: Clone> ServeConfigBuilder<P> {
         self
     }
 
+    /// Set whether server rendered pages are compressed with brotli or gzip when the client accepts it. (defaults to true)
+    pub fn compression(mut self, enabled: bool) -> Self {
+        self.encoding.compression = enabled;
+        self
+    }
+
+    /// Set whether server rendered pages are sent with an ETag so unchanged pages are answered with `304 Not Modified`. (defaults to true)
+    pub fn etag(mut self, enabled: bool) -> Self {
+        self.encoding.etag = enabled;
+        self
+    }
+
     /// Build the ServeConfig
     pub fn build(self) -> ServeConfig<P> {
         let assets_path = self.assets_path.unwrap_or("dist");
@@ -101,7 +198,10 @@ impl<P: Clone> ServeConfigBuilder<P> {
 
         let root_id = self.root_id.unwrap_or("main");
 
-        let index = load_index_html(index_path, root_id);
+        let index = match self.index_html {
+            Some(contents) => parse_index_html(contents, root_id),
+            None => load_index_html(index_path, root_id),
+        };
 
         ServeConfig {
             app: self.app,
@@ -109,6 +209,8 @@ impl<P: Clone> ServeConfigBuilder<P> {
             index,
             assets_path,
             incremental: self.incremental,
+            encoding: self.encoding,
+            render_strategy: self.render_strategy,
         }
     }
 }
@@ -120,6 +222,10 @@ fn load_index_html(path: PathBuf, root_id: &'static str) -> IndexHtml {
     file.read_to_string(&mut contents)
         .expect("Failed to read index.html");
 
+    parse_index_html(&contents, root_id)
+}
+
+fn parse_index_html(contents: &str, root_id: &'static str) -> IndexHtml {
     let (pre_main, post_main) = contents.split_once(&format!("id=\"{root_id}\"")).unwrap_or_else(|| panic!("Failed to find id=\"{root_id}\" in index.html. The id is used to inject the application into the page."));
 
     let post_main = post_main.split_once('>').unwrap_or_else(|| {
@@ -153,6 +259,15 @@ pub struct ServeConfig<P: Clone> {
     pub(crate) assets_path: &'static str,
     pub(crate) incremental:
         Option<std::sync::Arc<dioxus_ssr::incremental::IncrementalRendererConfig>>,
+    pub(crate) encoding: EncodingOptions,
+    pub(crate) render_strategy: Option<RenderStrategyFn>,
+}
+
+impl<P: Clone> ServeConfig<P> {
+    /// Get how the page at `url` is rendered and the key it is cached under, or `None` if no strategy was configured
+    pub(crate) fn render_strategy(&self, url: &str) -> Option<(RenderStrategy, String)> {
+        self.render_strategy.as_ref().map(|strategy| strategy(url))
+    }
 }
 
 impl<P: Clone> From<ServeConfigBuilder<P>> for ServeConfig<P> {
@@ -1,4 +1,5 @@
 use crate::html_storage::HTMLData;
+use crate::resource_hints::ResourceHint;
 pub use server_fn_impl::*;
 use std::sync::Arc;
 use std::sync::RwLock;
@@ -15,6 +16,8 @@ pub struct DioxusServerContext {
     response_parts: std::sync::Arc<std::sync::RwLock<http::response::Parts>>,
     pub(crate) parts: Arc<RwLock<http::request::Parts>>,
     html_data: Arc<RwLock<HTMLData>>,
+    resource_hints: Arc<RwLock<Vec<ResourceHint>>>,
+    timed_out_suspense: Arc<RwLock<Vec<usize>>>,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -27,6 +30,8 @@ impl Default for DioxusServerContext {
             )),
             parts: std::sync::Arc::new(RwLock::new(http::request::Request::new(()).into_parts().0)),
             html_data: Arc::new(RwLock::new(HTMLData::default())),
+            resource_hints: Default::default(),
+            timed_out_suspense: Default::default(),
         }
     }
 }
@@ -49,6 +54,8 @@ mod server_fn_impl {
                     http::response::Response::new(()).into_parts().0,
                 )),
                 html_data: Arc::new(RwLock::new(HTMLData::default())),
+                resource_hints: Default::default(),
+                timed_out_suspense: Default::default(),
             }
         }
 
@@ -119,6 +126,38 @@ mod server_fn_impl {
         pub(crate) fn html_data(&self) -> LockResult<RwLockReadGuard<'_, HTMLData>> {
             self.html_data.read()
         }
+
+        /// Add a resource hint to the head of the page that is being rendered
+        pub(crate) fn push_resource_hint(&self, hint: ResourceHint) {
+            if let Ok(mut hints) = self.resource_hints.write() {
+                if !hints.contains(&hint) {
+                    hints.push(hint);
+                }
+            }
+        }
+
+        /// Get the resource hints that were declared while rendering
+        pub(crate) fn resource_hints(&self) -> Vec<ResourceHint> {
+            self.resource_hints
+                .read()
+                .map(|hints| hints.clone())
+                .unwrap_or_default()
+        }
+
+        /// Remember the suspense boundaries that timed out while the page was rendered
+        pub(crate) fn set_timed_out_suspense(&self, timed_out: Vec<usize>) {
+            if let Ok(mut boundaries) = self.timed_out_suspense.write() {
+                *boundaries = timed_out;
+            }
+        }
+
+        /// Get the suspense boundaries that timed out while the page was rendered
+        pub(crate) fn timed_out_suspense(&self) -> Vec<usize> {
+            self.timed_out_suspense
+                .read()
+                .map(|boundaries| boundaries.clone())
+                .unwrap_or_default()
+        }
     }
 }
 
@@ -0,0 +1,595 @@
+//! Cookie based sessions for server functions and server side rendering.
+//!
+//! The browser only ever sees a random session id in a signed, `HttpOnly` cookie. The data for the session lives in a
+//! pluggable [`SessionStore`] on the server. When a user logs in, a second cookie with the user's id is set so the
+//! client can render its login state without a round trip (see [`use_login_state`](crate::prelude::use_login_state)).
+//! That cookie is signed with the same key as the session cookie, and the server ignores it if the signature is wrong.
+//!
+//! ```rust, no_run
+//! use dioxus_fullstack::prelude::*;
+//!
+//! // Once when the server starts
+//! SessionConfig::new(b"a secret key that is at least 32 bytes long").install();
+//!
+//! #[server]
+//! async fn login(user: String) -> Result<(), ServerFnError> {
+//!     let session = use_session().await?;
+//!     session.login(user).await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use http::header::{COOKIE, SET_COOKIE, VARY};
+use http::{HeaderMap, HeaderValue};
+use once_cell::sync::OnceCell;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::Sha256;
+
+use crate::prelude::{server_context, DioxusServerContext, FromServerContext};
+
+pub use crate::hooks::login_state::LOGIN_COOKIE;
+
+/// The bytes that are escaped in the login cookie. Everything but the unreserved characters of RFC 3986.
+const LOGIN_COOKIE_ESCAPED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+static SESSION_CONFIG: OnceCell<SessionConfig> = OnceCell::new();
+
+/// An error that can occur while loading or saving a session.
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    /// No [`SessionConfig`] was installed or provided in the server context
+    #[error("Sessions are not configured. Call `SessionConfig::install` when the server starts")]
+    NotConfigured,
+    /// A value in the session could not be serialized or deserialized
+    #[error("Failed to serialize session data: {0}")]
+    Serialization(#[from] serde_json::Error),
+    /// The session store failed
+    #[error("Session store error: {0}")]
+    Store(String),
+}
+
+impl From<SessionError> for server_fn::ServerFnError {
+    fn from(err: SessionError) -> Self {
+        server_fn::ServerFnError::ServerError(err.to_string())
+    }
+}
+
+/// The data that is persisted for a session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, serde::Deserialize)]
+pub struct SessionRecord {
+    /// The values stored in the session, serialized as JSON
+    pub data: HashMap<String, String>,
+    /// The id of the logged in user, if any
+    pub user_id: Option<String>,
+    /// When the session expires
+    pub expires: SystemTime,
+}
+
+impl SessionRecord {
+    fn new(max_age: Duration) -> Self {
+        Self {
+            data: HashMap::new(),
+            user_id: None,
+            expires: SystemTime::now() + max_age,
+        }
+    }
+
+    /// Check if the session has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires <= SystemTime::now()
+    }
+}
+
+/// A backend that persists sessions between requests.
+#[async_trait::async_trait]
+pub trait SessionStore: Send + Sync + 'static {
+    /// Load the session with the given id. Returns `None` if the session does not exist.
+    async fn load(&self, id: &str) -> Result<Option<SessionRecord>, SessionError>;
+
+    /// Create or replace the session with the given id.
+    async fn save(&self, id: &str, record: &SessionRecord) -> Result<(), SessionError>;
+
+    /// Remove the session with the given id.
+    async fn destroy(&self, id: &str) -> Result<(), SessionError>;
+}
+
+/// A [`SessionStore`] that keeps sessions in memory. Sessions are lost when the server restarts.
+#[derive(Default)]
+pub struct MemoryStore {
+    sessions: RwLock<HashMap<String, SessionRecord>>,
+}
+
+impl MemoryStore {
+    /// Create an empty memory store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for MemoryStore {
+    async fn load(&self, id: &str) -> Result<Option<SessionRecord>, SessionError> {
+        let mut sessions = self.sessions.write().unwrap();
+        // Prune expired sessions while we hold the lock
+        sessions.retain(|_, record| !record.is_expired());
+        Ok(sessions.get(id).cloned())
+    }
+
+    async fn save(&self, id: &str, record: &SessionRecord) -> Result<(), SessionError> {
+        self.sessions
+            .write()
+            .unwrap()
+            .insert(id.to_string(), record.clone());
+        Ok(())
+    }
+
+    async fn destroy(&self, id: &str) -> Result<(), SessionError> {
+        self.sessions.write().unwrap().remove(id);
+        Ok(())
+    }
+}
+
+/// The `SameSite` attribute of the session cookies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    /// Only send the cookie for requests from the same site
+    Strict,
+    /// Send the cookie for requests from the same site and top level navigations
+    Lax,
+    /// Send the cookie with every request. Requires a secure cookie.
+    None,
+}
+
+/// How sessions are stored and how the session cookie is built.
+#[derive(Clone)]
+pub struct SessionConfig {
+    key: Arc<[u8]>,
+    store: Arc<dyn SessionStore>,
+    cookie_name: String,
+    max_age: Duration,
+    secure: bool,
+    same_site: SameSite,
+}
+
+impl SessionConfig {
+    /// Create a new session config that signs cookies with `key` and keeps sessions in a [`MemoryStore`].
+    ///
+    /// The key should be at least 32 random bytes and must stay the same between restarts, or every session will be lost.
+    pub fn new(key: impl AsRef<[u8]>) -> Self {
+        Self {
+            key: Arc::from(key.as_ref()),
+            store: Arc::new(MemoryStore::new()),
+            cookie_name: "dioxus_session".to_string(),
+            max_age: Duration::from_secs(60 * 60 * 24 * 7),
+            secure: true,
+            same_site: SameSite::Lax,
+        }
+    }
+
+    /// Set the store sessions are persisted in
+    pub fn store(mut self, store: impl SessionStore) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Set the name of the session cookie (defaults to `dioxus_session`)
+    pub fn cookie_name(mut self, name: impl Into<String>) -> Self {
+        self.cookie_name = name.into();
+        self
+    }
+
+    /// Set how long a session lives after it was last changed (defaults to one week)
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Set whether the cookies are only sent over https (defaults to true)
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Set the `SameSite` attribute of the cookies (defaults to [`SameSite::Lax`])
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = same_site;
+        self
+    }
+
+    /// Use this config for every request that does not provide its own config in the server context.
+    ///
+    /// This can only be called once. Later calls are ignored.
+    pub fn install(self) {
+        if SESSION_CONFIG.set(self).is_err() {
+            tracing::warn!("A session config was already installed. Ignoring the new config.");
+        }
+    }
+
+    /// A MAC of `value` for the cookie `name`. The name is part of the MAC so a signed value can't be moved to another cookie.
+    fn mac(&self, name: &str, value: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key");
+        mac.update(name.as_bytes());
+        mac.update(b"=");
+        mac.update(value.as_bytes());
+        mac
+    }
+
+    /// Sign `value` for the cookie `name`. The value must not be changed by percent encoding.
+    fn sign(&self, name: &str, value: &str) -> String {
+        let signature = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(self.mac(name, value).finalize().into_bytes());
+        format!("{value}.{signature}")
+    }
+
+    /// Check the signature of the cookie `name` and return the value that was signed
+    fn verify(&self, name: &str, cookie: &str) -> Option<String> {
+        let (value, signature) = cookie.rsplit_once('.')?;
+        let signature = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(signature)
+            .ok()?;
+        // verify_slice compares in constant time
+        self.mac(name, value).verify_slice(&signature).ok()?;
+        Some(value.to_string())
+    }
+
+    fn cookie(&self, name: &str, value: &str, max_age: Duration, http_only: bool) -> String {
+        let mut cookie = format!(
+            "{name}={value}; Path=/; Max-Age={}; SameSite={:?}",
+            max_age.as_secs(),
+            self.same_site
+        );
+        if http_only {
+            cookie.push_str("; HttpOnly");
+        }
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+        cookie
+    }
+}
+
+struct SessionState {
+    id: Option<String>,
+    record: SessionRecord,
+}
+
+/// The session state of a request, stored in the server context. This does not hold the context itself to avoid a reference cycle.
+#[derive(Clone)]
+struct SessionCache {
+    state: Arc<RwLock<SessionState>>,
+    config: SessionConfig,
+}
+
+/// The session of the current request.
+///
+/// Get the session with [`use_session`] or by extracting it from the server context. Changes to the session are saved
+/// to the store immediately and the session cookie is added to the response.
+#[derive(Clone)]
+pub struct Session {
+    state: Arc<RwLock<SessionState>>,
+    config: SessionConfig,
+    context: DioxusServerContext,
+}
+
+impl Session {
+    /// Load the session for the request in `context`.
+    pub async fn load(context: &DioxusServerContext) -> Result<Self, SessionError> {
+        if let Some(SessionCache { state, config }) = context.get::<SessionCache>() {
+            return Ok(Self {
+                state,
+                config,
+                context: context.clone(),
+            });
+        }
+
+        let config = context
+            .get::<SessionConfig>()
+            .or_else(|| SESSION_CONFIG.get().cloned())
+            .ok_or(SessionError::NotConfigured)?;
+
+        let id = context
+            .request_parts()
+            .ok()
+            .and_then(|parts| request_cookie(&parts.headers, &config.cookie_name))
+            .and_then(|cookie| config.verify(&config.cookie_name, &cookie));
+
+        let mut state = SessionState {
+            id: None,
+            record: SessionRecord::new(config.max_age),
+        };
+        if let Some(id) = id {
+            if let Some(record) = config.store.load(&id).await? {
+                if !record.is_expired() {
+                    state = SessionState {
+                        id: Some(id),
+                        record,
+                    };
+                }
+            }
+        }
+
+        let state = Arc::new(RwLock::new(state));
+
+        // Cache the session so every extraction in this request shares it
+        let cache = SessionCache {
+            state: state.clone(),
+            config: config.clone(),
+        };
+        if let Err(err) = context.clone().insert(cache) {
+            tracing::error!("Failed to cache the session in the server context: {}", err);
+        }
+
+        Ok(Self {
+            state,
+            config,
+            context: context.clone(),
+        })
+    }
+
+    /// The id of the session, or `None` if nothing has been stored in it yet
+    pub fn id(&self) -> Option<String> {
+        self.state.read().unwrap().id.clone()
+    }
+
+    /// The id of the logged in user
+    pub fn user_id(&self) -> Option<String> {
+        self.state.read().unwrap().record.user_id.clone()
+    }
+
+    /// Check if a user is logged in
+    pub fn is_logged_in(&self) -> bool {
+        self.user_id().is_some()
+    }
+
+    /// Get a value from the session
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, SessionError> {
+        let state = self.state.read().unwrap();
+        match state.record.data.get(key) {
+            Some(value) => Ok(Some(serde_json::from_str(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Store a value in the session
+    pub async fn insert<T: Serialize>(&self, key: &str, value: &T) -> Result<(), SessionError> {
+        let value = serde_json::to_string(value)?;
+        self.state
+            .write()
+            .unwrap()
+            .record
+            .data
+            .insert(key.to_string(), value);
+        self.save().await
+    }
+
+    /// Remove a value from the session
+    pub async fn remove(&self, key: &str) -> Result<(), SessionError> {
+        self.state.write().unwrap().record.data.remove(key);
+        self.save().await
+    }
+
+    /// Log in as `user_id`.
+    ///
+    /// The session gets a new id to prevent session fixation.
+    pub async fn login(&self, user_id: impl Into<String>) -> Result<(), SessionError> {
+        let old_id = {
+            let mut state = self.state.write().unwrap();
+            state.record.user_id = Some(user_id.into());
+            state.id.take()
+        };
+        if let Some(old_id) = old_id {
+            self.config.store.destroy(&old_id).await?;
+        }
+        self.save().await
+    }
+
+    /// Log out and destroy the session
+    pub async fn logout(&self) -> Result<(), SessionError> {
+        let old_id = {
+            let mut state = self.state.write().unwrap();
+            state.record = SessionRecord::new(self.config.max_age);
+            state.id.take()
+        };
+        if let Some(old_id) = old_id {
+            self.config.store.destroy(&old_id).await?;
+        }
+
+        self.set_cookie(&self.config.cookie_name, "", Duration::ZERO, true);
+        self.set_cookie(LOGIN_COOKIE, "", Duration::ZERO, false);
+        Ok(())
+    }
+
+    /// Persist the session and add the cookies to the response
+    async fn save(&self) -> Result<(), SessionError> {
+        let (id, record) = {
+            let mut state = self.state.write().unwrap();
+            state.record.expires = SystemTime::now() + self.config.max_age;
+            let id = state.id.get_or_insert_with(new_session_id).clone();
+            (id, state.record.clone())
+        };
+        self.config.store.save(&id, &record).await?;
+
+        let max_age = self.config.max_age;
+        self.set_cookie(
+            &self.config.cookie_name,
+            &self.config.sign(&self.config.cookie_name, &id),
+            max_age,
+            true,
+        );
+        match &record.user_id {
+            Some(user_id) => {
+                let value = utf8_percent_encode(user_id, LOGIN_COOKIE_ESCAPED).to_string();
+                let value = self.config.sign(LOGIN_COOKIE, &value);
+                self.set_cookie(LOGIN_COOKIE, &value, max_age, false)
+            }
+            None => self.set_cookie(LOGIN_COOKIE, "", Duration::ZERO, false),
+        }
+        Ok(())
+    }
+
+    fn set_cookie(&self, name: &str, value: &str, max_age: Duration, http_only: bool) {
+        let cookie = self.config.cookie(name, value, max_age, http_only);
+        let Ok(value) = HeaderValue::from_str(&cookie) else {
+            tracing::error!("Invalid session cookie: {}", cookie);
+            return;
+        };
+        if let Ok(mut parts) = self.context.response_parts_mut() {
+            // Replace any cookie with the same name that was set earlier in this request
+            let prefix = format!("{name}=");
+            let mut headers: Vec<_> = parts
+                .headers
+                .get_all(SET_COOKIE)
+                .iter()
+                .filter(|header| !header.as_bytes().starts_with(prefix.as_bytes()))
+                .cloned()
+                .collect();
+            headers.push(value);
+            parts.headers.remove(SET_COOKIE);
+            for header in headers {
+                parts.headers.append(SET_COOKIE, header);
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl FromServerContext for Session {
+    type Rejection = SessionError;
+
+    async fn from_request(req: &DioxusServerContext) -> Result<Self, Self::Rejection> {
+        Session::load(req).await
+    }
+}
+
+/// Get the session of the current request.
+///
+/// This function will only work if it is called from a server function or on the server rendering a request.
+pub async fn use_session() -> Result<Session, SessionError> {
+    Session::load(&server_context()).await
+}
+
+/// Copy the headers set through the server context to a response
+pub(crate) fn merge_response_headers(headers: &HeaderMap, response: &mut HeaderMap) {
+    for (key, value) in headers.iter() {
+        // Multiple cookies can be set in one response, so they must not replace each other
+        if key == SET_COOKIE {
+            response.append(key, value.clone());
+        } else if key == VARY {
+            crate::compression::append_vary(response, value);
+        } else {
+            response.insert(key, value.clone());
+        }
+    }
+}
+
+/// Find the value of a cookie in the request headers
+pub(crate) fn request_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .flat_map(|header| header.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
+
+/// The id of the user in the login cookie of the request in `context`, if the cookie was signed with the session key
+pub(crate) fn verified_login(context: &DioxusServerContext) -> Option<String> {
+    let config = context
+        .get::<SessionConfig>()
+        .or_else(|| SESSION_CONFIG.get().cloned())?;
+    let parts = context.request_parts().ok()?;
+    let cookie = request_cookie(&parts.headers, LOGIN_COOKIE)?;
+    let user_id = config.verify(LOGIN_COOKIE, &cookie)?;
+    percent_encoding::percent_decode_str(&user_id)
+        .decode_utf8()
+        .ok()
+        .map(|user_id| user_id.into_owned())
+}
+
+fn new_session_id() -> String {
+    let mut bytes = [0u8; 32];
+    rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut bytes);
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SessionConfig {
+        SessionConfig::new(b"a secret key that is at least 32 bytes long")
+    }
+
+    fn request_with_login(config: &SessionConfig, login: &str) -> DioxusServerContext {
+        let (parts, _) = http::Request::builder()
+            .header(COOKIE, format!("{LOGIN_COOKIE}={login}"))
+            .body(())
+            .unwrap()
+            .into_parts();
+        let mut context = DioxusServerContext::new(parts);
+        context.insert(config.clone()).unwrap();
+        context
+    }
+
+    #[test]
+    fn signed_login_cookies_are_trusted() {
+        let config = config();
+        let cookie = config.sign(LOGIN_COOKIE, "ada%40example.com");
+        assert_eq!(
+            verified_login(&request_with_login(&config, &cookie)).as_deref(),
+            Some("ada@example.com")
+        );
+    }
+
+    #[test]
+    fn forged_login_cookies_are_rejected() {
+        let config = config();
+        let signed = config.sign(LOGIN_COOKIE, "ada");
+        let (_, signature) = signed.rsplit_once('.').unwrap();
+        let other_key = SessionConfig::new(b"another key that is at least 32 bytes long");
+
+        for forged in [
+            // no signature at all
+            "mallory".to_string(),
+            "mallory.".to_string(),
+            // the signature of another user
+            format!("mallory.{signature}"),
+            // signed with another key
+            other_key.sign(LOGIN_COOKIE, "mallory"),
+            // a session cookie signature can't be reused as a login cookie
+            config.sign(&config.cookie_name, "mallory"),
+        ] {
+            assert_eq!(
+                verified_login(&request_with_login(&config, &forged)),
+                None,
+                "{forged} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn login_cookies_are_ignored_without_a_session_config() {
+        let config = config();
+        let cookie = config.sign(LOGIN_COOKIE, "ada");
+        let (parts, _) = http::Request::builder()
+            .header(COOKIE, format!("{LOGIN_COOKIE}={cookie}"))
+            .body(())
+            .unwrap()
+            .into_parts();
+        // The global config is never installed in these tests
+        assert_eq!(verified_login(&DioxusServerContext::new(parts)), None);
+    }
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "openapi")]
+
+use dioxus_fullstack::openapi::{schemars::JsonSchema, OpenApi};
+use dioxus_fullstack::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(crate = "dioxus_fullstack::openapi::schemars")]
+struct Post {
+    title: String,
+    likes: u32,
+}
+
+/// Add two numbers
+#[server(AddNumbers, "/api")]
+async fn add_numbers(a: i32, b: i32) -> Result<i32, ServerFnError> {
+    Ok(a + b)
+}
+
+#[server(GetPost, "/api", "GetJson")]
+async fn get_post(id: u32) -> Result<Post, ServerFnError> {
+    Ok(Post {
+        title: format!("Post {id}"),
+        likes: 0,
+    })
+}
+
+fn document() -> serde_json::Value {
+    OpenApi::new("Blog", "1.0.0")
+        .server_fn_route("/api")
+        .build()
+}
+
+#[test]
+fn server_fns_are_described() {
+    let document = document();
+    assert_eq!(
+        document["info"],
+        json!({ "title": "Blog", "version": "1.0.0" })
+    );
+
+    let add = &document["paths"][format!("/api/{}", AddNumbers::URL)]["post"];
+    assert_eq!(add["operationId"], "add_numbers");
+    assert_eq!(add["description"], "Add two numbers");
+    let body = &add["requestBody"]["content"]["application/x-www-form-urlencoded"]["schema"];
+    assert_eq!(body["required"], json!(["a", "b"]));
+    assert_eq!(body["properties"]["a"]["type"], "integer");
+    assert_eq!(body["properties"]["b"]["type"], "integer");
+    let output = &add["responses"]["200"]["content"]["application/json"]["schema"];
+    assert_eq!(output["type"], "integer");
+
+    let get = &document["paths"][format!("/api/{}", GetPost::URL)]["get"];
+    assert_eq!(get["operationId"], "get_post");
+    assert_eq!(get["parameters"][0]["name"], "id");
+    assert_eq!(get["parameters"][0]["in"], "query");
+    let output = &get["responses"]["200"]["content"]["application/json"]["schema"];
+    assert_eq!(output["$ref"], "#/components/schemas/Post");
+    let post = &document["components"]["schemas"]["Post"];
+    assert_eq!(post["properties"]["title"]["type"], "string");
+    assert_eq!(post["properties"]["likes"]["type"], "integer");
+}
+
+/// Every path in the document must be a route the server handles
+#[cfg(feature = "axum")]
+#[tokio::test]
+async fn documented_paths_are_mounted() {
+    use tower::ServiceExt;
+
+    let path = format!("/api/{}", AddNumbers::URL);
+    assert!(document()["paths"].get(&path).is_some());
+
+    let router = axum::Router::new().register_server_fns("/api");
+    let request = http::Request::post(&path)
+        .header(
+            http::header::CONTENT_TYPE,
+            "application/x-www-form-urlencoded",
+        )
+        .body(hyper::Body::from("a=1&b=2"))
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), http::StatusCode::OK);
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(&body[..], b"3");
+}
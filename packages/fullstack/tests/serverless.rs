@@ -0,0 +1,90 @@
+#![cfg(feature = "serverless")]
+
+use dioxus::prelude::*;
+use dioxus_fullstack::prelude::*;
+use http::{Method, Request, StatusCode};
+use hyper::Body;
+
+const INDEX: &str = r#"<html><body><div id="main"></div></body></html>"#;
+
+#[server(AddNumbers, "/api")]
+async fn add_numbers(a: i32, b: i32) -> Result<i32, ServerFnError> {
+    Ok(a + b)
+}
+
+#[server(GetAnswer, "/api", "GetJson")]
+async fn get_answer() -> Result<i32, ServerFnError> {
+    Ok(42)
+}
+
+fn app(cx: Scope) -> Element {
+    render! { "Hello from the server" }
+}
+
+fn handler() -> DioxusHandler<()> {
+    DioxusHandler::new("/api", ServeConfigBuilder::new(app, ()).index_html(INDEX))
+        .register_server_fn::<AddNumbers>()
+        .register_server_fn::<GetAnswer>()
+}
+
+async fn body(response: http::Response<Body>) -> String {
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+fn add_request(method: Method, path: &str) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(path)
+        .header(
+            http::header::CONTENT_TYPE,
+            "application/x-www-form-urlencoded",
+        )
+        .body(Body::from("a=1&b=2"))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn server_functions_are_called() {
+    let response = handler()
+        .handle(add_request(
+            Method::POST,
+            &format!("/api/{}", AddNumbers::URL),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(response).await, "3");
+
+    let request = Request::get(format!("/api/{}", GetAnswer::URL))
+        .body(Body::empty())
+        .unwrap();
+    let response = handler().handle(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(response).await, "42");
+}
+
+#[tokio::test]
+async fn server_functions_check_the_method() {
+    let response = handler()
+        .handle(add_request(
+            Method::GET,
+            &format!("/api/{}", AddNumbers::URL),
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[tokio::test]
+async fn other_paths_render_the_app() {
+    for path in [
+        "/".to_string(),
+        "/api/unknown".to_string(),
+        // The server function route is a prefix of the path, not of the name
+        format!("/api{}", AddNumbers::URL),
+    ] {
+        let response = handler().handle(add_request(Method::POST, &path)).await;
+        assert_eq!(response.status(), StatusCode::OK, "{}", path);
+        let html = body(response).await;
+        assert!(html.contains("Hello from the server"), "{}", path);
+    }
+}
@@ -0,0 +1,150 @@
+#![cfg(feature = "ssr")]
+
+use dioxus_fullstack::prelude::*;
+use dioxus_fullstack::session::{SessionRecord, LOGIN_COOKIE};
+use http::header::{COOKIE, SET_COOKIE};
+use std::sync::Arc;
+
+const COOKIE_NAME: &str = "dioxus_session";
+
+/// A server context for a request that sends `cookies`, using `config` for its session
+fn request(config: &SessionConfig, cookies: &[(&str, &str)]) -> DioxusServerContext {
+    let mut builder = http::Request::builder();
+    if !cookies.is_empty() {
+        let header = cookies
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+        builder = builder.header(COOKIE, header);
+    }
+    let (parts, _) = builder.body(()).unwrap().into_parts();
+    let mut context = DioxusServerContext::new(parts);
+    context.insert(config.clone()).unwrap();
+    context
+}
+
+/// The value of the cookie set on the response of `context`
+fn response_cookie(context: &DioxusServerContext, name: &str) -> Option<String> {
+    let parts = context.response_parts().unwrap();
+    let prefix = format!("{name}=");
+    parts
+        .headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .find_map(|header| header.strip_prefix(&prefix))
+        .map(|cookie| cookie.split(';').next().unwrap().to_string())
+}
+
+/// Start a session with a value in it, and return the signed session cookie
+async fn start_session(config: &SessionConfig) -> String {
+    let context = request(config, &[]);
+    let session = Session::load(&context).await.unwrap();
+    session.insert("count", &1).await.unwrap();
+    response_cookie(&context, COOKIE_NAME).unwrap()
+}
+
+#[tokio::test]
+async fn signed_cookies_load_the_session() {
+    let config = SessionConfig::new(b"a secret key that is at least 32 bytes long");
+    let cookie = start_session(&config).await;
+
+    let context = request(&config, &[(COOKIE_NAME, &cookie)]);
+    let session = Session::load(&context).await.unwrap();
+    assert_eq!(session.get::<i32>("count").unwrap(), Some(1));
+    assert_eq!(
+        session.id().as_deref(),
+        cookie.rsplit_once('.').map(|(id, _)| id)
+    );
+}
+
+#[tokio::test]
+async fn cookies_signed_with_another_key_are_rejected() {
+    let store = Arc::new(MemoryStore::new());
+    let config = SessionConfig::new(b"a secret key that is at least 32 bytes long")
+        .store(SharedStore(store.clone()));
+    let cookie = start_session(&config).await;
+
+    // The same store, but a different key
+    let other =
+        SessionConfig::new(b"another key that is at least 32 bytes long").store(SharedStore(store));
+    let context = request(&other, &[(COOKIE_NAME, &cookie)]);
+    let session = Session::load(&context).await.unwrap();
+    assert_eq!(session.id(), None);
+    assert_eq!(session.get::<i32>("count").unwrap(), None);
+}
+
+#[tokio::test]
+async fn tampered_ids_are_rejected() {
+    let config = SessionConfig::new(b"a secret key that is at least 32 bytes long");
+    let cookie = start_session(&config).await;
+    let other_cookie = start_session(&config).await;
+    let (id, signature) = cookie.rsplit_once('.').unwrap();
+    let (other_id, _) = other_cookie.rsplit_once('.').unwrap();
+
+    for tampered in [
+        // another valid session id with the wrong signature
+        format!("{other_id}.{signature}"),
+        // a changed id
+        format!("{id}x.{signature}"),
+        // no signature
+        id.to_string(),
+        format!("{id}."),
+        // a changed signature
+        format!("{id}.{}", signature.chars().rev().collect::<String>()),
+    ] {
+        let context = request(&config, &[(COOKIE_NAME, &tampered)]);
+        let session = Session::load(&context).await.unwrap();
+        assert_eq!(session.id(), None, "{tampered} was accepted");
+        assert_eq!(session.get::<i32>("count").unwrap(), None);
+    }
+}
+
+#[tokio::test]
+async fn login_rotates_the_session_id() {
+    let config = SessionConfig::new(b"a secret key that is at least 32 bytes long");
+    let cookie = start_session(&config).await;
+
+    let context = request(&config, &[(COOKIE_NAME, &cookie)]);
+    let session = Session::load(&context).await.unwrap();
+    let old_id = session.id().unwrap();
+    session.login("ada@example.com").await.unwrap();
+
+    let new_id = session.id().unwrap();
+    assert_ne!(new_id, old_id);
+    assert_eq!(session.get::<i32>("count").unwrap(), Some(1));
+    let new_cookie = response_cookie(&context, COOKIE_NAME).unwrap();
+    assert!(new_cookie.starts_with(&format!("{new_id}.")));
+    // The login cookie is signed, so the client can't change the user
+    let login_cookie = response_cookie(&context, LOGIN_COOKIE).unwrap();
+    assert!(login_cookie.starts_with("ada%40example.com."));
+
+    // The old session was destroyed, so the cookie from before the login no longer works
+    let context = request(&config, &[(COOKIE_NAME, &cookie)]);
+    let session = Session::load(&context).await.unwrap();
+    assert_eq!(session.id(), None);
+    assert_eq!(session.user_id(), None);
+
+    let context = request(&config, &[(COOKIE_NAME, &new_cookie)]);
+    let session = Session::load(&context).await.unwrap();
+    assert_eq!(session.user_id().as_deref(), Some("ada@example.com"));
+}
+
+/// A store that can be shared by two configs
+struct SharedStore(Arc<MemoryStore>);
+
+#[async_trait::async_trait]
+impl SessionStore for SharedStore {
+    async fn load(&self, id: &str) -> Result<Option<SessionRecord>, SessionError> {
+        self.0.load(id).await
+    }
+
+    async fn save(&self, id: &str, record: &SessionRecord) -> Result<(), SessionError> {
+        self.0.save(id, record).await
+    }
+
+    async fn destroy(&self, id: &str) -> Result<(), SessionError> {
+        self.0.destroy(id).await
+    }
+}
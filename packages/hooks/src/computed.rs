@@ -38,6 +38,7 @@ use std::{
 /// }
 /// ```
 #[must_use]
+#[track_caller]
 pub fn use_tracked_state<T: 'static>(cx: &ScopeState, init: impl FnOnce() -> T) -> &Tracked<T> {
     cx.use_hook(|| {
         let init = init();
@@ -162,6 +163,7 @@ impl<I> Drop for Tracker<I> {
 }
 
 #[must_use = "Consider using the `use_effect` hook to rerun an effect whenever the tracked state changes if you don't need the result of the computation"]
+#[track_caller]
 pub fn use_selector<I: 'static, O: Clone + PartialEq + 'static>(
     cx: &ScopeState,
     tracked: &Tracked<I>,
@@ -189,6 +191,7 @@ impl<T, I> PartialEq for Selector<T, I> {
 
 impl<T: Clone + PartialEq, I> Selector<T, I> {
     /// Read the Selector state and subscribe to updates
+    #[track_caller]
     pub fn use_state(&self, cx: &ScopeState) -> T {
         cx.use_hook(|| {
             let id = cx.scope_id();
@@ -84,9 +84,15 @@ pub use use_future::*;
 mod use_effect;
 pub use use_effect::*;
 
+mod use_layout_effect;
+pub use use_layout_effect::*;
+
 mod use_callback;
 pub use use_callback::*;
 
+mod use_controlled_input;
+pub use use_controlled_input::*;
+
 mod use_memo;
 pub use use_memo::*;
 
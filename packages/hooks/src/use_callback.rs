@@ -25,6 +25,7 @@ macro_rules! use_callback {
     };
 }
 
+#[track_caller]
 pub fn use_callback<T, R, F>(cx: &ScopeState, make: impl FnOnce() -> R) -> impl FnMut(T) + '_
 where
     R: FnMut(T) -> F + 'static,
@@ -20,6 +20,7 @@ use dioxus_core::prelude::*;
 /// }
 /// ```
 #[must_use]
+#[track_caller]
 pub fn use_const<T: 'static>(
     cx: &ScopeState,
     initial_state_fn: impl FnOnce() -> T,
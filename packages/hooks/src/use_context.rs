@@ -4,6 +4,7 @@ use dioxus_core::ScopeState;
 ///
 /// Does not regenerate the value if the value is changed at the parent.
 #[must_use]
+#[track_caller]
 pub fn use_context<T: 'static + Clone>(cx: &ScopeState) -> Option<&T> {
     cx.use_hook(|| cx.consume_context::<T>()).as_ref()
 }
@@ -11,6 +12,7 @@ pub fn use_context<T: 'static + Clone>(cx: &ScopeState) -> Option<&T> {
 /// Provide some context via the tree and return a reference to it
 ///
 /// Once the context has been provided, it is immutable. Mutations should be done via interior mutability.
+#[track_caller]
 pub fn use_context_provider<T: 'static + Clone>(cx: &ScopeState, f: impl FnOnce() -> T) -> &T {
     cx.use_hook(|| {
         let val = f();
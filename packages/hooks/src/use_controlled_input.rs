@@ -0,0 +1,220 @@
+use dioxus_core::ScopeState;
+use std::{
+    cell::{Ref, RefCell},
+    collections::VecDeque,
+    fmt::{Debug, Display},
+    rc::Rc,
+};
+
+/// The number of typed values that may wait for the state to catch up before the oldest one is forgotten
+const MAX_PENDING: usize = 32;
+
+/// A hook that keeps an input in sync with a controlled value without fighting the user's typing.
+///
+/// A naive controlled input, `input { value: "{text}", oninput: move |evt| text.set(evt.value.clone()) }`, sets the
+/// value of the element every time the state renders. When the state catches up with the typing a render late, or is
+/// updated asynchronously, the element is set to a stale value: the caret jumps to the end, and the characters typed
+/// in between are lost. Writing to the element in the middle of an IME composition also breaks the composition.
+///
+/// `use_controlled_input` renders what the user typed until the state echoes it back, so the element is only written
+/// to when the value really changed from outside the input. While the user composes text with an IME, nothing is
+/// written to the element, and [`UseControlledInput::oninput`] holds back the intermediate text until the
+/// composition ends.
+///
+/// ## Example
+///
+/// ```rust, ignore
+/// fn Search(cx: Scope) -> Element {
+///     let query = use_state(cx, String::new);
+///     let input = use_controlled_input(cx, query.get());
+///
+///     render! {
+///         input {
+///             value: "{input}",
+///             oninput: move |evt| if let Some(value) = input.oninput(evt.value.clone()) {
+///                 query.set(value);
+///             },
+///             oncompositionstart: move |_| input.composition_start(),
+///             oncompositionend: move |_| if let Some(value) = input.composition_end() {
+///                 query.set(value);
+///             },
+///         }
+///     }
+/// }
+/// ```
+pub fn use_controlled_input<'a>(cx: &'a ScopeState, value: &str) -> &'a UseControlledInput {
+    let input = cx.use_hook(|| UseControlledInput {
+        inner: Rc::new(RefCell::new(InputReconciler::new(value))),
+    });
+    input.inner.borrow_mut().reconcile(value);
+    input
+}
+
+/// The handle returned by [`use_controlled_input`]
+#[derive(Clone)]
+pub struct UseControlledInput {
+    inner: Rc<RefCell<InputReconciler>>,
+}
+
+impl UseControlledInput {
+    /// The value to render in the input
+    pub fn value(&self) -> Ref<'_, str> {
+        Ref::map(self.inner.borrow(), |inner| inner.displayed.as_str())
+    }
+
+    /// Record the value of an `input` event. Returns the value to store in the state, or `None` while an IME
+    /// composition is in progress.
+    pub fn oninput(&self, value: String) -> Option<String> {
+        self.inner.borrow_mut().input(value)
+    }
+
+    /// Record the start of an IME composition, from a `compositionstart` event
+    pub fn composition_start(&self) {
+        self.inner.borrow_mut().composing = true;
+    }
+
+    /// Record the end of an IME composition, from a `compositionend` event. Returns the composed value to store in
+    /// the state.
+    pub fn composition_end(&self) -> Option<String> {
+        self.inner.borrow_mut().composition_end()
+    }
+
+    /// If an IME composition is in progress
+    pub fn is_composing(&self) -> bool {
+        self.inner.borrow().composing
+    }
+}
+
+impl PartialEq for UseControlledInput {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Display for UseControlledInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.value())
+    }
+}
+
+impl Debug for UseControlledInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UseControlledInput")
+            .field("value", &&*self.value())
+            .field("composing", &self.is_composing())
+            .finish()
+    }
+}
+
+/// Tracks the value the user sees against the value of the state
+struct InputReconciler {
+    /// The value in the element
+    displayed: String,
+    /// The controlled value the last render saw
+    source: String,
+    /// Values the user typed that the state has not echoed back yet, oldest first
+    pending: VecDeque<String>,
+    composing: bool,
+}
+
+impl InputReconciler {
+    fn new(value: &str) -> Self {
+        Self {
+            displayed: value.to_string(),
+            source: value.to_string(),
+            pending: VecDeque::new(),
+            composing: false,
+        }
+    }
+
+    /// Take in the controlled value of a render
+    fn reconcile(&mut self, value: &str) {
+        if value == self.source {
+            return;
+        }
+        self.source = value.to_string();
+
+        if let Some(echoed) = self.pending.iter().position(|pending| pending == value) {
+            // The state caught up with a value the user typed. The element already shows it, or something typed
+            // after it.
+            self.pending.drain(..=echoed);
+        } else if !self.composing {
+            // The value was changed from outside the input
+            self.pending.clear();
+            self.displayed = self.source.clone();
+        }
+    }
+
+    fn input(&mut self, value: String) -> Option<String> {
+        self.displayed = value;
+        if self.composing {
+            return None;
+        }
+        self.push_pending();
+        Some(self.displayed.clone())
+    }
+
+    fn composition_end(&mut self) -> Option<String> {
+        self.composing = false;
+        if self.displayed == self.source || self.pending.back() == Some(&self.displayed) {
+            return None;
+        }
+        self.push_pending();
+        Some(self.displayed.clone())
+    }
+
+    fn push_pending(&mut self) {
+        if self.pending.len() == MAX_PENDING {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(self.displayed.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn late_echoes_keep_the_typed_value() {
+        let mut input = InputReconciler::new("");
+        assert_eq!(input.input("a".into()), Some("a".into()));
+        assert_eq!(input.input("ab".into()), Some("ab".into()));
+
+        // The state renders the first keystroke after the second one was typed
+        input.reconcile("a");
+        assert_eq!(input.displayed, "ab");
+
+        input.reconcile("ab");
+        assert_eq!(input.displayed, "ab");
+        assert!(input.pending.is_empty());
+    }
+
+    #[test]
+    fn outside_changes_replace_the_value() {
+        let mut input = InputReconciler::new("");
+        input.input("a".into());
+        input.reconcile("cleared");
+        assert_eq!(input.displayed, "cleared");
+        assert!(input.pending.is_empty());
+    }
+
+    #[test]
+    fn compositions_are_committed_when_they_end() {
+        let mut input = InputReconciler::new("");
+        input.composing = true;
+        assert_eq!(input.input("k".into()), None);
+        assert_eq!(input.input("か".into()), None);
+
+        // A render in the middle of the composition doesn't touch the element
+        input.reconcile("other");
+        assert_eq!(input.displayed, "か");
+
+        assert_eq!(input.composition_end(), Some("か".into()));
+        input.reconcile("か");
+        assert_eq!(input.displayed, "か");
+
+        // The input event after the end of the composition commits the same value
+        assert_eq!(input.input("か".into()), Some("か".into()));
+    }
+}
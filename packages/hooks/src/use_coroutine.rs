@@ -63,6 +63,7 @@ use std::future::Future;
 ///     }
 /// })
 /// ```
+#[track_caller]
 pub fn use_coroutine<M, G, F>(cx: &ScopeState, init: G) -> &Coroutine<M>
 where
     M: 'static,
@@ -80,6 +81,7 @@ where
 ///
 /// See the docs for [`use_coroutine`] for more details.
 #[must_use]
+#[track_caller]
 pub fn use_coroutine_handle<M: 'static>(cx: &ScopeState) -> Option<&Coroutine<M>> {
     cx.use_hook(|| cx.consume_context::<Coroutine<M>>())
         .as_ref()
@@ -1,4 +1,4 @@
-use dioxus_core::{ScopeState, TaskId};
+use dioxus_core::{EffectKind, ScopeState, TaskId};
 use std::{
     any::Any,
     cell::{Cell, RefCell},
@@ -14,6 +14,10 @@ use crate::UseFutureDep;
 /// If a future is pending when the dependencies change, the previous future
 /// will be allowed to continue.
 ///
+/// The future is a [passive effect](dioxus_core::EffectKind::Passive): it starts after the render is committed and the
+/// layout effects ran, and before the next event is handled. Use [`use_layout_effect`](crate::use_layout_effect) to
+/// measure the DOM before it is painted.
+///
 /// **Note:** If your dependency list is always empty, use [`use_on_create`](crate::use_on_create).
 ///
 /// ## Arguments
@@ -60,14 +64,15 @@ use crate::UseFutureDep;
 ///     render!(Profile { id: 0 })
 /// }
 /// ```
+#[track_caller]
 pub fn use_effect<T, R, D>(cx: &ScopeState, dependencies: D, future: impl FnOnce(D::Out) -> R)
 where
     D: UseFutureDep,
-    R: UseEffectReturn<T>,
+    R: UseEffectReturn<T> + 'static,
 {
     struct UseEffect {
         needs_regen: bool,
-        task: Cell<Option<TaskId>>,
+        task: Rc<Cell<Option<TaskId>>>,
         dependencies: Vec<Box<dyn Any>>,
         cleanup: UseEffectCleanup,
     }
@@ -82,25 +87,27 @@ where
 
     let state = cx.use_hook(move || UseEffect {
         needs_regen: true,
-        task: Cell::new(None),
+        task: Rc::new(Cell::new(None)),
         dependencies: Vec::new(),
         cleanup: Rc::new(RefCell::new(None)),
     });
 
     if dependencies.clone().apply(&mut state.dependencies) || state.needs_regen {
-        // Call the cleanup function if it exists
-        if let Some(cleanup) = state.cleanup.borrow_mut().take() {
-            cleanup();
-        }
-
         // We don't need regen anymore
         state.needs_regen = false;
 
-        // Create the new future
+        // Create the new future. It is spawned once the render is committed.
         let return_value = future(dependencies.out());
 
-        let task = return_value.apply(state.cleanup.clone(), cx);
-        state.task.set(Some(task));
+        let (task, cleanup) = (state.task.clone(), state.cleanup.clone());
+        cx.queue_effect(EffectKind::Passive, move |cx| {
+            // Call the cleanup function of the previous effect if it exists
+            if let Some(cleanup) = cleanup.borrow_mut().take() {
+                cleanup();
+            }
+
+            task.set(Some(return_value.apply(cleanup, cx)));
+        });
     }
 }
 
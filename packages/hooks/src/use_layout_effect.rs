@@ -0,0 +1,103 @@
+use dioxus_core::{EffectKind, ScopeState};
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use crate::UseFutureDep;
+
+/// A hook that runs a closure after the render is committed to the DOM, but before the browser paints it.
+///
+/// This is a [layout effect](dioxus_core::EffectKind::Layout). It runs synchronously before any
+/// [`use_effect`](crate::use_effect), so it can measure an element and update the state the frame depends on without
+/// the user seeing the frame in between. Prefer [`use_effect`](crate::use_effect) for anything that doesn't read the
+/// layout, because layout effects delay the paint.
+///
+/// The closure runs the first time the component renders, and again every time the `dependencies` change. It may
+/// return a closure that cleans up the effect before it runs again and when the component is unmounted.
+///
+/// ## Examples
+///
+/// ```rust, ignore
+/// #[component]
+/// fn Tooltip(cx: Scope, text: String) -> Element {
+///     let above = use_state(cx, || false);
+///
+///     // Flip the tooltip above its anchor when it would overflow the window, before it is painted below it
+///     use_layout_effect(cx, (text,), {
+///         to_owned![above];
+///         move |_| {
+///             let rect = measure_tooltip();
+///             above.set(rect.bottom > window_height());
+///         }
+///     });
+///
+///     render! { div { class: if **above { "above" } else { "below" }, "{text}" } }
+/// }
+/// ```
+pub fn use_layout_effect<T, R, D>(
+    cx: &ScopeState,
+    dependencies: D,
+    effect: impl FnOnce(D::Out) -> R + 'static,
+) where
+    D: UseFutureDep,
+    D::Out: 'static,
+    R: UseLayoutEffectReturn<T>,
+{
+    struct UseLayoutEffect {
+        needs_regen: bool,
+        dependencies: Vec<Box<dyn Any>>,
+        cleanup: LayoutEffectCleanup,
+    }
+
+    impl Drop for UseLayoutEffect {
+        fn drop(&mut self) {
+            if let Some(cleanup) = self.cleanup.borrow_mut().take() {
+                cleanup();
+            }
+        }
+    }
+
+    let state = cx.use_hook(move || UseLayoutEffect {
+        needs_regen: true,
+        dependencies: Vec::new(),
+        cleanup: Rc::new(RefCell::new(None)),
+    });
+
+    if dependencies.clone().apply(&mut state.dependencies) || state.needs_regen {
+        state.needs_regen = false;
+
+        let dependencies = dependencies.out();
+        let cleanup = state.cleanup.clone();
+        cx.queue_effect(EffectKind::Layout, move |_| {
+            // Call the cleanup function of the previous effect if it exists
+            if let Some(cleanup) = cleanup.borrow_mut().take() {
+                cleanup();
+            }
+
+            let new_cleanup = effect(dependencies).into_cleanup();
+            *cleanup.borrow_mut() = new_cleanup;
+        });
+    }
+}
+
+type LayoutEffectCleanup = Rc<RefCell<Option<Box<dyn FnOnce()>>>>;
+
+/// Something that can be returned from a `use_layout_effect` hook.
+pub trait UseLayoutEffectReturn<T> {
+    fn into_cleanup(self) -> Option<Box<dyn FnOnce()>>;
+}
+
+impl UseLayoutEffectReturn<()> for () {
+    fn into_cleanup(self) -> Option<Box<dyn FnOnce()>> {
+        None
+    }
+}
+
+#[doc(hidden)]
+pub struct LayoutCleanupMarker;
+impl<F> UseLayoutEffectReturn<LayoutCleanupMarker> for F
+where
+    F: FnOnce() + 'static,
+{
+    fn into_cleanup(self) -> Option<Box<dyn FnOnce()>> {
+        Some(Box::new(self))
+    }
+}
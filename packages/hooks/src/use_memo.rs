@@ -29,6 +29,7 @@ use crate::UseFutureDep;
 /// }
 /// ```
 #[must_use = "Consider using `use_effect` to run rerun a callback when dependencies change"]
+#[track_caller]
 pub fn use_memo<T, D>(cx: &ScopeState, dependencies: D, callback: impl FnOnce(D::Out) -> T) -> &T
 where
     T: 'static,
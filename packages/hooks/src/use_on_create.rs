@@ -7,6 +7,7 @@ use std::future::Future;
 /// This is just [`use_effect`](crate::use_effect), but with no dependencies.
 /// If you have no dependencies, it's recommended to use this, not just because it's more readable,
 /// but also because it's a tiny bit more efficient.
+#[track_caller]
 pub fn use_on_create<T, F>(cx: &ScopeState, future: impl FnOnce() -> F)
 where
     T: 'static,
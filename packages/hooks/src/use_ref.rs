@@ -111,6 +111,7 @@ use std::{
 /// })
 /// ```
 #[must_use]
+#[track_caller]
 pub fn use_ref<T: 'static>(cx: &ScopeState, initialize_refcell: impl FnOnce() -> T) -> &UseRef<T> {
     let hook = cx.use_hook(|| UseRef {
         update: cx.schedule_update(),
@@ -1,6 +1,7 @@
 use dioxus_core::ScopeState;
 
 ///
+#[track_caller]
 pub fn use_root_context<T: 'static + Clone>(cx: &ScopeState, new: impl FnOnce() -> T) -> &T {
     cx.use_hook(|| {
         cx.consume_context::<T>()
@@ -31,6 +31,7 @@ use std::{
 /// }
 /// ```
 #[must_use]
+#[track_caller]
 pub fn use_state<T: 'static>(
     cx: &ScopeState,
     initial_state_fn: impl FnOnce() -> T,
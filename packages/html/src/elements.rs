@@ -1156,6 +1156,8 @@ builder_constructors! {
         formnovalidate: Bool DEFAULT,
         formtarget: Target DEFAULT,
         name: Id DEFAULT,
+        popovertarget: Id DEFAULT,
+        popovertargetaction: String DEFAULT,
         value: String DEFAULT,
         r#type: String "type",
     };
@@ -1213,6 +1215,8 @@ builder_constructors! {
         name: Id DEFAULT,
         pattern: String DEFAULT,
         placeholder: String DEFAULT,
+        popovertarget: Id DEFAULT,
+        popovertargetaction: String DEFAULT,
         readonly: Bool DEFAULT,
         required: Bool DEFAULT,
         size: usize DEFAULT,
@@ -1370,9 +1374,12 @@ builder_constructors! {
         open: Bool DEFAULT,
     };
 
-    /// Build dialog
+    /// Build a
     /// [`<dialog>`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/dialog)
     /// element.
+    ///
+    /// Open it as a modal with [`MountedData::show_modal`](crate::MountedData::show_modal) instead of the `open`
+    /// attribute, which only shows a non-modal dialog.
     dialog None {
         open: Bool DEFAULT,
     };
@@ -2,6 +2,8 @@
 
 use async_trait::async_trait;
 use dioxus_core::ScopeState;
+use futures_util::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
 use std::future::{Future, IntoFuture};
 use std::pin::Pin;
 use std::rc::Rc;
@@ -79,6 +81,41 @@ impl UseEval {
         self.evaluator.recv().await
     }
 
+    /// Receive every message the evaluated JavaScript sends with `dioxus.send` as a stream.
+    ///
+    /// This lets a script keep reporting values, like the size of an element from a `ResizeObserver`, without being
+    /// evaluated again for every value. The stream ends after the first error.
+    ///
+    /// ```rust, ignore
+    /// let eval = eval(r#"
+    ///     window.addEventListener("scroll", () => dioxus.send(window.scrollY));
+    /// "#)?;
+    /// let mut positions = eval.stream_as::<f64>();
+    /// while let Some(Ok(position)) = positions.next().await {
+    ///     scroll.set(position);
+    /// }
+    /// ```
+    pub fn stream(&self) -> impl Stream<Item = Result<serde_json::Value, EvalError>> {
+        futures_util::stream::unfold(Some(self.evaluator.clone()), |evaluator| async move {
+            let evaluator = evaluator?;
+            match evaluator.recv().await {
+                Ok(value) => Some((Ok(value), Some(evaluator))),
+                Err(err) => Some((Err(err), None)),
+            }
+        })
+    }
+
+    /// Like [`UseEval::stream`], but deserializes every message into `T`.
+    pub fn stream_as<T: DeserializeOwned>(&self) -> impl Stream<Item = Result<T, EvalError>> {
+        self.stream().map(|message| {
+            message.and_then(|value| {
+                serde_json::from_value(value).map_err(|err| {
+                    EvalError::Communication(format!("Failed to parse message - {}", err))
+                })
+            })
+        })
+    }
+
     /// Gets the return value of the evaluated JavaScript.
     pub async fn join(self) -> Result<serde_json::Value, EvalError> {
         self.evaluator.join().await
@@ -146,6 +146,9 @@ pub fn event_bubbles(evt: &str) -> bool {
         "animationiteration" => true,
         "transitionend" => true,
         "toggle" => true,
+        "beforetoggle" => false,
+        "close" => false,
+        "cancel" => false,
         "mounted" => false,
         _ => true,
     }
@@ -1,10 +1,50 @@
+use std::fmt::Debug;
+
 use dioxus_core::Event;
 
+use crate::FileEngine;
+
 pub type ClipboardEvent = Event<ClipboardData>;
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Clone, Default)]
 pub struct ClipboardData {
-    // DOMDataTransfer clipboardData
+    /// The plain text on the clipboard, if it has any
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub text: Option<String>,
+
+    /// The HTML on the clipboard, if it has any. Copying from a web page or a rich text editor usually puts both
+    /// HTML and plain text on the clipboard.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub html: Option<String>,
+
+    /// The files that were pasted. An image copied from another app is pasted as a file.
+    #[cfg_attr(
+        feature = "serialize",
+        serde(
+            default,
+            skip_serializing,
+            deserialize_with = "crate::events::form::deserialize_file_engine"
+        )
+    )]
+    pub files: Option<std::sync::Arc<dyn FileEngine>>,
+}
+
+impl PartialEq for ClipboardData {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text && self.html == other.html
+    }
+}
+
+impl Eq for ClipboardData {}
+
+impl Debug for ClipboardData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClipboardData")
+            .field("text", &self.text)
+            .field("html", &self.html)
+            .field("files", &self.files.as_ref().map(|files| files.files()))
+            .finish()
+    }
 }
 
 impl_event![
@@ -38,6 +38,33 @@ pub trait RenderedElementBacking {
     fn set_focus(&self, _focus: bool) -> Pin<Box<dyn Future<Output = MountedResult<()>>>> {
         Box::pin(async { Err(MountedError::NotSupported) })
     }
+
+    /// Open the element if it is a `<dialog>`, as a modal if `modal` is true
+    fn show_dialog(&self, _modal: bool) -> Pin<Box<dyn Future<Output = MountedResult<()>>>> {
+        Box::pin(async { Err(MountedError::NotSupported) })
+    }
+
+    /// Close the element if it is a `<dialog>`, and set its return value
+    fn close_dialog(
+        &self,
+        _return_value: Option<String>,
+    ) -> Pin<Box<dyn Future<Output = MountedResult<()>>>> {
+        Box::pin(async { Err(MountedError::NotSupported) })
+    }
+
+    /// Show or hide the element if it has the `popover` attribute
+    fn toggle_popover(&self, _open: bool) -> Pin<Box<dyn Future<Output = MountedResult<()>>>> {
+        Box::pin(async { Err(MountedError::NotSupported) })
+    }
+
+    /// Capture or release the pointer with the given id
+    fn set_pointer_capture(
+        &self,
+        _pointer_id: i32,
+        _capture: bool,
+    ) -> Pin<Box<dyn Future<Output = MountedResult<()>>>> {
+        Box::pin(async { Err(MountedError::NotSupported) })
+    }
 }
 
 impl RenderedElementBacking for () {}
@@ -73,6 +100,28 @@ impl MountedData {
         self.inner.get_raw_element()
     }
 
+    /// Get the renderer specific element if it is a `T`.
+    ///
+    /// Each renderer exposes its own element:
+    /// - web: the [`web_sys::Element`](https://docs.rs/web-sys/latest/web_sys/struct.Element.html)
+    /// - desktop: a `dioxus_desktop::DesktopElement`, which holds the id of the element and can run scripts against it
+    ///   in the webview
+    /// - liveview: a `dioxus_liveview::LiveviewElement`, which can run scripts against the element in the browser
+    /// - TUI: a `dioxus_tui::TuiElement`, which can read the layout of the node
+    ///
+    /// Returns `None` if the renderer exposes another type, or no element at all.
+    ///
+    /// ```rust, ignore
+    /// onmounted: move |event| {
+    ///     if let Some(element) = event.downcast::<web_sys::Element>() {
+    ///         element.set_scroll_top(0);
+    ///     }
+    /// }
+    /// ```
+    pub fn downcast<T: 'static>(&self) -> Option<&T> {
+        self.inner.get_raw_element().ok()?.downcast_ref::<T>()
+    }
+
     /// Get the bounding rectangle of the element relative to the viewport (this does not include the scroll position)
     pub async fn get_client_rect(&self) -> MountedResult<Rect<f64, f64>> {
         self.inner.get_client_rect().await
@@ -90,6 +139,52 @@ impl MountedData {
     pub fn set_focus(&self, focus: bool) -> Pin<Box<dyn Future<Output = MountedResult<()>>>> {
         self.inner.set_focus(focus)
     }
+
+    /// Open the `<dialog>` as a modal. The rest of the page is inert until the dialog is closed, and escape closes it.
+    pub fn show_modal(&self) -> Pin<Box<dyn Future<Output = MountedResult<()>>>> {
+        self.inner.show_dialog(true)
+    }
+
+    /// Open the `<dialog>` without blocking the rest of the page
+    pub fn show_dialog(&self) -> Pin<Box<dyn Future<Output = MountedResult<()>>>> {
+        self.inner.show_dialog(false)
+    }
+
+    /// Close the `<dialog>`. The `close` event of the dialog is fired, and its `returnValue` is set to `return_value`
+    /// if it is given.
+    pub fn close_dialog(
+        &self,
+        return_value: Option<String>,
+    ) -> Pin<Box<dyn Future<Output = MountedResult<()>>>> {
+        self.inner.close_dialog(return_value)
+    }
+
+    /// Show the element with the `popover` attribute in the top layer
+    pub fn show_popover(&self) -> Pin<Box<dyn Future<Output = MountedResult<()>>>> {
+        self.inner.toggle_popover(true)
+    }
+
+    /// Hide the element with the `popover` attribute
+    pub fn hide_popover(&self) -> Pin<Box<dyn Future<Output = MountedResult<()>>>> {
+        self.inner.toggle_popover(false)
+    }
+
+    /// Send the events of the pointer with the given id to this element until it is released, even when the pointer
+    /// leaves the element. The capture ends by itself when the pointer is lifted.
+    pub fn set_pointer_capture(
+        &self,
+        pointer_id: i32,
+    ) -> Pin<Box<dyn Future<Output = MountedResult<()>>>> {
+        self.inner.set_pointer_capture(pointer_id, true)
+    }
+
+    /// Stop capturing the pointer with the given id
+    pub fn release_pointer_capture(
+        &self,
+        pointer_id: i32,
+    ) -> Pin<Box<dyn Future<Output = MountedResult<()>>>> {
+        self.inner.set_pointer_capture(pointer_id, false)
+    }
 }
 
 use dioxus_core::Event;
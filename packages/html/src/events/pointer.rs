@@ -28,6 +28,58 @@ pub struct PointerData {
     pub pointer_type: String,
     pub is_primary: bool,
     // pub get_modifier_state: bool,
+    /// The angle between a pen and the surface in radians, from 0 when the pen lies flat to π/2 when it stands
+    /// upright. It is π/2 for pointers that can't tilt.
+    #[cfg_attr(feature = "serialize", serde(default = "upright"))]
+    pub altitude_angle: f64,
+    /// The angle of a pen around the axis of the surface in radians, from 0 when it points right, clockwise up to 2π
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub azimuth_angle: f64,
+    /// The samples the browser merged into this `pointermove` event, oldest first. Pens report positions faster than
+    /// the page renders, so drawing apps should draw through every sample instead of only the position of the event.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub coalesced_samples: Vec<PointerSample>,
+}
+
+#[cfg(feature = "serialize")]
+fn upright() -> f64 {
+    std::f64::consts::FRAC_PI_2
+}
+
+/// The kind of device of a [`PointerData`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerType {
+    Mouse,
+    Pen,
+    Touch,
+    /// A device the browser doesn't name
+    Other,
+}
+
+impl PointerData {
+    /// The kind of device that caused the event
+    pub fn kind(&self) -> PointerType {
+        match self.pointer_type.as_str() {
+            "mouse" => PointerType::Mouse,
+            "pen" => PointerType::Pen,
+            "touch" => PointerType::Touch,
+            _ => PointerType::Other,
+        }
+    }
+}
+
+/// A position of a pointer between two `pointermove` events
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointerSample {
+    pub client_x: f64,
+    pub client_y: f64,
+    pub pressure: f32,
+    pub tilt_x: i32,
+    pub tilt_y: i32,
+    pub twist: i32,
+    /// When the sample was taken, in milliseconds since the page loaded
+    pub time_stamp: f64,
 }
 
 impl_event![
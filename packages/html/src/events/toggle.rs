@@ -10,4 +10,13 @@ impl_event! {
 
     /// ontoggle
     ontoggle
+
+    /// onbeforetoggle, fired before a popover is shown or hidden
+    onbeforetoggle
+
+    /// onclose, fired when a dialog is closed, including when the user presses escape
+    onclose
+
+    /// oncancel, fired when the user dismisses a modal dialog with escape
+    oncancel
 }
@@ -2,16 +2,44 @@ use dioxus_core::Event;
 
 pub type TouchEvent = Event<TouchData>;
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TouchData {
     pub alt_key: bool,
     pub ctrl_key: bool,
     pub meta_key: bool,
     pub shift_key: bool,
     // get_modifier_state: bool,
-    // changedTouches: DOMTouchList,
-    // targetTouches: DOMTouchList,
-    // touches: DOMTouchList,
+    /// Every point that is touching the surface
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub touches: Vec<TouchPoint>,
+    /// The points that started, moved or ended in this event
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub changed_touches: Vec<TouchPoint>,
+    /// The points that started on the element the listener is on, and are still touching the surface
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub target_touches: Vec<TouchPoint>,
+}
+
+/// A point of contact with a touch surface, like a finger or a stylus
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TouchPoint {
+    /// Identifies the point for as long as it touches the surface
+    pub identifier: i32,
+    pub client_x: f64,
+    pub client_y: f64,
+    pub page_x: f64,
+    pub page_y: f64,
+    pub screen_x: f64,
+    pub screen_y: f64,
+    /// The radius of the ellipse that covers the contact area along the x axis, in CSS pixels
+    pub radius_x: f64,
+    /// The radius of the ellipse that covers the contact area along the y axis, in CSS pixels
+    pub radius_y: f64,
+    /// The clockwise rotation of the contact ellipse in degrees
+    pub rotation_angle: f64,
+    /// How hard the point presses, from 0.0 to 1.0. It is 0.0 on devices that can't measure it.
+    pub force: f64,
 }
 
 impl_event! {
@@ -0,0 +1,390 @@
+//! Read the location of the device with the [Geolocation API](https://developer.mozilla.org/en-US/docs/Web/API/Geolocation_API).
+//!
+//! The location is read through the renderer's JavaScript evaluator, so [`use_geolocation`] works with every renderer
+//! that supports [`use_eval`](crate::prelude::use_eval). In the browser, the browser asks the user for permission.
+//! On desktop, the location comes from `navigator.geolocation` in the webview, just like in a browser. Dioxus doesn't
+//! ask the operating system for the location itself, so whether one is reported depends on the webview and how it
+//! handles the permission request.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dioxus_core::ScopeState;
+use serde::Deserialize;
+
+use crate::prelude::{EvalProvider, UseEval};
+
+/// A position reported by the device
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct Position {
+    /// The latitude in decimal degrees
+    pub latitude: f64,
+    /// The longitude in decimal degrees
+    pub longitude: f64,
+    /// The accuracy of the latitude and longitude, in meters
+    pub accuracy: f64,
+    /// The altitude in meters above the WGS 84 ellipsoid, if the device can measure it
+    pub altitude: Option<f64>,
+    /// The accuracy of the altitude, in meters
+    pub altitude_accuracy: Option<f64>,
+    /// The direction the device is moving in, in degrees clockwise from true north
+    pub heading: Option<f64>,
+    /// The speed of the device, in meters per second
+    pub speed: Option<f64>,
+    /// When the position was measured, in milliseconds since the unix epoch
+    pub timestamp: f64,
+}
+
+/// If the app is allowed to read the location of the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeolocationPermission {
+    /// The platform doesn't report the permission. It becomes [`GeolocationPermission::Granted`] or
+    /// [`GeolocationPermission::Denied`] after the first request.
+    Unknown,
+    /// The user will be asked for permission the next time the location is requested
+    Prompt,
+    /// The app may read the location
+    Granted,
+    /// The user or the platform has denied access to the location
+    Denied,
+}
+
+/// Why the location could not be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GeolocationError {
+    /// The user or the platform denied access to the location
+    PermissionDenied,
+    /// The device could not determine its location
+    PositionUnavailable,
+    /// The device took longer than [`GeolocationOptions::timeout`] to determine its location
+    Timeout,
+    /// The renderer can't read the location of the device
+    Unsupported,
+}
+
+impl GeolocationError {
+    fn from_code(code: u16) -> Self {
+        // These are the codes of the browser's GeolocationPositionError
+        match code {
+            1 => Self::PermissionDenied,
+            2 => Self::PositionUnavailable,
+            3 => Self::Timeout,
+            _ => Self::Unsupported,
+        }
+    }
+}
+
+/// Options for reading the location of the device
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GeolocationOptions {
+    /// Ask the device for the most accurate position it can get. This may be slower and use more power.
+    pub high_accuracy: bool,
+    /// How long to wait for a position before failing with [`GeolocationError::Timeout`]. Wait forever if this is
+    /// `None`.
+    pub timeout: Option<Duration>,
+    /// How old a cached position may be. Always ask for a new position if this is `None`.
+    pub maximum_age: Option<Duration>,
+}
+
+impl GeolocationOptions {
+    fn to_js(self) -> serde_json::Value {
+        let mut options = serde_json::Map::new();
+        options.insert("enableHighAccuracy".into(), self.high_accuracy.into());
+        if let Some(timeout) = self.timeout {
+            options.insert("timeout".into(), (timeout.as_millis() as u64).into());
+        }
+        options.insert(
+            "maximumAge".into(),
+            self.maximum_age
+                .map(|age| age.as_millis() as u64)
+                .unwrap_or_default()
+                .into(),
+        );
+        options.into()
+    }
+}
+
+const PERMISSION_SCRIPT: &str = r#"
+    if (navigator.permissions && navigator.permissions.query) {
+        try {
+            const status = await navigator.permissions.query({ name: "geolocation" });
+            dioxus.send(status.state);
+            status.onchange = () => dioxus.send(status.state);
+            await dioxus.recv();
+            status.onchange = null;
+        } catch (_) {
+            dioxus.send("unknown");
+        }
+    } else {
+        dioxus.send("unknown");
+    }
+"#;
+
+const POSITION_SCRIPT: &str = r#"
+    const options = {OPTIONS};
+    const watch = {WATCH};
+    const position = (p) => ({
+        position: {
+            latitude: p.coords.latitude,
+            longitude: p.coords.longitude,
+            accuracy: p.coords.accuracy,
+            altitude: p.coords.altitude,
+            altitude_accuracy: p.coords.altitudeAccuracy,
+            heading: p.coords.heading,
+            speed: p.coords.speed,
+            timestamp: p.timestamp,
+        },
+    });
+    const error = (e) => ({ error: e.code });
+    if (!navigator.geolocation) {
+        dioxus.send({ error: 0 });
+    } else if (watch) {
+        const id = navigator.geolocation.watchPosition(
+            (p) => dioxus.send(position(p)),
+            (e) => dioxus.send(error(e)),
+            options
+        );
+        await dioxus.recv();
+        navigator.geolocation.clearWatch(id);
+        dioxus.send("stopped");
+    } else {
+        navigator.geolocation.getCurrentPosition(
+            (p) => dioxus.send(position(p)),
+            (e) => dioxus.send(error(e)),
+            options
+        );
+    }
+"#;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PositionMessage {
+    Position(Position),
+    Error(u16),
+    Stopped,
+}
+
+enum Command {
+    Request,
+    Watch,
+}
+
+struct GeolocationInner {
+    permission: Cell<GeolocationPermission>,
+    position: Cell<Option<Position>>,
+    error: Cell<Option<GeolocationError>>,
+    pending: Cell<bool>,
+    permission_eval: RefCell<Option<UseEval>>,
+    watch: RefCell<Option<UseEval>>,
+    commands: async_channel::Sender<Command>,
+    update: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl GeolocationInner {
+    fn set_permission(&self, permission: GeolocationPermission) {
+        if self.permission.replace(permission) != permission {
+            (self.update)();
+        }
+    }
+
+    fn on_message(&self, message: PositionMessage) {
+        match message {
+            PositionMessage::Position(position) => {
+                self.position.set(Some(position));
+                self.error.set(None);
+                self.permission.set(GeolocationPermission::Granted);
+            }
+            PositionMessage::Error(code) => {
+                let error = GeolocationError::from_code(code);
+                if error == GeolocationError::PermissionDenied {
+                    self.permission.set(GeolocationPermission::Denied);
+                }
+                self.error.set(Some(error));
+            }
+            PositionMessage::Stopped => {}
+        }
+        (self.update)();
+    }
+}
+
+impl Drop for GeolocationInner {
+    fn drop(&mut self) {
+        // Let the scripts remove their listeners
+        if let Some(eval) = self.permission_eval.take() {
+            let _ = eval.send(serde_json::Value::Null);
+        }
+        if let Some(watch) = self.watch.take() {
+            let _ = watch.send(serde_json::Value::Null);
+        }
+    }
+}
+
+/// The location of the device and the state of the permission to read it. Created with [`use_geolocation`].
+#[derive(Clone)]
+pub struct UseGeolocation {
+    inner: Rc<GeolocationInner>,
+}
+
+impl UseGeolocation {
+    /// Get if the app is allowed to read the location
+    pub fn permission(&self) -> GeolocationPermission {
+        self.inner.permission.get()
+    }
+
+    /// Get the last position the device reported
+    pub fn position(&self) -> Option<Position> {
+        self.inner.position.get()
+    }
+
+    /// Get the error from the last request, if it failed. This is cleared when a new position is reported.
+    pub fn error(&self) -> Option<GeolocationError> {
+        self.inner.error.get()
+    }
+
+    /// Check if a request started with [`UseGeolocation::request`] is waiting for a position
+    pub fn is_pending(&self) -> bool {
+        self.inner.pending.get()
+    }
+
+    /// Check if the position is being watched
+    pub fn is_watching(&self) -> bool {
+        self.inner.watch.borrow().is_some()
+    }
+
+    /// Read the position of the device once.
+    ///
+    /// If the permission is [`GeolocationPermission::Prompt`], this asks the user for permission. Does nothing while
+    /// the position is being watched.
+    pub fn request(&self) {
+        if self.is_watching() || self.inner.pending.replace(true) {
+            return;
+        }
+        let _ = self.inner.commands.try_send(Command::Request);
+        (self.inner.update)();
+    }
+
+    /// Report every new position of the device until [`UseGeolocation::stop`] is called or the component is
+    /// unmounted.
+    ///
+    /// If the permission is [`GeolocationPermission::Prompt`], this asks the user for permission.
+    pub fn watch(&self) {
+        if !self.is_watching() {
+            let _ = self.inner.commands.try_send(Command::Watch);
+        }
+    }
+
+    /// Stop watching the position of the device
+    pub fn stop(&self) {
+        if let Some(watch) = &*self.inner.watch.borrow() {
+            let _ = watch.send(serde_json::Value::Null);
+        }
+    }
+}
+
+/// Read the location of the device. The component rerenders when a new position is reported or the permission
+/// changes.
+///
+/// Nothing is read until [`UseGeolocation::request`] or [`UseGeolocation::watch`] is called, so the user is only
+/// asked for permission when the app needs the location. The options are read when the component is first rendered.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let geolocation = use_geolocation(cx, GeolocationOptions::default());
+///
+///     render! {
+///         match (geolocation.position(), geolocation.permission()) {
+///             (Some(position), _) => rsx! { "You are at {position.latitude}, {position.longitude}" },
+///             (None, GeolocationPermission::Denied) => rsx! { "Location access was denied" },
+///             (None, _) => rsx! { button { onclick: move |_| geolocation.request(), "Find me" } },
+///         }
+///     }
+/// }
+/// ```
+pub fn use_geolocation(cx: &ScopeState, options: GeolocationOptions) -> &UseGeolocation {
+    cx.use_hook(|| {
+        let provider = cx.consume_context::<Rc<dyn EvalProvider>>();
+        let (commands, receiver) = async_channel::unbounded();
+        let inner = Rc::new(GeolocationInner {
+            permission: Cell::new(GeolocationPermission::Unknown),
+            position: Cell::new(None),
+            error: Cell::new(None),
+            pending: Cell::new(false),
+            permission_eval: RefCell::new(None),
+            watch: RefCell::new(None),
+            commands,
+            update: cx.schedule_update(),
+        });
+
+        let provider = match provider {
+            Some(provider) => provider,
+            None => {
+                inner.error.set(Some(GeolocationError::Unsupported));
+                return UseGeolocation { inner };
+            }
+        };
+
+        if let Ok(eval) = provider.new_evaluator(PERMISSION_SCRIPT.to_string()) {
+            let eval = UseEval::new(eval);
+            *inner.permission_eval.borrow_mut() = Some(eval.clone());
+            cx.spawn({
+                let inner = inner.clone();
+                async move {
+                    while let Ok(value) = eval.recv().await {
+                        if let Ok(permission) = serde_json::from_value(value) {
+                            inner.set_permission(permission);
+                        }
+                    }
+                }
+            });
+        }
+
+        cx.spawn({
+            let inner = inner.clone();
+            async move {
+                while let Ok(command) = receiver.recv().await {
+                    let watch = matches!(command, Command::Watch);
+                    let script = POSITION_SCRIPT
+                        .replace("{OPTIONS}", &options.to_js().to_string())
+                        .replace("{WATCH}", if watch { "true" } else { "false" });
+                    let eval = match provider.new_evaluator(script) {
+                        Ok(eval) => UseEval::new(eval),
+                        Err(_) => {
+                            inner.pending.set(false);
+                            inner.error.set(Some(GeolocationError::Unsupported));
+                            (inner.update)();
+                            continue;
+                        }
+                    };
+                    if watch {
+                        *inner.watch.borrow_mut() = Some(eval.clone());
+                        (inner.update)();
+                    }
+
+                    while let Ok(value) = eval.recv().await {
+                        let message = match serde_json::from_value(value) {
+                            Ok(message) => message,
+                            Err(_) => break,
+                        };
+                        let stopped = matches!(message, PositionMessage::Stopped);
+                        inner.pending.set(false);
+                        inner.on_message(message);
+                        if !watch || stopped {
+                            break;
+                        }
+                    }
+
+                    inner.pending.set(false);
+                    if watch {
+                        inner.watch.borrow_mut().take();
+                        (inner.update)();
+                    }
+                }
+            }
+        });
+
+        UseGeolocation { inner }
+    })
+}
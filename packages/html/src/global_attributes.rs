@@ -198,6 +198,9 @@ trait_methods! {
     /// <https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/id>
     id;
 
+    /// <https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/inert>
+    inert;
+
     /// <https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/inputmode>
     inputmode;
 
@@ -228,6 +231,9 @@ trait_methods! {
     /// <https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/part>
     part;
 
+    /// <https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/popover>
+    popover;
+
     /// <https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/role>
     role;
 
@@ -249,6 +255,10 @@ trait_methods! {
     /// <https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/translate>
     translate;
 
+    /// A tooltip that the desktop renderer shows with the tooltip of the operating system when the pointer rests on
+    /// the element. Other renderers ignore it.
+    native_tooltip: "data-native-tooltip";
+
 
     /// dangerous_inner_html is Dioxus's replacement for using innerHTML in the browser DOM. In general, setting
     /// HTML from code is risky because it’s easy to inadvertently expose your users to a cross-site scripting (XSS)
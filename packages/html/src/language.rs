@@ -0,0 +1,137 @@
+//! Read the languages the user prefers, as [BCP 47](https://www.rfc-editor.org/info/bcp47) language tags like
+//! `en-US`, ordered from most to least preferred.
+//!
+//! The languages are read from `navigator.languages` through the renderer's JavaScript evaluator. In the browser
+//! they come from the browser settings. On desktop, the webview reports the languages of the operating system.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use dioxus_core::ScopeState;
+
+use crate::prelude::{EvalProvider, UseEval};
+
+const LANGUAGE_SCRIPT: &str = r#"
+    const languages = () =>
+        (navigator.languages && navigator.languages.length
+            ? Array.from(navigator.languages)
+            : [navigator.language]
+        ).filter(Boolean);
+    const onChange = () => dioxus.send(languages());
+    dioxus.send(languages());
+    window.addEventListener("languagechange", onChange);
+    await dioxus.recv();
+    window.removeEventListener("languagechange", onChange);
+"#;
+
+/// Stops the language script when the component is unmounted
+struct LanguageWatcher(UseEval);
+
+impl Drop for LanguageWatcher {
+    fn drop(&mut self) {
+        let _ = self.0.send(serde_json::Value::Null);
+    }
+}
+
+/// Call `on_change` with the preferred languages when the renderer first reports them and every time they change.
+fn watch_languages(
+    cx: &ScopeState,
+    mut on_change: impl FnMut(Rc<[String]>) + 'static,
+) -> Option<LanguageWatcher> {
+    let provider = cx.consume_context::<Rc<dyn EvalProvider>>()?;
+    let eval = UseEval::new(provider.new_evaluator(LANGUAGE_SCRIPT.to_string()).ok()?);
+
+    cx.spawn({
+        let eval = eval.clone();
+        async move {
+            while let Ok(value) = eval.recv().await {
+                if let Ok(languages) = serde_json::from_value::<Vec<String>>(value) {
+                    on_change(languages.into());
+                }
+            }
+        }
+    });
+
+    Some(LanguageWatcher(eval))
+}
+
+struct UsePreferredLanguages {
+    languages: Rc<RefCell<Rc<[String]>>>,
+    _watcher: Option<LanguageWatcher>,
+}
+
+/// Get the languages the user prefers, ordered from most to least preferred. The component rerenders when the
+/// languages change.
+///
+/// This is empty until the renderer reports the languages, and stays empty with renderers that can't evaluate
+/// JavaScript, like the server side renderer.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let languages = use_preferred_languages(cx);
+///     let greeting = match languages.first().map(|language| language.split('-').next()) {
+///         Some(Some("fr")) => "Bonjour",
+///         Some(Some("de")) => "Hallo",
+///         _ => "Hello",
+///     };
+///
+///     render! { h1 { greeting } }
+/// }
+/// ```
+pub fn use_preferred_languages(cx: &ScopeState) -> Rc<[String]> {
+    let state = cx.use_hook(|| {
+        let languages: Rc<RefCell<Rc<[String]>>> = Rc::new(RefCell::new(Rc::from(Vec::new())));
+        let update = cx.schedule_update();
+        let watcher = watch_languages(cx, {
+            let languages = languages.clone();
+            move |new_languages| {
+                if *languages.borrow() != new_languages {
+                    *languages.borrow_mut() = new_languages;
+                    update();
+                }
+            }
+        });
+
+        UsePreferredLanguages {
+            languages,
+            _watcher: watcher,
+        }
+    });
+    state.languages.borrow().clone()
+}
+
+/// Get the language the user prefers most. The component rerenders when it changes.
+///
+/// This is `None` until the renderer reports the languages.
+pub fn use_preferred_language(cx: &ScopeState) -> Option<String> {
+    use_preferred_languages(cx).first().cloned()
+}
+
+/// Call `handler` with the preferred languages when the renderer first reports them and every time the user changes
+/// them.
+///
+/// Unlike [`use_preferred_languages`], this does not rerender the component, so it can be used to switch the locale
+/// of an i18n library at the root of the app.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let i18n = use_i18n(cx);
+///     use_language_change(cx, {
+///         let i18n = i18n.clone();
+///         move |languages| i18n.negotiate(languages)
+///     });
+///
+///     render! { Router::<Route> {} }
+/// }
+/// ```
+pub fn use_language_change(cx: &ScopeState, mut handler: impl FnMut(&[String]) + 'static) {
+    cx.use_hook(|| {
+        let mut last: Option<Rc<[String]>> = None;
+        watch_languages(cx, move |languages| {
+            if last.as_ref() != Some(&languages) {
+                handler(&languages);
+                last = Some(languages);
+            }
+        })
+    });
+}
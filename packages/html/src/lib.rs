@@ -22,12 +22,17 @@ pub use elements::HtmlCtx;
 #[cfg(feature = "html-to-rsx")]
 pub use elements::{map_html_attribute_to_rsx, map_html_element_to_rsx};
 pub mod events;
+#[cfg(feature = "serialize")]
+pub mod geolocation;
 pub mod geometry;
 mod global_attributes;
 pub mod input_data;
+#[cfg(feature = "serialize")]
+pub mod language;
 #[cfg(feature = "native-bind")]
 pub mod native_bind;
 mod render_template;
+pub mod sanitize;
 #[cfg(feature = "wasm-bind")]
 mod web_sys_bind;
 
@@ -41,10 +46,23 @@ pub use elements::*;
 pub use events::*;
 pub use global_attributes::*;
 pub use render_template::*;
+pub use sanitize::SafeHtml;
+#[cfg(feature = "sanitize")]
+pub use sanitize::Sanitizer;
 
 mod eval;
 
 pub mod prelude {
     pub use crate::eval::*;
     pub use crate::events::*;
+    #[cfg(feature = "serialize")]
+    pub use crate::geolocation::{
+        use_geolocation, GeolocationError, GeolocationOptions, GeolocationPermission,
+        UseGeolocation,
+    };
+    #[cfg(feature = "serialize")]
+    pub use crate::language::{
+        use_language_change, use_preferred_language, use_preferred_languages,
+    };
+    pub use crate::sanitize::SafeHtml;
 }
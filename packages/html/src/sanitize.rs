@@ -0,0 +1,200 @@
+//! Helpers for setting `dangerous_inner_html` to HTML that came from somewhere you don't control.
+//!
+//! [`SafeHtml`] marks HTML that has been cleaned. With the `sanitize` feature, [`Sanitizer`] removes every tag,
+//! attribute and url scheme that is not on its allowlist.
+
+use dioxus_core::prelude::IntoAttributeValue;
+use dioxus_core::{exports::bumpalo::Bump, AttributeValue};
+use std::fmt::{Display, Formatter};
+
+/// The tags the default [`Sanitizer`] keeps.
+///
+/// The interpreters use the same list when they sanitize `dangerous_inner_html` in the browser.
+pub const DEFAULT_ALLOWED_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "b",
+    "blockquote",
+    "br",
+    "caption",
+    "code",
+    "del",
+    "div",
+    "em",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "img",
+    "ins",
+    "kbd",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "s",
+    "small",
+    "span",
+    "strong",
+    "sub",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "th",
+    "thead",
+    "tr",
+    "u",
+    "ul",
+];
+
+/// The attributes the default [`Sanitizer`] keeps on any allowed tag
+pub const DEFAULT_ALLOWED_ATTRIBUTES: &[&str] = &["title", "lang", "dir"];
+
+/// The attributes the default [`Sanitizer`] keeps on specific tags
+pub const DEFAULT_ALLOWED_TAG_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("a", &["href"]),
+    ("img", &["src", "alt", "width", "height"]),
+    ("td", &["colspan", "rowspan"]),
+    ("th", &["colspan", "rowspan"]),
+];
+
+/// The url schemes the default [`Sanitizer`] allows in `href` and `src` attributes
+pub const DEFAULT_ALLOWED_URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// The tags the default [`Sanitizer`] drops together with their content.
+///
+/// `svg` and `math` content is parsed with different rules than HTML, which is where most sanitizer bypasses hide.
+pub const DEFAULT_DROPPED_TAGS: &[&str] = &["script", "style", "svg", "math"];
+
+/// HTML that is safe to set as `dangerous_inner_html`.
+///
+/// Create it by cleaning untrusted HTML with [`Sanitizer::clean`] or [`SafeHtml::sanitize`], or wrap HTML you wrote
+/// yourself with [`SafeHtml::trusted`].
+///
+/// ```rust, ignore
+/// let comment = SafeHtml::sanitize(&user_comment);
+/// render! { div { dangerous_inner_html: comment } }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SafeHtml(String);
+
+impl SafeHtml {
+    /// Clean untrusted HTML with the default [`Sanitizer`]
+    #[cfg(feature = "sanitize")]
+    pub fn sanitize(html: &str) -> Self {
+        Sanitizer::new().clean(html)
+    }
+
+    /// Wrap HTML that is already known to be safe, like HTML that was sanitized before it was stored.
+    ///
+    /// The HTML is not checked. Never pass HTML that came from a user to this function.
+    pub fn trusted(html: impl Into<String>) -> Self {
+        Self(html.into())
+    }
+
+    /// Get the HTML as a string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Get the HTML as an owned string
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl Display for SafeHtml {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'a> IntoAttributeValue<'a> for SafeHtml {
+    fn into_value(self, bump: &'a Bump) -> AttributeValue<'a> {
+        AttributeValue::Text(bump.alloc_str(&self.0))
+    }
+}
+
+impl<'a> IntoAttributeValue<'a> for &'a SafeHtml {
+    fn into_value(self, _: &'a Bump) -> AttributeValue<'a> {
+        AttributeValue::Text(&self.0)
+    }
+}
+
+/// An allowlist based HTML sanitizer.
+///
+/// Everything that is not on the allowlist is removed: the content of unknown tags is kept, but `script`, `style`,
+/// `svg` and `math` elements are dropped entirely.
+#[cfg(feature = "sanitize")]
+pub struct Sanitizer {
+    builder: ammonia::Builder<'static>,
+}
+
+#[cfg(feature = "sanitize")]
+impl Default for Sanitizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "sanitize")]
+impl Sanitizer {
+    /// Create a sanitizer with the default allowlist
+    pub fn new() -> Self {
+        let mut builder = ammonia::Builder::default();
+        builder
+            .tags(DEFAULT_ALLOWED_TAGS.iter().copied().collect())
+            .generic_attributes(DEFAULT_ALLOWED_ATTRIBUTES.iter().copied().collect())
+            .tag_attributes(
+                DEFAULT_ALLOWED_TAG_ATTRIBUTES
+                    .iter()
+                    .map(|(tag, attributes)| (*tag, attributes.iter().copied().collect()))
+                    .collect(),
+            )
+            .url_schemes(DEFAULT_ALLOWED_URL_SCHEMES.iter().copied().collect())
+            .add_clean_content_tags(DEFAULT_DROPPED_TAGS.iter().copied());
+        Self { builder }
+    }
+
+    /// Allow more tags
+    pub fn allow_tags(mut self, tags: impl IntoIterator<Item = &'static str>) -> Self {
+        let tags: Vec<_> = tags.into_iter().collect();
+        // A tag can't be both kept and dropped with its content
+        self.builder.rm_clean_content_tags(tags.iter().copied());
+        self.builder.add_tags(tags);
+        self
+    }
+
+    /// Allow more attributes on a tag
+    pub fn allow_tag_attributes(
+        mut self,
+        tag: &'static str,
+        attributes: impl IntoIterator<Item = &'static str>,
+    ) -> Self {
+        self.builder.add_tag_attributes(tag, attributes);
+        self
+    }
+
+    /// Allow more attributes on every tag
+    pub fn allow_attributes(mut self, attributes: impl IntoIterator<Item = &'static str>) -> Self {
+        self.builder.add_generic_attributes(attributes);
+        self
+    }
+
+    /// Allow more url schemes in links and images
+    pub fn allow_url_schemes(mut self, schemes: impl IntoIterator<Item = &'static str>) -> Self {
+        self.builder.add_url_schemes(schemes);
+        self
+    }
+
+    /// Remove everything that is not on the allowlist from `html`
+    pub fn clean(&self, html: &str) -> SafeHtml {
+        SafeHtml(self.builder.clean(html).to_string())
+    }
+}
@@ -111,7 +111,7 @@ fn fun_name(
         "transitionend" => Transition(de(data)?),
 
         // Toggle
-        "toggle" => Toggle(de(data)?),
+        "toggle" | "beforetoggle" | "close" | "cancel" => Toggle(de(data)?),
 
         "load" | "error" => Image(de(data)?),
 
@@ -1,6 +1,6 @@
 use crate::events::{
-    AnimationData, CompositionData, KeyboardData, MouseData, PointerData, TouchData,
-    TransitionData, WheelData,
+    AnimationData, CompositionData, KeyboardData, MouseData, PointerData, PointerSample, TouchData,
+    TouchPoint, TransitionData, WheelData,
 };
 use crate::geometry::{ClientPoint, Coordinates, ElementPoint, PagePoint, ScreenPoint};
 use crate::input_data::{decode_key_location, decode_mouse_button_set, MouseButton};
@@ -10,8 +10,8 @@ use std::convert::TryInto;
 use std::str::FromStr;
 use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{
-    AnimationEvent, CompositionEvent, Event, KeyboardEvent, MouseEvent, PointerEvent, TouchEvent,
-    TransitionEvent, WheelEvent,
+    AnimationEvent, CompositionEvent, Event, KeyboardEvent, MouseEvent, PointerEvent, Touch,
+    TouchEvent, TouchList, TransitionEvent, WheelEvent,
 };
 
 macro_rules! uncheck_convert {
@@ -123,6 +123,7 @@ impl From<&MouseEvent> for DragData {
     fn from(value: &MouseEvent) -> Self {
         Self {
             mouse: MouseData::from(value),
+            files: None,
         }
     }
 }
@@ -134,10 +135,45 @@ impl From<&TouchEvent> for TouchData {
             ctrl_key: e.ctrl_key(),
             meta_key: e.meta_key(),
             shift_key: e.shift_key(),
+            touches: touch_points(&e.touches()),
+            changed_touches: touch_points(&e.changed_touches()),
+            target_touches: touch_points(&e.target_touches()),
         }
     }
 }
 
+fn touch_points(list: &TouchList) -> Vec<TouchPoint> {
+    (0..list.length())
+        .filter_map(|i| list.get(i))
+        .map(|touch| TouchPoint::from(&touch))
+        .collect()
+}
+
+impl From<&Touch> for TouchPoint {
+    fn from(touch: &Touch) -> Self {
+        Self {
+            identifier: touch.identifier(),
+            client_x: touch.client_x() as f64,
+            client_y: touch.client_y() as f64,
+            page_x: touch.page_x() as f64,
+            page_y: touch.page_y() as f64,
+            screen_x: touch.screen_x() as f64,
+            screen_y: touch.screen_y() as f64,
+            radius_x: touch.radius_x() as f64,
+            radius_y: touch.radius_y() as f64,
+            rotation_angle: touch.rotation_angle() as f64,
+            force: touch.force() as f64,
+        }
+    }
+}
+
+/// Read a number web-sys has no binding for yet
+fn number_property(value: &JsValue, name: &str) -> Option<f64> {
+    js_sys::Reflect::get(value, &JsValue::from_str(name))
+        .ok()
+        .and_then(|value| value.as_f64())
+}
+
 impl From<&PointerEvent> for PointerData {
     fn from(e: &PointerEvent) -> Self {
         Self {
@@ -164,10 +200,40 @@ impl From<&PointerEvent> for PointerData {
             pointer_type: e.pointer_type(),
             is_primary: e.is_primary(),
             // get_modifier_state: evt.get_modifier_state(),
+            altitude_angle: number_property(e, "altitudeAngle")
+                .unwrap_or(std::f64::consts::FRAC_PI_2),
+            azimuth_angle: number_property(e, "azimuthAngle").unwrap_or_default(),
+            coalesced_samples: coalesced_samples(e),
         }
     }
 }
 
+fn coalesced_samples(e: &PointerEvent) -> Vec<PointerSample> {
+    if e.type_() != "pointermove" {
+        return Vec::new();
+    }
+    let get_coalesced_events = js_sys::Reflect::get(e, &JsValue::from_str("getCoalescedEvents"))
+        .ok()
+        .and_then(|method| method.dyn_into::<js_sys::Function>().ok());
+    let events = match get_coalesced_events.and_then(|method| method.call0(e).ok()) {
+        Some(events) => js_sys::Array::from(&events),
+        None => return Vec::new(),
+    };
+    events
+        .iter()
+        .filter_map(|event| event.dyn_into::<PointerEvent>().ok())
+        .map(|event| PointerSample {
+            client_x: number_property(&event, "clientX").unwrap_or_default(),
+            client_y: number_property(&event, "clientY").unwrap_or_default(),
+            pressure: event.pressure(),
+            tilt_x: event.tilt_x(),
+            tilt_y: event.tilt_y(),
+            twist: event.twist(),
+            time_stamp: event.time_stamp(),
+        })
+        .collect()
+}
+
 impl From<&WheelEvent> for WheelData {
     fn from(e: &WheelEvent) -> Self {
         WheelData::from_web_attributes(e.delta_mode(), e.delta_x(), e.delta_y(), e.delta_z())
@@ -249,8 +315,93 @@ impl crate::RenderedElementBacking for web_sys::Element {
             });
         Box::pin(async { result })
     }
+
+    fn show_dialog(
+        &self,
+        modal: bool,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = crate::MountedResult<()>>>> {
+        let result = self
+            .dyn_ref::<web_sys::HtmlDialogElement>()
+            .ok_or(crate::MountedError::NotSupported)
+            .and_then(|dialog| {
+                if dialog.open() {
+                    dialog.close();
+                }
+                if modal {
+                    dialog.show_modal().map_err(|err| {
+                        crate::MountedError::OperationFailed(Box::new(DomError(err)))
+                    })
+                } else {
+                    dialog.show();
+                    Ok(())
+                }
+            });
+        Box::pin(async { result })
+    }
+
+    fn close_dialog(
+        &self,
+        return_value: Option<String>,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = crate::MountedResult<()>>>> {
+        let result = self
+            .dyn_ref::<web_sys::HtmlDialogElement>()
+            .ok_or(crate::MountedError::NotSupported)
+            .map(|dialog| match return_value {
+                Some(return_value) => dialog.close_with_return_value(&return_value),
+                None => dialog.close(),
+            });
+        Box::pin(async { result })
+    }
+
+    fn toggle_popover(
+        &self,
+        open: bool,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = crate::MountedResult<()>>>> {
+        // web-sys doesn't bind the popover API yet
+        let popover: &PopoverElement = self.unchecked_ref();
+        let result = popover
+            .toggle_popover(open)
+            .map(|_| ())
+            .map_err(|err| crate::MountedError::OperationFailed(Box::new(DomError(err))));
+        Box::pin(async { result })
+    }
+
+    fn set_pointer_capture(
+        &self,
+        pointer_id: i32,
+        capture: bool,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = crate::MountedResult<()>>>> {
+        let result = if capture {
+            web_sys::Element::set_pointer_capture(self, pointer_id)
+        } else {
+            web_sys::Element::release_pointer_capture(self, pointer_id)
+        }
+        .map_err(|err| crate::MountedError::OperationFailed(Box::new(DomError(err))));
+        Box::pin(async { result })
+    }
+}
+
+#[cfg(feature = "mounted")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = web_sys::Element)]
+    type PopoverElement;
+
+    #[wasm_bindgen(method, catch, js_name = togglePopover)]
+    fn toggle_popover(this: &PopoverElement, force: bool) -> Result<bool, JsValue>;
+}
+
+#[derive(Debug)]
+struct DomError(JsValue);
+
+impl std::fmt::Display for DomError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the browser rejected the operation: {:?}", self.0)
+    }
 }
 
+impl std::error::Error for DomError {}
+
 #[derive(Debug)]
 struct FocusError(JsValue);
 
@@ -0,0 +1,73 @@
+#![cfg(feature = "sanitize")]
+
+use dioxus_html::Sanitizer;
+
+fn clean(html: &str) -> String {
+    Sanitizer::new().clean(html).into_string()
+}
+
+#[test]
+fn drops_scripts() {
+    assert_eq!(clean("<p>hi<script>steal()</script></p>"), "<p>hi</p>");
+    assert_eq!(clean("<style>body { display: none }</style>ok"), "ok");
+}
+
+#[test]
+fn drops_event_handlers() {
+    let cleaned = clean(
+        r#"<img src="https://example.com/a.png" onerror="steal()"><p onclick="steal()">hi</p>"#,
+    );
+    assert!(!cleaned.contains("onerror"));
+    assert!(!cleaned.contains("onclick"));
+    assert!(cleaned.contains(r#"src="https://example.com/a.png""#));
+    assert!(cleaned.contains("<p>hi</p>"));
+}
+
+#[test]
+fn drops_javascript_urls() {
+    for html in [
+        r#"<a href="javascript:steal()">link</a>"#,
+        r#"<a href="JaVaScRiPt:steal()">link</a>"#,
+        r#"<a href=" javascript:steal()">link</a>"#,
+        r#"<img src="javascript:steal()">"#,
+        r#"<a href="data:text/html,<script>steal()</script>">link</a>"#,
+    ] {
+        let cleaned = clean(html);
+        assert!(
+            !cleaned.contains("steal"),
+            "{html} was cleaned to {cleaned}"
+        );
+    }
+
+    let cleaned = clean(r#"<a href="https://example.com">link</a>"#);
+    assert!(cleaned.contains(r#"href="https://example.com""#));
+}
+
+#[test]
+fn drops_svg_and_math() {
+    for html in [
+        "<svg><script>steal()</script></svg>",
+        r#"<svg><a href="javascript:steal()"><text>link</text></a></svg>"#,
+        r#"<svg><foreignObject><img src="x" onerror="steal()"></foreignObject></svg>"#,
+        r#"<math><mtext><table><mglyph><style><img src="x" onerror="steal()">"#,
+        r#"<svg></p><style><a id="</style><img src=x onerror=steal()>">"#,
+        r#"<math><style><img src=x onerror=steal()></style></math>"#,
+    ] {
+        let cleaned = clean(html);
+        assert!(
+            !cleaned.contains("steal"),
+            "{html} was cleaned to {cleaned}"
+        );
+        assert!(!cleaned.contains("<svg"), "{html} was cleaned to {cleaned}");
+        assert!(
+            !cleaned.contains("<math"),
+            "{html} was cleaned to {cleaned}"
+        );
+    }
+}
+
+#[test]
+fn allowed_tags_are_kept() {
+    let sanitizer = Sanitizer::new().allow_tags(["svg"]);
+    assert!(sanitizer.clean("<svg></svg>").as_str().contains("<svg"));
+}
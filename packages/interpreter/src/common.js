@@ -31,7 +31,7 @@ export function setAttributeInner(node, field, value, ns) {
         node.defaultSelected = truthy(value);
         break;
       case "dangerous_inner_html":
-        node.innerHTML = value;
+        setRawHtml(node, value);
         break;
       default:
         // https://github.com/facebook/react/blob/8b88ac2592c5f555f315f9440cbb665dd1e7457a/packages/react-dom/src/shared/DOMProperty.js#L352-L364
@@ -74,6 +74,97 @@ const bool_attrs = {
   webkitdirectory: true,
 };
 
+// Raw html nodes are rendered inside a wrapper that does not take part in the layout
+export function createRawHtml(value) {
+  const node = document.createElement("dioxus-raw-html");
+  node.style.display = "contents";
+  setRawHtml(node, value);
+  return node;
+}
+
+export function setRawHtml(node, value) {
+  if (window.__dioxusSanitizeInnerHtml) {
+    // Insert the sanitized nodes themselves. Serializing them and parsing the html again could turn markup that is
+    // safe after the first parse into markup that is not (mutation XSS).
+    const fragment = sanitizeHtml(value);
+    node.replaceChildren(...fragment.childNodes);
+  } else {
+    node.innerHTML = value;
+  }
+}
+
 function truthy(val) {
   return val === "true" || val === true;
 }
+
+// The allowlist of the html sanitizer. Keep this in sync with the defaults in packages/html/src/sanitize.rs
+const sanitize_tags = new Set([
+  "a", "abbr", "b", "blockquote", "br", "caption", "code", "del", "div", "em",
+  "h1", "h2", "h3", "h4", "h5", "h6", "hr", "i", "img", "ins", "kbd", "li", "ol",
+  "p", "pre", "q", "s", "small", "span", "strong", "sub", "sup", "table",
+  "tbody", "td", "th", "thead", "tr", "u", "ul",
+]);
+const sanitize_attributes = new Set(["title", "lang", "dir"]);
+const sanitize_tag_attributes = {
+  a: new Set(["href"]),
+  img: new Set(["src", "alt", "width", "height"]),
+  td: new Set(["colspan", "rowspan"]),
+  th: new Set(["colspan", "rowspan"]),
+};
+const sanitize_url_schemes = new Set(["http:", "https:", "mailto:"]);
+const sanitize_dropped_tags = new Set(["script", "style"]);
+
+const html_namespace = "http://www.w3.org/1999/xhtml";
+
+// Parse some html into a DocumentFragment, and remove every tag, attribute and url scheme that is not on the allowlist
+export function sanitizeHtml(html) {
+  const template = document.createElement("template");
+  template.innerHTML = html;
+  const fragment = template.content;
+  sanitizeChildren(fragment);
+  return fragment;
+}
+
+function sanitizeChildren(parent) {
+  for (const child of Array.from(parent.childNodes)) {
+    if (child.nodeType === Node.TEXT_NODE) {
+      continue;
+    }
+    if (child.nodeType !== Node.ELEMENT_NODE) {
+      child.remove();
+      continue;
+    }
+
+    const tag = child.localName;
+    // svg and math elements are parsed with different rules, which is where most sanitizer bypasses hide
+    if (child.namespaceURI !== html_namespace) {
+      child.remove();
+      continue;
+    }
+    sanitizeChildren(child);
+    if (sanitize_dropped_tags.has(tag)) {
+      child.remove();
+    } else if (!sanitize_tags.has(tag)) {
+      // keep the content of unknown tags
+      child.replaceWith(...child.childNodes);
+    } else {
+      for (const attribute of Array.from(child.attributes)) {
+        const name = attribute.name;
+        const allowed =
+          sanitize_attributes.has(name) ||
+          (sanitize_tag_attributes[tag] && sanitize_tag_attributes[tag].has(name));
+        if (!allowed || ((name === "href" || name === "src") && !safeUrl(attribute.value))) {
+          child.removeAttribute(name);
+        }
+      }
+    }
+  }
+}
+
+function safeUrl(url) {
+  try {
+    return sanitize_url_schemes.has(new URL(url, document.baseURI).protocol);
+  } catch (e) {
+    return false;
+  }
+}
@@ -71,20 +71,7 @@ function handler(event, name, bubbles, config) {
       const type = target.getAttribute("type");
       if (type === "file") {
         async function read_files() {
-          const files = target.files;
-          const file_contents = {};
-
-          for (let i = 0; i < files.length; i++) {
-            const file = files[i];
-
-            file_contents[file.name] = Array.from(
-              new Uint8Array(await file.arrayBuffer())
-            );
-          }
-          let file_engine = {
-            files: file_contents,
-          };
-          contents.files = file_engine;
+          contents.files = await serialize_files(target.files);
 
           if (realId === null) {
             return;
@@ -102,6 +89,32 @@ function handler(event, name, bubbles, config) {
       }
     }
 
+    // the pasted files can only be read while the event is dispatched, so grab them before reading them
+    if (
+      event.type === "paste" &&
+      event.clipboardData &&
+      event.clipboardData.files.length > 0
+    ) {
+      const files = Array.from(event.clipboardData.files);
+      async function read_pasted_files() {
+        contents.files = await serialize_files(files);
+
+        if (realId === null) {
+          return;
+        }
+        window.ipc.postMessage(
+          window.interpreter.serializeIpcMessage("user_event", {
+            name: name,
+            element: parseInt(realId),
+            data: contents,
+            bubbles,
+          })
+        );
+      }
+      read_pasted_files();
+      return;
+    }
+
     if (
       target.tagName === "FORM" &&
       (event.type === "submit" || event.type === "input")
@@ -142,6 +155,21 @@ function handler(event, name, bubbles, config) {
   }
 }
 
+async function serialize_files(files) {
+  const file_contents = {};
+
+  for (let i = 0; i < files.length; i++) {
+    const file = files[i];
+
+    file_contents[file.name] = Array.from(
+      new Uint8Array(await file.arrayBuffer())
+    );
+  }
+  return {
+    files: file_contents,
+  };
+}
+
 function find_real_id(target) {
   let realId = null;
   if (target instanceof Element) {
@@ -248,6 +276,10 @@ window.interpreter.initialize = function (root) {
   listeners.root = root;
 }
 
+window.interpreter.getNode = function (id) {
+  return nodes[id];
+}
+
 window.interpreter.getClientRect = function (id) {
   const node = nodes[id];
   if (!node) {
@@ -286,6 +318,81 @@ window.interpreter.setFocus = function (id, focus) {
   return true;
 }
 
+/// Open a dialog, as a modal if `modal` is true
+window.interpreter.showDialog = function (id, modal) {
+  const node = nodes[id];
+  if (!node || typeof node.showModal !== "function") {
+    return false;
+  }
+  try {
+    if (node.open) {
+      node.close();
+    }
+    if (modal) {
+      node.showModal();
+    } else {
+      node.show();
+    }
+  } catch (e) {
+    // The element is not a dialog
+    return false;
+  }
+  return true;
+}
+
+/// Close a dialog, and set its return value if it is not null
+window.interpreter.closeDialog = function (id, returnValue) {
+  const node = nodes[id];
+  if (!node || typeof node.close !== "function") {
+    return false;
+  }
+  try {
+    if (returnValue === null) {
+      node.close();
+    } else {
+      node.close(returnValue);
+    }
+  } catch (e) {
+    // The element is not a dialog
+    return false;
+  }
+  return true;
+}
+
+/// Show or hide a popover
+window.interpreter.togglePopover = function (id, open) {
+  const node = nodes[id];
+  if (!node || typeof node.togglePopover !== "function") {
+    return false;
+  }
+  try {
+    node.togglePopover(open);
+  } catch (e) {
+    // The element is not a popover
+    return false;
+  }
+  return true;
+}
+
+/// Capture or release a pointer
+window.interpreter.setPointerCapture = function (id, pointerId, capture) {
+  const node = nodes[id];
+  if (!node) {
+    return false;
+  }
+  try {
+    if (capture) {
+      node.setPointerCapture(pointerId);
+    } else {
+      node.releasePointerCapture(pointerId);
+    }
+  } catch (e) {
+    // The pointer is not active
+    return false;
+  }
+  return true;
+}
+
 function get_mouse_data(event) {
   const {
     altKey,
@@ -321,12 +428,54 @@ function get_mouse_data(event) {
   };
 }
 
+function get_touch_points(list) {
+  const points = [];
+  for (const touch of list || []) {
+    points.push({
+      identifier: touch.identifier,
+      client_x: touch.clientX,
+      client_y: touch.clientY,
+      page_x: touch.pageX,
+      page_y: touch.pageY,
+      screen_x: touch.screenX,
+      screen_y: touch.screenY,
+      radius_x: touch.radiusX || 0,
+      radius_y: touch.radiusY || 0,
+      rotation_angle: touch.rotationAngle || 0,
+      force: touch.force || 0,
+    });
+  }
+  return points;
+}
+
+function get_coalesced_samples(event) {
+  if (event.type !== "pointermove" || typeof event.getCoalescedEvents !== "function") {
+    return [];
+  }
+  return event.getCoalescedEvents().map((sample) => ({
+    client_x: sample.clientX,
+    client_y: sample.clientY,
+    pressure: sample.pressure,
+    tilt_x: sample.tiltX,
+    tilt_y: sample.tiltY,
+    twist: sample.twist,
+    time_stamp: sample.timeStamp,
+  }));
+}
+
 function serialize_event(event) {
   switch (event.type) {
     case "copy":
     case "cut":
-    case "past": {
-      return {};
+    case "paste": {
+      const data = event.clipboardData;
+      if (!data) {
+        return {};
+      }
+      return {
+        text: data.getData("text/plain") || null,
+        html: data.getData("text/html") || null,
+      };
     }
     case "compositionend":
     case "compositionstart":
@@ -455,23 +604,26 @@ function serialize_event(event) {
         twist,
         pointerType,
         isPrimary,
+        altitudeAngle,
+        azimuthAngle,
       } = event;
+      // Pens and touch screens report fractional positions and sizes, the full precision is in the samples
       return {
         alt_key: altKey,
         button: button,
         buttons: buttons,
-        client_x: clientX,
-        client_y: clientY,
+        client_x: Math.round(clientX),
+        client_y: Math.round(clientY),
         ctrl_key: ctrlKey,
         meta_key: metaKey,
-        page_x: pageX,
-        page_y: pageY,
-        screen_x: screenX,
-        screen_y: screenY,
+        page_x: Math.round(pageX),
+        page_y: Math.round(pageY),
+        screen_x: Math.round(screenX),
+        screen_y: Math.round(screenY),
         shift_key: shiftKey,
         pointer_id: pointerId,
-        width: width,
-        height: height,
+        width: Math.round(width),
+        height: Math.round(height),
         pressure: pressure,
         tangential_pressure: tangentialPressure,
         tilt_x: tiltX,
@@ -479,6 +631,9 @@ function serialize_event(event) {
         twist: twist,
         pointer_type: pointerType,
         is_primary: isPrimary,
+        altitude_angle: altitudeAngle === undefined ? Math.PI / 2 : altitudeAngle,
+        azimuth_angle: azimuthAngle === undefined ? 0 : azimuthAngle,
+        coalesced_samples: get_coalesced_samples(event),
       };
     }
     case "select": {
@@ -490,13 +645,13 @@ function serialize_event(event) {
     case "touchstart": {
       const { altKey, ctrlKey, metaKey, shiftKey } = event;
       return {
-        // changed_touches: event.changedTouches,
-        // target_touches: event.targetTouches,
-        // touches: event.touches,
         alt_key: altKey,
         ctrl_key: ctrlKey,
         meta_key: metaKey,
         shift_key: shiftKey,
+        touches: get_touch_points(event.touches),
+        changed_touches: get_touch_points(event.changedTouches),
+        target_touches: get_touch_points(event.targetTouches),
       };
     }
     case "scroll": {
@@ -554,7 +709,10 @@ function serialize_event(event) {
     case "waiting": {
       return {};
     }
-    case "toggle": {
+    case "toggle":
+    case "beforetoggle":
+    case "close":
+    case "cancel": {
       return {};
     }
     default: {
@@ -735,6 +893,12 @@ function event_bubbles(event) {
       return true;
     case "toggle":
       return true;
+    case "beforetoggle":
+      return false;
+    case "close":
+      return false;
+    case "cancel":
+      return false;
     case "mounted":
       return false;
   }
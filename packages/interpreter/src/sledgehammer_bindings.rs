@@ -189,6 +189,12 @@ mod js {
     fn create_placeholder(id: u32) {
         "{node = document.createElement('pre'); node.hidden = true; stack.push(node); nodes[$id$] = node;}"
     }
+    fn create_raw_html(value: &str, id: u32) {
+        "{node = createRawHtml($value$); nodes[$id$] = node; stack.push(node);}"
+    }
+    fn set_raw_html(id: u32, value: &str) {
+        "{setRawHtml(nodes[$id$], $value$);}"
+    }
     fn new_event_listener(event_name: &str<u8, evt>, id: u32, bubbles: u8) {
         r#"node = nodes[id]; if(node.listening){node.listening += 1;}else{node.listening = 1;} node.setAttribute('data-dioxus-id', `\${id}`); listeners.create($event_name$, node, $bubbles$);"#
     }
@@ -311,6 +317,12 @@ pub mod binary_protocol {
         fn create_placeholder(id: u32) {
             "{node = document.createElement('pre'); node.hidden = true; stack.push(node); nodes[$id$] = node;}"
         }
+        fn create_raw_html(value: &str, id: u32) {
+            "{node = createRawHtml($value$); nodes[$id$] = node; stack.push(node);}"
+        }
+        fn set_raw_html(id: u32, value: &str) {
+            "{setRawHtml(nodes[$id$], $value$);}"
+        }
         fn add_placeholder() {
             "{node = document.createElement('pre'); node.hidden = true; stack.push(node);}"
         }
@@ -0,0 +1,224 @@
+//! Components that run in the browser inside an otherwise server driven LiveView page.
+//!
+//! Every event of a LiveView page makes a round trip to the server, which is too slow for widgets like text editors.
+//! Those widgets can be compiled to a separate WASM bundle and run in the browser instead, while the rest of the page
+//! stays on the server. The server and the client component talk through a small protocol:
+//!
+//! 1. The server renders a placeholder with [`ClientComponent::render`]. It is an empty `div` with the name of the
+//!    component, the id of the placeholder and the props of the component serialized as JSON.
+//! 2. The bundle registers the component in the browser with
+//!    `window.dioxusClientComponents.register(name, mount)`. The glue calls `mount(element, props, send)` for every
+//!    placeholder with that name. `mount` renders the component into the element.
+//! 3. The component sends its state to the server with `send(state)`. The server deserializes it, stores it in the
+//!    [`ClientComponent`] and re-renders the component that owns it.
+//! 4. When the server renders new props, the glue calls `update(props)` on the object `mount` returned. When the
+//!    placeholder is removed, it calls `unmount()`.
+//!
+//! The server never renders children into the placeholder, so the client component owns everything inside it.
+//!
+//! ```rust, ignore
+//! fn app(cx: Scope) -> Element {
+//!     let editor = use_client_component::<String>(cx);
+//!     let text = editor.state().unwrap_or_default();
+//!
+//!     render! {
+//!         editor.render(cx, "editor", &text)
+//!         "{text.len()} characters"
+//!     }
+//! }
+//! ```
+//!
+//! The bundle is loaded after the [`interpreter_glue`](crate::interpreter_glue) with
+//! [`client_bundle_glue`](crate::client_bundle_glue), and registers the component:
+//!
+//! ```js
+//! window.dioxusClientComponents.register("editor", (element, text, send) => {
+//!     const editor = createEditor(element, text);
+//!     editor.onChange((text) => send(text));
+//!     return {
+//!         update: (text) => editor.setText(text),
+//!         unmount: () => editor.destroy(),
+//!     };
+//! });
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    rc::Rc,
+    sync::Arc,
+};
+
+use dioxus::prelude::*;
+use rustc_hash::FxHashMap;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// The client components of a session
+#[derive(Clone, Default)]
+pub(crate) struct ClientComponents {
+    // Ids are never reused, so state the browser sent to a component that was unmounted can't reach a new one
+    next_id: Rc<Cell<usize>>,
+    slots: Rc<RefCell<FxHashMap<usize, ClientSlot>>>,
+}
+
+struct ClientSlot {
+    state: Option<serde_json::Value>,
+    update: Arc<dyn Fn()>,
+}
+
+impl ClientComponents {
+    fn insert(&self, update: Arc<dyn Fn()>) -> usize {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.slots.borrow_mut().insert(
+            id,
+            ClientSlot {
+                state: None,
+                update,
+            },
+        );
+        id
+    }
+
+    fn remove(&self, id: usize) {
+        self.slots.borrow_mut().remove(&id);
+    }
+
+    fn state(&self, id: usize) -> Option<serde_json::Value> {
+        self.slots.borrow().get(&id)?.state.clone()
+    }
+
+    /// Store the state a client component sent, and re-render the component that owns it
+    pub(crate) fn receive(&self, message: ClientState) {
+        let update = {
+            let mut slots = self.slots.borrow_mut();
+            match slots.get_mut(&message.id) {
+                Some(slot) => {
+                    slot.state = Some(message.state);
+                    slot.update.clone()
+                }
+                None => return,
+            }
+        };
+        update();
+    }
+}
+
+/// The state a client component sent to the server
+#[derive(Deserialize, Debug)]
+pub(crate) struct ClientState {
+    id: usize,
+    state: serde_json::Value,
+}
+
+/// A component that runs in the browser. Create it with [`use_client_component`].
+pub struct ClientComponent<S> {
+    id: usize,
+    components: ClientComponents,
+    _state: PhantomData<S>,
+}
+
+impl<S: DeserializeOwned> ClientComponent<S> {
+    /// Get the last state the client component sent, or `None` if it hasn't sent any state yet or the state doesn't
+    /// deserialize into `S`.
+    pub fn state(&self) -> Option<S> {
+        let state = self.components.state(self.id)?;
+        match serde_json::from_value(state) {
+            Ok(state) => Some(state),
+            Err(err) => {
+                tracing::error!(
+                    "Failed to deserialize the state of a client component: {}",
+                    err
+                );
+                None
+            }
+        }
+    }
+
+    /// Render the placeholder the client component registered as `name` is mounted into
+    pub fn render<'a>(
+        &self,
+        cx: &'a ScopeState,
+        name: &str,
+        props: &impl Serialize,
+    ) -> Element<'a> {
+        let props = match serde_json::to_string(props) {
+            Ok(props) => props,
+            Err(err) => {
+                tracing::error!(
+                    "Failed to serialize the props of the client component {}: {}",
+                    name,
+                    err
+                );
+                "null".to_string()
+            }
+        };
+        let id = self.id;
+
+        cx.render(rsx! {
+            div {
+                "data-dioxus-client": "{name}",
+                "data-dioxus-client-id": "{id}",
+                "data-dioxus-client-props": "{props}",
+            }
+        })
+    }
+}
+
+impl<S> Drop for ClientComponent<S> {
+    fn drop(&mut self) {
+        self.components.remove(self.id);
+    }
+}
+
+/// Create a component that runs in the browser. The component re-renders every time the client component sends its
+/// state, which is deserialized into `S`.
+///
+/// See the [module documentation](crate::client_component) for the protocol the client component follows.
+pub fn use_client_component<S: DeserializeOwned + 'static>(cx: &ScopeState) -> &ClientComponent<S> {
+    cx.use_hook(|| {
+        // Outside of a LiveView session, the component never receives any state
+        let components = cx.consume_context::<ClientComponents>().unwrap_or_default();
+        let id = components.insert(cx.schedule_update());
+        ClientComponent {
+            id,
+            components,
+            _state: PhantomData,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_of_an_unmounted_component_does_not_reach_a_new_one() {
+        let components = ClientComponents::default();
+        let updates = Rc::new(Cell::new(0));
+        let update: Arc<dyn Fn()> = {
+            let updates = updates.clone();
+            Arc::new(move || updates.set(updates.get() + 1))
+        };
+
+        let unmounted = components.insert(update.clone());
+        components.remove(unmounted);
+        let mounted = components.insert(update);
+        assert_ne!(unmounted, mounted);
+
+        // The browser sends the state of the old placeholder after the server removed it
+        components.receive(ClientState {
+            id: unmounted,
+            state: serde_json::json!("stale"),
+        });
+        assert_eq!(components.state(mounted), None);
+        assert_eq!(updates.get(), 0);
+
+        components.receive(ClientState {
+            id: mounted,
+            state: serde_json::json!("fresh"),
+        });
+        assert_eq!(components.state(mounted), Some(serde_json::json!("fresh")));
+        assert_eq!(updates.get(), 1);
+    }
+}
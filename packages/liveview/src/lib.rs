@@ -27,11 +27,15 @@ pub mod adapters {
 
 pub use adapters::*;
 
+pub mod client_component;
 mod element;
 pub mod pool;
 mod query;
+pub use client_component::{use_client_component, ClientComponent};
+pub use element::LiveviewElement;
 use futures_util::{SinkExt, StreamExt};
 pub use pool::*;
+pub use query::QueryError;
 mod eval;
 
 pub trait WebsocketTx: SinkExt<String, Error = LiveViewError> {}
@@ -44,6 +48,14 @@ impl<T> WebsocketRx for T where T: StreamExt<Item = Result<String, LiveViewError
 pub enum LiveViewError {
     #[error("warp error")]
     SendingFailed,
+    #[error("too many liveview sessions are open")]
+    TooManySessions,
+    #[error("the client stopped answering heartbeats")]
+    HeartbeatTimeout,
+    #[error("the session was idle for too long")]
+    IdleTimeout,
+    #[error("the session used more than {limit} bytes of memory")]
+    MemoryLimitExceeded { limit: usize },
 }
 
 static MINIFIED: &str = include_str!("./minified.js");
@@ -97,3 +109,22 @@ pub fn interpreter_glue(url_or_path: &str) -> String {
     "#
     )
 }
+
+/// Load the WASM bundle of the [client components](crate::client_component) of the page. Put this after the
+/// [`interpreter_glue`], so the bundle can register its components.
+///
+/// `path` is the JavaScript module wasm-bindgen generated for the bundle, like `/assets/widgets.js`.
+///
+/// ```
+/// dioxus_liveview::client_bundle_glue("/assets/widgets.js");
+/// ```
+pub fn client_bundle_glue(path: &str) -> String {
+    format!(
+        r#"
+<script type="module">
+    import init from "{path}";
+    init();
+</script>
+    "#
+    )
+}
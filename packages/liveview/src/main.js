@@ -1,5 +1,16 @@
 const config = new InterpreterConfig(false);
 
+// Client components register themselves here. The bundle can load before or after the socket connects.
+window.dioxusClientComponents = {
+  mounts: {},
+  register(name, mount) {
+    this.mounts[name] = mount;
+    if (window.ipc) {
+      window.ipc.clientComponents.sync();
+    }
+  },
+};
+
 function main() {
   let root = window.document.getElementById("main");
   if (root != null) {
@@ -43,8 +54,10 @@ class IPC {
 
         // Using decode method to get string output 
         let str = decoder.decode(messageData);
-        // Ignore pongs
-        if (str != "__pong__") {
+        // Answer the heartbeats of the server and ignore pongs
+        if (str == "__ping__") {
+          ws.send("__pong__");
+        } else if (str != "__pong__") {
           const event = JSON.parse(str);
           switch (event.type) {
             case "query":
@@ -56,6 +69,9 @@ class IPC {
     };
 
     this.ws = ws;
+    this.clientComponents = new ClientComponents(root, (id, state) =>
+      ws.send(JSON.stringify({ method: "client_state", params: { id, state } }))
+    );
   }
 
   postMessage(msg) {
@@ -63,4 +79,46 @@ class IPC {
   }
 }
 
+// Mounts the client components into their placeholders, and keeps their props in sync with the server
+class ClientComponents {
+  constructor(root, send) {
+    this.root = root;
+    this.send = send;
+    this.instances = new Map();
+    this.observer = new MutationObserver(() => this.sync());
+    this.observer.observe(root, {
+      subtree: true,
+      childList: true,
+      attributes: true,
+      attributeFilter: ["data-dioxus-client-props"],
+    });
+  }
+
+  sync() {
+    for (const element of this.root.querySelectorAll("[data-dioxus-client]")) {
+      const props = element.getAttribute("data-dioxus-client-props");
+      const instance = this.instances.get(element);
+      if (instance) {
+        if (instance.props !== props) {
+          instance.props = props;
+          instance.handle?.update?.(JSON.parse(props));
+        }
+        continue;
+      }
+      const mount = window.dioxusClientComponents.mounts[element.getAttribute("data-dioxus-client")];
+      if (mount) {
+        const id = parseInt(element.getAttribute("data-dioxus-client-id"));
+        const handle = mount(element, JSON.parse(props), (state) => this.send(id, state));
+        this.instances.set(element, { props, handle });
+      }
+    }
+    for (const [element, instance] of this.instances) {
+      if (!element.isConnected) {
+        this.instances.delete(element);
+        instance.handle?.unmount?.();
+      }
+    }
+  }
+}
+
 main();
\ No newline at end of file
@@ -1,4 +1,5 @@
 use crate::{
+    client_component::{ClientComponents, ClientState},
     element::LiveviewElement,
     eval::init_eval,
     query::{QueryEngine, QueryResult},
@@ -10,12 +11,23 @@ use dioxus_interpreter_js::binary_protocol::Channel;
 use futures_util::{pin_mut, SinkExt, StreamExt};
 use rustc_hash::FxHashMap;
 use serde::Serialize;
-use std::{rc::Rc, time::Duration};
+use std::{
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::time::Instant;
 use tokio_util::task::LocalPoolHandle;
 
 #[derive(Clone)]
 pub struct LiveViewPool {
     pub(crate) pool: LocalPoolHandle,
+    pub(crate) limits: SessionLimits,
+    pub(crate) max_sessions: Option<usize>,
+    pub(crate) sessions: Arc<AtomicUsize>,
 }
 
 impl Default for LiveViewPool {
@@ -28,9 +40,45 @@ impl LiveViewPool {
     pub fn new() -> Self {
         LiveViewPool {
             pool: LocalPoolHandle::new(16),
+            limits: SessionLimits::default(),
+            max_sessions: None,
+            sessions: Default::default(),
         }
     }
 
+    /// Send a heartbeat to every client on this interval, and close sessions whose client missed two heartbeats in a row.
+    pub fn with_heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.limits.heartbeat_interval = Some(interval);
+        self
+    }
+
+    /// Close sessions that have not received a user event for this long.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.limits.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Refuse new sessions while this many sessions are open.
+    ///
+    /// Launching a session over the limit returns [`LiveViewError::TooManySessions`].
+    pub fn with_max_sessions(mut self, max: usize) -> Self {
+        self.max_sessions = Some(max);
+        self
+    }
+
+    /// Close sessions whose VirtualDom holds more than this many bytes.
+    ///
+    /// The memory of a session is estimated with [`VirtualDom::allocated_bytes`] after every render.
+    pub fn with_max_session_memory(mut self, bytes: usize) -> Self {
+        self.limits.max_memory = Some(bytes);
+        self
+    }
+
+    /// The number of sessions that are currently open
+    pub fn active_sessions(&self) -> usize {
+        self.sessions.load(Ordering::SeqCst)
+    }
+
     pub async fn launch(
         &self,
         ws: impl LiveViewSocket,
@@ -54,12 +102,48 @@ impl LiveViewPool {
         ws: impl LiveViewSocket,
         make_app: F,
     ) -> Result<(), LiveViewError> {
-        match self.pool.spawn_pinned(move || run(make_app(), ws)).await {
+        let _session = self.open_session()?;
+        let limits = self.limits;
+        match self
+            .pool
+            .spawn_pinned(move || run_with_limits(make_app(), ws, limits))
+            .await
+        {
             Ok(Ok(_)) => Ok(()),
             Ok(Err(e)) => Err(e),
             Err(_) => Err(LiveViewError::SendingFailed),
         }
     }
+
+    fn open_session(&self) -> Result<SessionGuard, LiveViewError> {
+        let max = self.max_sessions.unwrap_or(usize::MAX);
+        self.sessions
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |open| {
+                (open < max).then_some(open + 1)
+            })
+            .map_err(|_| LiveViewError::TooManySessions)?;
+        Ok(SessionGuard(self.sessions.clone()))
+    }
+}
+
+/// Removes a session from the count of open sessions when it ends
+struct SessionGuard(Arc<AtomicUsize>);
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Limits that keep a single session from holding onto server resources forever
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SessionLimits {
+    /// How often the server sends a heartbeat to the client. The session is closed if the client misses two heartbeats in a row.
+    pub heartbeat_interval: Option<Duration>,
+    /// How long the session can go without a user event before it is closed
+    pub idle_timeout: Option<Duration>,
+    /// The most memory the VirtualDom of the session can hold, in bytes
+    pub max_memory: Option<usize>,
 }
 
 /// A LiveViewSocket is a Sink and Stream of Strings that Dioxus uses to communicate with the client
@@ -112,7 +196,16 @@ impl<S> LiveViewSocket for S where
 /// As long as your framework can provide a Sink and Stream of Bytes, you can use this function.
 ///
 /// You might need to transform the error types of the web backend into the LiveView error type.
-pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), LiveViewError> {
+pub async fn run(vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), LiveViewError> {
+    run_with_limits(vdom, ws, SessionLimits::default()).await
+}
+
+/// The same as [`run`], but the session is closed when it goes over `limits`
+pub async fn run_with_limits(
+    mut vdom: VirtualDom,
+    ws: impl LiveViewSocket,
+    limits: SessionLimits,
+) -> Result<(), LiveViewError> {
     #[cfg(all(feature = "hot-reload", debug_assertions))]
     let mut hot_reload_rx = {
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
@@ -130,6 +223,8 @@ pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), Li
     let query_engine = QueryEngine::new(query_tx);
     vdom.base_scope().provide_context(query_engine.clone());
     init_eval(vdom.base_scope());
+    let client_components = ClientComponents::default();
+    vdom.base_scope().provide_context(client_components.clone());
 
     // pin the futures so we can use select!
     pin_mut!(ws);
@@ -147,6 +242,17 @@ pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), Li
         // send the initial render to the client
         ws.send(edits).await?;
     }
+    // The first frame is on its way to the client
+    vdom.flush_layout_effects();
+    check_memory(&vdom, &limits)?;
+
+    // The last time we heard anything from the client
+    let mut last_seen = Instant::now();
+    let mut heartbeat = limits
+        .heartbeat_interval
+        .map(|interval| tokio::time::interval_at(Instant::now() + interval, interval));
+    let idle = tokio::time::sleep(limits.idle_timeout.unwrap_or_default());
+    pin_mut!(idle);
 
     // desktop uses this wrapper struct thing around the actual event itself
     // this is sorta driven by tao/wry
@@ -157,6 +263,8 @@ pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), Li
         Event(HtmlEvent),
         #[serde(rename = "query")]
         Query(QueryResult),
+        #[serde(rename = "client_state")]
+        ClientState(ClientState),
     }
 
     loop {
@@ -165,20 +273,29 @@ pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), Li
         #[cfg(not(all(feature = "hot-reload", debug_assertions)))]
         let hot_reload_wait: std::future::Pending<Option<()>> = std::future::pending();
 
+        let mut handled_event = false;
         tokio::select! {
             // poll any futures or suspense
             _ = vdom.wait_for_work() => {}
 
             evt = ws.next() => {
+                last_seen = Instant::now();
                 match evt.as_ref().map(|o| o.as_deref()) {
                     // respond with a pong every ping to keep the websocket alive
                     Some(Ok(b"__ping__")) => {
                         ws.send(text_frame("__pong__")).await?;
                     }
+                    // the client answered our heartbeat
+                    Some(Ok(b"__pong__")) => {}
                     Some(Ok(evt)) => {
                         if let Ok(message) = serde_json::from_str::<IpcMessage>(&String::from_utf8_lossy(evt)) {
                             match message {
                                 IpcMessage::Event(evt) => {
+                                    if let Some(timeout) = limits.idle_timeout {
+                                        idle.as_mut().reset(Instant::now() + timeout);
+                                    }
+                                    handled_event = true;
+
                                     // Intercept the mounted event and insert a custom element type
                                     if let EventData::Mounted = &evt.data {
                                         let element = LiveviewElement::new(evt.element, query_engine.clone());
@@ -201,6 +318,9 @@ pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), Li
                                 IpcMessage::Query(result) => {
                                     query_engine.send(result);
                                 },
+                                IpcMessage::ClientState(state) => {
+                                    client_components.receive(state);
+                                }
                             }
                         }
                     }
@@ -210,6 +330,18 @@ pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), Li
                 }
             }
 
+            _ = async { heartbeat.as_mut().unwrap().tick().await }, if heartbeat.is_some() => {
+                let interval = heartbeat.as_ref().unwrap().period();
+                if last_seen.elapsed() > interval * 2 {
+                    return Err(LiveViewError::HeartbeatTimeout);
+                }
+                ws.send(text_frame("__ping__")).await?;
+            }
+
+            _ = &mut idle, if limits.idle_timeout.is_some() => {
+                return Err(LiveViewError::IdleTimeout);
+            }
+
             // handle any new queries
             Some(query) = query_rx.recv() => {
                 ws.send(text_frame(&serde_json::to_string(&ClientUpdate::Query(query)).unwrap())).await?;
@@ -230,6 +362,11 @@ pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), Li
             }
         }
 
+        // With render batching the updates of the event wait for the other events of the tick
+        if handled_event && !vdom.should_render_after_event() {
+            continue;
+        }
+
         let edits = vdom
             .render_with_deadline(tokio::time::sleep(Duration::from_millis(10)))
             .await;
@@ -244,6 +381,17 @@ pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), Li
         } {
             ws.send(edits).await?;
         }
+        vdom.flush_layout_effects();
+        check_memory(&vdom, &limits)?;
+    }
+}
+
+fn check_memory(vdom: &VirtualDom, limits: &SessionLimits) -> Result<(), LiveViewError> {
+    match limits.max_memory {
+        Some(limit) if vdom.allocated_bytes() > limit => {
+            Err(LiveViewError::MemoryLimitExceeded { limit })
+        }
+        _ => Ok(()),
     }
 }
 
@@ -325,6 +473,7 @@ fn apply_edits(
             AppendChildren { id, m } => channel.append_children(id.0 as u32, m as u16),
             AssignId { path, id } => channel.assign_id(path, id.0 as u32),
             CreatePlaceholder { id } => channel.create_placeholder(id.0 as u32),
+            CreateRawHtml { value, id } => channel.create_raw_html(value, id.0 as u32),
             CreateTextNode { value, id } => channel.create_text_node(value, id.0 as u32),
             HydrateText { path, value, id } => channel.hydrate_text(path, value, id.0 as u32),
             LoadTemplate { name, index, id } => {
@@ -363,6 +512,7 @@ fn apply_edits(
                 _ => unreachable!(),
             },
             SetText { value, id } => channel.set_text(id.0 as u32, value),
+            SetRawHtml { value, id } => channel.set_raw_html(id.0 as u32, value),
             NewEventListener { name, id, .. } => {
                 channel.new_event_listener(name, id.0 as u32, event_bubbles(name) as u8)
             }
@@ -387,3 +537,150 @@ enum ClientUpdate {
     #[serde(rename = "query")]
     Query(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    /// A socket connected to a fake client. The session ends when the client is dropped.
+    struct TestSocket {
+        incoming: UnboundedReceiver<Result<Vec<u8>, LiveViewError>>,
+        outgoing: UnboundedSender<Vec<u8>>,
+    }
+
+    /// The client side of a [`TestSocket`]
+    struct TestClient {
+        _send: UnboundedSender<Result<Vec<u8>, LiveViewError>>,
+        received: UnboundedReceiver<Vec<u8>>,
+    }
+
+    fn socket() -> (TestSocket, TestClient) {
+        let (send, incoming) = unbounded();
+        let (outgoing, received) = unbounded();
+        (
+            TestSocket { incoming, outgoing },
+            TestClient {
+                _send: send,
+                received,
+            },
+        )
+    }
+
+    impl futures_util::Stream for TestSocket {
+        type Item = Result<Vec<u8>, LiveViewError>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.incoming.poll_next_unpin(cx)
+        }
+    }
+
+    impl futures_util::Sink<Vec<u8>> for TestSocket {
+        type Error = LiveViewError;
+
+        fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: Vec<u8>) -> Result<(), Self::Error> {
+            self.outgoing
+                .unbounded_send(item)
+                .map_err(|_| LiveViewError::SendingFailed)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn app(cx: Scope) -> Element {
+        dioxus::prelude::render! { div { "hello" } }
+    }
+
+    #[test]
+    fn sessions_are_limited() {
+        let pool = LiveViewPool::new().with_max_sessions(2);
+        let first = pool.open_session().unwrap();
+        let second = pool.open_session().unwrap();
+        assert_eq!(pool.active_sessions(), 2);
+        assert!(matches!(
+            pool.open_session(),
+            Err(LiveViewError::TooManySessions)
+        ));
+        assert_eq!(pool.active_sessions(), 2);
+
+        // Closing a session makes room for another one
+        drop(first);
+        assert_eq!(pool.active_sessions(), 1);
+        let _third = pool.open_session().unwrap();
+        assert_eq!(pool.active_sessions(), 2);
+        drop(second);
+        assert_eq!(pool.active_sessions(), 1);
+    }
+
+    #[tokio::test]
+    async fn launching_over_the_limit_is_refused() {
+        let pool = LiveViewPool::new().with_max_sessions(1);
+        let (ws, mut client) = socket();
+        let session = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.launch(ws, app).await }
+        });
+        // The first frame is the initial render
+        assert!(client.received.next().await.is_some());
+        assert_eq!(pool.active_sessions(), 1);
+
+        let (ws, _other) = socket();
+        assert!(matches!(
+            pool.launch(ws, app).await,
+            Err(LiveViewError::TooManySessions)
+        ));
+
+        // The session ends when the client disconnects
+        drop(client);
+        session.await.unwrap().unwrap();
+        assert_eq!(pool.active_sessions(), 0);
+    }
+
+    #[tokio::test]
+    async fn idle_sessions_are_closed() {
+        let pool = LiveViewPool::new().with_idle_timeout(Duration::from_millis(50));
+        let (ws, _client) = socket();
+        assert!(matches!(
+            pool.launch(ws, app).await,
+            Err(LiveViewError::IdleTimeout)
+        ));
+        assert_eq!(pool.active_sessions(), 0);
+    }
+
+    #[tokio::test]
+    async fn clients_that_miss_heartbeats_are_closed() {
+        let pool = LiveViewPool::new().with_heartbeat_interval(Duration::from_millis(20));
+        let (ws, client) = socket();
+        assert!(matches!(
+            pool.launch(ws, app).await,
+            Err(LiveViewError::HeartbeatTimeout)
+        ));
+
+        // The client was pinged before it was closed
+        let frames: Vec<_> = client.received.collect().await;
+        assert!(frames.contains(&text_frame("__ping__")));
+    }
+
+    #[tokio::test]
+    async fn sessions_over_the_memory_limit_are_closed() {
+        let pool = LiveViewPool::new().with_max_session_memory(1);
+        let (ws, _client) = socket();
+        assert!(matches!(
+            pool.launch(ws, app).await,
+            Err(LiveViewError::MemoryLimitExceeded { limit: 1 })
+        ));
+        assert_eq!(pool.active_sessions(), 0);
+    }
+}
@@ -112,7 +112,8 @@ impl DioxusState {
                     self.set_element_id(node, id);
                     self.stack.push(node_id);
                 }
-                CreateTextNode { value, id } => {
+                // Native renderers can't parse html, so raw html is shown as text
+                CreateTextNode { value, id } | CreateRawHtml { value, id } => {
                     let node_data = NodeType::Text(TextNode {
                         listeners: FxHashSet::default(),
                         text: value.to_string(),
@@ -204,7 +205,7 @@ impl DioxusState {
                         }
                     }
                 }
-                SetText { value, id } => {
+                SetText { value, id } | SetRawHtml { value, id } => {
                     let node_id = self.element_to_node_id(id);
                     let mut node = rdom.get_mut(node_id).unwrap();
                     let node_type_mut = node.node_type_mut();
@@ -0,0 +1,158 @@
+//! A text input with a filtered list of suggestions.
+
+use crate::id::use_unique_id;
+use dioxus::html::input_data::keyboard_types::Key;
+use dioxus::prelude::*;
+
+/// The properties for a [`Combobox`].
+#[derive(Props)]
+pub struct ComboboxProps<'a> {
+    /// All of the options the user can pick from.
+    pub options: &'a [String],
+    /// Called when the user picks an option.
+    pub onselect: EventHandler<'a, String>,
+    /// The placeholder of the input.
+    pub placeholder: Option<&'a str>,
+    /// The accessible label of the input.
+    pub label: Option<&'a str>,
+    /// The class attribute for the root element.
+    pub class: Option<&'a str>,
+    /// The class attribute for the list of options.
+    pub listbox_class: Option<&'a str>,
+    /// The class attribute for each option.
+    pub option_class: Option<&'a str>,
+}
+
+/// A text input that suggests options matching the text the user typed.
+///
+/// The list of options opens when the user types or presses `ArrowDown`. The arrow keys move the active option, `Enter`
+/// picks it and `Escape` closes the list. Options are filtered with a case-insensitive substring match.
+///
+/// The active option receives a `data-active` attribute.
+pub fn Combobox<'a>(cx: Scope<'a, ComboboxProps<'a>>) -> Element<'a> {
+    let query = use_state(cx, String::new);
+    let open = use_state(cx, || false);
+    let active = use_state(cx, || None::<usize>);
+    let listbox_id = use_unique_id(cx, "combobox");
+
+    let matches: Vec<(usize, &String)> = filter_options(cx.props.options, query.get());
+    let match_count = matches.len();
+    let active_option = active.get().filter(|index| *index < match_count);
+    let active_descendant = active_option
+        .map(|index| format!("{listbox_id}-option-{index}"))
+        .unwrap_or_default();
+    let expanded = *open.get() && match_count > 0;
+
+    let select = move |option: String| {
+        query.set(option.clone());
+        open.set(false);
+        active.set(None);
+        cx.props.onselect.call(option);
+    };
+
+    render! {
+        div {
+            class: cx.props.class.unwrap_or_default(),
+            "data-state": if expanded { "open" } else { "closed" },
+            input {
+                r#type: "text",
+                role: "combobox",
+                autocomplete: "off",
+                aria_autocomplete: "list",
+                aria_expanded: expanded,
+                aria_controls: listbox_id,
+                aria_activedescendant: "{active_descendant}",
+                aria_label: cx.props.label.unwrap_or_default(),
+                placeholder: cx.props.placeholder.unwrap_or_default(),
+                value: "{query}",
+                oninput: move |evt| {
+                    query.set(evt.value.clone());
+                    open.set(true);
+                    active.set(None);
+                },
+                onkeydown: move |evt| match evt.key() {
+                    Key::ArrowDown => {
+                        open.set(true);
+                        if match_count > 0 {
+                            active.set(Some(active_option.map_or(0, |index| (index + 1) % match_count)));
+                        }
+                    }
+                    Key::ArrowUp => {
+                        open.set(true);
+                        if match_count > 0 {
+                            active.set(Some(active_option.map_or(match_count - 1, |index| {
+                                (index + match_count - 1) % match_count
+                            })));
+                        }
+                    }
+                    Key::Enter => {
+                        let matches = filter_options(cx.props.options, query.get());
+                        if let Some((_, option)) = active_option.and_then(|index| matches.get(index)) {
+                            select(option.to_string());
+                        }
+                    }
+                    Key::Escape => {
+                        open.set(false);
+                        active.set(None);
+                    }
+                    _ => {}
+                },
+                onblur: move |_| open.set(false),
+            }
+            if expanded {
+                rsx! {
+                    ul {
+                        id: listbox_id,
+                        class: cx.props.listbox_class.unwrap_or_default(),
+                        role: "listbox",
+                        for (position, &(index, option)) in matches.iter().enumerate() {
+                            li {
+                                key: "{index}",
+                                id: "{listbox_id}-option-{position}",
+                                class: cx.props.option_class.unwrap_or_default(),
+                                role: "option",
+                                aria_selected: active_option == Some(position),
+                                "data-active": active_option == Some(position),
+                                // Picking an option on mousedown keeps focus in the input, so the list is not closed by the blur event first
+                                prevent_default: "onmousedown",
+                                onmousedown: move |_| select(option.to_string()),
+                                "{option}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Find the options that contain the query, ignoring case
+fn filter_options<'a>(options: &'a [String], query: &str) -> Vec<(usize, &'a String)> {
+    let query = query.to_lowercase();
+    options
+        .iter()
+        .enumerate()
+        .filter(|(_, option)| option.to_lowercase().contains(&query))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_case_insensitively() {
+        let options = vec![
+            "Apple".to_string(),
+            "Pineapple".to_string(),
+            "Cherry".to_string(),
+        ];
+
+        let matches: Vec<_> = filter_options(&options, "APP")
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(matches, [0, 1]);
+        assert_eq!(filter_options(&options, "").len(), 3);
+    }
+}
@@ -0,0 +1,272 @@
+//! An accessible dialog that traps focus while it is open.
+
+use crate::eval::try_eval;
+use crate::id::use_unique_id;
+use dioxus::html::input_data::keyboard_types::Key;
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Remembers the element that was focused when the dialog opened and focuses it again once the dialog closes, unless
+/// focus already moved somewhere else outside of the dialog
+const RETURN_FOCUS: &str = r#"
+    const previous = document.activeElement;
+    await dioxus.recv();
+    const active = document.activeElement;
+    const focusLeft = active && active !== document.body && !active.closest(`#${CSS.escape(DIALOG_ID)}`);
+    if (previous && previous.isConnected && typeof previous.focus === "function" && !focusLeft) {
+        previous.focus();
+    }
+"#;
+
+/// Focuses the first focusable element of the dialog, or the last one if `LAST` is set. The dialog itself is focused
+/// if nothing inside of it can be.
+const FOCUS_EDGE: &str = r#"
+    const dialog = document.getElementById(DIALOG_ID);
+    if (dialog) {
+        const selector = `a[href], area[href], button, input, select, textarea, iframe, summary, [contenteditable], [tabindex]`;
+        const focusable = [...dialog.querySelectorAll(selector)].filter(
+            (element) => element.tabIndex >= 0 && !element.disabled && element.getClientRects().length > 0
+        );
+        ((LAST ? focusable[focusable.length - 1] : focusable[0]) ?? dialog).focus();
+    }
+"#;
+
+/// Runs [`RETURN_FOCUS`] while the dialog is open and moves focus back when it is dropped
+struct FocusReturn(UseEval);
+
+impl FocusReturn {
+    fn new(cx: &ScopeState, dialog_id: &str) -> Option<Self> {
+        // Without JavaScript there is no focus to return, see [`try_eval`]
+        try_eval(
+            cx,
+            format!("const DIALOG_ID = \"{dialog_id}\";{RETURN_FOCUS}"),
+        )
+        .map(Self)
+    }
+}
+
+impl Drop for FocusReturn {
+    fn drop(&mut self) {
+        let _ = self.0.send(().into());
+    }
+}
+
+/// The properties for a [`Dialog`].
+#[derive(Props)]
+pub struct DialogProps<'a> {
+    /// Whether the dialog is currently shown.
+    pub open: bool,
+    /// Called when the dialog requests to be closed, either because `Escape` was pressed or the overlay was clicked.
+    pub onclose: EventHandler<'a, ()>,
+    /// When [`true`] (the default), the dialog renders an overlay and keeps focus inside of the dialog while it is open.
+    #[props(default = true)]
+    pub modal: bool,
+    /// Whether clicking the overlay of a modal dialog should close it. Defaults to [`true`].
+    #[props(default = true)]
+    pub close_on_overlay_click: bool,
+    /// The accessible label of the dialog.
+    pub label: Option<&'a str>,
+    /// The class attribute for the dialog element.
+    pub class: Option<&'a str>,
+    /// The class attribute for the overlay element of a modal dialog.
+    pub overlay_class: Option<&'a str>,
+    /// The contents of the dialog.
+    pub children: Element<'a>,
+}
+
+/// A dialog window rendered on top of the rest of the page.
+///
+/// The first focusable element of the dialog is focused when it opens, and the dialog closes when `Escape` is pressed.
+/// When it closes, focus moves back to the element that was focused before the dialog opened. A modal dialog also
+/// renders an overlay behind the dialog and traps focus: tabbing past the last element of the dialog focuses the
+/// first one, and tabbing back from the first element focuses the last one.
+///
+/// The dialog does not manage its own open state, render it with `open` and update that state in `onclose`:
+///
+/// ```rust, ignore
+/// let open = use_state(cx, || false);
+///
+/// render! {
+///     button { onclick: move |_| open.set(true), "Open" }
+///     Dialog {
+///         open: **open,
+///         onclose: move |_| open.set(false),
+///         label: "Settings",
+///         "Dialog contents"
+///     }
+/// }
+/// ```
+pub fn Dialog<'a>(cx: Scope<'a, DialogProps<'a>>) -> Element<'a> {
+    let container: &Rc<RefCell<Option<Rc<MountedData>>>> = cx.use_hook(Default::default);
+    let id = use_unique_id(cx, "dialog");
+    let focus_return: &RefCell<Option<FocusReturn>> = cx.use_hook(Default::default);
+
+    if !cx.props.open {
+        // Dropping the guard moves focus back to where it was before the dialog opened
+        focus_return.borrow_mut().take();
+        return None;
+    }
+    if focus_return.borrow().is_none() {
+        *focus_return.borrow_mut() = FocusReturn::new(cx, id);
+    }
+
+    // Without JavaScript the elements of the dialog can't be searched, so the dialog itself is focused instead
+    let focus_edge = move |last: bool| {
+        let script = format!("const DIALOG_ID = \"{id}\"; const LAST = {last};{FOCUS_EDGE}");
+        let focused = try_eval(cx, script).is_some();
+        let container = container.borrow().clone();
+        async move {
+            if let Some(container) = container.filter(|_| !focused) {
+                _ = container.set_focus(true).await;
+            }
+        }
+    };
+
+    let dialog = render! {
+        div {
+            id: id,
+            class: cx.props.class.unwrap_or_default(),
+            role: "dialog",
+            aria_modal: cx.props.modal,
+            aria_label: cx.props.label.unwrap_or_default(),
+            tabindex: -1,
+            "data-state": "open",
+            onmounted: move |evt| {
+                *container.borrow_mut() = Some(evt.inner().clone());
+                focus_edge(false)
+            },
+            onclick: move |evt| evt.stop_propagation(),
+            onkeydown: move |evt| {
+                if evt.key() == Key::Escape {
+                    evt.stop_propagation();
+                    cx.props.onclose.call(());
+                }
+            },
+            &cx.props.children
+        }
+    };
+
+    if !cx.props.modal {
+        return dialog;
+    }
+
+    render! {
+        div {
+            class: cx.props.overlay_class.unwrap_or_default(),
+            "data-dialog-overlay": true,
+            onclick: move |_| {
+                if cx.props.close_on_overlay_click {
+                    cx.props.onclose.call(());
+                }
+            },
+            div { tabindex: 0, "data-focus-guard": true, onfocus: move |_| focus_edge(true) }
+            dialog
+            div { tabindex: 0, "data-focus-guard": true, onfocus: move |_| focus_edge(false) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::cell::Cell;
+
+    /// The scripts a renderer was asked to run, and the messages sent to each of them
+    #[derive(Default)]
+    struct Scripts {
+        scripts: RefCell<Vec<String>>,
+        sent: RefCell<Vec<usize>>,
+    }
+
+    struct TestEvalProvider(Rc<Scripts>);
+
+    impl EvalProvider for TestEvalProvider {
+        fn new_evaluator(&self, js: String) -> Result<Rc<dyn Evaluator>, EvalError> {
+            let mut scripts = self.0.scripts.borrow_mut();
+            scripts.push(js);
+            Ok(Rc::new(TestEvaluator {
+                scripts: self.0.clone(),
+                index: scripts.len() - 1,
+            }))
+        }
+    }
+
+    struct TestEvaluator {
+        scripts: Rc<Scripts>,
+        index: usize,
+    }
+
+    #[async_trait(?Send)]
+    impl Evaluator for TestEvaluator {
+        fn send(&self, _data: serde_json::Value) -> Result<(), EvalError> {
+            self.scripts.sent.borrow_mut().push(self.index);
+            Ok(())
+        }
+
+        async fn recv(&self) -> Result<serde_json::Value, EvalError> {
+            std::future::pending().await
+        }
+
+        async fn join(&self) -> Result<serde_json::Value, EvalError> {
+            std::future::pending().await
+        }
+    }
+
+    struct AppProps {
+        scripts: Rc<Scripts>,
+        open: Rc<Cell<bool>>,
+    }
+
+    fn app(cx: Scope<AppProps>) -> Element {
+        cx.use_hook(|| {
+            let provider: Rc<dyn EvalProvider> =
+                Rc::new(TestEvalProvider(cx.props.scripts.clone()));
+            cx.provide_context(provider)
+        });
+
+        render! {
+            Dialog { open: cx.props.open.get(), onclose: move |_| {}, label: "Settings", "Contents" }
+        }
+    }
+
+    #[test]
+    fn focus_returns_when_the_dialog_closes() {
+        let scripts = Rc::new(Scripts::default());
+        let open = Rc::new(Cell::new(true));
+        let mut dom = VirtualDom::new_with_props(
+            app,
+            AppProps {
+                scripts: scripts.clone(),
+                open: open.clone(),
+            },
+        );
+        _ = dom.rebuild();
+
+        // The element focused before the dialog opened is remembered while it is open
+        let return_focus = scripts
+            .scripts
+            .borrow()
+            .iter()
+            .position(|script| script.contains(RETURN_FOCUS))
+            .expect("the dialog should remember the focused element when it opens");
+        dom.mark_dirty(ScopeId::ROOT);
+        _ = dom.render_immediate();
+        assert!(scripts.sent.borrow().is_empty());
+        assert_eq!(scripts.scripts.borrow().len(), return_focus + 1);
+
+        // Closing the dialog tells the script to focus it again
+        open.set(false);
+        dom.mark_dirty(ScopeId::ROOT);
+        _ = dom.render_immediate();
+        assert_eq!(*scripts.sent.borrow(), [return_focus]);
+
+        // Opening it again remembers the focused element again
+        open.set(true);
+        dom.mark_dirty(ScopeId::ROOT);
+        _ = dom.render_immediate();
+        assert_eq!(scripts.scripts.borrow().len(), return_focus + 2);
+        assert!(scripts.scripts.borrow()[return_focus + 1].contains(RETURN_FOCUS));
+    }
+}
@@ -0,0 +1,14 @@
+//! Running the scripts some primitives need in the renderer.
+
+use dioxus::prelude::*;
+use std::rc::Rc;
+
+/// Start running `script` in the renderer.
+///
+/// Renderers without JavaScript (like SSR) do not provide an evaluator, so this returns `None` there. The primitives
+/// that use it still render, and skip whatever the script would have done.
+pub(crate) fn try_eval(cx: &ScopeState, script: String) -> Option<UseEval> {
+    cx.consume_context::<Rc<dyn EvalProvider>>()
+        .and_then(|provider| provider.new_evaluator(script).ok())
+        .map(UseEval::new)
+}
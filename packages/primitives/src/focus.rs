@@ -0,0 +1,621 @@
+//! Keyboard navigation helpers for composite widgets like menus, listboxes, toolbars, trees and grids.
+//!
+//! Composite widgets should only have a single tab stop. [`use_roving_focus`] tracks which item of the widget is
+//! currently active, gives that item `tabindex="0"` and every other item `tabindex="-1"`, and moves focus between
+//! items with the arrow keys, `Home`, `End` and typeahead.
+//!
+//! Prevent the default action of `onkeydown` on the items, so the arrow keys don't scroll the page while they move
+//! focus. It goes on the items and not the widget, because the focused item is the target of the key press.
+//!
+//! ```rust, ignore
+//! fn Menu(cx: Scope) -> Element {
+//!     let items = ["Copy", "Cut", "Paste"];
+//!     let roving = use_roving_focus(cx, RovingFocusConfig::new().orientation(Orientation::Vertical));
+//!     roving.set_items(items);
+//!
+//!     render! {
+//!         div {
+//!             role: "menu",
+//!             onkeydown: move |evt| {
+//!                 roving.onkeydown(&evt);
+//!             },
+//!             for (index, item) in items.iter().enumerate() {
+//!                 div {
+//!                     role: "menuitem",
+//!                     prevent_default: "onkeydown",
+//!                     tabindex: roving.tabindex(index),
+//!                     onmounted: move |evt| roving.mounted(index, evt.inner().clone()),
+//!                     onfocus: move |_| roving.set_active(index),
+//!                     "{item}"
+//!                 }
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+
+use dioxus::html::input_data::keyboard_types::{Key, Modifiers};
+use dioxus::prelude::*;
+use instant::{Duration, Instant};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// How long after the last key press typeahead starts a new search
+const TYPEAHEAD_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The arrow keys that move focus between the items of a widget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Orientation {
+    /// Left and right arrows move focus
+    Horizontal,
+    /// Up and down arrows move focus
+    #[default]
+    Vertical,
+    /// All arrow keys move focus
+    Both,
+}
+
+/// The configuration for [`use_roving_focus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RovingFocusConfig {
+    orientation: Orientation,
+    wrap: bool,
+    columns: Option<usize>,
+    typeahead: bool,
+}
+
+impl Default for RovingFocusConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RovingFocusConfig {
+    /// Create a new configuration for a vertical list that wraps around and supports typeahead.
+    pub fn new() -> Self {
+        Self {
+            orientation: Orientation::Vertical,
+            wrap: true,
+            columns: None,
+            typeahead: true,
+        }
+    }
+
+    /// Set the arrow keys that move focus between items.
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Set whether moving past the last item focuses the first item (and the other way around).
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Lay the items out in a grid with the given number of columns.
+    ///
+    /// Left and right arrows move within a row, up and down arrows move within a column. `Home` and `End` move to the
+    /// start and end of the row, or of the whole grid if `Ctrl` is held.
+    pub fn grid(mut self, columns: usize) -> Self {
+        self.columns = Some(columns.max(1));
+        self.orientation = Orientation::Both;
+        self
+    }
+
+    /// Set whether typing printable characters should focus the next item with a matching label.
+    pub fn typeahead(mut self, typeahead: bool) -> Self {
+        self.typeahead = typeahead;
+        self
+    }
+}
+
+/// An item in a widget with roving focus.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RovingItem {
+    label: String,
+    disabled: bool,
+}
+
+impl RovingItem {
+    /// Create a new item with the given label. The label is used for typeahead.
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            disabled: false,
+        }
+    }
+
+    /// Set whether the item is disabled. Disabled items are skipped by keyboard navigation.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl From<&str> for RovingItem {
+    fn from(label: &str) -> Self {
+        Self::new(label)
+    }
+}
+
+impl From<String> for RovingItem {
+    fn from(label: String) -> Self {
+        Self::new(label)
+    }
+}
+
+struct RovingFocusInner {
+    config: RovingFocusConfig,
+    items: Vec<RovingItem>,
+    elements: HashMap<usize, Rc<MountedData>>,
+    active: usize,
+    typeahead: String,
+    last_typeahead: Option<Instant>,
+}
+
+impl RovingFocusInner {
+    /// Move the active item to the nearest item that can be focused, if it was removed
+    fn keep_active_focusable(&mut self) {
+        let len = self.items.len();
+        if len == 0 {
+            self.active = 0;
+            return;
+        }
+        let active = self.active.min(len - 1);
+        let focusable = |index: &usize| !self.items[*index].disabled;
+        self.active = (0..=active)
+            .rev()
+            .find(focusable)
+            .or_else(|| (active..len).find(focusable))
+            .unwrap_or(active);
+    }
+
+    /// Add `text` typed at `now` to the typeahead search and find the item it matches
+    fn typeahead(&mut self, text: &str, now: Instant) -> Option<usize> {
+        let expired = self
+            .last_typeahead
+            .map_or(true, |last| now.duration_since(last) > TYPEAHEAD_TIMEOUT);
+        if expired {
+            self.typeahead.clear();
+        }
+        self.last_typeahead = Some(now);
+
+        let text = text.to_lowercase();
+        self.typeahead.push_str(&text);
+        let found = typeahead(&self.items, &self.typeahead, self.active);
+        if found.is_none() {
+            // Start a new search with just the latest character
+            self.typeahead = text;
+            return typeahead(&self.items, &self.typeahead, self.active);
+        }
+        found
+    }
+}
+
+/// The state of a widget with roving focus created by [`use_roving_focus`].
+#[derive(Clone)]
+pub struct RovingFocus {
+    inner: Rc<RefCell<RovingFocusInner>>,
+    update: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl RovingFocus {
+    /// Set the items of the widget. This should be called every time the component renders.
+    pub fn set_items<I: Into<RovingItem>>(&self, items: impl IntoIterator<Item = I>) {
+        let items: Vec<RovingItem> = items.into_iter().map(Into::into).collect();
+        let len = items.len();
+        self.inner.borrow_mut().items = items;
+        self.truncate(len);
+    }
+
+    /// Remove the items at `len` and after, so they can't be focused anymore.
+    ///
+    /// Call this with the number of items when items rendered with [`RovingFocus::set_item`] are removed.
+    pub fn truncate(&self, len: usize) {
+        let mut inner = self.inner.borrow_mut();
+        inner.items.truncate(len);
+        inner.elements.retain(|index, _| *index < len);
+        if inner.active >= len {
+            inner.keep_active_focusable();
+        }
+    }
+
+    /// Set a single item of the widget, growing the list of items if needed.
+    ///
+    /// This is useful when each item is rendered by its own component and the full list of items is not known in one
+    /// place. The list never shrinks here, so call [`RovingFocus::remove_item`] or [`RovingFocus::truncate`] when
+    /// items are removed.
+    pub fn set_item(&self, index: usize, item: impl Into<RovingItem>) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.items.len() <= index {
+            inner.items.resize(index + 1, RovingItem::default());
+        }
+        inner.items[index] = item.into();
+    }
+
+    /// Remove the item at `index` when the component that rendered it with [`RovingFocus::set_item`] is removed.
+    ///
+    /// The last item is truncated off the list. Items before it keep their place so the indices of the items after
+    /// them don't change, but are skipped by keyboard navigation.
+    pub fn remove_item(&self, index: usize) {
+        let len = self.inner.borrow().items.len();
+        if index + 1 == len {
+            self.truncate(index);
+        } else if index < len {
+            let mut inner = self.inner.borrow_mut();
+            inner.items[index].disabled = true;
+            inner.elements.remove(&index);
+            if inner.active == index {
+                inner.keep_active_focusable();
+            }
+        }
+    }
+
+    /// Register the mounted element for the item at `index` so it can be focused
+    pub fn mounted(&self, index: usize, element: Rc<MountedData>) {
+        self.inner.borrow_mut().elements.insert(index, element);
+    }
+
+    /// The index of the item that is currently active
+    pub fn active(&self) -> usize {
+        self.inner.borrow().active
+    }
+
+    /// Check if the item at `index` is currently active
+    pub fn is_active(&self, index: usize) -> bool {
+        self.active() == index
+    }
+
+    /// The tabindex the item at `index` should be rendered with
+    pub fn tabindex(&self, index: usize) -> i32 {
+        if self.is_active(index) {
+            0
+        } else {
+            -1
+        }
+    }
+
+    /// Make the item at `index` the active item without moving focus.
+    ///
+    /// This should be called when an item receives focus some other way (for example, when it is clicked).
+    pub fn set_active(&self, index: usize) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.active != index && index < inner.items.len() {
+            inner.active = index;
+            drop(inner);
+            (self.update)();
+        }
+    }
+
+    /// Make the item at `index` the active item and move focus to it
+    pub fn focus(&self, index: usize) -> impl Future<Output = ()> + 'static {
+        self.set_active(index);
+        let element = self.inner.borrow().elements.get(&index).cloned();
+        async move {
+            if let Some(element) = element {
+                if let Err(err) = element.set_focus(true).await {
+                    tracing::trace!("failed to focus item {index}: {err}");
+                }
+            }
+        }
+    }
+
+    /// Find the item the given key press should move focus to without changing any state.
+    pub fn next_index(&self, evt: &KeyboardData) -> Option<usize> {
+        let inner = self.inner.borrow();
+        let key = evt.key();
+        let ctrl = evt.modifiers().contains(Modifiers::CONTROL);
+        let enabled: Vec<bool> = inner.items.iter().map(|item| !item.disabled).collect();
+        navigate(&key, ctrl, inner.active, &enabled, &inner.config)
+    }
+
+    /// Handle a key press on the widget, moving focus to the next item if the key is a navigation key.
+    ///
+    /// Returns the index of the newly focused item, if focus moved.
+    pub fn onkeydown(&self, evt: &KeyboardData) -> Option<usize> {
+        let next = match self.next_index(evt) {
+            Some(next) => {
+                self.inner.borrow_mut().typeahead.clear();
+                Some(next)
+            }
+            None => self.typeahead(evt),
+        }?;
+        spawn(self.focus(next));
+        Some(next)
+    }
+
+    fn typeahead(&self, evt: &KeyboardData) -> Option<usize> {
+        let mut inner = self.inner.borrow_mut();
+        if !inner.config.typeahead {
+            return None;
+        }
+        let modifiers = evt.modifiers();
+        if modifiers.intersects(Modifiers::CONTROL | Modifiers::ALT | Modifiers::META) {
+            return None;
+        }
+        let Key::Character(text) = evt.key() else {
+            inner.typeahead.clear();
+            return None;
+        };
+
+        inner.typeahead(&text, Instant::now())
+    }
+}
+
+/// Create the state for a widget with a single tab stop where the arrow keys move focus between items.
+pub fn use_roving_focus(cx: &ScopeState, config: RovingFocusConfig) -> &RovingFocus {
+    let roving = cx.use_hook(|| RovingFocus {
+        inner: Rc::new(RefCell::new(RovingFocusInner {
+            config,
+            items: Vec::new(),
+            elements: HashMap::new(),
+            active: 0,
+            typeahead: String::new(),
+            last_typeahead: None,
+        })),
+        update: cx.schedule_update(),
+    });
+    roving.inner.borrow_mut().config = config;
+    roving
+}
+
+/// Find the item a navigation key moves focus to, skipping disabled items.
+fn navigate(
+    key: &Key,
+    ctrl: bool,
+    current: usize,
+    enabled: &[bool],
+    config: &RovingFocusConfig,
+) -> Option<usize> {
+    let len = enabled.len();
+    if len == 0 {
+        return None;
+    }
+    let horizontal = matches!(
+        config.orientation,
+        Orientation::Horizontal | Orientation::Both
+    );
+    let vertical = matches!(
+        config.orientation,
+        Orientation::Vertical | Orientation::Both
+    );
+    let columns = config.columns.unwrap_or(1);
+    let is_enabled = |index: usize| enabled.get(index).copied().unwrap_or(false);
+
+    // Walk from `current` in steps of `step`, returning the first enabled item
+    let walk = |step: isize| -> Option<usize> {
+        let mut index = current as isize;
+        for _ in 0..len {
+            index += step;
+            if index < 0 || index >= len as isize {
+                if !config.wrap {
+                    return None;
+                }
+                index = index.rem_euclid(len as isize);
+            }
+            if is_enabled(index as usize) {
+                return Some(index as usize);
+            }
+        }
+        None
+    };
+    let first_from = |range: &mut dyn Iterator<Item = usize>| range.find(|i| is_enabled(*i));
+
+    let next = match key {
+        Key::ArrowDown if config.columns.is_some() => walk(columns as isize),
+        Key::ArrowUp if config.columns.is_some() => walk(-(columns as isize)),
+        Key::ArrowDown if vertical => walk(1),
+        Key::ArrowUp if vertical => walk(-1),
+        Key::ArrowRight if horizontal => walk(1),
+        Key::ArrowLeft if horizontal => walk(-1),
+        Key::Home if config.columns.is_some() && !ctrl => {
+            let row_start = current - current % columns;
+            first_from(&mut (row_start..(row_start + columns).min(len)))
+        }
+        Key::End if config.columns.is_some() && !ctrl => {
+            let row_start = current - current % columns;
+            first_from(&mut (row_start..(row_start + columns).min(len)).rev())
+        }
+        Key::Home => first_from(&mut (0..len)),
+        Key::End => first_from(&mut (0..len).rev()),
+        _ => None,
+    }?;
+
+    (next != current).then_some(next)
+}
+
+/// Find the first item after `current` whose label starts with `search`.
+///
+/// If the search is a single repeated character, this cycles through the items starting with that character.
+fn typeahead(items: &[RovingItem], search: &str, current: usize) -> Option<usize> {
+    if items.is_empty() || search.is_empty() {
+        return None;
+    }
+    let mut chars = search.chars();
+    let first = chars.next()?;
+    let repeated = chars.all(|c| c == first);
+    let search = if repeated {
+        first.to_string()
+    } else {
+        search.to_string()
+    };
+    // A single character moves past the current item, a longer search may keep matching the current item
+    let start = if search.chars().count() == 1 {
+        current + 1
+    } else {
+        current
+    };
+
+    (0..items.len())
+        .map(|offset| (start + offset) % items.len())
+        .find(|index| {
+            let item = &items[*index];
+            !item.disabled && item.label.to_lowercase().starts_with(&search)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrows_skip_disabled_and_wrap() {
+        let config = RovingFocusConfig::new();
+        let enabled = [true, false, true, true];
+
+        assert_eq!(
+            navigate(&Key::ArrowDown, false, 0, &enabled, &config),
+            Some(2)
+        );
+        assert_eq!(
+            navigate(&Key::ArrowDown, false, 3, &enabled, &config),
+            Some(0)
+        );
+        assert_eq!(
+            navigate(&Key::ArrowUp, false, 0, &enabled, &config),
+            Some(3)
+        );
+        // a vertical widget ignores horizontal arrows
+        assert_eq!(
+            navigate(&Key::ArrowRight, false, 0, &enabled, &config),
+            None
+        );
+        assert_eq!(navigate(&Key::End, false, 0, &enabled, &config), Some(3));
+
+        let config = config.wrap(false);
+        assert_eq!(navigate(&Key::ArrowDown, false, 3, &enabled, &config), None);
+    }
+
+    #[test]
+    fn grid_navigation() {
+        let config = RovingFocusConfig::new().grid(3).wrap(false);
+        let enabled = [true; 9];
+
+        assert_eq!(
+            navigate(&Key::ArrowDown, false, 1, &enabled, &config),
+            Some(4)
+        );
+        assert_eq!(
+            navigate(&Key::ArrowRight, false, 4, &enabled, &config),
+            Some(5)
+        );
+        assert_eq!(navigate(&Key::ArrowUp, false, 1, &enabled, &config), None);
+        assert_eq!(navigate(&Key::Home, false, 5, &enabled, &config), Some(3));
+        assert_eq!(navigate(&Key::End, false, 3, &enabled, &config), Some(5));
+        assert_eq!(navigate(&Key::End, true, 3, &enabled, &config), Some(8));
+    }
+
+    #[test]
+    fn typeahead_matches_labels() {
+        let items: Vec<RovingItem> = ["Apple", "Banana", "Blueberry", "Cherry"]
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        assert_eq!(typeahead(&items, "b", 0), Some(1));
+        // repeating the same character cycles through matches
+        assert_eq!(typeahead(&items, "bb", 1), Some(2));
+        assert_eq!(typeahead(&items, "blu", 1), Some(2));
+        assert_eq!(typeahead(&items, "z", 0), None);
+    }
+
+    #[test]
+    fn typeahead_resets_after_a_pause() {
+        let mut inner = RovingFocusInner {
+            config: RovingFocusConfig::new(),
+            items: ["Apple", "Banana", "Blueberry", "Cherry"]
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            elements: HashMap::new(),
+            active: 0,
+            typeahead: String::new(),
+            last_typeahead: None,
+        };
+        let start = Instant::now();
+
+        assert_eq!(inner.typeahead("b", start), Some(1));
+        inner.active = 1;
+        assert_eq!(
+            inner.typeahead("l", start + Duration::from_millis(100)),
+            Some(2)
+        );
+        inner.active = 2;
+        assert_eq!(inner.typeahead, "bl");
+
+        // After a pause, "u" starts a new search instead of searching for "blu"
+        let later = start + Duration::from_millis(100) + TYPEAHEAD_TIMEOUT * 2;
+        assert_eq!(inner.typeahead("u", later), None);
+        assert_eq!(inner.typeahead, "u");
+        assert_eq!(inner.typeahead("c", later), Some(3));
+    }
+
+    fn roving(items: &[&str]) -> RovingFocus {
+        let roving = RovingFocus {
+            inner: Rc::new(RefCell::new(RovingFocusInner {
+                config: RovingFocusConfig::new(),
+                items: Vec::new(),
+                elements: HashMap::new(),
+                active: 0,
+                typeahead: String::new(),
+                last_typeahead: None,
+            })),
+            update: Arc::new(|| {}),
+        };
+        for (index, item) in items.iter().enumerate() {
+            roving.set_item(index, *item);
+        }
+        roving
+    }
+
+    fn enabled(roving: &RovingFocus) -> Vec<bool> {
+        let inner = roving.inner.borrow();
+        inner.items.iter().map(|item| !item.disabled).collect()
+    }
+
+    #[test]
+    fn truncated_items_are_not_focusable() {
+        let roving = roving(&["Copy", "Cut", "Paste", "Delete"]);
+        roving.set_active(3);
+
+        roving.truncate(2);
+        assert_eq!(enabled(&roving), [true, true]);
+        assert_eq!(roving.active(), 1);
+        let config = RovingFocusConfig::new();
+        assert_eq!(
+            navigate(&Key::End, false, 0, &enabled(&roving), &config),
+            Some(1)
+        );
+
+        // set_items truncates the same way
+        roving.set_items(["Copy"]);
+        assert_eq!(enabled(&roving), [true]);
+        assert_eq!(roving.active(), 0);
+    }
+
+    #[test]
+    fn removed_items_are_not_focusable() {
+        let roving = roving(&["Copy", "Cut", "Paste"]);
+
+        // Removing an item in the middle keeps the indices of the items after it
+        roving.remove_item(1);
+        assert_eq!(enabled(&roving), [true, false, true]);
+        let config = RovingFocusConfig::new();
+        assert_eq!(
+            navigate(&Key::ArrowDown, false, 0, &enabled(&roving), &config),
+            Some(2)
+        );
+
+        // The active item moves to the nearest item that is left
+        roving.set_active(2);
+        roving.remove_item(2);
+        assert_eq!(enabled(&roving), [true, false]);
+        assert_eq!(roving.active(), 0);
+        roving.remove_item(5);
+        assert_eq!(enabled(&roving), [true, false]);
+    }
+}
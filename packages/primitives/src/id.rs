@@ -0,0 +1,14 @@
+//! Unique ids for connecting elements through aria attributes like `aria-controls` and `aria-labelledby`.
+
+use dioxus::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Get an id that is unique to this component for the lifetime of the program.
+///
+/// The id is made of the prefix followed by a counter. Ids are handed out in the order components are created, so
+/// they are only stable across server and client renders if both create the same components in the same order.
+pub fn use_unique_id<'a>(cx: &'a ScopeState, prefix: &str) -> &'a str {
+    cx.use_hook(|| format!("{prefix}-{}", NEXT_ID.fetch_add(1, Ordering::Relaxed)))
+}
@@ -0,0 +1,30 @@
+#![doc = include_str!("../README.md")]
+#![doc(html_logo_url = "https://avatars.githubusercontent.com/u/79236386")]
+#![doc(html_favicon_url = "https://avatars.githubusercontent.com/u/79236386")]
+// cannot use forbid, because props derive macro generates #[allow(missing_docs)]
+#![deny(missing_docs)]
+#![allow(non_snake_case)]
+
+pub mod combobox;
+pub mod dialog;
+mod eval;
+pub mod focus;
+pub mod id;
+pub mod popover;
+pub mod positioning;
+pub mod sortable;
+pub mod tabs;
+pub mod tooltip;
+
+/// A collection of all the primitives in this crate.
+pub mod prelude {
+    pub use crate::combobox::*;
+    pub use crate::dialog::*;
+    pub use crate::focus::*;
+    pub use crate::id::*;
+    pub use crate::popover::*;
+    pub use crate::positioning::*;
+    pub use crate::sortable::*;
+    pub use crate::tabs::*;
+    pub use crate::tooltip::*;
+}
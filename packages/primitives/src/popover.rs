@@ -0,0 +1,188 @@
+//! A popover that shows floating content next to a trigger button.
+//!
+//! A popover is made of three parts: the [`Popover`] root that owns the open state, a [`PopoverTrigger`] that toggles
+//! it, and the [`PopoverContent`] that is shown while the popover is open.
+//!
+//! ```rust, ignore
+//! render! {
+//!     Popover {
+//!         PopoverTrigger { "Options" }
+//!         PopoverContent { placement: Placement::new(Side::Bottom, Align::Start), "Popover contents" }
+//!     }
+//! }
+//! ```
+
+use crate::id::use_unique_id;
+use crate::positioning::{use_floating, Placement, PositionOptions};
+use dioxus::html::input_data::keyboard_types::Key;
+use dioxus::prelude::*;
+use std::rc::Rc;
+
+/// The shared state of a [`Popover`].
+pub struct PopoverState {
+    open: bool,
+    content_id: String,
+    anchor: Option<Rc<MountedData>>,
+}
+
+impl PopoverState {
+    /// Check if the popover is open
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Open or close the popover
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    /// Toggle the popover
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    /// The id of the popover content element
+    pub fn content_id(&self) -> &str {
+        &self.content_id
+    }
+
+    /// The mounted trigger element the content is positioned next to
+    pub fn anchor(&self) -> Option<Rc<MountedData>> {
+        self.anchor.clone()
+    }
+}
+
+/// Get the state of the nearest [`Popover`] above the current component.
+pub fn use_popover(cx: &ScopeState) -> Option<&UseSharedState<PopoverState>> {
+    use_shared_state::<PopoverState>(cx)
+}
+
+/// The properties for a [`Popover`].
+#[derive(Props)]
+pub struct PopoverProps<'a> {
+    /// Whether the popover starts out open.
+    #[props(default)]
+    pub default_open: bool,
+    /// The class attribute for the root element.
+    pub class: Option<&'a str>,
+    /// The trigger and content of the popover.
+    pub children: Element<'a>,
+}
+
+/// The root of a popover. This provides the open state to the [`PopoverTrigger`] and [`PopoverContent`] inside of it.
+///
+/// Pressing `Escape` anywhere inside of the popover closes it.
+pub fn Popover<'a>(cx: Scope<'a, PopoverProps<'a>>) -> Element<'a> {
+    let content_id = use_unique_id(cx, "popover");
+    use_shared_state_provider(cx, || PopoverState {
+        open: cx.props.default_open,
+        content_id: content_id.to_string(),
+        anchor: None,
+    });
+    let state = use_popover(cx)?;
+    let open = state.read().is_open();
+
+    render! {
+        div {
+            class: cx.props.class.unwrap_or_default(),
+            "data-state": if open { "open" } else { "closed" },
+            onkeydown: move |evt| {
+                if evt.key() == Key::Escape && state.read().is_open() {
+                    state.write().set_open(false);
+                }
+            },
+            &cx.props.children
+        }
+    }
+}
+
+/// The properties for a [`PopoverTrigger`].
+#[derive(Props)]
+pub struct PopoverTriggerProps<'a> {
+    /// The class attribute for the trigger button.
+    pub class: Option<&'a str>,
+    /// The contents of the trigger button.
+    pub children: Element<'a>,
+}
+
+/// A button that toggles the nearest [`Popover`].
+pub fn PopoverTrigger<'a>(cx: Scope<'a, PopoverTriggerProps<'a>>) -> Element<'a> {
+    let state = use_popover(cx).expect("`PopoverTrigger` must be rendered inside of a `Popover`");
+    let (open, content_id) = {
+        let state = state.read();
+        (state.is_open(), state.content_id().to_string())
+    };
+
+    render! {
+        button {
+            class: cx.props.class.unwrap_or_default(),
+            r#type: "button",
+            aria_haspopup: "dialog",
+            aria_expanded: open,
+            aria_controls: "{content_id}",
+            "data-state": if open { "open" } else { "closed" },
+            onmounted: move |evt| state.write_silent().anchor = Some(evt.inner().clone()),
+            onclick: move |_| state.write().toggle(),
+            &cx.props.children
+        }
+    }
+}
+
+/// The properties for a [`PopoverContent`].
+#[derive(Props)]
+pub struct PopoverContentProps<'a> {
+    /// Where the content is placed relative to the trigger. Defaults to below the trigger, centered.
+    ///
+    /// The content is flipped to the other side of the trigger if it does not fit in the viewport.
+    #[props(default, into)]
+    pub placement: Placement,
+    /// The distance (in pixels) between the trigger and the content.
+    #[props(default = 4.0)]
+    pub offset: f64,
+    /// The class attribute for the content element.
+    pub class: Option<&'a str>,
+    /// The contents of the popover.
+    pub children: Element<'a>,
+}
+
+/// The floating content of the nearest [`Popover`]. This is only rendered while the popover is open.
+///
+/// The content is positioned next to the [`PopoverTrigger`] with [`use_floating`] and receives a `data-placement`
+/// attribute with the placement that was used after flipping.
+pub fn PopoverContent<'a>(cx: Scope<'a, PopoverContentProps<'a>>) -> Element<'a> {
+    let state = use_popover(cx).expect("`PopoverContent` must be rendered inside of a `Popover`");
+    let floating = use_floating(
+        cx,
+        PositionOptions::new(cx.props.placement).offset(cx.props.offset),
+    );
+    let (open, content_id) = {
+        let state = state.read();
+        (state.is_open(), state.content_id().to_string())
+    };
+    let placement = floating
+        .position()
+        .map_or(cx.props.placement, |position| position.placement);
+
+    if !open {
+        return None;
+    }
+
+    render! {
+        div {
+            id: "{content_id}",
+            class: cx.props.class.unwrap_or_default(),
+            role: "dialog",
+            style: "{floating.style()}",
+            "data-placement": "{placement.as_str()}",
+            "data-state": "open",
+            onmounted: move |evt| {
+                if let Some(anchor) = state.read().anchor() {
+                    floating.set_anchor(anchor);
+                }
+                floating.set_floating(evt.inner().clone());
+                floating.update()
+            },
+            &cx.props.children
+        }
+    }
+}
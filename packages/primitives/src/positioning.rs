@@ -0,0 +1,505 @@
+//! Positioning for floating elements like tooltips, popovers and dropdowns.
+//!
+//! [`compute_position`] places a floating element next to an anchor element, flipping it to the opposite side when it
+//! would overflow the viewport and shifting it along the anchor to keep it on screen. [`use_floating`] measures the
+//! anchor and floating elements with [`MountedData::get_client_rect`] and keeps the position up to date when the page
+//! is scrolled or resized.
+//!
+//! ```rust, ignore
+//! fn Dropdown(cx: Scope) -> Element {
+//!     let floating = use_floating(cx, PositionOptions::new(Placement::new(Side::Bottom, Align::Start)));
+//!
+//!     render! {
+//!         button { onmounted: move |evt| floating.set_anchor(evt.inner().clone()), "Menu" }
+//!         div {
+//!             style: "{floating.style()}",
+//!             onmounted: move |evt| {
+//!                 floating.set_floating(evt.inner().clone());
+//!                 floating.update()
+//!             },
+//!             "Menu items"
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::eval::try_eval;
+use dioxus::html::geometry::euclid::{Rect, Size2D};
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::future::Future;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Reports the size of the viewport whenever the page scrolls or is resized, until it receives a message
+const WATCH_VIEWPORT: &str = r#"
+    const send = () => dioxus.send([window.innerWidth, window.innerHeight]);
+    window.addEventListener("scroll", send, { capture: true, passive: true });
+    window.addEventListener("resize", send, { passive: true });
+    send();
+    await dioxus.recv();
+    window.removeEventListener("scroll", send, { capture: true });
+    window.removeEventListener("resize", send);
+"#;
+
+/// Removes the scroll and resize listeners of [`WATCH_VIEWPORT`] when the component is unmounted
+struct ViewportWatcher(UseEval);
+
+impl Drop for ViewportWatcher {
+    fn drop(&mut self) {
+        let _ = self.0.send(().into());
+    }
+}
+
+/// The side of the anchor a floating element is rendered on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Side {
+    /// Above the anchor
+    Top,
+    /// Below the anchor
+    #[default]
+    Bottom,
+    /// To the left of the anchor
+    Left,
+    /// To the right of the anchor
+    Right,
+}
+
+impl Side {
+    /// The side on the other side of the anchor
+    pub fn opposite(self) -> Self {
+        match self {
+            Side::Top => Side::Bottom,
+            Side::Bottom => Side::Top,
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Side::Top => "top",
+            Side::Bottom => "bottom",
+            Side::Left => "left",
+            Side::Right => "right",
+        }
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Side::Top | Side::Bottom)
+    }
+}
+
+/// How a floating element is aligned along the side of the anchor it is rendered on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    /// Align the start (left or top) edges of the anchor and floating element
+    Start,
+    /// Center the floating element on the anchor
+    #[default]
+    Center,
+    /// Align the end (right or bottom) edges of the anchor and floating element
+    End,
+}
+
+impl Align {
+    fn as_str(self) -> &'static str {
+        match self {
+            Align::Start => "start",
+            Align::Center => "center",
+            Align::End => "end",
+        }
+    }
+}
+
+/// Where a floating element is placed relative to its anchor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Placement {
+    /// The side of the anchor the floating element is rendered on
+    pub side: Side,
+    /// The alignment of the floating element along that side
+    pub align: Align,
+}
+
+impl Placement {
+    /// Create a new placement
+    pub const fn new(side: Side, align: Align) -> Self {
+        Self { side, align }
+    }
+
+    /// The placement as a string like `bottom-start`. This is useful for `data-*` attributes.
+    pub fn as_str(&self) -> String {
+        format!("{}-{}", self.side.as_str(), self.align.as_str())
+    }
+}
+
+impl From<Side> for Placement {
+    fn from(side: Side) -> Self {
+        Self::new(side, Align::Center)
+    }
+}
+
+/// The options for [`compute_position`] and [`use_floating`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionOptions {
+    placement: Placement,
+    offset: f64,
+    flip: bool,
+    shift: bool,
+    padding: f64,
+}
+
+impl Default for PositionOptions {
+    fn default() -> Self {
+        Self::new(Placement::default())
+    }
+}
+
+impl PositionOptions {
+    /// Create options with the preferred placement. Flipping and shifting are enabled by default.
+    pub fn new(placement: impl Into<Placement>) -> Self {
+        Self {
+            placement: placement.into(),
+            offset: 4.0,
+            flip: true,
+            shift: true,
+            padding: 8.0,
+        }
+    }
+
+    /// Set the distance (in pixels) between the anchor and the floating element.
+    pub fn offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Set whether the floating element is moved to the opposite side of the anchor if it does not fit on the preferred side.
+    pub fn flip(mut self, flip: bool) -> Self {
+        self.flip = flip;
+        self
+    }
+
+    /// Set whether the floating element is moved along the anchor to keep it within the viewport.
+    pub fn shift(mut self, shift: bool) -> Self {
+        self.shift = shift;
+        self
+    }
+
+    /// Set the minimum distance (in pixels) between the floating element and the edges of the viewport.
+    pub fn padding(mut self, padding: f64) -> Self {
+        self.padding = padding;
+        self
+    }
+}
+
+/// The computed position of a floating element, relative to the viewport.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    /// The left edge of the floating element
+    pub x: f64,
+    /// The top edge of the floating element
+    pub y: f64,
+    /// The placement that was used after flipping
+    pub placement: Placement,
+}
+
+impl Position {
+    /// The css that places a floating element at this position
+    pub fn css(&self) -> String {
+        format!(
+            "position: fixed; left: {}px; top: {}px;",
+            self.x.round(),
+            self.y.round()
+        )
+    }
+}
+
+/// Compute the position of a floating element with the given size next to an anchor.
+///
+/// The anchor rect and the returned position are relative to the viewport. If the size of the viewport is not known,
+/// the floating element is placed at the preferred placement without flipping or shifting.
+pub fn compute_position(
+    anchor: Rect<f64, f64>,
+    floating: Size2D<f64, f64>,
+    viewport: Option<Size2D<f64, f64>>,
+    options: &PositionOptions,
+) -> Position {
+    let mut placement = options.placement;
+
+    if let (Some(viewport), true) = (viewport, options.flip) {
+        let preferred = overflow(anchor, floating, viewport, placement, options);
+        if preferred > 0.0 {
+            let flipped = Placement::new(placement.side.opposite(), placement.align);
+            if overflow(anchor, floating, viewport, flipped, options) < preferred {
+                placement = flipped;
+            }
+        }
+    }
+
+    let (mut x, mut y) = place(anchor, floating, placement, options.offset);
+
+    if let (Some(viewport), true) = (viewport, options.shift) {
+        let clamp = |value: f64, size: f64, max: f64| {
+            let max = max - size - options.padding;
+            value.min(max).max(options.padding)
+        };
+        if placement.side.is_vertical() {
+            x = clamp(x, floating.width, viewport.width);
+        } else {
+            y = clamp(y, floating.height, viewport.height);
+        }
+    }
+
+    Position { x, y, placement }
+}
+
+/// Place the floating element at the placement without checking the viewport
+fn place(
+    anchor: Rect<f64, f64>,
+    floating: Size2D<f64, f64>,
+    placement: Placement,
+    offset: f64,
+) -> (f64, f64) {
+    let align = |start: f64, anchor_size: f64, floating_size: f64| match placement.align {
+        Align::Start => start,
+        Align::Center => start + (anchor_size - floating_size) / 2.0,
+        Align::End => start + anchor_size - floating_size,
+    };
+
+    match placement.side {
+        Side::Top => (
+            align(anchor.min_x(), anchor.width(), floating.width),
+            anchor.min_y() - floating.height - offset,
+        ),
+        Side::Bottom => (
+            align(anchor.min_x(), anchor.width(), floating.width),
+            anchor.max_y() + offset,
+        ),
+        Side::Left => (
+            anchor.min_x() - floating.width - offset,
+            align(anchor.min_y(), anchor.height(), floating.height),
+        ),
+        Side::Right => (
+            anchor.max_x() + offset,
+            align(anchor.min_y(), anchor.height(), floating.height),
+        ),
+    }
+}
+
+/// How far the floating element sticks out of the viewport along the main axis of the placement
+fn overflow(
+    anchor: Rect<f64, f64>,
+    floating: Size2D<f64, f64>,
+    viewport: Size2D<f64, f64>,
+    placement: Placement,
+    options: &PositionOptions,
+) -> f64 {
+    let (x, y) = place(anchor, floating, placement, options.offset);
+    let padding = options.padding;
+    let overflow = match placement.side {
+        Side::Top => padding - y,
+        Side::Bottom => y + floating.height - (viewport.height - padding),
+        Side::Left => padding - x,
+        Side::Right => x + floating.width - (viewport.width - padding),
+    };
+    overflow.max(0.0)
+}
+
+struct FloatingInner {
+    options: PositionOptions,
+    anchor: Option<Rc<MountedData>>,
+    floating: Option<Rc<MountedData>>,
+    viewport: Option<Size2D<f64, f64>>,
+    position: Option<Position>,
+}
+
+/// The state of a floating element created by [`use_floating`].
+#[derive(Clone)]
+pub struct UseFloating {
+    inner: Rc<RefCell<FloatingInner>>,
+    rerender: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl UseFloating {
+    /// Set the element the floating element is positioned next to
+    pub fn set_anchor(&self, anchor: Rc<MountedData>) {
+        self.inner.borrow_mut().anchor = Some(anchor);
+    }
+
+    /// Set the floating element
+    pub fn set_floating(&self, floating: Rc<MountedData>) {
+        self.inner.borrow_mut().floating = Some(floating);
+    }
+
+    /// The last computed position, if both elements have been measured
+    pub fn position(&self) -> Option<Position> {
+        self.inner.borrow().position
+    }
+
+    /// The css for the floating element.
+    ///
+    /// Until the position has been computed, the element is rendered invisible so it can be measured without flashing at the wrong position.
+    pub fn style(&self) -> String {
+        match self.position() {
+            Some(position) => position.css(),
+            None => "position: fixed; left: 0px; top: 0px; visibility: hidden;".to_string(),
+        }
+    }
+
+    /// Measure the anchor and floating elements and recompute the position.
+    ///
+    /// This should be called after both elements have been mounted. The position is updated automatically when the page is scrolled or resized.
+    pub fn update(&self) -> impl Future<Output = ()> + 'static {
+        let floating = self.clone();
+        async move {
+            let (anchor, element) = {
+                let inner = floating.inner.borrow();
+                (inner.anchor.clone(), inner.floating.clone())
+            };
+            let (Some(anchor), Some(element)) = (anchor, element) else {
+                return;
+            };
+            let (Ok(anchor), Ok(element)) = (
+                anchor.get_client_rect().await,
+                element.get_client_rect().await,
+            ) else {
+                return;
+            };
+
+            let mut inner = floating.inner.borrow_mut();
+            let position = compute_position(anchor, element.size, inner.viewport, &inner.options);
+            if inner.position != Some(position) {
+                inner.position = Some(position);
+                drop(inner);
+                (floating.rerender)();
+            }
+        }
+    }
+}
+
+struct FloatingState {
+    floating: UseFloating,
+    _watcher: Option<ViewportWatcher>,
+}
+
+/// Position a floating element next to an anchor element and keep it positioned while the page scrolls or resizes.
+pub fn use_floating(cx: &ScopeState, options: PositionOptions) -> &UseFloating {
+    let state = cx.use_hook(|| {
+        let floating = UseFloating {
+            inner: Rc::new(RefCell::new(FloatingInner {
+                options,
+                anchor: None,
+                floating: None,
+                viewport: None,
+                position: None,
+            })),
+            rerender: cx.schedule_update(),
+        };
+
+        // Without JavaScript the position is still computed, but without the viewport. See [`try_eval`].
+        let watcher = try_eval(cx, WATCH_VIEWPORT.to_string());
+        if let Some(evaluator) = watcher.clone() {
+            let floating = floating.clone();
+            cx.push_future(async move {
+                while let Ok(value) = evaluator.recv().await {
+                    let size: Option<(f64, f64)> = value
+                        .as_array()
+                        .and_then(|size| Some((size.first()?.as_f64()?, size.get(1)?.as_f64()?)));
+                    if let Some((width, height)) = size {
+                        floating.inner.borrow_mut().viewport = Some(Size2D::new(width, height));
+                        floating.update().await;
+                    }
+                }
+            });
+        }
+
+        FloatingState {
+            floating,
+            _watcher: watcher.map(ViewportWatcher),
+        }
+    });
+    let floating = &state.floating;
+
+    let changed = {
+        let mut inner = floating.inner.borrow_mut();
+        let changed = inner.options != options;
+        inner.options = options;
+        changed
+    };
+    if changed {
+        cx.spawn(floating.update());
+    }
+
+    floating
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anchor() -> Rect<f64, f64> {
+        Rect::new((100.0, 100.0).into(), (50.0, 20.0).into())
+    }
+
+    #[test]
+    fn places_on_each_side() {
+        let floating = Size2D::new(30.0, 10.0);
+        let options = |placement: Placement| PositionOptions::new(placement).offset(5.0);
+
+        let position = compute_position(anchor(), floating, None, &options(Side::Bottom.into()));
+        assert_eq!((position.x, position.y), (110.0, 125.0));
+
+        let position = compute_position(
+            anchor(),
+            floating,
+            None,
+            &options(Placement::new(Side::Top, Align::Start)),
+        );
+        assert_eq!((position.x, position.y), (100.0, 85.0));
+
+        let position = compute_position(
+            anchor(),
+            floating,
+            None,
+            &options(Placement::new(Side::Right, Align::End)),
+        );
+        assert_eq!((position.x, position.y), (155.0, 110.0));
+    }
+
+    #[test]
+    fn flips_when_overflowing() {
+        let floating = Size2D::new(30.0, 50.0);
+        let viewport = Some(Size2D::new(500.0, 160.0));
+
+        let position = compute_position(
+            anchor(),
+            floating,
+            viewport,
+            &PositionOptions::new(Side::Bottom),
+        );
+        assert_eq!(position.placement.side, Side::Top);
+
+        // without flipping the preferred side is kept
+        let position = compute_position(
+            anchor(),
+            floating,
+            viewport,
+            &PositionOptions::new(Side::Bottom).flip(false),
+        );
+        assert_eq!(position.placement.side, Side::Bottom);
+    }
+
+    #[test]
+    fn shifts_into_viewport() {
+        let floating = Size2D::new(200.0, 10.0);
+        let viewport = Some(Size2D::new(220.0, 500.0));
+
+        let position = compute_position(
+            anchor(),
+            floating,
+            viewport,
+            &PositionOptions::new(Placement::new(Side::Bottom, Align::Start)),
+        );
+        assert_eq!(position.x, 12.0);
+    }
+}
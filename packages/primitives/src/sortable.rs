@@ -0,0 +1,588 @@
+//! Primitives for reordering a list of items by dragging them with the pointer.
+//!
+//! A sortable list is made of a [`DropZone`] that owns the drag state and tracks the pointer, and one [`Draggable`]
+//! per item. When the user releases a dragged item, the [`DropZone`] emits a [`Reorder`] event that you can apply to
+//! your own list of items.
+
+use dioxus::html::geometry::euclid::Rect;
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// How far (in pixels) the pointer has to move before a press turns into a drag. This keeps clicks on draggable items working.
+const DRAG_THRESHOLD: f64 = 4.0;
+
+/// The axis the items of a sortable list are laid out along.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Axis {
+    /// Items are stacked from top to bottom
+    #[default]
+    Vertical,
+    /// Items are laid out from left to right
+    Horizontal,
+}
+
+impl Axis {
+    fn along(self, x: f64, y: f64) -> f64 {
+        match self {
+            Axis::Vertical => y,
+            Axis::Horizontal => x,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Axis::Vertical => "vertical",
+            Axis::Horizontal => "horizontal",
+        }
+    }
+}
+
+/// A request to move the item at index `from` so that it ends up at index `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reorder {
+    /// The index of the item that was dragged
+    pub from: usize,
+    /// The index the item should be moved to
+    pub to: usize,
+}
+
+impl Reorder {
+    /// Apply the reorder to a list of items.
+    ///
+    /// Out of bounds reorders are ignored.
+    pub fn apply<T>(&self, items: &mut Vec<T>) {
+        if self.from == self.to || self.from >= items.len() || self.to >= items.len() {
+            return;
+        }
+        let item = items.remove(self.from);
+        items.insert(self.to, item);
+    }
+}
+
+struct Drag {
+    index: usize,
+    pointer_id: i32,
+    origin: (f64, f64),
+    pointer: (f64, f64),
+    active: bool,
+    target: usize,
+    rects: Vec<(usize, Rect<f64, f64>)>,
+}
+
+struct SortableInner {
+    axis: Axis,
+    // The index and element of every item, by the scope of its `Draggable`. Items keep their scope when the list is
+    // reordered, so the index is updated on every render.
+    elements: HashMap<ScopeId, (usize, Rc<MountedData>)>,
+    subscribers: HashSet<ScopeId>,
+    drag: Option<Drag>,
+    // Bumped for every new drag so measurements from an older drag are discarded
+    generation: usize,
+}
+
+/// The shared state of a sortable list.
+///
+/// This is created by [`use_sortable`] and provided to every [`Draggable`] inside of a [`DropZone`] through the context API.
+#[derive(Clone)]
+pub struct Sortable {
+    inner: Rc<RefCell<SortableInner>>,
+    update_any: Arc<dyn Fn(ScopeId) + Send + Sync>,
+}
+
+impl Sortable {
+    /// The axis the list is laid out along
+    pub fn axis(&self) -> Axis {
+        self.inner.borrow().axis
+    }
+
+    /// Rerender the component with the given id whenever the drag state changes
+    pub fn subscribe(&self, scope: ScopeId) {
+        self.inner.borrow_mut().subscribers.insert(scope);
+    }
+
+    /// Stop rerendering the component with the given id when the drag state changes
+    pub fn unsubscribe(&self, scope: ScopeId) {
+        self.inner.borrow_mut().subscribers.remove(&scope);
+    }
+
+    /// Register the mounted element of the item rendered by the component with the given id, so it can be used for
+    /// hit testing
+    pub fn register(&self, scope: ScopeId, index: usize, element: Rc<MountedData>) {
+        self.inner
+            .borrow_mut()
+            .elements
+            .insert(scope, (index, element));
+    }
+
+    /// Update the index of the item rendered by the component with the given id. Call this on every render, because
+    /// the same component may render a different item after the list was reordered.
+    pub fn set_index(&self, scope: ScopeId, index: usize) {
+        if let Some((current, _)) = self.inner.borrow_mut().elements.get_mut(&scope) {
+            *current = index;
+        }
+    }
+
+    /// Remove the mounted element of the item rendered by the component with the given id
+    pub fn unregister(&self, scope: ScopeId) {
+        self.inner.borrow_mut().elements.remove(&scope);
+    }
+
+    /// The index of the item that is currently being dragged, if any
+    pub fn dragging(&self) -> Option<usize> {
+        let inner = self.inner.borrow();
+        inner
+            .drag
+            .as_ref()
+            .filter(|drag| drag.active)
+            .map(|drag| drag.index)
+    }
+
+    /// The index the dragged item would be moved to if it was released now
+    pub fn target(&self) -> Option<usize> {
+        let inner = self.inner.borrow();
+        inner
+            .drag
+            .as_ref()
+            .filter(|drag| drag.active)
+            .map(|drag| drag.target)
+    }
+
+    /// The rect the item at `index` should be rendered at while it is being dragged.
+    ///
+    /// This is the rect the item had when the drag started, moved along the axis of the list by the distance the pointer traveled.
+    pub fn floating_rect(&self, index: usize) -> Option<Rect<f64, f64>> {
+        let inner = self.inner.borrow();
+        let drag = inner.drag.as_ref().filter(|drag| drag.active)?;
+        if drag.index != index {
+            return None;
+        }
+        let (_, rect) = drag.rects.iter().find(|(i, _)| *i == index)?;
+        let mut rect = *rect;
+        match inner.axis {
+            Axis::Vertical => rect.origin.y += drag.pointer.1 - drag.origin.1,
+            Axis::Horizontal => rect.origin.x += drag.pointer.0 - drag.origin.0,
+        }
+        Some(rect)
+    }
+
+    /// Check if the placeholder for the dragged item should be rendered directly before the item at `index`
+    pub fn placeholder_before(&self, index: usize) -> bool {
+        let inner = self.inner.borrow();
+        matches!(&inner.drag, Some(drag) if drag.active && drag.target < drag.index && drag.target == index)
+    }
+
+    /// Check if the placeholder for the dragged item should be rendered directly after the item at `index`
+    pub fn placeholder_after(&self, index: usize) -> bool {
+        let inner = self.inner.borrow();
+        matches!(&inner.drag, Some(drag) if drag.active && drag.target >= drag.index && drag.target == index)
+    }
+
+    /// The size of the item that is currently being dragged, if it has been measured
+    pub fn placeholder_size(&self) -> Option<(f64, f64)> {
+        let inner = self.inner.borrow();
+        let drag = inner.drag.as_ref().filter(|drag| drag.active)?;
+        drag.rects
+            .iter()
+            .find(|(i, _)| *i == drag.index)
+            .map(|(_, rect)| (rect.size.width, rect.size.height))
+    }
+
+    /// Start tracking a press on the item at `index`. The press turns into a drag once the pointer moves far enough.
+    ///
+    /// You should spawn the future returned by [`Sortable::measure`] after calling this so the items can be hit tested.
+    pub fn start(&self, index: usize, pointer_id: i32, x: f64, y: f64) {
+        let mut inner = self.inner.borrow_mut();
+        inner.generation += 1;
+        inner.drag = Some(Drag {
+            index,
+            pointer_id,
+            origin: (x, y),
+            pointer: (x, y),
+            active: false,
+            target: index,
+            rects: Vec::new(),
+        });
+    }
+
+    /// Measure every registered item for the current drag.
+    pub fn measure(&self) -> impl Future<Output = ()> + 'static {
+        let sortable = self.clone();
+        let (generation, elements) = {
+            let inner = self.inner.borrow();
+            let elements: Vec<_> = inner
+                .elements
+                .values()
+                .map(|(index, element)| (*index, element.clone()))
+                .collect();
+            (inner.generation, elements)
+        };
+
+        async move {
+            let mut rects = Vec::with_capacity(elements.len());
+            for (index, element) in elements {
+                match element.get_client_rect().await {
+                    Ok(rect) => rects.push((index, rect)),
+                    Err(err) => tracing::trace!("failed to measure sortable item {index}: {err}"),
+                }
+            }
+
+            let mut inner = sortable.inner.borrow_mut();
+            let axis = inner.axis;
+            if inner.generation != generation {
+                return;
+            }
+            if let Some(drag) = &mut inner.drag {
+                drag.rects = rects;
+                drag.target = hit_test(&drag.rects, axis, drag.index, drag.pointer);
+            }
+            drop(inner);
+            sortable.notify();
+        }
+    }
+
+    /// Update the position of the pointer.
+    ///
+    /// If the pointer is no longer pressed (because it was released outside of the drop zone), the drag is cancelled.
+    pub fn pointer_move(&self, pointer_id: i32, x: f64, y: f64, buttons: u16) {
+        let mut inner = self.inner.borrow_mut();
+        let axis = inner.axis;
+        let Some(drag) = &mut inner.drag else {
+            return;
+        };
+        if drag.pointer_id != pointer_id {
+            return;
+        }
+        if buttons == 0 {
+            drop(inner);
+            self.cancel();
+            return;
+        }
+
+        drag.pointer = (x, y);
+        if !drag.active {
+            let (dx, dy) = (x - drag.origin.0, y - drag.origin.1);
+            if (dx * dx + dy * dy).sqrt() < DRAG_THRESHOLD {
+                return;
+            }
+            drag.active = true;
+        }
+        drag.target = hit_test(&drag.rects, axis, drag.index, drag.pointer);
+
+        drop(inner);
+        self.notify();
+    }
+
+    /// Finish the drag with the given pointer, returning the reorder that should be applied if the item was moved
+    pub fn finish(&self, pointer_id: i32) -> Option<Reorder> {
+        let drag = {
+            let mut inner = self.inner.borrow_mut();
+            if inner.drag.as_ref().map(|drag| drag.pointer_id) != Some(pointer_id) {
+                return None;
+            }
+            inner.drag.take()?
+        };
+        if !drag.active {
+            return None;
+        }
+        self.notify();
+
+        (drag.target != drag.index).then_some(Reorder {
+            from: drag.index,
+            to: drag.target,
+        })
+    }
+
+    /// Cancel the current drag without reordering anything
+    pub fn cancel(&self) {
+        let was_active = {
+            let mut inner = self.inner.borrow_mut();
+            inner.drag.take().map(|drag| drag.active)
+        };
+        if was_active == Some(true) {
+            self.notify();
+        }
+    }
+
+    fn notify(&self) {
+        let subscribers: Vec<_> = self.inner.borrow().subscribers.iter().copied().collect();
+        for scope in subscribers {
+            (self.update_any)(scope);
+        }
+    }
+}
+
+/// Find the index the dragged item should be moved to.
+///
+/// The returned index is the position in the list after the dragged item has been removed, which matches the semantics of [`Reorder`].
+fn hit_test(
+    rects: &[(usize, Rect<f64, f64>)],
+    axis: Axis,
+    dragged: usize,
+    (x, y): (f64, f64),
+) -> usize {
+    if rects.is_empty() {
+        return dragged;
+    }
+    let pointer = axis.along(x, y);
+    let before = rects
+        .iter()
+        .filter(|(index, _)| *index != dragged)
+        .filter(|(_, rect)| {
+            let center = rect.center();
+            axis.along(center.x, center.y) < pointer
+        })
+        .count();
+    before.min(rects.len() - 1)
+}
+
+/// Create the state for a sortable list and provide it to any children of the current component.
+///
+/// The returned [`Sortable`] is shared with every [`Draggable`] rendered below the current component. Most applications
+/// should use the [`DropZone`] component which calls this hook and wires up the pointer events for you.
+pub fn use_sortable(cx: &ScopeState, axis: Axis) -> &Sortable {
+    let sortable = cx.use_hook(|| {
+        let sortable = Sortable {
+            inner: Rc::new(RefCell::new(SortableInner {
+                axis,
+                elements: HashMap::new(),
+                subscribers: HashSet::new(),
+                drag: None,
+                generation: 0,
+            })),
+            update_any: cx.schedule_update_any(),
+        };
+        sortable.subscribe(cx.scope_id());
+        cx.provide_context(sortable)
+    });
+    sortable.inner.borrow_mut().axis = axis;
+    sortable
+}
+
+/// The properties for a [`DropZone`].
+#[derive(Props)]
+pub struct DropZoneProps<'a> {
+    /// The axis the items are laid out along. Defaults to [`Axis::Vertical`].
+    #[props(default)]
+    pub axis: Axis,
+    /// Called when an item was dropped at a new position.
+    pub onreorder: EventHandler<'a, Reorder>,
+    /// The class attribute for the container element.
+    pub class: Option<&'a str>,
+    /// The [`Draggable`] items in the list.
+    pub children: Element<'a>,
+}
+
+/// A container for a sortable list of [`Draggable`] items.
+///
+/// The dragged item captures the pointer, so the drop zone keeps tracking it when it leaves the zone or the window.
+///
+/// The container receives a `data-dragging` attribute while an item is being dragged.
+pub fn DropZone<'a>(cx: Scope<'a, DropZoneProps<'a>>) -> Element<'a> {
+    let sortable = use_sortable(cx, cx.props.axis);
+    let dragging = sortable.dragging().is_some();
+
+    render! {
+        div {
+            class: cx.props.class.unwrap_or_default(),
+            role: "list",
+            "data-sortable-axis": sortable.axis().as_str(),
+            "data-dragging": dragging,
+            onpointermove: move |evt| {
+                sortable.pointer_move(evt.pointer_id, evt.client_x as f64, evt.client_y as f64, evt.buttons);
+            },
+            onpointerup: move |evt| {
+                if let Some(reorder) = sortable.finish(evt.pointer_id) {
+                    cx.props.onreorder.call(reorder);
+                }
+            },
+            onpointercancel: move |_| sortable.cancel(),
+            &cx.props.children
+        }
+    }
+}
+
+/// The properties for a [`Draggable`].
+#[derive(Props)]
+pub struct DraggableProps<'a> {
+    /// The index of this item in the list.
+    pub index: usize,
+    /// The class attribute for the item element.
+    pub class: Option<&'a str>,
+    /// The class attribute for the placeholder rendered where the item will be dropped.
+    pub placeholder_class: Option<&'a str>,
+    /// The contents of the item.
+    pub children: Element<'a>,
+}
+
+/// An item of a sortable list. This must be rendered inside of a [`DropZone`] (or a component that calls [`use_sortable`]).
+///
+/// While the item is dragged, it is taken out of the document flow and follows the pointer along the axis of the list.
+/// An empty placeholder of the same size is rendered at the position the item will be dropped at.
+///
+/// The item receives a `data-dragging` attribute while it is being dragged.
+pub fn Draggable<'a>(cx: Scope<'a, DraggableProps<'a>>) -> Element<'a> {
+    let sortable: &SortableSubscription = cx.use_hook(|| {
+        let sortable = cx
+            .consume_context::<Sortable>()
+            .expect("`Draggable` must be rendered inside of a `DropZone`");
+        sortable.subscribe(cx.scope_id());
+        SortableSubscription {
+            sortable,
+            scope: cx.scope_id(),
+            element: RefCell::new(None),
+        }
+    });
+    let index = cx.props.index;
+    let sortable_ref = &sortable.sortable;
+    // The element was registered with the index of the first render
+    sortable_ref.set_index(cx.scope_id(), index);
+
+    let dragging = sortable_ref.dragging() == Some(index);
+    let style = match sortable_ref.floating_rect(index) {
+        Some(rect) => format!(
+            "position: fixed; left: {}px; top: {}px; width: {}px; height: {}px; pointer-events: none; z-index: 1000;",
+            rect.origin.x, rect.origin.y, rect.size.width, rect.size.height
+        ),
+        None => String::new(),
+    };
+    let placeholder_style = sortable_ref
+        .placeholder_size()
+        .map(|(width, height)| format!("width: {width}px; height: {height}px;"))
+        .unwrap_or_default();
+    let placeholder_class = cx.props.placeholder_class.unwrap_or_default();
+
+    render! {
+        if sortable_ref.placeholder_before(index) {
+            rsx! { div { class: placeholder_class, style: "{placeholder_style}", "data-sortable-placeholder": true } }
+        }
+        div {
+            class: cx.props.class.unwrap_or_default(),
+            role: "listitem",
+            style: "{style}",
+            "data-dragging": dragging,
+            onmounted: move |evt| {
+                sortable.register(index, evt.inner().clone());
+            },
+            onpointerdown: move |evt| {
+                // only start dragging with the primary button
+                if evt.button != 0 {
+                    return;
+                }
+                sortable_ref.start(index, evt.pointer_id, evt.client_x as f64, evt.client_y as f64);
+                cx.spawn(sortable_ref.measure());
+                // Keep the events of the pointer coming while it is outside of the drop zone
+                if let Some(element) = sortable.element.borrow().clone() {
+                    let pointer_id = evt.pointer_id;
+                    cx.spawn(async move {
+                        if let Err(err) = element.set_pointer_capture(pointer_id).await {
+                            tracing::trace!("failed to capture the pointer of a sortable item: {err}");
+                        }
+                    });
+                }
+            },
+            &cx.props.children
+        }
+        if sortable_ref.placeholder_after(index) {
+            rsx! { div { class: placeholder_class, style: "{placeholder_style}", "data-sortable-placeholder": true } }
+        }
+    }
+}
+
+/// Keeps a [`Draggable`] registered with its [`Sortable`] and cleans up when the item is removed
+struct SortableSubscription {
+    sortable: Sortable,
+    scope: ScopeId,
+    element: RefCell<Option<Rc<MountedData>>>,
+}
+
+impl SortableSubscription {
+    fn register(&self, index: usize, element: Rc<MountedData>) {
+        self.sortable.register(self.scope, index, element.clone());
+        *self.element.borrow_mut() = Some(element);
+    }
+}
+
+impl Drop for SortableSubscription {
+    fn drop(&mut self) {
+        self.sortable.unsubscribe(self.scope);
+        self.sortable.unregister(self.scope);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> Rect<f64, f64> {
+        Rect::new((x, y).into(), (width, height).into())
+    }
+
+    #[test]
+    fn reorder_moves_items() {
+        let mut items = vec!['a', 'b', 'c', 'd'];
+        Reorder { from: 0, to: 2 }.apply(&mut items);
+        assert_eq!(items, ['b', 'c', 'a', 'd']);
+        Reorder { from: 3, to: 0 }.apply(&mut items);
+        assert_eq!(items, ['d', 'b', 'c', 'a']);
+        Reorder { from: 7, to: 0 }.apply(&mut items);
+        assert_eq!(items, ['d', 'b', 'c', 'a']);
+    }
+
+    #[test]
+    fn hit_test_finds_insertion_point() {
+        let rects: Vec<_> = (0..4)
+            .map(|i| (i, rect(0.0, i as f64 * 10.0, 100.0, 10.0)))
+            .collect();
+
+        // dragging the first item past the center of the third item
+        assert_eq!(hit_test(&rects, Axis::Vertical, 0, (0.0, 26.0)), 2);
+        // dragging the last item above everything
+        assert_eq!(hit_test(&rects, Axis::Vertical, 3, (0.0, -5.0)), 0);
+        // dragging past the end of the list clamps to the last position
+        assert_eq!(hit_test(&rects, Axis::Vertical, 1, (0.0, 500.0)), 3);
+        // the horizontal axis ignores the vertical position
+        assert_eq!(hit_test(&rects, Axis::Horizontal, 1, (50.0, 500.0)), 0);
+        // without measurements the item stays where it is
+        assert_eq!(hit_test(&[], Axis::Vertical, 2, (0.0, 0.0)), 2);
+    }
+
+    #[test]
+    fn registrations_follow_reorders() {
+        let sortable = Sortable {
+            inner: Rc::new(RefCell::new(SortableInner {
+                axis: Axis::Vertical,
+                elements: HashMap::new(),
+                subscribers: HashSet::new(),
+                drag: None,
+                generation: 0,
+            })),
+            update_any: Arc::new(|_| {}),
+        };
+        let (first, second) = (ScopeId(1), ScopeId(2));
+        sortable.register(first, 0, Rc::new(MountedData::new(())));
+        sortable.register(second, 1, Rc::new(MountedData::new(())));
+
+        // the items were swapped, so each component renders with the index of the other
+        sortable.set_index(first, 1);
+        sortable.set_index(second, 0);
+        let index = |scope| {
+            sortable
+                .inner
+                .borrow()
+                .elements
+                .get(&scope)
+                .map(|(index, _)| *index)
+        };
+        assert_eq!(index(first), Some(1));
+        assert_eq!(index(second), Some(0));
+
+        // removing an item doesn't touch the registration of the item that took its index
+        sortable.unregister(first);
+        assert_eq!(index(first), None);
+        assert_eq!(index(second), Some(0));
+    }
+}
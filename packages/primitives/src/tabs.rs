@@ -0,0 +1,228 @@
+//! Accessible tabs with keyboard navigation.
+//!
+//! ```rust, ignore
+//! render! {
+//!     Tabs {
+//!         TabList {
+//!             Tab { index: 0, "Account" }
+//!             Tab { index: 1, "Password" }
+//!         }
+//!         TabPanel { index: 0, "Account settings" }
+//!         TabPanel { index: 1, "Password settings" }
+//!     }
+//! }
+//! ```
+
+use crate::focus::{use_roving_focus, Orientation, RovingFocus, RovingFocusConfig, RovingItem};
+use crate::id::use_unique_id;
+use dioxus::prelude::*;
+
+/// The shared state of a [`Tabs`] component.
+pub struct TabsState {
+    selected: usize,
+    base_id: String,
+}
+
+impl TabsState {
+    /// The index of the selected tab
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Select the tab at `index`
+    pub fn select(&mut self, index: usize) {
+        self.selected = index;
+    }
+
+    fn tab_id(&self, index: usize) -> String {
+        format!("{}-tab-{index}", self.base_id)
+    }
+
+    fn panel_id(&self, index: usize) -> String {
+        format!("{}-panel-{index}", self.base_id)
+    }
+}
+
+/// Get the state of the nearest [`Tabs`] above the current component.
+pub fn use_tabs(cx: &ScopeState) -> Option<&UseSharedState<TabsState>> {
+    use_shared_state::<TabsState>(cx)
+}
+
+/// The properties for [`Tabs`].
+#[derive(Props)]
+pub struct TabsProps<'a> {
+    /// The index of the tab that is selected when the component is created.
+    #[props(default)]
+    pub default_tab: usize,
+    /// The class attribute for the root element.
+    pub class: Option<&'a str>,
+    /// The [`TabList`] and [`TabPanel`]s.
+    pub children: Element<'a>,
+}
+
+/// The root of a set of tabs. This provides the selected tab to the [`TabList`], [`Tab`]s and [`TabPanel`]s inside of it.
+pub fn Tabs<'a>(cx: Scope<'a, TabsProps<'a>>) -> Element<'a> {
+    let base_id = use_unique_id(cx, "tabs");
+    use_shared_state_provider(cx, || TabsState {
+        selected: cx.props.default_tab,
+        base_id: base_id.to_string(),
+    });
+
+    render! {
+        div {
+            class: cx.props.class.unwrap_or_default(),
+            &cx.props.children
+        }
+    }
+}
+
+/// The properties for a [`TabList`].
+#[derive(Props)]
+pub struct TabListProps<'a> {
+    /// Whether the tabs are laid out horizontally or vertically. Defaults to [`Orientation::Horizontal`].
+    #[props(default = Orientation::Horizontal)]
+    pub orientation: Orientation,
+    /// The class attribute for the list element.
+    pub class: Option<&'a str>,
+    /// The [`Tab`]s.
+    pub children: Element<'a>,
+}
+
+/// The list of [`Tab`]s. The arrow keys move focus between the tabs and select the focused tab.
+pub fn TabList<'a>(cx: Scope<'a, TabListProps<'a>>) -> Element<'a> {
+    let tabs = use_tabs(cx).expect("`TabList` must be rendered inside of `Tabs`");
+    let roving = use_roving_focus(
+        cx,
+        RovingFocusConfig::new()
+            .orientation(cx.props.orientation)
+            .typeahead(false),
+    );
+    cx.use_hook(|| {
+        roving.set_item(tabs.read().selected(), RovingItem::default());
+        roving.set_active(tabs.read().selected());
+        cx.provide_context(roving.clone());
+    });
+    let orientation = match cx.props.orientation {
+        Orientation::Vertical => "vertical",
+        _ => "horizontal",
+    };
+
+    render! {
+        div {
+            class: cx.props.class.unwrap_or_default(),
+            role: "tablist",
+            aria_orientation: orientation,
+            onkeydown: move |evt| {
+                roving.onkeydown(&evt);
+            },
+            &cx.props.children
+        }
+    }
+}
+
+/// The properties for a [`Tab`].
+#[derive(Props)]
+pub struct TabProps<'a> {
+    /// The index of this tab. This must match the index of the [`TabPanel`] the tab controls.
+    pub index: usize,
+    /// Whether the tab can be selected.
+    #[props(default)]
+    pub disabled: bool,
+    /// The class attribute for the tab button.
+    pub class: Option<&'a str>,
+    /// The label of the tab.
+    pub children: Element<'a>,
+}
+
+/// A tab that selects the [`TabPanel`] with the same index when it is clicked or focused.
+///
+/// The selected tab receives a `data-state="active"` attribute.
+pub fn Tab<'a>(cx: Scope<'a, TabProps<'a>>) -> Element<'a> {
+    let tabs = use_tabs(cx).expect("`Tab` must be rendered inside of `Tabs`");
+    let roving = cx
+        .use_hook(|| cx.consume_context::<RovingFocus>())
+        .as_ref()
+        .expect("`Tab` must be rendered inside of a `TabList`");
+    let index = cx.props.index;
+    let disabled = cx.props.disabled;
+    roving.set_item(index, RovingItem::default().disabled(disabled));
+    use_on_destroy(cx, {
+        let roving = roving.clone();
+        move || roving.remove_item(index)
+    });
+
+    let (selected, tab_id, panel_id) = {
+        let tabs = tabs.read();
+        (
+            tabs.selected() == index,
+            tabs.tab_id(index),
+            tabs.panel_id(index),
+        )
+    };
+    let select = move || {
+        if !disabled && tabs.read().selected() != index {
+            tabs.write().select(index);
+        }
+    };
+
+    render! {
+        button {
+            id: "{tab_id}",
+            class: cx.props.class.unwrap_or_default(),
+            r#type: "button",
+            role: "tab",
+            aria_selected: selected,
+            aria_controls: "{panel_id}",
+            aria_disabled: disabled,
+            tabindex: roving.tabindex(index),
+            "data-state": if selected { "active" } else { "inactive" },
+            onmounted: move |evt| roving.mounted(index, evt.inner().clone()),
+            onfocus: move |_| {
+                roving.set_active(index);
+                select();
+            },
+            onclick: move |_| select(),
+            &cx.props.children
+        }
+    }
+}
+
+/// The properties for a [`TabPanel`].
+#[derive(Props)]
+pub struct TabPanelProps<'a> {
+    /// The index of this panel. This must match the index of the [`Tab`] that controls it.
+    pub index: usize,
+    /// The class attribute for the panel element.
+    pub class: Option<&'a str>,
+    /// The contents of the panel.
+    pub children: Element<'a>,
+}
+
+/// The contents of a tab. This is only rendered while the [`Tab`] with the same index is selected.
+pub fn TabPanel<'a>(cx: Scope<'a, TabPanelProps<'a>>) -> Element<'a> {
+    let tabs = use_tabs(cx).expect("`TabPanel` must be rendered inside of `Tabs`");
+    let index = cx.props.index;
+    let (selected, tab_id, panel_id) = {
+        let tabs = tabs.read();
+        (
+            tabs.selected() == index,
+            tabs.tab_id(index),
+            tabs.panel_id(index),
+        )
+    };
+
+    if !selected {
+        return None;
+    }
+
+    render! {
+        div {
+            id: "{panel_id}",
+            class: cx.props.class.unwrap_or_default(),
+            role: "tabpanel",
+            aria_labelledby: "{tab_id}",
+            tabindex: 0,
+            &cx.props.children
+        }
+    }
+}
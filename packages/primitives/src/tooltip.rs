@@ -0,0 +1,71 @@
+//! A tooltip that describes an element while it is hovered or focused.
+
+use crate::id::use_unique_id;
+use crate::positioning::{use_floating, Placement, PositionOptions, Side};
+use dioxus::html::input_data::keyboard_types::Key;
+use dioxus::prelude::*;
+
+/// The properties for a [`Tooltip`].
+#[derive(Props)]
+pub struct TooltipProps<'a> {
+    /// The text of the tooltip.
+    pub text: &'a str,
+    /// Where the tooltip is placed relative to the element. Defaults to above the element, centered.
+    #[props(default = Side::Top.into(), into)]
+    pub placement: Placement,
+    /// The class attribute for the tooltip element.
+    pub class: Option<&'a str>,
+    /// The element the tooltip describes.
+    pub children: Element<'a>,
+}
+
+/// Show a tooltip while the children are hovered or contain focus.
+///
+/// The children are wrapped in a `span` that is described by the tooltip through `aria-describedby`. Pressing `Escape`
+/// hides the tooltip until the pointer or focus leaves the element.
+///
+/// The tooltip is positioned with [`use_floating`] and receives a `data-placement` attribute with the placement that
+/// was used after flipping.
+pub fn Tooltip<'a>(cx: Scope<'a, TooltipProps<'a>>) -> Element<'a> {
+    let open = use_state(cx, || false);
+    let id = use_unique_id(cx, "tooltip");
+    let floating = use_floating(cx, PositionOptions::new(cx.props.placement));
+    let placement = floating
+        .position()
+        .map_or(cx.props.placement, |position| position.placement);
+
+    render! {
+        span {
+            display: "inline-block",
+            aria_describedby: id,
+            "data-state": if **open { "open" } else { "closed" },
+            onmounted: move |evt| floating.set_anchor(evt.inner().clone()),
+            onmouseenter: move |_| open.set(true),
+            onmouseleave: move |_| open.set(false),
+            onfocusin: move |_| open.set(true),
+            onfocusout: move |_| open.set(false),
+            onkeydown: move |evt| {
+                if evt.key() == Key::Escape {
+                    open.set(false);
+                }
+            },
+            &cx.props.children
+            if **open {
+                rsx! {
+                    div {
+                        id: id,
+                        class: cx.props.class.unwrap_or_default(),
+                        role: "tooltip",
+                        style: "{floating.style()} white-space: nowrap;",
+                        "data-placement": "{placement.as_str()}",
+                        onmounted: move |evt| {
+                            floating.set_floating(evt.inner().clone());
+                            floating.update()
+                        },
+                        "{cx.props.text}"
+                    }
+                }
+            }
+        }
+    }
+}
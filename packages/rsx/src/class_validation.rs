@@ -0,0 +1,315 @@
+//! Compile time validation of `class` attributes
+//! =============================================
+//!
+//! With the `class-validation` feature, every class in a literal `class` attribute is checked against a manifest of
+//! the classes the project defines, and unknown classes fail the build.
+//!
+//! The manifest is read from the files listed in the `DIOXUS_CLASS_MANIFEST` environment variable, separated like
+//! `PATH`. Relative paths are resolved from the crate being compiled. The variable is usually set in
+//! `.cargo/config.toml`:
+//!
+//! ```toml
+//! [env]
+//! DIOXUS_CLASS_MANIFEST = "assets/main.css:tailwind-classes.txt"
+//! ```
+//!
+//! - Stylesheets (`.css`) define every class used in one of their selectors.
+//! - Any other file lists classes separated by whitespace, with `#` starting a comment. This fits class lists that
+//!   are generated from a Tailwind config.
+//!
+//! If the variable isn't set, classes are not checked. Words that touch a formatted segment, like `btn-{size}`, can
+//! only be known at runtime and are skipped.
+//!
+//! Cargo doesn't know about the manifest, so crates are not rebuilt when only the manifest changes.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use proc_macro2::Span;
+
+use crate::{ElementAttr, ElementAttrName, ElementAttrValue, IfmtInput, Segment};
+
+/// The environment variable that lists the manifest files
+const MANIFEST_VAR: &str = "DIOXUS_CLASS_MANIFEST";
+
+/// Stands in for formatted segments when a class string is split into words
+const FORMATTED: char = '\0';
+
+thread_local! {
+    /// Manifests are read once per compiler process, and again when the file changes
+    static MANIFESTS: RefCell<HashMap<PathBuf, (Option<SystemTime>, Rc<HashSet<String>>)>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Check the classes of a `class` attribute against the manifest. Other attributes are ignored.
+pub(crate) fn validate_classes(attr: &ElementAttr) -> syn::Result<()> {
+    let is_class = match &attr.name {
+        ElementAttrName::BuiltIn(name) => name == "class",
+        ElementAttrName::Custom(name) => name.value() == "class",
+    };
+    if !is_class {
+        return Ok(());
+    }
+
+    let paths = match manifest_paths() {
+        Some(paths) => paths,
+        None => return Ok(()),
+    };
+    validate_value(&attr.value, &paths)
+}
+
+fn validate_value(value: &ElementAttrValue, paths: &[PathBuf]) -> syn::Result<()> {
+    match value {
+        ElementAttrValue::AttrLiteral(input) => validate_input(input, paths),
+        ElementAttrValue::AttrOptionalExpr { value, .. } => validate_value(value, paths),
+        ElementAttrValue::AttrExpr(_) | ElementAttrValue::EventTokens(_) => Ok(()),
+    }
+}
+
+fn validate_input(input: &IfmtInput, paths: &[PathBuf]) -> syn::Result<()> {
+    let span = input
+        .source
+        .as_ref()
+        .map(|source| source.span())
+        .unwrap_or_else(Span::call_site);
+
+    let mut known = HashSet::new();
+    for path in paths {
+        let manifest = load_manifest(path).map_err(|err| {
+            syn::Error::new(
+                span,
+                format!(
+                    "failed to read the class manifest `{}`: {}",
+                    path.display(),
+                    err
+                ),
+            )
+        })?;
+        known.extend(manifest.iter().cloned());
+    }
+
+    for class in static_classes(input) {
+        if !known.contains(&class) {
+            let mut message = format!("unknown class `{}`", class);
+            if let Some(suggestion) = closest_class(&class, &known) {
+                message.push_str(&format!("\n  = help: did you mean `{}`?", suggestion));
+            }
+            return Err(syn::Error::new(span, message));
+        }
+    }
+    Ok(())
+}
+
+fn manifest_paths() -> Option<Vec<PathBuf>> {
+    let paths = std::env::var_os(MANIFEST_VAR)?;
+    let root = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    Some(
+        std::env::split_paths(&paths)
+            .filter(|path| !path.as_os_str().is_empty())
+            .map(|path| root.join(path))
+            .collect(),
+    )
+}
+
+fn load_manifest(path: &Path) -> std::io::Result<Rc<HashSet<String>>> {
+    let modified = std::fs::metadata(path)?.modified().ok();
+    let cached = MANIFESTS.with(|manifests| {
+        manifests
+            .borrow()
+            .get(path)
+            .filter(|(cached_modified, _)| modified.is_some() && *cached_modified == modified)
+            .map(|(_, classes)| classes.clone())
+    });
+    if let Some(classes) = cached {
+        return Ok(classes);
+    }
+
+    let contents = std::fs::read_to_string(path)?;
+    let is_stylesheet = path
+        .extension()
+        .map_or(false, |extension| extension.eq_ignore_ascii_case("css"));
+    let classes = Rc::new(if is_stylesheet {
+        stylesheet_classes(&contents)
+    } else {
+        listed_classes(&contents)
+    });
+
+    MANIFESTS.with(|manifests| {
+        manifests
+            .borrow_mut()
+            .insert(path.to_path_buf(), (modified, classes.clone()))
+    });
+    Ok(classes)
+}
+
+/// The classes in a class list, separated by whitespace with `#` comments
+fn listed_classes(contents: &str) -> HashSet<String> {
+    contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .flat_map(str::split_whitespace)
+        .map(str::to_string)
+        .collect()
+}
+
+/// The classes used in the selectors of a stylesheet.
+///
+/// This doesn't parse the CSS, it collects every `.name` outside of comments and strings. Declarations like
+/// `url(icon.svg)` add classes that don't exist, which only makes the check less strict.
+fn stylesheet_classes(css: &str) -> HashSet<String> {
+    let mut classes = HashSet::new();
+    let mut chars = css.chars().peekable();
+    let mut previous = ' ';
+
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            '"' | '\'' => {
+                while let Some(next) = chars.next() {
+                    match next {
+                        '\\' => {
+                            chars.next();
+                        }
+                        _ if next == c => break,
+                        _ => {}
+                    }
+                }
+            }
+            // Numbers like `1.5em` are not classes
+            '.' if !previous.is_ascii_alphanumeric() => {
+                let mut class = String::new();
+                while let Some(&next) = chars.peek() {
+                    if next == '\\' {
+                        // Escapes like `md\:flex` are part of the class
+                        chars.next();
+                        if let Some(escaped) = chars.next() {
+                            class.push(escaped);
+                        }
+                    } else if next.is_alphanumeric()
+                        || next == '-'
+                        || next == '_'
+                        || !next.is_ascii()
+                    {
+                        class.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let starts_like_class = class
+                    .chars()
+                    .next()
+                    .map_or(false, |first| !first.is_ascii_digit());
+                if starts_like_class {
+                    classes.insert(class);
+                }
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+
+    classes
+}
+
+/// The classes of a class string that are known at compile time
+fn static_classes(input: &IfmtInput) -> Vec<String> {
+    let mut text = String::new();
+    for segment in &input.segments {
+        match segment {
+            Segment::Literal(literal) => text.push_str(literal),
+            Segment::Formatted(_) => text.push(FORMATTED),
+        }
+    }
+    text.split_whitespace()
+        .filter(|word| !word.contains(FORMATTED))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Find a known class that is at most two edits away from `class`
+fn closest_class<'a>(class: &str, known: &'a HashSet<String>) -> Option<&'a str> {
+    known
+        .iter()
+        .map(|candidate| (edit_distance(class, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min()
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if a == *b {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stylesheet_selectors() {
+        let classes = stylesheet_classes(
+            r#"
+            /* .commented { } */
+            .btn, .btn.primary:hover > .icon { padding: 1.5em; }
+            @media (min-width: 640px) { .md\:flex { display: flex; } }
+            .w-1\/2 { width: 50%; content: ".quoted"; }
+            "#,
+        );
+        let mut classes: Vec<_> = classes.into_iter().collect();
+        classes.sort();
+        assert_eq!(classes, ["btn", "icon", "md:flex", "primary", "w-1/2"]);
+    }
+
+    #[test]
+    fn listed_manifest() {
+        let classes = listed_classes("# generated\nbtn primary\n  card # the card class\n");
+        let mut classes: Vec<_> = classes.into_iter().collect();
+        classes.sort();
+        assert_eq!(classes, ["btn", "card", "primary"]);
+    }
+
+    #[test]
+    fn formatted_words_are_skipped() {
+        let input: IfmtInput = syn::parse_str(r#""btn btn-{size} {extra} primary""#).unwrap();
+        assert_eq!(static_classes(&input), ["btn", "primary"]);
+    }
+
+    #[test]
+    fn suggests_close_classes() {
+        let known: HashSet<String> = ["primary", "secondary"]
+            .iter()
+            .map(|class| class.to_string())
+            .collect();
+        assert_eq!(closest_class("primry", &known), Some("primary"));
+        assert_eq!(closest_class("danger", &known), None);
+    }
+}
@@ -8,6 +8,7 @@
 //! - [x] Componentbuilder synax
 //! - [x] Optional commas
 //! - [ ] Children
+//! - [x] Named children slots with `name: render! { ... }`
 //! - [ ] Keys
 //! - [ ] Properties spreading with with `..` syntax
 
@@ -57,10 +58,19 @@ impl Component {
     }
 
     pub fn key(&self) -> Option<&IfmtInput> {
+        self.formatted_field("key")
+    }
+
+    /// The key that keeps the state of the component when it moves to another parent
+    pub fn global_key(&self) -> Option<&IfmtInput> {
+        self.formatted_field("global_key")
+    }
+
+    fn formatted_field(&self, name: &str) -> Option<&IfmtInput> {
         match self
             .fields
             .iter()
-            .find(|f| f.name == "key")
+            .find(|f| f.name == name)
             .map(|f| &f.content)
         {
             Some(ContentField::Formatted(fmt)) => Some(fmt),
@@ -132,7 +142,7 @@ impl ToTokens for Component {
                     let mut __manual_props = #manual_props;
                 };
                 for field in &self.fields {
-                    if field.name == "key" {
+                    if field.name == "key" || field.name == "global_key" {
                         // skip keys
                     } else {
                         let name = &field.name;
@@ -156,7 +166,7 @@ impl ToTokens for Component {
                 };
                 for field in &self.fields {
                     match field.name.to_string().as_str() {
-                        "key" => {}
+                        "key" | "global_key" => {}
                         _ => toks.append_all(quote! {#field}),
                     }
                 }
@@ -188,13 +198,23 @@ impl ToTokens for Component {
             None => quote! { #name },
         };
 
-        tokens.append_all(quote! {
-            __cx.component(
-                #gen_name,
-                #builder,
-                #fn_name
-            )
-        })
+        match self.global_key() {
+            Some(global_key) => tokens.append_all(quote! {
+                __cx.component_with_global_key(
+                    #gen_name,
+                    #builder,
+                    #fn_name,
+                    __cx.raw_text(#global_key)
+                )
+            }),
+            None => tokens.append_all(quote! {
+                __cx.component(
+                    #gen_name,
+                    #builder,
+                    #fn_name
+                )
+            }),
+        }
     }
 }
 
@@ -210,6 +230,12 @@ pub enum ContentField {
     ManExpr(Expr),
     Formatted(IfmtInput),
     OnHandlerRaw(Expr),
+    /// A named slot, like `header: render! { h1 { "Title" } }`, rendered into an `Element` in the scope that renders
+    /// the component. A field set to `rsx! { ... }` is a normal expression and stays `LazyNodes`.
+    Slot {
+        mac: syn::Macro,
+        roots: Vec<BodyNode>,
+    },
 }
 
 impl ToTokens for ContentField {
@@ -222,6 +248,15 @@ impl ToTokens for ContentField {
             ContentField::OnHandlerRaw(e) => tokens.append_all(quote! {
                 __cx.event_handler(#e)
             }),
+            ContentField::Slot { mac, .. } => {
+                // The body is expanded as a nested `rsx!` call that keeps the position of the slot, so its template is
+                // named after the slot instead of sharing the location of the surrounding call
+                let mut mac = mac.clone();
+                mac.path = Ident::new("rsx", mac.path.span()).into();
+                tokens.append_all(quote! {
+                    __cx.render(#mac)
+                })
+            }
         }
     }
 }
@@ -234,9 +269,11 @@ impl Parse for ComponentField {
         let content = {
             if name.to_string().starts_with("on") {
                 ContentField::OnHandlerRaw(input.parse()?)
-            } else if name == "key" {
+            } else if name == "key" || name == "global_key" {
                 let content = ContentField::Formatted(input.parse()?);
                 return Ok(Self { name, content });
+            } else if let Some(slot) = parse_slot(input)? {
+                slot
             } else if input.peek(LitStr) {
                 let forked = input.fork();
                 let t: LitStr = forked.parse()?;
@@ -266,6 +303,27 @@ impl ToTokens for ComponentField {
     }
 }
 
+/// Parse a field value that is exactly `render! { ... }` as a slot
+fn parse_slot(input: ParseStream) -> Result<Option<ContentField>> {
+    if !(input.peek(Ident) && input.peek2(Token![!])) {
+        return Ok(None);
+    }
+
+    let forked = input.fork();
+    let mac: syn::Macro = forked.parse()?;
+    // anything after the macro, like `render! { ... }.unwrap()`, makes it a normal expression
+    if !mac.path.is_ident("render") || !(forked.is_empty() || forked.peek(Token![,])) {
+        return Ok(None);
+    }
+
+    input.parse::<syn::Macro>()?;
+    let body: CallBody = mac.parse_body()?;
+    Ok(Some(ContentField::Slot {
+        mac,
+        roots: body.roots,
+    }))
+}
+
 fn is_literal_foramtted(lit: &LitStr) -> bool {
     let s = lit.value();
     let mut chars = s.chars();
@@ -281,3 +339,58 @@ fn is_literal_foramtted(lit: &LitStr) -> bool {
 
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field<'a>(component: &'a Component, name: &str) -> &'a ContentField {
+        &component
+            .fields
+            .iter()
+            .find(|field| field.name == name)
+            .unwrap()
+            .content
+    }
+
+    fn expansion(content: &ContentField) -> String {
+        content.to_token_stream().to_string()
+    }
+
+    #[test]
+    fn render_fields_are_slots() {
+        let component: Component = syn::parse2(quote! {
+            Card { header: render! { h1 { "Title" } }, footer: render!("footer") }
+        })
+        .unwrap();
+
+        for name in ["header", "footer"] {
+            let slot = field(&component, name);
+            assert!(matches!(slot, ContentField::Slot { .. }));
+            assert!(expansion(slot).starts_with("__cx . render (rsx !"));
+        }
+        let ContentField::Slot { roots, .. } = field(&component, "header") else {
+            unreachable!()
+        };
+        assert!(matches!(roots.as_slice(), [BodyNode::Element(_)]));
+    }
+
+    #[test]
+    fn rsx_fields_keep_their_expansion() {
+        let component: Component = syn::parse2(quote! {
+            Card { header: rsx! { h1 { "Title" } }, footer: render! { "footer" }.unwrap() }
+        })
+        .unwrap();
+
+        let header = field(&component, "header");
+        assert!(matches!(header, ContentField::ManExpr(_)));
+        assert_eq!(
+            expansion(header),
+            quote!(rsx! { h1 { "Title" } }).to_string()
+        );
+
+        // anything after the macro makes it a normal expression
+        let footer = field(&component, "footer");
+        assert!(matches!(footer, ContentField::ManExpr(_)));
+    }
+}
@@ -118,6 +118,11 @@ impl Parse for Element {
             break;
         }
 
+        #[cfg(feature = "class-validation")]
+        for attr in &attributes {
+            crate::class_validation::validate_classes(&attr.attr)?;
+        }
+
         // Deduplicate any attributes that can be combined
         // For example, if there are two `class` attributes, combine them into one
         let mut merged_attributes: Vec<ElementAttrNamed> = Vec::new();
@@ -17,6 +17,8 @@
 #[macro_use]
 mod errors;
 mod attribute;
+#[cfg(feature = "class-validation")]
+mod class_validation;
 mod component;
 mod element;
 #[cfg(feature = "hot_reload")]
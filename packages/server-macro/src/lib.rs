@@ -5,6 +5,7 @@
 use convert_case::{Case, Converter};
 use proc_macro::TokenStream;
 use proc_macro2::Literal;
+use proc_macro_crate::{crate_name, FoundCrate};
 use quote::{ToTokens, __private::TokenStream as TokenStream2};
 use server_fn_macro::*;
 use syn::{
@@ -85,6 +86,14 @@ pub fn server(args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
         }
     });
 
+    #[cfg(feature = "openapi")]
+    let schema = match server_fn_schema(&function) {
+        Ok(schema) => schema,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    #[cfg(not(feature = "openapi"))]
+    let schema = TokenStream2::new();
+
     let ItemFn {
         attrs,
         vis,
@@ -98,9 +107,12 @@ pub fn server(args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
         }
     };
 
-    let server_fn_path: syn::Path = syn::parse_quote!(::dioxus_fullstack::prelude::server_fn);
-    let trait_obj_wrapper: syn::Type =
-        syn::parse_quote!(::dioxus_fullstack::prelude::ServerFnTraitObj);
+    let fullstack = match fullstack_path() {
+        Ok(path) => path,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let server_fn_path: syn::Path = syn::parse_quote!(#fullstack::prelude::server_fn);
+    let trait_obj_wrapper: syn::Type = syn::parse_quote!(#fullstack::prelude::ServerFnTraitObj);
     let mut args: ServerFnArgs = match syn::parse(args) {
         Ok(args) => args,
         Err(e) => return e.to_compile_error().into(),
@@ -113,6 +125,17 @@ pub fn server(args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
         args.struct_name = Some(Ident::new(&upper_cammel_case_name, sig.ident.span()));
     }
     let struct_name = args.struct_name.as_ref().unwrap();
+    #[cfg(feature = "openapi")]
+    let schema = quote::quote! {
+        #[cfg(feature = "ssr")]
+        #server_fn_path::inventory::submit! {
+            #fullstack::openapi::ServerFnSchema {
+                prefix: #struct_name::PREFIX,
+                url: #struct_name::URL,
+                #schema
+            }
+        }
+    };
     match server_macro_impl(
         quote::quote!(#args),
         mapped_body,
@@ -125,7 +148,7 @@ pub fn server(args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
             #tokens
             #[cfg(feature = "ssr")]
             #server_fn_path::inventory::submit! {
-                ::dioxus_fullstack::prelude::ServerFnMiddleware {
+                #fullstack::prelude::ServerFnMiddleware {
                     prefix: #struct_name::PREFIX,
                     url: #struct_name::URL,
                     middleware: || vec![
@@ -135,12 +158,127 @@ pub fn server(args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
                     ]
                 }
             }
+            #schema
         }
         .to_token_stream()
         .into(),
     }
 }
 
+/// The path to `dioxus_fullstack` from the crate the macro is expanded in.
+///
+/// This follows renamed dependencies. The `dioxus` crate doesn't re-export `dioxus_fullstack`, so a crate without it
+/// in its dependencies gets an error that names the missing dependency.
+fn fullstack_path() -> syn::Result<syn::Path> {
+    match crate_name("dioxus-fullstack") {
+        Ok(FoundCrate::Name(name)) => {
+            let name = Ident::new(&name, proc_macro2::Span::call_site());
+            Ok(syn::parse_quote!(::#name))
+        }
+        // The tests and examples of dioxus-fullstack use the crate by its name
+        Ok(FoundCrate::Itself) => Ok(syn::parse_quote!(::dioxus_fullstack)),
+        Err(_) => Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`#[server]` functions need `dioxus-fullstack` in the dependencies of this crate",
+        )),
+    }
+}
+
+/// Build the fields of a `ServerFnSchema` that describe the arguments and return type of a server function.
+#[cfg(feature = "openapi")]
+fn server_fn_schema(function: &ItemFn) -> syn::Result<TokenStream2> {
+    let name = function.sig.ident.to_string();
+
+    let description = function
+        .attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(doc),
+                    ..
+                }) => Some(doc.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut argument_names = Vec::new();
+    let mut argument_types = Vec::new();
+    for input in &function.sig.inputs {
+        let syn::FnArg::Typed(input) = input else {
+            continue;
+        };
+        // The server context is injected on the server, so it isn't part of the request
+        if last_segment_is(&input.ty, "DioxusServerContext") {
+            continue;
+        }
+        let syn::Pat::Ident(ident) = &*input.pat else {
+            return Err(syn::Error::new_spanned(
+                &input.pat,
+                "server function arguments must be named to generate an OpenAPI schema",
+            ));
+        };
+        argument_names.push(ident.ident.to_string());
+        argument_types.push(&input.ty);
+    }
+
+    let output = match &function.sig.output {
+        syn::ReturnType::Type(_, ty) => ok_type(ty).unwrap_or(ty),
+        syn::ReturnType::Default => {
+            return Err(syn::Error::new_spanned(
+                &function.sig,
+                "server functions must return Result<T, ServerFnError>",
+            ))
+        }
+    };
+
+    Ok(quote::quote! {
+        name: #name,
+        description: #description,
+        arguments: |generator| vec![
+            #(
+                (#argument_names, generator.subschema_for::<#argument_types>()),
+            )*
+        ],
+        output: |generator| generator.subschema_for::<#output>(),
+    })
+}
+
+/// Check if the last segment of a type path is `name`.
+#[cfg(feature = "openapi")]
+fn last_segment_is(ty: &syn::Type, name: &str) -> bool {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map_or(false, |segment| segment.ident == name),
+        _ => false,
+    }
+}
+
+/// Get `T` from `Result<T, E>`.
+#[cfg(feature = "openapi")]
+fn ok_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
 #[derive(Debug)]
 struct Middleware {
     expr: syn::Expr,
@@ -0,0 +1,727 @@
+//! Collections that report what changed about them.
+//!
+//! Writing to a `Signal<Vec<T>>` only says that the vector changed, so anything derived from it has to compare the
+//! whole list again. [`SignalVec`] and [`SignalMap`] also tell their listeners which items were inserted, updated,
+//! moved or removed, so derived collections and keyed lists can apply the change to their own state directly.
+
+use std::collections::BTreeMap;
+
+use dioxus_core::ScopeState;
+use futures_util::Stream;
+use generational_box::GenerationalRef;
+
+use crate::{CopyValue, Signal};
+
+/// A change to a [`SignalVec`]. Listeners get the values of the change by reference, as a `VecDiff<&T>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VecDiff<T> {
+    /// Every item was replaced
+    Replace {
+        /// The new items
+        values: Vec<T>,
+    },
+    /// An item was inserted at `index`
+    InsertAt {
+        /// The index of the new item
+        index: usize,
+        /// The new item
+        value: T,
+    },
+    /// The item at `index` was replaced
+    UpdateAt {
+        /// The index of the item
+        index: usize,
+        /// The new value of the item
+        value: T,
+    },
+    /// The item at `index` was removed
+    RemoveAt {
+        /// The index the item had
+        index: usize,
+    },
+    /// The item at `old_index` was moved to `new_index`
+    Move {
+        /// The index the item had
+        old_index: usize,
+        /// The index the item has now
+        new_index: usize,
+    },
+    /// An item was added to the end
+    Push {
+        /// The new item
+        value: T,
+    },
+    /// The last item was removed
+    Pop,
+    /// Every item was removed
+    Clear,
+}
+
+impl<T> VecDiff<T> {
+    /// Convert the values of the change with `f`
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> VecDiff<U> {
+        match self {
+            VecDiff::Replace { values } => VecDiff::Replace {
+                values: values.into_iter().map(f).collect(),
+            },
+            VecDiff::InsertAt { index, value } => VecDiff::InsertAt {
+                index,
+                value: f(value),
+            },
+            VecDiff::UpdateAt { index, value } => VecDiff::UpdateAt {
+                index,
+                value: f(value),
+            },
+            VecDiff::RemoveAt { index } => VecDiff::RemoveAt { index },
+            VecDiff::Move {
+                old_index,
+                new_index,
+            } => VecDiff::Move {
+                old_index,
+                new_index,
+            },
+            VecDiff::Push { value } => VecDiff::Push { value: f(value) },
+            VecDiff::Pop => VecDiff::Pop,
+            VecDiff::Clear => VecDiff::Clear,
+        }
+    }
+
+    /// Make the same change to `values`. This panics if an index is out of bounds, like the methods of `Vec` do.
+    pub fn apply(self, values: &mut Vec<T>) {
+        match self {
+            VecDiff::Replace { values: new } => *values = new,
+            VecDiff::InsertAt { index, value } => values.insert(index, value),
+            VecDiff::UpdateAt { index, value } => values[index] = value,
+            VecDiff::RemoveAt { index } => {
+                values.remove(index);
+            }
+            VecDiff::Move {
+                old_index,
+                new_index,
+            } => {
+                let value = values.remove(old_index);
+                values.insert(new_index, value);
+            }
+            VecDiff::Push { value } => values.push(value),
+            VecDiff::Pop => {
+                values.pop();
+            }
+            VecDiff::Clear => values.clear(),
+        }
+    }
+}
+
+impl<T: Clone> VecDiff<&T> {
+    /// Clone the values of the change
+    pub fn cloned(self) -> VecDiff<T> {
+        self.map(T::clone)
+    }
+}
+
+/// A change that was made to a [`SignalVec`], without the values, which are read from the vector once it is changed
+#[derive(Clone, Copy)]
+enum VecChange {
+    Replace,
+    InsertAt(usize),
+    UpdateAt(usize),
+    RemoveAt(usize),
+    Move(usize, usize),
+    Push,
+    Pop,
+    Clear,
+}
+
+impl VecChange {
+    fn diff<T>(self, values: &[T]) -> VecDiff<&T> {
+        match self {
+            VecChange::Replace => VecDiff::Replace {
+                values: values.iter().collect(),
+            },
+            VecChange::InsertAt(index) => VecDiff::InsertAt {
+                index,
+                value: &values[index],
+            },
+            VecChange::UpdateAt(index) => VecDiff::UpdateAt {
+                index,
+                value: &values[index],
+            },
+            VecChange::RemoveAt(index) => VecDiff::RemoveAt { index },
+            VecChange::Move(old_index, new_index) => VecDiff::Move {
+                old_index,
+                new_index,
+            },
+            VecChange::Push => VecDiff::Push {
+                value: &values[values.len() - 1],
+            },
+            VecChange::Pop => VecDiff::Pop,
+            VecChange::Clear => VecDiff::Clear,
+        }
+    }
+}
+
+/// A listener of a [`SignalVec`]. It returns `false` once it doesn't need more changes.
+type VecListener<T> = Box<dyn FnMut(VecDiff<&T>) -> bool>;
+
+/// Creates a new [`SignalVec`] with the items `f` returns.
+///
+/// ```rust, ignore
+/// fn App(cx: Scope) -> Element {
+///     let todos = use_signal_vec(cx, Vec::<String>::new);
+///     let lengths = *cx.use_hook(|| todos.map(|todo| todo.len()));
+///
+///     render! {
+///         button { onclick: move |_| todos.push("New todo".to_string()), "Add" }
+///         for (index, todo) in todos.read().iter().enumerate() {
+///             div { key: "{index}", "{todo}" }
+///         }
+///     }
+/// }
+/// ```
+#[must_use]
+pub fn use_signal_vec<T: 'static>(cx: &ScopeState, f: impl FnOnce() -> Vec<T>) -> SignalVec<T> {
+    *cx.use_hook(|| SignalVec::new(f()))
+}
+
+/// A list that tells its listeners which items changed.
+///
+/// Reading the list subscribes to it like a [`Signal`], so components that read it rerender when it changes. Listeners
+/// added with [`SignalVec::listen`] get a [`VecDiff`] for every change instead, which lets derived lists like the ones
+/// made by [`SignalVec::map`] update only the items that changed.
+pub struct SignalVec<T: 'static> {
+    values: Signal<Vec<T>>,
+    listeners: CopyValue<Vec<VecListener<T>>>,
+}
+
+impl<T: 'static> Clone for SignalVec<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for SignalVec<T> {}
+
+impl<T: 'static> PartialEq for SignalVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+impl<T: 'static> SignalVec<T> {
+    /// Create a new list. It is stored in the current component, and dropped with it.
+    #[track_caller]
+    pub fn new(values: Vec<T>) -> Self {
+        Self {
+            values: Signal::new(values),
+            listeners: CopyValue::new(Vec::new()),
+        }
+    }
+
+    /// Get the items. This subscribes the current scope to the list.
+    #[track_caller]
+    pub fn read(&self) -> GenerationalRef<Vec<T>> {
+        self.values.read()
+    }
+
+    /// Get the items without subscribing to the list
+    pub fn peek(&self) -> GenerationalRef<Vec<T>> {
+        self.values.peek()
+    }
+
+    /// The number of items. This subscribes the current scope to the list.
+    pub fn len(&self) -> usize {
+        self.values.read().len()
+    }
+
+    /// Check if there are no items. This subscribes the current scope to the list.
+    pub fn is_empty(&self) -> bool {
+        self.values.read().is_empty()
+    }
+
+    /// Add an item to the end of the list
+    pub fn push(&self, value: T) {
+        self.values.write().push(value);
+        self.notify(VecChange::Push);
+    }
+
+    /// Remove the last item of the list
+    pub fn pop(&self) -> Option<T> {
+        let value = self.values.write().pop();
+        if value.is_some() {
+            self.notify(VecChange::Pop);
+        }
+        value
+    }
+
+    /// Insert an item at `index`, moving the items after it
+    pub fn insert(&self, index: usize, value: T) {
+        self.values.write().insert(index, value);
+        self.notify(VecChange::InsertAt(index));
+    }
+
+    /// Replace the item at `index`
+    pub fn set(&self, index: usize, value: T) {
+        self.values.write()[index] = value;
+        self.notify(VecChange::UpdateAt(index));
+    }
+
+    /// Remove the item at `index`, moving the items after it
+    pub fn remove(&self, index: usize) -> T {
+        let value = self.values.write().remove(index);
+        self.notify(VecChange::RemoveAt(index));
+        value
+    }
+
+    /// Move the item at `old_index` to `new_index`
+    pub fn move_item(&self, old_index: usize, new_index: usize) {
+        {
+            let mut values = self.values.write();
+            let value = values.remove(old_index);
+            values.insert(new_index, value);
+        }
+        self.notify(VecChange::Move(old_index, new_index));
+    }
+
+    /// Replace every item
+    pub fn replace(&self, values: Vec<T>) {
+        *self.values.write() = values;
+        self.notify(VecChange::Replace);
+    }
+
+    /// Remove every item
+    pub fn clear(&self) {
+        self.values.write().clear();
+        self.notify(VecChange::Clear);
+    }
+
+    /// Make a change to the list
+    pub fn apply(&self, diff: VecDiff<T>) {
+        match diff {
+            VecDiff::Replace { values } => self.replace(values),
+            VecDiff::InsertAt { index, value } => self.insert(index, value),
+            VecDiff::UpdateAt { index, value } => self.set(index, value),
+            VecDiff::RemoveAt { index } => {
+                self.remove(index);
+            }
+            VecDiff::Move {
+                old_index,
+                new_index,
+            } => self.move_item(old_index, new_index),
+            VecDiff::Push { value } => self.push(value),
+            VecDiff::Pop => {
+                self.pop();
+            }
+            VecDiff::Clear => self.clear(),
+        }
+    }
+
+    /// Call `listener` with every change made to the list from now on.
+    ///
+    /// The listener runs while the list is borrowed, so it must not change the list itself.
+    pub fn listen(&self, mut listener: impl FnMut(VecDiff<&T>) + 'static) {
+        self.listen_while(move |diff| {
+            listener(diff);
+            true
+        });
+    }
+
+    fn listen_while(&self, listener: impl FnMut(VecDiff<&T>) -> bool + 'static) {
+        self.listeners.write().push(Box::new(listener));
+    }
+
+    /// Create a list with `f` called on every item, that is updated with every change to this one. Only the items
+    /// that change are converted again.
+    ///
+    /// The new list is stored in the current component.
+    #[track_caller]
+    pub fn map<U: 'static>(&self, mut f: impl FnMut(&T) -> U + 'static) -> SignalVec<U> {
+        let mapped = SignalVec::new(self.peek().iter().map(&mut f).collect());
+        self.listen_while(move |diff| {
+            if !mapped.is_alive() {
+                return false;
+            }
+            mapped.apply(diff.map(&mut f));
+            true
+        });
+        mapped
+    }
+
+    /// Get a stream of every change made to the list, starting with a [`VecDiff::Replace`] with the current items.
+    ///
+    /// ```rust, ignore
+    /// use_future!(cx, |()| {
+    ///     let mut changes = messages.diff_stream();
+    ///     async move {
+    ///         let mut mirror = Vec::new();
+    ///         while let Some(diff) = changes.next().await {
+    ///             diff.apply(&mut mirror);
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub fn diff_stream(&self) -> impl Stream<Item = VecDiff<T>>
+    where
+        T: Clone,
+    {
+        let (sender, receiver) = futures_channel::mpsc::unbounded();
+        _ = sender.unbounded_send(VecDiff::Replace {
+            values: self.peek().clone(),
+        });
+        self.listen_while(move |diff| sender.unbounded_send(diff.cloned()).is_ok());
+        receiver
+    }
+
+    fn is_alive(&self) -> bool {
+        self.listeners.try_read().is_ok()
+    }
+
+    /// Send a change to the listeners that still want changes
+    fn notify(&self, change: VecChange) {
+        // The listeners are taken out while they run, so they can add listeners to this list
+        let listeners = std::mem::take(&mut *self.listeners.write());
+        if listeners.is_empty() {
+            return;
+        }
+        let mut listeners: Vec<_> = {
+            let values = self.values.peek();
+            listeners
+                .into_iter()
+                .filter_map(|mut listener| listener(change.diff(&values)).then(|| listener))
+                .collect()
+        };
+        let mut current = self.listeners.write();
+        listeners.append(&mut current);
+        *current = listeners;
+    }
+}
+
+/// A change to a [`SignalMap`]. Listeners get the keys and values of the change by reference, as a
+/// `MapDiff<&K, &V>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapDiff<K, V> {
+    /// Every entry was replaced
+    Replace {
+        /// The new entries, ordered by key
+        entries: Vec<(K, V)>,
+    },
+    /// An entry was added
+    Insert {
+        /// The key of the new entry
+        key: K,
+        /// The value of the new entry
+        value: V,
+    },
+    /// The value of an entry was replaced
+    Update {
+        /// The key of the entry
+        key: K,
+        /// The new value of the entry
+        value: V,
+    },
+    /// An entry was removed
+    Remove {
+        /// The key the entry had
+        key: K,
+    },
+    /// Every entry was removed
+    Clear,
+}
+
+impl<K, V> MapDiff<K, V> {
+    /// Convert the values of the change with `f`
+    pub fn map_values<U>(self, mut f: impl FnMut(V) -> U) -> MapDiff<K, U> {
+        match self {
+            MapDiff::Replace { entries } => MapDiff::Replace {
+                entries: entries
+                    .into_iter()
+                    .map(|(key, value)| (key, f(value)))
+                    .collect(),
+            },
+            MapDiff::Insert { key, value } => MapDiff::Insert {
+                key,
+                value: f(value),
+            },
+            MapDiff::Update { key, value } => MapDiff::Update {
+                key,
+                value: f(value),
+            },
+            MapDiff::Remove { key } => MapDiff::Remove { key },
+            MapDiff::Clear => MapDiff::Clear,
+        }
+    }
+
+    /// Make the same change to `entries`
+    pub fn apply(self, entries: &mut BTreeMap<K, V>)
+    where
+        K: Ord,
+    {
+        match self {
+            MapDiff::Replace { entries: new } => *entries = new.into_iter().collect(),
+            MapDiff::Insert { key, value } | MapDiff::Update { key, value } => {
+                entries.insert(key, value);
+            }
+            MapDiff::Remove { key } => {
+                entries.remove(&key);
+            }
+            MapDiff::Clear => entries.clear(),
+        }
+    }
+}
+
+impl<K: Clone, V: Clone> MapDiff<&K, &V> {
+    /// Clone the keys and values of the change
+    pub fn cloned(self) -> MapDiff<K, V> {
+        match self {
+            MapDiff::Replace { entries } => MapDiff::Replace {
+                entries: entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            },
+            MapDiff::Insert { key, value } => MapDiff::Insert {
+                key: key.clone(),
+                value: value.clone(),
+            },
+            MapDiff::Update { key, value } => MapDiff::Update {
+                key: key.clone(),
+                value: value.clone(),
+            },
+            MapDiff::Remove { key } => MapDiff::Remove { key: key.clone() },
+            MapDiff::Clear => MapDiff::Clear,
+        }
+    }
+}
+
+/// A change that was made to a [`SignalMap`], without the values, which are read from the map once it is changed
+enum MapChange<K> {
+    Replace,
+    Insert(K),
+    Update(K),
+    Remove(K),
+    Clear,
+}
+
+impl<K: Ord> MapChange<K> {
+    fn diff<'a, V>(&'a self, entries: &'a BTreeMap<K, V>) -> MapDiff<&'a K, &'a V> {
+        match self {
+            MapChange::Replace => MapDiff::Replace {
+                entries: entries.iter().collect(),
+            },
+            MapChange::Insert(key) => MapDiff::Insert {
+                key,
+                value: &entries[key],
+            },
+            MapChange::Update(key) => MapDiff::Update {
+                key,
+                value: &entries[key],
+            },
+            MapChange::Remove(key) => MapDiff::Remove { key },
+            MapChange::Clear => MapDiff::Clear,
+        }
+    }
+}
+
+/// A listener of a [`SignalMap`]. It returns `false` once it doesn't need more changes.
+type MapListener<K, V> = Box<dyn FnMut(MapDiff<&K, &V>) -> bool>;
+
+/// Creates a new [`SignalMap`] with the entries `f` returns.
+#[must_use]
+pub fn use_signal_map<K: Ord + Clone + 'static, V: 'static>(
+    cx: &ScopeState,
+    f: impl FnOnce() -> BTreeMap<K, V>,
+) -> SignalMap<K, V> {
+    *cx.use_hook(|| SignalMap::new(f()))
+}
+
+/// A map ordered by key that tells its listeners which entries changed.
+///
+/// Like [`SignalVec`], reading the map subscribes to it, and listeners added with [`SignalMap::listen`] get a
+/// [`MapDiff`] for every change. Rendering the entries in key order with the key as the `key` of each item gives a
+/// keyed list that only changes where the map did.
+pub struct SignalMap<K: 'static, V: 'static> {
+    entries: Signal<BTreeMap<K, V>>,
+    listeners: CopyValue<Vec<MapListener<K, V>>>,
+}
+
+impl<K: 'static, V: 'static> Clone for SignalMap<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K: 'static, V: 'static> Copy for SignalMap<K, V> {}
+
+impl<K: 'static, V: 'static> PartialEq for SignalMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl<K: Ord + Clone + 'static, V: 'static> SignalMap<K, V> {
+    /// Create a new map. It is stored in the current component, and dropped with it.
+    #[track_caller]
+    pub fn new(entries: BTreeMap<K, V>) -> Self {
+        Self {
+            entries: Signal::new(entries),
+            listeners: CopyValue::new(Vec::new()),
+        }
+    }
+
+    /// Get the entries. This subscribes the current scope to the map.
+    #[track_caller]
+    pub fn read(&self) -> GenerationalRef<BTreeMap<K, V>> {
+        self.entries.read()
+    }
+
+    /// Get the entries without subscribing to the map
+    pub fn peek(&self) -> GenerationalRef<BTreeMap<K, V>> {
+        self.entries.peek()
+    }
+
+    /// Get the value of `key`. This subscribes the current scope to the map.
+    pub fn get(&self, key: &K) -> Option<GenerationalRef<V>> {
+        GenerationalRef::filter_map(self.entries.read(), |entries| entries.get(key))
+    }
+
+    /// The number of entries. This subscribes the current scope to the map.
+    pub fn len(&self) -> usize {
+        self.entries.read().len()
+    }
+
+    /// Check if there are no entries. This subscribes the current scope to the map.
+    pub fn is_empty(&self) -> bool {
+        self.entries.read().is_empty()
+    }
+
+    /// Set the value of `key`, and return the value it had
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let old = self.entries.write().insert(key.clone(), value);
+        self.notify(match old {
+            Some(_) => MapChange::Update(key),
+            None => MapChange::Insert(key),
+        });
+        old
+    }
+
+    /// Remove the entry of `key`, and return its value
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let value = self.entries.write().remove(key);
+        if value.is_some() {
+            self.notify(MapChange::Remove(key.clone()));
+        }
+        value
+    }
+
+    /// Replace every entry
+    pub fn replace(&self, entries: BTreeMap<K, V>) {
+        *self.entries.write() = entries;
+        self.notify(MapChange::Replace);
+    }
+
+    /// Remove every entry
+    pub fn clear(&self) {
+        self.entries.write().clear();
+        self.notify(MapChange::Clear);
+    }
+
+    /// Make a change to the map
+    pub fn apply(&self, diff: MapDiff<K, V>) {
+        match diff {
+            MapDiff::Replace { entries } => self.replace(entries.into_iter().collect()),
+            MapDiff::Insert { key, value } | MapDiff::Update { key, value } => {
+                self.insert(key, value);
+            }
+            MapDiff::Remove { key } => {
+                self.remove(&key);
+            }
+            MapDiff::Clear => self.clear(),
+        }
+    }
+
+    /// Call `listener` with every change made to the map from now on.
+    ///
+    /// The listener runs while the map is borrowed, so it must not change the map itself.
+    pub fn listen(&self, mut listener: impl FnMut(MapDiff<&K, &V>) + 'static) {
+        self.listen_while(move |diff| {
+            listener(diff);
+            true
+        });
+    }
+
+    fn listen_while(&self, listener: impl FnMut(MapDiff<&K, &V>) -> bool + 'static) {
+        self.listeners.write().push(Box::new(listener));
+    }
+
+    /// Create a map with `f` called on every value, that is updated with every change to this one. Only the values
+    /// that change are converted again.
+    ///
+    /// The new map is stored in the current component.
+    #[track_caller]
+    pub fn map_values<U: 'static>(&self, mut f: impl FnMut(&V) -> U + 'static) -> SignalMap<K, U> {
+        let mapped = SignalMap::new(
+            self.peek()
+                .iter()
+                .map(|(key, value)| (key.clone(), f(value)))
+                .collect(),
+        );
+        self.listen_while(move |diff| {
+            if !mapped.is_alive() {
+                return false;
+            }
+            let diff = match diff {
+                MapDiff::Replace { entries } => MapDiff::Replace {
+                    entries: entries
+                        .into_iter()
+                        .map(|(key, value)| (key.clone(), f(value)))
+                        .collect(),
+                },
+                MapDiff::Insert { key, value } => MapDiff::Insert {
+                    key: key.clone(),
+                    value: f(value),
+                },
+                MapDiff::Update { key, value } => MapDiff::Update {
+                    key: key.clone(),
+                    value: f(value),
+                },
+                MapDiff::Remove { key } => MapDiff::Remove { key: key.clone() },
+                MapDiff::Clear => MapDiff::Clear,
+            };
+            mapped.apply(diff);
+            true
+        });
+        mapped
+    }
+
+    /// Get a stream of every change made to the map, starting with a [`MapDiff::Replace`] with the current entries.
+    pub fn diff_stream(&self) -> impl Stream<Item = MapDiff<K, V>>
+    where
+        V: Clone,
+    {
+        let (sender, receiver) = futures_channel::mpsc::unbounded();
+        _ = sender.unbounded_send(MapChange::Replace.diff(&self.peek()).cloned());
+        self.listen_while(move |diff| sender.unbounded_send(diff.cloned()).is_ok());
+        receiver
+    }
+
+    fn is_alive(&self) -> bool {
+        self.listeners.try_read().is_ok()
+    }
+
+    /// Send a change to the listeners that still want changes
+    fn notify(&self, change: MapChange<K>) {
+        // The listeners are taken out while they run, so they can add listeners to this map
+        let listeners = std::mem::take(&mut *self.listeners.write());
+        if listeners.is_empty() {
+            return;
+        }
+        let mut listeners: Vec<_> = {
+            let entries = self.entries.peek();
+            listeners
+                .into_iter()
+                .filter_map(|mut listener| listener(change.diff(&entries)).then(|| listener))
+                .collect()
+        };
+        let mut current = self.listeners.write();
+        listeners.append(&mut current);
+        *current = listeners;
+    }
+}
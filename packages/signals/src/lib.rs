@@ -14,3 +14,6 @@ pub(crate) mod signal;
 pub use signal::*;
 mod dependency;
 pub use dependency::*;
+mod collections;
+pub use collections::*;
+mod stream;
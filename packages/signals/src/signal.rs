@@ -255,7 +255,7 @@ impl<T: 'static> Signal<T> {
         }
     }
 
-    fn update_subscribers(&self) {
+    pub(crate) fn update_subscribers(&self) {
         {
             let inner = self.inner.read();
             for &scope_id in &*inner.subscribers.borrow() {
@@ -0,0 +1,88 @@
+use dioxus_core::prelude::spawn;
+use futures_util::{pin_mut, Stream, StreamExt};
+
+use crate::{Effect, Signal};
+
+impl<T: 'static> Signal<T> {
+    /// Create a signal that starts with `initial` and is set to every item `stream` yields.
+    ///
+    /// The stream is polled by a task of the current component, so it stops when the component is dropped. Create the
+    /// signal in a hook to only subscribe to the stream once:
+    ///
+    /// ```rust, ignore
+    /// let messages = *cx.use_hook(|| Signal::from_stream(Vec::new(), inbox_updates()));
+    /// ```
+    #[track_caller]
+    pub fn from_stream(initial: T, stream: impl Stream<Item = T> + 'static) -> Self {
+        let signal = Signal::new(initial);
+        spawn(async move {
+            pin_mut!(stream);
+            while let Some(value) = stream.next().await {
+                if !signal.try_set(value) {
+                    break;
+                }
+            }
+        });
+        signal
+    }
+
+    /// Create a signal with the current value of a [`tokio::sync::watch`] channel that is set to every value sent on
+    /// it afterwards.
+    ///
+    /// The channel is watched by a task of the current component, so it stops when the component is dropped.
+    #[cfg(feature = "tokio")]
+    #[track_caller]
+    pub fn from_watch(mut receiver: tokio::sync::watch::Receiver<T>) -> Self
+    where
+        T: Clone,
+    {
+        let signal = Signal::new(receiver.borrow_and_update().clone());
+        spawn(async move {
+            while receiver.changed().await.is_ok() {
+                let value = receiver.borrow_and_update().clone();
+                if !signal.try_set(value) {
+                    break;
+                }
+            }
+        });
+        signal
+    }
+
+    /// Set the value of the signal, or return `false` if it was dropped
+    fn try_set(&self, value: T) -> bool {
+        match self.inner.try_write() {
+            Ok(mut inner) => inner.value = value,
+            Err(_) => return false,
+        }
+        self.update_subscribers();
+        true
+    }
+}
+
+impl<T: Clone + 'static> Signal<T> {
+    /// Get a stream that yields the current value of the signal, and then its value every time it changes.
+    ///
+    /// The stream ends when the current component is dropped.
+    ///
+    /// ```rust, ignore
+    /// let query = use_signal(cx, String::new);
+    /// use_future!(cx, |()| {
+    ///     let mut queries = query.to_stream();
+    ///     async move {
+    ///         while let Some(query) = queries.next().await {
+    ///             search(&query).await;
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub fn to_stream(&self) -> impl Stream<Item = T> {
+        let (sender, receiver) = futures_channel::mpsc::unbounded();
+        let signal = *self;
+        // The effect reads the signal, so it runs again with every change. It is dropped with the component, along
+        // with the sender, which ends the stream.
+        Effect::new(move || {
+            _ = sender.unbounded_send(signal.value());
+        });
+        receiver
+    }
+}
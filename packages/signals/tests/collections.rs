@@ -0,0 +1,135 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use dioxus::prelude::*;
+use dioxus_signals::*;
+
+#[test]
+fn vec_listeners_get_each_change() {
+    let diffs = Rc::new(RefCell::new(Vec::<VecDiff<i32>>::new()));
+    let mut dom = VirtualDom::new_with_props(
+        |cx| {
+            cx.use_hook(|| {
+                let list = SignalVec::new(vec![1, 2]);
+                let diffs = cx.props.clone();
+                list.listen(move |diff| diffs.borrow_mut().push(diff.cloned()));
+
+                list.push(3);
+                list.insert(0, 0);
+                list.set(1, 10);
+                list.move_item(0, 3);
+                assert_eq!(list.remove(0), 10);
+                assert_eq!(list.pop(), Some(0));
+                list.clear();
+                assert_eq!(list.pop(), None);
+            });
+
+            render! { div {} }
+        },
+        diffs.clone(),
+    );
+    let _ = dom.rebuild();
+
+    assert_eq!(
+        *diffs.borrow(),
+        [
+            VecDiff::Push { value: 3 },
+            VecDiff::InsertAt { index: 0, value: 0 },
+            VecDiff::UpdateAt {
+                index: 1,
+                value: 10
+            },
+            VecDiff::Move {
+                old_index: 0,
+                new_index: 3
+            },
+            VecDiff::RemoveAt { index: 0 },
+            VecDiff::Pop,
+            VecDiff::Clear,
+        ]
+    );
+}
+
+#[test]
+fn mapped_vec_follows_the_source() {
+    let mut dom = VirtualDom::new(|cx| {
+        cx.use_hook(|| {
+            let calls = Rc::new(RefCell::new(0));
+            let list = SignalVec::new(vec![1, 2, 3]);
+            let doubled = {
+                let calls = calls.clone();
+                list.map(move |value| {
+                    *calls.borrow_mut() += 1;
+                    value * 2
+                })
+            };
+            assert_eq!(*doubled.peek(), [2, 4, 6]);
+
+            list.push(4);
+            list.set(0, 5);
+            list.move_item(2, 0);
+            list.remove(1);
+            assert_eq!(*doubled.peek(), [6, 4, 8]);
+            // Only the new and updated items were mapped again
+            assert_eq!(*calls.borrow(), 5);
+
+            // Changes applied to a mirror give the same list
+            let mut mirror = vec![1, 2];
+            VecDiff::Push { value: 3 }.apply(&mut mirror);
+            VecDiff::Move {
+                old_index: 2,
+                new_index: 0,
+            }
+            .apply(&mut mirror);
+            assert_eq!(mirror, [3, 1, 2]);
+        });
+
+        render! { div {} }
+    });
+    let _ = dom.rebuild();
+}
+
+#[test]
+fn map_listeners_get_each_change() {
+    let diffs = Rc::new(RefCell::new(Vec::<MapDiff<&'static str, i32>>::new()));
+    let mut dom = VirtualDom::new_with_props(
+        |cx| {
+            cx.use_hook(|| {
+                let map = SignalMap::new(BTreeMap::new());
+                let diffs = cx.props.clone();
+                map.listen(move |diff| diffs.borrow_mut().push(diff.cloned()));
+                let lengths = map.map_values(|value: &i32| value.to_string().len());
+
+                assert_eq!(map.insert("a", 1), None);
+                assert_eq!(map.insert("b", 20), None);
+                assert_eq!(map.insert("a", 300), Some(1));
+                assert_eq!(map.remove(&"b"), Some(20));
+                assert_eq!(map.remove(&"b"), None);
+
+                assert_eq!(*lengths.peek(), BTreeMap::from([("a", 3)]));
+            });
+
+            render! { div {} }
+        },
+        diffs.clone(),
+    );
+    let _ = dom.rebuild();
+
+    assert_eq!(
+        *diffs.borrow(),
+        [
+            MapDiff::Insert { key: "a", value: 1 },
+            MapDiff::Insert {
+                key: "b",
+                value: 20
+            },
+            MapDiff::Update {
+                key: "a",
+                value: 300
+            },
+            MapDiff::Remove { key: "b" },
+        ]
+    );
+}
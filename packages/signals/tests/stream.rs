@@ -0,0 +1,74 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+use dioxus::prelude::*;
+use dioxus_signals::*;
+use futures_channel::mpsc::unbounded;
+use futures_util::StreamExt;
+
+/// Let the tasks of the VirtualDom run until they wait for something
+async fn run_tasks(dom: &mut VirtualDom) {
+    _ = tokio::time::timeout(Duration::from_millis(100), dom.wait_for_work()).await;
+}
+
+#[tokio::test]
+async fn from_stream_sets_the_signal() {
+    let (sender, receiver) = unbounded::<i32>();
+    let signal = Rc::new(Cell::new(None::<Signal<i32>>));
+    let mut dom = VirtualDom::new_with_props(
+        |cx| {
+            let signal = *cx.use_hook(|| {
+                let receiver = cx.props.0.borrow_mut().take().unwrap();
+                Signal::from_stream(0, receiver)
+            });
+            cx.props.1.set(Some(signal));
+
+            render! { "{signal}" }
+        },
+        (RefCell::new(Some(receiver)), signal.clone()),
+    );
+    let _ = dom.rebuild();
+    let signal = signal.get().unwrap();
+    assert_eq!(*signal.peek(), 0);
+
+    sender.unbounded_send(1).unwrap();
+    run_tasks(&mut dom).await;
+    assert_eq!(*signal.peek(), 1);
+
+    sender.unbounded_send(2).unwrap();
+    run_tasks(&mut dom).await;
+    assert_eq!(*signal.peek(), 2);
+}
+
+#[tokio::test]
+async fn to_stream_yields_every_change() {
+    let values = Rc::new(RefCell::new(Vec::<i32>::new()));
+    let signal = Rc::new(Cell::new(None::<Signal<i32>>));
+    let mut dom = VirtualDom::new_with_props(
+        |cx| {
+            let signal = use_signal(cx, || 0);
+            cx.props.1.set(Some(signal));
+            cx.use_hook(|| {
+                let mut stream = signal.to_stream();
+                let values = cx.props.0.clone();
+                cx.spawn(async move {
+                    while let Some(value) = stream.next().await {
+                        values.borrow_mut().push(value);
+                    }
+                });
+            });
+
+            render! { div {} }
+        },
+        (values.clone(), signal.clone()),
+    );
+    let _ = dom.rebuild();
+    run_tasks(&mut dom).await;
+    assert_eq!(*values.borrow(), [0]);
+
+    signal.get().unwrap().set(1);
+    run_tasks(&mut dom).await;
+    assert_eq!(*values.borrow(), [0, 1]);
+}
@@ -41,6 +41,18 @@ impl IncrementalRenderer {
         &mut self.ssr_renderer
     }
 
+    /// Get how long a rendered route is served from the cache before it is rendered again. `None` means the route is
+    /// cached until it is invalidated.
+    pub fn invalidate_after(&self) -> Option<Duration> {
+        self.invalidate_after
+    }
+
+    /// Set how long a rendered route is served from the cache before it is rendered again. `None` means the route is
+    /// cached until it is invalidated.
+    pub fn set_invalidate_after(&mut self, invalidate_after: Option<Duration>) {
+        self.invalidate_after = invalidate_after;
+    }
+
     /// Create a new incremental renderer builder.
     pub fn builder() -> IncrementalRendererConfig {
         IncrementalRendererConfig::new()
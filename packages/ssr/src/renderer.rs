@@ -22,6 +22,10 @@ pub struct Renderer {
     /// Choose to write ElementIDs into elements so the page can be re-hydrated later on
     pub pre_render: bool,
 
+    /// Clean dynamic `dangerous_inner_html` values before they are written. Static values written in rsx are trusted.
+    #[cfg(feature = "sanitize")]
+    pub sanitizer: Option<dioxus_html::Sanitizer>,
+
     // Currently not implemented
     // Don't proceed onto new components. Instead, put the name of the component.
     pub skip_components: bool,
@@ -148,6 +152,23 @@ impl Renderer {
                             write!(buf, "<!--#-->")?;
                         }
                     }
+                    DynamicNode::RawHtml(html) => {
+                        write!(buf, "<dioxus-raw-html style=\"display: contents\"")?;
+                        if self.pre_render {
+                            write!(buf, " data-node-hydration={}", self.dynamic_node_id)?;
+                            self.dynamic_node_id += 1;
+                        }
+                        write!(buf, ">")?;
+                        #[cfg(feature = "sanitize")]
+                        if let Some(sanitizer) = &self.sanitizer {
+                            write!(buf, "{}", sanitizer.clean(html.value))?;
+                        } else {
+                            write!(buf, "{}", html.value)?;
+                        }
+                        #[cfg(not(feature = "sanitize"))]
+                        write!(buf, "{}", html.value)?;
+                        write!(buf, "</dioxus-raw-html>")?;
+                    }
                     DynamicNode::Fragment(nodes) => {
                         for child in *nodes {
                             self.render_template(buf, dom, child)?;
@@ -192,6 +213,11 @@ impl Renderer {
                     if let Some(inner_html) = inner_html.take() {
                         let inner_html = &inner_html.value;
                         match inner_html {
+                            #[cfg(feature = "sanitize")]
+                            AttributeValue::Text(value) if self.sanitizer.is_some() => {
+                                let sanitizer = self.sanitizer.as_ref().unwrap();
+                                write!(buf, "{}", sanitizer.clean(value))?
+                            }
                             AttributeValue::Text(value) => write!(buf, "{}", value)?,
                             AttributeValue::Bool(value) => write!(buf, "{}", value)?,
                             AttributeValue::Float(f) => write!(buf, "{}", f)?,
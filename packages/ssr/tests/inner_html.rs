@@ -24,3 +24,19 @@ fn dynamic_inner_html() {
 
     assert_eq!(dioxus_ssr::render(&dom), r#"<div><div>1234</div></div>"#);
 }
+
+#[cfg(feature = "sanitize")]
+#[test]
+fn sanitized_inner_html() {
+    fn app(cx: Scope) -> Element {
+        let inner_html = r#"<p onclick="steal()">hi<script>steal()</script></p>"#;
+        render! { div { dangerous_inner_html: "{inner_html}" } }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+
+    let mut renderer = dioxus_ssr::Renderer::new();
+    renderer.sanitizer = Some(dioxus_html::Sanitizer::new());
+    assert_eq!(renderer.render(&dom), r#"<div><p>hi</p></div>"#);
+}
@@ -62,6 +62,44 @@ fn components() {
     );
 }
 
+#[test]
+fn component_slots() {
+    #[component]
+    fn Card<'a>(
+        cx: Scope<'a>,
+        title: Element<'a>,
+        actions: Option<Element<'a>>,
+        children: Element<'a>,
+    ) -> Element {
+        render! {
+            div {
+                h1 { title }
+                children
+                actions.as_ref().map(|actions| rsx! { nav { actions } })
+            }
+        }
+    }
+
+    assert_eq!(
+        dioxus_ssr::render_lazy(rsx! {
+            Card {
+                title: render! { "Settings" },
+                actions: render! { button { "Save" } },
+                p { "body" }
+            }
+        }),
+        "<div><h1>Settings</h1><p>body</p><nav><button>Save</button></nav></div>"
+    );
+
+    // optional slots can be left out
+    assert_eq!(
+        dioxus_ssr::render_lazy(rsx! {
+            Card { title: render! { "Empty" } }
+        }),
+        "<div><h1>Empty</h1></div>"
+    );
+}
+
 #[test]
 fn fragments() {
     assert_eq!(
@@ -0,0 +1,411 @@
+//! Accessibility checks that run after every render in debug builds.
+//!
+//! The page is walked alongside the VirtualDom, so every violation is listed with the component and the `rsx!` call
+//! that rendered the element. The checks are basic: images without alt text, form fields without a label, and text
+//! with a contrast below the WCAG AA ratio. Violations are logged and listed in an overlay in the corner of the page.
+
+use dioxus_core::{
+    AttributeValue, DynamicNode, RenderReturn, ScopeState, TemplateAttribute, TemplateNode, VNode,
+    VirtualDom,
+};
+use wasm_bindgen::JsCast;
+use web_sys::{Document, Element, Node};
+
+const OVERLAY_ID: &str = "dioxus-a11y-audit";
+
+/// Audits the page after each render and keeps the overlay up to date
+#[derive(Default)]
+pub(crate) struct A11yAudit {
+    /// The violations of the last audit, so the overlay and the log only change when they do
+    last: Vec<String>,
+}
+
+impl A11yAudit {
+    pub(crate) fn run(&mut self, root: &Element, dom: &VirtualDom) {
+        let Some(document) = root.owner_document() else {
+            return;
+        };
+
+        let mut auditor = Auditor {
+            dom,
+            document: &document,
+            violations: Vec::new(),
+            component: String::new(),
+            location: "",
+        };
+        let mut cursor = Cursor::children(root);
+        auditor.audit_scope(dom.base_scope(), &mut cursor);
+
+        let violations: Vec<String> = auditor
+            .violations
+            .iter()
+            .map(Violation::to_string)
+            .collect();
+        if violations == self.last {
+            return;
+        }
+        if !violations.is_empty() {
+            tracing::warn!(
+                "Found {} accessibility issues:\n{}",
+                violations.len(),
+                violations
+                    .iter()
+                    .map(|violation| format!("  - {violation}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            );
+        }
+        show_overlay(&document, &violations);
+        self.last = violations;
+    }
+}
+
+struct Violation {
+    component: String,
+    location: &'static str,
+    tag: String,
+    message: String,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "<{}> in {} ({}): {}",
+            self.tag, self.component, self.location, self.message
+        )
+    }
+}
+
+/// The children of a node the walk hasn't reached yet
+struct Cursor {
+    next: Option<Node>,
+}
+
+impl Cursor {
+    fn children(parent: &Node) -> Self {
+        Self {
+            next: parent.first_child(),
+        }
+    }
+
+    fn take(&mut self) -> Option<Node> {
+        let node = self.next.take()?;
+        self.next = node.next_sibling();
+        Some(node)
+    }
+}
+
+struct Auditor<'a> {
+    dom: &'a VirtualDom,
+    document: &'a Document,
+    violations: Vec<Violation>,
+    /// The component that rendered the element being audited
+    component: String,
+    /// The `rsx!` call of the element being audited
+    location: &'static str,
+}
+
+impl Auditor<'_> {
+    fn audit_scope(&mut self, scope: &ScopeState, cursor: &mut Cursor) {
+        let RenderReturn::Ready(vnode) = scope.root_node() else {
+            return;
+        };
+        let parent = std::mem::replace(&mut self.component, scope.name().to_string());
+        self.audit_vnode(vnode, cursor);
+        self.component = parent;
+    }
+
+    fn audit_vnode(&mut self, vnode: &VNode, cursor: &mut Cursor) {
+        let template = vnode.template.get();
+        // The name of a template is the location of its `rsx!` call followed by the index of the call
+        let location = template
+            .name
+            .rsplit_once(':')
+            .map_or(template.name, |(location, _)| location);
+        let parent = std::mem::replace(&mut self.location, location);
+        for root in template.roots {
+            self.audit_template_node(vnode, root, cursor);
+        }
+        self.location = parent;
+    }
+
+    fn audit_template_node(&mut self, vnode: &VNode, node: &TemplateNode, cursor: &mut Cursor) {
+        match node {
+            TemplateNode::Element {
+                tag,
+                attrs,
+                children,
+                ..
+            } => {
+                let node = cursor.take();
+                // The page was changed outside of the VirtualDom, so its elements can't be matched to components
+                let Some(element) = node
+                    .as_ref()
+                    .and_then(|node| node.dyn_ref::<Element>())
+                    .filter(|element| element.local_name().eq_ignore_ascii_case(tag))
+                else {
+                    return;
+                };
+                self.audit_element(element);
+
+                // The user sets inner html, so its elements don't come from a template
+                if !has_inner_html(vnode, attrs) {
+                    let mut children_cursor = Cursor::children(element);
+                    for child in *children {
+                        self.audit_template_node(vnode, child, &mut children_cursor);
+                    }
+                }
+            }
+            TemplateNode::Text { .. } | TemplateNode::DynamicText { .. } => {
+                cursor.take();
+            }
+            TemplateNode::Dynamic { id } => {
+                self.audit_dynamic_node(&vnode.dynamic_nodes[*id], cursor)
+            }
+        }
+    }
+
+    fn audit_dynamic_node(&mut self, node: &DynamicNode, cursor: &mut Cursor) {
+        match node {
+            DynamicNode::Text(_) | DynamicNode::RawHtml(_) | DynamicNode::Placeholder(_) => {
+                cursor.take();
+            }
+            DynamicNode::Component(component) => {
+                if let Some(scope) = component
+                    .mounted_scope()
+                    .and_then(|scope| self.dom.get_scope(scope))
+                {
+                    self.audit_scope(scope, cursor);
+                }
+            }
+            DynamicNode::Fragment(nodes) => {
+                for vnode in *nodes {
+                    self.audit_vnode(vnode, cursor);
+                }
+            }
+        }
+    }
+
+    fn audit_element(&mut self, element: &Element) {
+        let tag = element.local_name();
+        match tag.as_str() {
+            "img" if !element.has_attribute("alt") => self.violation(
+                element,
+                "image without alt text. Use alt: \"\" if it is decorative",
+            ),
+            "input" | "select" | "textarea"
+                if needs_label(element) && !has_label(self.document, element) =>
+            {
+                self.violation(element, "form field without a label")
+            }
+            _ => {}
+        }
+
+        if has_own_text(element) {
+            if let Some((ratio, required)) = contrast(element) {
+                if ratio < required {
+                    self.violation(
+                        element,
+                        &format!("text contrast {ratio:.2}:1 is below {required}:1"),
+                    );
+                }
+            }
+        }
+    }
+
+    fn violation(&mut self, element: &Element, message: &str) {
+        self.violations.push(Violation {
+            component: self.component.clone(),
+            location: self.location,
+            tag: element.local_name(),
+            message: message.to_string(),
+        });
+    }
+}
+
+fn has_inner_html(vnode: &VNode, attrs: &[TemplateAttribute]) -> bool {
+    attrs.iter().any(|attr| match attr {
+        TemplateAttribute::Static { name, .. } => *name == "dangerous_inner_html",
+        TemplateAttribute::Dynamic { id } => {
+            let attribute = &vnode.dynamic_attrs[*id];
+            attribute.name == "dangerous_inner_html"
+                && !matches!(attribute.value, AttributeValue::None)
+        }
+    })
+}
+
+/// Whether users need a label to know what the form field is for
+fn needs_label(element: &Element) -> bool {
+    let kind = element.get_attribute("type").unwrap_or_default();
+    !matches!(
+        kind.to_ascii_lowercase().as_str(),
+        "hidden" | "submit" | "reset" | "button" | "image"
+    )
+}
+
+fn has_label(document: &Document, element: &Element) -> bool {
+    let named = ["aria-label", "aria-labelledby", "title"]
+        .iter()
+        .any(|name| {
+            element
+                .get_attribute(name)
+                .map_or(false, |value| !value.trim().is_empty())
+        });
+    let wrapped = element.closest("label").ok().flatten().is_some();
+    let labelled = !element.id().is_empty()
+        && document
+            .query_selector(&format!(
+                "label[for=\"{}\"]",
+                element.id().replace('"', "\\\"")
+            ))
+            .ok()
+            .flatten()
+            .is_some();
+    named || wrapped || labelled
+}
+
+fn has_own_text(element: &Element) -> bool {
+    let children = element.child_nodes();
+    (0..children.length()).any(|i| {
+        children.item(i).map_or(false, |child| {
+            child.node_type() == Node::TEXT_NODE
+                && child
+                    .text_content()
+                    .map_or(false, |text| !text.trim().is_empty())
+        })
+    })
+}
+
+/// The contrast ratio of the text of `element` with its background, and the ratio WCAG AA requires for it.
+///
+/// Returns `None` if the background is an image, so the contrast can't be known.
+fn contrast(element: &Element) -> Option<(f64, f64)> {
+    let window = web_sys::window()?;
+    let style = window.get_computed_style(element).ok()??;
+    let color = parse_color(&style.get_property_value("color").ok()?)?;
+
+    // Large text is easier to read, so it needs less contrast
+    let size: f64 = style
+        .get_property_value("font-size")
+        .ok()?
+        .trim_end_matches("px")
+        .parse()
+        .ok()?;
+    let weight: f64 = style
+        .get_property_value("font-weight")
+        .ok()?
+        .parse()
+        .unwrap_or(400.0);
+    let large = size >= 24.0 || (size >= 18.66 && weight >= 700.0);
+    let required = if large { 3.0 } else { 4.5 };
+
+    // The background is the first opaque background of the element or its ancestors
+    let mut background = [1.0, 1.0, 1.0, 1.0];
+    let mut current = Some(element.clone());
+    while let Some(element) = current {
+        let style = window.get_computed_style(&element).ok()??;
+        if style.get_property_value("background-image").ok()? != "none" {
+            return None;
+        }
+        let color = parse_color(&style.get_property_value("background-color").ok()?)?;
+        if color[3] > 0.0 {
+            background = blend(color, [1.0, 1.0, 1.0, 1.0]);
+            break;
+        }
+        current = element.parent_element();
+    }
+
+    let color = blend(color, background);
+    let (lighter, darker) = {
+        let (a, b) = (luminance(color), luminance(background));
+        if a > b {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    };
+    Some(((lighter + 0.05) / (darker + 0.05), required))
+}
+
+/// Parse a computed `rgb(r, g, b)` or `rgba(r, g, b, a)` color into channels between 0 and 1
+fn parse_color(color: &str) -> Option<[f64; 4]> {
+    let inner = color
+        .trim()
+        .strip_prefix("rgba(")
+        .or_else(|| color.trim().strip_prefix("rgb("))?
+        .strip_suffix(')')?;
+    let mut channels = inner
+        .split(|c| c == ',' || c == '/' || c == ' ')
+        .filter(|channel| !channel.is_empty())
+        .map(|channel| channel.parse::<f64>().ok());
+    let r = channels.next()??;
+    let g = channels.next()??;
+    let b = channels.next()??;
+    let a = channels.next().flatten().unwrap_or(1.0);
+    Some([r / 255.0, g / 255.0, b / 255.0, a])
+}
+
+/// Draw a translucent `color` over an opaque `background`
+fn blend(color: [f64; 4], background: [f64; 4]) -> [f64; 4] {
+    let alpha = color[3];
+    [
+        color[0] * alpha + background[0] * (1.0 - alpha),
+        color[1] * alpha + background[1] * (1.0 - alpha),
+        color[2] * alpha + background[2] * (1.0 - alpha),
+        1.0,
+    ]
+}
+
+/// The relative luminance of a color, as WCAG defines it
+fn luminance(color: [f64; 4]) -> f64 {
+    let channel = |c: f64| {
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(color[0]) + 0.7152 * channel(color[1]) + 0.0722 * channel(color[2])
+}
+
+/// List the violations in a panel in the corner of the page, or remove the panel if there are none. The panel is
+/// outside of the root element, so the VirtualDom never changes it.
+fn show_overlay(document: &Document, violations: &[String]) {
+    if let Some(overlay) = document.get_element_by_id(OVERLAY_ID) {
+        overlay.remove();
+    }
+    if violations.is_empty() {
+        return;
+    }
+    let (Some(body), Ok(overlay)) = (document.body(), document.create_element("div")) else {
+        return;
+    };
+
+    let items: String = violations
+        .iter()
+        .map(|violation| format!("<li>{}</li>", escape(violation)))
+        .collect();
+    overlay.set_id(OVERLAY_ID);
+    _ = overlay.set_attribute(
+        "style",
+        "position: fixed; bottom: 8px; right: 8px; z-index: 2147483647; max-width: 480px; max-height: 40vh; \
+         overflow: auto; padding: 8px 12px; background: #fff; color: #000; border: 2px solid #b00020; \
+         border-radius: 4px; font: 12px/1.4 monospace; box-shadow: 0 2px 8px rgba(0, 0, 0, 0.3);",
+    );
+    overlay.set_inner_html(&format!(
+        "<div style=\"display: flex; justify-content: space-between; font-weight: bold;\">\
+         <span>{} accessibility issues</span>\
+         <button aria-label=\"Close\" onclick=\"this.closest('#{OVERLAY_ID}').remove()\">×</button>\
+         </div><ul style=\"margin: 4px 0 0; padding-left: 16px;\">{items}</ul>",
+        violations.len()
+    ));
+    _ = body.append_child(&overlay);
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
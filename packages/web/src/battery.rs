@@ -0,0 +1,155 @@
+//! Battery status from the browser's [Battery Status API](https://developer.mozilla.org/en-US/docs/Web/API/Battery_Status_API).
+//!
+//! Only Chromium based browsers implement the API. In other browsers the battery status is always `None`.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+use dioxus_core::ScopeState;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// The events the battery manager fires when any part of the status changes
+const BATTERY_EVENTS: &[&str] = &[
+    "chargingchange",
+    "levelchange",
+    "chargingtimechange",
+    "dischargingtimechange",
+];
+
+/// The status of the device's battery
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatteryStatus {
+    /// The charge level, from `0.0` (empty) to `1.0` (full)
+    pub level: f64,
+    /// If the device is plugged in. This is `true` when the battery is full and the device is still plugged in.
+    pub charging: bool,
+    /// How long until the battery is full, if it is charging and the browser can estimate it
+    pub time_to_full: Option<Duration>,
+    /// How long until the battery is empty, if it is discharging and the browser can estimate it
+    pub time_to_empty: Option<Duration>,
+}
+
+impl BatteryStatus {
+    fn from_manager(manager: &JsValue) -> Option<Self> {
+        let get = |name: &str| js_sys::Reflect::get(manager, &JsValue::from_str(name)).ok();
+        Some(Self {
+            level: get("level")?.as_f64()?,
+            charging: get("charging")?.as_bool()?,
+            time_to_full: get("chargingTime")
+                .and_then(|time| time.as_f64())
+                .and_then(seconds_to_duration),
+            time_to_empty: get("dischargingTime")
+                .and_then(|time| time.as_f64())
+                .and_then(seconds_to_duration),
+        })
+    }
+}
+
+/// The browser reports unknown times as `Infinity`
+fn seconds_to_duration(seconds: f64) -> Option<Duration> {
+    (seconds.is_finite() && seconds >= 0.0).then(|| Duration::from_secs_f64(seconds))
+}
+
+async fn battery_manager() -> Option<web_sys::EventTarget> {
+    let navigator = web_sys::window()?.navigator();
+    let get_battery = js_sys::Reflect::get(&navigator, &JsValue::from_str("getBattery"))
+        .ok()?
+        .dyn_into::<js_sys::Function>()
+        .ok()?;
+    let promise = get_battery
+        .call0(&navigator)
+        .ok()?
+        .dyn_into::<js_sys::Promise>()
+        .ok()?;
+    JsFuture::from(promise).await.ok()?.dyn_into().ok()
+}
+
+/// Read the status of the device's battery.
+///
+/// Returns `None` if the browser doesn't implement the Battery Status API.
+pub async fn battery_status() -> Option<BatteryStatus> {
+    BatteryStatus::from_manager(&battery_manager().await?)
+}
+
+struct BatteryListener {
+    manager: web_sys::EventTarget,
+    on_change: Closure<dyn FnMut()>,
+}
+
+impl Drop for BatteryListener {
+    fn drop(&mut self) {
+        for event in BATTERY_EVENTS {
+            let _ = self.manager.remove_event_listener_with_callback(
+                event,
+                self.on_change.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
+
+struct UseBattery {
+    status: Rc<Cell<Option<BatteryStatus>>>,
+    _listener: Rc<RefCell<Option<BatteryListener>>>,
+}
+
+/// Get the status of the device's battery. The component rerenders when the status changes.
+///
+/// This is `None` until the browser reports the status, and stays `None` in browsers that don't implement the
+/// Battery Status API.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let battery = use_battery(cx);
+///     let power_saving = matches!(battery, Some(battery) if !battery.charging && battery.level < 0.2);
+///
+///     render! {
+///         Animations { enabled: !power_saving }
+///     }
+/// }
+/// ```
+pub fn use_battery(cx: &ScopeState) -> Option<BatteryStatus> {
+    let state = cx.use_hook(|| {
+        let status = Rc::new(Cell::new(None));
+        let listener = Rc::new(RefCell::new(None));
+        let update = cx.schedule_update();
+
+        cx.spawn({
+            let status = status.clone();
+            let listener = listener.clone();
+            async move {
+                let manager = match battery_manager().await {
+                    Some(manager) => manager,
+                    None => return,
+                };
+
+                let on_change = Closure::<dyn FnMut()>::new({
+                    let manager = manager.clone();
+                    let status = status.clone();
+                    let update = update.clone();
+                    move || {
+                        status.set(BatteryStatus::from_manager(&manager));
+                        update();
+                    }
+                });
+                for event in BATTERY_EVENTS {
+                    let _ = manager.add_event_listener_with_callback(
+                        event,
+                        on_change.as_ref().unchecked_ref(),
+                    );
+                }
+
+                status.set(BatteryStatus::from_manager(&manager));
+                *listener.borrow_mut() = Some(BatteryListener { manager, on_change });
+                update();
+            }
+        });
+
+        UseBattery {
+            status,
+            _listener: listener,
+        }
+    });
+    state.status.get()
+}
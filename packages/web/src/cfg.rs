@@ -10,9 +10,15 @@
 pub struct Config {
     #[cfg(feature = "hydrate")]
     pub(crate) hydrate: bool,
+    #[cfg(feature = "hydrate")]
+    pub(crate) timed_out_suspense: Vec<usize>,
     pub(crate) rootname: String,
     pub(crate) cached_strings: Vec<String>,
     pub(crate) default_panic_hook: bool,
+    pub(crate) route_transitions: bool,
+    pub(crate) sanitize_inner_html: bool,
+    #[cfg(feature = "a11y_audit")]
+    pub(crate) a11y_audit: bool,
 }
 
 impl Default for Config {
@@ -20,9 +26,15 @@ impl Default for Config {
         Self {
             #[cfg(feature = "hydrate")]
             hydrate: false,
+            #[cfg(feature = "hydrate")]
+            timed_out_suspense: Vec::new(),
             rootname: "main".to_string(),
             cached_strings: Vec::new(),
             default_panic_hook: true,
+            route_transitions: false,
+            sanitize_inner_html: false,
+            #[cfg(feature = "a11y_audit")]
+            a11y_audit: false,
         }
     }
 }
@@ -47,6 +59,15 @@ impl Config {
         self
     }
 
+    #[cfg(feature = "hydrate")]
+    /// Start the suspense boundaries that timed out while the page was rendered on the server timed out, so they
+    /// are hydrated with the fallback the server sent. See
+    /// [`VirtualDom::with_timed_out_suspense_boundaries`](dioxus_core::VirtualDom::with_timed_out_suspense_boundaries).
+    pub fn with_timed_out_suspense(mut self, timed_out: Vec<usize>) -> Self {
+        self.timed_out_suspense = timed_out;
+        self
+    }
+
     /// Set the name of the element that Dioxus will use as the root.
     ///
     /// This is akin to calling React.render() on the element with the specified name.
@@ -71,4 +92,34 @@ impl Config {
         self.default_panic_hook = f;
         self
     }
+
+    /// Animate every change of the page's url with a [view transition](https://developer.mozilla.org/en-US/docs/Web/API/View_Transitions_API).
+    ///
+    /// Give elements that appear on both routes the same `view-transition-name` style to animate them as a shared
+    /// element. Browsers without the View Transitions API switch routes without an animation.
+    pub fn with_route_transitions(mut self, route_transitions: bool) -> Self {
+        self.route_transitions = route_transitions;
+        self
+    }
+
+    /// Remove every tag, attribute and url scheme that is not on the allowlist from `dangerous_inner_html` before it
+    /// is set.
+    ///
+    /// The allowlist is the same as the one of [`Sanitizer`](dioxus_html::sanitize). Use
+    /// [`SafeHtml`](dioxus_html::SafeHtml) to sanitize values with a custom allowlist instead.
+    pub fn with_inner_html_sanitizer(mut self, sanitize: bool) -> Self {
+        self.sanitize_inner_html = sanitize;
+        self
+    }
+
+    #[cfg(feature = "a11y_audit")]
+    /// Check the page for accessibility issues after every render, and list them in an overlay with the components
+    /// that rendered them.
+    ///
+    /// The checks find images without alt text, form fields without a label and text with too little contrast with
+    /// its background. They only run in debug builds.
+    pub fn with_a11y_audit(mut self, audit: bool) -> Self {
+        self.a11y_audit = audit;
+        self
+    }
 }
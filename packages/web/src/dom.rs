@@ -9,7 +9,9 @@
 use dioxus_core::{
     BorrowedAttributeValue, ElementId, Mutation, Template, TemplateAttribute, TemplateNode,
 };
-use dioxus_html::{event_bubbles, CompositionData, FormData, MountedData};
+use dioxus_html::{
+    event_bubbles, ClipboardData, CompositionData, DragData, FormData, MountedData, MouseData,
+};
 use dioxus_interpreter_js::{get_node, minimal_bindings, save_template, Channel};
 use futures_channel::mpsc;
 use js_sys::Array;
@@ -188,6 +190,7 @@ impl WebsysDom {
                     i.assign_id(path.as_ptr() as u32, path.len() as u8, id.0 as u32)
                 }
                 CreatePlaceholder { id } => i.create_placeholder(id.0 as u32),
+                CreateRawHtml { value, id } => i.create_raw_html(value, id.0 as u32),
                 CreateTextNode { value, id } => i.create_text_node(value, id.0 as u32),
                 HydrateText { path, value, id } => {
                     i.hydrate_text(path.as_ptr() as u32, path.len() as u8, value, id.0 as u32)
@@ -230,6 +233,7 @@ impl WebsysDom {
                     _ => unreachable!(),
                 },
                 SetText { value, id } => i.set_text(id.0 as u32, value),
+                SetRawHtml { value, id } => i.set_raw_html(id.0 as u32, value),
                 NewEventListener { name, id, .. } => {
                     match *name {
                         // mounted events are fired immediately after the element is mounted.
@@ -280,7 +284,7 @@ pub fn virtual_event_from_websys_event(event: web_sys::Event, target: Element) -
     use dioxus_html::events::*;
 
     match event.type_().as_str() {
-        "copy" | "cut" | "paste" => Rc::new(ClipboardData {}),
+        "copy" | "cut" | "paste" => read_clipboard_to_data(&event),
         "compositionend" | "compositionstart" | "compositionupdate" => {
             make_composition_event(&event)
         }
@@ -294,10 +298,7 @@ pub fn virtual_event_from_websys_event(event: web_sys::Event, target: Element) -
             Rc::new(MouseData::from(event))
         }
         "drag" | "dragend" | "dragenter" | "dragexit" | "dragleave" | "dragover" | "dragstart"
-        | "drop" => {
-            let mouse = MouseData::from(event);
-            Rc::new(DragData { mouse })
-        }
+        | "drop" => read_drag_to_data(event),
 
         "pointerdown" | "pointermove" | "pointerup" | "pointercancel" | "gotpointercapture"
         | "lostpointercapture" | "pointerenter" | "pointerleave" | "pointerover" | "pointerout" => {
@@ -318,7 +319,7 @@ pub fn virtual_event_from_websys_event(event: web_sys::Event, target: Element) -
         | "timeupdate" | "volumechange" | "waiting" => Rc::new(MediaData {}),
         "error" => Rc::new(ImageData { load_error: true }),
         "load" => Rc::new(ImageData { load_error: false }),
-        "toggle" => Rc::new(ToggleData {}),
+        "toggle" | "beforetoggle" | "close" | "cancel" => Rc::new(ToggleData {}),
 
         _ => Rc::new(()),
     }
@@ -416,6 +417,61 @@ fn read_input_to_data(target: Element) -> Rc<FormData> {
     })
 }
 
+fn read_drag_to_data(event: Event) -> Rc<dyn Any> {
+    #[cfg(not(feature = "file_engine"))]
+    let files = None;
+    #[cfg(feature = "file_engine")]
+    let files = event
+        .dyn_ref::<web_sys::DragEvent>()
+        .and_then(|event| event.data_transfer())
+        .and_then(|transfer| transfer.files())
+        .filter(|files| files.length() > 0)
+        .and_then(|files| {
+            #[allow(clippy::arc_with_non_send_sync)]
+            crate::file_engine::WebFileEngine::new(files)
+                .map(|f| std::sync::Arc::new(f) as std::sync::Arc<dyn dioxus_html::FileEngine>)
+        });
+
+    Rc::new(DragData {
+        mouse: MouseData::from(event),
+        files,
+    })
+}
+
+fn read_clipboard_to_data(event: &Event) -> Rc<dyn Any> {
+    let transfer = event
+        .dyn_ref::<web_sys::ClipboardEvent>()
+        .and_then(|event| event.clipboard_data());
+    let Some(transfer) = transfer else {
+        return Rc::new(ClipboardData::default());
+    };
+
+    let get_data = |format: &str| {
+        transfer
+            .get_data(format)
+            .ok()
+            .filter(|data| !data.is_empty())
+    };
+
+    #[cfg(not(feature = "file_engine"))]
+    let files = None;
+    #[cfg(feature = "file_engine")]
+    let files = transfer
+        .files()
+        .filter(|files| files.length() > 0)
+        .and_then(|files| {
+            #[allow(clippy::arc_with_non_send_sync)]
+            crate::file_engine::WebFileEngine::new(files)
+                .map(|f| std::sync::Arc::new(f) as std::sync::Arc<dyn dioxus_html::FileEngine>)
+        });
+
+    Rc::new(ClipboardData {
+        text: get_data("text/plain"),
+        html: get_data("text/html"),
+        files,
+    })
+}
+
 // web-sys does not expose the keys api for form data, so we need to manually bind to it
 #[wasm_bindgen(inline_js = r#"
     export function get_form_data(form) {
@@ -0,0 +1,85 @@
+//! Listen to events of the window or the document, like `keydown`, `visibilitychange` and `paste`.
+
+use dioxus_core::ScopeState;
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// The object a listener is added to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ListenerTarget {
+    /// `window`
+    Window,
+    /// `document`
+    Document,
+}
+
+impl ListenerTarget {
+    fn event_target(&self) -> Option<web_sys::EventTarget> {
+        let window = web_sys::window()?;
+        match self {
+            ListenerTarget::Window => Some(window.into()),
+            ListenerTarget::Document => window.document().map(Into::into),
+        }
+    }
+}
+
+/// Calls a callback every time an event of the window or the document fires. The callback is removed when this is
+/// dropped.
+pub struct EventListener {
+    target: Option<web_sys::EventTarget>,
+    event: String,
+    callback: Closure<dyn FnMut(web_sys::Event)>,
+}
+
+impl Drop for EventListener {
+    fn drop(&mut self) {
+        if let Some(target) = &self.target {
+            let _ = target.remove_event_listener_with_callback(
+                &self.event,
+                self.callback.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
+
+/// Call `callback` every time `event` fires on the window or the document.
+pub fn add_event_listener(
+    target: ListenerTarget,
+    event: &str,
+    callback: impl FnMut(web_sys::Event) + 'static,
+) -> EventListener {
+    let callback = Closure::<dyn FnMut(web_sys::Event)>::new(callback);
+    let target = target.event_target();
+
+    if let Some(target) = &target {
+        let _ = target.add_event_listener_with_callback(event, callback.as_ref().unchecked_ref());
+    }
+
+    EventListener {
+        target,
+        event: event.to_string(),
+        callback,
+    }
+}
+
+/// Call `handler` with every `event` of the window or the document, for the lifetime of the component.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let hidden = use_state(cx, || false);
+///
+///     use_event_listener(cx, ListenerTarget::Document, "visibilitychange", {
+///         to_owned![hidden];
+///         move |_| hidden.set(web_sys::window().unwrap().document().unwrap().hidden())
+///     });
+///
+///     render! { "The page is hidden: {hidden}" }
+/// }
+/// ```
+pub fn use_event_listener(
+    cx: &ScopeState,
+    target: ListenerTarget,
+    event: &str,
+    handler: impl FnMut(web_sys::Event) + 'static,
+) -> &EventListener {
+    cx.use_hook(|| add_event_listener(target, event, handler))
+}
@@ -0,0 +1,318 @@
+//! Reports, in debug builds, where the HTML rendered on the server is different from the first render of the client.
+//!
+//! Hydration reuses the nodes the server rendered instead of creating new ones. If they don't match the nodes the
+//! client renders, ids and listeners are attached to the wrong nodes and the page stops responding to events without
+//! any error. Before hydrating, the server HTML is walked alongside the VirtualDom and every difference is reported
+//! with the component and the `rsx!` call it comes from.
+
+use std::fmt::{self, Display};
+
+use dioxus_core::{
+    AttributeValue, DynamicNode, RenderReturn, ScopeState, TemplateAttribute, TemplateNode, VNode,
+    VirtualDom,
+};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Node};
+
+/// A difference between the server HTML and the first render of the client
+#[derive(Debug)]
+pub(crate) struct HydrationMismatch {
+    component: String,
+    location: &'static str,
+    path: String,
+    expected: String,
+    found: String,
+}
+
+impl Display for HydrationMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} in {} ({}): expected {}, found {}",
+            self.path, self.component, self.location, self.expected, self.found
+        )
+    }
+}
+
+/// Log every difference between the children of `root` and the first render of `dom`
+pub(crate) fn report(root: &Element, dom: &VirtualDom) {
+    let mismatches = find_mismatches(root, dom);
+    if mismatches.is_empty() {
+        return;
+    }
+
+    let list = mismatches
+        .iter()
+        .map(|mismatch| format!("  - {mismatch}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    tracing::error!(
+        "The HTML rendered on the server doesn't match the first render of the client. Events may be sent to the wrong elements until the mismatched nodes are rendered again:\n{}",
+        list
+    );
+}
+
+/// Every difference between the children of `root` and the first render of `dom`
+fn find_mismatches(root: &Element, dom: &VirtualDom) -> Vec<HydrationMismatch> {
+    let mut checker = Checker {
+        dom,
+        mismatches: Vec::new(),
+        component: String::new(),
+        location: "",
+        path: Vec::new(),
+    };
+    let mut cursor = Cursor::children(root);
+    checker.check_scope(dom.base_scope(), &mut cursor);
+    checker.check_end(&mut cursor, "the end of the root element");
+    checker.mismatches
+}
+
+/// The children of a node the walk hasn't reached yet
+struct Cursor {
+    next: Option<Node>,
+}
+
+impl Cursor {
+    fn children(parent: &Node) -> Self {
+        Self {
+            next: parent.first_child(),
+        }
+    }
+
+    fn take(&mut self) -> Option<Node> {
+        let node = self.next.take()?;
+        self.next = node.next_sibling();
+        Some(node)
+    }
+}
+
+struct Checker<'a> {
+    dom: &'a VirtualDom,
+    mismatches: Vec<HydrationMismatch>,
+    /// The component that rendered the node being checked
+    component: String,
+    /// The `rsx!` call of the node being checked
+    location: &'static str,
+    /// The tags of the elements above the node being checked
+    path: Vec<String>,
+}
+
+impl Checker<'_> {
+    fn check_scope(&mut self, scope: &ScopeState, cursor: &mut Cursor) {
+        let RenderReturn::Ready(vnode) = scope.root_node() else {
+            return;
+        };
+        let parent = std::mem::replace(&mut self.component, scope.name().to_string());
+        self.check_vnode(vnode, cursor);
+        self.component = parent;
+    }
+
+    fn check_vnode(&mut self, vnode: &VNode, cursor: &mut Cursor) {
+        let template = vnode.template.get();
+        // The name of a template is the location of its `rsx!` call followed by the index of the call
+        let location = template
+            .name
+            .rsplit_once(':')
+            .map_or(template.name, |(location, _)| location);
+        let parent = std::mem::replace(&mut self.location, location);
+        for root in template.roots {
+            self.check_template_node(vnode, root, cursor);
+        }
+        self.location = parent;
+    }
+
+    fn check_template_node(&mut self, vnode: &VNode, node: &TemplateNode, cursor: &mut Cursor) {
+        match node {
+            TemplateNode::Element {
+                tag,
+                attrs,
+                children,
+                ..
+            } => {
+                let Some(element) = self.expect_element(cursor, tag) else {
+                    return;
+                };
+                self.path.push(tag.to_string());
+                let has_inner_html = self.check_attributes(vnode, attrs, &element);
+                // Inner html is set by the user, so there is nothing to compare it to
+                if !has_inner_html {
+                    let mut children_cursor = Cursor::children(&element);
+                    for child in *children {
+                        self.check_template_node(vnode, child, &mut children_cursor);
+                    }
+                    self.check_end(&mut children_cursor, &format!("the end of <{tag}>"));
+                }
+                self.path.pop();
+            }
+            // Empty text isn't rendered as a node
+            TemplateNode::Text { text } if text.is_empty() => {}
+            TemplateNode::Text { text } => self.expect_text(cursor, text),
+            TemplateNode::Dynamic { id } | TemplateNode::DynamicText { id } => {
+                self.check_dynamic_node(&vnode.dynamic_nodes[*id], cursor)
+            }
+        }
+    }
+
+    fn check_dynamic_node(&mut self, node: &DynamicNode, cursor: &mut Cursor) {
+        match node {
+            // Dynamic text is wrapped in comments so it isn't merged with the text around it
+            DynamicNode::Text(text) => {
+                self.expect_comment(
+                    cursor,
+                    |comment| comment.starts_with("node-id"),
+                    "<!--node-id-->",
+                );
+                if !text.value.is_empty() {
+                    self.expect_text(cursor, text.value);
+                }
+                self.expect_comment(cursor, |comment| comment == "#", "<!--#-->");
+            }
+            DynamicNode::RawHtml(_) => {
+                self.expect_element(cursor, "dioxus-raw-html");
+            }
+            DynamicNode::Placeholder(_) => {
+                self.expect_element(cursor, "pre");
+            }
+            DynamicNode::Component(component) => {
+                if let Some(scope) = component
+                    .mounted_scope()
+                    .and_then(|scope| self.dom.get_scope(scope))
+                {
+                    self.check_scope(scope, cursor);
+                }
+            }
+            DynamicNode::Fragment(nodes) => {
+                for vnode in *nodes {
+                    self.check_vnode(vnode, cursor);
+                }
+            }
+        }
+    }
+
+    /// Check the attributes the server renders, and return whether the element has inner html
+    fn check_attributes(
+        &mut self,
+        vnode: &VNode,
+        attrs: &[TemplateAttribute],
+        element: &Element,
+    ) -> bool {
+        let mut has_inner_html = false;
+        for attr in attrs {
+            let (name, namespace, value) = match attr {
+                TemplateAttribute::Static {
+                    name,
+                    value,
+                    namespace,
+                } => (*name, *namespace, value.to_string()),
+                TemplateAttribute::Dynamic { id } => {
+                    let attribute = &vnode.dynamic_attrs[*id];
+                    let value = match &attribute.value {
+                        AttributeValue::Text(value) => value.to_string(),
+                        AttributeValue::Int(value) => value.to_string(),
+                        AttributeValue::Float(value) => value.to_string(),
+                        // Booleans, listeners and values are not rendered as attribute text
+                        _ => continue,
+                    };
+                    (attribute.name, attribute.namespace, value)
+                }
+            };
+
+            if name == "dangerous_inner_html" {
+                has_inner_html = true;
+                continue;
+            }
+            // Styles are merged into one attribute, and false boolean attributes are left out
+            if namespace.is_some() || value == "false" {
+                continue;
+            }
+
+            let found = element.get_attribute(name);
+            if found.as_deref() != Some(value.as_str()) {
+                let found = match found {
+                    Some(found) => format!("{name}=\"{found}\""),
+                    None => format!("no {name} attribute"),
+                };
+                self.mismatch(format!("{name}=\"{value}\""), found);
+            }
+        }
+        has_inner_html
+    }
+
+    fn expect_element(&mut self, cursor: &mut Cursor, tag: &str) -> Option<Element> {
+        let node = cursor.take();
+        match node.as_ref().and_then(|node| node.dyn_ref::<Element>()) {
+            Some(element) if element.local_name().eq_ignore_ascii_case(tag) => {
+                Some(element.clone())
+            }
+            _ => {
+                self.mismatch(format!("<{tag}>"), describe(node.as_ref()));
+                None
+            }
+        }
+    }
+
+    fn expect_text(&mut self, cursor: &mut Cursor, text: &str) {
+        let node = cursor.take();
+        let matches = node.as_ref().map_or(false, |node| {
+            node.node_type() == Node::TEXT_NODE && node.text_content().as_deref() == Some(text)
+        });
+        if !matches {
+            self.mismatch(format!("text {text:?}"), describe(node.as_ref()));
+        }
+    }
+
+    fn expect_comment(
+        &mut self,
+        cursor: &mut Cursor,
+        matches: impl Fn(&str) -> bool,
+        expected: &str,
+    ) {
+        let node = cursor.take();
+        let found = node.as_ref().map_or(false, |node| {
+            node.node_type() == Node::COMMENT_NODE
+                && node
+                    .text_content()
+                    .map_or(false, |comment| matches(&comment))
+        });
+        if !found {
+            self.mismatch(expected.to_string(), describe(node.as_ref()));
+        }
+    }
+
+    /// Report the nodes the server rendered after the last node of the client
+    fn check_end(&mut self, cursor: &mut Cursor, expected: &str) {
+        if let Some(node) = cursor.take() {
+            self.mismatch(expected.to_string(), describe(Some(&node)));
+        }
+    }
+
+    fn mismatch(&mut self, expected: String, found: String) {
+        let path = if self.path.is_empty() {
+            "root".to_string()
+        } else {
+            self.path.join(" > ")
+        };
+        self.mismatches.push(HydrationMismatch {
+            component: self.component.clone(),
+            location: self.location,
+            path,
+            expected,
+            found,
+        });
+    }
+}
+
+fn describe(node: Option<&Node>) -> String {
+    let Some(node) = node else {
+        return "nothing".to_string();
+    };
+    if let Some(element) = node.dyn_ref::<Element>() {
+        return format!("<{}>", element.local_name());
+    }
+    let content = node.text_content().unwrap_or_default();
+    match node.node_type() {
+        Node::TEXT_NODE => format!("text {content:?}"),
+        Node::COMMENT_NODE => format!("<!--{content}-->"),
+        _ => node.node_name(),
+    }
+}
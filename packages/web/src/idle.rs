@@ -0,0 +1,176 @@
+//! Detect when the user stops interacting with the page.
+
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dioxus_core::ScopeState;
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// The events that count as the user interacting with the page
+const ACTIVITY_EVENTS: &[&str] = &[
+    "pointerdown",
+    "pointermove",
+    "keydown",
+    "wheel",
+    "touchstart",
+    "scroll",
+];
+
+/// Calls a callback every time the user interacts with the page. The callback is removed when this is dropped.
+pub struct UserActivityListener {
+    on_activity: Closure<dyn FnMut()>,
+}
+
+impl Drop for UserActivityListener {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            for event in ACTIVITY_EVENTS {
+                let _ = window.remove_event_listener_with_callback_and_bool(
+                    event,
+                    self.on_activity.as_ref().unchecked_ref(),
+                    true,
+                );
+            }
+        }
+    }
+}
+
+/// Call `callback` every time the user moves the pointer, presses a key, scrolls or touches the page.
+///
+/// The callback is called for every event, so it should be cheap.
+pub fn watch_user_activity(callback: impl FnMut() + 'static) -> UserActivityListener {
+    let on_activity = Closure::<dyn FnMut()>::new(callback);
+
+    if let Some(window) = web_sys::window() {
+        for event in ACTIVITY_EVENTS {
+            // Listen in the capture phase so events that don't bubble, like scrolling inside an element, are seen
+            let _ = window.add_event_listener_with_callback_and_bool(
+                event,
+                on_activity.as_ref().unchecked_ref(),
+                true,
+            );
+        }
+    }
+
+    UserActivityListener { on_activity }
+}
+
+struct IdleTimer {
+    threshold_ms: f64,
+    idle: Cell<bool>,
+    last_active: Cell<f64>,
+    timeout: Cell<Option<i32>>,
+    on_timeout: RefCell<Option<Closure<dyn FnMut()>>>,
+    update: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl IdleTimer {
+    fn arm(&self, delay_ms: f64) {
+        let window = match web_sys::window() {
+            Some(window) => window,
+            None => return,
+        };
+        if let Some(timeout) = self.timeout.take() {
+            window.clear_timeout_with_handle(timeout);
+        }
+        if let Some(on_timeout) = &*self.on_timeout.borrow() {
+            self.timeout.set(
+                window
+                    .set_timeout_with_callback_and_timeout_and_arguments_0(
+                        on_timeout.as_ref().unchecked_ref(),
+                        delay_ms.ceil() as i32,
+                    )
+                    .ok(),
+            );
+        }
+    }
+
+    fn on_timeout(&self) {
+        self.timeout.set(None);
+        let elapsed = js_sys::Date::now() - self.last_active.get();
+        if elapsed >= self.threshold_ms {
+            self.idle.set(true);
+            (self.update)();
+        } else {
+            self.arm(self.threshold_ms - elapsed);
+        }
+    }
+
+    fn on_activity(&self) {
+        self.last_active.set(js_sys::Date::now());
+        if self.idle.replace(false) {
+            (self.update)();
+        }
+        // While the user is active the timer is always running, so we only need to restart it after going idle
+        if self.timeout.get().is_none() {
+            self.arm(self.threshold_ms);
+        }
+    }
+}
+
+impl Drop for IdleTimer {
+    fn drop(&mut self) {
+        if let (Some(window), Some(timeout)) = (web_sys::window(), self.timeout.take()) {
+            window.clear_timeout_with_handle(timeout);
+        }
+    }
+}
+
+struct UseIdle {
+    timer: Rc<IdleTimer>,
+    _listener: UserActivityListener,
+}
+
+/// Get whether the user has not interacted with the page for at least `threshold`. The component rerenders when the
+/// user goes idle and when they become active again.
+///
+/// The threshold is read when the component is first rendered. Changing it later has no effect.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let idle = use_idle(cx, Duration::from_secs(5 * 60));
+///
+///     render! {
+///         if idle {
+///             rsx! { LockScreen {} }
+///         }
+///     }
+/// }
+/// ```
+pub fn use_idle(cx: &ScopeState, threshold: Duration) -> bool {
+    let state = cx.use_hook(|| {
+        let timer = Rc::new(IdleTimer {
+            threshold_ms: threshold.as_secs_f64() * 1000.0,
+            idle: Cell::new(false),
+            last_active: Cell::new(js_sys::Date::now()),
+            timeout: Cell::new(None),
+            on_timeout: RefCell::new(None),
+            update: cx.schedule_update(),
+        });
+
+        let weak: Weak<IdleTimer> = Rc::downgrade(&timer);
+        *timer.on_timeout.borrow_mut() = Some(Closure::<dyn FnMut()>::new(move || {
+            if let Some(timer) = weak.upgrade() {
+                timer.on_timeout();
+            }
+        }));
+        timer.arm(timer.threshold_ms);
+
+        let listener = watch_user_activity({
+            let weak = Rc::downgrade(&timer);
+            move || {
+                if let Some(timer) = weak.upgrade() {
+                    timer.on_activity();
+                }
+            }
+        });
+
+        UseIdle {
+            timer,
+            _listener: listener,
+        }
+    });
+    state.timer.idle.get()
+}
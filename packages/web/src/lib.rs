@@ -55,26 +55,42 @@
 //     - Do the VDOM work during the idlecallback
 //     - Do DOM work in the next requestAnimationFrame callback
 
+pub use crate::battery::{battery_status, use_battery, BatteryStatus};
 pub use crate::cfg::Config;
+pub use crate::event_listener::{
+    add_event_listener, use_event_listener, EventListener, ListenerTarget,
+};
 #[cfg(feature = "file_engine")]
 pub use crate::file_engine::WebFileEngineExt;
+pub use crate::idle::{use_idle, watch_user_activity, UserActivityListener};
+pub use crate::online::{is_online, use_online_status, watch_online_status, OnlineStatusListener};
+pub use crate::view_transition::{use_view_transition, view_transitions_supported, ViewTransition};
 use dioxus_core::{Element, Scope, VirtualDom};
 use futures_util::{
     future::{select, Either},
     pin_mut, FutureExt, StreamExt,
 };
 
+#[cfg(all(feature = "a11y_audit", debug_assertions))]
+mod a11y_audit;
+mod battery;
 mod cache;
 mod cfg;
 mod dom;
 #[cfg(feature = "eval")]
 mod eval;
+mod event_listener;
 #[cfg(feature = "file_engine")]
 mod file_engine;
 #[cfg(all(feature = "hot_reload", debug_assertions))]
 mod hot_reload;
+#[cfg(all(feature = "hydrate", debug_assertions))]
+mod hydration_mismatch;
+mod idle;
+mod online;
 #[cfg(feature = "hydrate")]
 mod rehydrate;
+mod view_transition;
 
 // Currently disabled since it actually slows down immediate rendering
 // todo: only schedule non-immediate renders through ric/raf
@@ -176,6 +192,10 @@ pub async fn run_with_props<T: 'static>(root: fn(Scope<T>) -> Element, root_prop
     tracing::info!("Starting up");
 
     let mut dom = VirtualDom::new_with_props(root, root_props);
+    #[cfg(feature = "hydrate")]
+    if cfg.hydrate {
+        dom = dom.with_timed_out_suspense_boundaries(cfg.timed_out_suspense.clone());
+    }
 
     #[cfg(feature = "eval")]
     {
@@ -192,6 +212,15 @@ pub async fn run_with_props<T: 'static>(root: fn(Scope<T>) -> Element, root_prop
     #[cfg(all(feature = "hot_reload", debug_assertions))]
     let mut hotreload_rx = hot_reload::init();
 
+    if cfg.sanitize_inner_html {
+        // The interpreter checks this flag every time it sets dangerous_inner_html
+        let _ = js_sys::Reflect::set(
+            &web_sys::window().unwrap(),
+            &"__dioxusSanitizeInnerHtml".into(),
+            &true.into(),
+        );
+    }
+
     for s in crate::cache::BUILTIN_INTERNED_STRINGS {
         wasm_bindgen::intern(s);
     }
@@ -206,6 +235,10 @@ pub async fn run_with_props<T: 'static>(root: fn(Scope<T>) -> Element, root_prop
     #[cfg(not(feature = "hydrate"))]
     let should_hydrate = false;
 
+    let mut view_transitions = view_transition::ViewTransitions::new(cfg.route_transitions);
+    #[cfg(all(feature = "a11y_audit", debug_assertions))]
+    let mut a11y_audit = cfg.a11y_audit.then(a11y_audit::A11yAudit::default);
+
     let mut websys_dom = dom::WebsysDom::new(cfg, tx);
 
     tracing::info!("rebuilding app");
@@ -223,6 +256,10 @@ pub async fn run_with_props<T: 'static>(root: fn(Scope<T>) -> Element, root_prop
                 websys_dom.load_templates(&templates);
                 websys_dom.interpreter.flush();
             }
+            // Point out where the server HTML doesn't match the client before it is hydrated
+            #[cfg(debug_assertions)]
+            hydration_mismatch::report(&websys_dom.root, &dom);
+
             if let Err(err) = websys_dom.rehydrate(&dom) {
                 tracing::error!("Rehydration failed. {:?}", err);
                 tracing::error!("Rebuild DOM into element from scratch");
@@ -243,6 +280,12 @@ pub async fn run_with_props<T: 'static>(root: fn(Scope<T>) -> Element, root_prop
 
     // the mutations come back with nothing - we need to actually mount them
     websys_dom.mount();
+    // The first frame is in the DOM but not painted yet
+    dom.flush_layout_effects();
+    #[cfg(all(feature = "a11y_audit", debug_assertions))]
+    if let Some(audit) = &mut a11y_audit {
+        audit.run(&websys_dom.root, &dom);
+    }
 
     loop {
         tracing::trace!("waiting for work");
@@ -272,6 +315,7 @@ pub async fn run_with_props<T: 'static>(root: fn(Scope<T>) -> Element, root_prop
 
         // Dequeue all of the events from the channel in send order
         // todo: we should re-order these if possible
+        let handled_event = res.is_some();
         while let Some(evt) = res {
             web_sys::console::log_1(
                 &format!(
@@ -284,6 +328,11 @@ pub async fn run_with_props<T: 'static>(root: fn(Scope<T>) -> Element, root_prop
             res = rx.try_next().transpose().unwrap().ok();
         }
 
+        // With render batching the updates of the events wait for the other events of the tick
+        if handled_event && !dom.should_render_after_event() {
+            continue;
+        }
+
         // Todo: This is currently disabled because it has a negative impact on response times for events but it could be re-enabled for tasks
         // Jank free rendering
         //
@@ -301,7 +350,25 @@ pub async fn run_with_props<T: 'static>(root: fn(Scope<T>) -> Element, root_prop
         // wait for the animation frame to fire so we can apply our changes
         // work_loop.wait_for_raf().await;
 
+        // If this render is animated, the browser needs to capture the old page before we change it
+        if let Some(ready) = view_transitions.begin(!edits.edits.is_empty()) {
+            let _ = ready.await;
+        }
+
+        #[cfg(all(feature = "a11y_audit", debug_assertions))]
+        let changed = !edits.edits.is_empty();
+
         websys_dom.load_templates(&edits.templates);
         websys_dom.apply_edits(edits.edits);
+
+        // Layout effects run before the browser gets to paint the changes
+        dom.flush_layout_effects();
+
+        #[cfg(all(feature = "a11y_audit", debug_assertions))]
+        if let (true, Some(audit)) = (changed, &mut a11y_audit) {
+            audit.run(&websys_dom.root, &dom);
+        }
+
+        view_transitions.finish();
     }
 }
@@ -0,0 +1,99 @@
+//! Detect when the browser goes offline or comes back online.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use dioxus_core::ScopeState;
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// Check if the browser currently has a network connection.
+///
+/// This is `false` when the browser is definitely offline. A `true` value means the browser is connected to a
+/// network, not that the server is reachable.
+pub fn is_online() -> bool {
+    web_sys::window()
+        .map(|window| window.navigator().on_line())
+        .unwrap_or(true)
+}
+
+/// Calls a callback every time the browser goes offline or comes back online. The callback is removed when this is
+/// dropped.
+pub struct OnlineStatusListener {
+    on_online: Closure<dyn FnMut()>,
+    on_offline: Closure<dyn FnMut()>,
+}
+
+impl Drop for OnlineStatusListener {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            let _ = window.remove_event_listener_with_callback(
+                "online",
+                self.on_online.as_ref().unchecked_ref(),
+            );
+            let _ = window.remove_event_listener_with_callback(
+                "offline",
+                self.on_offline.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
+
+/// Call `callback` with the new status every time the browser goes offline or comes back online.
+pub fn watch_online_status(callback: impl FnMut(bool) + 'static) -> OnlineStatusListener {
+    let callback = Rc::new(std::cell::RefCell::new(callback));
+
+    let on_online = Closure::<dyn FnMut()>::new({
+        let callback = callback.clone();
+        move || (callback.borrow_mut())(true)
+    });
+    let on_offline = Closure::<dyn FnMut()>::new(move || (callback.borrow_mut())(false));
+
+    if let Some(window) = web_sys::window() {
+        let _ =
+            window.add_event_listener_with_callback("online", on_online.as_ref().unchecked_ref());
+        let _ =
+            window.add_event_listener_with_callback("offline", on_offline.as_ref().unchecked_ref());
+    }
+
+    OnlineStatusListener {
+        on_online,
+        on_offline,
+    }
+}
+
+struct UseOnlineStatus {
+    online: Rc<Cell<bool>>,
+    _listener: OnlineStatusListener,
+}
+
+/// Get whether the browser has a network connection. The component rerenders when the status changes.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let online = use_online_status(cx);
+///
+///     render! {
+///         if !online {
+///             rsx! { div { class: "banner", "You are offline. Changes will be saved when you reconnect." } }
+///         }
+///     }
+/// }
+/// ```
+pub fn use_online_status(cx: &ScopeState) -> bool {
+    let state = cx.use_hook(|| {
+        let online = Rc::new(Cell::new(is_online()));
+        let update = cx.schedule_update();
+        let listener = watch_online_status({
+            let online = online.clone();
+            move |status| {
+                online.set(status);
+                update();
+            }
+        });
+        UseOnlineStatus {
+            online,
+            _listener: listener,
+        }
+    });
+    state.online.get()
+}
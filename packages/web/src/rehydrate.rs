@@ -118,6 +118,9 @@ impl WebsysDom {
             dioxus_core::DynamicNode::Text(text) => {
                 ids.push(text.mounted_element().ok_or(VNodeNotInitialized)?.0 as u32);
             }
+            dioxus_core::DynamicNode::RawHtml(html) => {
+                ids.push(html.mounted_element().ok_or(VNodeNotInitialized)?.0 as u32);
+            }
             dioxus_core::DynamicNode::Placeholder(placeholder) => {
                 ids.push(placeholder.mounted_element().ok_or(VNodeNotInitialized)?.0 as u32);
             }
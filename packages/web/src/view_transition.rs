@@ -0,0 +1,130 @@
+//! Integration with the browser's [View Transitions API](https://developer.mozilla.org/en-US/docs/Web/API/View_Transitions_API).
+//!
+//! When a transition is requested, the renderer asks the browser to capture the page before it applies the next set
+//! of edits, and the browser animates between the old and new page. Elements with the same `view-transition-name`
+//! style in both pages are animated as a shared element. In browsers without the API the edits are applied
+//! immediately, as if no transition was requested.
+
+use std::cell::{Cell, RefCell};
+
+use dioxus_core::ScopeState;
+use futures_channel::oneshot;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+thread_local! {
+    /// If the next render should be animated with a view transition
+    static PENDING: Cell<bool> = Cell::new(false);
+    /// Resolves the promise the browser is waiting on before it captures the new page
+    static FINISH: RefCell<Option<js_sys::Function>> = RefCell::new(None);
+}
+
+/// Check if the browser supports view transitions.
+pub fn view_transitions_supported() -> bool {
+    start_view_transition_fn().is_some()
+}
+
+fn start_view_transition_fn() -> Option<(web_sys::Document, js_sys::Function)> {
+    let document = web_sys::window()?.document()?;
+    let start = js_sys::Reflect::get(&document, &JsValue::from_str("startViewTransition"))
+        .ok()?
+        .dyn_into::<js_sys::Function>()
+        .ok()?;
+    Some((document, start))
+}
+
+/// A handle to trigger view transitions from a component.
+#[derive(Clone, Copy)]
+pub struct ViewTransition {
+    _private: (),
+}
+
+impl ViewTransition {
+    /// Run `update` and animate the changes it makes to the page with a view transition.
+    ///
+    /// `update` should change the state of the app. The next render after it runs is animated.
+    pub fn start(&self, update: impl FnOnce()) {
+        PENDING.with(|pending| pending.set(true));
+        update();
+    }
+
+    /// Check if the browser supports view transitions. If it does not, [`ViewTransition::start`] just runs `update`.
+    pub fn is_supported(&self) -> bool {
+        view_transitions_supported()
+    }
+}
+
+/// Get a handle to trigger custom view transitions.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let expanded = use_state(cx, || false);
+///     let transition = use_view_transition(cx);
+///
+///     render! {
+///         img {
+///             style: "view-transition-name: hero",
+///             class: if **expanded { "large" } else { "small" },
+///             onclick: move |_| transition.start(|| expanded.set(!expanded)),
+///         }
+///     }
+/// }
+/// ```
+pub fn use_view_transition(cx: &ScopeState) -> &ViewTransition {
+    cx.use_hook(|| ViewTransition { _private: () })
+}
+
+/// Decides which renders are animated and coordinates them with the browser.
+pub(crate) struct ViewTransitions {
+    route_transitions: bool,
+    last_url: Option<String>,
+}
+
+impl ViewTransitions {
+    pub(crate) fn new(route_transitions: bool) -> Self {
+        Self {
+            route_transitions,
+            last_url: current_url(),
+        }
+    }
+
+    /// Start a view transition if one was requested or the route changed. The returned receiver resolves once the
+    /// browser has captured the old page and the edits can be applied.
+    pub(crate) fn begin(&mut self, has_edits: bool) -> Option<oneshot::Receiver<()>> {
+        // Keep the request around until there is something to animate
+        if !has_edits {
+            return None;
+        }
+
+        let url = current_url();
+        let route_changed = self.route_transitions && url != self.last_url;
+        self.last_url = url;
+
+        let requested = PENDING.with(|pending| pending.replace(false));
+        if !requested && !route_changed {
+            return None;
+        }
+
+        let (document, start) = start_view_transition_fn()?;
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let update = Closure::once_into_js(move || {
+            let _ = ready_tx.send(());
+            js_sys::Promise::new(&mut |resolve, _| {
+                FINISH.with(|finish| *finish.borrow_mut() = Some(resolve));
+            })
+        });
+        start.call1(&document, &update).ok()?;
+
+        Some(ready_rx)
+    }
+
+    /// Let the browser capture the new page after the edits were applied.
+    pub(crate) fn finish(&mut self) {
+        if let Some(resolve) = FINISH.with(|finish| finish.borrow_mut().take()) {
+            let _ = resolve.call0(&JsValue::NULL);
+        }
+    }
+}
+
+fn current_url() -> Option<String> {
+    web_sys::window()?.location().href().ok()
+}